@@ -1,12 +1,26 @@
 //! Saorsa WebRTC CLI Application
 
 use anyhow::Result;
-use clap::{Parser, Subcommand};
-use rand::Rng;
+use clap::{CommandFactory, Parser, Subcommand};
+use menu::{MainMenu, MenuAction, MenuState};
+use recent::RecentPeers;
+use saorsa_webrtc_core::audio_pipeline::{AudioPipeline, DeviceFormat, DEFAULT_MONITOR_GAIN};
 use saorsa_webrtc_core::prelude::*;
+use saorsa_webrtc_core::voicemail::AutoAnswerConfig;
+use saorsa_webrtc_core::{
+    synthetic, AudioLevelMeter, AudioParameters, CallInvite, CallProgress, Contact, ContactBook,
+    ContactError, ContactPermissions, DndAction, DndConfig, HealthStatus, InviteError, PortRange,
+    QuietHours, ToneSource, WebhookConfig,
+};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 use terminal_ui::{CliDisplayMode, TerminalUI};
 
+mod menu;
+mod qr;
+mod recent;
+mod stress;
 mod terminal_ui;
 #[cfg(test)]
 mod terminal_ui_tests;
@@ -18,6 +32,10 @@ struct Cli {
     #[arg(short, long, env = "SAORSA_IDENTITY")]
     identity: Option<String>,
 
+    /// Contacts file (defaults to contacts.json in the user data directory)
+    #[arg(long, env = "SAORSA_CONTACTS", value_name = "PATH", global = true)]
+    contacts: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -26,8 +44,13 @@ struct Cli {
 enum Commands {
     /// Initiate a call
     Call {
-        /// Peer to call (four-word address)
-        peer: String,
+        /// Peer to call: contact name (partial names are matched), four-word
+        /// address or saorsa://call invitation; omit to pick a recent peer
+        peer: Option<String>,
+
+        /// Call the only matching contact without asking
+        #[arg(short, long)]
+        yes: bool,
 
         /// Enable video
         #[arg(long, default_value = "true")]
@@ -51,10 +74,189 @@ enum Commands {
         /// Video display mode for accepted calls
         #[arg(long, value_enum, default_value = "sixel")]
         display: CliDisplayMode,
+
+        /// Send unanswered calls to voicemail after this many seconds
+        #[arg(long, value_name = "SECS")]
+        voicemail_after: Option<u64>,
+
+        /// Pre-encoded Opus greeting played to voicemail callers
+        #[arg(long, value_name = "PATH", requires = "voicemail_after")]
+        greeting: Option<PathBuf>,
+
+        /// Directory voicemail recordings are written to
+        #[arg(long, value_name = "DIR", requires = "voicemail_after")]
+        voicemail_dir: Option<PathBuf>,
+
+        /// UDP port or port range to listen on (e.g. 5000 or 5000-5010)
+        #[arg(long, value_name = "PORTS")]
+        port_range: Option<PortRange>,
+
+        /// Do not disturb: incoming calls do not ring and are listed as missed
+        #[arg(long)]
+        dnd: bool,
+
+        /// Local times during which do-not-disturb is on (e.g. 22:00-07:00);
+        /// may be repeated
+        #[arg(long, value_name = "HH:MM-HH:MM")]
+        quiet_hours: Vec<QuietHours>,
+
+        /// Decline calls during do-not-disturb instead of leaving them
+        /// unanswered
+        #[arg(long)]
+        dnd_reject: bool,
+
+        /// URL to POST call notifications to; may be repeated
+        #[arg(long, value_name = "URL")]
+        webhook: Vec<String>,
+
+        /// Key for signing webhook requests
+        #[arg(
+            long,
+            env = "SAORSA_WEBHOOK_SECRET",
+            value_name = "SECRET",
+            requires = "webhook"
+        )]
+        webhook_secret: Option<String>,
+    },
+
+    /// Show an invitation link and QR code others can use to call you
+    Invite {
+        /// Invite to an audio-only call
+        #[arg(long)]
+        audio_only: bool,
+
+        /// Print only the link, without the QR code
+        #[arg(long)]
+        no_qr: bool,
+    },
+
+    /// List media devices and test audio
+    Devices {
+        /// Run one second of audio through the capture and playback path
+        #[arg(long)]
+        test: bool,
+
+        /// Monitor the microphone during the test at this gain (0.0-1.0)
+        #[arg(
+            long,
+            value_name = "GAIN",
+            num_args = 0..=1,
+            default_missing_value = "0.1",
+            requires = "test"
+        )]
+        monitor: Option<f32>,
+    },
+
+    /// Repeatedly set up and tear down calls to stress the call manager
+    Stress {
+        /// Peer to call
+        #[arg(long)]
+        peer: String,
+
+        /// Total calls to place
+        #[arg(long, default_value = "100")]
+        calls: usize,
+
+        /// Calls in flight at once
+        #[arg(long, default_value = "10")]
+        parallel: usize,
+
+        /// How long each call stays up before hanging up
+        #[arg(long, value_name = "MS", default_value = "0")]
+        hold_ms: u64,
+
+        /// How long a call may take to be accepted before it counts as failed
+        #[arg(long, value_name = "MS", default_value = "10000")]
+        timeout_ms: u64,
+    },
+
+    /// Manage the contact list
+    Contacts {
+        #[command(subcommand)]
+        action: ContactsCommand,
     },
 
     /// Show status and available commands
-    Status,
+    Status {
+        /// Start a service and report the health of its parts
+        #[arg(long)]
+        live: bool,
+    },
+
+    /// Browse contacts, recent peers and calls in an interactive menu
+    Tui {
+        /// Video display mode for calls placed from the menu
+        #[arg(long, value_enum, default_value = "sixel")]
+        display: CliDisplayMode,
+    },
+
+    /// Print a shell completion script
+    ///
+    /// For example `saorsa completions bash > /etc/bash_completion.d/saorsa`
+    Completions {
+        /// Shell to complete in
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+}
+
+#[derive(Subcommand)]
+enum ContactsCommand {
+    /// Add a contact
+    Add {
+        /// Name to call them by
+        name: String,
+
+        /// Their four-word identity
+        identity: String,
+
+        /// Fingerprint you verified with them
+        #[arg(long)]
+        fingerprint: Option<String>,
+
+        /// Notes about the contact
+        #[arg(long, default_value = "")]
+        notes: String,
+
+        /// Answer their calls without asking
+        #[arg(long)]
+        auto_accept: bool,
+
+        /// Reject their video calls
+        #[arg(long)]
+        no_video: bool,
+
+        /// Accept files they send
+        #[arg(long)]
+        allow_files: bool,
+    },
+
+    /// List contacts
+    List,
+
+    /// Remove a contact
+    Remove {
+        /// Contact name
+        name: String,
+    },
+
+    /// Call a contact by name
+    Call {
+        /// Contact name
+        name: String,
+
+        /// Enable video
+        #[arg(long, default_value = "true")]
+        video: bool,
+
+        /// Enable audio
+        #[arg(long, default_value = "true")]
+        audio: bool,
+
+        /// Video display mode
+        #[arg(long, value_enum, default_value = "sixel")]
+        display: CliDisplayMode,
+    },
 }
 
 #[tokio::main]
@@ -66,28 +268,134 @@ async fn main() -> Result<()> {
 
     let cli = Cli::parse();
 
+    // Completion scripts go to stdout, so print nothing else
+    if let Commands::Completions { shell } = cli.command {
+        clap_complete::generate(shell, &mut Cli::command(), "saorsa", &mut std::io::stdout());
+        return Ok(());
+    }
+
     // Get or generate identity
-    let identity = cli.identity.unwrap_or_else(generate_random_identity);
+    let identity = cli
+        .identity
+        .unwrap_or_else(|| FourWordIdentity::generate().to_string());
 
     println!("🔗 Using identity: {}", identity);
 
     match cli.command {
         Commands::Call {
             peer,
+            yes,
             video,
             audio,
             display,
         } => {
+            let contacts = cli.contacts.map_or_else(default_contacts_path, Ok)?;
+            let mut recent = RecentPeers::open(RecentPeers::path_for(&contacts))?;
+            // Invitation links carry the peer and the media to use
+            let (peer, video, audio) = match peer.as_deref().map(CallInvite::parse) {
+                Some(Ok(invite)) => (
+                    invite.peer,
+                    invite.constraints.video,
+                    invite.constraints.audio,
+                ),
+                Some(Err(InviteError::NotAnInvite(_))) | None => {
+                    let book = ContactBook::<PeerIdentityString>::open(&contacts)?;
+                    (
+                        resolve_peer(peer.as_deref(), &book, &recent, yes)?,
+                        video,
+                        audio,
+                    )
+                }
+                Some(Err(e)) => return Err(e.into()),
+            };
+            recent.record(&peer)?;
             handle_call(&identity, &peer, video, audio, display).await?;
         }
         Commands::Listen {
             auto_accept,
             display,
+            voicemail_after,
+            greeting,
+            voicemail_dir,
+            port_range,
+            dnd,
+            quiet_hours,
+            dnd_reject,
+            webhook,
+            webhook_secret,
         } => {
-            handle_listen(&identity, auto_accept, display).await?;
+            let auto_answer = AutoAnswerConfig {
+                enabled: voicemail_after.is_some(),
+                delay: voicemail_after.map_or(
+                    saorsa_webrtc_core::voicemail::DEFAULT_AUTO_ANSWER_DELAY,
+                    Duration::from_secs,
+                ),
+                greeting,
+                recording_dir: voicemail_dir,
+                ..Default::default()
+            };
+            let dnd = DndConfig {
+                enabled: dnd,
+                quiet_hours,
+                utc_offset_minutes: chrono::Local::now().offset().local_minus_utc() / 60,
+                action: if dnd_reject {
+                    DndAction::Reject
+                } else {
+                    DndAction::Divert
+                },
+            };
+            let webhooks = (!webhook.is_empty()).then(|| WebhookConfig {
+                urls: webhook,
+                secret: webhook_secret,
+                ..Default::default()
+            });
+            handle_listen(
+                &identity,
+                auto_accept,
+                display,
+                auto_answer,
+                dnd,
+                port_range,
+                webhooks,
+            )
+            .await?;
         }
-        Commands::Status => {
-            handle_status().await?;
+        Commands::Invite { audio_only, no_qr } => {
+            handle_invite(&identity, audio_only, no_qr)?;
+        }
+        Commands::Devices { test, monitor } => {
+            handle_devices(test, monitor).await?;
+        }
+        Commands::Stress {
+            peer,
+            calls,
+            parallel,
+            hold_ms,
+            timeout_ms,
+        } => {
+            let config = stress::StressConfig {
+                calls,
+                parallel,
+                hold: Duration::from_millis(hold_ms),
+                setup_timeout: Duration::from_millis(timeout_ms),
+            };
+            handle_stress(&peer, config).await?;
+        }
+        Commands::Contacts { action } => {
+            let path = cli.contacts.map_or_else(default_contacts_path, Ok)?;
+            handle_contacts(&identity, &path, action).await?;
+        }
+        Commands::Tui { display } => {
+            let contacts = cli.contacts.map_or_else(default_contacts_path, Ok)?;
+            handle_tui(&identity, &contacts, display).await?;
+        }
+        Commands::Completions { .. } => {}
+        Commands::Status { live } => {
+            if live {
+                handle_live_status().await?;
+            } else {
+                handle_status().await?;
+            }
         }
     }
 
@@ -95,7 +403,7 @@ async fn main() -> Result<()> {
 }
 
 async fn handle_call(
-    _identity: &str,
+    identity: &str,
     peer: &str,
     video: bool,
     audio: bool,
@@ -107,6 +415,24 @@ async fn handle_call(
         video, audio, display
     );
 
+    let service = start_call_service().await?;
+    println!("✅ WebRTC service started");
+
+    // Set up media constraints
+    let constraints = MediaConstraints {
+        audio,
+        video,
+        screen_share: false,
+        latency: None,
+    };
+    place_call(&service, identity, peer, constraints, display).await?;
+
+    println!("📞 Call ended");
+    Ok(())
+}
+
+/// Create and start a service for placing calls
+async fn start_call_service() -> Result<Arc<WebRtcService<PeerIdentityString, AntQuicTransport>>> {
     // Create transport configuration
     let transport_config = TransportConfig::default();
 
@@ -117,41 +443,174 @@ async fn handle_call(
     let signaling = Arc::new(SignalingHandler::new(transport.clone()));
 
     // Create WebRTC service
-    let service = Arc::new(WebRtcService::builder(signaling).build().await?);
+    let config = WebRtcConfig {
+        call_config: call_manager_config(),
+        ..Default::default()
+    };
+    let service = Arc::new(
+        WebRtcService::builder(signaling)
+            .with_config(config)
+            .build()
+            .await?,
+    );
 
     // Start the service
     service.start().await?;
-    println!("✅ WebRTC service started");
+    Ok(service)
+}
 
-    // Set up media constraints
-    let constraints = MediaConstraints {
-        audio,
-        video,
-        screen_share: false,
-    };
+/// Call manager settings for interactive calls
+///
+/// What was learned about each peer's bandwidth is kept next to the
+/// contact list, so repeat calls start near the rate they left off at.
+fn call_manager_config() -> CallManagerConfig {
+    let mut config = CallManagerConfig::default();
+    config.peer_quality.path = directories::ProjectDirs::from("com", "saorsalabs", "saorsa")
+        .map(|dirs| dirs.data_dir().join("peer_quality.json"));
+    config
+}
 
+/// Call a peer and show the call until the user leaves it
+async fn place_call(
+    service: &Arc<WebRtcService<PeerIdentityString, AntQuicTransport>>,
+    identity: &str,
+    peer: &str,
+    constraints: MediaConstraints,
+    display: CliDisplayMode,
+) -> Result<()> {
     // Initiate call
+    let mut events = service.subscribe_events();
     let peer_identity = PeerIdentityString::new(peer);
     let call_id = service.initiate_call(peer_identity, constraints).await?;
-    println!("📞 Call initiated with ID: {}", call_id);
+    println!(
+        "📞 Call initiated with ID: {} (code {})",
+        call_id,
+        call_id.short_code()
+    );
+
+    // Show how far the callee has got until the call is answered
+    tokio::spawn(async move {
+        while let Ok(event) = events.recv().await {
+            match event {
+                WebRtcEvent::Call(CallEvent::CallProgress {
+                    call_id: id,
+                    progress,
+                }) if id == call_id => match progress {
+                    CallProgress::Ringing => println!("🔔 Ringing..."),
+                    CallProgress::Queued {
+                        position: Some(position),
+                    } => println!("⏳ Queued, position {}", position),
+                    CallProgress::Queued { position: None } => println!("⏳ Queued"),
+                    CallProgress::EarlyMedia => println!("🔊 Early media"),
+                },
+                WebRtcEvent::Call(
+                    CallEvent::CallAccepted { call_id: id, .. }
+                    | CallEvent::CallRejected { call_id: id }
+                    | CallEvent::CallEnded { call_id: id },
+                ) if id == call_id => break,
+                _ => {}
+            }
+        }
+    });
 
     // Start terminal UI
-    let mut ui = TerminalUI::new(display.into())?;
-    ui.run(Arc::clone(&service), call_id).await?;
+    let mut ui = TerminalUI::new(display.into(), identity)?;
+    ui.run(Arc::clone(service), call_id).await
+}
 
-    println!("📞 Call ended");
-    Ok(())
+async fn handle_tui(identity: &str, contacts: &Path, display: CliDisplayMode) -> Result<()> {
+    let service = start_call_service().await?;
+    let mut recent = RecentPeers::open(RecentPeers::path_for(contacts))?;
+    let mut state = MenuState::new(identity.to_string(), display);
+    state.do_not_disturb = service.dnd().config().enabled;
+
+    loop {
+        // Lists may change while a call is shown, so reload them each time
+        let book = ContactBook::<PeerIdentityString>::open(contacts)?;
+        state.contacts = book
+            .list()
+            .map(|contact| (contact.name.clone(), contact.identity.to_string()))
+            .collect();
+        state.recent = recent
+            .list()
+            .iter()
+            .map(|peer| (peer_label(&book, &peer.identity), peer.identity.clone()))
+            .collect();
+
+        let mut menu = MainMenu::new()?;
+        let action = loop {
+            state.calls = service
+                .calls()
+                .await
+                .into_iter()
+                .map(|(call_id, peer, call_state)| (call_id, peer.to_string(), call_state))
+                .collect();
+            state.clamp_selection();
+            if let Some(action) = menu.run(&mut state, Duration::from_millis(250))? {
+                break action;
+            }
+        };
+        // Give the terminal back before showing a call
+        drop(menu);
+
+        match action {
+            MenuAction::Quit => return Ok(()),
+            MenuAction::Call(peer) => {
+                recent.record(&peer)?;
+                place_call(
+                    &service,
+                    identity,
+                    &peer,
+                    MediaConstraints::video_call(),
+                    state.display,
+                )
+                .await?;
+            }
+            MenuAction::Open(call_id) => {
+                let mut ui = TerminalUI::new(state.display.into(), identity)?;
+                ui.run(Arc::clone(&service), call_id).await?;
+            }
+            MenuAction::SetDoNotDisturb(enabled) => service.set_do_not_disturb(enabled),
+        }
+    }
 }
 
-async fn handle_listen(_identity: &str, auto_accept: bool, display: CliDisplayMode) -> Result<()> {
+async fn handle_listen(
+    identity: &str,
+    auto_accept: bool,
+    display: CliDisplayMode,
+    auto_answer: AutoAnswerConfig,
+    dnd: DndConfig,
+    port_range: Option<PortRange>,
+    webhooks: Option<WebhookConfig>,
+) -> Result<()> {
     println!("👂 Listening for incoming calls...");
     if auto_accept {
         println!("   Auto-accept: enabled");
     }
+    let voicemail = auto_answer.enabled;
+    if voicemail {
+        println!("   Voicemail: after {}s", auto_answer.delay.as_secs());
+    }
+    if dnd.enabled {
+        println!("   Do not disturb: on");
+    }
+    for hours in &dnd.quiet_hours {
+        println!("   Quiet hours: {}", hours);
+    }
     println!("   Display mode: {:?}", display);
+    if let Some(range) = port_range {
+        println!("   UDP ports: {}", range);
+    }
+    for url in webhooks.iter().flat_map(|webhooks| &webhooks.urls) {
+        println!("   Webhook: {}", url);
+    }
 
     // Create transport configuration
-    let transport_config = TransportConfig::default();
+    let transport_config = TransportConfig {
+        port_range,
+        ..Default::default()
+    };
 
     // Create transport
     let transport = Arc::new(AntQuicTransport::new(transport_config));
@@ -160,7 +619,19 @@ async fn handle_listen(_identity: &str, auto_accept: bool, display: CliDisplayMo
     let signaling = Arc::new(SignalingHandler::new(transport.clone()));
 
     // Create WebRTC service
-    let service = Arc::new(WebRtcService::builder(signaling).build().await?);
+    let config = WebRtcConfig {
+        auto_answer,
+        dnd,
+        webhooks,
+        call_config: call_manager_config(),
+        ..Default::default()
+    };
+    let service = Arc::new(
+        WebRtcService::builder(signaling)
+            .with_config(config)
+            .build()
+            .await?,
+    );
 
     // Start the service
     service.start().await?;
@@ -174,12 +645,22 @@ async fn handle_listen(_identity: &str, auto_accept: bool, display: CliDisplayMo
             event = events.recv() => {
                 match event {
                     Ok(WebRtcEvent::Call(CallEvent::IncomingCall { offer })) => {
-                        println!("📞 Incoming call from {}", offer.caller);
+                        println!(
+                            "📞 Incoming call from {} (code {})",
+                            offer.caller,
+                            offer.call_id.short_code()
+                        );
                         println!("   Video: {} | Audio: {}",
                             offer.media_types.contains(&saorsa_webrtc_core::types::MediaType::Video),
                             offer.media_types.contains(&saorsa_webrtc_core::types::MediaType::Audio)
                         );
 
+                        if voicemail && !auto_accept {
+                            // The service answers the call with the greeting
+                            println!("📼 Call will go to voicemail if unanswered");
+                            continue;
+                        }
+
                         let should_accept = if auto_accept {
                             true
                         } else {
@@ -196,17 +677,44 @@ async fn handle_listen(_identity: &str, auto_accept: bool, display: CliDisplayMo
                                 audio: offer.media_types.contains(&saorsa_webrtc_core::types::MediaType::Audio),
                                 video: offer.media_types.contains(&saorsa_webrtc_core::types::MediaType::Video),
                                 screen_share: offer.media_types.contains(&saorsa_webrtc_core::types::MediaType::ScreenShare),
+                                latency: None,
                             };
                             service.accept_call(offer.call_id, constraints).await?;
 
                             // Start terminal UI
-                            let mut ui = TerminalUI::new(display.into())?;
+                            let mut ui = TerminalUI::new(display.into(), identity)?;
                             ui.run(Arc::clone(&service), offer.call_id).await?;
                         } else {
                             println!("❌ Rejecting call...");
                             service.reject_call(offer.call_id).await?;
                         }
                     }
+                    Ok(WebRtcEvent::Call(CallEvent::CallWaiting { offer, active })) => {
+                        println!(
+                            "📞 Call waiting from {} (on call {})",
+                            offer.caller,
+                            active.short_code()
+                        );
+                        if !auto_accept {
+                            println!("❌ Busy, rejecting call...");
+                            service.reject_busy(offer.call_id, &offer.caller).await?;
+                            continue;
+                        }
+
+                        println!("✅ Holding current call and accepting...");
+                        let constraints = MediaConstraints::from_media_types(&offer.media_types);
+                        let held = service.answer_waiting_call(offer.call_id, constraints).await?;
+                        let mut ui = TerminalUI::new(display.into(), identity)?;
+                        ui.run(Arc::clone(&service), offer.call_id).await?;
+
+                        for call_id in held {
+                            println!("▶️  Resuming call {}", call_id.short_code());
+                            service.resume_call(call_id).await?;
+                        }
+                    }
+                    Ok(WebRtcEvent::MissedCall(missed)) => {
+                        println!("🔕 Missed call from {} (do not disturb)", missed.caller);
+                    }
                     Ok(other) => {
                         tracing::debug!("Received event: {:?}", other);
                     }
@@ -222,6 +730,288 @@ async fn handle_listen(_identity: &str, auto_accept: bool, display: CliDisplayMo
     Ok(())
 }
 
+fn handle_invite(identity: &str, audio_only: bool, no_qr: bool) -> Result<()> {
+    let constraints = if audio_only {
+        MediaConstraints::audio_only()
+    } else {
+        MediaConstraints::video_call()
+    };
+    let link = CallInvite::new(identity, constraints).to_uri();
+
+    if !no_qr {
+        match qr::QrCode::encode(link.as_bytes()) {
+            Some(code) => println!("{}", code.render()),
+            None => println!("⚠️  Link too long for a QR code"),
+        }
+    }
+    println!("📨 Invitation: {}", link);
+    println!("   Others can call you with: saorsa call '{}'", link);
+    Ok(())
+}
+
+async fn handle_devices(test: bool, monitor: Option<f32>) -> Result<()> {
+    let media = MediaStreamManager::new();
+    media.initialize().await?;
+
+    println!("🎤 Audio devices:");
+    for device in media
+        .get_audio_devices()
+        .iter()
+        .chain([&synthetic::tone_device()])
+    {
+        println!("  {} ({})", device.name, device.id);
+    }
+    println!("📹 Video devices:");
+    for device in media
+        .get_video_devices()
+        .iter()
+        .chain([&synthetic::test_pattern_device()])
+    {
+        println!("  {} ({})", device.name, device.id);
+    }
+
+    if !test {
+        return Ok(());
+    }
+
+    let device = DeviceFormat::default();
+    let mut pipeline = AudioPipeline::new(AudioParameters::default(), device)?;
+    pipeline.set_monitor(monitor);
+    match pipeline.monitor_gain() {
+        Some(gain) => println!("🔁 Monitoring microphone at gain {gain:.2}"),
+        None => println!(
+            "🔁 Monitoring off (use --monitor to hear yourself, default gain {DEFAULT_MONITOR_GAIN})"
+        ),
+    }
+
+    // No capture backend yet, so the test tone stands in for the microphone
+    let mut tone = ToneSource::new(synthetic::DEFAULT_TONE_HZ, device);
+    let frame_len = device.sample_rate_hz as usize / 50;
+    let mut capture = AudioLevelMeter::default();
+    let mut playback = AudioLevelMeter::default();
+    for _ in 0..50 {
+        let timestamp = tone.timestamp_ms();
+        let pcm = tone.next_frames(frame_len);
+        capture.process(&pcm);
+        pipeline.encode(&pcm, timestamp)?;
+
+        let mut output = vec![0i16; frame_len];
+        pipeline.mix_monitor(&mut output);
+        playback.process(&output);
+    }
+
+    println!("✅ Capture level: {:.1} dBFS", capture.level().rms_dbfs());
+    println!(
+        "✅ Local output level: {:.1} dBFS",
+        playback.level().rms_dbfs()
+    );
+    Ok(())
+}
+
+async fn handle_stress(peer: &str, config: stress::StressConfig) -> Result<()> {
+    println!(
+        "🔥 Stressing {} calls to {} ({} in parallel, held {:?})",
+        config.calls, peer, config.parallel, config.hold
+    );
+
+    let transport = Arc::new(AntQuicTransport::new(TransportConfig::default()));
+    let signaling = Arc::new(SignalingHandler::new(transport));
+
+    // Allow every call in flight, so failures come from churn rather than the limit
+    let service_config = WebRtcConfig {
+        call_config: CallManagerConfig {
+            max_concurrent_calls: config.parallel.max(1),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let service = Arc::new(
+        WebRtcService::builder(signaling)
+            .with_config(service_config)
+            .build()
+            .await?,
+    );
+    service.start().await?;
+
+    let report = stress::run(service, peer, config).await;
+    println!("{report}");
+    if !report.is_clean() {
+        anyhow::bail!(
+            "{} calls failed, {} leaked",
+            report.failed(),
+            report.leaked_calls
+        );
+    }
+    Ok(())
+}
+
+/// Work out whom `saorsa call` means
+///
+/// Four-word addresses are called as given. Anything else is looked up as a
+/// contact name: an exact name is called straight away, a single partial
+/// match after confirming it, and several matches or none at all offer the
+/// matches and recent peers to pick from. Without a peer the recent peers
+/// are offered.
+fn resolve_peer(
+    query: Option<&str>,
+    book: &ContactBook<PeerIdentityString>,
+    recent: &RecentPeers,
+    assume_yes: bool,
+) -> Result<String> {
+    let Some(query) = query else {
+        let candidates: Vec<(String, String)> = recent
+            .list()
+            .iter()
+            .map(|peer| (peer_label(book, &peer.identity), peer.identity.clone()))
+            .collect();
+        if candidates.is_empty() {
+            anyhow::bail!("no peer given and no recent calls; run `saorsa call <peer>`");
+        }
+        println!("🕘 Recent peers:");
+        return pick(&candidates);
+    };
+    if let Ok(address) = FourWordIdentity::parse(query) {
+        return Ok(address.to_string());
+    }
+
+    let contacts = book.search(query);
+    if let [contact] = contacts.as_slice() {
+        let exact = contact.name.eq_ignore_ascii_case(query.trim());
+        if exact
+            || assume_yes
+            || confirm(&format!("Call {} ({})?", contact.name, contact.identity))?
+        {
+            println!("👤 {} is {}", contact.name, contact.identity);
+            return Ok(contact.identity.as_str().to_string());
+        }
+        anyhow::bail!("call cancelled");
+    }
+
+    let mut candidates: Vec<(String, String)> = contacts
+        .iter()
+        .map(|contact| {
+            (
+                format!("{} ({})", contact.name, contact.identity),
+                contact.identity.as_str().to_string(),
+            )
+        })
+        .collect();
+    for peer in recent.search(query) {
+        if !candidates.iter().any(|(_, id)| *id == peer.identity) {
+            candidates.push((peer_label(book, &peer.identity), peer.identity.clone()));
+        }
+    }
+    if candidates.is_empty() {
+        // Not a name we know; let the transport try it as an address
+        return Ok(query.to_string());
+    }
+    println!("🔎 \"{}\" matches:", query);
+    pick(&candidates)
+}
+
+/// Peer identity with the contact name it is saved under, if any
+fn peer_label(book: &ContactBook<PeerIdentityString>, identity: &str) -> String {
+    match book.find_by_repr(identity) {
+        Some(contact) => format!("{} ({})", contact.name, identity),
+        None => identity.to_string(),
+    }
+}
+
+/// Ask a yes/no question, yes by default
+fn confirm(question: &str) -> Result<bool> {
+    let answer = prompt(&format!("{} [Y/n] ", question))?;
+    Ok(matches!(answer.to_lowercase().as_str(), "" | "y" | "yes"))
+}
+
+/// List `(label, peer)` candidates and read the number of one
+fn pick(candidates: &[(String, String)]) -> Result<String> {
+    for (number, (label, _)) in candidates.iter().enumerate() {
+        println!("  {}. {}", number + 1, label);
+    }
+    let answer = prompt("Call which? ")?;
+    answer
+        .parse::<usize>()
+        .ok()
+        .and_then(|number| candidates.get(number.checked_sub(1)?))
+        .map(|(_, peer)| peer.clone())
+        .ok_or_else(|| anyhow::anyhow!("no peer picked"))
+}
+
+/// Print a prompt and read one trimmed line
+fn prompt(text: &str) -> Result<String> {
+    use std::io::Write;
+
+    print!("{}", text);
+    std::io::stdout().flush()?;
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line)?;
+    Ok(line.trim().to_string())
+}
+
+fn default_contacts_path() -> Result<PathBuf> {
+    directories::ProjectDirs::from("com", "saorsalabs", "saorsa")
+        .map(|dirs| dirs.data_dir().join("contacts.json"))
+        .ok_or_else(|| anyhow::anyhow!("no home directory; pass --contacts"))
+}
+
+async fn handle_contacts(identity: &str, path: &Path, action: ContactsCommand) -> Result<()> {
+    let mut book = ContactBook::<PeerIdentityString>::open(path)?;
+    match action {
+        ContactsCommand::Add {
+            name,
+            identity: peer,
+            fingerprint,
+            notes,
+            auto_accept,
+            no_video,
+            allow_files,
+        } => {
+            // Store four-word identities in canonical form
+            let peer = FourWordIdentity::parse(&peer).map_or(peer, |peer| peer.to_string());
+            let mut contact = Contact::new(name, PeerIdentityString::new(peer));
+            contact.fingerprint = fingerprint;
+            contact.notes = notes;
+            contact.permissions = ContactPermissions {
+                auto_accept,
+                video_allowed: !no_video,
+                file_transfer_allowed: allow_files,
+            };
+            println!("👤 Added {} ({})", contact.name, contact.identity);
+            book.add(contact)?;
+        }
+        ContactsCommand::List => {
+            if book.is_empty() {
+                println!("No contacts in {}", path.display());
+            }
+            for contact in book.list() {
+                let verified = if contact.is_verified() { "✅" } else { "  " };
+                print!("{verified} {:<20} {}", contact.name, contact.identity);
+                if !contact.notes.is_empty() {
+                    print!("  ({})", contact.notes);
+                }
+                println!();
+            }
+        }
+        ContactsCommand::Remove { name } => {
+            let contact = book.remove(&name)?;
+            println!("🗑️  Removed {}", contact.name);
+        }
+        ContactsCommand::Call {
+            name,
+            video,
+            audio,
+            display,
+        } => {
+            let contact = book
+                .get(&name)
+                .ok_or_else(|| ContactError::NotFound(name.clone()))?;
+            println!("👤 {} is {}", contact.name, contact.identity);
+            handle_call(identity, contact.identity.as_str(), video, audio, display).await?;
+        }
+    }
+    Ok(())
+}
+
 async fn handle_status() -> Result<()> {
     println!("📊 Saorsa WebRTC CLI Status");
     println!("==========================");
@@ -232,30 +1022,97 @@ async fn handle_status() -> Result<()> {
     println!("⚠️  Real codecs: Needs OpenH264 integration");
     println!();
     println!("Available commands:");
-    println!("  saorsa call <peer> [options]  - Initiate a call");
+    println!("  saorsa call [peer] [options]  - Call a peer, contact name or recent peer");
     println!("  saorsa listen [options]       - Listen for calls");
+    println!("  saorsa invite [options]       - Show a link and QR code to call you");
+    println!("  saorsa devices [--test]       - List devices and test audio");
+    println!("  saorsa stress --peer <peer>   - Stress call setup and teardown");
+    println!("  saorsa contacts add|list|...  - Manage contacts and call them by name");
     println!("  saorsa status                 - Show this status");
+    println!("  saorsa status --live          - Check the health of a running service");
+    println!("  saorsa tui                    - Browse contacts and calls in a menu");
+    println!("  saorsa completions <shell>    - Print a shell completion script");
     println!();
     println!("Use 'saorsa --help' for detailed options");
 
     Ok(())
 }
 
-fn generate_random_identity() -> String {
-    const WORDS: &[&str] = &[
-        "alpha", "bravo", "charlie", "delta", "echo", "foxtrot", "golf", "hotel", "india",
-        "juliet", "kilo", "lima", "mike", "november", "oscar", "papa", "quebec", "romeo", "sierra",
-        "tango", "uniform", "victor", "whiskey", "xray", "yankee", "zulu", "atlas", "beacon",
-        "comet", "dragon", "eagle", "falcon", "galaxy", "harbor", "icarus", "jupiter", "knight",
-        "lunar", "meteor", "nebula", "orbit", "phoenix", "quasar", "rocket", "stellar", "titan",
-        "universe", "vortex",
-    ];
-
-    let mut rng = rand::thread_rng();
-    let indices: Vec<usize> = (0..4).map(|_| rng.gen_range(0..WORDS.len())).collect();
-
-    format!(
-        "{}-{}-{}-{}",
-        WORDS[indices[0]], WORDS[indices[1]], WORDS[indices[2]], WORDS[indices[3]]
-    )
+async fn handle_live_status() -> Result<()> {
+    let transport = Arc::new(AntQuicTransport::new(TransportConfig::default()));
+    let signaling = Arc::new(SignalingHandler::new(transport));
+    let service: WebRtcService<PeerIdentityString, _> =
+        WebRtcService::builder(signaling).build().await?;
+    service.start().await?;
+
+    let report = service.health().await;
+    let mark = |status: HealthStatus| match status {
+        HealthStatus::Healthy => "✅",
+        HealthStatus::Degraded => "⚠️ ",
+        HealthStatus::Unhealthy => "❌",
+    };
+
+    println!("📊 Saorsa WebRTC Service Health");
+    println!("==============================");
+    println!(
+        "{} Transport: {} calls, {} connected, {} connecting, {} failed",
+        mark(report.transport.status()),
+        report.transport.calls,
+        report.transport.connected,
+        report.transport.connecting,
+        report.transport.failed
+    );
+    let last_message = report
+        .signaling
+        .last_message_ms
+        .map_or("no messages yet".to_string(), |ms| {
+            format!("last message {ms} ms ago")
+        });
+    println!(
+        "{} Signaling: {}, {} consecutive errors",
+        mark(report.signaling.status()),
+        last_message,
+        report.signaling.consecutive_errors
+    );
+    let list = |names: &[String]| {
+        if names.is_empty() {
+            "none".to_string()
+        } else {
+            names.join(", ")
+        }
+    };
+    println!(
+        "{} Codecs: video {} | audio {}",
+        mark(report.codecs.status()),
+        list(&report.codecs.video),
+        list(&report.codecs.audio)
+    );
+    println!(
+        "{} Devices: audio {} | video {}",
+        mark(report.devices.status()),
+        list(&report.devices.audio),
+        list(&report.devices.video)
+    );
+    println!("{} Tasks:", mark(report.task_status()));
+    for task in &report.tasks.tasks {
+        println!(
+            "   {}: {:?}, {} restarts",
+            task.name, task.status, task.restarts
+        );
+    }
+    println!();
+    println!(
+        "Overall: {} ({})",
+        report.status(),
+        if report.is_ready() {
+            "ready"
+        } else {
+            "not ready"
+        }
+    );
+
+    if !report.is_ready() {
+        anyhow::bail!("service is not ready");
+    }
+    Ok(())
 }
@@ -0,0 +1,375 @@
+//! Interactive main menu for `saorsa tui`
+//!
+//! Tabs for contacts, recently called peers, the calls of the running
+//! service and settings, so the CLI can be driven without remembering its
+//! subcommands. [`MenuState`] holds what is shown and turns key presses into
+//! [`MenuAction`]s; [`MainMenu`] draws it in the terminal.
+
+use anyhow::Result;
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Tabs},
+    Frame, Terminal,
+};
+use std::io::{self, Stdout};
+use std::time::Duration;
+
+use crate::terminal_ui::CliDisplayMode;
+use saorsa_webrtc_core::types::{CallId, CallState};
+
+/// Tabs of the menu, in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tab {
+    /// Saved contacts
+    Contacts,
+    /// Recently called peers
+    History,
+    /// Calls of the running service
+    Calls,
+    /// Settings
+    Settings,
+}
+
+impl Tab {
+    const ALL: [Tab; 4] = [Tab::Contacts, Tab::History, Tab::Calls, Tab::Settings];
+
+    fn title(self) -> &'static str {
+        match self {
+            Tab::Contacts => "Contacts",
+            Tab::History => "History",
+            Tab::Calls => "Calls",
+            Tab::Settings => "Settings",
+        }
+    }
+
+    fn index(self) -> usize {
+        Tab::ALL.iter().position(|tab| *tab == self).unwrap_or(0)
+    }
+}
+
+/// What the user asked the menu for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MenuAction {
+    /// Leave the menu
+    Quit,
+    /// Call a peer
+    Call(String),
+    /// Show a call of the service
+    Open(CallId),
+    /// Turn Do Not Disturb on or off
+    SetDoNotDisturb(bool),
+}
+
+/// Settings row the cursor can be on
+const SETTING_DISPLAY: usize = 0;
+const SETTING_DND: usize = 1;
+
+/// Everything the menu shows
+#[derive(Debug)]
+pub struct MenuState {
+    /// Tab shown
+    pub tab: Tab,
+    /// Highlighted row of the tab
+    pub selected: usize,
+    /// `(name, identity)` of each contact
+    pub contacts: Vec<(String, String)>,
+    /// `(label, identity)` of each recent peer, most recent first
+    pub recent: Vec<(String, String)>,
+    /// Calls of the service
+    pub calls: Vec<(CallId, String, CallState)>,
+    /// Our identity
+    pub identity: String,
+    /// Video display mode for calls placed from the menu
+    pub display: CliDisplayMode,
+    /// Whether Do Not Disturb is on
+    pub do_not_disturb: bool,
+}
+
+impl MenuState {
+    /// Menu on the contacts tab
+    pub fn new(identity: String, display: CliDisplayMode) -> Self {
+        Self {
+            tab: Tab::Contacts,
+            selected: 0,
+            contacts: Vec::new(),
+            recent: Vec::new(),
+            calls: Vec::new(),
+            identity,
+            display,
+            do_not_disturb: false,
+        }
+    }
+
+    /// Rows of the tab shown
+    pub fn rows(&self) -> Vec<String> {
+        match self.tab {
+            Tab::Contacts => self
+                .contacts
+                .iter()
+                .map(|(name, identity)| format!("{name}  {identity}"))
+                .collect(),
+            Tab::History => self.recent.iter().map(|(label, _)| label.clone()).collect(),
+            Tab::Calls => self
+                .calls
+                .iter()
+                .map(|(call_id, peer, state)| {
+                    format!("{peer}  {state:?}  {}", call_id.short_code())
+                })
+                .collect(),
+            Tab::Settings => vec![
+                format!("Video display: {:?}", self.display),
+                format!(
+                    "Do Not Disturb: {}",
+                    if self.do_not_disturb { "on" } else { "off" }
+                ),
+                format!("Identity: {}", self.identity),
+            ],
+        }
+    }
+
+    /// Apply a key press, returning the action it asks for, if any
+    pub fn handle_key(&mut self, key: KeyCode) -> Option<MenuAction> {
+        let rows = self.rows().len();
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return Some(MenuAction::Quit),
+            KeyCode::Tab | KeyCode::Right => self.switch_tab(1),
+            KeyCode::BackTab | KeyCode::Left => self.switch_tab(Tab::ALL.len() - 1),
+            KeyCode::Char(c @ '1'..='4') => {
+                let index = usize::from(c as u8 - b'1');
+                self.tab = Tab::ALL[index];
+                self.selected = 0;
+            }
+            KeyCode::Down | KeyCode::Char('j') if self.selected + 1 < rows => {
+                self.selected += 1;
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Enter => return self.activate(),
+            _ => {}
+        }
+        None
+    }
+
+    /// Keep the cursor on a row after the lists change
+    pub fn clamp_selection(&mut self) {
+        self.selected = self.selected.min(self.rows().len().saturating_sub(1));
+    }
+
+    fn switch_tab(&mut self, step: usize) {
+        self.tab = Tab::ALL[(self.tab.index() + step) % Tab::ALL.len()];
+        self.selected = 0;
+    }
+
+    /// Act on the highlighted row
+    fn activate(&mut self) -> Option<MenuAction> {
+        match self.tab {
+            Tab::Contacts => self
+                .contacts
+                .get(self.selected)
+                .map(|(_, identity)| MenuAction::Call(identity.clone())),
+            Tab::History => self
+                .recent
+                .get(self.selected)
+                .map(|(_, identity)| MenuAction::Call(identity.clone())),
+            Tab::Calls => self
+                .calls
+                .get(self.selected)
+                .map(|(call_id, _, _)| MenuAction::Open(*call_id)),
+            Tab::Settings => match self.selected {
+                SETTING_DISPLAY => {
+                    self.display = match self.display {
+                        CliDisplayMode::Sixel => CliDisplayMode::Ascii,
+                        CliDisplayMode::Ascii => CliDisplayMode::None,
+                        CliDisplayMode::None => CliDisplayMode::Sixel,
+                    };
+                    None
+                }
+                SETTING_DND => {
+                    self.do_not_disturb = !self.do_not_disturb;
+                    Some(MenuAction::SetDoNotDisturb(self.do_not_disturb))
+                }
+                _ => None,
+            },
+        }
+    }
+}
+
+/// The menu drawn in the terminal
+pub struct MainMenu {
+    terminal: Terminal<CrosstermBackend<Stdout>>,
+}
+
+impl MainMenu {
+    /// Take over the terminal
+    pub fn new() -> Result<Self> {
+        enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen)?;
+        let terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+        Ok(Self { terminal })
+    }
+
+    /// Draw the menu and wait up to `refresh` for a key press
+    ///
+    /// Returns the action the key asked for, or `None` if there was none,
+    /// so the caller can update the lists and draw the menu again.
+    pub fn run(&mut self, state: &mut MenuState, refresh: Duration) -> Result<Option<MenuAction>> {
+        self.terminal.draw(|f| draw_menu(f, state))?;
+        if !event::poll(refresh)? {
+            return Ok(None);
+        }
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => Ok(state.handle_key(key.code)),
+            _ => Ok(None),
+        }
+    }
+}
+
+impl Drop for MainMenu {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = execute!(self.terminal.backend_mut(), LeaveAlternateScreen);
+        let _ = self.terminal.show_cursor();
+    }
+}
+
+fn draw_menu(f: &mut Frame, state: &MenuState) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(3),
+            Constraint::Length(3),
+        ])
+        .split(f.size());
+
+    let tabs = Tabs::new(
+        Tab::ALL
+            .iter()
+            .enumerate()
+            .map(|(i, tab)| Line::from(format!("{} {}", i + 1, tab.title())))
+            .collect(),
+    )
+    .block(Block::default().title("📞 Saorsa").borders(Borders::ALL))
+    .select(state.tab.index())
+    .highlight_style(
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    );
+    f.render_widget(tabs, chunks[0]);
+
+    let rows = state.rows();
+    let empty = match state.tab {
+        Tab::Contacts => "No contacts yet; add one with `saorsa contacts add`",
+        Tab::History => "No recent calls",
+        Tab::Calls => "No calls",
+        Tab::Settings => "",
+    };
+    if rows.is_empty() {
+        let paragraph = Paragraph::new(empty).block(
+            Block::default()
+                .title(state.tab.title())
+                .borders(Borders::ALL),
+        );
+        f.render_widget(paragraph, chunks[1]);
+    } else {
+        let items: Vec<ListItem> = rows.into_iter().map(ListItem::new).collect();
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title(state.tab.title())
+                    .borders(Borders::ALL),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("> ");
+        let mut list_state = ListState::default();
+        list_state.select(Some(state.selected));
+        f.render_stateful_widget(list, chunks[1], &mut list_state);
+    }
+
+    let hint = match state.tab {
+        Tab::Contacts | Tab::History => " Call",
+        Tab::Calls => " Show",
+        Tab::Settings => " Change",
+    };
+    let help = Paragraph::new(Line::from(vec![
+        Span::styled("(Tab/1-4)", Style::default().fg(Color::Blue)),
+        Span::raw(" Switch | "),
+        Span::styled("(↑/↓)", Style::default().fg(Color::Blue)),
+        Span::raw(" Move | "),
+        Span::styled("(Enter)", Style::default().fg(Color::Green)),
+        Span::raw(hint),
+        Span::raw(" | "),
+        Span::styled(
+            "(q/Esc)",
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(" Quit"),
+    ]))
+    .block(Block::default().borders(Borders::ALL));
+    f.render_widget(help, chunks[2]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> MenuState {
+        let mut state = MenuState::new("me".to_string(), CliDisplayMode::Sixel);
+        state.contacts = vec![
+            ("Alice".to_string(), "alice-id".to_string()),
+            ("Bob".to_string(), "bob-id".to_string()),
+        ];
+        state.recent = vec![("carol-id".to_string(), "carol-id".to_string())];
+        state
+    }
+
+    #[test]
+    fn test_menu_calls_selected_contact() {
+        let mut state = state();
+        assert_eq!(state.handle_key(KeyCode::Down), None);
+        assert_eq!(state.handle_key(KeyCode::Down), None);
+        assert_eq!(state.selected, 1);
+        assert_eq!(
+            state.handle_key(KeyCode::Enter),
+            Some(MenuAction::Call("bob-id".to_string()))
+        );
+
+        state.handle_key(KeyCode::Tab);
+        assert_eq!(state.tab, Tab::History);
+        assert_eq!(state.selected, 0);
+        assert_eq!(
+            state.handle_key(KeyCode::Enter),
+            Some(MenuAction::Call("carol-id".to_string()))
+        );
+
+        state.handle_key(KeyCode::Char('3'));
+        assert_eq!(state.handle_key(KeyCode::Enter), None);
+        assert_eq!(state.handle_key(KeyCode::Esc), Some(MenuAction::Quit));
+    }
+
+    #[test]
+    fn test_menu_settings() {
+        let mut state = state();
+        state.handle_key(KeyCode::BackTab);
+        assert_eq!(state.tab, Tab::Settings);
+
+        assert_eq!(state.handle_key(KeyCode::Enter), None);
+        assert!(matches!(state.display, CliDisplayMode::Ascii));
+
+        state.handle_key(KeyCode::Down);
+        assert_eq!(
+            state.handle_key(KeyCode::Enter),
+            Some(MenuAction::SetDoNotDisturb(true))
+        );
+        assert!(state.rows()[1].ends_with("on"));
+    }
+}
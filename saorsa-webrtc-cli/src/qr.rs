@@ -0,0 +1,546 @@
+//! Minimal QR code encoder for showing invitation links in the terminal
+//!
+//! Encodes bytes in byte mode at error correction level M, versions 1 to 10
+//! (up to 213 bytes), which comfortably fits a `saorsa://call` link.
+
+/// Error correction layout of one version at level M
+struct Layout {
+    /// Error correction codewords per block
+    ec_per_block: usize,
+    /// (block count, data codewords per block) for the two block groups
+    groups: [(usize, usize); 2],
+}
+
+const LAYOUTS: [Layout; 10] = [
+    Layout {
+        ec_per_block: 10,
+        groups: [(1, 16), (0, 0)],
+    },
+    Layout {
+        ec_per_block: 16,
+        groups: [(1, 28), (0, 0)],
+    },
+    Layout {
+        ec_per_block: 26,
+        groups: [(1, 44), (0, 0)],
+    },
+    Layout {
+        ec_per_block: 18,
+        groups: [(2, 32), (0, 0)],
+    },
+    Layout {
+        ec_per_block: 24,
+        groups: [(2, 43), (0, 0)],
+    },
+    Layout {
+        ec_per_block: 16,
+        groups: [(4, 27), (0, 0)],
+    },
+    Layout {
+        ec_per_block: 18,
+        groups: [(4, 31), (0, 0)],
+    },
+    Layout {
+        ec_per_block: 22,
+        groups: [(2, 38), (2, 39)],
+    },
+    Layout {
+        ec_per_block: 22,
+        groups: [(3, 36), (2, 37)],
+    },
+    Layout {
+        ec_per_block: 26,
+        groups: [(4, 43), (1, 44)],
+    },
+];
+
+/// Alignment pattern centre coordinates per version
+const ALIGNMENT: [&[usize]; 10] = [
+    &[],
+    &[6, 18],
+    &[6, 22],
+    &[6, 26],
+    &[6, 30],
+    &[6, 34],
+    &[6, 22, 38],
+    &[6, 24, 42],
+    &[6, 26, 46],
+    &[6, 28, 50],
+];
+
+/// Modules of light border around the code
+const QUIET_ZONE: usize = 4;
+
+impl Layout {
+    fn data_codewords(&self) -> usize {
+        self.groups.iter().map(|(blocks, len)| blocks * len).sum()
+    }
+}
+
+/// Encoded QR symbol
+#[derive(Debug, Clone)]
+pub struct QrCode {
+    size: usize,
+    modules: Vec<bool>,
+    function: Vec<bool>,
+}
+
+impl QrCode {
+    /// Encode bytes in the smallest version that fits, or `None` if they
+    /// exceed version 10
+    pub fn encode(data: &[u8]) -> Option<Self> {
+        let (version, layout) = (1..=LAYOUTS.len()).find_map(|version| {
+            let layout = &LAYOUTS[version - 1];
+            let bits = 4 + count_bits(version) + data.len() * 8;
+            (bits <= layout.data_codewords() * 8).then_some((version, layout))
+        })?;
+
+        let codewords = add_error_correction(&data_codewords(data, version, layout), layout);
+        let size = 17 + 4 * version;
+        let mut code = Self {
+            size,
+            modules: vec![false; size * size],
+            function: vec![false; size * size],
+        };
+        code.draw_function_patterns(version);
+        code.draw_codewords(&codewords);
+
+        let mask = (0..8)
+            .min_by_key(|&mask| {
+                code.apply_mask(mask);
+                code.draw_format(mask);
+                let penalty = code.penalty();
+                code.apply_mask(mask);
+                penalty
+            })
+            .unwrap_or(0);
+        code.apply_mask(mask);
+        code.draw_format(mask);
+        Some(code)
+    }
+
+    /// Width and height in modules
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Whether the module at column `x`, row `y` is dark
+    pub fn is_dark(&self, x: usize, y: usize) -> bool {
+        x < self.size && y < self.size && self.modules[y * self.size + x]
+    }
+
+    /// Render with half-block characters, two module rows per text line
+    ///
+    /// Dark modules are drawn as blank cells and light ones as filled, so
+    /// the code scans on terminals with a dark background.
+    pub fn render(&self) -> String {
+        let extent = self.size() + 2 * QUIET_ZONE;
+        let dark = |x: usize, y: usize| {
+            x >= QUIET_ZONE && y >= QUIET_ZONE && self.is_dark(x - QUIET_ZONE, y - QUIET_ZONE)
+        };
+        let mut out = String::new();
+        for y in (0..extent).step_by(2) {
+            for x in 0..extent {
+                out.push(match (dark(x, y), dark(x, y + 1)) {
+                    (false, false) => '█',
+                    (false, true) => '▀',
+                    (true, false) => '▄',
+                    (true, true) => ' ',
+                });
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        let index = y * self.size + x;
+        self.modules[index] = dark;
+        self.function[index] = true;
+    }
+
+    fn draw_function_patterns(&mut self, version: usize) {
+        let size = self.size;
+        for i in 0..size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+
+        // Finder patterns with their separators
+        for (cx, cy) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+            for dy in -4isize..=4 {
+                for dx in -4isize..=4 {
+                    let (x, y) = (cx as isize + dx, cy as isize + dy);
+                    if (0..size as isize).contains(&x) && (0..size as isize).contains(&y) {
+                        let distance = dx.abs().max(dy.abs());
+                        self.set_function(x as usize, y as usize, distance != 2 && distance != 4);
+                    }
+                }
+            }
+        }
+
+        let centres = ALIGNMENT[version - 1];
+        let last = centres.len().saturating_sub(1);
+        for (i, &cy) in centres.iter().enumerate() {
+            for (j, &cx) in centres.iter().enumerate() {
+                // Skip the three corners taken by finder patterns
+                if [(0, 0), (0, last), (last, 0)].contains(&(i, j)) {
+                    continue;
+                }
+                for dy in -2isize..=2 {
+                    for dx in -2isize..=2 {
+                        let distance = dx.abs().max(dy.abs());
+                        self.set_function(
+                            (cx as isize + dx) as usize,
+                            (cy as isize + dy) as usize,
+                            distance != 1,
+                        );
+                    }
+                }
+            }
+        }
+
+        // Reserve the format areas; real bits are drawn after masking
+        self.draw_format(0);
+
+        if version >= 7 {
+            let bits = version_bits(version as u32);
+            for i in 0..18 {
+                let dark = bits >> i & 1 == 1;
+                let (a, b) = (size - 11 + i % 3, i / 3);
+                self.set_function(a, b, dark);
+                self.set_function(b, a, dark);
+            }
+        }
+    }
+
+    /// Draw format information for level M and a mask
+    fn draw_format(&mut self, mask: u32) {
+        let bits = format_bits(mask);
+        let bit = |i: usize| bits >> i & 1 == 1;
+        let size = self.size;
+
+        for i in 0..=5 {
+            self.set_function(8, i, bit(i));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(i));
+        }
+
+        for i in 0..8 {
+            self.set_function(size - 1 - i, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i, bit(i));
+        }
+        // Always-dark module
+        self.set_function(8, size - 8, true);
+    }
+
+    /// Place codewords in the zigzag column-pair order
+    fn draw_codewords(&mut self, codewords: &[u8]) {
+        let size = self.size;
+        let total_bits = codewords.len() * 8;
+        let mut bit = 0;
+        let mut right = size - 1;
+        loop {
+            if right == 6 {
+                right = 5;
+            }
+            let upward = (right + 1) & 2 == 0;
+            for vertical in 0..size {
+                let y = if upward {
+                    size - 1 - vertical
+                } else {
+                    vertical
+                };
+                for x in [right, right - 1] {
+                    let index = y * size + x;
+                    if !self.function[index] && bit < total_bits {
+                        self.modules[index] = codewords[bit / 8] >> (7 - bit % 8) & 1 == 1;
+                        bit += 1;
+                    }
+                }
+            }
+            if right < 2 {
+                break;
+            }
+            right -= 2;
+        }
+    }
+
+    /// Toggle data modules under a mask pattern; applying twice undoes it
+    fn apply_mask(&mut self, mask: u32) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let invert = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                let index = y * self.size + x;
+                if invert && !self.function[index] {
+                    self.modules[index] = !self.modules[index];
+                }
+            }
+        }
+    }
+
+    /// Penalty score used to pick the mask that is easiest to scan
+    fn penalty(&self) -> usize {
+        let size = self.size;
+        let mut penalty = 0;
+
+        // Runs of five or more modules and finder-like patterns, in rows and
+        // columns
+        let finder_like = [
+            true, false, true, true, true, false, true, false, false, false, false,
+        ];
+        for transpose in [false, true] {
+            for a in 0..size {
+                let line: Vec<bool> = (0..size)
+                    .map(|b| {
+                        if transpose {
+                            self.is_dark(a, b)
+                        } else {
+                            self.is_dark(b, a)
+                        }
+                    })
+                    .collect();
+                let mut run = 1;
+                for b in 1..=size {
+                    if b < size && line[b] == line[b - 1] {
+                        run += 1;
+                    } else {
+                        if run >= 5 {
+                            penalty += run - 2;
+                        }
+                        run = 1;
+                    }
+                }
+                for window in line.windows(finder_like.len()) {
+                    if window == finder_like || window.iter().rev().eq(finder_like.iter()) {
+                        penalty += 40;
+                    }
+                }
+            }
+        }
+
+        // 2x2 blocks of one colour
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let colour = self.is_dark(x, y);
+                if colour == self.is_dark(x + 1, y)
+                    && colour == self.is_dark(x, y + 1)
+                    && colour == self.is_dark(x + 1, y + 1)
+                {
+                    penalty += 3;
+                }
+            }
+        }
+
+        // Balance of dark and light
+        let dark = self.modules.iter().filter(|&&m| m).count();
+        let total = size * size;
+        let deviation = (dark * 20).abs_diff(total * 10);
+        penalty + deviation.div_ceil(total).saturating_sub(1) * 10
+    }
+}
+
+/// Bits in the byte-mode character count for a version
+fn count_bits(version: usize) -> usize {
+    if version < 10 {
+        8
+    } else {
+        16
+    }
+}
+
+/// Byte-mode segment, terminator and padding as data codewords
+fn data_codewords(data: &[u8], version: usize, layout: &Layout) -> Vec<u8> {
+    let capacity = layout.data_codewords() * 8;
+    let mut bits = Vec::with_capacity(capacity);
+    let mut push = |value: usize, len: usize| {
+        for i in (0..len).rev() {
+            bits.push(value >> i & 1 == 1);
+        }
+    };
+    push(0b0100, 4);
+    push(data.len(), count_bits(version));
+    for &byte in data {
+        push(usize::from(byte), 8);
+    }
+
+    let terminator = (capacity - bits.len()).min(4);
+    bits.extend(std::iter::repeat_n(false, terminator));
+    while bits.len() % 8 != 0 {
+        bits.push(false);
+    }
+    let mut codewords: Vec<u8> = bits
+        .chunks(8)
+        .map(|byte| byte.iter().fold(0, |acc, &bit| acc << 1 | u8::from(bit)))
+        .collect();
+    for pad in [0xEC, 0x11].into_iter().cycle() {
+        if codewords.len() >= layout.data_codewords() {
+            break;
+        }
+        codewords.push(pad);
+    }
+    codewords
+}
+
+/// Split data into blocks, add Reed-Solomon codewords and interleave
+fn add_error_correction(data: &[u8], layout: &Layout) -> Vec<u8> {
+    let generator = rs_generator(layout.ec_per_block);
+    let mut blocks = Vec::new();
+    let mut offset = 0;
+    for &(count, len) in &layout.groups {
+        for _ in 0..count {
+            let block = &data[offset..offset + len];
+            blocks.push((block, rs_remainder(block, &generator)));
+            offset += len;
+        }
+    }
+
+    let longest = layout.groups.iter().map(|&(_, len)| len).max().unwrap_or(0);
+    let mut out = Vec::with_capacity(data.len() + blocks.len() * layout.ec_per_block);
+    for i in 0..longest {
+        out.extend(blocks.iter().filter_map(|(block, _)| block.get(i)));
+    }
+    for i in 0..layout.ec_per_block {
+        out.extend(blocks.iter().filter_map(|(_, ec)| ec.get(i)));
+    }
+    out
+}
+
+/// Multiply in GF(256) with the QR polynomial x^8 + x^4 + x^3 + x^2 + 1
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        let carry = a & 0x80 != 0;
+        a <<= 1;
+        if carry {
+            a ^= 0x1D;
+        }
+        b >>= 1;
+    }
+    product
+}
+
+/// Reed-Solomon generator polynomial coefficients, highest degree first,
+/// without the leading 1
+fn rs_generator(degree: usize) -> Vec<u8> {
+    let mut coefficients = vec![0u8; degree];
+    if let Some(last) = coefficients.last_mut() {
+        *last = 1;
+    }
+    let mut root = 1u8;
+    for _ in 0..degree {
+        for j in 0..degree {
+            coefficients[j] = gf_mul(coefficients[j], root);
+            if j + 1 < degree {
+                coefficients[j] ^= coefficients[j + 1];
+            }
+        }
+        root = gf_mul(root, 0x02);
+    }
+    coefficients
+}
+
+/// Reed-Solomon error correction codewords for a block
+fn rs_remainder(data: &[u8], generator: &[u8]) -> Vec<u8> {
+    let mut remainder = vec![0u8; generator.len()];
+    for &byte in data {
+        let factor = byte ^ remainder.first().copied().unwrap_or(0);
+        remainder.rotate_left(1);
+        if let Some(last) = remainder.last_mut() {
+            *last = 0;
+        }
+        for (r, &g) in remainder.iter_mut().zip(generator) {
+            *r ^= gf_mul(g, factor);
+        }
+    }
+    remainder
+}
+
+/// 15-bit format information for level M and a mask
+fn format_bits(mask: u32) -> u32 {
+    // Level M is 0b00
+    let data = mask;
+    let mut remainder = data;
+    for _ in 0..10 {
+        remainder = (remainder << 1) ^ ((remainder >> 9) * 0x537);
+    }
+    (data << 10 | remainder) ^ 0x5412
+}
+
+/// 18-bit version information, drawn for versions 7 and up
+fn version_bits(version: u32) -> u32 {
+    let mut remainder = version;
+    for _ in 0..12 {
+        remainder = (remainder << 1) ^ ((remainder >> 11) * 0x1F25);
+    }
+    version << 12 | remainder
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reed_solomon_reference_block() {
+        // "HELLO WORLD" at 1-M, from the QR specification walkthrough
+        let data = [
+            32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236, 17, 236, 17,
+        ];
+        assert_eq!(
+            rs_remainder(&data, &rs_generator(10)),
+            vec![196, 35, 39, 119, 235, 215, 231, 226, 93, 23]
+        );
+    }
+
+    #[test]
+    fn test_format_and_version_bits() {
+        assert_eq!(format_bits(0), 0b101010000010010);
+        assert_eq!(format_bits(5), 0b100000011001110);
+        assert_eq!(format_bits(7), 0b100101010100000);
+        assert_eq!(version_bits(7), 0b000111110010010100);
+    }
+
+    #[test]
+    fn test_version_selection() {
+        assert_eq!(QrCode::encode(b"saorsa").map(|c| c.size()), Some(21));
+        assert_eq!(QrCode::encode(&[b'x'; 213]).map(|c| c.size()), Some(57));
+        assert!(QrCode::encode(&[b'x'; 214]).is_none());
+    }
+
+    #[test]
+    fn test_finder_patterns_and_render() {
+        let code =
+            QrCode::encode(b"saorsa://call?peer=alpha-bravo-charlie-delta&caps=audio").unwrap();
+        let size = code.size();
+        for (x, y) in [(0, 0), (size - 1, 0), (0, size - 1), (3, 3), (6, 6)] {
+            assert!(code.is_dark(x, y), "({x}, {y})");
+        }
+        for (x, y) in [(1, 1), (7, 7), (size - 8, 0)] {
+            assert!(!code.is_dark(x, y), "({x}, {y})");
+        }
+        assert!(code.is_dark(8, size - 8));
+
+        let rendered = code.render();
+        let extent = size + 2 * QUIET_ZONE;
+        assert_eq!(rendered.lines().count(), extent.div_ceil(2));
+        assert!(rendered.lines().all(|line| line.chars().count() == extent));
+    }
+}
@@ -0,0 +1,114 @@
+//! Peers called recently
+//!
+//! Kept in `recent.json` next to the contacts file, most recent first, so
+//! `saorsa call` can offer them when it is not told whom to call or the name
+//! matches no contact.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Number of peers remembered
+pub const MAX_RECENT_PEERS: usize = 20;
+
+/// A peer called before
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecentPeer {
+    /// Peer identity as called
+    pub identity: String,
+    /// When they were last called
+    pub last_called: DateTime<Utc>,
+}
+
+/// Recently called peers, most recent first
+#[derive(Debug)]
+pub struct RecentPeers {
+    peers: Vec<RecentPeer>,
+    path: PathBuf,
+}
+
+impl RecentPeers {
+    /// File of the recent peers for a contacts file
+    pub fn path_for(contacts: &Path) -> PathBuf {
+        contacts.with_file_name("recent.json")
+    }
+
+    /// Open the list kept at `path`, empty if the file does not exist yet
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let peers = match std::fs::read(&path) {
+            Ok(data) => serde_json::from_slice(&data)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self { peers, path })
+    }
+
+    /// Recent peers, most recent first
+    pub fn list(&self) -> &[RecentPeer] {
+        &self.peers
+    }
+
+    /// Recent peers whose identity contains `query`, ignoring case
+    pub fn search(&self, query: &str) -> Vec<&RecentPeer> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        self.peers
+            .iter()
+            .filter(|peer| peer.identity.to_lowercase().contains(&query))
+            .collect()
+    }
+
+    /// Note a call to `identity` and save the list
+    pub fn record(&mut self, identity: &str) -> Result<()> {
+        self.peers.retain(|peer| peer.identity != identity);
+        self.peers.insert(
+            0,
+            RecentPeer {
+                identity: identity.to_string(),
+                last_called: Utc::now(),
+            },
+        );
+        self.peers.truncate(MAX_RECENT_PEERS);
+
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&self.path, serde_json::to_vec_pretty(&self.peers)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_peers_most_recent_first() {
+        let dir = std::env::temp_dir().join(format!("saorsa-recent-{}", std::process::id()));
+        let path = RecentPeers::path_for(&dir.join("contacts.json"));
+        assert_eq!(path, dir.join("recent.json"));
+
+        let mut recent = RecentPeers::open(&path).unwrap();
+        recent.record("ocean-forest-moon-star").unwrap();
+        recent.record("river-mountain-sun-cloud").unwrap();
+        recent.record("ocean-forest-moon-star").unwrap();
+
+        let reopened = RecentPeers::open(&path).unwrap();
+        let identities: Vec<&str> = reopened
+            .list()
+            .iter()
+            .map(|peer| peer.identity.as_str())
+            .collect();
+        assert_eq!(
+            identities,
+            vec!["ocean-forest-moon-star", "river-mountain-sun-cloud"]
+        );
+        assert_eq!(reopened.search("MOUNTAIN").len(), 1);
+        assert!(reopened.search("").is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
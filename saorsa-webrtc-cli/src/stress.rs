@@ -0,0 +1,321 @@
+//! Back-to-back call stress testing
+//!
+//! Repeatedly sets up and tears down calls against one peer with a bounded
+//! number in flight, to shake out leaks and races in the call manager under
+//! churn. Setup latency is the time from placing a call until the callee
+//! accepts it and the connection is established. Calls that are rejected,
+//! fail to connect or are not accepted within the setup timeout count as
+//! failures, tallied by reason, and any call still held by the manager
+//! afterwards is reported as leaked.
+
+use saorsa_webrtc_core::prelude::*;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, Semaphore};
+use tokio::task::JoinSet;
+
+/// Shape of a stress run
+#[derive(Debug, Clone, Copy)]
+pub struct StressConfig {
+    /// Total calls to place
+    pub calls: usize,
+    /// Calls in flight at once
+    pub parallel: usize,
+    /// How long each call stays up before hanging up
+    pub hold: Duration,
+    /// How long a call may take to be accepted before it counts as failed
+    pub setup_timeout: Duration,
+}
+
+/// Result of a stress run
+#[derive(Debug, Default)]
+pub struct StressReport {
+    /// Setup latency of each successful call, sorted ascending
+    pub setup: Vec<Duration>,
+    /// Failed calls by reason
+    pub failures: BTreeMap<String, usize>,
+    /// Calls still held by the call manager after the run
+    pub leaked_calls: usize,
+    /// Wall-clock duration of the run
+    pub elapsed: Duration,
+}
+
+impl StressReport {
+    /// Setup latency at percentile `p` (0-100), nearest rank
+    pub fn percentile(&self, p: f64) -> Option<Duration> {
+        if self.setup.is_empty() {
+            return None;
+        }
+        let rank = (p / 100.0 * self.setup.len() as f64).ceil() as usize;
+        self.setup.get(rank.clamp(1, self.setup.len()) - 1).copied()
+    }
+
+    /// Total failed calls
+    pub fn failed(&self) -> usize {
+        self.failures.values().sum()
+    }
+
+    /// Whether every call succeeded and nothing leaked
+    pub fn is_clean(&self) -> bool {
+        self.failures.is_empty() && self.leaked_calls == 0
+    }
+
+    fn record(&mut self, outcome: Result<Duration, String>) {
+        match outcome {
+            Ok(latency) => self.setup.push(latency),
+            Err(reason) => *self.failures.entry(reason).or_default() += 1,
+        }
+    }
+}
+
+impl fmt::Display for StressReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Calls: {} ok, {} failed in {:.2?}",
+            self.setup.len(),
+            self.failed(),
+            self.elapsed
+        )?;
+        if let (Some(p50), Some(p90), Some(p99), Some(max)) = (
+            self.percentile(50.0),
+            self.percentile(90.0),
+            self.percentile(99.0),
+            self.setup.last(),
+        ) {
+            writeln!(
+                f,
+                "Setup latency: p50 {p50:.2?} | p90 {p90:.2?} | p99 {p99:.2?} | max {max:.2?}"
+            )?;
+        }
+        for (reason, count) in &self.failures {
+            writeln!(f, "  {count} x {reason}")?;
+        }
+        write!(f, "Leaked calls: {}", self.leaked_calls)
+    }
+}
+
+/// Place `config.calls` calls to `peer`, at most `config.parallel` at a time
+pub async fn run<T: SignalingTransport + 'static>(
+    service: Arc<WebRtcService<PeerIdentityString, T>>,
+    peer: &str,
+    config: StressConfig,
+) -> StressReport {
+    let started = Instant::now();
+    let permits = Arc::new(Semaphore::new(config.parallel.max(1)));
+    let mut tasks = JoinSet::new();
+
+    for _ in 0..config.calls {
+        let service = Arc::clone(&service);
+        let permits = Arc::clone(&permits);
+        let peer = PeerIdentityString::new(peer);
+        tasks.spawn(async move {
+            let _permit = permits.acquire_owned().await.map_err(|e| e.to_string())?;
+            place_call(&service, peer, config).await
+        });
+    }
+
+    let mut report = StressReport::default();
+    while let Some(joined) = tasks.join_next().await {
+        report.record(joined.unwrap_or_else(|e| Err(format!("task: {e}"))));
+    }
+    report.setup.sort();
+    report.leaked_calls = service.call_manager().active_call_count().await;
+    report.elapsed = started.elapsed();
+    report
+}
+
+/// Set up one call, hold it, and hang up
+async fn place_call<T: SignalingTransport>(
+    service: &WebRtcService<PeerIdentityString, T>,
+    peer: PeerIdentityString,
+    config: StressConfig,
+) -> Result<Duration, String> {
+    // Subscribe first so the call's events cannot be missed
+    let mut events = service.subscribe_call_events();
+    let started = Instant::now();
+    let call_id = service
+        .initiate_call(peer, MediaConstraints::audio_only())
+        .await
+        .map_err(|e| format!("setup: {e}"))?;
+
+    let connected = tokio::time::timeout(
+        config.setup_timeout,
+        wait_connected(service, &mut events, call_id),
+    )
+    .await
+    .unwrap_or_else(|_| Err("setup: timed out".to_string()));
+    let latency = started.elapsed();
+    if let Err(reason) = connected {
+        // Release whatever is left of the call so it is not counted as leaked
+        let _ = service.end_call(call_id).await;
+        return Err(reason);
+    }
+
+    tokio::time::sleep(config.hold).await;
+    service
+        .end_call(call_id)
+        .await
+        .map_err(|e| format!("teardown: {e}"))?;
+    Ok(latency)
+}
+
+/// Wait until a call is accepted and connected, or fails
+async fn wait_connected<T: SignalingTransport>(
+    service: &WebRtcService<PeerIdentityString, T>,
+    events: &mut broadcast::Receiver<CallEvent<PeerIdentityString>>,
+    call_id: CallId,
+) -> Result<(), String> {
+    loop {
+        match events.recv().await {
+            Ok(
+                CallEvent::CallAccepted { call_id: id, .. }
+                | CallEvent::ConnectionEstablished { call_id: id },
+            ) if id == call_id => return Ok(()),
+            Ok(CallEvent::CallRejected { call_id: id }) if id == call_id => {
+                return Err("setup: rejected".to_string())
+            }
+            Ok(CallEvent::ConnectionFailed { call_id: id, error }) if id == call_id => {
+                return Err(format!("setup: connection failed: {error}"))
+            }
+            Ok(CallEvent::CallEnded { call_id: id }) if id == call_id => {
+                return Err("setup: ended before connecting".to_string())
+            }
+            Ok(_) => {}
+            // Events were dropped under load; the call's state still tells
+            Err(broadcast::error::RecvError::Lagged(_)) => {
+                match service.get_call_state(call_id).await {
+                    Some(CallState::Connected) => return Ok(()),
+                    Some(CallState::Failed) => return Err("setup: failed".to_string()),
+                    None => return Err("setup: ended before connecting".to_string()),
+                    Some(_) => {}
+                }
+            }
+            Err(broadcast::error::RecvError::Closed) => {
+                return Err("setup: service stopped".to_string())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles() {
+        let mut report = StressReport::default();
+        assert_eq!(report.percentile(50.0), None);
+
+        report.setup = (1..=100).map(Duration::from_millis).collect();
+        assert_eq!(report.percentile(50.0), Some(Duration::from_millis(50)));
+        assert_eq!(report.percentile(99.0), Some(Duration::from_millis(99)));
+        assert_eq!(report.percentile(100.0), Some(Duration::from_millis(100)));
+        assert_eq!(report.percentile(0.0), Some(Duration::from_millis(1)));
+    }
+
+    #[test]
+    fn test_failures_grouped_by_reason() {
+        let mut report = StressReport::default();
+        report.record(Ok(Duration::from_millis(3)));
+        report.record(Err("setup: limit".to_string()));
+        report.record(Err("setup: limit".to_string()));
+        report.record(Err("teardown: not found".to_string()));
+
+        assert_eq!(report.failed(), 3);
+        assert_eq!(report.failures["setup: limit"], 2);
+        assert!(!report.is_clean());
+        assert!(report.to_string().contains("2 x setup: limit"));
+    }
+
+    async fn stress_service() -> Arc<WebRtcService<PeerIdentityString, AntQuicTransport>> {
+        let transport = Arc::new(AntQuicTransport::new(TransportConfig::default()));
+        let signaling = Arc::new(SignalingHandler::new(transport));
+        let config = WebRtcConfig {
+            call_config: CallManagerConfig {
+                max_concurrent_calls: 4,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        Arc::new(
+            WebRtcService::builder(signaling)
+                .with_config(config)
+                .build()
+                .await
+                .unwrap(),
+        )
+    }
+
+    /// Stand in for the callee: answer the `n`th call as `answer(n)` says,
+    /// `Some(true)` to accept, `Some(false)` to reject and `None` to ignore
+    fn answer_calls(
+        service: &Arc<WebRtcService<PeerIdentityString, AntQuicTransport>>,
+        answer: fn(usize) -> Option<bool>,
+    ) {
+        let manager = Arc::clone(service.call_manager());
+        let mut events = manager.subscribe_events();
+        tokio::spawn(async move {
+            let mut placed = 0;
+            while let Ok(event) = events.recv().await {
+                if let CallEvent::CallInitiated { call_id, .. } = event {
+                    let _ = match answer(placed) {
+                        Some(true) => {
+                            manager
+                                .accept_call(call_id, MediaConstraints::audio_only())
+                                .await
+                        }
+                        Some(false) => manager.reject_call(call_id).await,
+                        None => Ok(()),
+                    };
+                    placed += 1;
+                }
+            }
+        });
+    }
+
+    fn config(calls: usize) -> StressConfig {
+        StressConfig {
+            calls,
+            parallel: 4,
+            hold: Duration::from_millis(1),
+            setup_timeout: Duration::from_secs(5),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_churn_leaves_no_calls() {
+        let service = stress_service().await;
+        answer_calls(&service, |_| Some(true));
+
+        let report = run(service, "alpha-bravo-charlie-delta", config(40)).await;
+        assert!(report.is_clean(), "{report}");
+        assert_eq!(report.setup.len(), 40);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_and_unanswered_calls_fail() {
+        let service = stress_service().await;
+        answer_calls(&service, |n| match n % 3 {
+            0 => Some(true),
+            1 => Some(false),
+            _ => None,
+        });
+
+        let report = run(
+            service,
+            "alpha-bravo-charlie-delta",
+            StressConfig {
+                setup_timeout: Duration::from_millis(200),
+                ..config(9)
+            },
+        )
+        .await;
+        assert_eq!(report.setup.len(), 3, "{report}");
+        assert_eq!(report.failures["setup: rejected"], 3);
+        assert_eq!(report.failures["setup: timed out"], 3);
+        assert_eq!(report.leaked_calls, 0);
+    }
+}
@@ -15,11 +15,14 @@ use ratatui::{
     Frame, Terminal,
 };
 use std::{
+    collections::VecDeque,
     io::{self, Stdout},
     sync::Arc,
     time::{Duration, Instant},
 };
+use tokio::sync::broadcast;
 
+use saorsa_webrtc_core::conference::{ChatConfig, ConferenceChat, ConferenceEvent, MessageId};
 use saorsa_webrtc_core::{prelude::*, types::CallId};
 
 /// Display mode for video
@@ -41,6 +44,89 @@ pub struct TerminalUI {
     stats: ConnectionStats,
     muted: bool,
     video_enabled: bool,
+    identity: PeerIdentityString,
+    chat: ChatPanel,
+}
+
+/// Reactions sent with the number keys
+pub const REACTIONS: [&str; 4] = ["👍", "😂", "🎉", "👏"];
+
+/// Chat lines kept on screen
+const CHAT_HISTORY: usize = 50;
+
+/// What the user typed into the chat panel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChatInput {
+    /// Send a message
+    Send(String),
+    /// React to the latest message
+    React(&'static str),
+}
+
+/// Chat and reactions shown beside the video
+#[derive(Debug, Default)]
+pub struct ChatPanel {
+    lines: VecDeque<String>,
+    draft: Option<String>,
+    latest_message: Option<MessageId>,
+}
+
+impl ChatPanel {
+    /// Add a message or reaction to the panel
+    pub fn push(&mut self, event: &ConferenceEvent<PeerIdentityString>) {
+        let line = match event {
+            ConferenceEvent::ChatMessage { id, from, text, .. } => {
+                self.latest_message = Some(*id);
+                format!("{from}: {text}")
+            }
+            ConferenceEvent::Reaction { from, emoji, .. } => format!("{from} {emoji}"),
+        };
+        self.lines.push_back(line);
+        while self.lines.len() > CHAT_HISTORY {
+            self.lines.pop_front();
+        }
+    }
+
+    /// Lines shown, oldest first
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        self.lines.iter().map(String::as_str)
+    }
+
+    /// Message the latest reaction is sent to
+    pub fn latest_message(&self) -> Option<MessageId> {
+        self.latest_message
+    }
+
+    /// Whether a message is being typed
+    pub fn is_composing(&self) -> bool {
+        self.draft.is_some()
+    }
+
+    /// Apply a key press
+    ///
+    /// While a message is being typed every key goes to it; otherwise `c`
+    /// starts a message and the number keys send [`REACTIONS`].
+    pub fn handle_key(&mut self, key: KeyCode) -> Option<ChatInput> {
+        match (&mut self.draft, key) {
+            (Some(draft), KeyCode::Char(c)) => draft.push(c),
+            (Some(draft), KeyCode::Backspace) => {
+                draft.pop();
+            }
+            (Some(_), KeyCode::Esc) => self.draft = None,
+            (Some(_), KeyCode::Enter) => {
+                let text = self.draft.take().unwrap_or_default();
+                if !text.trim().is_empty() {
+                    return Some(ChatInput::Send(text));
+                }
+            }
+            (None, KeyCode::Char('c')) => self.draft = Some(String::new()),
+            (None, KeyCode::Char(c @ '1'..='4')) => {
+                return Some(ChatInput::React(REACTIONS[usize::from(c as u8 - b'1')]));
+            }
+            _ => {}
+        }
+        None
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -52,6 +138,11 @@ pub struct ConnectionStats {
     pub packets_sent: Option<u32>,
 }
 
+/// A statistic with its unit for display, or `-` when it is not measured
+fn or_unknown(value: Option<u32>, unit: &str) -> String {
+    value.map_or_else(|| "-".to_string(), |value| format!("{value}{unit}"))
+}
+
 /// Static UI drawing function for closures
 fn draw_ui_static(
     f: &mut Frame,
@@ -60,6 +151,7 @@ fn draw_ui_static(
     muted: bool,
     video_enabled: bool,
     start_time: Instant,
+    chat: &ChatPanel,
 ) {
     let size = f.size();
 
@@ -67,14 +159,21 @@ fn draw_ui_static(
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Min(10),   // Video area
+            Constraint::Min(10),   // Video and chat
             Constraint::Length(3), // Stats
             Constraint::Length(3), // Controls
         ])
         .split(size);
+    let top = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+        .split(chunks[0]);
 
     // Video display area
-    draw_video_area_static(f, chunks[0], display_mode);
+    draw_video_area_static(f, top[0], display_mode);
+
+    // Chat area
+    draw_chat_area_static(f, top[1], chat);
 
     // Statistics area
     draw_stats_area_static(f, chunks[1], stats, start_time);
@@ -124,6 +223,34 @@ fn draw_video_area_static(f: &mut Frame, area: Rect, display_mode: DisplayMode)
     f.render_widget(paragraph, area);
 }
 
+/// Draw the chat area (static)
+fn draw_chat_area_static(f: &mut Frame, area: Rect, chat: &ChatPanel) {
+    let block = Block::default().title("💬 Chat").borders(Borders::ALL);
+
+    // Newest lines at the bottom, above the message being typed
+    let rows = usize::from(area.height.saturating_sub(3));
+    let lines: Vec<&str> = chat.lines().collect();
+    let mut content: Vec<Line> = lines[lines.len().saturating_sub(rows)..]
+        .iter()
+        .map(|line| Line::from(line.to_string()))
+        .collect();
+    content.push(match &chat.draft {
+        Some(draft) => Line::from(Span::styled(
+            format!("> {draft}_"),
+            Style::default().fg(Color::Yellow),
+        )),
+        None => Line::from(Span::styled(
+            "(c) Chat | (1-4) React",
+            Style::default().fg(Color::DarkGray),
+        )),
+    });
+
+    let paragraph = Paragraph::new(content)
+        .block(block)
+        .wrap(ratatui::widgets::Wrap { trim: false });
+    f.render_widget(paragraph, area);
+}
+
 /// Draw the statistics area (static)
 fn draw_stats_area_static(f: &mut Frame, area: Rect, stats: ConnectionStats, start_time: Instant) {
     let block = Block::default()
@@ -132,15 +259,15 @@ fn draw_stats_area_static(f: &mut Frame, area: Rect, stats: ConnectionStats, sta
 
     let stats_text = vec![
         Line::from(format!(
-            "RTT: {}ms | Bitrate: {}kbps | FPS: {}",
-            stats.rtt_ms.unwrap_or(0),
-            stats.bitrate_kbps.unwrap_or(0),
-            stats.fps.unwrap_or(0)
+            "RTT: {} | Bitrate: {} | FPS: {}",
+            or_unknown(stats.rtt_ms, "ms"),
+            or_unknown(stats.bitrate_kbps, "kbps"),
+            or_unknown(stats.fps, "")
         )),
         Line::from(format!(
             "Packets: Sent {} | Lost {}",
-            stats.packets_sent.unwrap_or(0),
-            stats.packets_lost.unwrap_or(0)
+            or_unknown(stats.packets_sent, ""),
+            or_unknown(stats.packets_lost, "")
         )),
         Line::from(format!(
             "Duration: {:.1}s",
@@ -188,7 +315,7 @@ fn draw_controls_area_static(f: &mut Frame, area: Rect, muted: bool, video_enabl
 
 impl TerminalUI {
     /// Create a new terminal UI
-    pub fn new(display_mode: DisplayMode) -> Result<Self> {
+    pub fn new(display_mode: DisplayMode, identity: &str) -> Result<Self> {
         enable_raw_mode()?;
         let mut stdout = io::stdout();
         execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
@@ -202,19 +329,45 @@ impl TerminalUI {
             stats: ConnectionStats::default(),
             muted: false,
             video_enabled: true,
+            identity: PeerIdentityString::new(identity),
+            chat: ChatPanel::default(),
         })
     }
 
     /// Run the terminal UI main loop
     pub async fn run(
         &mut self,
-        _service: Arc<WebRtcService<PeerIdentityString, AntQuicTransport>>,
-        _call_id: CallId,
+        service: Arc<WebRtcService<PeerIdentityString, AntQuicTransport>>,
+        call_id: CallId,
     ) -> Result<()> {
+        let chat = ConferenceChat::new(self.identity.clone(), ChatConfig::default());
+        let mut chat_events = chat.subscribe();
+        let mut chat_joined = false;
         loop {
+            // Carry the chat over the call once its media is up
+            if !chat_joined {
+                if let Some(transport) = service.call_manager().media_transport(call_id).await {
+                    chat.add_participant(call_id, transport);
+                    chat_joined = true;
+                }
+            }
+            loop {
+                match chat_events.try_recv() {
+                    Ok(event) => self.chat.push(&event),
+                    Err(broadcast::error::TryRecvError::Lagged(_)) => {}
+                    Err(_) => break,
+                }
+            }
+
             // Handle input
             if event::poll(Duration::from_millis(100))? {
                 if let Event::Key(key) = event::read()? {
+                    if self.chat.is_composing() {
+                        if let Some(input) = self.chat.handle_key(key.code) {
+                            send_chat(&chat, &self.chat, input).await;
+                        }
+                        continue;
+                    }
                     match key.code {
                         KeyCode::Char('q') | KeyCode::Esc => break,
                         KeyCode::Char('m') => {
@@ -231,13 +384,17 @@ impl TerminalUI {
                         KeyCode::Char('h') => {
                             // Show help
                         }
-                        _ => {}
+                        code => {
+                            if let Some(input) = self.chat.handle_key(code) {
+                                send_chat(&chat, &self.chat, input).await;
+                            }
+                        }
                     }
                 }
             }
 
             // Update stats
-            self.update_stats().await;
+            self.update_stats(&service, call_id).await;
 
             // Render UI
             let stats = self.stats.clone();
@@ -245,6 +402,7 @@ impl TerminalUI {
             let video_enabled = self.video_enabled;
             let start_time = self.start_time;
             let display_mode = self.display_mode;
+            let chat_panel = &self.chat;
             self.terminal.draw(|f| {
                 draw_ui_static(
                     f,
@@ -253,6 +411,7 @@ impl TerminalUI {
                     muted,
                     video_enabled,
                     start_time,
+                    chat_panel,
                 )
             })?;
 
@@ -264,17 +423,26 @@ impl TerminalUI {
     }
 
     /// Update connection statistics
-    async fn update_stats(&mut self) {
-        // TODO: Get real stats from the service
-        // For now, simulate some stats
-        let elapsed = self.start_time.elapsed().as_secs();
+    ///
+    /// Bitrate and packet counts come from the call's media transport. It
+    /// measures neither RTT, loss nor frame rate, so those stay unknown.
+    async fn update_stats(
+        &mut self,
+        service: &WebRtcService<PeerIdentityString, AntQuicTransport>,
+        call_id: CallId,
+    ) {
+        let transport = service.get_call_stats(call_id).await;
 
         self.stats = ConnectionStats {
-            rtt_ms: Some(23 + (elapsed % 10) as u32),
-            bitrate_kbps: Some(1500 + (elapsed % 500) as u32),
-            fps: Some(30),
-            packets_lost: Some((elapsed / 10) as u32),
-            packets_sent: Some((elapsed * 100) as u32),
+            rtt_ms: None,
+            bitrate_kbps: transport
+                .as_ref()
+                .map(|stats| stats.sent_rates().bitrate_kbps()),
+            fps: None,
+            packets_lost: None,
+            packets_sent: transport
+                .as_ref()
+                .map(|stats| u32::try_from(stats.packets_sent).unwrap_or(u32::MAX)),
         };
     }
 
@@ -383,15 +551,15 @@ impl TerminalUI {
 
         let stats_text = vec![
             Line::from(format!(
-                "RTT: {}ms | Bitrate: {}kbps | FPS: {}",
-                stats.rtt_ms.unwrap_or(0),
-                stats.bitrate_kbps.unwrap_or(0),
-                stats.fps.unwrap_or(0)
+                "RTT: {} | Bitrate: {} | FPS: {}",
+                or_unknown(stats.rtt_ms, "ms"),
+                or_unknown(stats.bitrate_kbps, "kbps"),
+                or_unknown(stats.fps, "")
             )),
             Line::from(format!(
                 "Packets: Sent {} | Lost {}",
-                stats.packets_sent.unwrap_or(0),
-                stats.packets_lost.unwrap_or(0)
+                or_unknown(stats.packets_sent, ""),
+                or_unknown(stats.packets_lost, "")
             )),
             Line::from(format!(
                 "Duration: {:.1}s",
@@ -480,6 +648,17 @@ impl TerminalUI {
     }
 }
 
+/// Send what was typed into the chat panel
+async fn send_chat(chat: &ConferenceChat<PeerIdentityString>, panel: &ChatPanel, input: ChatInput) {
+    let result = match input {
+        ChatInput::Send(text) => chat.send_chat(text).await,
+        ChatInput::React(emoji) => chat.react(emoji, panel.latest_message()).await,
+    };
+    if let Err(e) = result {
+        tracing::warn!("Chat message not sent: {}", e);
+    }
+}
+
 impl Drop for TerminalUI {
     fn drop(&mut self) {
         // Restore terminal state
@@ -46,6 +46,50 @@ mod tests {
         assert!(matches!(display, DisplayMode::None));
     }
 
+    #[test]
+    fn test_chat_panel_input() {
+        use crossterm::event::KeyCode;
+        use saorsa_webrtc_core::conference::{ConferenceEvent, MessageId};
+        use saorsa_webrtc_core::prelude::PeerIdentityString;
+
+        let mut chat = ChatPanel::default();
+        assert_eq!(
+            chat.handle_key(KeyCode::Char('2')),
+            Some(ChatInput::React("😂"))
+        );
+
+        assert_eq!(chat.handle_key(KeyCode::Char('c')), None);
+        assert!(chat.is_composing());
+        for c in "hi!".chars() {
+            chat.handle_key(KeyCode::Char(c));
+        }
+        chat.handle_key(KeyCode::Backspace);
+        assert_eq!(
+            chat.handle_key(KeyCode::Enter),
+            Some(ChatInput::Send("hi".to_string()))
+        );
+        assert!(!chat.is_composing());
+
+        let id = MessageId::new_v4();
+        chat.push(&ConferenceEvent::ChatMessage {
+            id,
+            from: PeerIdentityString::new("alice"),
+            text: "Hello".to_string(),
+            sent_at: chrono::Utc::now(),
+        });
+        chat.push(&ConferenceEvent::Reaction {
+            id: MessageId::new_v4(),
+            from: PeerIdentityString::new("bob"),
+            emoji: "👍".to_string(),
+            target: Some(id),
+        });
+        assert_eq!(chat.latest_message(), Some(id));
+        assert_eq!(
+            chat.lines().collect::<Vec<_>>(),
+            vec!["alice: Hello", "bob 👍"]
+        );
+    }
+
     // Integration test that terminal UI can be created and dropped
     // Note: This test won't run in CI without a TTY, but validates the structure
     #[test]
@@ -33,6 +33,8 @@
 
 pub mod openh264;
 pub mod opus;
+pub mod pool;
+pub mod registry;
 
 use bytes::Bytes;
 
@@ -68,16 +70,15 @@ pub const MAX_WIDTH: u32 = 8192;
 pub const MAX_HEIGHT: u32 = 8192;
 pub const MAX_RGB_SIZE: usize = 100 * 1024 * 1024; // 100MB
 
-/// Video codec selection
-#[derive(Debug, Clone, Copy)]
-pub enum VideoCodec {
-    H264,
-}
-
-/// Audio codec selection
-#[derive(Debug, Clone, Copy)]
-pub enum AudioCodec {
-    Opus,
+/// MIME types of the built-in codecs
+///
+/// Codecs are selected by MIME type through a [`CodecRegistry`]; any type
+/// registered there can be used, not only these.
+pub mod mime {
+    /// H.264 video
+    pub const H264: &str = "video/H264";
+    /// Opus audio
+    pub const OPUS: &str = "audio/opus";
 }
 
 /// Video frame
@@ -89,16 +90,107 @@ pub struct VideoFrame {
     pub timestamp: u64,
 }
 
+/// Temporal scalability: how many frame-rate layers an encoder produces
+///
+/// Frames of temporal layer 0 only reference earlier layer 0 frames, and
+/// each higher layer only references the layers below it. A receiver can
+/// therefore drop everything above a layer, halving the frame rate per
+/// layer dropped, without waiting for a keyframe.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TemporalLayers {
+    /// A single layer at the full frame rate
+    #[default]
+    L1,
+    /// Two layers: base at half the frame rate
+    L2,
+    /// Three layers: base at a quarter, two layers at half the frame rate
+    L3,
+}
+
+impl TemporalLayers {
+    /// Number of layers
+    pub const fn count(self) -> u8 {
+        match self {
+            Self::L1 => 1,
+            Self::L2 => 2,
+            Self::L3 => 3,
+        }
+    }
+
+    /// Temporal layer of the frame `index` frames after the last keyframe
+    ///
+    /// Keyframes themselves are index 0, always in the base layer.
+    pub const fn layer_of(self, index: u64) -> u8 {
+        match self {
+            Self::L1 => 0,
+            Self::L2 => (index % 2) as u8,
+            // 0, 2, 1, 2: layer 1 sits halfway between base frames
+            Self::L3 => match index % 4 {
+                0 => 0,
+                2 => 1,
+                _ => 2,
+            },
+        }
+    }
+
+    /// Divisor of the full frame rate for a receiver of layers up to `layer`
+    pub const fn frame_rate_divisor(self, layer: u8) -> u32 {
+        let dropped = self.count().saturating_sub(1).saturating_sub(layer);
+        1 << dropped
+    }
+}
+
 /// Video encoder trait
 pub trait VideoEncoder: Send + Sync {
     fn encode(&mut self, frame: &VideoFrame) -> Result<Bytes>;
     fn request_keyframe(&mut self);
+
+    /// Produce this many temporal layers from the next keyframe on;
+    /// encoders without temporal scalability support only one
+    fn set_temporal_layers(&mut self, layers: TemporalLayers) -> Result<()> {
+        match layers {
+            TemporalLayers::L1 => Ok(()),
+            _ => Err(CodecError::NotImplemented("temporal layers")),
+        }
+    }
+
+    /// Temporal layer of the frame last encoded
+    fn temporal_layer(&self) -> u8 {
+        0
+    }
+
+    /// Return to the freshly created state so the encoder can be reused for
+    /// a new stream; encoders that cannot are never pooled
+    fn reset(&mut self) -> Result<()> {
+        Err(CodecError::NotImplemented("encoder reset"))
+    }
 }
 
 /// Video decoder trait
 pub trait VideoDecoder: Send + Sync {
     fn decode(&mut self, data: &[u8]) -> Result<VideoFrame>;
+
+    /// Return to the freshly created state so the decoder can be reused for
+    /// a new stream; decoders that cannot are never pooled
+    fn reset(&mut self) -> Result<()> {
+        Err(CodecError::NotImplemented("decoder reset"))
+    }
+}
+
+/// Audio encoder trait
+pub trait AudioEncoder: Send + Sync {
+    fn encode(&mut self, frame: &AudioFrame) -> Result<Bytes>;
+}
+
+/// Audio decoder trait
+pub trait AudioDecoder: Send + Sync {
+    fn decode(&mut self, data: &[u8]) -> Result<AudioFrame>;
 }
 
 pub use openh264::{OpenH264Decoder, OpenH264Encoder};
 pub use opus::{AudioFrame, Channels, OpusDecoder, OpusEncoder, OpusEncoderConfig, SampleRate};
+pub use pool::{CodecPool, PoolConfig, PoolStats, PooledVideoDecoder, PooledVideoEncoder};
+pub use registry::{
+    AudioDecoderFactory, AudioEncoderFactory, CodecRegistry, VideoDecoderFactory,
+    VideoEncoderFactory, BUILTIN_SCORE,
+};
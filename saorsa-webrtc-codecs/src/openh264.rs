@@ -10,7 +10,7 @@
 //! For production use, replace with actual openh264 integration using the
 //! openh264-sys bindings or similar library.
 
-use crate::{CodecError, Result, VideoDecoder, VideoEncoder, VideoFrame};
+use crate::{CodecError, Result, TemporalLayers, VideoDecoder, VideoEncoder, VideoFrame};
 use crate::{MAX_HEIGHT, MAX_RGB_SIZE, MAX_WIDTH};
 use bytes::Bytes;
 
@@ -21,6 +21,9 @@ pub struct OpenH264Encoder {
     width: u32,
     height: u32,
     pending_keyframe: bool,
+    temporal_layers: TemporalLayers,
+    /// Frames since the last keyframe; `None` until the first frame
+    frame_index: Option<u64>,
 }
 
 impl OpenH264Encoder {
@@ -52,8 +55,16 @@ impl OpenH264Encoder {
             width,
             height,
             pending_keyframe: false,
+            temporal_layers: TemporalLayers::L1,
+            frame_index: None,
         })
     }
+
+    /// Produce temporal layers, see [`TemporalLayers`]
+    pub fn with_temporal_layers(mut self, layers: TemporalLayers) -> Self {
+        self.temporal_layers = layers;
+        self
+    }
 }
 
 impl VideoEncoder for OpenH264Encoder {
@@ -89,6 +100,10 @@ impl VideoEncoder for OpenH264Encoder {
             i += count;
         }
 
+        self.frame_index = match self.frame_index {
+            Some(index) if !self.pending_keyframe => Some(index + 1),
+            _ => Some(0),
+        };
         self.pending_keyframe = false;
         Ok(Bytes::from(compressed))
     }
@@ -96,6 +111,27 @@ impl VideoEncoder for OpenH264Encoder {
     fn request_keyframe(&mut self) {
         self.pending_keyframe = true;
     }
+
+    fn set_temporal_layers(&mut self, layers: TemporalLayers) -> Result<()> {
+        if layers != self.temporal_layers {
+            // The new reference structure starts from a keyframe
+            self.temporal_layers = layers;
+            self.pending_keyframe = true;
+        }
+        Ok(())
+    }
+
+    fn temporal_layer(&self) -> u8 {
+        self.frame_index
+            .map_or(0, |index| self.temporal_layers.layer_of(index))
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        self.pending_keyframe = false;
+        self.temporal_layers = TemporalLayers::L1;
+        self.frame_index = None;
+        Ok(())
+    }
 }
 
 /// OpenH264 video decoder (stub implementation for now)
@@ -178,6 +214,10 @@ impl VideoDecoder for OpenH264Decoder {
             timestamp,
         })
     }
+
+    fn reset(&mut self) -> Result<()> {
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -366,6 +406,43 @@ mod tests {
         assert!(!encoder.pending_keyframe);
     }
 
+    #[test]
+    fn test_temporal_layer_pattern() {
+        let mut encoder = OpenH264Encoder::with_dimensions(16, 16)
+            .unwrap()
+            .with_temporal_layers(TemporalLayers::L3);
+        let frame = VideoFrame {
+            data: vec![1; 16 * 16 * 3],
+            width: 16,
+            height: 16,
+            timestamp: 0,
+        };
+        let mut layers = Vec::new();
+        for _ in 0..6 {
+            encoder.encode(&frame).unwrap();
+            layers.push(encoder.temporal_layer());
+        }
+        assert_eq!(layers, vec![0, 2, 1, 2, 0, 2]);
+
+        // A keyframe restarts the pattern in the base layer
+        encoder.request_keyframe();
+        encoder.encode(&frame).unwrap();
+        assert_eq!(encoder.temporal_layer(), 0);
+        encoder.encode(&frame).unwrap();
+        assert_eq!(encoder.temporal_layer(), 2);
+
+        encoder.set_temporal_layers(TemporalLayers::L2).unwrap();
+        encoder.encode(&frame).unwrap();
+        assert_eq!(encoder.temporal_layer(), 0);
+        encoder.encode(&frame).unwrap();
+        assert_eq!(encoder.temporal_layer(), 1);
+
+        assert_eq!(TemporalLayers::L3.frame_rate_divisor(0), 4);
+        assert_eq!(TemporalLayers::L3.frame_rate_divisor(1), 2);
+        assert_eq!(TemporalLayers::L3.frame_rate_divisor(2), 1);
+        assert_eq!(TemporalLayers::L1.frame_rate_divisor(0), 1);
+    }
+
     #[test]
     fn test_encode_varied_content() {
         let mut encoder = OpenH264Encoder::new().unwrap();
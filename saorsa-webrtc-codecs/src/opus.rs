@@ -10,7 +10,7 @@
 //! For production use, replace with actual libopus integration using the
 //! opus crate or similar library.
 
-use crate::{CodecError, Result};
+use crate::{AudioDecoder, AudioEncoder, CodecError, Result};
 use bytes::Bytes;
 
 /// Opus audio sample rates (Hz)
@@ -77,6 +77,8 @@ impl Default for OpusEncoderConfig {
 /// Opus audio encoder (stub implementation)
 pub struct OpusEncoder {
     config: OpusEncoderConfig,
+    /// Expected packet loss the in-band FEC is tuned for, in percent
+    packet_loss_percent: u8,
 }
 
 impl OpusEncoder {
@@ -88,7 +90,30 @@ impl OpusEncoder {
             ));
         }
 
-        Ok(Self { config })
+        Ok(Self {
+            config,
+            packet_loss_percent: 0,
+        })
+    }
+
+    /// Tune in-band forward error correction for the expected packet loss
+    ///
+    /// Like `OPUS_SET_PACKET_LOSS_PERC` with `OPUS_SET_INBAND_FEC`: above
+    /// zero, each packet carries a low-bitrate copy of the previous frame,
+    /// more of the bitrate going to it the higher the loss. Zero disables
+    /// FEC. Values above 100 are clamped.
+    pub fn set_packet_loss_percent(&mut self, percent: u8) {
+        self.packet_loss_percent = percent.min(100);
+    }
+
+    /// Expected packet loss the in-band FEC is tuned for, in percent
+    pub fn packet_loss_percent(&self) -> u8 {
+        self.packet_loss_percent
+    }
+
+    /// Whether in-band FEC is enabled
+    pub fn inband_fec(&self) -> bool {
+        self.packet_loss_percent > 0
     }
 
     /// Encode PCM audio data to Opus
@@ -213,6 +238,18 @@ impl OpusDecoder {
     }
 }
 
+impl AudioEncoder for OpusEncoder {
+    fn encode(&mut self, frame: &AudioFrame) -> Result<Bytes> {
+        OpusEncoder::encode(self, frame)
+    }
+}
+
+impl AudioDecoder for OpusDecoder {
+    fn decode(&mut self, data: &[u8]) -> Result<AudioFrame> {
+        OpusDecoder::decode(self, data)
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
@@ -225,6 +262,17 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_encoder_packet_loss_tuning() {
+        let mut encoder = OpusEncoder::new(OpusEncoderConfig::default()).unwrap();
+        assert!(!encoder.inband_fec());
+        encoder.set_packet_loss_percent(15);
+        assert!(encoder.inband_fec());
+        assert_eq!(encoder.packet_loss_percent(), 15);
+        encoder.set_packet_loss_percent(200);
+        assert_eq!(encoder.packet_loss_percent(), 100);
+    }
+
     #[test]
     fn test_encoder_creation_custom() {
         let config = OpusEncoderConfig {
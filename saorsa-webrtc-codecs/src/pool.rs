@@ -0,0 +1,448 @@
+//! Pool of warm encoder and decoder instances
+//!
+//! Creating a codec can cost far more than using it, which adds up when
+//! calls churn quickly or a conference opens a track per participant. A
+//! [`CodecPool`] hands out instances from a [`CodecRegistry`] and takes them
+//! back when they are dropped: each returned instance is reset to its
+//! freshly created state and kept idle for the next track of the same codec
+//! (and, for encoders, the same dimensions).
+//!
+//! Instances whose [`VideoEncoder::reset`] or [`VideoDecoder::reset`] fails
+//! are dropped rather than pooled, so implementations that cannot reset are
+//! simply created afresh each time.
+
+use crate::{CodecRegistry, Result, TemporalLayers, VideoDecoder, VideoEncoder, VideoFrame};
+use bytes::Bytes;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, Weak};
+
+/// Pool sizing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolConfig {
+    /// Idle instances kept per codec and dimensions; 0 disables pooling
+    pub max_idle: usize,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self { max_idle: 4 }
+    }
+}
+
+/// Pool usage counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// Instances created through the registry
+    pub created: u64,
+    /// Checkouts served by an idle instance
+    pub reused: u64,
+    /// Instances currently idle
+    pub idle: usize,
+}
+
+type EncoderKey = (String, u32, u32);
+
+#[derive(Default)]
+struct Idle {
+    encoders: HashMap<EncoderKey, Vec<Box<dyn VideoEncoder>>>,
+    decoders: HashMap<String, Vec<Box<dyn VideoDecoder>>>,
+    created: u64,
+    reused: u64,
+}
+
+struct Shared {
+    config: PoolConfig,
+    idle: Mutex<Idle>,
+}
+
+impl Shared {
+    fn idle(&self) -> MutexGuard<'_, Idle> {
+        self.idle.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Pool of warm codec instances
+pub struct CodecPool {
+    registry: Arc<CodecRegistry>,
+    shared: Arc<Shared>,
+}
+
+impl fmt::Debug for CodecPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CodecPool")
+            .field("config", &self.shared.config)
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+impl CodecPool {
+    /// Create an empty pool over a registry
+    pub fn new(registry: Arc<CodecRegistry>, config: PoolConfig) -> Self {
+        Self {
+            registry,
+            shared: Arc::new(Shared {
+                config,
+                idle: Mutex::new(Idle::default()),
+            }),
+        }
+    }
+
+    /// Registry the pool creates instances from
+    pub fn registry(&self) -> &Arc<CodecRegistry> {
+        &self.registry
+    }
+
+    /// Pool sizing
+    pub fn config(&self) -> &PoolConfig {
+        &self.shared.config
+    }
+
+    /// Take a video encoder for a MIME type and dimensions
+    ///
+    /// # Errors
+    ///
+    /// Returns error if no instance is idle and the registry cannot create one
+    pub fn video_encoder(
+        &self,
+        mime_type: &str,
+        width: u32,
+        height: u32,
+    ) -> Result<PooledVideoEncoder> {
+        let key = (mime_type.to_ascii_lowercase(), width, height);
+        let idle = {
+            let mut idle = self.shared.idle();
+            let encoder = idle.encoders.get_mut(&key).and_then(Vec::pop);
+            if encoder.is_some() {
+                idle.reused += 1;
+            }
+            encoder
+        };
+        let encoder = match idle {
+            Some(encoder) => encoder,
+            None => self.create_encoder(&key)?,
+        };
+        Ok(PooledVideoEncoder {
+            encoder: Some(encoder),
+            key,
+            pool: Arc::downgrade(&self.shared),
+        })
+    }
+
+    /// Take a video decoder for a MIME type
+    ///
+    /// # Errors
+    ///
+    /// Returns error if no instance is idle and the registry cannot create one
+    pub fn video_decoder(&self, mime_type: &str) -> Result<PooledVideoDecoder> {
+        let key = mime_type.to_ascii_lowercase();
+        let idle = {
+            let mut idle = self.shared.idle();
+            let decoder = idle.decoders.get_mut(&key).and_then(Vec::pop);
+            if decoder.is_some() {
+                idle.reused += 1;
+            }
+            decoder
+        };
+        let decoder = match idle {
+            Some(decoder) => decoder,
+            None => self.create_decoder(&key)?,
+        };
+        Ok(PooledVideoDecoder {
+            decoder: Some(decoder),
+            key,
+            pool: Arc::downgrade(&self.shared),
+        })
+    }
+
+    /// Create encoders ahead of time until `count` are idle, capped by
+    /// [`PoolConfig::max_idle`]
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the registry cannot create an encoder
+    pub fn warm_video_encoders(
+        &self,
+        mime_type: &str,
+        width: u32,
+        height: u32,
+        count: usize,
+    ) -> Result<()> {
+        let key = (mime_type.to_ascii_lowercase(), width, height);
+        let count = count.min(self.shared.config.max_idle);
+        while self.shared.idle().encoders.get(&key).map_or(0, Vec::len) < count {
+            let encoder = self.create_encoder(&key)?;
+            self.shared
+                .idle()
+                .encoders
+                .entry(key.clone())
+                .or_default()
+                .push(encoder);
+        }
+        Ok(())
+    }
+
+    /// Create decoders ahead of time until `count` are idle, capped by
+    /// [`PoolConfig::max_idle`]
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the registry cannot create a decoder
+    pub fn warm_video_decoders(&self, mime_type: &str, count: usize) -> Result<()> {
+        let key = mime_type.to_ascii_lowercase();
+        let count = count.min(self.shared.config.max_idle);
+        while self.shared.idle().decoders.get(&key).map_or(0, Vec::len) < count {
+            let decoder = self.create_decoder(&key)?;
+            self.shared
+                .idle()
+                .decoders
+                .entry(key.clone())
+                .or_default()
+                .push(decoder);
+        }
+        Ok(())
+    }
+
+    /// Drop every idle instance
+    pub fn clear(&self) {
+        let mut idle = self.shared.idle();
+        idle.encoders.clear();
+        idle.decoders.clear();
+    }
+
+    /// Usage counters
+    pub fn stats(&self) -> PoolStats {
+        let idle = self.shared.idle();
+        PoolStats {
+            created: idle.created,
+            reused: idle.reused,
+            idle: idle.encoders.values().map(Vec::len).sum::<usize>()
+                + idle.decoders.values().map(Vec::len).sum::<usize>(),
+        }
+    }
+
+    fn create_encoder(
+        &self,
+        (mime_type, width, height): &EncoderKey,
+    ) -> Result<Box<dyn VideoEncoder>> {
+        let encoder = self.registry.video_encoder(mime_type, *width, *height)?;
+        self.shared.idle().created += 1;
+        Ok(encoder)
+    }
+
+    fn create_decoder(&self, mime_type: &str) -> Result<Box<dyn VideoDecoder>> {
+        let decoder = self.registry.video_decoder(mime_type)?;
+        self.shared.idle().created += 1;
+        Ok(decoder)
+    }
+}
+
+/// Video encoder borrowed from a [`CodecPool`], returned to it on drop
+pub struct PooledVideoEncoder {
+    encoder: Option<Box<dyn VideoEncoder>>,
+    key: EncoderKey,
+    pool: Weak<Shared>,
+}
+
+impl VideoEncoder for PooledVideoEncoder {
+    fn encode(&mut self, frame: &VideoFrame) -> Result<Bytes> {
+        match &mut self.encoder {
+            Some(encoder) => encoder.encode(frame),
+            None => Err(crate::CodecError::InvalidData("encoder returned to pool")),
+        }
+    }
+
+    fn request_keyframe(&mut self) {
+        if let Some(encoder) = &mut self.encoder {
+            encoder.request_keyframe();
+        }
+    }
+
+    fn set_temporal_layers(&mut self, layers: TemporalLayers) -> Result<()> {
+        match &mut self.encoder {
+            Some(encoder) => encoder.set_temporal_layers(layers),
+            None => Err(crate::CodecError::InvalidData("encoder returned to pool")),
+        }
+    }
+
+    fn temporal_layer(&self) -> u8 {
+        self.encoder
+            .as_ref()
+            .map_or(0, |encoder| encoder.temporal_layer())
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        match &mut self.encoder {
+            Some(encoder) => encoder.reset(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for PooledVideoEncoder {
+    fn drop(&mut self) {
+        let (Some(mut encoder), Some(pool)) = (self.encoder.take(), self.pool.upgrade()) else {
+            return;
+        };
+        if encoder.reset().is_err() {
+            return;
+        }
+        let mut idle = pool.idle();
+        let max_idle = pool.config.max_idle;
+        let slot = idle.encoders.entry(self.key.clone()).or_default();
+        if slot.len() < max_idle {
+            slot.push(encoder);
+        }
+    }
+}
+
+/// Video decoder borrowed from a [`CodecPool`], returned to it on drop
+pub struct PooledVideoDecoder {
+    decoder: Option<Box<dyn VideoDecoder>>,
+    key: String,
+    pool: Weak<Shared>,
+}
+
+impl VideoDecoder for PooledVideoDecoder {
+    fn decode(&mut self, data: &[u8]) -> Result<VideoFrame> {
+        match &mut self.decoder {
+            Some(decoder) => decoder.decode(data),
+            None => Err(crate::CodecError::InvalidData("decoder returned to pool")),
+        }
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        match &mut self.decoder {
+            Some(decoder) => decoder.reset(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for PooledVideoDecoder {
+    fn drop(&mut self) {
+        let (Some(mut decoder), Some(pool)) = (self.decoder.take(), self.pool.upgrade()) else {
+            return;
+        };
+        if decoder.reset().is_err() {
+            return;
+        }
+        let mut idle = pool.idle();
+        let max_idle = pool.config.max_idle;
+        let slot = idle.decoders.entry(self.key.clone()).or_default();
+        if slot.len() < max_idle {
+            slot.push(decoder);
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::{mime, CodecError};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct NoReset;
+
+    impl VideoEncoder for NoReset {
+        fn encode(&mut self, frame: &VideoFrame) -> Result<Bytes> {
+            Ok(Bytes::from(frame.data.clone()))
+        }
+
+        fn request_keyframe(&mut self) {}
+    }
+
+    struct Counting(Arc<AtomicUsize>);
+
+    impl VideoEncoder for Counting {
+        fn encode(&mut self, frame: &VideoFrame) -> Result<Bytes> {
+            Ok(Bytes::from(frame.data.clone()))
+        }
+
+        fn request_keyframe(&mut self) {}
+
+        fn reset(&mut self) -> Result<()> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    fn test_pool(max_idle: usize) -> CodecPool {
+        let mut registry = CodecRegistry::with_defaults();
+        registry.register_video_encoder("video/x-no-reset", 1, |_, _| Ok(Box::new(NoReset)));
+        CodecPool::new(Arc::new(registry), PoolConfig { max_idle })
+    }
+
+    #[test]
+    fn test_instances_are_reused_per_codec_and_size() {
+        let pool = test_pool(2);
+        pool.warm_video_encoders(mime::H264, 640, 480, 5).unwrap();
+        assert_eq!(pool.stats().idle, 2);
+        assert_eq!(pool.stats().created, 2);
+
+        let first = pool.video_encoder(mime::H264, 640, 480).unwrap();
+        let second = pool.video_encoder("video/h264", 640, 480).unwrap();
+        let third = pool.video_encoder(mime::H264, 640, 480).unwrap();
+        // A different size needs its own encoder
+        let small = pool.video_encoder(mime::H264, 320, 240).unwrap();
+        assert_eq!(
+            pool.stats(),
+            PoolStats {
+                created: 4,
+                reused: 2,
+                idle: 0
+            }
+        );
+
+        drop((first, second, third, small));
+        // Only max_idle per key are kept
+        assert_eq!(pool.stats().idle, 3);
+
+        let mut decoder = pool.video_decoder(mime::H264).unwrap();
+        assert!(matches!(
+            decoder.decode(&[]),
+            Err(CodecError::InvalidData(_))
+        ));
+        drop(decoder);
+        assert!(pool.video_decoder(mime::H264).is_ok());
+        assert_eq!(pool.stats().reused, 3);
+
+        pool.clear();
+        assert_eq!(pool.stats().idle, 0);
+    }
+
+    #[test]
+    fn test_returned_encoder_is_reset() {
+        let resets = Arc::new(AtomicUsize::new(0));
+        let mut registry = CodecRegistry::new();
+        let counter = Arc::clone(&resets);
+        registry.register_video_encoder("video/x-counting", 1, move |_, _| {
+            Ok(Box::new(Counting(Arc::clone(&counter))))
+        });
+        let counting = CodecPool::new(Arc::new(registry), PoolConfig::default());
+        drop(counting.video_encoder("video/x-counting", 2, 1).unwrap());
+        assert_eq!(resets.load(Ordering::SeqCst), 1);
+        drop(counting.video_encoder("video/x-counting", 2, 1).unwrap());
+        assert_eq!(resets.load(Ordering::SeqCst), 2);
+        assert_eq!(counting.stats().created, 1);
+
+        let pool = test_pool(1);
+        drop(pool.video_encoder(mime::H264, 2, 1).unwrap());
+
+        // Encoders that cannot reset are not pooled
+        drop(pool.video_encoder("video/x-no-reset", 2, 1).unwrap());
+        assert_eq!(pool.stats().idle, 1);
+
+        // Disabled pooling keeps nothing
+        let disabled = test_pool(0);
+        drop(disabled.video_encoder(mime::H264, 2, 1).unwrap());
+        assert_eq!(disabled.stats().idle, 0);
+
+        // Instances outliving the pool are simply dropped
+        let encoder = pool.video_encoder(mime::H264, 2, 1).unwrap();
+        drop(pool);
+        drop(encoder);
+    }
+}
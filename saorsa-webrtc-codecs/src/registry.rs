@@ -0,0 +1,418 @@
+//! Registry of codec implementations
+//!
+//! Codecs are identified by MIME type (`video/H264`, `audio/opus`), so a
+//! crate can add a codec this crate knows nothing about, proprietary or
+//! experimental, by registering factories for its MIME type. Several
+//! implementations may register for the same MIME type, each with a
+//! capability score: the highest score is tried first and the others are
+//! fallbacks if it cannot be created, e.g. a hardware encoder falling back to
+//! software. On equal scores the most recent registration wins.
+//!
+//! MIME types are matched case-insensitively and listed in lower case. The
+//! built-in implementations register with [`BUILTIN_SCORE`].
+
+use crate::{
+    mime, AudioDecoder, AudioEncoder, Channels, CodecError, OpenH264Decoder, OpenH264Encoder,
+    OpusDecoder, OpusEncoder, OpusEncoderConfig, Result, SampleRate, VideoDecoder, VideoEncoder,
+};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Capability score of the built-in implementations
+pub const BUILTIN_SCORE: u32 = 100;
+
+/// Creates a video encoder for the given width and height
+pub type VideoEncoderFactory = Arc<dyn Fn(u32, u32) -> Result<Box<dyn VideoEncoder>> + Send + Sync>;
+
+/// Creates a video decoder
+pub type VideoDecoderFactory = Arc<dyn Fn() -> Result<Box<dyn VideoDecoder>> + Send + Sync>;
+
+/// Creates an audio encoder for the given sample rate and channels
+pub type AudioEncoderFactory =
+    Arc<dyn Fn(SampleRate, Channels) -> Result<Box<dyn AudioEncoder>> + Send + Sync>;
+
+/// Creates an audio decoder for the given sample rate and channels
+pub type AudioDecoderFactory =
+    Arc<dyn Fn(SampleRate, Channels) -> Result<Box<dyn AudioDecoder>> + Send + Sync>;
+
+/// Implementations registered for one kind of codec, by MIME type
+struct Implementations<F> {
+    /// Best score first
+    by_mime: HashMap<String, Vec<(u32, F)>>,
+}
+
+impl<F> Default for Implementations<F> {
+    fn default() -> Self {
+        Self {
+            by_mime: HashMap::new(),
+        }
+    }
+}
+
+impl<F: Clone> Clone for Implementations<F> {
+    fn clone(&self) -> Self {
+        Self {
+            by_mime: self.by_mime.clone(),
+        }
+    }
+}
+
+impl<F> Implementations<F> {
+    fn register(&mut self, mime_type: &str, score: u32, factory: F) {
+        let entries = self
+            .by_mime
+            .entry(mime_type.to_ascii_lowercase())
+            .or_default();
+        let at = entries.partition_point(|(existing, _)| *existing > score);
+        entries.insert(at, (score, factory));
+    }
+
+    fn get(&self, mime_type: &str) -> &[(u32, F)] {
+        self.by_mime
+            .get(&mime_type.to_ascii_lowercase())
+            .map_or(&[], Vec::as_slice)
+    }
+
+    fn contains(&self, mime_type: &str) -> bool {
+        !self.get(mime_type).is_empty()
+    }
+
+    fn best_score(&self, mime_type: &str) -> Option<u32> {
+        self.get(mime_type).first().map(|(score, _)| *score)
+    }
+
+    /// Create the best implementation that can be created
+    fn create<T>(
+        &self,
+        mime_type: &str,
+        missing: &'static str,
+        create: impl Fn(&F) -> Result<T>,
+    ) -> Result<T> {
+        let mut last_error = CodecError::NotImplemented(missing);
+        for (_, factory) in self.get(mime_type) {
+            match create(factory) {
+                Ok(codec) => return Ok(codec),
+                Err(e) => last_error = e,
+            }
+        }
+        Err(last_error)
+    }
+}
+
+/// Codec implementations available to a service
+#[derive(Clone, Default)]
+pub struct CodecRegistry {
+    video_encoders: Implementations<VideoEncoderFactory>,
+    video_decoders: Implementations<VideoDecoderFactory>,
+    audio_encoders: Implementations<AudioEncoderFactory>,
+    audio_decoders: Implementations<AudioDecoderFactory>,
+}
+
+impl fmt::Debug for CodecRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CodecRegistry")
+            .field("video", &self.video_mime_types())
+            .field("audio", &self.audio_mime_types())
+            .finish()
+    }
+}
+
+impl CodecRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a registry holding the built-in codecs
+    pub fn with_defaults() -> Self {
+        let mut registry = Self::new();
+        registry.register_video_encoder(mime::H264, BUILTIN_SCORE, |width, height| {
+            Ok(Box::new(OpenH264Encoder::with_dimensions(width, height)?))
+        });
+        registry.register_video_decoder(mime::H264, BUILTIN_SCORE, || {
+            Ok(Box::new(OpenH264Decoder::new()?))
+        });
+        registry.register_audio_encoder(mime::OPUS, BUILTIN_SCORE, |sample_rate, channels| {
+            Ok(Box::new(OpusEncoder::new(OpusEncoderConfig {
+                sample_rate,
+                channels,
+                ..OpusEncoderConfig::default()
+            })?))
+        });
+        registry.register_audio_decoder(mime::OPUS, BUILTIN_SCORE, |sample_rate, channels| {
+            Ok(Box::new(OpusDecoder::new(sample_rate, channels)?))
+        });
+        registry
+    }
+
+    /// Register a video encoder for a MIME type
+    pub fn register_video_encoder<F>(&mut self, mime_type: &str, score: u32, factory: F)
+    where
+        F: Fn(u32, u32) -> Result<Box<dyn VideoEncoder>> + Send + Sync + 'static,
+    {
+        self.video_encoders
+            .register(mime_type, score, Arc::new(factory));
+    }
+
+    /// Register a video decoder for a MIME type
+    pub fn register_video_decoder<F>(&mut self, mime_type: &str, score: u32, factory: F)
+    where
+        F: Fn() -> Result<Box<dyn VideoDecoder>> + Send + Sync + 'static,
+    {
+        self.video_decoders
+            .register(mime_type, score, Arc::new(factory));
+    }
+
+    /// Register an audio encoder for a MIME type
+    pub fn register_audio_encoder<F>(&mut self, mime_type: &str, score: u32, factory: F)
+    where
+        F: Fn(SampleRate, Channels) -> Result<Box<dyn AudioEncoder>> + Send + Sync + 'static,
+    {
+        self.audio_encoders
+            .register(mime_type, score, Arc::new(factory));
+    }
+
+    /// Register an audio decoder for a MIME type
+    pub fn register_audio_decoder<F>(&mut self, mime_type: &str, score: u32, factory: F)
+    where
+        F: Fn(SampleRate, Channels) -> Result<Box<dyn AudioDecoder>> + Send + Sync + 'static,
+    {
+        self.audio_decoders
+            .register(mime_type, score, Arc::new(factory));
+    }
+
+    /// Create the best video encoder for a MIME type
+    ///
+    /// # Errors
+    ///
+    /// Returns error if no encoder is registered for the MIME type or none
+    /// of the registered encoders can be created
+    pub fn video_encoder(
+        &self,
+        mime_type: &str,
+        width: u32,
+        height: u32,
+    ) -> Result<Box<dyn VideoEncoder>> {
+        self.video_encoders
+            .create(mime_type, "no encoder registered for codec", |factory| {
+                factory(width, height)
+            })
+    }
+
+    /// Create the best video decoder for a MIME type
+    ///
+    /// # Errors
+    ///
+    /// Returns error if no decoder is registered for the MIME type or none
+    /// of the registered decoders can be created
+    pub fn video_decoder(&self, mime_type: &str) -> Result<Box<dyn VideoDecoder>> {
+        self.video_decoders
+            .create(mime_type, "no decoder registered for codec", |factory| {
+                factory()
+            })
+    }
+
+    /// Create the best audio encoder for a MIME type
+    ///
+    /// # Errors
+    ///
+    /// Returns error if no encoder is registered for the MIME type or none
+    /// of the registered encoders can be created
+    pub fn audio_encoder(
+        &self,
+        mime_type: &str,
+        sample_rate: SampleRate,
+        channels: Channels,
+    ) -> Result<Box<dyn AudioEncoder>> {
+        self.audio_encoders
+            .create(mime_type, "no encoder registered for codec", |factory| {
+                factory(sample_rate, channels)
+            })
+    }
+
+    /// Create the best audio decoder for a MIME type
+    ///
+    /// # Errors
+    ///
+    /// Returns error if no decoder is registered for the MIME type or none
+    /// of the registered decoders can be created
+    pub fn audio_decoder(
+        &self,
+        mime_type: &str,
+        sample_rate: SampleRate,
+        channels: Channels,
+    ) -> Result<Box<dyn AudioDecoder>> {
+        self.audio_decoders
+            .create(mime_type, "no decoder registered for codec", |factory| {
+                factory(sample_rate, channels)
+            })
+    }
+
+    /// Check if both an encoder and a decoder are registered for the MIME type
+    pub fn supports(&self, mime_type: &str) -> bool {
+        (self.video_encoders.contains(mime_type) && self.video_decoders.contains(mime_type))
+            || (self.audio_encoders.contains(mime_type) && self.audio_decoders.contains(mime_type))
+    }
+
+    /// Best encoder score registered for the MIME type, if any
+    pub fn score(&self, mime_type: &str) -> Option<u32> {
+        self.video_encoders
+            .best_score(mime_type)
+            .or_else(|| self.audio_encoders.best_score(mime_type))
+    }
+
+    /// Supported video MIME types, best encoder score first
+    pub fn video_mime_types(&self) -> Vec<String> {
+        Self::ranked(&self.video_encoders, &self.video_decoders)
+    }
+
+    /// Supported audio MIME types, best encoder score first
+    pub fn audio_mime_types(&self) -> Vec<String> {
+        Self::ranked(&self.audio_encoders, &self.audio_decoders)
+    }
+
+    fn ranked<E, D>(encoders: &Implementations<E>, decoders: &Implementations<D>) -> Vec<String> {
+        let mut supported: Vec<(u32, &String)> = encoders
+            .by_mime
+            .iter()
+            .filter(|(mime_type, entries)| !entries.is_empty() && decoders.contains(mime_type))
+            .filter_map(|(mime_type, entries)| {
+                entries.first().map(|(score, _)| (*score, mime_type))
+            })
+            .collect();
+        supported.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(b.1)));
+        supported
+            .into_iter()
+            .map(|(_, mime_type)| mime_type.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::VideoFrame;
+    use bytes::Bytes;
+
+    struct PassthroughEncoder;
+
+    impl VideoEncoder for PassthroughEncoder {
+        fn encode(&mut self, frame: &VideoFrame) -> Result<Bytes> {
+            Ok(Bytes::from(frame.data.clone()))
+        }
+
+        fn request_keyframe(&mut self) {}
+    }
+
+    struct PassthroughDecoder;
+
+    impl VideoDecoder for PassthroughDecoder {
+        fn decode(&mut self, data: &[u8]) -> Result<VideoFrame> {
+            Ok(VideoFrame {
+                data: data.to_vec(),
+                width: 0,
+                height: 0,
+                timestamp: 0,
+            })
+        }
+    }
+
+    fn frame() -> VideoFrame {
+        VideoFrame {
+            data: vec![1, 2, 3, 4, 5, 6],
+            width: 2,
+            height: 1,
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn test_defaults_and_replacement() {
+        let mut registry = CodecRegistry::with_defaults();
+        assert!(registry.supports(mime::H264));
+        assert!(registry.supports("VIDEO/h264"));
+        assert!(registry.supports(mime::OPUS));
+        assert!(registry.video_encoder(mime::H264, 0, 0).is_err());
+
+        registry.register_video_encoder(mime::H264, BUILTIN_SCORE, |_, _| {
+            Ok(Box::new(PassthroughEncoder))
+        });
+        let mut encoder = registry.video_encoder(mime::H264, 2, 1).unwrap();
+        assert_eq!(
+            encoder.encode(&frame()).unwrap().as_ref(),
+            &frame().data[..]
+        );
+
+        let empty = CodecRegistry::new();
+        assert!(!empty.supports(mime::H264));
+        assert!(matches!(
+            empty.video_decoder(mime::H264),
+            Err(CodecError::NotImplemented(_))
+        ));
+    }
+
+    #[test]
+    fn test_score_orders_implementations_and_falls_back() {
+        let mut registry = CodecRegistry::with_defaults();
+        registry.register_video_encoder("video/x-experimental", 50, |_, _| {
+            Ok(Box::new(PassthroughEncoder))
+        });
+        registry.register_video_decoder("video/x-experimental", 50, || {
+            Ok(Box::new(PassthroughDecoder))
+        });
+        assert_eq!(
+            registry.video_mime_types(),
+            vec![
+                mime::H264.to_ascii_lowercase(),
+                "video/x-experimental".to_string()
+            ]
+        );
+        assert_eq!(registry.audio_mime_types(), vec![mime::OPUS.to_string()]);
+
+        // A higher-scoring encoder that cannot be created falls back to the next
+        registry.register_video_encoder("video/x-experimental", 200, |_, _| {
+            Err(CodecError::InitFailed("no hardware".to_string()))
+        });
+        assert_eq!(registry.score("video/x-experimental"), Some(200));
+        assert_eq!(registry.video_mime_types()[0], "video/x-experimental");
+        let mut encoder = registry
+            .video_encoder("video/x-experimental", 2, 1)
+            .unwrap();
+        assert_eq!(
+            encoder.encode(&frame()).unwrap().as_ref(),
+            &frame().data[..]
+        );
+
+        // Only a lower-scoring encoder is registered for the decoder-less type
+        registry.register_video_encoder("video/x-encode-only", 1, |_, _| {
+            Ok(Box::new(PassthroughEncoder))
+        });
+        assert!(!registry.supports("video/x-encode-only"));
+        assert!(!registry
+            .video_mime_types()
+            .contains(&"video/x-encode-only".to_string()));
+    }
+
+    #[test]
+    fn test_builtin_opus_round_trip() {
+        use crate::AudioFrame;
+
+        let registry = CodecRegistry::with_defaults();
+        let mut encoder = registry
+            .audio_encoder(mime::OPUS, SampleRate::Hz48000, Channels::Mono)
+            .unwrap();
+        let mut decoder = registry
+            .audio_decoder("AUDIO/OPUS", SampleRate::Hz48000, Channels::Mono)
+            .unwrap();
+        let frame = AudioFrame {
+            data: vec![1, -2, 3],
+            sample_rate: SampleRate::Hz48000,
+            channels: Channels::Mono,
+            timestamp: 7,
+        };
+        let decoded = decoder.decode(&encoder.encode(&frame).unwrap()).unwrap();
+        assert_eq!(decoded.data, frame.data);
+    }
+}
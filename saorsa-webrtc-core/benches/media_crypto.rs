@@ -0,0 +1,88 @@
+//! Per-packet cost of media frame encryption across packet sizes
+//!
+//! `copy` is the baseline of moving the payload into a frame buffer without
+//! encryption; the gap to `seal` is the cryptographic overhead. `seal_batch`
+//! seals a burst of 16 packets, as for one video frame split for the MTU.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use saorsa_webrtc_core::media_crypto::{FrameOpener, FrameSealer, MediaKey, FRAME_OVERHEAD};
+
+/// Opus audio, small and large video packets, and a full MTU
+const PACKET_SIZES: [usize; 4] = [64, 160, 512, 1200];
+
+/// Packets per batch in `seal_batch`
+const BATCH: usize = 16;
+
+fn key() -> MediaKey {
+    MediaKey::from_secret(1, &[7u8; 32])
+}
+
+fn bench_framing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("media_crypto");
+    for size in PACKET_SIZES {
+        let payload = vec![0xA5u8; size];
+        group.throughput(Throughput::Bytes(size as u64));
+
+        let mut out = Vec::with_capacity(size + FRAME_OVERHEAD);
+        group.bench_with_input(BenchmarkId::new("copy", size), &payload, |b, payload| {
+            b.iter(|| {
+                out.clear();
+                out.extend_from_slice(&[0u8; FRAME_OVERHEAD]);
+                out.extend_from_slice(black_box(payload));
+                black_box(&out);
+            });
+        });
+
+        let mut sealer = FrameSealer::new(key());
+        group.bench_with_input(BenchmarkId::new("seal", size), &payload, |b, payload| {
+            b.iter(|| {
+                out.clear();
+                let _ = sealer.seal(black_box(payload), &mut out);
+                black_box(&out);
+            });
+        });
+
+        let mut frame = Vec::new();
+        let _ = FrameSealer::new(key()).seal(&payload, &mut frame);
+        group.bench_with_input(BenchmarkId::new("open", size), &frame, |b, frame| {
+            b.iter(|| {
+                // A fresh opener per packet keeps the replay check passing
+                let mut opener = FrameOpener::new(key());
+                out.clear();
+                let _ = opener.open(black_box(frame), &mut out);
+                black_box(&out);
+            });
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("media_crypto_batch");
+    for size in PACKET_SIZES {
+        let payloads = vec![vec![0xA5u8; size]; BATCH];
+        let refs: Vec<&[u8]> = payloads.iter().map(Vec::as_slice).collect();
+        group.throughput(Throughput::Bytes((size * BATCH) as u64));
+
+        let mut out = Vec::with_capacity((size + FRAME_OVERHEAD) * BATCH);
+        let mut sealer = FrameSealer::new(key());
+        group.bench_with_input(BenchmarkId::new("seal_each", size), &refs, |b, refs| {
+            b.iter(|| {
+                out.clear();
+                for payload in refs {
+                    let _ = sealer.seal(black_box(payload), &mut out);
+                }
+                black_box(&out);
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("seal_batch", size), &refs, |b, refs| {
+            b.iter(|| {
+                out.clear();
+                let _ = sealer.seal_batch(black_box(refs), &mut out);
+                black_box(&out);
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_framing);
+criterion_main!(benches);
@@ -0,0 +1,21 @@
+//! Length-prefixed RTP framing on QUIC media streams
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use saorsa_webrtc_core::quic_media_transport::framing::{frame_rtp, split_frames, unframe_rtp};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok((len, rest)) = unframe_rtp(data) {
+        assert!(rest.len() >= usize::from(len));
+    }
+
+    if let Ok(frames) = split_frames(data) {
+        // A successful split accounts for every byte and re-frames exactly
+        let mut reframed = Vec::with_capacity(data.len());
+        for frame in frames {
+            reframed.extend_from_slice(&frame_rtp(frame).unwrap());
+        }
+        assert_eq!(reframed, data);
+    }
+});
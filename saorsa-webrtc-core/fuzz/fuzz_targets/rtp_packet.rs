@@ -0,0 +1,18 @@
+//! RTP packets received from QUIC streams
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use saorsa_webrtc_core::pcap::rtp_wire_bytes;
+use saorsa_webrtc_core::quic_bridge::RtpPacket;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(packet) = RtpPacket::from_bytes(data) {
+        let bytes = packet.to_bytes().unwrap();
+        let again = RtpPacket::from_bytes(&bytes).unwrap();
+        assert_eq!(again.to_bytes().unwrap(), bytes);
+        let _ = rtp_wire_bytes(&packet);
+    }
+
+    let _ = RtpPacket::from_tagged_bytes(data);
+});
@@ -0,0 +1,16 @@
+//! Signaling frames in every wire format, compressed or not
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use saorsa_webrtc_core::wire_format::{decode_frame, WireFrame};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(WireFrame::Message(message, format)) = decode_frame(data) {
+        let frame = format.encode(&message).unwrap();
+        assert_eq!(
+            decode_frame(&frame),
+            Ok(WireFrame::Message(message, format))
+        );
+    }
+});
@@ -0,0 +1,243 @@
+//! Audio level metering
+//!
+//! Audio levels are computed from PCM the pipeline already has: captured
+//! samples before they are encoded and decoded samples before they are
+//! played back, so metering never needs an extra decode. Levels cover a
+//! sliding wall-clock window, so a silent or stalled stream decays to zero.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Default window audio levels are computed over (300ms)
+pub const DEFAULT_LEVEL_WINDOW: Duration = Duration::from_millis(300);
+
+/// Default interval between audio level events (100ms)
+pub const DEFAULT_LEVEL_REPORT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Level reported for digital silence, in dBFS
+pub const SILENCE_DBFS: f32 = -127.0;
+
+/// Full-scale magnitude of a 16-bit sample
+const FULL_SCALE: f32 = 32768.0;
+
+/// Direction of an audio stream relative to the local device
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AudioDirection {
+    /// Local microphone audio sent to the peer
+    Capture,
+    /// Remote audio played back locally
+    Playback,
+}
+
+/// Audio level over a metering window
+///
+/// Both values are linear and normalized to full scale, in `0.0..=1.0`.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct AudioLevel {
+    /// Root mean square level
+    pub rms: f32,
+    /// Peak absolute sample
+    pub peak: f32,
+}
+
+impl AudioLevel {
+    /// Level of one block of interleaved 16-bit PCM samples
+    #[must_use]
+    pub fn of_samples(samples: &[i16]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        let (sum_squares, peak) = sum_squares_and_peak(samples);
+        let rms = (sum_squares / samples.len() as f64).sqrt() / f64::from(FULL_SCALE);
+        Self {
+            rms: (rms as f32).min(1.0),
+            peak: (peak as f32 / FULL_SCALE).min(1.0),
+        }
+    }
+
+    /// RMS level in dBFS
+    #[must_use]
+    pub fn rms_dbfs(&self) -> f32 {
+        to_dbfs(self.rms)
+    }
+
+    /// Peak level in dBFS
+    #[must_use]
+    pub fn peak_dbfs(&self) -> f32 {
+        to_dbfs(self.peak)
+    }
+}
+
+fn sum_squares_and_peak(samples: &[i16]) -> (f64, i32) {
+    let mut sum_squares = 0.0f64;
+    let mut peak = 0i32;
+    for &sample in samples {
+        let sample = i32::from(sample);
+        sum_squares += f64::from(sample * sample);
+        peak = peak.max(sample.abs());
+    }
+    (sum_squares, peak)
+}
+
+fn to_dbfs(linear: f32) -> f32 {
+    if linear <= 0.0 {
+        SILENCE_DBFS
+    } else {
+        (20.0 * linear.log10()).max(SILENCE_DBFS)
+    }
+}
+
+/// Statistics of one block of samples
+#[derive(Debug, Clone, Copy)]
+struct Block {
+    at: Instant,
+    sum_squares: f64,
+    samples: u64,
+    peak: f32,
+}
+
+/// Sliding-window RMS and peak meter
+#[derive(Debug)]
+pub struct AudioLevelMeter {
+    window: Duration,
+    blocks: VecDeque<Block>,
+}
+
+impl Default for AudioLevelMeter {
+    fn default() -> Self {
+        Self::new(DEFAULT_LEVEL_WINDOW)
+    }
+}
+
+impl AudioLevelMeter {
+    /// Create a meter over the given window
+    #[must_use]
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            blocks: VecDeque::new(),
+        }
+    }
+
+    /// Get the metering window
+    #[must_use]
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Add a block of interleaved 16-bit PCM samples
+    pub fn process(&mut self, samples: &[i16]) {
+        if samples.is_empty() {
+            return;
+        }
+
+        let (sum_squares, peak) = sum_squares_and_peak(samples);
+        let now = Instant::now();
+        self.expire(now);
+        self.blocks.push_back(Block {
+            at: now,
+            sum_squares,
+            samples: samples.len() as u64,
+            peak: peak as f32 / FULL_SCALE,
+        });
+    }
+
+    /// Current level over the window
+    #[must_use]
+    pub fn level(&self) -> AudioLevel {
+        let now = Instant::now();
+        let mut sum_squares = 0.0f64;
+        let mut samples = 0u64;
+        let mut peak = 0.0f32;
+        for block in self.blocks.iter().filter(|b| self.in_window(b, now)) {
+            sum_squares += block.sum_squares;
+            samples += block.samples;
+            peak = peak.max(block.peak);
+        }
+
+        if samples == 0 {
+            return AudioLevel::default();
+        }
+        let rms = (sum_squares / samples as f64).sqrt() / f64::from(FULL_SCALE);
+        AudioLevel {
+            rms: (rms as f32).min(1.0),
+            peak: peak.min(1.0),
+        }
+    }
+
+    fn in_window(&self, block: &Block, now: Instant) -> bool {
+        now.duration_since(block.at) < self.window
+    }
+
+    fn expire(&mut self, now: Instant) {
+        while let Some(block) = self.blocks.front() {
+            if self.in_window(block, now) {
+                break;
+            }
+            self.blocks.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_scale_square_wave() {
+        let mut meter = AudioLevelMeter::default();
+        let samples: Vec<i16> = (0..960)
+            .map(|i| if i % 2 == 0 { i16::MAX } else { i16::MIN })
+            .collect();
+        meter.process(&samples);
+
+        let level = meter.level();
+        assert!(level.rms > 0.999);
+        assert!((level.peak - 1.0).abs() < f32::EPSILON);
+        assert!(level.peak_dbfs().abs() < 0.01);
+    }
+
+    #[test]
+    fn test_silence() {
+        let mut meter = AudioLevelMeter::default();
+        assert_eq!(meter.level(), AudioLevel::default());
+
+        meter.process(&[0; 480]);
+        let level = meter.level();
+        assert_eq!(level.rms, 0.0);
+        assert_eq!(level.rms_dbfs(), SILENCE_DBFS);
+    }
+
+    #[test]
+    fn test_half_scale_is_minus_six_db() {
+        let mut meter = AudioLevelMeter::default();
+        meter.process(&[16384, -16384, 16384, -16384]);
+        let level = meter.level();
+        assert!((level.rms_dbfs() - -6.02).abs() < 0.01);
+        assert_eq!(
+            AudioLevel::of_samples(&[16384, -16384, 16384, -16384]),
+            level
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_level_decays_after_window() {
+        let mut meter = AudioLevelMeter::new(Duration::from_millis(100));
+        meter.process(&[i16::MAX; 480]);
+
+        tokio::time::advance(Duration::from_millis(50)).await;
+        meter.process(&[1000; 480]);
+        assert!((meter.level().peak - 1.0).abs() < 0.001);
+
+        // The loud block leaves the window first
+        tokio::time::advance(Duration::from_millis(60)).await;
+        let level = meter.level();
+        assert!((level.peak - 1000.0 / FULL_SCALE).abs() < 0.001);
+
+        tokio::time::advance(Duration::from_millis(100)).await;
+        assert_eq!(meter.level(), AudioLevel::default());
+    }
+}
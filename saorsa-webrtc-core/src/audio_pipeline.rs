@@ -0,0 +1,422 @@
+//! Audio encode/decode pipeline
+//!
+//! Connects the local audio device to Opus using the format agreed with the
+//! peer ([`AudioParameters`]). Captured PCM is remixed to the agreed channel
+//! count and resampled to the agreed rate before encoding; decoded PCM is
+//! converted back to the device format for playback. Resamplers are only
+//! inserted when the device and codec rates differ, and their delay is
+//! reported so callers can account for it.
+//!
+//! The pipeline can also monitor the microphone: captured audio is fed
+//! back to the local output at low volume ("sidetone"), so headset users
+//! hear themselves and device tests can be checked by ear.
+
+use crate::media::MediaError;
+use crate::resample::Resampler;
+use crate::types::AudioParameters;
+use bytes::Bytes;
+use saorsa_webrtc_codecs::{AudioFrame, OpusDecoder, OpusEncoder};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Default monitor gain, about -20 dB
+pub const DEFAULT_MONITOR_GAIN: f32 = 0.1;
+
+/// Most monitored audio held for playback before the oldest is dropped
+const MAX_MONITOR_DELAY: Duration = Duration::from_millis(200);
+
+/// PCM format of a local capture or playback device
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeviceFormat {
+    /// Sample rate in Hz
+    pub sample_rate_hz: u32,
+    /// Channel count, 1 or 2
+    pub channels: u8,
+}
+
+impl DeviceFormat {
+    /// Create a device format
+    #[must_use]
+    pub fn new(sample_rate_hz: u32, channels: u8) -> Self {
+        Self {
+            sample_rate_hz,
+            channels,
+        }
+    }
+
+    fn validate(&self) -> Result<(), MediaError> {
+        if self.sample_rate_hz == 0 || !(1..=2).contains(&self.channels) {
+            return Err(MediaError::ConfigError(format!(
+                "unsupported device format: {} Hz, {} channels",
+                self.sample_rate_hz, self.channels
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Default for DeviceFormat {
+    fn default() -> Self {
+        Self::new(48_000, 1)
+    }
+}
+
+/// Opus encoder and decoder configured from negotiated parameters
+pub struct AudioPipeline {
+    params: AudioParameters,
+    device: DeviceFormat,
+    encoder: OpusEncoder,
+    decoder: OpusDecoder,
+    /// Device rate to codec rate, when they differ
+    capture_resampler: Option<Resampler>,
+    /// Codec rate to device rate, when they differ
+    playback_resampler: Option<Resampler>,
+    /// Captured audio waiting to be mixed into playback
+    monitor: Option<Monitor>,
+}
+
+impl std::fmt::Debug for AudioPipeline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AudioPipeline")
+            .field("params", &self.params)
+            .field("device", &self.device)
+            .field("monitor_gain", &self.monitor_gain())
+            .finish_non_exhaustive()
+    }
+}
+
+impl AudioPipeline {
+    /// Build a pipeline for the agreed parameters and a device format
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the device format is unsupported or the codec
+    /// rejects the parameters.
+    pub fn new(params: AudioParameters, device: DeviceFormat) -> Result<Self, MediaError> {
+        device.validate()?;
+        let config = params.encoder_config();
+        let codec_rate = config.sample_rate.as_hz();
+        let encoder = OpusEncoder::new(config.clone())
+            .map_err(|e| MediaError::ConfigError(format!("Opus encoder: {e}")))?;
+        let decoder = OpusDecoder::new(config.sample_rate, config.channels)
+            .map_err(|e| MediaError::ConfigError(format!("Opus decoder: {e}")))?;
+        let channels = config.channels.count();
+
+        let (capture_resampler, playback_resampler) = if device.sample_rate_hz == codec_rate {
+            (None, None)
+        } else {
+            let unsupported = || {
+                MediaError::ConfigError(format!(
+                    "cannot resample {} Hz to {codec_rate} Hz",
+                    device.sample_rate_hz
+                ))
+            };
+            (
+                Some(
+                    Resampler::new(device.sample_rate_hz, codec_rate, channels)
+                        .ok_or_else(unsupported)?,
+                ),
+                Some(
+                    Resampler::new(codec_rate, device.sample_rate_hz, device.channels.into())
+                        .ok_or_else(unsupported)?,
+                ),
+            )
+        };
+
+        Ok(Self {
+            params,
+            device,
+            encoder,
+            decoder,
+            capture_resampler,
+            playback_resampler,
+            monitor: None,
+        })
+    }
+
+    /// Negotiated parameters the pipeline was built for
+    #[must_use]
+    pub fn params(&self) -> AudioParameters {
+        self.params
+    }
+
+    /// Device format the pipeline converts to and from
+    #[must_use]
+    pub fn device(&self) -> DeviceFormat {
+        self.device
+    }
+
+    /// Whether device and codec rates differ so audio is resampled
+    #[must_use]
+    pub fn is_resampling(&self) -> bool {
+        self.capture_resampler.is_some()
+    }
+
+    /// Delay the capture path adds before encoding
+    ///
+    /// Zero unless a resampler was inserted for a rate mismatch.
+    #[must_use]
+    pub fn capture_latency(&self) -> Duration {
+        self.capture_resampler
+            .as_ref()
+            .map_or(Duration::ZERO, Resampler::latency)
+    }
+
+    /// Delay the playback path adds after decoding
+    #[must_use]
+    pub fn playback_latency(&self) -> Duration {
+        self.playback_resampler
+            .as_ref()
+            .map_or(Duration::ZERO, Resampler::latency)
+    }
+
+    /// Tune the encoder's in-band FEC for the expected packet loss, in
+    /// percent; 0 turns it off
+    pub fn set_expected_loss_percent(&mut self, percent: u8) {
+        self.encoder.set_packet_loss_percent(percent);
+    }
+
+    /// Expected packet loss the encoder's in-band FEC is tuned for
+    #[must_use]
+    pub fn expected_loss_percent(&self) -> u8 {
+        self.encoder.packet_loss_percent()
+    }
+
+    /// Enable microphone monitoring at `gain` (0.0 to 1.0), or disable it
+    /// with `None`
+    ///
+    /// Monitored audio is what [`encode`](Self::encode) receives, mixed into
+    /// [`decode`](Self::decode) output or drained with
+    /// [`mix_monitor`](Self::mix_monitor) when nothing is being received.
+    pub fn set_monitor(&mut self, gain: Option<f32>) {
+        self.monitor = gain.map(|gain| {
+            let capacity = (MAX_MONITOR_DELAY.as_millis() as usize)
+                * self.device.sample_rate_hz as usize
+                / 1000
+                * usize::from(self.device.channels);
+            Monitor {
+                gain: gain.clamp(0.0, 1.0),
+                pending: VecDeque::with_capacity(capacity),
+                capacity,
+            }
+        });
+    }
+
+    /// Current monitor gain, if monitoring is enabled
+    #[must_use]
+    pub fn monitor_gain(&self) -> Option<f32> {
+        self.monitor.as_ref().map(|m| m.gain)
+    }
+
+    /// Mix pending monitored audio into a playback buffer in the device
+    /// format
+    pub fn mix_monitor(&mut self, playback: &mut [i16]) {
+        let Some(monitor) = &mut self.monitor else {
+            return;
+        };
+        let ready = playback.len().min(monitor.pending.len());
+        for (out, sample) in playback.iter_mut().zip(monitor.pending.drain(..ready)) {
+            *out = out.saturating_add(sample);
+        }
+    }
+
+    /// Encode captured PCM in the device format
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the PCM does not hold whole device frames or
+    /// encoding fails.
+    pub fn encode(&mut self, pcm: &[i16], timestamp_ms: u64) -> Result<Bytes, MediaError> {
+        let config = self.params.encoder_config();
+        let captured = pcm;
+        let pcm = remix(pcm, self.device.channels, self.params.channels)?;
+        if let Some(monitor) = &mut self.monitor {
+            monitor.push(captured);
+        }
+        let pcm = match &mut self.capture_resampler {
+            Some(resampler) => resampler.process(&pcm),
+            None => pcm,
+        };
+        if pcm.is_empty() {
+            return Ok(Bytes::new());
+        }
+        let frame = AudioFrame {
+            data: pcm,
+            sample_rate: config.sample_rate,
+            channels: config.channels,
+            timestamp: timestamp_ms,
+        };
+        self.encoder
+            .encode(&frame)
+            .map_err(|e| MediaError::StreamError(format!("Opus encode failed: {e}")))
+    }
+
+    /// Decode a received packet to PCM in the device format
+    ///
+    /// # Errors
+    ///
+    /// Returns error if decoding fails or the packet is not in the agreed
+    /// format.
+    pub fn decode(&mut self, data: &[u8]) -> Result<Vec<i16>, MediaError> {
+        if data.is_empty() {
+            return Ok(Vec::new());
+        }
+        let config = self.params.encoder_config();
+        let frame = self
+            .decoder
+            .decode(data)
+            .map_err(|e| MediaError::StreamError(format!("Opus decode failed: {e}")))?;
+        if frame.sample_rate != config.sample_rate || frame.channels != config.channels {
+            return Err(MediaError::StreamError(
+                "received audio does not match the negotiated format".to_string(),
+            ));
+        }
+
+        let pcm = remix(&frame.data, self.params.channels, self.device.channels)?;
+        let mut pcm = match &mut self.playback_resampler {
+            Some(resampler) => resampler.process(&pcm),
+            None => pcm,
+        };
+        self.mix_monitor(&mut pcm);
+        Ok(pcm)
+    }
+}
+
+/// Attenuated copy of captured audio for local playback
+#[derive(Debug)]
+struct Monitor {
+    gain: f32,
+    pending: VecDeque<i16>,
+    /// Samples held before the oldest are dropped, bounding the delay
+    capacity: usize,
+}
+
+impl Monitor {
+    fn push(&mut self, pcm: &[i16]) {
+        self.pending
+            .extend(pcm.iter().map(|&s| (f32::from(s) * self.gain) as i16));
+        // Capacity holds whole frames, so dropping keeps channels aligned
+        let excess = self.pending.len().saturating_sub(self.capacity);
+        self.pending.drain(..excess);
+    }
+}
+
+/// Convert interleaved PCM between mono and stereo
+///
+/// Mono is duplicated to both channels; stereo is averaged down to mono.
+fn remix(pcm: &[i16], from: u8, to: u8) -> Result<Vec<i16>, MediaError> {
+    let from = usize::from(from.clamp(1, 2));
+    let to = usize::from(to.clamp(1, 2));
+    if !pcm.len().is_multiple_of(from) {
+        return Err(MediaError::StreamError(format!(
+            "{} samples is not a whole number of {from}-channel frames",
+            pcm.len()
+        )));
+    }
+    Ok(match (from, to) {
+        (1, 2) => pcm.iter().flat_map(|&s| [s, s]).collect(),
+        (2, 1) => pcm
+            .chunks_exact(2)
+            .map(|f| ((i32::from(f[0]) + i32::from(f[1])) / 2) as i16)
+            .collect(),
+        _ => pcm.to_vec(),
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matching_formats_roundtrip() {
+        let params = AudioParameters::default();
+        let mut pipeline = AudioPipeline::new(params, DeviceFormat::default()).unwrap();
+        assert!(!pipeline.is_resampling());
+        assert_eq!(pipeline.capture_latency(), Duration::ZERO);
+
+        let pcm: Vec<i16> = (0..960).map(|i| (i * 7) as i16).collect();
+        let packet = pipeline.encode(&pcm, 20).unwrap();
+        assert_eq!(pipeline.decode(&packet).unwrap(), pcm);
+    }
+
+    #[test]
+    fn test_stereo_device_with_mono_codec() {
+        let mut pipeline =
+            AudioPipeline::new(AudioParameters::default(), DeviceFormat::new(48_000, 2)).unwrap();
+
+        let pcm = [100i16, 300, -50, 50];
+        let packet = pipeline.encode(&pcm, 0).unwrap();
+        // Downmixed for sending, duplicated back out for playback
+        assert_eq!(pipeline.decode(&packet).unwrap(), vec![200, 200, 0, 0]);
+        assert!(pipeline.encode(&[1, 2, 3], 0).is_err());
+    }
+
+    #[test]
+    fn test_monitor_feeds_capture_to_playback() {
+        let mut pipeline =
+            AudioPipeline::new(AudioParameters::default(), DeviceFormat::default()).unwrap();
+        pipeline.encode(&[1000; 480], 0).unwrap();
+        let mut silent = [0i16; 480];
+        pipeline.mix_monitor(&mut silent);
+        assert_eq!(silent, [0; 480]);
+
+        pipeline.set_monitor(Some(DEFAULT_MONITOR_GAIN));
+        let packet = pipeline.encode(&[1000; 480], 0).unwrap();
+        // Sidetone is mixed under received audio
+        let played = pipeline.decode(&packet).unwrap();
+        assert!(played.iter().all(|&s| s == 1100));
+
+        // and drained on its own when nothing is received
+        pipeline.encode(&[-1000; 480], 0).unwrap();
+        let mut output = [0i16; 960];
+        pipeline.mix_monitor(&mut output);
+        assert!(output[..480].iter().all(|&s| s == -100));
+        assert!(output[480..].iter().all(|&s| s == 0));
+    }
+
+    #[test]
+    fn test_monitor_delay_is_bounded() {
+        let mut pipeline =
+            AudioPipeline::new(AudioParameters::default(), DeviceFormat::new(48_000, 2)).unwrap();
+        pipeline.set_monitor(Some(2.0));
+        assert_eq!(pipeline.monitor_gain(), Some(1.0));
+        for _ in 0..50 {
+            pipeline.encode(&[7; 1920], 0).unwrap();
+        }
+        // One second captured, only the newest 200 ms kept
+        let mut output = vec![0i16; 48_000 * 2];
+        pipeline.mix_monitor(&mut output);
+        assert_eq!(output.iter().filter(|&&s| s == 7).count(), 9600 * 2);
+
+        pipeline.set_monitor(None);
+        assert_eq!(pipeline.monitor_gain(), None);
+    }
+
+    #[test]
+    fn test_rate_mismatch_inserts_resampler() {
+        let params = AudioParameters {
+            max_sample_rate_hz: 16_000,
+            ..Default::default()
+        };
+        let mut pipeline = AudioPipeline::new(params, DeviceFormat::new(48_000, 1)).unwrap();
+        assert!(pipeline.is_resampling());
+        assert!(pipeline.capture_latency() > Duration::ZERO);
+
+        // 20 ms at 48 kHz becomes 20 ms at 16 kHz on the wire
+        let pcm = vec![1000i16; 960];
+        let mut sent = 0;
+        for _ in 0..5 {
+            let packet = pipeline.encode(&pcm, 0).unwrap();
+            sent += OpusDecoder::new(params.sample_rate(), params.opus_channels())
+                .unwrap()
+                .decode(&packet)
+                .unwrap()
+                .data
+                .len();
+        }
+        // Less the few frames the resampler still holds back
+        let held = 5 * 320 - sent;
+        assert!((1..=6).contains(&held), "{held} frames held");
+    }
+}
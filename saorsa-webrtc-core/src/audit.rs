@@ -0,0 +1,345 @@
+//! Append-only audit log of call lifecycle events
+//!
+//! For deployments that must account for every call, the call manager can
+//! write one JSON object per line to an audit file: calls placed and
+//! received, accepted, rejected, ended and failed, and the policy decisions
+//! taken along the way (call limits, glare resolution, auto-answer).
+//!
+//! The audit log is independent of `tracing`: it is not filtered by log
+//! level, its identities are not redacted, and records are written directly
+//! to the file as they happen. The file is only ever appended to, except by
+//! the purge methods, which rewrite it without the purged records.
+//!
+//! ```text
+//! {"timestamp":"2026-05-01T09:00:00Z","call_id":"…","event":"call_initiated","peer":"alice-bob-carol-dave"}
+//! {"timestamp":"2026-05-01T09:00:04Z","call_id":"…","event":"call_accepted"}
+//! ```
+
+use crate::clock::SharedClock;
+use crate::types::CallId;
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Audit log errors
+#[derive(Error, Debug)]
+pub enum AuditError {
+    /// Reading or writing the audit file failed
+    #[error("Audit log I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// A record could not be encoded or decoded
+    #[error("Audit record error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Audit log configuration
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditConfig {
+    /// File to append records to; auditing is off when unset
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+}
+
+/// What happened to a call
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AuditEvent {
+    /// We placed a call
+    CallInitiated {
+        /// Who was called
+        peer: String,
+    },
+    /// A peer called us
+    IncomingCall {
+        /// Who called
+        peer: String,
+    },
+    /// The call was answered
+    CallAccepted,
+    /// The call was declined before connecting
+    CallRejected,
+    /// The call was hung up
+    CallEnded,
+    /// The call failed
+    CallFailed {
+        /// Why it failed
+        reason: String,
+    },
+    /// The call moved to another device of the same user
+    HandedOff {
+        /// Device now holding the call
+        device: String,
+    },
+    /// The application attached metadata to the call
+    MetadataSet {
+        /// Key that was set
+        key: String,
+        /// New value
+        value: String,
+    },
+    /// A policy allowed or refused something for the call
+    PolicyDecision {
+        /// Which policy decided, e.g. `max_concurrent_calls`
+        policy: String,
+        /// Whether the call was allowed to proceed
+        allowed: bool,
+        /// Explanation, if any
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+    },
+}
+
+impl AuditEvent {
+    /// Peer named by the event, for events that name one
+    #[must_use]
+    pub fn peer(&self) -> Option<&str> {
+        match self {
+            Self::CallInitiated { peer }
+            | Self::IncomingCall { peer }
+            | Self::HandedOff { device: peer } => Some(peer),
+            _ => None,
+        }
+    }
+}
+
+/// One line of the audit log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// When it happened
+    pub timestamp: DateTime<Utc>,
+    /// Call the event belongs to, correlating the records of one call
+    pub call_id: CallId,
+    /// What happened
+    #[serde(flatten)]
+    pub event: AuditEvent,
+}
+
+/// Append-only JSON-lines audit log
+#[derive(Debug)]
+pub struct AuditLog {
+    path: PathBuf,
+    file: Mutex<File>,
+    clock: SharedClock,
+}
+
+impl AuditLog {
+    /// Open an audit file for appending, creating it if needed
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be opened
+    pub fn open(path: &Path, clock: SharedClock) -> Result<Self, AuditError> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            file: Mutex::new(file),
+            clock,
+        })
+    }
+
+    /// Path of the audit file
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append a record stamped with the current time
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the record cannot be written
+    pub fn record(&self, call_id: CallId, event: AuditEvent) -> Result<(), AuditError> {
+        let record = AuditRecord {
+            timestamp: self.clock.utc_now(),
+            call_id,
+            event,
+        };
+        let mut line = serde_json::to_vec(&record)?;
+        line.push(b'\n');
+        // One write per record, so concurrent appends never interleave
+        self.file.lock().write_all(&line)?;
+        Ok(())
+    }
+
+    /// Calls with `peer`, matched by their `call_initiated` and
+    /// `incoming_call` records
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be read
+    pub fn calls_with_peer(&self, peer: &str) -> Result<HashSet<CallId>, AuditError> {
+        let _appends = self.file.lock();
+        Ok(Self::read(&self.path)?
+            .into_iter()
+            .filter(|record| record.event.peer() == Some(peer))
+            .map(|record| record.call_id)
+            .collect())
+    }
+
+    /// Remove every record of the given calls
+    ///
+    /// # Returns
+    ///
+    /// Number of records removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be read or rewritten
+    pub fn purge_calls(&self, calls: &HashSet<CallId>) -> Result<usize, AuditError> {
+        self.rewrite(|record| !calls.contains(&record.call_id))
+    }
+
+    /// Remove records written before `before`
+    ///
+    /// # Returns
+    ///
+    /// Number of records removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be read or rewritten
+    pub fn purge_before(&self, before: DateTime<Utc>) -> Result<usize, AuditError> {
+        self.rewrite(|record| record.timestamp >= before)
+    }
+
+    /// Replace the file with a copy holding only the records to keep
+    ///
+    /// The copy is written next to the file and renamed over it, so a crash
+    /// leaves either the old or the new records. Appends wait meanwhile.
+    fn rewrite(&self, keep: impl Fn(&AuditRecord) -> bool) -> Result<usize, AuditError> {
+        let mut file = self.file.lock();
+        let mut records = Self::read(&self.path)?;
+        let held = records.len();
+        records.retain(keep);
+
+        let mut temp = self.path.clone().into_os_string();
+        temp.push(".purge");
+        let temp = PathBuf::from(temp);
+        let mut out = BufWriter::new(File::create(&temp)?);
+        for record in &records {
+            serde_json::to_writer(&mut out, record)?;
+            out.write_all(b"\n")?;
+        }
+        out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        std::fs::rename(&temp, &self.path)?;
+
+        *file = OpenOptions::new().append(true).open(&self.path)?;
+        Ok(held - records.len())
+    }
+
+    /// Read every record of an audit file, oldest first
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be read or a line is not a record
+    pub fn read(path: &Path) -> Result<Vec<AuditRecord>, AuditError> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut records = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                records.push(serde_json::from_str(&line)?);
+            }
+        }
+        Ok(records)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use std::time::Duration;
+
+    #[test]
+    fn test_records_append_as_json_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let clock = MockClock::new();
+        let call_id = CallId::new();
+
+        let log = AuditLog::open(&path, clock.shared()).unwrap();
+        log.record(
+            call_id,
+            AuditEvent::CallInitiated {
+                peer: "alice".to_string(),
+            },
+        )
+        .unwrap();
+        clock.advance(Duration::from_secs(3));
+        log.record(call_id, AuditEvent::CallAccepted).unwrap();
+        drop(log);
+
+        // Reopening appends rather than truncating
+        let log = AuditLog::open(&path, clock.shared()).unwrap();
+        log.record(
+            call_id,
+            AuditEvent::PolicyDecision {
+                policy: "max_concurrent_calls".to_string(),
+                allowed: false,
+                reason: None,
+            },
+        )
+        .unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(text.lines().count(), 3);
+        assert!(text
+            .lines()
+            .nth(1)
+            .unwrap()
+            .contains(r#""event":"call_accepted""#));
+        assert!(!text.contains("reason"));
+
+        let records = AuditLog::read(&path).unwrap();
+        assert_eq!(records[1].event, AuditEvent::CallAccepted);
+        assert_eq!(
+            records[1].timestamp - records[0].timestamp,
+            chrono::Duration::seconds(3)
+        );
+        assert!(records.iter().all(|r| r.call_id == call_id));
+    }
+
+    #[test]
+    fn test_purge_rewrites_without_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let clock = MockClock::new();
+        let log = AuditLog::open(&path, clock.shared()).unwrap();
+
+        let (alice, bob) = (CallId::new(), CallId::new());
+        for (call_id, peer) in [(alice, "alice"), (bob, "bob")] {
+            log.record(
+                call_id,
+                AuditEvent::CallInitiated {
+                    peer: peer.to_string(),
+                },
+            )
+            .unwrap();
+            log.record(call_id, AuditEvent::CallEnded).unwrap();
+            clock.advance(Duration::from_secs(60));
+        }
+
+        let calls = log.calls_with_peer("alice").unwrap();
+        assert_eq!(calls, HashSet::from([alice]));
+        assert_eq!(log.purge_calls(&calls).unwrap(), 2);
+        let records = AuditLog::read(&path).unwrap();
+        assert_eq!(records.len(), 2);
+        assert!(records.iter().all(|r| r.call_id == bob));
+
+        // Appends continue on the rewritten file
+        log.record(alice, AuditEvent::CallEnded).unwrap();
+        let cutoff = records[0].timestamp + chrono::Duration::seconds(1);
+        assert_eq!(log.purge_before(cutoff).unwrap(), 2);
+        let records = AuditLog::read(&path).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].call_id, alice);
+    }
+}
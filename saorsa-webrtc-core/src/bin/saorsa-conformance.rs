@@ -0,0 +1,49 @@
+//! Wire protocol conformance checker
+//!
+//! ```text
+//! saorsa-conformance generate [PATH]   write this crate's test vectors
+//! saorsa-conformance check PATH        check a vector file against this crate
+//! ```
+//!
+//! `check` exits with status 1 if any vector does not conform, so other
+//! implementations can run it in CI against vectors their encoder wrote.
+
+use anyhow::{bail, Context, Result};
+use saorsa_webrtc_core::TestVectors;
+use std::process::ExitCode;
+
+const USAGE: &str = "usage: saorsa-conformance generate [PATH] | check PATH";
+
+fn main() -> Result<ExitCode> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["generate"] => {
+            println!("{}", TestVectors::generate()?.to_json()?);
+            Ok(ExitCode::SUCCESS)
+        }
+        ["generate", path] => {
+            let json = TestVectors::generate()?.to_json()?;
+            std::fs::write(path, json + "\n").with_context(|| format!("writing {path}"))?;
+            Ok(ExitCode::SUCCESS)
+        }
+        ["check", path] => {
+            let json = std::fs::read_to_string(path).with_context(|| format!("reading {path}"))?;
+            let vectors = TestVectors::from_json(&json)?;
+            let mismatches = vectors.check();
+            for mismatch in &mismatches {
+                println!("FAIL {}: {}", mismatch.name, mismatch.detail);
+            }
+            let total = vectors.frames.len() + vectors.negotiations.len();
+            println!(
+                "{} of {total} vectors conform",
+                total.saturating_sub(mismatches.len())
+            );
+            Ok(if mismatches.is_empty() {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            })
+        }
+        _ => bail!(USAGE),
+    }
+}
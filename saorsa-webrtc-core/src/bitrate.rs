@@ -0,0 +1,261 @@
+//! Windowed bitrate and packet-rate estimation
+//!
+//! Rates are exponentially weighted moving averages over a short (1s) and
+//! a long (10s) time constant. Each estimator keeps an exponentially
+//! decaying sum of bytes and packets, so rates fall towards zero while a
+//! stream is idle and no per-packet history is stored.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Time constant of the short-term rate (1 second)
+pub const SHORT_RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Time constant of the long-term rate (10 seconds)
+pub const LONG_RATE_WINDOW: Duration = Duration::from_secs(10);
+
+/// Bit and packet rates of one stream direction
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Rates {
+    /// Bitrate over the 1s window, in bits per second
+    pub bitrate_1s_bps: f64,
+    /// Bitrate over the 10s window, in bits per second
+    pub bitrate_10s_bps: f64,
+    /// Packet rate over the 1s window, in packets per second
+    pub packet_rate_1s: f64,
+    /// Packet rate over the 10s window, in packets per second
+    pub packet_rate_10s: f64,
+}
+
+impl Rates {
+    /// Short-term bitrate in kbps, rounded down
+    #[must_use]
+    pub fn bitrate_kbps(&self) -> u32 {
+        (self.bitrate_1s_bps / 1000.0) as u32
+    }
+}
+
+impl std::ops::Add for Rates {
+    type Output = Rates;
+
+    fn add(self, other: Rates) -> Rates {
+        Rates {
+            bitrate_1s_bps: self.bitrate_1s_bps + other.bitrate_1s_bps,
+            bitrate_10s_bps: self.bitrate_10s_bps + other.bitrate_10s_bps,
+            packet_rate_1s: self.packet_rate_1s + other.packet_rate_1s,
+            packet_rate_10s: self.packet_rate_10s + other.packet_rate_10s,
+        }
+    }
+}
+
+/// Sent and received rates of a stream
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct StreamRates {
+    /// Outbound rates
+    pub sent: Rates,
+    /// Inbound rates
+    pub received: Rates,
+}
+
+/// Exponentially decaying byte and packet sums for one time constant
+#[derive(Debug, Clone, Copy)]
+struct Ewma {
+    tau: f64,
+    bytes: f64,
+    packets: f64,
+    updated: Instant,
+}
+
+impl Ewma {
+    fn new(window: Duration, now: Instant) -> Self {
+        Self {
+            tau: window.as_secs_f64(),
+            bytes: 0.0,
+            packets: 0.0,
+            updated: now,
+        }
+    }
+
+    fn decay_factor(&self, now: Instant) -> f64 {
+        (-now.duration_since(self.updated).as_secs_f64() / self.tau).exp()
+    }
+
+    fn record(&mut self, bytes: u64, now: Instant) {
+        let decay = self.decay_factor(now);
+        self.bytes = self.bytes * decay + bytes as f64;
+        self.packets = self.packets * decay + 1.0;
+        self.updated = now;
+    }
+
+    /// Bits and packets per second as of `now`
+    fn rates(&self, now: Instant) -> (f64, f64) {
+        let decay = self.decay_factor(now);
+        (
+            self.bytes * decay * 8.0 / self.tau,
+            self.packets * decay / self.tau,
+        )
+    }
+}
+
+/// Short- and long-term rate estimator for one stream direction
+#[derive(Debug, Clone, Copy)]
+pub struct RateEstimator {
+    short: Ewma,
+    long: Ewma,
+}
+
+impl Default for RateEstimator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RateEstimator {
+    /// Create an estimator with no traffic
+    #[must_use]
+    pub fn new() -> Self {
+        Self::new_at(Instant::now())
+    }
+
+    /// Create an estimator with no traffic as of `now`
+    #[must_use]
+    pub fn new_at(now: Instant) -> Self {
+        Self {
+            short: Ewma::new(SHORT_RATE_WINDOW, now),
+            long: Ewma::new(LONG_RATE_WINDOW, now),
+        }
+    }
+
+    /// Record a packet
+    pub fn record(&mut self, bytes: u64) {
+        self.record_at(bytes, Instant::now());
+    }
+
+    /// Record a packet seen at `now`
+    pub fn record_at(&mut self, bytes: u64, now: Instant) {
+        self.short.record(bytes, now);
+        self.long.record(bytes, now);
+    }
+
+    /// Current rates
+    #[must_use]
+    pub fn rates(&self) -> Rates {
+        self.rates_at(Instant::now())
+    }
+
+    /// Rates as of `now`
+    #[must_use]
+    pub fn rates_at(&self, now: Instant) -> Rates {
+        let (bitrate_1s_bps, packet_rate_1s) = self.short.rates(now);
+        let (bitrate_10s_bps, packet_rate_10s) = self.long.rates(now);
+        Rates {
+            bitrate_1s_bps,
+            bitrate_10s_bps,
+            packet_rate_1s,
+            packet_rate_10s,
+        }
+    }
+}
+
+/// Rate estimators for both directions of a stream
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StreamRateEstimator {
+    /// Outbound estimator
+    pub sent: RateEstimator,
+    /// Inbound estimator
+    pub received: RateEstimator,
+}
+
+impl StreamRateEstimator {
+    /// Create estimators with no traffic as of `now`
+    #[must_use]
+    pub fn new_at(now: Instant) -> Self {
+        Self {
+            sent: RateEstimator::new_at(now),
+            received: RateEstimator::new_at(now),
+        }
+    }
+
+    /// Current rates in both directions
+    #[must_use]
+    pub fn snapshot(&self) -> StreamRates {
+        self.snapshot_at(Instant::now())
+    }
+
+    /// Rates in both directions as of `now`
+    #[must_use]
+    pub fn snapshot_at(&self, now: Instant) -> StreamRates {
+        StreamRates {
+            sent: self.sent.rates_at(now),
+            received: self.received.rates_at(now),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    /// Feed `packets_per_sec` packets of `bytes` for `secs` seconds
+    async fn feed(estimator: &mut RateEstimator, bytes: u64, packets_per_sec: u32, secs: u32) {
+        let gap = Duration::from_secs(1) / packets_per_sec;
+        for _ in 0..packets_per_sec * secs {
+            tokio::time::advance(gap).await;
+            estimator.record(bytes);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_steady_rate_converges() {
+        let mut estimator = RateEstimator::new();
+        // 50 packets/s of 160 bytes = 64 kbps
+        feed(&mut estimator, 160, 50, 60).await;
+
+        let rates = estimator.rates();
+        assert!((rates.bitrate_1s_bps - 64_000.0).abs() < 2_000.0);
+        assert!((rates.bitrate_10s_bps - 64_000.0).abs() < 2_000.0);
+        assert!((rates.packet_rate_1s - 50.0).abs() < 2.0);
+        assert_eq!(rates.bitrate_kbps() / 10, 6);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_short_window_reacts_faster() {
+        let mut estimator = RateEstimator::new();
+        feed(&mut estimator, 1000, 100, 30).await;
+
+        // Traffic stops: the 1s rate collapses, the 10s rate lingers
+        tokio::time::advance(Duration::from_secs(3)).await;
+        let rates = estimator.rates();
+        assert!(rates.bitrate_1s_bps < 0.1 * 800_000.0);
+        assert!(rates.bitrate_10s_bps > 0.5 * 800_000.0);
+    }
+
+    #[test]
+    fn test_explicit_time() {
+        let start = Instant::now();
+        let mut estimator = RateEstimator::new_at(start);
+        for i in 1..=100u32 {
+            estimator.record_at(125, start + Duration::from_millis(10) * i);
+        }
+        // 100 packets/s of 1000 bits
+        let rates = estimator.rates_at(start + Duration::from_secs(1));
+        assert!(rates.packet_rate_1s > 60.0 && rates.packet_rate_1s < 100.0);
+        assert!(
+            estimator
+                .rates_at(start + Duration::from_secs(30))
+                .bitrate_1s_bps
+                < 1.0
+        );
+    }
+
+    #[test]
+    fn test_idle_estimator_is_zero() {
+        assert_eq!(RateEstimator::new().rates(), Rates::default());
+        assert_eq!(
+            StreamRateEstimator::default().snapshot(),
+            StreamRates::default()
+        );
+    }
+}
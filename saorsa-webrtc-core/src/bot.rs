@@ -0,0 +1,555 @@
+//! Bots and auto-responders
+//!
+//! A [`CallBot`] decides what to do with incoming calls and reacts to the
+//! audio and data the peer sends; [`BotRunner`] wires it to a
+//! [`WebRtcService`]. IVR menus, echo servers and automated test answerers
+//! only implement the callbacks they need:
+//!
+//! ```rust,no_run
+//! use saorsa_webrtc_core::bot::{BotCall, BotRunner, CallBot};
+//! use saorsa_webrtc_core::quic_bridge::RtpPacket;
+//! use saorsa_webrtc_core::{PeerIdentityString, SignalingTransport, WebRtcService};
+//! use std::sync::Arc;
+//!
+//! struct Echo;
+//!
+//! #[async_trait::async_trait]
+//! impl CallBot<PeerIdentityString> for Echo {
+//!     async fn on_audio_frame(&self, call: &BotCall<PeerIdentityString>, packet: RtpPacket) {
+//!         let _ = call.send_audio(&packet.payload).await;
+//!     }
+//! }
+//!
+//! # async fn example<T: SignalingTransport + 'static>(
+//! #     service: Arc<WebRtcService<PeerIdentityString, T>>,
+//! # ) {
+//! tokio::spawn(BotRunner::new(service, Arc::new(Echo)).run());
+//! # }
+//! ```
+//!
+//! Bots send Opus payloads; the runner wraps them in RTP packets with the
+//! call's own SSRC, sequence numbers and timestamps.
+
+use crate::call::CallManager;
+use crate::identity::PeerIdentity;
+use crate::link_transport::StreamType as LinkStreamType;
+use crate::quic_bridge::{RtpPacket, StreamType};
+use crate::quic_media_transport::{QuicMediaTransport, StreamKey};
+use crate::service::WebRtcService;
+use crate::signaling::SignalingTransport;
+use crate::types::{CallEvent, CallId, CallOffer, MediaConstraints};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinHandle;
+
+/// RTP payload type of the Opus audio bots send
+pub const OPUS_PAYLOAD_TYPE: u8 = 111;
+
+/// RTP clock rate of Opus audio
+pub const OPUS_CLOCK_RATE: u32 = 48_000;
+
+/// Bot errors
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum BotError {
+    /// The payload does not fit in an RTP packet
+    #[error("Bot packet error: {0}")]
+    Packet(String),
+
+    /// The call's media transport refused the packet
+    #[error("Bot media error: {0}")]
+    Media(String),
+}
+
+/// Bot runner configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BotConfig {
+    /// Audio carried by each packet; paces [`BotCall::respond_with_audio`]
+    pub packet_interval: Duration,
+    /// How long a connected call may go without a media transport before
+    /// the bot gives up on it
+    pub media_timeout: Duration,
+}
+
+impl Default for BotConfig {
+    fn default() -> Self {
+        Self {
+            packet_interval: Duration::from_millis(20),
+            media_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// What to do with a ringing call
+#[derive(Debug, Clone)]
+pub enum BotDecision {
+    /// Answer with these media
+    Accept(MediaConstraints),
+    /// Decline the call
+    Reject,
+    /// Leave it ringing for someone else to answer
+    Ignore,
+}
+
+/// A bot that takes part in calls
+///
+/// Every callback has a default: calls are answered with the media offered
+/// and everything the peer sends is ignored. Callbacks for one call run one
+/// at a time, in the order the media arrived.
+#[async_trait]
+pub trait CallBot<I: PeerIdentity>: Send + Sync + 'static {
+    /// Decide what to do with a ringing call
+    async fn on_incoming(&self, offer: &CallOffer<I>) -> BotDecision {
+        BotDecision::Accept(MediaConstraints::from_media_types(&offer.media_types))
+    }
+
+    /// A call is connected and its media can flow
+    async fn on_connected(&self, _call: &BotCall<I>) {}
+
+    /// The peer sent a packet of audio
+    async fn on_audio_frame(&self, _call: &BotCall<I>, _packet: RtpPacket) {}
+
+    /// The peer sent a message on the data channel
+    async fn on_data_message(&self, _call: &BotCall<I>, _message: Vec<u8>) {}
+
+    /// A call the bot was in has ended
+    async fn on_ended(&self, _call_id: CallId) {}
+}
+
+/// RTP numbering of one outgoing stream
+#[derive(Debug)]
+struct RtpSequence {
+    ssrc: u32,
+    sequence_number: u16,
+    timestamp: u32,
+}
+
+impl RtpSequence {
+    fn new() -> Self {
+        Self {
+            ssrc: rand::random(),
+            sequence_number: rand::random(),
+            timestamp: rand::random(),
+        }
+    }
+
+    /// Number the next packet, advancing the timestamp by `ticks`
+    fn next(&mut self, ticks: u32) -> (u16, u32) {
+        let numbers = (self.sequence_number, self.timestamp);
+        self.sequence_number = self.sequence_number.wrapping_add(1);
+        self.timestamp = self.timestamp.wrapping_add(ticks);
+        numbers
+    }
+}
+
+/// A connected call as a bot sees it
+pub struct BotCall<I: PeerIdentity> {
+    call_id: CallId,
+    peer: I,
+    transport: Arc<QuicMediaTransport>,
+    packet_interval: Duration,
+    audio: parking_lot::Mutex<RtpSequence>,
+    data: parking_lot::Mutex<RtpSequence>,
+    hang_up: mpsc::UnboundedSender<CallId>,
+}
+
+impl<I: PeerIdentity> BotCall<I> {
+    /// Call identifier
+    #[must_use]
+    pub fn call_id(&self) -> CallId {
+        self.call_id
+    }
+
+    /// Remote peer
+    #[must_use]
+    pub fn peer(&self) -> &I {
+        &self.peer
+    }
+
+    /// Send one packet of Opus audio
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the payload is too large or the transport refuses it
+    pub async fn send_audio(&self, payload: &[u8]) -> Result<(), BotError> {
+        let ticks =
+            u32::try_from(u128::from(OPUS_CLOCK_RATE) * self.packet_interval.as_millis() / 1000)
+                .unwrap_or(u32::MAX);
+        let (sequence_number, timestamp) = self.audio.lock().next(ticks);
+        let ssrc = self.audio.lock().ssrc;
+        let bytes = rtp_bytes(payload, sequence_number, timestamp, ssrc, StreamType::Audio)?;
+        self.transport
+            .send_audio(&bytes)
+            .await
+            .map_err(|e| BotError::Media(e.to_string()))
+    }
+
+    /// Play Opus packets to the peer, one per packet interval
+    ///
+    /// Returns once the last packet is sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error sending a packet; the rest are not sent
+    pub async fn respond_with_audio(&self, packets: &[Vec<u8>]) -> Result<(), BotError> {
+        let mut interval = tokio::time::interval(self.packet_interval);
+        for packet in packets {
+            interval.tick().await;
+            self.send_audio(packet).await?;
+        }
+        Ok(())
+    }
+
+    /// Send a message on the data channel
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the message is too large or the transport refuses it
+    pub async fn send_data(&self, message: &[u8]) -> Result<(), BotError> {
+        let (sequence_number, timestamp) = self.data.lock().next(0);
+        let ssrc = self.data.lock().ssrc;
+        let bytes = rtp_bytes(message, sequence_number, timestamp, ssrc, StreamType::Data)?;
+        self.transport
+            .send_data(&bytes)
+            .await
+            .map_err(|e| BotError::Media(e.to_string()))
+    }
+
+    /// End the call
+    pub fn hang_up(&self) {
+        let _ = self.hang_up.send(self.call_id);
+    }
+}
+
+fn rtp_bytes(
+    payload: &[u8],
+    sequence_number: u16,
+    timestamp: u32,
+    ssrc: u32,
+    stream_type: StreamType,
+) -> Result<Vec<u8>, BotError> {
+    RtpPacket::new(
+        OPUS_PAYLOAD_TYPE,
+        sequence_number,
+        timestamp,
+        ssrc,
+        payload.to_vec(),
+        stream_type,
+    )
+    .and_then(|packet| packet.to_bytes())
+    .map_err(|e| BotError::Packet(e.to_string()))
+}
+
+/// Runs a [`CallBot`] on a service's calls
+///
+/// The runner subscribes to the service's call events when created, so
+/// calls arriving before [`run`](Self::run) is polled are not lost. It
+/// answers ringing calls as the bot decides and joins the bot to every call
+/// that connects, incoming or placed.
+pub struct BotRunner<I: PeerIdentity, T: SignalingTransport, B: CallBot<I>> {
+    service: Arc<WebRtcService<I, T>>,
+    bot: Arc<B>,
+    config: BotConfig,
+    events: broadcast::Receiver<CallEvent<I>>,
+}
+
+impl<I: PeerIdentity, T: SignalingTransport, B: CallBot<I>> BotRunner<I, T, B> {
+    /// Create a runner for a bot on a service
+    #[must_use]
+    pub fn new(service: Arc<WebRtcService<I, T>>, bot: Arc<B>) -> Self {
+        let events = service.subscribe_call_events();
+        Self {
+            service,
+            bot,
+            config: BotConfig::default(),
+            events,
+        }
+    }
+
+    /// Use this configuration
+    #[must_use]
+    pub fn with_config(mut self, config: BotConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Run until the service is dropped
+    pub async fn run(mut self) {
+        let (hang_up, mut hang_ups) = mpsc::unbounded_channel();
+        let mut peers: HashMap<CallId, I> = HashMap::new();
+        let mut calls: HashMap<CallId, JoinHandle<()>> = HashMap::new();
+        loop {
+            let event = tokio::select! {
+                event = self.events.recv() => event,
+                Some(call_id) = hang_ups.recv() => {
+                    if let Err(e) = self.service.end_call(call_id).await {
+                        tracing::warn!(call_id = %call_id, error = %e, "Bot failed to hang up");
+                    }
+                    continue;
+                }
+            };
+            let event = match event {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "Bot missed call events");
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+
+            match event {
+                CallEvent::IncomingCall { offer } => {
+                    peers.insert(offer.call_id, offer.caller.clone());
+                    self.answer(&offer).await;
+                }
+                CallEvent::CallInitiated {
+                    call_id, callee, ..
+                } => {
+                    peers.insert(call_id, callee);
+                }
+                CallEvent::ConnectionEstablished { call_id } if !calls.contains_key(&call_id) => {
+                    let peer = match peers.get(&call_id) {
+                        Some(peer) => peer.clone(),
+                        None => match self.peer_of(call_id).await {
+                            Some(peer) => peer,
+                            None => continue,
+                        },
+                    };
+                    let call = self.join(call_id, peer, hang_up.clone());
+                    calls.insert(call_id, call);
+                }
+                CallEvent::CallEnded { call_id }
+                | CallEvent::CallRejected { call_id }
+                | CallEvent::CallCancelled { call_id, .. }
+                | CallEvent::ConnectionFailed { call_id, .. } => {
+                    peers.remove(&call_id);
+                    if let Some(call) = calls.remove(&call_id) {
+                        call.abort();
+                        self.bot.on_ended(call_id).await;
+                    }
+                }
+                _ => {}
+            }
+        }
+        for call in calls.into_values() {
+            call.abort();
+        }
+    }
+
+    /// Act on the bot's decision about a ringing call
+    async fn answer(&self, offer: &CallOffer<I>) {
+        let call_id = offer.call_id;
+        let result = match self.bot.on_incoming(offer).await {
+            BotDecision::Accept(constraints) => {
+                self.service.accept_call(call_id, constraints).await
+            }
+            BotDecision::Reject => self.service.reject_call(call_id).await,
+            BotDecision::Ignore => Ok(()),
+        };
+        if let Err(e) = result {
+            tracing::warn!(call_id = %call_id, error = %e, "Bot failed to answer call");
+        }
+    }
+
+    async fn peer_of(&self, call_id: CallId) -> Option<I> {
+        self.service
+            .calls()
+            .await
+            .into_iter()
+            .find(|(id, _, _)| *id == call_id)
+            .map(|(_, peer, _)| peer)
+    }
+
+    /// Feed a connected call's media to the bot
+    fn join(
+        &self,
+        call_id: CallId,
+        peer: I,
+        hang_up: mpsc::UnboundedSender<CallId>,
+    ) -> JoinHandle<()> {
+        let call_manager = Arc::clone(self.service.call_manager());
+        let bot = Arc::clone(&self.bot);
+        let config = self.config.clone();
+        tokio::spawn(async move {
+            let Some(transport) =
+                wait_for_transport(&call_manager, call_id, config.media_timeout).await
+            else {
+                tracing::warn!(call_id = %call_id, "Bot gave up waiting for media");
+                return;
+            };
+            let mut audio = transport.subscribe_track(StreamKey::primary(LinkStreamType::Audio));
+            let mut data = transport.subscribe_track(StreamKey::primary(LinkStreamType::Data));
+            let call = BotCall {
+                call_id,
+                peer,
+                transport,
+                packet_interval: config.packet_interval,
+                audio: parking_lot::Mutex::new(RtpSequence::new()),
+                data: parking_lot::Mutex::new(RtpSequence::new()),
+                hang_up,
+            };
+            bot.on_connected(&call).await;
+
+            let (mut audio_open, mut data_open) = (true, true);
+            while audio_open || data_open {
+                tokio::select! {
+                    packet = audio.recv(), if audio_open => match packet {
+                        Some(packet) => bot.on_audio_frame(&call, packet).await,
+                        None => audio_open = false,
+                    },
+                    packet = data.recv(), if data_open => match packet {
+                        Some(packet) => bot.on_data_message(&call, packet.payload).await,
+                        None => data_open = false,
+                    },
+                }
+            }
+        })
+    }
+}
+
+/// Wait for a call's media transport to be set up
+async fn wait_for_transport<I: PeerIdentity>(
+    call_manager: &CallManager<I>,
+    call_id: CallId,
+    timeout: Duration,
+) -> Option<Arc<QuicMediaTransport>> {
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        if let Some(transport) = call_manager.media_transport(call_id).await {
+            return Some(transport);
+        }
+        if tokio::time::Instant::now() >= deadline
+            || call_manager.get_call_state(call_id).await.is_none()
+        {
+            return None;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::identity::PeerIdentityString;
+    use crate::testing::{LoopbackHarness, Role};
+    use crate::types::CallState;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Echoes audio back and ignores ringing calls, so the harness answers
+    #[derive(Default)]
+    struct EchoBot {
+        connected: AtomicUsize,
+        ended: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl CallBot<PeerIdentityString> for EchoBot {
+        async fn on_incoming(&self, _offer: &CallOffer<PeerIdentityString>) -> BotDecision {
+            BotDecision::Ignore
+        }
+
+        async fn on_connected(&self, _call: &BotCall<PeerIdentityString>) {
+            self.connected.fetch_add(1, Ordering::SeqCst);
+        }
+
+        async fn on_audio_frame(&self, call: &BotCall<PeerIdentityString>, packet: RtpPacket) {
+            call.send_audio(&packet.payload).await.unwrap();
+        }
+
+        async fn on_ended(&self, _call_id: CallId) {
+            self.ended.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_echo_bot_answers_audio() {
+        let harness = LoopbackHarness::new().await.unwrap();
+        let bot = Arc::new(EchoBot::default());
+        let runner = BotRunner::new(Arc::clone(harness.callee().service()), Arc::clone(&bot));
+        let runner = tokio::spawn(runner.run());
+
+        let call_id = harness
+            .connect_call(MediaConstraints::audio_only())
+            .await
+            .unwrap();
+        let mut tap = harness
+            .callee()
+            .service()
+            .tap_media(call_id, StreamType::Audio)
+            .await
+            .unwrap();
+        // Wait for the bot to join the call before sending it audio
+        for _ in 0..100 {
+            if bot.connected.load(Ordering::SeqCst) == 1 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(bot.connected.load(Ordering::SeqCst), 1);
+
+        let hello =
+            RtpPacket::new(111, 1, 960, 0xCAFE, b"hello".to_vec(), StreamType::Audio).unwrap();
+        harness
+            .send_media(Role::Caller, call_id, &hello)
+            .await
+            .unwrap();
+
+        // The tap sees the packet arrive, then the bot's echo leave
+        let timeout = crate::testing::DELIVERY_TIMEOUT;
+        let received = tokio::time::timeout(timeout, tap.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(received.ssrc, 0xCAFE);
+        let echoed = tokio::time::timeout(timeout, tap.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(echoed.payload, b"hello");
+        assert_ne!(echoed.ssrc, 0xCAFE);
+        assert_eq!(echoed.payload_type, OPUS_PAYLOAD_TYPE);
+
+        harness.hang_up(Role::Caller, call_id).await.unwrap();
+        for _ in 0..100 {
+            if bot.ended.load(Ordering::SeqCst) == 1 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(bot.ended.load(Ordering::SeqCst), 1);
+        runner.abort();
+    }
+
+    #[tokio::test]
+    async fn test_bot_rejects_calls() {
+        struct Rejecting;
+
+        #[async_trait]
+        impl CallBot<PeerIdentityString> for Rejecting {
+            async fn on_incoming(&self, _offer: &CallOffer<PeerIdentityString>) -> BotDecision {
+                BotDecision::Reject
+            }
+        }
+
+        let harness = LoopbackHarness::new().await.unwrap();
+        let service = Arc::clone(harness.callee().service());
+        let runner = tokio::spawn(BotRunner::new(Arc::clone(&service), Arc::new(Rejecting)).run());
+
+        let call_id = harness.offer(MediaConstraints::audio_only()).await.unwrap();
+        let mut state = service.get_call_state(call_id).await;
+        for _ in 0..100 {
+            if state != Some(CallState::Calling) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            state = service.get_call_state(call_id).await;
+        }
+        assert_ne!(state, Some(CallState::Calling));
+        assert_ne!(state, Some(CallState::Connected));
+        runner.abort();
+    }
+}
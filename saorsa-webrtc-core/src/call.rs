@@ -3,16 +3,54 @@
 //! **Note:** This module uses the webrtc crate types (requires legacy-webrtc feature).
 //! In Phase 2, this will be replaced with a QUIC-native implementation via QuicMediaTransport.
 
+use crate::audit::{AuditConfig, AuditError, AuditEvent, AuditLog};
+use crate::clock::{system_clock, SharedClock};
+use crate::dnd::DndAction;
+use crate::events::{EventChannelStats, EventSender, DEFAULT_CALL_EVENT_CAPACITY};
+use crate::fec::{FecConfig, FecController};
+use crate::handoff::{HandoffError, HandoffKey, HandoffState, HandoffToken};
 use crate::identity::PeerIdentity;
-use crate::link_transport::PeerConnection;
-use crate::media::{GenericTrack, MediaStreamManager, WebRtcTrack};
-use crate::quic_media_transport::{MediaTransportError, MediaTransportState, QuicMediaTransport};
-use crate::types::{CallEvent, CallId, CallState, MediaCapabilities, MediaConstraints};
+use crate::keepalive::{KeepaliveConfig, Liveness};
+use crate::link_transport::{PeerConnection, StreamType as LinkStreamType};
+use crate::media::GenericTrack;
+#[cfg(feature = "legacy-webrtc")]
+use crate::media::{MediaStreamManager, WebRtcTrack};
+use crate::metrics::{self, noop_metrics, MetricsRecorder, SharedMetrics};
+use crate::peer_quality::{
+    sustained_bitrate_kbps, PeerQualityCache, PeerQualityConfig, QualitySeed,
+};
+use crate::playback::{MediaFile, PlaybackError};
+use crate::policy::{DataRequest, SharedCallPolicy};
+use crate::protocol_handler::AuthDecision;
+use crate::quality::{DegradationConfig, QualityMonitor, QualityTransition};
+use crate::quic_bridge::{RtpPacket, StreamType as RtpStreamType};
+use crate::quic_media_transport::{
+    MediaGate, MediaTransportError, MediaTransportState, QuicMediaTransport, StreamKey, TrackId,
+    TransportStats,
+};
+use crate::ramp::{BitrateRamp, RampConfig};
+use crate::redact;
+use crate::resources::{ResourceCounts, ResourceGauges, ResourceGuard};
+use crate::signaling::SignalingMessage;
+use crate::state_machine::{call_state_for_transport, StateMachine};
+use crate::stats_history::{
+    SharedHistoryStore, StatsDelta, StatsHistory, StatsHistoryConfig, StatsHistoryError,
+    StatsHistoryStore, StatsSample,
+};
+use crate::types::{
+    AudioParameters, CallDirection, CallEvent, CallId, CallMetadata, CallOffer, CallProgress,
+    CallQualityMetrics, CallState, LatencyProfile, LatencyTuning, MediaCapabilities,
+    MediaConstraints, MediaType, ShortCode, TrackInfo, VideoLayer,
+};
+use chrono::{DateTime, Utc};
+use saorsa_webrtc_codecs::mime as codec_mime;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
 use std::sync::Arc;
 use thiserror::Error;
-use tokio::sync::{broadcast, RwLock};
+use tokio::sync::{broadcast, mpsc, Mutex, RwLock};
+#[cfg(feature = "legacy-webrtc")]
 use webrtc::peer_connection::RTCPeerConnection;
 
 /// Call management errors
@@ -22,6 +60,10 @@ pub enum CallError {
     #[error("Call not found: {0}")]
     CallNotFound(String),
 
+    /// A short code matches more than one call
+    #[error("Short code {0} matches more than one call; use the full call ID")]
+    AmbiguousShortCode(ShortCode),
+
     /// Invalid state
     #[error("Invalid call state")]
     InvalidState,
@@ -33,6 +75,57 @@ pub enum CallError {
     /// Transport error
     #[error("Transport error: {0}")]
     TransportError(String),
+
+    /// Stored call data could not be read or written
+    #[error("Storage error: {0}")]
+    StorageError(String),
+
+    /// A call policy refused the call
+    #[error("Call denied by policy: {0}")]
+    PolicyDenied(String),
+
+    /// Media transport error on a call
+    #[error("Media error on call {call_id}: {source}")]
+    MediaError {
+        /// Call the transport belongs to
+        call_id: CallId,
+        /// Transport error
+        #[source]
+        source: MediaTransportError,
+    },
+
+    /// A call handoff between devices was refused
+    #[error("Handoff error: {0}")]
+    Handoff(#[from] HandoffError),
+
+    /// A media file could not be played into a call
+    #[error("Playback error: {0}")]
+    Playback(#[from] PlaybackError),
+}
+
+impl CallError {
+    /// Wrap a media transport error with the call it occurred on
+    #[must_use]
+    pub fn media(call_id: CallId, source: MediaTransportError) -> Self {
+        CallError::MediaError { call_id, source }
+    }
+
+    /// Call the error refers to, when known
+    #[must_use]
+    pub fn call_id(&self) -> Option<CallId> {
+        match self {
+            CallError::CallNotFound(call_id) => call_id.parse().ok(),
+            CallError::MediaError { call_id, .. }
+            | CallError::Handoff(HandoffError::NotPending(call_id)) => Some(*call_id),
+            _ => None,
+        }
+    }
+}
+
+impl From<AuditError> for CallError {
+    fn from(err: AuditError) -> Self {
+        CallError::StorageError(err.to_string())
+    }
 }
 
 impl From<MediaTransportError> for CallError {
@@ -41,35 +134,156 @@ impl From<MediaTransportError> for CallError {
     }
 }
 
+/// What a data purge removed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PurgeReport {
+    /// Calls whose data was removed; only filled in by peer purges
+    pub calls: BTreeSet<CallId>,
+    /// Statistics samples removed
+    pub stats_samples: usize,
+    /// Audit log records removed
+    pub audit_records: usize,
+    /// Voicemail recordings deleted
+    pub recordings: usize,
+}
+
+/// A track the remote peer is sending
+#[derive(Debug)]
+pub struct RemoteTrack {
+    /// What the peer says the track is
+    pub info: TrackInfo,
+    /// Packets received on the track
+    pub packets: mpsc::Receiver<RtpPacket>,
+}
+
+/// Preparing media for outgoing calls before they are accepted
+///
+/// While an outgoing call rings, its media streams are opened and
+/// [`CallEvent::MediaPrepared`] asks the application to start its
+/// encoders, so the first frame is ready the moment the call is accepted.
+/// Nothing is sent until then: the media gate stays closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpeculativeMediaConfig {
+    /// Prepare media while ringing
+    pub enabled: bool,
+    /// Also start the camera and microphone while ringing; when unset,
+    /// capture stays off until the call is accepted
+    pub capture_before_accept: bool,
+}
+
+impl Default for SpeculativeMediaConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            capture_before_accept: false,
+        }
+    }
+}
+
 /// Call manager configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CallManagerConfig {
     /// Maximum concurrent calls
     pub max_concurrent_calls: usize,
+    /// Keepalive and dead-peer detection for QUIC calls
+    #[serde(default)]
+    pub keepalive: KeepaliveConfig,
+    /// Offer early media (audio before acceptance) in capability exchange
+    #[serde(default)]
+    pub early_media: bool,
+    /// Preparing media for outgoing calls while they ring
+    #[serde(default)]
+    pub speculative_media: SpeculativeMediaConfig,
+    /// Per-call statistics sampling and retention
+    #[serde(default)]
+    pub stats_history: StatsHistoryConfig,
+    /// Audio format offered in capability exchange
+    #[serde(default)]
+    pub audio: AudioParameters,
+    /// Append-only audit log of call lifecycle events
+    #[serde(default)]
+    pub audit: AuditConfig,
+    /// Call events buffered per subscriber before a lagging subscriber
+    /// loses the oldest
+    #[serde(default = "default_call_event_capacity")]
+    pub event_capacity: usize,
+    /// Latency profile of calls whose constraints do not set one
+    #[serde(default)]
+    pub latency: LatencyProfile,
+    /// Time source for call transports and statistics
+    #[serde(skip, default = "system_clock")]
+    pub clock: SharedClock,
+    /// Policy consulted before placing calls and letting calls ring
+    #[serde(skip)]
+    pub policy: Option<SharedCallPolicy>,
+    /// Storage for statistics histories; in memory, sized by
+    /// `stats_history`, when unset
+    #[serde(skip)]
+    pub history_store: Option<SharedHistoryStore>,
+    /// Sink for call lifecycle counters
+    #[serde(skip, default = "noop_metrics")]
+    pub metrics: SharedMetrics,
+    /// Suspending video on poor networks
+    #[serde(default)]
+    pub degradation: DegradationConfig,
+    /// Learning what each peer's path can carry to seed later calls
+    #[serde(default)]
+    pub peer_quality: PeerQualityConfig,
+    /// Video bitrate ramp and keyframe cadence at the start of calls
+    #[serde(default)]
+    pub ramp: RampConfig,
+    /// Audio in-band FEC following the measured loss
+    #[serde(default)]
+    pub fec: FecConfig,
 }
 
 impl Default for CallManagerConfig {
     fn default() -> Self {
         Self {
             max_concurrent_calls: 10,
+            keepalive: KeepaliveConfig::default(),
+            early_media: false,
+            speculative_media: SpeculativeMediaConfig::default(),
+            stats_history: StatsHistoryConfig::default(),
+            audio: AudioParameters::default(),
+            audit: AuditConfig::default(),
+            event_capacity: DEFAULT_CALL_EVENT_CAPACITY,
+            latency: LatencyProfile::default(),
+            clock: system_clock(),
+            policy: None,
+            history_store: None,
+            metrics: noop_metrics(),
+            degradation: DegradationConfig::default(),
+            peer_quality: PeerQualityConfig::default(),
+            ramp: RampConfig::default(),
+            fec: FecConfig::default(),
         }
     }
 }
 
+fn default_call_event_capacity() -> usize {
+    DEFAULT_CALL_EVENT_CAPACITY
+}
+
 /// Network adapter trait (placeholder for future implementation)
 pub trait NetworkAdapter: Send + Sync {}
 
-/// Active call with WebRTC peer connection
+/// Active call
 ///
 /// Supports both legacy WebRTC tracks and QUIC-native generic tracks.
-/// For new QUIC calls, use `quic_tracks` instead of `tracks`.
+/// For new QUIC calls, use `quic_tracks` instead of `tracks`. QUIC-native
+/// calls have no `peer_connection`.
 pub struct Call<I: PeerIdentity> {
     /// Call identifier
     pub id: CallId,
     /// Remote peer
     pub remote_peer: I,
-    /// WebRTC peer connection (legacy, will be removed in Phase 3.2)
-    pub peer_connection: Arc<RTCPeerConnection>,
+    /// Which side placed the call
+    pub direction: CallDirection,
+    /// WebRTC peer connection (legacy calls only)
+    #[cfg(feature = "legacy-webrtc")]
+    pub peer_connection: Option<Arc<RTCPeerConnection>>,
     /// QUIC-based media transport (Phase 3 migration)
     pub media_transport: Option<Arc<QuicMediaTransport>>,
     /// Current state
@@ -77,18 +291,68 @@ pub struct Call<I: PeerIdentity> {
     /// Media constraints
     pub constraints: MediaConstraints,
     /// WebRTC tracks for this call (legacy)
+    #[cfg(feature = "legacy-webrtc")]
     pub tracks: Vec<WebRtcTrack>,
     /// QUIC-backed generic tracks (new)
     pub quic_tracks: Vec<GenericTrack>,
+    /// Audio format agreed with the peer, once the connection is confirmed
+    pub audio_params: Option<AudioParameters>,
+    /// Metadata of the tracks we send, announced to the peer
+    pub local_tracks: Vec<TrackInfo>,
+    /// Metadata of the tracks the peer sends, from its last track update
+    pub remote_tracks: Vec<TrackInfo>,
+    /// Last progress the callee reported, on outgoing calls
+    pub progress: Option<CallProgress>,
+    /// We put the call on hold; media is paused until resumed
+    pub held: bool,
+    /// Metadata the application attached to the call
+    pub metadata: CallMetadata,
+    /// Network quality tracking behind the audio-only fallback
+    quality: QualityMonitor,
+    /// Audio in-band FEC strength following the measured loss
+    fec: FecController,
+    /// Progress of a handoff to or from another device, if any
+    handoff: Option<HandoffState<I>>,
+    /// Counts this call in the manager's resource gauges while alive
+    _resources: ResourceGuard,
 }
 
 impl<I: PeerIdentity> Call<I> {
     /// Check if this is a QUIC-native call
     #[must_use]
     pub fn is_quic_call(&self) -> bool {
+        #[cfg(feature = "legacy-webrtc")]
+        if self.peer_connection.is_some() {
+            return false;
+        }
         self.media_transport.is_some()
     }
 
+    /// Get the number of tracks on this call, legacy and QUIC
+    #[must_use]
+    pub fn track_count(&self) -> usize {
+        #[cfg(feature = "legacy-webrtc")]
+        let legacy = self.tracks.len();
+        #[cfg(not(feature = "legacy-webrtc"))]
+        let legacy = 0;
+        legacy + self.quic_tracks.len()
+    }
+
+    /// Get the legacy WebRTC peer connection
+    ///
+    /// # Errors
+    ///
+    /// Returns error if this is a QUIC-native call
+    #[cfg(feature = "legacy-webrtc")]
+    pub fn legacy_peer_connection(&self) -> Result<&Arc<RTCPeerConnection>, CallError> {
+        self.peer_connection.as_ref().ok_or_else(|| {
+            CallError::ConfigError(
+                "QUIC-native call has no WebRTC peer connection; use the capability exchange flow"
+                    .to_string(),
+            )
+        })
+    }
+
     /// Get all QUIC tracks
     #[must_use]
     pub fn get_quic_tracks(&self) -> &[GenericTrack] {
@@ -134,6 +398,55 @@ impl<I: PeerIdentity> Call<I> {
     }
 }
 
+/// Reason given when declining a call because we are on another
+pub const BUSY_REASON: &str = "busy";
+
+/// Most metadata keys a call can carry
+pub const MAX_METADATA_ENTRIES: usize = 32;
+
+/// Longest metadata key, in bytes
+pub const MAX_METADATA_KEY_LEN: usize = 64;
+
+/// Longest metadata value, in bytes
+pub const MAX_METADATA_VALUE_LEN: usize = 1024;
+
+/// Deltas buffered per stats subscription before sampling waits
+const STATS_DELTA_CAPACITY: usize = 16;
+
+/// Outcome of registering an incoming call offer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IncomingCallOutcome {
+    /// No glare: the incoming call was registered and is ringing
+    Ringing(CallId),
+    /// Glare: our outgoing call won and the incoming offer was discarded
+    OutgoingKept {
+        /// Our outgoing call, which continues
+        kept: CallId,
+        /// The discarded incoming call
+        cancelled: CallId,
+    },
+    /// Glare: the incoming call won and our outgoing call was cancelled
+    IncomingKept {
+        /// The incoming call, now ringing
+        kept: CallId,
+        /// Our cancelled outgoing call
+        cancelled: CallId,
+    },
+    /// Do-not-disturb was on: the call was not registered and does not
+    /// ring; with [`DndAction::Reject`] the caller should be told it was
+    /// declined
+    DoNotDisturb {
+        /// The incoming call
+        call_id: CallId,
+        /// What to do with the call
+        action: DndAction,
+    },
+}
+
+/// A call behind its own lock, so operations on different calls never
+/// contend with each other
+type CallEntry<I> = Arc<Mutex<Call<I>>>;
+
 /// Call manager
 ///
 /// Manages call lifecycle for both legacy WebRTC and QUIC-native calls.
@@ -166,6 +479,8 @@ impl<I: PeerIdentity> Call<I> {
 ///
 /// Key transitions:
 /// - `initiate_quic_call`: Idle → Connecting (transport connects immediately)
+/// - `handle_incoming_call`: registers an incoming call in Calling,
+///   resolving glare with an outgoing call to the same peer
 /// - `exchange_capabilities`: Calling → Connecting
 /// - `confirm_connection`: Connecting → Connected
 /// - `end_call`: Any → Ending → removed from manager
@@ -177,6 +492,12 @@ impl<I: PeerIdentity> Call<I> {
 /// - `initiate_call` + `create_offer` for SDP
 /// - `handle_answer` + `add_ice_candidate` for connection
 ///
+/// # Concurrency
+///
+/// Each call has its own lock. The call map is locked only to look up,
+/// insert or remove entries, so a slow operation on one call (e.g. SDP
+/// negotiation) never blocks operations on other calls.
+///
 /// # Type Safety
 ///
 /// All methods preserve the `I: PeerIdentity` type parameter, ensuring:
@@ -184,10 +505,14 @@ impl<I: PeerIdentity> Call<I> {
 /// - Remote peer information is type-safe throughout call lifecycle
 /// - No accidental mixing of different identity schemes
 pub struct CallManager<I: PeerIdentity> {
-    calls: Arc<RwLock<HashMap<CallId, Call<I>>>>,
-    event_sender: broadcast::Sender<CallEvent<I>>,
-    #[allow(dead_code)]
+    calls: Arc<RwLock<HashMap<CallId, CallEntry<I>>>>,
+    event_sender: EventSender<CallEvent<I>>,
     config: CallManagerConfig,
+    stats_history: SharedHistoryStore,
+    resources: Arc<ResourceGauges>,
+    audit: Option<Arc<AuditLog>>,
+    peer_quality: parking_lot::Mutex<PeerQualityCache>,
+    #[cfg(feature = "legacy-webrtc")]
     media_manager: Arc<RwLock<MediaStreamManager>>,
 }
 
@@ -198,16 +523,47 @@ impl<I: PeerIdentity> CallManager<I> {
     ///
     /// Returns error if initialization fails
     pub async fn new(config: CallManagerConfig) -> Result<Self, CallError> {
-        let (event_sender, _) = broadcast::channel(100);
-        let media_manager = Arc::new(RwLock::new(MediaStreamManager::new()));
+        let event_sender = EventSender::new(config.event_capacity)
+            .with_metrics(config.metrics.clone(), metrics::CALL_EVENTS_OVERFLOWED);
+        let audit = config
+            .audit
+            .path
+            .as_deref()
+            .map(|path| AuditLog::open(path, config.clock.clone()))
+            .transpose()
+            .map_err(|e| CallError::ConfigError(e.to_string()))?
+            .map(Arc::new);
+        let peer_quality = match &config.peer_quality.path {
+            Some(path) => {
+                PeerQualityCache::open(path).map_err(|e| CallError::ConfigError(e.to_string()))?
+            }
+            None => PeerQualityCache::new(),
+        };
         Ok(Self {
             calls: Arc::new(RwLock::new(HashMap::new())),
             event_sender,
+            stats_history: config.history_store.clone().unwrap_or_else(|| {
+                Arc::new(parking_lot::Mutex::new(StatsHistoryStore::new(
+                    config.stats_history,
+                )))
+            }),
+            resources: ResourceGauges::new(),
+            audit,
+            peer_quality: parking_lot::Mutex::new(peer_quality),
             config,
-            media_manager,
+            #[cfg(feature = "legacy-webrtc")]
+            media_manager: Arc::new(RwLock::new(MediaStreamManager::new())),
         })
     }
 
+    /// Create a call's media transport, reporting to this manager's clock
+    /// and gauges
+    fn new_transport(&self, call_id: CallId) -> QuicMediaTransport {
+        QuicMediaTransport::with_clock(self.config.clock.clone())
+            .with_resources(Arc::clone(&self.resources))
+            .with_call_id(call_id)
+    }
+
     /// Start the call manager
     ///
     /// # Errors
@@ -227,29 +583,79 @@ impl<I: PeerIdentity> CallManager<I> {
         callee: I,
         constraints: MediaConstraints,
     ) -> Result<CallId, CallError> {
-        // Enforce max_concurrent_calls limit
-        let calls = self.calls.read().await;
-        if calls.len() >= self.config.max_concurrent_calls {
-            return Err(CallError::ConfigError(format!(
-                "Maximum concurrent calls limit reached: {}",
-                self.config.max_concurrent_calls
-            )));
-        }
-        drop(calls);
-
         let call_id = CallId::new();
 
+        // Enforce max_concurrent_calls limit
+        self.check_call_limit(call_id, self.calls.read().await.len())?;
+        self.check_call_policy(call_id, CallDirection::Outgoing, &callee, &constraints)
+            .await?;
+
         tracing::info!(
-            "Initiating call {} to peer: {}",
+            "Initiating call {} ({}) to peer: {}",
             call_id,
-            callee.to_string_repr()
+            call_id.short_code(),
+            redact::identity(callee.to_string_repr())
         );
 
         // Create QUIC-based media transport (Phase 3 migration)
-        let media_transport = Arc::new(QuicMediaTransport::new());
+        let media_transport = Arc::new(self.new_transport(call_id));
+        media_transport.set_media_gate(MediaGate::Closed).await;
         tracing::debug!("Created QuicMediaTransport for call {}", call_id);
 
-        // Create WebRTC peer connection (legacy path, will be removed in Phase 3.2)
+        // Create WebRTC peer connection and tracks (legacy path)
+        #[cfg(feature = "legacy-webrtc")]
+        let (peer_connection, tracks) = self.create_legacy_session(call_id, &constraints).await?;
+
+        let call = Call {
+            id: call_id,
+            remote_peer: callee.clone(),
+            direction: CallDirection::Outgoing,
+            #[cfg(feature = "legacy-webrtc")]
+            peer_connection: Some(peer_connection),
+            media_transport: Some(media_transport),
+            state: CallState::Calling,
+            constraints: constraints.clone(),
+            #[cfg(feature = "legacy-webrtc")]
+            tracks,
+            quic_tracks: Vec::new(),
+            audio_params: None,
+            local_tracks: Vec::new(),
+            remote_tracks: Vec::new(),
+            progress: None,
+            held: false,
+            metadata: CallMetadata::new(),
+            quality: QualityMonitor::default(),
+            fec: FecController::new(),
+            handoff: None,
+            _resources: self.resources.track_call(),
+        };
+
+        self.insert_call(call).await?;
+
+        self.audit(
+            call_id,
+            AuditEvent::CallInitiated {
+                peer: callee.to_string_repr(),
+            },
+        );
+
+        // Emit call initiated event
+        let _ = self.event_sender.send(CallEvent::CallInitiated {
+            call_id,
+            callee,
+            constraints,
+        });
+
+        Ok(call_id)
+    }
+
+    /// Create the legacy WebRTC peer connection and tracks for a call
+    #[cfg(feature = "legacy-webrtc")]
+    async fn create_legacy_session(
+        &self,
+        call_id: CallId,
+        constraints: &MediaConstraints,
+    ) -> Result<(Arc<RTCPeerConnection>, Vec<WebRtcTrack>), CallError> {
         let peer_connection = Arc::new(
             webrtc::api::APIBuilder::new()
                 .build()
@@ -303,28 +709,336 @@ impl<I: PeerIdentity> CallManager<I> {
                 .map_err(|e| CallError::ConfigError(format!("Failed to add video track: {}", e)))?;
         }
 
+        Ok((peer_connection, tracks))
+    }
+
+    /// Register an incoming call offer, resolving glare
+    ///
+    /// If we have an outgoing call to the caller that has not connected yet,
+    /// both peers called each other at the same time. Each side keeps the
+    /// call with the greater [`CallId`], so both converge on the same call
+    /// without further signaling. The losing call is cancelled and
+    /// [`CallEvent::GlareResolved`] is emitted.
+    ///
+    /// A surviving incoming call is registered in the `Calling` state and
+    /// announced with [`CallEvent::IncomingCall`], or with
+    /// [`CallEvent::CallWaiting`] if we are already on a call.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the call ID is already in use, the concurrent call
+    /// limit is reached or the call policy denies the call
+    pub async fn handle_incoming_call(
+        &self,
+        offer: CallOffer<I>,
+    ) -> Result<IncomingCallOutcome, CallError> {
+        let incoming_id = offer.call_id;
+        if self.call_entry(incoming_id).await.is_some() {
+            return Err(CallError::ConfigError(format!(
+                "Call {} already exists",
+                incoming_id
+            )));
+        }
+        let constraints = MediaConstraints::from_media_types(&offer.media_types);
+        self.check_call_policy(
+            incoming_id,
+            CallDirection::Incoming,
+            &offer.caller,
+            &constraints,
+        )
+        .await?;
+
+        let mut outcome = IncomingCallOutcome::Ringing(incoming_id);
+        if let Some(outgoing_id) = self.find_glare(&offer.caller).await {
+            tracing::info!(
+                incoming = %incoming_id,
+                outgoing = %outgoing_id,
+                peer = %redact::identity(offer.caller.to_string_repr()),
+                "Glare detected"
+            );
+
+            if outgoing_id > incoming_id {
+                self.record_policy_decision(
+                    incoming_id,
+                    "glare",
+                    false,
+                    Some(format!("Outgoing call {outgoing_id} kept")),
+                );
+                let _ = self.event_sender.send(CallEvent::GlareResolved {
+                    cancelled: incoming_id,
+                    kept: outgoing_id,
+                });
+                return Ok(IncomingCallOutcome::OutgoingKept {
+                    kept: outgoing_id,
+                    cancelled: incoming_id,
+                });
+            }
+
+            let entry = self.calls.write().await.remove(&outgoing_id);
+            if let Some(entry) = entry {
+                self.release_call(&*entry.lock().await).await;
+            }
+            self.record_policy_decision(
+                outgoing_id,
+                "glare",
+                false,
+                Some(format!("Incoming call {incoming_id} kept")),
+            );
+            let _ = self.event_sender.send(CallEvent::GlareResolved {
+                cancelled: outgoing_id,
+                kept: incoming_id,
+            });
+            outcome = IncomingCallOutcome::IncomingKept {
+                kept: incoming_id,
+                cancelled: outgoing_id,
+            };
+        }
+
+        let active = self.active_call().await;
+        let media_transport = Arc::new(self.new_transport(incoming_id));
+        media_transport
+            .set_keepalive_config(self.config.keepalive)
+            .await;
+        media_transport.set_media_gate(MediaGate::Closed).await;
+
         let call = Call {
-            id: call_id,
-            remote_peer: callee.clone(),
-            peer_connection,
+            id: incoming_id,
+            remote_peer: offer.caller.clone(),
+            direction: CallDirection::Incoming,
+            #[cfg(feature = "legacy-webrtc")]
+            peer_connection: None,
             media_transport: Some(media_transport),
             state: CallState::Calling,
-            constraints: constraints.clone(),
-            tracks,
+            constraints,
+            #[cfg(feature = "legacy-webrtc")]
+            tracks: Vec::new(),
             quic_tracks: Vec::new(),
+            audio_params: None,
+            local_tracks: Vec::new(),
+            remote_tracks: Vec::new(),
+            progress: None,
+            held: false,
+            metadata: CallMetadata::new(),
+            quality: QualityMonitor::default(),
+            fec: FecController::new(),
+            handoff: None,
+            _resources: self.resources.track_call(),
         };
+        self.insert_call(call).await?;
 
-        let mut calls = self.calls.write().await;
-        calls.insert(call_id, call);
+        self.audit(
+            incoming_id,
+            AuditEvent::IncomingCall {
+                peer: offer.caller.to_string_repr(),
+            },
+        );
+        let event = match active {
+            Some(active) => {
+                tracing::info!(call_id = %incoming_id, active = %active, "Call waiting");
+                CallEvent::CallWaiting { offer, active }
+            }
+            None => CallEvent::IncomingCall { offer },
+        };
+        let _ = self.event_sender.send(event);
+        Ok(outcome)
+    }
 
-        // Emit call initiated event
-        let _ = self.event_sender.send(CallEvent::CallInitiated {
+    /// Get the connected call we are on, if any
+    ///
+    /// Calls on hold do not count.
+    pub async fn active_call(&self) -> Option<CallId> {
+        let entries: Vec<CallEntry<I>> = self.calls.read().await.values().cloned().collect();
+        for entry in entries {
+            let call = entry.lock().await;
+            if !call.held && matches!(call.state, CallState::Connected | CallState::Reconnecting) {
+                return Some(call.id);
+            }
+        }
+        None
+    }
+
+    /// Answer an incoming call, putting every call we are on on hold
+    ///
+    /// Returns the calls put on hold.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if call not found or it is not an unanswered incoming
+    /// call
+    pub async fn answer_waiting_call(
+        &self,
+        call_id: CallId,
+        constraints: MediaConstraints,
+    ) -> Result<Vec<CallId>, CallError> {
+        {
+            let entry = self
+                .call_entry(call_id)
+                .await
+                .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+            let call = entry.lock().await;
+            if call.direction != CallDirection::Incoming || call.state != CallState::Calling {
+                return Err(CallError::InvalidState);
+            }
+        }
+
+        let mut held = Vec::new();
+        while let Some(active) = self.active_call().await {
+            self.hold_call(active).await?;
+            held.push(active);
+        }
+        self.accept_call(call_id, constraints).await?;
+        Ok(held)
+    }
+
+    /// Decline an incoming call because we are busy
+    ///
+    /// Returns the [`SignalingMessage::Bye`] to send the caller, with
+    /// [`BUSY_REASON`] as the reason.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if call cannot be rejected
+    pub async fn reject_busy(&self, call_id: CallId) -> Result<SignalingMessage, CallError> {
+        self.reject_call(call_id).await?;
+        Ok(SignalingMessage::Bye {
+            session_id: call_id.to_string(),
+            reason: Some(BUSY_REASON.to_string()),
+        })
+    }
+
+    /// Put a connected call on hold, pausing its media
+    ///
+    /// Holding a call already on hold does nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if call not found or not connected
+    pub async fn hold_call(&self, call_id: CallId) -> Result<(), CallError> {
+        let entry = self
+            .call_entry(call_id)
+            .await
+            .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+        let mut call = entry.lock().await;
+        if !matches!(call.state, CallState::Connected | CallState::Reconnecting) {
+            return Err(CallError::InvalidState);
+        }
+        if call.held {
+            return Ok(());
+        }
+        call.held = true;
+        if let Some(ref transport) = call.media_transport {
+            transport.set_media_gate(MediaGate::Closed).await;
+        }
+        drop(call);
+
+        tracing::info!(call_id = %call_id, "Call on hold");
+        let _ = self.event_sender.send(CallEvent::CallHeld { call_id });
+        Ok(())
+    }
+
+    /// Take a call off hold, resuming its media
+    ///
+    /// Resuming a call that is not on hold does nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if call not found
+    pub async fn resume_call(&self, call_id: CallId) -> Result<(), CallError> {
+        let entry = self
+            .call_entry(call_id)
+            .await
+            .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+        let mut call = entry.lock().await;
+        if !call.held {
+            return Ok(());
+        }
+        call.held = false;
+        open_media_gate(&call).await;
+        drop(call);
+
+        tracing::info!(call_id = %call_id, "Call resumed");
+        let _ = self.event_sender.send(CallEvent::CallResumed { call_id });
+        Ok(())
+    }
+
+    /// Attach a metadata value to a call, replacing any value under `key`
+    ///
+    /// Metadata is carried in [`CallEvent::MetadataChanged`], the audit
+    /// log, the call's statistics history and its webhook notifications,
+    /// so e.g. a support ticket ID can follow the call everywhere.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if call not found, the key is empty, the key or value
+    /// is too long, or the call already has [`MAX_METADATA_ENTRIES`] keys
+    pub async fn set_call_metadata(
+        &self,
+        call_id: CallId,
+        key: &str,
+        value: &str,
+    ) -> Result<(), CallError> {
+        if key.is_empty() || key.len() > MAX_METADATA_KEY_LEN {
+            return Err(CallError::ConfigError(format!(
+                "Metadata keys must be 1 to {MAX_METADATA_KEY_LEN} bytes"
+            )));
+        }
+        if value.len() > MAX_METADATA_VALUE_LEN {
+            return Err(CallError::ConfigError(format!(
+                "Metadata values must be at most {MAX_METADATA_VALUE_LEN} bytes"
+            )));
+        }
+        let entry = self
+            .call_entry(call_id)
+            .await
+            .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+        let mut call = entry.lock().await;
+        if !call.metadata.contains_key(key) && call.metadata.len() >= MAX_METADATA_ENTRIES {
+            return Err(CallError::ConfigError(format!(
+                "Calls carry at most {MAX_METADATA_ENTRIES} metadata keys"
+            )));
+        }
+        call.metadata.insert(key.to_string(), value.to_string());
+        drop(call);
+
+        self.stats_history
+            .set_metadata(call_id, key.to_string(), value.to_string());
+        self.audit(
             call_id,
-            callee,
-            constraints,
+            AuditEvent::MetadataSet {
+                key: key.to_string(),
+                value: value.to_string(),
+            },
+        );
+        let _ = self.event_sender.send(CallEvent::MetadataChanged {
+            call_id,
+            key: key.to_string(),
+            value: value.to_string(),
         });
+        Ok(())
+    }
 
-        Ok(call_id)
+    /// Get the metadata attached to a call
+    ///
+    /// Returns `None` if the call does not exist.
+    pub async fn call_metadata(&self, call_id: CallId) -> Option<CallMetadata> {
+        let entry = self.call_entry(call_id).await?;
+        let metadata = entry.lock().await.metadata.clone();
+        Some(metadata)
+    }
+
+    /// Find an unconnected outgoing call to a peer
+    async fn find_glare(&self, peer: &I) -> Option<CallId> {
+        let entries: Vec<CallEntry<I>> = self.calls.read().await.values().cloned().collect();
+        for entry in entries {
+            let call = entry.lock().await;
+            if call.direction == CallDirection::Outgoing
+                && matches!(call.state, CallState::Calling | CallState::Connecting)
+                && call.remote_peer.unique_id() == peer.unique_id()
+            {
+                return Some(call.id);
+            }
+        }
+        None
     }
 
     /// Accept a call
@@ -337,13 +1051,14 @@ impl<I: PeerIdentity> CallManager<I> {
         call_id: CallId,
         _constraints: MediaConstraints,
     ) -> Result<(), CallError> {
-        let mut calls = self.calls.write().await;
-        if let Some(call) = calls.get_mut(&call_id) {
+        if let Some(entry) = self.call_entry(call_id).await {
+            let mut call = entry.lock().await;
             // Validate state transition
             match call.state {
                 CallState::Calling | CallState::Connecting => {
                     let old_state = call.state;
                     call.state = CallState::Connected;
+                    open_media_gate(&call).await;
                     tracing::debug!(
                         call_id = %call_id,
                         old_state = ?old_state,
@@ -351,6 +1066,8 @@ impl<I: PeerIdentity> CallManager<I> {
                         "Call state transition"
                     );
 
+                    self.audit(call_id, AuditEvent::CallAccepted);
+
                     // Emit connection established event
                     let _ = self
                         .event_sender
@@ -380,8 +1097,8 @@ impl<I: PeerIdentity> CallManager<I> {
     ///
     /// Returns error if call cannot be rejected
     pub async fn reject_call(&self, call_id: CallId) -> Result<(), CallError> {
-        let mut calls = self.calls.write().await;
-        if let Some(call) = calls.get_mut(&call_id) {
+        if let Some(entry) = self.call_entry(call_id).await {
+            let mut call = entry.lock().await;
             // Validate state transition - can only reject calls that are not yet connected/ended
             match call.state {
                 CallState::Calling | CallState::Connecting => {
@@ -394,6 +1111,8 @@ impl<I: PeerIdentity> CallManager<I> {
                         "Call state transition"
                     );
 
+                    self.audit(call_id, AuditEvent::CallRejected);
+
                     // Emit call rejected event
                     let _ = self.event_sender.send(CallEvent::CallRejected { call_id });
 
@@ -413,57 +1132,279 @@ impl<I: PeerIdentity> CallManager<I> {
         }
     }
 
-    /// End a call
+    /// Stop ringing an incoming call the caller cancelled
+    ///
+    /// Callers that ring several of our devices cancel the call on every
+    /// device but the one that answered. The call is removed and
+    /// [`CallEvent::CallCancelled`] is emitted.
     ///
     /// # Errors
     ///
-    /// Returns error if call cannot be ended
-    pub async fn end_call(&self, call_id: CallId) -> Result<(), CallError> {
-        let mut calls = self.calls.write().await;
-        if let Some(call) = calls.remove(&call_id) {
-            // Remove all tracks associated with this call from media manager
-            let mut media_manager = self.media_manager.write().await;
-            for track in &call.tracks {
-                media_manager.remove_track(&track.id);
-            }
-            drop(media_manager);
-
-            // Disconnect QuicMediaTransport if present (Phase 3 path)
-            if let Some(ref transport) = call.media_transport {
-                if let Err(e) = transport.disconnect().await {
-                    tracing::warn!(
-                        "Failed to disconnect QuicMediaTransport for call {}: {}",
-                        call_id,
-                        e
-                    );
-                    // Continue cleanup even if disconnect fails
-                } else {
-                    tracing::debug!("QuicMediaTransport disconnected for call {}", call_id);
-                }
+    /// Returns error if call not found or it is not an unanswered incoming
+    /// call
+    pub async fn cancel_incoming_call(
+        &self,
+        call_id: CallId,
+        reason: Option<String>,
+    ) -> Result<(), CallError> {
+        let entry = self
+            .call_entry(call_id)
+            .await
+            .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+        {
+            let call = entry.lock().await;
+            if call.direction != CallDirection::Incoming || call.state != CallState::Calling {
+                return Err(CallError::InvalidState);
             }
-
-            // Close the peer connection (legacy path)
-            let _ = call.peer_connection.close().await;
-
-            // Emit call ended event
-            let _ = self.event_sender.send(CallEvent::CallEnded { call_id });
-
-            tracing::info!(
-                "Ended call {} and cleaned up {} tracks",
-                call_id,
-                call.tracks.len()
-            );
-            Ok(())
-        } else {
-            Err(CallError::CallNotFound(call_id.to_string()))
         }
-    }
 
-    /// Get call state
-    #[must_use]
+        self.calls.write().await.remove(&call_id);
+        self.release_call(&*entry.lock().await).await;
+        self.audit(call_id, AuditEvent::CallEnded);
+        tracing::info!(call_id = %call_id, reason = ?reason, "Incoming call cancelled");
+        let _ = self
+            .event_sender
+            .send(CallEvent::CallCancelled { call_id, reason });
+        Ok(())
+    }
+
+    /// Feed a call's network quality to the audio-only fallback
+    ///
+    /// Emits [`CallEvent::QualityChanged`]. The loss rate also sets the
+    /// strength of the audio's in-band FEC (see [`FecConfig`]), announced
+    /// with [`CallEvent::AudioFecChanged`] when it changes. When the network has stayed poor
+    /// long enough (see [`DegradationConfig`]) outgoing video is suspended
+    /// and [`CallEvent::VideoSuspended`] emitted; once it has recovered
+    /// video resumes with [`CallEvent::VideoResumed`]. Either way the
+    /// returned [`SignalingMessage::AudioOnly`] tells the peer. Calls
+    /// without video are never degraded.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if call not found or not connected
+    pub async fn report_quality(
+        &self,
+        call_id: CallId,
+        metrics: CallQualityMetrics,
+    ) -> Result<Option<SignalingMessage>, CallError> {
+        let entry = self
+            .call_entry(call_id)
+            .await
+            .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+        let mut call = entry.lock().await;
+        if !matches!(call.state, CallState::Connected | CallState::Reconnecting) {
+            return Err(CallError::InvalidState);
+        }
+        let transition = if call.constraints.video {
+            call.quality.evaluate(&self.config.degradation, &metrics)
+        } else {
+            None
+        };
+        if transition.is_some() {
+            open_media_gate(&call).await;
+        }
+        let fec = if call.constraints.audio {
+            call.fec
+                .update(&self.config.fec, metrics.packet_loss_percent)
+        } else {
+            None
+        };
+        if let (Some(level), Some(transport)) = (fec, &call.media_transport) {
+            transport.set_audio_fec(level).await;
+        }
+        drop(call);
+
+        let _ = self
+            .event_sender
+            .send(CallEvent::QualityChanged { call_id, metrics });
+        if let Some(expected_loss_percent) = fec {
+            tracing::debug!(call_id = %call_id, expected_loss_percent, "Audio FEC adjusted");
+            let _ = self.event_sender.send(CallEvent::AudioFecChanged {
+                call_id,
+                expected_loss_percent,
+            });
+        }
+        let (event, active) = match transition {
+            Some(QualityTransition::Degraded(reason)) => {
+                tracing::warn!(call_id = %call_id, reason = ?reason, "Suspending video on poor network");
+                (CallEvent::VideoSuspended { call_id, reason }, true)
+            }
+            Some(QualityTransition::Recovered) => {
+                tracing::info!(call_id = %call_id, "Resuming video after network recovered");
+                (CallEvent::VideoResumed { call_id }, false)
+            }
+            None => return Ok(None),
+        };
+        let _ = self.event_sender.send(event);
+        Ok(Some(SignalingMessage::AudioOnly {
+            session_id: call_id.to_string(),
+            active,
+        }))
+    }
+
+    /// Handle the peer suspending or resuming its video
+    ///
+    /// Emits [`CallEvent::PeerAudioOnly`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error if call not found
+    pub async fn handle_audio_only(&self, call_id: CallId, active: bool) -> Result<(), CallError> {
+        if self.call_entry(call_id).await.is_none() {
+            return Err(CallError::CallNotFound(call_id.to_string()));
+        }
+        tracing::debug!(call_id = %call_id, active, "Peer audio-only");
+        let _ = self
+            .event_sender
+            .send(CallEvent::PeerAudioOnly { call_id, active });
+        Ok(())
+    }
+
+    /// Build a progress message for the caller of a ringing call
+    ///
+    /// Send [`CallProgress::Ringing`] once the user is alerted, so the caller
+    /// plays ringback, or [`CallProgress::EarlyMedia`] once early media
+    /// flows (see [`Self::negotiate_early_media`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if call not found or it is not an unanswered incoming
+    /// call
+    pub async fn report_progress(
+        &self,
+        call_id: CallId,
+        progress: CallProgress,
+    ) -> Result<SignalingMessage, CallError> {
+        let entry = self
+            .call_entry(call_id)
+            .await
+            .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+        let call = entry.lock().await;
+        if call.direction != CallDirection::Incoming || call.state != CallState::Calling {
+            return Err(CallError::InvalidState);
+        }
+        Ok(SignalingMessage::Progress {
+            session_id: call_id.to_string(),
+            progress,
+        })
+    }
+
+    /// Handle progress reported by the callee of an outgoing call
+    ///
+    /// Emits [`CallEvent::CallProgress`] when the progress changes. Reports
+    /// arriving after the call was answered are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if call not found or it is not an outgoing call
+    pub async fn handle_progress(
+        &self,
+        call_id: CallId,
+        progress: CallProgress,
+    ) -> Result<(), CallError> {
+        let entry = self
+            .call_entry(call_id)
+            .await
+            .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+        let mut call = entry.lock().await;
+        if call.direction != CallDirection::Outgoing {
+            return Err(CallError::InvalidState);
+        }
+        if !matches!(call.state, CallState::Calling | CallState::Connecting)
+            || call.progress == Some(progress)
+        {
+            return Ok(());
+        }
+        call.progress = Some(progress);
+        drop(call);
+
+        tracing::debug!(call_id = %call_id, progress = ?progress, "Call progress");
+        let _ = self
+            .event_sender
+            .send(CallEvent::CallProgress { call_id, progress });
+        Ok(())
+    }
+
+    /// Last progress the callee reported on an outgoing call
+    pub async fn call_progress(&self, call_id: CallId) -> Option<CallProgress> {
+        let entry = self.call_entry(call_id).await?;
+        let call = entry.lock().await;
+        call.progress
+    }
+
+    /// End a call
+    ///
+    /// # Errors
+    ///
+    /// Returns error if call cannot be ended
+    pub async fn end_call(&self, call_id: CallId) -> Result<(), CallError> {
+        let entry = self.calls.write().await.remove(&call_id);
+        if let Some(entry) = entry {
+            let call = entry.lock().await;
+            self.release_call(&call).await;
+            self.learn_peer_quality(&call);
+
+            self.audit(call_id, AuditEvent::CallEnded);
+
+            // Emit call ended event
+            let _ = self.event_sender.send(CallEvent::CallEnded { call_id });
+
+            tracing::info!(
+                "Ended call {} and cleaned up {} tracks",
+                call_id,
+                call.track_count()
+            );
+            Ok(())
+        } else {
+            Err(CallError::CallNotFound(call_id.to_string()))
+        }
+    }
+
+    /// Release the media resources of a call removed from the manager
+    async fn release_call(&self, call: &Call<I>) {
+        let call_id = call.id;
+
+        // Remove all tracks associated with this call from media manager
+        #[cfg(feature = "legacy-webrtc")]
+        {
+            let mut media_manager = self.media_manager.write().await;
+            for track in &call.tracks {
+                media_manager.remove_track(&track.id);
+            }
+        }
+
+        // Disconnect QuicMediaTransport if present (Phase 3 path)
+        if let Some(ref transport) = call.media_transport {
+            if let Err(e) = transport.disconnect().await {
+                tracing::warn!(
+                    "Failed to disconnect QuicMediaTransport for call {}: {}",
+                    call_id,
+                    e
+                );
+                // Continue cleanup even if disconnect fails
+            } else {
+                tracing::debug!("QuicMediaTransport disconnected for call {}", call_id);
+            }
+        }
+
+        // Close the peer connection (legacy path)
+        #[cfg(feature = "legacy-webrtc")]
+        if let Some(ref peer_connection) = call.peer_connection {
+            let _ = peer_connection.close().await;
+        }
+    }
+
+    /// Number of calls currently held by the manager
+    pub async fn active_call_count(&self) -> usize {
+        self.calls.read().await.len()
+    }
+
+    /// Get call state
+    #[must_use]
     pub async fn get_call_state(&self, call_id: CallId) -> Option<CallState> {
-        let calls = self.calls.read().await;
-        calls.get(&call_id).map(|call| call.state)
+        let entry = self.call_entry(call_id).await?;
+        let state = entry.lock().await.state;
+        Some(state)
     }
 
     /// Create SDP offer for a call (legacy WebRTC only)
@@ -479,20 +1420,22 @@ impl<I: PeerIdentity> CallManager<I> {
     /// # Errors
     ///
     /// Returns error if offer cannot be created
+    #[cfg(feature = "legacy-webrtc")]
     #[deprecated(
         since = "0.3.0",
         note = "Use QUIC-native call flow (exchange_capabilities) instead. SDP is only for legacy WebRTC calls."
     )]
     #[tracing::instrument(skip(self), fields(call_id = %call_id))]
     pub async fn create_offer(&self, call_id: CallId) -> Result<String, CallError> {
-        let calls = self.calls.read().await;
-        if let Some(call) = calls.get(&call_id) {
+        if let Some(entry) = self.call_entry(call_id).await {
+            let call = entry.lock().await;
+            let peer_connection = call.legacy_peer_connection()?;
             tracing::debug!("Creating SDP offer");
-            let offer = call.peer_connection.create_offer(None).await.map_err(|e| {
+            let offer = peer_connection.create_offer(None).await.map_err(|e| {
                 tracing::error!("Failed to create offer: {}", e);
                 CallError::ConfigError(format!("Failed to create offer: {}", e))
             })?;
-            call.peer_connection
+            peer_connection
                 .set_local_description(offer.clone())
                 .await
                 .map_err(|e| {
@@ -520,6 +1463,7 @@ impl<I: PeerIdentity> CallManager<I> {
     /// # Errors
     ///
     /// Returns error if answer cannot be handled
+    #[cfg(feature = "legacy-webrtc")]
     #[deprecated(
         since = "0.3.0",
         note = "Use QUIC-native call flow (confirm_connection) instead. SDP is only for legacy WebRTC calls."
@@ -528,8 +1472,8 @@ impl<I: PeerIdentity> CallManager<I> {
     pub async fn handle_answer(&self, call_id: CallId, sdp: String) -> Result<(), CallError> {
         tracing::debug!("Processing SDP answer");
 
-        let calls = self.calls.read().await;
-        if let Some(call) = calls.get(&call_id) {
+        if let Some(entry) = self.call_entry(call_id).await {
+            let call = entry.lock().await;
             // Validate SDP is not empty
             if sdp.trim().is_empty() {
                 return Err(CallError::ConfigError(
@@ -543,7 +1487,7 @@ impl<I: PeerIdentity> CallManager<I> {
                 )
                 .map_err(|e| CallError::ConfigError(format!("Invalid SDP answer: {}", e)))?;
 
-            call.peer_connection
+            call.legacy_peer_connection()?
                 .set_remote_description(answer)
                 .await
                 .map_err(|e| {
@@ -568,6 +1512,7 @@ impl<I: PeerIdentity> CallManager<I> {
     /// # Errors
     ///
     /// Returns error if candidate cannot be added
+    #[cfg(feature = "legacy-webrtc")]
     #[deprecated(
         since = "0.3.0",
         note = "Use QUIC-native call flow (exchange_capabilities/confirm_connection) instead. ICE is only for legacy WebRTC calls."
@@ -580,13 +1525,13 @@ impl<I: PeerIdentity> CallManager<I> {
     ) -> Result<(), CallError> {
         tracing::trace!("Adding ICE candidate (legacy WebRTC)");
 
-        let calls = self.calls.read().await;
-        if let Some(call) = calls.get(&call_id) {
+        if let Some(entry) = self.call_entry(call_id).await {
+            let call = entry.lock().await;
             let rtc_candidate = webrtc::ice_transport::ice_candidate::RTCIceCandidateInit {
                 candidate,
                 ..Default::default()
             };
-            call.peer_connection
+            call.legacy_peer_connection()?
                 .add_ice_candidate(rtc_candidate)
                 .await
                 .map_err(|e| {
@@ -611,13 +1556,13 @@ impl<I: PeerIdentity> CallManager<I> {
     /// # Errors
     ///
     /// Returns error if gathering cannot be started
+    #[cfg(feature = "legacy-webrtc")]
     #[deprecated(
         since = "0.3.0",
         note = "Use QUIC-native call flow (exchange_capabilities/confirm_connection) instead. ICE is only for legacy WebRTC calls."
     )]
     pub async fn start_ice_gathering(&self, call_id: CallId) -> Result<(), CallError> {
-        let calls = self.calls.read().await;
-        if let Some(_call) = calls.get(&call_id) {
+        if self.call_entry(call_id).await.is_some() {
             // ICE gathering is typically started automatically when creating offer
             // For now, this is a no-op as gathering happens during offer creation
             Ok(())
@@ -649,10 +1594,11 @@ impl<I: PeerIdentity> CallManager<I> {
         &self,
         call_id: CallId,
     ) -> Result<MediaCapabilities, CallError> {
-        let mut calls = self.calls.write().await;
-        let call = calls
-            .get_mut(&call_id)
+        let entry = self
+            .call_entry(call_id)
+            .await
             .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+        let mut call = entry.lock().await;
 
         // Validate call is in a state where capability exchange is valid
         match call.state {
@@ -679,7 +1625,14 @@ impl<I: PeerIdentity> CallManager<I> {
         }
 
         // Generate capabilities from call constraints
-        let capabilities = MediaCapabilities::from_constraints(&call.constraints);
+        let mut capabilities = MediaCapabilities::from_constraints(&call.constraints)
+            .with_audio_params(self.config.audio);
+        if self.config.early_media && call.constraints.audio {
+            capabilities = capabilities.with_extension(MediaCapabilities::EXT_EARLY_MEDIA, "1");
+        }
+        if self.config.speculative_media.enabled && call.direction == CallDirection::Outgoing {
+            self.prepare_media(&call).await;
+        }
 
         tracing::info!(
             call_id = %call_id,
@@ -693,6 +1646,53 @@ impl<I: PeerIdentity> CallManager<I> {
         Ok(capabilities)
     }
 
+    /// Enable early media for a call if both peers support it
+    ///
+    /// Early media lets audio such as ringback tones or announcements flow
+    /// while the call is still ringing. It is used only when we offer it
+    /// (see [`CallManagerConfig::early_media`]) and the peer advertises
+    /// [`MediaCapabilities::EXT_EARLY_MEDIA`]. Video stays blocked until the
+    /// call is accepted.
+    ///
+    /// # Returns
+    ///
+    /// `true` if early media is now enabled for the call.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the call is not found or has already been accepted.
+    #[tracing::instrument(skip(self, peer_capabilities), fields(call_id = %call_id))]
+    pub async fn negotiate_early_media(
+        &self,
+        call_id: CallId,
+        peer_capabilities: &MediaCapabilities,
+    ) -> Result<bool, CallError> {
+        let entry = self
+            .call_entry(call_id)
+            .await
+            .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+        let call = entry.lock().await;
+
+        if !matches!(call.state, CallState::Calling | CallState::Connecting) {
+            return Err(CallError::InvalidState);
+        }
+
+        let enabled = self.config.early_media
+            && call.constraints.audio
+            && peer_capabilities.supports_extension(MediaCapabilities::EXT_EARLY_MEDIA);
+        if let Some(ref transport) = call.media_transport {
+            let gate = if enabled {
+                MediaGate::EarlyMedia
+            } else {
+                MediaGate::Closed
+            };
+            transport.set_media_gate(gate).await;
+        }
+
+        tracing::debug!(call_id = %call_id, enabled, "Early media negotiated");
+        Ok(enabled)
+    }
+
     /// Confirm peer capabilities and activate connection (QUIC-native)
     ///
     /// Called after exchanging capabilities. Verifies peer capabilities
@@ -719,10 +1719,11 @@ impl<I: PeerIdentity> CallManager<I> {
         call_id: CallId,
         peer_capabilities: MediaCapabilities,
     ) -> Result<(), CallError> {
-        let mut calls = self.calls.write().await;
-        let call = calls
-            .get_mut(&call_id)
+        let entry = self
+            .call_entry(call_id)
+            .await
             .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+        let mut call = entry.lock().await;
 
         // Validate call is in Connecting state
         if call.state != CallState::Connecting {
@@ -745,6 +1746,7 @@ impl<I: PeerIdentity> CallManager<I> {
                 error = %e,
                 "Peer capabilities do not satisfy call constraints"
             );
+            self.record_policy_decision(call_id, "capabilities", false, Some(e.to_string()));
             return Err(e);
         }
 
@@ -757,9 +1759,7 @@ impl<I: PeerIdentity> CallManager<I> {
                     transport_state = ?transport_state,
                     "Transport is not connected"
                 );
-                return Err(CallError::TransportError(
-                    "Transport is not connected".to_string(),
-                ));
+                return Err(CallError::media(call_id, MediaTransportError::NotConnected));
             }
         } else {
             return Err(CallError::ConfigError(
@@ -769,11 +1769,23 @@ impl<I: PeerIdentity> CallManager<I> {
 
         // Update call state to Connected
         call.state = CallState::Connected;
+        let mut audio_params = self.config.audio.negotiate(&peer_capabilities.audio_params);
+        // A path known to carry less than the audio bitrate starts within it
+        if let Some(seed) = self.seed_for(&call.remote_peer) {
+            audio_params.target_bitrate_bps = audio_params
+                .target_bitrate_bps
+                .min(seed.bitrate_kbps.saturating_mul(1000))
+                .max(AudioParameters::MIN_BITRATE_BPS);
+        }
+        call.audio_params = Some(audio_params);
+        open_media_gate(&call).await;
         tracing::debug!(
             call_id = %call_id,
             "Call state transition: Connecting -> Connected"
         );
 
+        self.audit(call_id, AuditEvent::CallAccepted);
+
         // Emit ConnectionEstablished event
         let _ = self
             .event_sender
@@ -783,459 +1795,2900 @@ impl<I: PeerIdentity> CallManager<I> {
             call_id = %call_id,
             peer_audio = peer_capabilities.audio,
             peer_video = peer_capabilities.video,
+            audio_channels = audio_params.channels,
+            audio_sample_rate = audio_params.max_sample_rate_hz,
             "Connection confirmed"
         );
 
         Ok(())
     }
 
-    /// Validate remote capabilities against call constraints
-    ///
-    /// Checks whether the remote peer's capabilities satisfy the call's
-    /// media requirements. This is used during connection confirmation
-    /// to ensure both peers can support the required media types.
-    ///
-    /// # Validation Rules
-    ///
-    /// - If audio is required by constraints, remote must support audio
-    /// - If video is required by constraints, remote must support video
-    /// - If screen share is required, remote must support video
-    /// - Bandwidth requirements are considered but not strictly enforced
-    ///
-    /// # Arguments
+    /// Describe a track we send on a call
     ///
-    /// * `constraints` - The local call constraints
-    /// * `remote_caps` - The remote peer's capabilities
+    /// Replaces any earlier description of the same track. The description
+    /// reaches the peer with the next [`Self::track_update`].
     ///
     /// # Errors
     ///
-    /// Returns error describing which capability is missing or incompatible.
-    pub fn validate_remote_capabilities(
-        constraints: &MediaConstraints,
-        remote_caps: &MediaCapabilities,
-    ) -> Result<(), CallError> {
-        // Check audio requirement
-        if constraints.audio && !remote_caps.audio {
-            return Err(CallError::ConfigError(
-                "Remote peer does not support audio".to_string(),
-            ));
-        }
-
-        // Check video requirement (video or screen share)
-        if (constraints.video || constraints.screen_share) && !remote_caps.video {
-            return Err(CallError::ConfigError(
-                "Remote peer does not support video".to_string(),
-            ));
+    /// Returns error if call not found
+    pub async fn describe_track(&self, call_id: CallId, track: TrackInfo) -> Result<(), CallError> {
+        let entry = self
+            .call_entry(call_id)
+            .await
+            .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+        let mut call = entry.lock().await;
+        let key = track.stream_key();
+        match call.local_tracks.iter_mut().find(|t| t.stream_key() == key) {
+            Some(existing) => *existing = track,
+            None => call.local_tracks.push(track),
         }
-
-        // Note: Bandwidth is advisory, not strictly enforced
-        // In the future, we could add bandwidth negotiation logic here
-
         Ok(())
     }
 
-    /// Subscribe to call events
-    #[must_use]
-    pub fn subscribe_events(&self) -> broadcast::Receiver<CallEvent<I>> {
-        self.event_sender.subscribe()
+    /// Stop describing a track we no longer send
+    ///
+    /// # Returns
+    ///
+    /// Whether the track was described.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if call not found
+    pub async fn withdraw_track(&self, call_id: CallId, key: StreamKey) -> Result<bool, CallError> {
+        let entry = self
+            .call_entry(call_id)
+            .await
+            .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+        let mut call = entry.lock().await;
+        let before = call.local_tracks.len();
+        call.local_tracks.retain(|t| t.stream_key() != key);
+        Ok(call.local_tracks.len() != before)
     }
 
-    /// Check if a call has a QUIC media transport
+    /// Build the track update announcing our tracks to the peer
     ///
-    /// Returns `true` if the call has an associated `QuicMediaTransport`.
-    #[must_use]
-    pub async fn has_media_transport(&self, call_id: CallId) -> bool {
-        let calls = self.calls.read().await;
-        calls
-            .get(&call_id)
-            .is_some_and(|call| call.media_transport.is_some())
+    /// Send it after the capability exchange and again after describing
+    /// or withdrawing tracks.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if call not found
+    pub async fn track_update(&self, call_id: CallId) -> Result<SignalingMessage, CallError> {
+        let entry = self
+            .call_entry(call_id)
+            .await
+            .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+        let call = entry.lock().await;
+        Ok(SignalingMessage::TrackUpdate {
+            session_id: call_id.to_string(),
+            tracks: call.local_tracks.clone(),
+        })
     }
 
-    /// Initiate a QUIC-native call (bypasses SDP/ICE)
+    /// Handle a track update from the peer
     ///
-    /// Creates a call using only QuicMediaTransport, without creating a
-    /// legacy RTCPeerConnection. This is the preferred method for new
-    /// QUIC-native calls.
+    /// Replaces the peer's track metadata and emits
+    /// [`CallEvent::RemoteTrackMetadata`], followed by
+    /// [`CallEvent::RemoteTrackRemoved`] for each track no longer listed and
+    /// [`CallEvent::RemoteTrackAdded`] for each new one. Receivers of removed
+    /// tracks are ended.
     ///
-    /// # Arguments
+    /// # Errors
     ///
-    /// * `callee` - The remote peer identity
-    /// * `constraints` - Media constraints for the call
-    /// * `peer` - The QUIC peer connection to use for transport
+    /// Returns error if call not found
+    pub async fn handle_track_update(
+        &self,
+        call_id: CallId,
+        tracks: Vec<TrackInfo>,
+    ) -> Result<(), CallError> {
+        let entry = self
+            .call_entry(call_id)
+            .await
+            .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+        let (previous, transport) = {
+            let mut call = entry.lock().await;
+            let previous = std::mem::replace(&mut call.remote_tracks, tracks.clone());
+            (previous, call.media_transport.clone())
+        };
+        let listed = |list: &[TrackInfo], track: &TrackInfo| {
+            list.iter().any(|t| t.stream_key() == track.stream_key())
+        };
+        let removed: Vec<TrackInfo> = previous
+            .iter()
+            .filter(|t| !listed(&tracks, t))
+            .cloned()
+            .collect();
+        let added: Vec<TrackInfo> = tracks
+            .iter()
+            .filter(|t| !listed(&previous, t))
+            .cloned()
+            .collect();
+        tracing::debug!(
+            call_id = %call_id,
+            tracks = tracks.len(),
+            added = added.len(),
+            removed = removed.len(),
+            "Received remote track metadata"
+        );
+
+        let _ = self
+            .event_sender
+            .send(CallEvent::RemoteTrackMetadata { call_id, tracks });
+        for track in removed {
+            if let Some(ref transport) = transport {
+                transport.close_track_receivers(track.stream_key());
+            }
+            let _ = self
+                .event_sender
+                .send(CallEvent::RemoteTrackRemoved { call_id, track });
+        }
+        for track in added {
+            let _ = self
+                .event_sender
+                .send(CallEvent::RemoteTrackAdded { call_id, track });
+        }
+        Ok(())
+    }
+
+    /// Choose the highest layer to receive of one of the peer's video tracks
+    ///
+    /// For simulcast and SVC tracks: the peer stops sending the layers above
+    /// `layer`, saving bandwidth when the video is shown small. Send the
+    /// returned message to the peer.
     ///
     /// # Errors
     ///
-    /// Returns error if call cannot be initiated or transport connection fails.
-    pub async fn initiate_quic_call(
+    /// Returns error if call not found or the peer's last track update
+    /// lists no such video track
+    pub async fn select_layer(
         &self,
-        callee: I,
-        constraints: MediaConstraints,
-        peer: PeerConnection,
-    ) -> Result<CallId, CallError> {
-        // Enforce max_concurrent_calls limit
-        let calls = self.calls.read().await;
-        if calls.len() >= self.config.max_concurrent_calls {
+        call_id: CallId,
+        track_id: TrackId,
+        layer: VideoLayer,
+    ) -> Result<SignalingMessage, CallError> {
+        let entry = self
+            .call_entry(call_id)
+            .await
+            .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+        let call = entry.lock().await;
+        if !has_video_track(&call.remote_tracks, track_id) {
             return Err(CallError::ConfigError(format!(
-                "Maximum concurrent calls limit reached: {}",
-                self.config.max_concurrent_calls
+                "Peer sends no video track {track_id}"
             )));
         }
-        drop(calls);
+        Ok(SignalingMessage::LayerSelection {
+            session_id: call_id.to_string(),
+            track_id,
+            layer,
+        })
+    }
 
-        let call_id = CallId::new();
-
-        tracing::info!(
-            "Initiating QUIC call {} to peer: {}",
-            call_id,
-            callee.to_string_repr()
-        );
-
-        // Create and connect QUIC-based media transport
-        let media_transport = Arc::new(QuicMediaTransport::new());
-        media_transport.connect(peer).await?;
-        tracing::debug!("QuicMediaTransport connected for call {}", call_id);
-
-        // Create a placeholder peer connection (required for legacy compatibility)
-        // This will be removed in Phase 3.2
-        let peer_connection = Arc::new(
-            webrtc::api::APIBuilder::new()
-                .build()
-                .new_peer_connection(
-                    webrtc::peer_connection::configuration::RTCConfiguration::default(),
-                )
-                .await
-                .map_err(|e| {
-                    tracing::error!(
-                        "Failed to create peer connection for call {}: {}",
-                        call_id,
-                        e
-                    );
-                    CallError::ConfigError(format!("Failed to create peer connection: {}", e))
-                })?,
+    /// Handle the peer choosing layers of one of our video tracks
+    ///
+    /// Emits [`CallEvent::LayerSelected`] for the application to apply to
+    /// the track.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if call not found or we describe no such video track
+    pub async fn handle_layer_selection(
+        &self,
+        call_id: CallId,
+        track_id: TrackId,
+        layer: VideoLayer,
+    ) -> Result<(), CallError> {
+        let entry = self
+            .call_entry(call_id)
+            .await
+            .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+        if !has_video_track(&entry.lock().await.local_tracks, track_id) {
+            return Err(CallError::ConfigError(format!(
+                "No local video track {track_id}"
+            )));
+        }
+        tracing::debug!(
+            call_id = %call_id,
+            track_id,
+            spatial = layer.spatial,
+            temporal = layer.temporal,
+            "Peer selected video layer"
         );
-
-        let call = Call {
-            id: call_id,
-            remote_peer: callee.clone(),
-            peer_connection,
-            media_transport: Some(media_transport),
-            state: CallState::Connecting,
-            constraints: constraints.clone(),
-            tracks: Vec::new(),      // QUIC calls don't use WebRTC tracks
-            quic_tracks: Vec::new(), // QUIC tracks added after call creation
-        };
-
-        let mut calls = self.calls.write().await;
-        calls.insert(call_id, call);
-
-        // Emit call initiated event
-        let _ = self.event_sender.send(CallEvent::CallInitiated {
+        let _ = self.event_sender.send(CallEvent::LayerSelected {
             call_id,
-            callee,
-            constraints,
+            track_id,
+            layer,
         });
-
-        Ok(call_id)
+        Ok(())
     }
 
-    /// Connect an existing call's QuicMediaTransport to a peer
-    ///
-    /// This method connects the call's media transport to the specified peer,
-    /// enabling QUIC-based media streaming.
+    /// Tracks the peer is sending, each with its own packet receiver
     ///
-    /// # Arguments
-    ///
-    /// * `call_id` - The call to connect
-    /// * `peer` - The QUIC peer connection to use
+    /// Tracks are those of the peer's last track update. Every call
+    /// subscribes fresh receivers, which only see packets received from
+    /// then on.
     ///
     /// # Errors
     ///
-    /// Returns error if call not found, no media transport exists, or
-    /// connection fails.
-    pub async fn connect_quic_transport(
-        &self,
-        call_id: CallId,
-        peer: PeerConnection,
-    ) -> Result<(), CallError> {
-        let calls = self.calls.read().await;
-        let call = calls
-            .get(&call_id)
+    /// Returns error if the call does not exist or has no media transport
+    pub async fn remote_tracks(&self, call_id: CallId) -> Result<Vec<RemoteTrack>, CallError> {
+        let entry = self
+            .call_entry(call_id)
+            .await
             .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
-
+        let call = entry.lock().await;
         let transport = call
             .media_transport
             .as_ref()
-            .ok_or_else(|| CallError::ConfigError("Call has no media transport".to_string()))?;
+            .ok_or_else(|| CallError::media(call_id, MediaTransportError::NotConnected))?;
+        Ok(call
+            .remote_tracks
+            .iter()
+            .map(|info| RemoteTrack {
+                packets: transport.subscribe_track(info.stream_key()),
+                info: info.clone(),
+            })
+            .collect())
+    }
 
-        tracing::debug!(
-            "Connecting QuicMediaTransport for call {} to peer {}",
-            call_id,
-            peer.peer_id
-        );
+    /// Receive the packets the peer sends on one track
+    ///
+    /// Use with [`CallEvent::RemoteTrackAdded`] to render tracks as they
+    /// appear. The receiver ends when the track is removed or the call ends.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the call does not exist or has no media transport
+    pub async fn subscribe_remote_track(
+        &self,
+        call_id: CallId,
+        key: StreamKey,
+    ) -> Result<mpsc::Receiver<RtpPacket>, CallError> {
+        let transport = self
+            .media_transport(call_id)
+            .await
+            .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+        Ok(transport.subscribe_track(key))
+    }
 
-        transport.connect(peer).await?;
+    /// Metadata of the tracks the peer sends on a call
+    ///
+    /// Returns `None` if call not found.
+    pub async fn remote_track_info(&self, call_id: CallId) -> Option<Vec<TrackInfo>> {
+        let entry = self.call_entry(call_id).await?;
+        let call = entry.lock().await;
+        Some(call.remote_tracks.clone())
+    }
 
-        tracing::info!("QuicMediaTransport connected for call {}", call_id);
-        Ok(())
+    /// Metadata of the peer's track received on `key`
+    ///
+    /// Returns `None` if call not found or the peer has not described the
+    /// track.
+    pub async fn remote_track(&self, call_id: CallId, key: StreamKey) -> Option<TrackInfo> {
+        let entry = self.call_entry(call_id).await?;
+        let call = entry.lock().await;
+        call.remote_tracks
+            .iter()
+            .find(|t| t.stream_key() == key)
+            .cloned()
     }
 
-    /// Update call state based on QuicMediaTransport state
+    /// Validate remote capabilities against call constraints
     ///
-    /// Synchronizes the call's `CallState` with the underlying transport state.
-    /// This should be called when transport state changes are detected.
+    /// Checks whether the remote peer's capabilities satisfy the call's
+    /// media requirements. This is used during connection confirmation
+    /// to ensure both peers can support the required media types.
     ///
-    /// # State Transitions
+    /// # Validation Rules
     ///
-    /// - `Disconnected` transport: Maps to `Idle` (or `Ending` if call was active)
-    /// - `Connecting` transport: Maps to `Connecting`
-    /// - `Connected` transport: Maps to `Connected`
-    /// - `Failed` transport: Maps to `Failed`
+    /// - If audio is required by constraints, remote must support audio
+    /// - If video is required by constraints, remote must support video
+    /// - If screen share is required, remote must support video
+    /// - Bandwidth requirements are considered but not strictly enforced
     ///
     /// # Arguments
     ///
-    /// * `call_id` - The call to update
+    /// * `constraints` - The local call constraints
+    /// * `remote_caps` - The remote peer's capabilities
     ///
     /// # Errors
     ///
-    /// Returns error if call not found or has no media transport.
-    pub async fn update_state_from_transport(
+    /// Returns error describing which capability is missing or incompatible.
+    pub fn validate_remote_capabilities(
+        constraints: &MediaConstraints,
+        remote_caps: &MediaCapabilities,
+    ) -> Result<(), CallError> {
+        // Check audio requirement
+        if constraints.audio && !remote_caps.audio {
+            return Err(CallError::ConfigError(
+                "Remote peer does not support audio".to_string(),
+            ));
+        }
+
+        // Check video requirement (video or screen share)
+        if (constraints.video || constraints.screen_share) && !remote_caps.video {
+            return Err(CallError::ConfigError(
+                "Remote peer does not support video".to_string(),
+            ));
+        }
+
+        // Note: Bandwidth is advisory, not strictly enforced
+        // In the future, we could add bandwidth negotiation logic here
+
+        Ok(())
+    }
+
+    /// Get the audio format agreed for a call
+    ///
+    /// `None` until [`confirm_connection`](Self::confirm_connection) has
+    /// negotiated it, or if the call is unknown. Configure the call's
+    /// [`AudioPipeline`](crate::audio_pipeline::AudioPipeline) from it.
+    pub async fn negotiated_audio(&self, call_id: CallId) -> Option<AudioParameters> {
+        self.call_entry(call_id).await?.lock().await.audio_params
+    }
+
+    /// Latency profile of a call, from its constraints or the default
+    ///
+    /// Returns `None` if call not found.
+    pub async fn latency_profile(&self, call_id: CallId) -> Option<LatencyProfile> {
+        let entry = self.call_entry(call_id).await?;
+        let constraints = &entry.lock().await.constraints;
+        Some(constraints.latency.unwrap_or(self.config.latency))
+    }
+
+    /// Media settings for a call's latency profile
+    ///
+    /// Jitter buffers, encoders, the pacer and FEC of the call read their
+    /// targets from here. Returns `None` if call not found.
+    pub async fn latency_tuning(&self, call_id: CallId) -> Option<LatencyTuning> {
+        self.latency_profile(call_id)
+            .await
+            .map(LatencyProfile::tuning)
+    }
+
+    /// The audit log, if one is configured
+    #[must_use]
+    pub fn audit_log(&self) -> Option<&Arc<AuditLog>> {
+        self.audit.as_ref()
+    }
+
+    /// Record a policy decision about a call in the audit log
+    ///
+    /// Does nothing when no audit log is configured. Components outside the
+    /// call manager, such as auto-answer, use this for their own decisions.
+    pub fn record_policy_decision(
         &self,
         call_id: CallId,
-    ) -> Result<CallState, CallError> {
-        let mut calls = self.calls.write().await;
-        let call = calls
-            .get_mut(&call_id)
-            .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+        policy: &str,
+        allowed: bool,
+        reason: Option<String>,
+    ) {
+        self.audit(
+            call_id,
+            AuditEvent::PolicyDecision {
+                policy: policy.to_string(),
+                allowed,
+                reason,
+            },
+        );
+    }
 
-        let transport = call
-            .media_transport
-            .as_ref()
-            .ok_or_else(|| CallError::ConfigError("Call has no media transport".to_string()))?;
+    /// Leak-detection gauges shared with this manager's transports
+    #[must_use]
+    pub fn resources(&self) -> &Arc<ResourceGauges> {
+        &self.resources
+    }
 
-        let transport_state = transport.state().await;
-        let old_state = call.state;
+    /// Live calls, streams, tasks and buffered packets
+    #[must_use]
+    pub fn resource_counts(&self) -> ResourceCounts {
+        self.resources.snapshot()
+    }
 
-        // Map transport state to call state, using ending context if appropriate
-        let new_state = if old_state == CallState::Connected
-            && transport_state == MediaTransportState::Disconnected
-        {
-            // Connected call that disconnected -> Ending
-            CallState::Ending
-        } else {
-            CallState::from_transport_state(transport_state)
-        };
+    /// Time source of call transports and statistics
+    #[must_use]
+    pub fn clock(&self) -> &SharedClock {
+        &self.config.clock
+    }
 
-        if old_state != new_state {
-            call.state = new_state;
-            tracing::debug!(
-                call_id = %call_id,
-                old_state = ?old_state,
-                new_state = ?new_state,
-                transport_state = ?transport_state,
-                "Call state updated from transport"
-            );
+    /// Subscribe to call events
+    #[must_use]
+    pub fn subscribe_events(&self) -> broadcast::Receiver<CallEvent<I>> {
+        self.event_sender.subscribe()
+    }
 
-            // Emit appropriate event based on transition
-            match new_state {
-                CallState::Connected => {
-                    let _ = self
-                        .event_sender
-                        .send(CallEvent::ConnectionEstablished { call_id });
-                }
-                CallState::Failed => {
-                    let _ = self.event_sender.send(CallEvent::ConnectionFailed {
-                        call_id,
-                        error: "Transport failed".to_string(),
-                    });
-                }
-                _ => {}
+    /// Counters of the call event channel, including events lost by
+    /// lagging subscribers
+    #[must_use]
+    pub fn event_stats(&self) -> EventChannelStats {
+        self.event_sender.stats()
+    }
+
+    /// Check if a call has a QUIC media transport
+    ///
+    /// Returns `true` if the call has an associated `QuicMediaTransport`.
+    #[must_use]
+    pub async fn has_media_transport(&self, call_id: CallId) -> bool {
+        match self.call_entry(call_id).await {
+            Some(entry) => entry.lock().await.media_transport.is_some(),
+            None => false,
+        }
+    }
+
+    /// Get the QuicMediaTransport of a call
+    #[must_use]
+    pub async fn media_transport(&self, call_id: CallId) -> Option<Arc<QuicMediaTransport>> {
+        let entry = self.call_entry(call_id).await?;
+        let transport = entry.lock().await.media_transport.clone();
+        transport
+    }
+
+    /// Get the QuicMediaTransports of all calls that have one
+    pub async fn media_transports(&self) -> Vec<(CallId, Arc<QuicMediaTransport>)> {
+        let entries: Vec<(CallId, CallEntry<I>)> = self
+            .calls
+            .read()
+            .await
+            .iter()
+            .map(|(call_id, entry)| (*call_id, Arc::clone(entry)))
+            .collect();
+        let mut transports = Vec::with_capacity(entries.len());
+        for (call_id, entry) in entries {
+            if let Some(transport) = entry.lock().await.media_transport.clone() {
+                transports.push((call_id, transport));
             }
         }
+        transports
+    }
 
-        Ok(new_state)
+    /// Get the ID, remote peer and state of every call, in call ID order
+    pub async fn calls(&self) -> Vec<(CallId, I, CallState)> {
+        let entries: Vec<CallEntry<I>> = self.calls.read().await.values().cloned().collect();
+        let mut calls = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let call = entry.lock().await;
+            calls.push((call.id, call.remote_peer.clone(), call.state));
+        }
+        calls.sort_by_key(|(call_id, _, _)| *call_id);
+        calls
     }
 
-    /// Check if a call state transition is valid for QUIC flow
+    /// Resolve a call ID or a call's short code, as typed by a person
     ///
-    /// Validates that the proposed state transition follows the QUIC call
-    /// state machine rules.
+    /// A full call ID is returned as is, whether or not the call exists. A
+    /// short code is looked up among the current calls.
     ///
-    /// # Valid Transitions (QUIC-native)
+    /// # Errors
     ///
-    /// - **Setup**: Idle → Calling → Connecting → Connected
-    /// - **Direct connect**: Idle → Connecting (for QUIC with pre-established transport)
-    /// - **Teardown**: Connected → Ending → Idle
-    /// - **Failure**: Any active state → Failed
-    /// - **Recovery**: Failed → Idle
-    #[must_use]
-    pub fn is_valid_quic_transition(from: CallState, to: CallState) -> bool {
-        matches!(
-            (from, to),
-            // Starting a call
-            (CallState::Idle, CallState::Calling)
-                | (CallState::Idle, CallState::Connecting) // Direct QUIC connect
-                // Progressing through call setup
-                | (CallState::Calling, CallState::Connecting)
-                | (CallState::Connecting, CallState::Connected)
-                // Ending a call
-                | (CallState::Connected, CallState::Ending)
-                | (CallState::Ending, CallState::Idle)
-                // Failures can happen from any active state
-                | (CallState::Calling, CallState::Failed)
-                | (CallState::Connecting, CallState::Failed)
-                | (CallState::Connected, CallState::Failed)
-                // Recovery from failure
-                | (CallState::Failed, CallState::Idle)
-        )
+    /// Returns error if the input is neither, no call has the short code, or
+    /// several calls do
+    pub async fn resolve_call_id(&self, id: &str) -> Result<CallId, CallError> {
+        let id = id.trim();
+        if let Ok(call_id) = id.parse::<CallId>() {
+            return Ok(call_id);
+        }
+        let code: ShortCode = id
+            .parse()
+            .map_err(|_| CallError::CallNotFound(id.to_string()))?;
+        let matches: Vec<CallId> = self
+            .calls
+            .read()
+            .await
+            .keys()
+            .copied()
+            .filter(|call_id| call_id.short_code() == code)
+            .collect();
+        match matches.as_slice() {
+            [call_id] => Ok(*call_id),
+            [] => Err(CallError::CallNotFound(id.to_string())),
+            _ => Err(CallError::AmbiguousShortCode(code)),
+        }
     }
 
-    /// Transition call to failed state
+    /// Tap a call's media packets of one stream type
     ///
-    /// Marks the call as failed and emits a `ConnectionFailed` event.
-    /// This can be called from any active state (Calling, Connecting, Connected).
+    /// The receiver gets read-only copies of packets the call sends and
+    /// receives, without affecting the media path.
     ///
-    /// # Arguments
+    /// # Errors
     ///
-    /// * `call_id` - The call to mark as failed
-    /// * `reason` - Description of why the call failed
+    /// Returns error if the call does not exist or has no media transport
+    pub async fn tap_media(
+        &self,
+        call_id: CallId,
+        stream_type: RtpStreamType,
+    ) -> Result<mpsc::Receiver<RtpPacket>, CallError> {
+        let transport = self
+            .media_transport(call_id)
+            .await
+            .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+        Ok(transport.tap(stream_type))
+    }
+
+    /// Write a call's sent and received media packets to a pcapng file
+    ///
+    /// The capture runs until [`stop_media_dump`](Self::stop_media_dump) or
+    /// the end of the call.
     ///
     /// # Errors
     ///
-    /// Returns error if call not found or already in terminal state.
-    pub async fn fail_call(&self, call_id: CallId, reason: String) -> Result<(), CallError> {
-        let mut calls = self.calls.write().await;
-        let call = calls
-            .get_mut(&call_id)
+    /// Returns error if the call does not exist, has no media transport or
+    /// the file cannot be created
+    pub async fn start_media_dump(&self, call_id: CallId, path: &Path) -> Result<(), CallError> {
+        let transport = self
+            .media_transport(call_id)
+            .await
             .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+        transport
+            .start_dump(path)
+            .map_err(|e| CallError::media(call_id, e))?;
+        Ok(())
+    }
 
-        // Validate transition is allowed
-        if !Self::is_valid_quic_transition(call.state, CallState::Failed) {
-            tracing::warn!(
-                call_id = %call_id,
-                current_state = ?call.state,
-                "Cannot transition to Failed from current state"
-            );
-            return Err(CallError::InvalidState);
+    /// Stop a call's media capture, returning the number of packets written
+    ///
+    /// Returns `None` if the call does not exist or no capture was running.
+    pub async fn stop_media_dump(&self, call_id: CallId) -> Option<u64> {
+        self.media_transport(call_id).await?.stop_dump()
+    }
+
+    /// Get transport statistics of a call, including per-stream rates
+    ///
+    /// Returns `None` if the call does not exist or has no media transport.
+    #[must_use]
+    pub async fn transport_stats(&self, call_id: CallId) -> Option<TransportStats> {
+        let transport = self.media_transport(call_id).await?;
+        Some(transport.stats().await)
+    }
+
+    /// Get quality metrics of a call measured by its media transport
+    ///
+    /// See [`CallQualityMetrics::from_transport_stats`]. Returns `None` if
+    /// the call does not exist or has no media transport.
+    #[must_use]
+    pub async fn quality_metrics(&self, call_id: CallId) -> Option<CallQualityMetrics> {
+        let stats = self.transport_stats(call_id).await?;
+        Some(CallQualityMetrics::from_transport_stats(&stats))
+    }
+
+    /// Feed the quality measured by a call's media transport to the
+    /// audio-only fallback
+    ///
+    /// Like [`report_quality`](Self::report_quality) with
+    /// [`quality_metrics`](Self::quality_metrics), so bandwidth comes from
+    /// the transport's windowed rates.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if call not found, has no media transport or is not
+    /// connected
+    pub async fn report_transport_quality(
+        &self,
+        call_id: CallId,
+    ) -> Result<Option<SignalingMessage>, CallError> {
+        let metrics = self
+            .quality_metrics(call_id)
+            .await
+            .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+        self.report_quality(call_id, metrics).await
+    }
+
+    /// Initiate a QUIC-native call (bypasses SDP/ICE)
+    ///
+    /// Creates a call using only QuicMediaTransport, without creating a
+    /// legacy RTCPeerConnection. This is the preferred method for new
+    /// QUIC-native calls.
+    ///
+    /// # Arguments
+    ///
+    /// * `callee` - The remote peer identity
+    /// * `constraints` - Media constraints for the call
+    /// * `peer` - The QUIC peer connection to use for transport
+    ///
+    /// # Errors
+    ///
+    /// Returns error if call cannot be initiated or transport connection fails.
+    pub async fn initiate_quic_call(
+        &self,
+        callee: I,
+        constraints: MediaConstraints,
+        peer: PeerConnection,
+    ) -> Result<CallId, CallError> {
+        let call_id = CallId::new();
+
+        // Enforce max_concurrent_calls limit
+        self.check_call_limit(call_id, self.calls.read().await.len())?;
+        self.check_call_policy(call_id, CallDirection::Outgoing, &callee, &constraints)
+            .await?;
+
+        tracing::info!(
+            "Initiating QUIC call {} ({}) to peer: {}",
+            call_id,
+            call_id.short_code(),
+            redact::identity(callee.to_string_repr())
+        );
+
+        // Create and connect QUIC-based media transport
+        let media_transport = Arc::new(self.new_transport(call_id));
+        media_transport
+            .set_keepalive_config(self.config.keepalive)
+            .await;
+        media_transport.set_media_gate(MediaGate::Closed).await;
+        media_transport
+            .connect(peer)
+            .await
+            .map_err(|e| CallError::media(call_id, e))?;
+        tracing::debug!("QuicMediaTransport connected for call {}", call_id);
+
+        let call = Call {
+            id: call_id,
+            remote_peer: callee.clone(),
+            direction: CallDirection::Outgoing,
+            #[cfg(feature = "legacy-webrtc")]
+            peer_connection: None,
+            media_transport: Some(media_transport),
+            state: CallState::Connecting,
+            constraints: constraints.clone(),
+            #[cfg(feature = "legacy-webrtc")]
+            tracks: Vec::new(), // QUIC calls don't use WebRTC tracks
+            quic_tracks: Vec::new(), // QUIC tracks added after call creation
+            audio_params: None,
+            local_tracks: Vec::new(),
+            remote_tracks: Vec::new(),
+            progress: None,
+            held: false,
+            metadata: CallMetadata::new(),
+            quality: QualityMonitor::default(),
+            fec: FecController::new(),
+            handoff: None,
+            _resources: self.resources.track_call(),
+        };
+
+        self.insert_call(call).await?;
+
+        self.audit(
+            call_id,
+            AuditEvent::CallInitiated {
+                peer: callee.to_string_repr(),
+            },
+        );
+
+        // Emit call initiated event
+        let _ = self.event_sender.send(CallEvent::CallInitiated {
+            call_id,
+            callee,
+            constraints,
+        });
+
+        Ok(call_id)
+    }
+
+    /// Start handing a connected call over to another of our devices
+    ///
+    /// Returns the token to pass to the new device out of band (e.g. as a QR
+    /// code or over our own device sync) and the message announcing the
+    /// handoff to the remote peer. The token is refused after `ttl`. The
+    /// call keeps running here until the peer confirms the new device took
+    /// over; see [`Self::handle_handoff_complete`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error if call not found, not connected, or `ttl` is out of range
+    pub async fn prepare_handoff(
+        &self,
+        call_id: CallId,
+        to_device: &I,
+        ttl: std::time::Duration,
+    ) -> Result<(HandoffToken, SignalingMessage), CallError> {
+        let ttl = chrono::Duration::from_std(ttl)
+            .map_err(|e| CallError::ConfigError(format!("Invalid handoff TTL: {e}")))?;
+        let entry = self
+            .call_entry(call_id)
+            .await
+            .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+        let mut call = entry.lock().await;
+        if call.state != CallState::Connected {
+            return Err(CallError::InvalidState);
         }
 
-        let old_state = call.state;
-        call.state = CallState::Failed;
+        let key = HandoffKey::generate();
+        let token = HandoffToken::sign(
+            &key,
+            call_id,
+            to_device.to_string_repr(),
+            call.remote_peer.to_string_repr(),
+            self.config.clock.utc_now() + ttl,
+        );
+        call.handoff = Some(HandoffState::Offered {
+            to_device: to_device.clone(),
+        });
+        tracing::info!(
+            call_id = %call_id,
+            device = %redact::identity(to_device.to_string_repr()),
+            "Offering call handoff"
+        );
 
-        tracing::warn!(
+        let offer = SignalingMessage::HandoffOffer {
+            session_id: call_id.to_string(),
+            key: key.encode(),
+        };
+        Ok((token, offer))
+    }
+
+    /// Handle the peer's announcement that it is moving a call to another
+    /// of its devices
+    ///
+    /// Remembers the handoff key so [`Self::handle_handoff_join`] can check
+    /// the joining device. A later announcement replaces an earlier one.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if call not found, not connected, or the key is malformed
+    pub async fn handle_handoff_offer(&self, call_id: CallId, key: &str) -> Result<(), CallError> {
+        let key = HandoffKey::decode(key)?;
+        let entry = self
+            .call_entry(call_id)
+            .await
+            .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+        let mut call = entry.lock().await;
+        if call.state != CallState::Connected {
+            return Err(CallError::InvalidState);
+        }
+        call.handoff = Some(HandoffState::Expected { key });
+        tracing::debug!(call_id = %call_id, "Peer announced call handoff");
+        Ok(())
+    }
+
+    /// Take over a call from another of our devices
+    ///
+    /// Registers the call under its existing ID in the `Connecting` state
+    /// with its transport connected to `peer`, and returns the message that
+    /// presents the token to the remote peer. The call becomes `Connected`
+    /// when the peer completes the handoff.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the token names an unusable peer, the call already
+    /// exists here, the concurrent call limit is reached or the transport
+    /// connection fails
+    pub async fn join_handoff(
+        &self,
+        token: &HandoffToken,
+        constraints: MediaConstraints,
+        peer: PeerConnection,
+    ) -> Result<SignalingMessage, CallError> {
+        let call_id = token.call_id;
+        let remote_peer = I::from_string_repr(&token.remote_peer)
+            .map_err(|e| HandoffError::Malformed(e.to_string()))?;
+        if self.call_entry(call_id).await.is_some() {
+            return Err(CallError::ConfigError(format!(
+                "Call {} already exists",
+                call_id
+            )));
+        }
+
+        let media_transport = Arc::new(self.new_transport(call_id));
+        media_transport
+            .set_keepalive_config(self.config.keepalive)
+            .await;
+        media_transport.set_media_gate(MediaGate::Closed).await;
+        media_transport
+            .connect(peer)
+            .await
+            .map_err(|e| CallError::media(call_id, e))?;
+
+        let call = Call {
+            id: call_id,
+            remote_peer,
+            direction: CallDirection::Outgoing,
+            #[cfg(feature = "legacy-webrtc")]
+            peer_connection: None,
+            media_transport: Some(media_transport),
+            state: CallState::Connecting,
+            constraints,
+            #[cfg(feature = "legacy-webrtc")]
+            tracks: Vec::new(),
+            quic_tracks: Vec::new(),
+            audio_params: None,
+            local_tracks: Vec::new(),
+            remote_tracks: Vec::new(),
+            progress: None,
+            held: false,
+            metadata: CallMetadata::new(),
+            quality: QualityMonitor::default(),
+            fec: FecController::new(),
+            handoff: Some(HandoffState::Joining),
+            _resources: self.resources.track_call(),
+        };
+        self.insert_call(call).await?;
+        tracing::info!(call_id = %call_id, "Joining handed-off call");
+
+        Ok(SignalingMessage::HandoffJoin {
+            session_id: call_id.to_string(),
+            token: token.encode(),
+        })
+    }
+
+    /// Handle a device joining a call the peer is handing off to it
+    ///
+    /// Checks the token against the key from the peer's handoff offer, then
+    /// switches the call's media over to `peer` and makes `from` the call's
+    /// remote peer. Returns the message to send to both the old and the new
+    /// device. If the switch fails the call stays with the old device and
+    /// the handoff is abandoned.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if call not found, no handoff was announced, the token
+    /// is invalid, expired or names another device, or the transport
+    /// connection fails
+    pub async fn handle_handoff_join(
+        &self,
+        call_id: CallId,
+        from: I,
+        token: &str,
+        peer: PeerConnection,
+    ) -> Result<SignalingMessage, CallError> {
+        let token = HandoffToken::decode(token)?;
+        if token.call_id != call_id {
+            return Err(HandoffError::Malformed(format!(
+                "token is for call {}, not {}",
+                token.call_id, call_id
+            ))
+            .into());
+        }
+        let entry = self
+            .call_entry(call_id)
+            .await
+            .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+        let mut call = entry.lock().await;
+        let Some(HandoffState::Expected { key }) = &call.handoff else {
+            return Err(HandoffError::NotPending(call_id).into());
+        };
+        token.verify(key, self.config.clock.utc_now())?;
+        if token.to_device != from.to_string_repr() {
+            return Err(HandoffError::WrongDevice.into());
+        }
+
+        let transport = call
+            .media_transport
+            .clone()
+            .ok_or_else(|| CallError::ConfigError("Call has no media transport".to_string()))?;
+        // The old device's media keeps flowing unless the new one is reached
+        if let Err(e) = transport.switch_peer(peer).await {
+            call.handoff = None;
+            tracing::warn!(call_id = %call_id, error = %e, "Handoff join failed");
+            return Err(CallError::media(call_id, e));
+        }
+
+        let old_peer = std::mem::replace(&mut call.remote_peer, from.clone());
+        call.remote_tracks.clear();
+        call.handoff = None;
+        tracing::info!(
             call_id = %call_id,
-            old_state = ?old_state,
-            reason = %reason,
-            "Call failed"
+            from = %redact::identity(old_peer.to_string_repr()),
+            to = %redact::identity(from.to_string_repr()),
+            "Peer handed off call"
         );
 
-        let _ = self.event_sender.send(CallEvent::ConnectionFailed {
+        self.audit(
             call_id,
-            error: reason,
+            AuditEvent::HandedOff {
+                device: from.to_string_repr(),
+            },
+        );
+        let _ = self.event_sender.send(CallEvent::PeerHandedOff {
+            call_id,
+            from: old_peer,
+            to: from,
         });
 
-        Ok(())
+        Ok(SignalingMessage::HandoffComplete {
+            session_id: call_id.to_string(),
+        })
+    }
+
+    /// Handle the peer's confirmation that a handoff finished
+    ///
+    /// On the device that offered the call, the call is released and
+    /// removed. On the device that took it over, the call becomes
+    /// `Connected` and media flows.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if call not found or this device has no handoff of the
+    /// call in progress
+    pub async fn handle_handoff_complete(&self, call_id: CallId) -> Result<(), CallError> {
+        let entry = self
+            .call_entry(call_id)
+            .await
+            .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+        let mut call = entry.lock().await;
+        match call.handoff.take() {
+            Some(HandoffState::Offered { to_device }) => {
+                // Lock order is map, then call
+                drop(call);
+                self.calls.write().await.remove(&call_id);
+                let call = entry.lock().await;
+                self.release_call(&call).await;
+                self.audit(
+                    call_id,
+                    AuditEvent::HandedOff {
+                        device: to_device.to_string_repr(),
+                    },
+                );
+                let _ = self.event_sender.send(CallEvent::CallHandedOff {
+                    call_id,
+                    to: to_device,
+                });
+                tracing::info!(call_id = %call_id, "Call handed off to another device");
+                Ok(())
+            }
+            Some(HandoffState::Joining) => {
+                call.state = CallState::Connected;
+                open_media_gate(&call).await;
+                self.audit(call_id, AuditEvent::CallAccepted);
+                let _ = self
+                    .event_sender
+                    .send(CallEvent::ConnectionEstablished { call_id });
+                tracing::info!(call_id = %call_id, "Took over handed-off call");
+                Ok(())
+            }
+            other => {
+                call.handoff = other;
+                Err(HandoffError::NotPending(call_id).into())
+            }
+        }
+    }
+
+    /// Connect an existing call's QuicMediaTransport to a peer
+    ///
+    /// This method connects the call's media transport to the specified peer,
+    /// enabling QUIC-based media streaming.
+    ///
+    /// # Arguments
+    ///
+    /// * `call_id` - The call to connect
+    /// * `peer` - The QUIC peer connection to use
+    ///
+    /// # Errors
+    ///
+    /// Returns error if call not found, no media transport exists, or
+    /// connection fails.
+    pub async fn connect_quic_transport(
+        &self,
+        call_id: CallId,
+        peer: PeerConnection,
+    ) -> Result<(), CallError> {
+        let entry = self
+            .call_entry(call_id)
+            .await
+            .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+        let call = entry.lock().await;
+
+        let transport = call
+            .media_transport
+            .as_ref()
+            .ok_or_else(|| CallError::ConfigError("Call has no media transport".to_string()))?;
+
+        tracing::debug!(
+            "Connecting QuicMediaTransport for call {} to peer {}",
+            call_id,
+            redact::identity(&peer.peer_id)
+        );
+
+        transport
+            .connect(peer)
+            .await
+            .map_err(|e| CallError::media(call_id, e))?;
+
+        tracing::info!("QuicMediaTransport connected for call {}", call_id);
+        Ok(())
+    }
+
+    /// Update call state based on QuicMediaTransport state
+    ///
+    /// Synchronizes the call's `CallState` with the underlying transport state.
+    /// This should be called when transport state changes are detected.
+    ///
+    /// The new state comes from [`call_state_for_transport`]: an active call
+    /// whose transport disconnected is `Ending`, one still connecting has
+    /// `Failed`, and an ending or failed call does not follow a reconnecting
+    /// transport.
+    ///
+    /// # Arguments
+    ///
+    /// * `call_id` - The call to update
+    ///
+    /// # Errors
+    ///
+    /// Returns error if call not found, has no media transport, or the
+    /// transport state would drive a transition the call state machine does
+    /// not allow.
+    pub async fn update_state_from_transport(
+        &self,
+        call_id: CallId,
+    ) -> Result<CallState, CallError> {
+        let entry = self
+            .call_entry(call_id)
+            .await
+            .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+        let mut call = entry.lock().await;
+
+        let transport = call
+            .media_transport
+            .as_ref()
+            .ok_or_else(|| CallError::ConfigError("Call has no media transport".to_string()))?;
+
+        let transport_state = transport.state().await;
+        let old_state = call.state;
+
+        let new_state = call_state_for_transport(old_state, transport_state);
+        if old_state != new_state && !old_state.can_transition_to(new_state) {
+            return Err(CallError::InvalidState);
+        }
+
+        if old_state != new_state {
+            call.state = new_state;
+            tracing::debug!(
+                call_id = %call_id,
+                old_state = ?old_state,
+                new_state = ?new_state,
+                transport_state = ?transport_state,
+                "Call state updated from transport"
+            );
+
+            // Emit appropriate event based on transition
+            match new_state {
+                CallState::Connected => {
+                    open_media_gate(&call).await;
+                    let _ = self
+                        .event_sender
+                        .send(CallEvent::ConnectionEstablished { call_id });
+                }
+                CallState::Failed => {
+                    self.audit(
+                        call_id,
+                        AuditEvent::CallFailed {
+                            reason: "Transport failed".to_string(),
+                        },
+                    );
+                    let _ = self.event_sender.send(CallEvent::ConnectionFailed {
+                        call_id,
+                        error: "Transport failed".to_string(),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        Ok(new_state)
+    }
+
+    /// Check if a call state transition is valid for QUIC flow
+    ///
+    /// Validates the proposed transition against [`CALL_TRANSITIONS`](crate::state_machine::CALL_TRANSITIONS), the
+    /// call state machine.
+    #[must_use]
+    pub fn is_valid_quic_transition(from: CallState, to: CallState) -> bool {
+        from.can_transition_to(to)
+    }
+
+    /// Transition call to failed state
+    ///
+    /// Marks the call as failed and emits a `ConnectionFailed` event.
+    /// This can be called from any active state (Calling, Connecting, Connected).
+    ///
+    /// # Arguments
+    ///
+    /// * `call_id` - The call to mark as failed
+    /// * `reason` - Description of why the call failed
+    ///
+    /// # Errors
+    ///
+    /// Returns error if call not found or already in terminal state.
+    pub async fn fail_call(&self, call_id: CallId, reason: String) -> Result<(), CallError> {
+        let entry = self
+            .call_entry(call_id)
+            .await
+            .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+        let mut call = entry.lock().await;
+
+        // Validate transition is allowed
+        if !Self::is_valid_quic_transition(call.state, CallState::Failed) {
+            tracing::warn!(
+                call_id = %call_id,
+                current_state = ?call.state,
+                "Cannot transition to Failed from current state"
+            );
+            return Err(CallError::InvalidState);
+        }
+
+        let old_state = call.state;
+        call.state = CallState::Failed;
+
+        tracing::warn!(
+            call_id = %call_id,
+            old_state = ?old_state,
+            reason = %reason,
+            "Call failed"
+        );
+
+        self.audit(
+            call_id,
+            AuditEvent::CallFailed {
+                reason: reason.clone(),
+            },
+        );
+        let _ = self.event_sender.send(CallEvent::ConnectionFailed {
+            call_id,
+            error: reason,
+        });
+
+        Ok(())
+    }
+
+    /// Check a call's peer liveness and update its state
+    ///
+    /// Moves a `Connected` call to `Reconnecting` once the peer has missed
+    /// `miss_threshold` keepalive intervals, back to `Connected` when traffic
+    /// resumes, and to `Failed` after `failure_threshold` intervals. Calls in
+    /// other states are left unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `call_id` - The call to check
+    ///
+    /// # Errors
+    ///
+    /// Returns error if call not found or has no media transport.
+    pub async fn check_liveness(&self, call_id: CallId) -> Result<CallState, CallError> {
+        check_call_liveness(
+            &self.calls,
+            &self.event_sender,
+            self.audit.as_deref(),
+            self.config.metrics.as_ref(),
+            call_id,
+        )
+        .await
+    }
+
+    /// Play a pre-encoded media file into a call
+    ///
+    /// The file is read and demuxed up front (see [`crate::playback`] for
+    /// the formats), then a task sends its frames through the call's media
+    /// transport at their recorded pace, alongside whatever else the call
+    /// sends. The task resolves to the number of frames sent, or to the
+    /// error that stopped it, e.g. the call ending.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be read or demuxed, or the call is
+    /// not found or has no media transport.
+    pub async fn play_media_file(
+        &self,
+        call_id: CallId,
+        path: impl AsRef<Path>,
+    ) -> Result<tokio::task::JoinHandle<Result<u64, PlaybackError>>, CallError> {
+        let file = MediaFile::open(path.as_ref())?;
+        let transport = self
+            .call_entry(call_id)
+            .await
+            .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?
+            .lock()
+            .await
+            .media_transport
+            .clone()
+            .ok_or_else(|| CallError::ConfigError("Call has no media transport".to_string()))?;
+
+        let task_guard = self.resources.track_task();
+        Ok(tokio::spawn(async move {
+            let _task_guard = task_guard;
+            let result = file.play(&transport).await;
+            if let Err(ref e) = result {
+                tracing::warn!(call_id = %call_id, error = %e, "Media file playback stopped");
+            }
+            result
+        }))
+    }
+
+    /// Start sending keepalives for a QUIC call
+    ///
+    /// Spawns a task that pings the peer every keepalive interval and runs
+    /// [`check_liveness`](Self::check_liveness). The task stops once the call
+    /// is removed or leaves the `Connected`/`Reconnecting` states.
+    ///
+    /// # Arguments
+    ///
+    /// * `call_id` - The call to monitor
+    ///
+    /// # Errors
+    ///
+    /// Returns error if call not found or has no media transport.
+    pub async fn start_keepalive(
+        &self,
+        call_id: CallId,
+    ) -> Result<tokio::task::JoinHandle<()>, CallError> {
+        let transport = self
+            .call_entry(call_id)
+            .await
+            .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?
+            .lock()
+            .await
+            .media_transport
+            .clone()
+            .ok_or_else(|| CallError::ConfigError("Call has no media transport".to_string()))?;
+
+        let calls = Arc::clone(&self.calls);
+        let event_sender = self.event_sender.clone();
+        let audit = self.audit.clone();
+        let metrics = self.config.metrics.clone();
+        let interval = transport.keepalive_config().await.interval;
+        let task_guard = self.resources.track_task();
+
+        Ok(tokio::spawn(async move {
+            let _task_guard = task_guard;
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+
+                if let Err(e) = transport.send_keepalive().await {
+                    tracing::debug!(call_id = %call_id, error = %e, "Keepalive send failed");
+                }
+
+                match check_call_liveness(
+                    &calls,
+                    &event_sender,
+                    audit.as_deref(),
+                    metrics.as_ref(),
+                    call_id,
+                )
+                .await
+                {
+                    Ok(CallState::Connected | CallState::Reconnecting) => {}
+                    Ok(_) | Err(_) => break,
+                }
+            }
+            tracing::debug!(call_id = %call_id, "Keepalive task stopped");
+        }))
+    }
+
+    /// Start sampling a call's statistics into its history
+    ///
+    /// Spawns a task that records a [`StatsSample`] every
+    /// `stats_history.interval` until the call is removed. The history is
+    /// kept after the call ends.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if call not found or has no media transport.
+    pub async fn start_stats_history(
+        &self,
+        call_id: CallId,
+    ) -> Result<tokio::task::JoinHandle<()>, CallError> {
+        if self.media_transport(call_id).await.is_none() {
+            return Err(match self.call_entry(call_id).await {
+                Some(_) => CallError::ConfigError("Call has no media transport".to_string()),
+                None => CallError::CallNotFound(call_id.to_string()),
+            });
+        }
+
+        if let Some(entry) = self.call_entry(call_id).await {
+            let call = entry.lock().await;
+            self.stats_history
+                .set_peer(call_id, call.remote_peer.to_string_repr());
+            for (key, value) in &call.metadata {
+                self.stats_history
+                    .set_metadata(call_id, key.clone(), value.clone());
+            }
+        }
+
+        let calls = Arc::clone(&self.calls);
+        let store = Arc::clone(&self.stats_history);
+        let interval = self.config.stats_history.interval;
+        let clock = self.config.clock.clone();
+        let task_guard = self.resources.track_task();
+
+        Ok(tokio::spawn(async move {
+            let _task_guard = task_guard;
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+
+                let Some(entry) = calls.read().await.get(&call_id).cloned() else {
+                    break;
+                };
+                let Some(transport) = entry.lock().await.media_transport.clone() else {
+                    break;
+                };
+                let sample = StatsSample::from_stats_at(&transport.stats().await, clock.utc_now());
+                store.record(call_id, sample);
+            }
+            tracing::debug!(call_id = %call_id, "Stats history task stopped");
+        }))
+    }
+
+    /// Subscribe to changes in a call's statistics
+    ///
+    /// Samples the call's transport statistics every `interval` and sends
+    /// the change since the previous delta. Samples in which no counter
+    /// changed are skipped; the next delta's `elapsed` spans them. Deltas
+    /// stop when the call is removed or the receiver is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if call not found or has no media transport.
+    pub async fn subscribe_stats(
+        &self,
+        call_id: CallId,
+        interval: std::time::Duration,
+    ) -> Result<mpsc::Receiver<StatsDelta>, CallError> {
+        let Some(transport) = self.media_transport(call_id).await else {
+            return Err(match self.call_entry(call_id).await {
+                Some(_) => CallError::ConfigError("Call has no media transport".to_string()),
+                None => CallError::CallNotFound(call_id.to_string()),
+            });
+        };
+
+        let (sender, receiver) = mpsc::channel(STATS_DELTA_CAPACITY);
+        let calls = Arc::clone(&self.calls);
+        let clock = self.config.clock.clone();
+        let task_guard = self.resources.track_task();
+        let mut previous = transport.stats().await;
+        let start = clock.now();
+
+        let interval = interval.max(std::time::Duration::from_millis(1));
+
+        tokio::spawn(async move {
+            let _task_guard = task_guard;
+            let mut last_sent = start;
+            let mut ticker =
+                tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    () = sender.closed() => break,
+                }
+
+                let Some(entry) = calls.read().await.get(&call_id).cloned() else {
+                    break;
+                };
+                let Some(transport) = entry.lock().await.media_transport.clone() else {
+                    break;
+                };
+                let current = transport.stats().await;
+                let now = clock.now();
+                let delta = StatsDelta::between(
+                    &previous,
+                    &current,
+                    now.duration_since(start),
+                    now.duration_since(last_sent),
+                );
+                previous = current;
+                if delta.is_empty() {
+                    continue;
+                }
+                last_sent = now;
+                if sender.send(delta).await.is_err() {
+                    break;
+                }
+            }
+            tracing::debug!(call_id = %call_id, "Stats subscription stopped");
+        });
+
+        Ok(receiver)
+    }
+
+    /// Get the recorded statistics samples of a call, oldest first
+    ///
+    /// Available for ended calls until their history is evicted.
+    #[must_use]
+    pub fn stats_history(&self, call_id: CallId) -> Option<Vec<StatsSample>> {
+        self.stats_history
+            .get(call_id)
+            .map(|history| history.samples())
+    }
+
+    /// Export a call's statistics history as CSV
+    ///
+    /// # Errors
+    ///
+    /// Returns error if no history exists or the file cannot be written
+    pub fn export_stats_csv(&self, call_id: CallId, path: &Path) -> Result<(), StatsHistoryError> {
+        self.recorded_history(call_id)?.export_csv(path)
+    }
+
+    /// Export a call's statistics history as JSON
+    ///
+    /// # Errors
+    ///
+    /// Returns error if no history exists or the file cannot be written
+    pub fn export_stats_json(&self, call_id: CallId, path: &Path) -> Result<(), StatsHistoryError> {
+        self.recorded_history(call_id)?.export_json(path)
+    }
+
+    /// Remove stored data about calls with `peer`
+    ///
+    /// Drops the statistics histories and audit log records of the peer's
+    /// calls. Calls in progress keep running.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the audit log cannot be read or rewritten
+    pub fn purge_peer_data(&self, peer: &I) -> Result<PurgeReport, CallError> {
+        let peer = peer.to_string_repr();
+        let mut calls: HashSet<CallId> = self
+            .stats_history
+            .calls_with_peer(&peer)
+            .into_iter()
+            .collect();
+        if let Some(ref audit) = self.audit {
+            calls.extend(audit.calls_with_peer(&peer)?);
+        }
+
+        let stats_samples = calls
+            .iter()
+            .filter_map(|&call_id| self.stats_history.remove(call_id))
+            .map(|history| history.len())
+            .sum();
+        let audit_records = match self.audit {
+            Some(ref audit) => audit.purge_calls(&calls)?,
+            None => 0,
+        };
+
+        tracing::info!(
+            peer = %redact::identity(&peer),
+            calls = calls.len(),
+            "Purged peer data"
+        );
+        Ok(PurgeReport {
+            calls: calls.into_iter().collect(),
+            stats_samples,
+            audit_records,
+            recordings: 0,
+        })
+    }
+
+    /// Remove stored data recorded before `before`
+    ///
+    /// Drops older statistics samples and audit log records.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the audit log cannot be read or rewritten
+    pub fn purge_all(&self, before: DateTime<Utc>) -> Result<PurgeReport, CallError> {
+        let stats_samples = self.stats_history.purge_before(before);
+        let audit_records = match self.audit {
+            Some(ref audit) => audit.purge_before(before)?,
+            None => 0,
+        };
+
+        tracing::info!(%before, stats_samples, audit_records, "Purged stored call data");
+        Ok(PurgeReport {
+            stats_samples,
+            audit_records,
+            ..Default::default()
+        })
+    }
+
+    /// Copy a call's history out of the store
+    fn recorded_history(&self, call_id: CallId) -> Result<StatsHistory, StatsHistoryError> {
+        self.stats_history
+            .get(call_id)
+            .ok_or_else(|| StatsHistoryError::NotFound(call_id.to_string()))
+    }
+
+    /// Get current call information
+    ///
+    /// Returns a snapshot of the call's current state, constraints, and
+    /// transport status.
+    ///
+    /// # Arguments
+    ///
+    /// * `call_id` - The call to query
+    ///
+    /// # Returns
+    ///
+    /// Returns `None` if call not found.
+    pub async fn get_call_info(
+        &self,
+        call_id: CallId,
+    ) -> Option<(CallState, MediaConstraints, bool)> {
+        let entry = self.call_entry(call_id).await?;
+        let call = entry.lock().await;
+        Some((
+            call.state,
+            call.constraints.clone(),
+            call.media_transport.is_some(),
+        ))
+    }
+
+    /// Get a call's entry, holding the map lock only for the lookup
+    async fn call_entry(&self, call_id: CallId) -> Option<CallEntry<I>> {
+        self.calls.read().await.get(&call_id).cloned()
+    }
+
+    /// Check whether another call fits under `max_concurrent_calls`
+    fn check_call_limit(&self, call_id: CallId, active_calls: usize) -> Result<(), CallError> {
+        if active_calls >= self.config.max_concurrent_calls {
+            let reason = format!(
+                "Maximum concurrent calls limit reached: {}",
+                self.config.max_concurrent_calls
+            );
+            self.record_policy_decision(
+                call_id,
+                "max_concurrent_calls",
+                false,
+                Some(reason.clone()),
+            );
+            return Err(CallError::ConfigError(reason));
+        }
+        Ok(())
+    }
+
+    /// Ask the call policy, if any, whether a call may proceed
+    async fn check_call_policy(
+        &self,
+        call_id: CallId,
+        direction: CallDirection,
+        peer: &I,
+        constraints: &MediaConstraints,
+    ) -> Result<(), CallError> {
+        let Some(ref policy) = self.config.policy else {
+            return Ok(());
+        };
+        let peer = peer.to_string_repr();
+        let decision = match direction {
+            CallDirection::Outgoing => policy.outgoing(&peer, constraints).await,
+            CallDirection::Incoming => policy.incoming(&peer, constraints).await,
+        };
+        match decision {
+            AuthDecision::Allow => Ok(()),
+            AuthDecision::Deny(reason) => {
+                tracing::info!(
+                    call_id = %call_id,
+                    peer = %redact::identity(&peer),
+                    policy = policy.name(),
+                    reason = %reason,
+                    "Call denied by policy"
+                );
+                self.record_policy_decision(call_id, policy.name(), false, Some(reason.clone()));
+                Err(CallError::PolicyDenied(reason))
+            }
+        }
+    }
+
+    /// Ask the call policy, if any, whether a peer may open a data channel
+    /// or send a file during a call
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the policy denies the request
+    pub async fn check_data_request(
+        &self,
+        call_id: CallId,
+        peer: &I,
+        request: DataRequest,
+    ) -> Result<(), CallError> {
+        let Some(ref policy) = self.config.policy else {
+            return Ok(());
+        };
+        let peer = peer.to_string_repr();
+        match policy.data_request(&peer, request).await {
+            AuthDecision::Allow => Ok(()),
+            AuthDecision::Deny(reason) => {
+                tracing::info!(
+                    call_id = %call_id,
+                    peer = %redact::identity(&peer),
+                    policy = policy.name(),
+                    request = ?request,
+                    reason = %reason,
+                    "Data request denied by policy"
+                );
+                self.record_policy_decision(call_id, policy.name(), false, Some(reason.clone()));
+                Err(CallError::PolicyDenied(reason))
+            }
+        }
+    }
+
+    /// Ask the call policy, if any, whether to answer a call from `peer`
+    /// without asking
+    pub async fn should_auto_accept(&self, peer: &I, constraints: &MediaConstraints) -> bool {
+        match self.config.policy {
+            Some(ref policy) => {
+                policy
+                    .auto_accept(&peer.to_string_repr(), constraints)
+                    .await
+            }
+            None => false,
+        }
+    }
+
+    /// Open a ringing call's streams and ask for its encoders to be started
+    ///
+    /// See [`SpeculativeMediaConfig`]. Streams that cannot be opened yet
+    /// are opened on first send as usual.
+    async fn prepare_media(&self, call: &Call<I>) {
+        let Some(ref transport) = call.media_transport else {
+            return;
+        };
+        let constraints = &call.constraints;
+        let stream_types = [
+            (constraints.audio, LinkStreamType::Audio),
+            (constraints.video, LinkStreamType::Video),
+            (constraints.screen_share, LinkStreamType::Screen),
+            (true, LinkStreamType::RtcpFeedback),
+        ];
+        for (wanted, stream_type) in stream_types {
+            if !wanted {
+                continue;
+            }
+            if let Err(e) = transport.open_stream(stream_type).await {
+                tracing::debug!(call_id = %call.id, error = %e, "Speculative stream not opened");
+                return;
+            }
+        }
+
+        let capture = self.config.speculative_media.capture_before_accept;
+        tracing::debug!(call_id = %call.id, capture, "Media prepared before acceptance");
+        let _ = self.event_sender.send(CallEvent::MediaPrepared {
+            call_id: call.id,
+            constraints: constraints.clone(),
+            capture,
+        });
+    }
+
+    /// Starting point for a call, from earlier calls to the same peer
+    ///
+    /// `None` if the peer has not been called recently or learning is off
+    /// (see [`CallManagerConfig::peer_quality`]).
+    pub async fn quality_seed(&self, call_id: CallId) -> Option<QualitySeed> {
+        let entry = self.call_entry(call_id).await?;
+        let peer = entry.lock().await.remote_peer.clone();
+        self.seed_for(&peer)
+    }
+
+    /// Video bitrate and keyframe schedule for the start of a call
+    ///
+    /// Follows [`CallManagerConfig::ramp`], starting from what the path to
+    /// the peer carried last time when that is known.
+    pub async fn startup_ramp(&self, call_id: CallId) -> Option<BitrateRamp> {
+        let entry = self.call_entry(call_id).await?;
+        let peer = entry.lock().await.remote_peer.clone();
+        let seed = self.seed_for(&peer);
+        Some(BitrateRamp::new(self.config.ramp.clone(), seed.as_ref()))
+    }
+
+    fn seed_for(&self, peer: &I) -> Option<QualitySeed> {
+        if !self.config.peer_quality.enabled {
+            return None;
+        }
+        self.peer_quality.lock().seed(
+            &peer.to_string_repr(),
+            &self.config.peer_quality,
+            self.config.clock.utc_now(),
+        )
+    }
+
+    /// Remember what an ending call's path carried
+    fn learn_peer_quality(&self, call: &Call<I>) {
+        if !self.config.peer_quality.enabled {
+            return;
+        }
+        let Some(bitrate_kbps) = self
+            .stats_history
+            .get(call.id)
+            .and_then(|history| sustained_bitrate_kbps(&history.samples()))
+        else {
+            return;
+        };
+        let codec = if call.constraints.has_video() {
+            codec_mime::H264
+        } else {
+            codec_mime::OPUS
+        };
+        let peer = call.remote_peer.to_string_repr();
+        let learned = self.peer_quality.lock().learn(
+            &peer,
+            bitrate_kbps,
+            codec,
+            &self.config.peer_quality,
+            self.config.clock.utc_now(),
+        );
+        match learned {
+            Ok(quality) => tracing::debug!(
+                call_id = %call.id,
+                bitrate_kbps = quality.bitrate_kbps,
+                codec = %quality.codec,
+                "Learned peer quality"
+            ),
+            Err(e) => tracing::warn!(call_id = %call.id, error = %e, "Failed to save peer quality"),
+        }
+    }
+
+    /// Count a lifecycle event and append it to the audit log, if one is
+    /// configured
+    fn audit(&self, call_id: CallId, event: AuditEvent) {
+        audit(
+            self.audit.as_deref(),
+            self.config.metrics.as_ref(),
+            call_id,
+            event,
+        );
+    }
+
+    /// Register a new call
+    ///
+    /// The limit is checked again under the write lock so concurrent
+    /// initiations cannot overshoot it.
+    async fn insert_call(&self, call: Call<I>) -> Result<(), CallError> {
+        let mut calls = self.calls.write().await;
+        self.check_call_limit(call.id, calls.len())?;
+        calls.insert(call.id, Arc::new(Mutex::new(call)));
+        Ok(())
+    }
+}
+
+/// Count a lifecycle event and append it to an audit log if configured
+///
+/// Audit failures are logged, never fatal.
+fn audit(
+    log: Option<&AuditLog>,
+    metrics: &dyn MetricsRecorder,
+    call_id: CallId,
+    event: AuditEvent,
+) {
+    let counter = match event {
+        AuditEvent::CallInitiated { .. } => Some(metrics::CALLS_INITIATED),
+        AuditEvent::IncomingCall { .. } => Some(metrics::CALLS_INCOMING),
+        AuditEvent::CallAccepted => Some(metrics::CALLS_ACCEPTED),
+        AuditEvent::CallRejected => Some(metrics::CALLS_REJECTED),
+        AuditEvent::CallEnded => Some(metrics::CALLS_ENDED),
+        AuditEvent::CallFailed { .. } => Some(metrics::CALLS_FAILED),
+        AuditEvent::HandedOff { .. } => Some(metrics::CALLS_HANDED_OFF),
+        AuditEvent::PolicyDecision { allowed: false, .. } => Some(metrics::POLICY_DENIALS),
+        AuditEvent::PolicyDecision { allowed: true, .. } | AuditEvent::MetadataSet { .. } => None,
+    };
+    if let Some(counter) = counter {
+        metrics.increment_counter(counter, 1);
+    }
+    if let Some(log) = log {
+        if let Err(e) = log.record(call_id, event) {
+            tracing::warn!(call_id = %call_id, error = %e, "Failed to write audit record");
+        }
+    }
+}
+
+/// Let media flow once a call is accepted, as far as the call allows
+///
+/// Nothing flows while the call is on hold, and only audio while poor
+/// network quality has suspended video.
+async fn open_media_gate<I: PeerIdentity>(call: &Call<I>) {
+    let gate = if call.held {
+        MediaGate::Closed
+    } else if call.quality.degraded().is_some() {
+        MediaGate::EarlyMedia
+    } else {
+        MediaGate::Open
+    };
+    if let Some(ref transport) = call.media_transport {
+        transport.set_media_gate(gate).await;
+    }
+}
+
+/// Check if a track list describes a video track
+fn has_video_track(tracks: &[TrackInfo], track_id: TrackId) -> bool {
+    tracks
+        .iter()
+        .any(|track| track.track_id == track_id && track.kind == MediaType::Video)
+}
+
+/// Apply keepalive liveness to a call's state, emitting events on change
+async fn check_call_liveness<I: PeerIdentity>(
+    calls: &RwLock<HashMap<CallId, CallEntry<I>>>,
+    event_sender: &EventSender<CallEvent<I>>,
+    audit_log: Option<&AuditLog>,
+    metrics: &dyn MetricsRecorder,
+    call_id: CallId,
+) -> Result<CallState, CallError> {
+    let entry = calls
+        .read()
+        .await
+        .get(&call_id)
+        .cloned()
+        .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+    let mut call = entry.lock().await;
+
+    let transport = call
+        .media_transport
+        .as_ref()
+        .ok_or_else(|| CallError::ConfigError("Call has no media transport".to_string()))?;
+
+    if !matches!(call.state, CallState::Connected | CallState::Reconnecting) {
+        return Ok(call.state);
+    }
+
+    let old_state = call.state;
+    let liveness = transport.liveness().await;
+    match (old_state, liveness) {
+        (CallState::Reconnecting, Liveness::Alive) => {
+            call.state = CallState::Connected;
+            tracing::info!(call_id = %call_id, "Peer responsive again");
+            let _ = event_sender.send(CallEvent::ConnectionEstablished { call_id });
+        }
+        (CallState::Connected, Liveness::Suspect { missed }) => {
+            call.state = CallState::Reconnecting;
+            tracing::warn!(call_id = %call_id, missed, "Peer missed keepalives");
+            let _ = event_sender.send(CallEvent::Reconnecting { call_id, missed });
+        }
+        (_, Liveness::Dead { missed }) => {
+            call.state = CallState::Failed;
+            tracing::warn!(call_id = %call_id, missed, "Peer unresponsive, failing call");
+            let error = format!("Peer unresponsive for {missed} keepalive intervals");
+            audit(
+                audit_log,
+                metrics,
+                call_id,
+                AuditEvent::CallFailed {
+                    reason: error.clone(),
+                },
+            );
+            let _ = event_sender.send(CallEvent::ConnectionFailed { call_id, error });
+        }
+        _ => {}
+    }
+
+    Ok(call.state)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::identity::PeerIdentityString;
+    use crate::link_transport::StreamType;
+    use crate::quality::DegradationReason;
+
+    #[tokio::test]
+    async fn test_call_manager_initiate_call() {
+        let config = CallManagerConfig::default();
+        let call_manager = CallManager::<PeerIdentityString>::new(config)
+            .await
+            .unwrap();
+
+        let callee = PeerIdentityString::new("callee");
+        let constraints = MediaConstraints::audio_only();
+
+        let call_id = call_manager
+            .initiate_call(callee, constraints)
+            .await
+            .unwrap();
+
+        let state = call_manager.get_call_state(call_id).await;
+        assert_eq!(state, Some(CallState::Calling));
+    }
+
+    #[tokio::test]
+    async fn test_call_manager_initiate_call_creates_media_transport() {
+        let config = CallManagerConfig::default();
+        let call_manager = CallManager::<PeerIdentityString>::new(config)
+            .await
+            .unwrap();
+
+        let callee = PeerIdentityString::new("callee");
+        let constraints = MediaConstraints::audio_only();
+
+        let call_id = call_manager
+            .initiate_call(callee, constraints)
+            .await
+            .unwrap();
+
+        // Verify QuicMediaTransport is created for new calls
+        assert!(
+            call_manager.has_media_transport(call_id).await,
+            "New calls should have QuicMediaTransport initialized"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_call_manager_accept_call() {
+        let config = CallManagerConfig::default();
+        let call_manager = CallManager::<PeerIdentityString>::new(config)
+            .await
+            .unwrap();
+
+        let callee = PeerIdentityString::new("callee");
+        let constraints = MediaConstraints::audio_only();
+
+        let call_id = call_manager
+            .initiate_call(callee, constraints.clone())
+            .await
+            .unwrap();
+
+        call_manager
+            .accept_call(call_id, constraints)
+            .await
+            .unwrap();
+
+        let state = call_manager.get_call_state(call_id).await;
+        assert_eq!(state, Some(CallState::Connected));
+    }
+
+    #[tokio::test]
+    async fn test_call_manager_reject_call() {
+        let config = CallManagerConfig::default();
+        let call_manager = CallManager::<PeerIdentityString>::new(config)
+            .await
+            .unwrap();
+
+        let callee = PeerIdentityString::new("callee");
+        let constraints = MediaConstraints::audio_only();
+
+        let call_id = call_manager
+            .initiate_call(callee, constraints)
+            .await
+            .unwrap();
+
+        call_manager.reject_call(call_id).await.unwrap();
+
+        let state = call_manager.get_call_state(call_id).await;
+        assert_eq!(state, Some(CallState::Failed));
+    }
+
+    #[tokio::test]
+    async fn test_call_manager_end_call() {
+        let config = CallManagerConfig::default();
+        let call_manager = CallManager::<PeerIdentityString>::new(config)
+            .await
+            .unwrap();
+
+        let callee = PeerIdentityString::new("callee");
+        let constraints = MediaConstraints::audio_only();
+
+        let call_id = call_manager
+            .initiate_call(callee, constraints)
+            .await
+            .unwrap();
+        assert_eq!(call_manager.active_call_count().await, 1);
+
+        call_manager.end_call(call_id).await.unwrap();
+
+        let state = call_manager.get_call_state(call_id).await;
+        assert_eq!(state, None);
+        assert_eq!(call_manager.active_call_count().await, 0);
+    }
+
+    #[tokio::test]
+    #[allow(deprecated)]
+    async fn test_call_manager_create_offer_legacy() {
+        // Tests legacy SDP offer creation (deprecated for QUIC-native calls)
+        let config = CallManagerConfig::default();
+        let call_manager = CallManager::<PeerIdentityString>::new(config)
+            .await
+            .unwrap();
+
+        let callee = PeerIdentityString::new("callee");
+        let constraints = MediaConstraints::audio_only();
+
+        let _call_id = call_manager
+            .initiate_call(callee, constraints)
+            .await
+            .unwrap();
+
+        // Skip the offer creation test for now since it requires proper codec setup
+        // This would need more complex WebRTC setup
+        // let offer = call_manager.create_offer(call_id).await.unwrap();
+        // assert!(!offer.is_empty());
+        // assert!(offer.contains("v=0"));
+    }
+
+    #[cfg(feature = "legacy-webrtc")]
+    #[tokio::test]
+    #[allow(deprecated)]
+    async fn test_call_manager_add_ice_candidate_legacy() {
+        let config = CallManagerConfig::default();
+        let call_manager = CallManager::<PeerIdentityString>::new(config)
+            .await
+            .unwrap();
+
+        let callee = PeerIdentityString::new("callee");
+        let constraints = MediaConstraints::audio_only();
+
+        let call_id = call_manager
+            .initiate_call(callee, constraints)
+            .await
+            .unwrap();
+
+        // Test with a dummy ICE candidate (legacy WebRTC only)
+        let candidate = "candidate:1 1 UDP 2122260223 192.168.1.1 12345 typ host".to_string();
+        let result = call_manager.add_ice_candidate(call_id, candidate).await;
+        // This might fail in test environment, but should not panic
+        // We just test that the method exists and handles call not found
+        assert!(result.is_ok() || matches!(result, Err(CallError::ConfigError(_))));
+    }
+
+    #[cfg(feature = "legacy-webrtc")]
+    #[tokio::test]
+    #[allow(deprecated)]
+    async fn test_call_manager_start_ice_gathering_legacy() {
+        let config = CallManagerConfig::default();
+        let call_manager = CallManager::<PeerIdentityString>::new(config)
+            .await
+            .unwrap();
+
+        let callee = PeerIdentityString::new("callee");
+        let constraints = MediaConstraints::audio_only();
+
+        let call_id = call_manager
+            .initiate_call(callee, constraints)
+            .await
+            .unwrap();
+
+        // Legacy WebRTC ICE gathering
+        let result = call_manager.start_ice_gathering(call_id).await;
+        // This might fail in test environment, but should not panic
+        assert!(result.is_ok() || matches!(result, Err(CallError::ConfigError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_call_manager_call_not_found() {
+        let config = CallManagerConfig::default();
+        let call_manager = CallManager::<PeerIdentityString>::new(config)
+            .await
+            .unwrap();
+
+        let fake_call_id = CallId::new();
+
+        let result = call_manager
+            .accept_call(fake_call_id, MediaConstraints::audio_only())
+            .await;
+        assert!(matches!(result, Err(CallError::CallNotFound(_))));
+
+        let result = call_manager.reject_call(fake_call_id).await;
+        assert!(matches!(result, Err(CallError::CallNotFound(_))));
+
+        let result = call_manager.end_call(fake_call_id).await;
+        assert!(matches!(result, Err(CallError::CallNotFound(_))));
+
+        #[cfg(feature = "legacy-webrtc")]
+        {
+            #[allow(deprecated)]
+            let result = call_manager.create_offer(fake_call_id).await;
+            assert!(matches!(result, Err(CallError::CallNotFound(_))));
+
+            #[allow(deprecated)]
+            let result = call_manager
+                .handle_answer(fake_call_id, "dummy".to_string())
+                .await;
+            assert!(matches!(result, Err(CallError::CallNotFound(_))));
+
+            #[allow(deprecated)]
+            let result = call_manager
+                .add_ice_candidate(fake_call_id, "dummy".to_string())
+                .await;
+            assert!(matches!(result, Err(CallError::CallNotFound(_))));
+
+            #[allow(deprecated)]
+            let result = call_manager.start_ice_gathering(fake_call_id).await;
+            assert!(matches!(result, Err(CallError::CallNotFound(_))));
+        }
+    }
+
+    /// Helper to create a test PeerConnection
+    fn test_peer() -> crate::link_transport::PeerConnection {
+        crate::link_transport::PeerConnection {
+            peer_id: "test-peer".to_string(),
+            remote_addr: "127.0.0.1:9000".parse().unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_initiate_quic_call() {
+        let config = CallManagerConfig::default();
+        let call_manager = CallManager::<PeerIdentityString>::new(config)
+            .await
+            .unwrap();
+
+        let callee = PeerIdentityString::new("quic-callee");
+        let constraints = MediaConstraints::audio_only();
+        let peer = test_peer();
+
+        let call_id = call_manager
+            .initiate_quic_call(callee, constraints, peer)
+            .await
+            .unwrap();
+
+        // QUIC calls start in Connecting state (already connected to transport)
+        let state = call_manager.get_call_state(call_id).await;
+        assert_eq!(state, Some(CallState::Connecting));
+
+        // Verify media transport is set and connected
+        assert!(call_manager.has_media_transport(call_id).await);
+    }
+
+    #[tokio::test]
+    async fn test_initiate_quic_call_respects_max_concurrent() {
+        let config = CallManagerConfig {
+            max_concurrent_calls: 1,
+            ..Default::default()
+        };
+        let call_manager = CallManager::<PeerIdentityString>::new(config)
+            .await
+            .unwrap();
+
+        let callee = PeerIdentityString::new("callee");
+        let constraints = MediaConstraints::audio_only();
+
+        // First call should succeed
+        let _ = call_manager
+            .initiate_quic_call(callee.clone(), constraints.clone(), test_peer())
+            .await
+            .unwrap();
+
+        // Second call should fail due to limit
+        let result = call_manager
+            .initiate_quic_call(callee, constraints, test_peer())
+            .await;
+
+        assert!(matches!(result, Err(CallError::ConfigError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_connect_quic_transport() {
+        let config = CallManagerConfig::default();
+        let call_manager = CallManager::<PeerIdentityString>::new(config)
+            .await
+            .unwrap();
+
+        let callee = PeerIdentityString::new("callee");
+        let constraints = MediaConstraints::audio_only();
+
+        // Create a regular call (has unconnected media transport)
+        let call_id = call_manager
+            .initiate_call(callee, constraints)
+            .await
+            .unwrap();
+
+        // Connect the transport
+        let peer = test_peer();
+        let result = call_manager.connect_quic_transport(call_id, peer).await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_connect_quic_transport_call_not_found() {
+        let config = CallManagerConfig::default();
+        let call_manager = CallManager::<PeerIdentityString>::new(config)
+            .await
+            .unwrap();
+
+        let fake_call_id = CallId::new();
+        let peer = test_peer();
+
+        let result = call_manager
+            .connect_quic_transport(fake_call_id, peer)
+            .await;
+
+        assert!(matches!(result, Err(CallError::CallNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_end_call_with_quic_transport() {
+        let config = CallManagerConfig::default();
+        let call_manager = CallManager::<PeerIdentityString>::new(config)
+            .await
+            .unwrap();
+
+        let callee = PeerIdentityString::new("quic-callee");
+        let constraints = MediaConstraints::audio_only();
+        let peer = test_peer();
+
+        // Create a QUIC call (has connected transport)
+        let call_id = call_manager
+            .initiate_quic_call(callee, constraints, peer)
+            .await
+            .unwrap();
+
+        // Verify transport is present
+        assert!(call_manager.has_media_transport(call_id).await);
+
+        // End the call - should disconnect both transport and peer connection
+        let result = call_manager.end_call(call_id).await;
+        assert!(result.is_ok());
+
+        // Verify call is removed
+        let state = call_manager.get_call_state(call_id).await;
+        assert_eq!(state, None);
+    }
+
+    #[tokio::test]
+    async fn test_end_call_with_legacy_transport() {
+        let config = CallManagerConfig::default();
+        let call_manager = CallManager::<PeerIdentityString>::new(config)
+            .await
+            .unwrap();
+
+        let callee = PeerIdentityString::new("legacy-callee");
+        let constraints = MediaConstraints::audio_only();
+
+        // Create a legacy call (has unconnected transport)
+        let call_id = call_manager
+            .initiate_call(callee, constraints)
+            .await
+            .unwrap();
+
+        // End the call - should handle both transport paths gracefully
+        let result = call_manager.end_call(call_id).await;
+        assert!(result.is_ok());
+
+        // Verify call is removed
+        let state = call_manager.get_call_state(call_id).await;
+        assert_eq!(state, None);
+    }
+
+    #[tokio::test]
+    async fn test_update_state_from_transport() {
+        let config = CallManagerConfig::default();
+        let call_manager = CallManager::<PeerIdentityString>::new(config)
+            .await
+            .unwrap();
+
+        let callee = PeerIdentityString::new("callee");
+        let constraints = MediaConstraints::audio_only();
+        let peer = test_peer();
+
+        // Create a QUIC call (starts in Connecting since transport connects immediately)
+        let call_id = call_manager
+            .initiate_quic_call(callee, constraints, peer)
+            .await
+            .unwrap();
+
+        // Transport is connected, update should reflect Connected state
+        let new_state = call_manager.update_state_from_transport(call_id).await;
+        assert!(new_state.is_ok());
+        assert_eq!(new_state.unwrap(), CallState::Connected);
+
+        // Verify the call state was actually updated
+        let state = call_manager.get_call_state(call_id).await;
+        assert_eq!(state, Some(CallState::Connected));
+    }
+
+    #[tokio::test]
+    async fn test_update_state_from_transport_follows_state_machine() {
+        use crate::state_machine::transport_state_for_call;
+
+        let config = CallManagerConfig {
+            max_concurrent_calls: CallState::STATES.len() * MediaTransportState::STATES.len(),
+            ..Default::default()
+        };
+        let call_manager = CallManager::<PeerIdentityString>::new(config)
+            .await
+            .unwrap();
+        for &from in CallState::STATES {
+            for &transport_state in MediaTransportState::STATES {
+                let call_id = call_manager
+                    .initiate_quic_call(
+                        PeerIdentityString::new("callee"),
+                        MediaConstraints::audio_only(),
+                        test_peer(),
+                    )
+                    .await
+                    .unwrap();
+                let transport = {
+                    let entry = call_manager.call_entry(call_id).await.unwrap();
+                    let mut call = entry.lock().await;
+                    call.state = from;
+                    call.transport().cloned().unwrap()
+                };
+                *transport.state.write() = transport_state;
+
+                let expected = call_state_for_transport(from, transport_state);
+                let result = call_manager.update_state_from_transport(call_id).await;
+                if expected == from || from.can_transition_to(expected) {
+                    assert_eq!(
+                        *result.as_ref().unwrap(),
+                        expected,
+                        "{from:?} over {transport_state:?}"
+                    );
+                } else {
+                    assert!(
+                        matches!(result, Err(CallError::InvalidState)),
+                        "{from:?} over {transport_state:?}"
+                    );
+                    // Only a transport out of step with the call is refused
+                    assert_ne!(transport_state, transport_state_for_call(from));
+                }
+                assert_eq!(
+                    call_manager.get_call_state(call_id).await,
+                    Some(result.as_ref().copied().unwrap_or(from))
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reconnecting_call_ends_when_transport_disconnects() {
+        let call_manager = CallManager::<PeerIdentityString>::new(CallManagerConfig::default())
+            .await
+            .unwrap();
+        let call_id = call_manager
+            .initiate_quic_call(
+                PeerIdentityString::new("callee"),
+                MediaConstraints::audio_only(),
+                test_peer(),
+            )
+            .await
+            .unwrap();
+        let transport = {
+            let entry = call_manager.call_entry(call_id).await.unwrap();
+            let mut call = entry.lock().await;
+            call.state = CallState::Reconnecting;
+            call.transport().cloned().unwrap()
+        };
+        transport.disconnect().await.unwrap();
+
+        assert_eq!(
+            call_manager
+                .update_state_from_transport(call_id)
+                .await
+                .unwrap(),
+            CallState::Ending
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_check_liveness_reconnecting_then_failed() {
+        let config = CallManagerConfig {
+            keepalive: KeepaliveConfig {
+                interval: std::time::Duration::from_secs(1),
+                miss_threshold: 2,
+                failure_threshold: 4,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let call_manager = CallManager::<PeerIdentityString>::new(config)
+            .await
+            .unwrap();
+        let mut events = call_manager.subscribe_events();
+
+        let call_id = call_manager
+            .initiate_quic_call(
+                PeerIdentityString::new("callee"),
+                MediaConstraints::audio_only(),
+                test_peer(),
+            )
+            .await
+            .unwrap();
+        call_manager
+            .update_state_from_transport(call_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            call_manager.check_liveness(call_id).await.unwrap(),
+            CallState::Connected
+        );
+
+        tokio::time::advance(std::time::Duration::from_secs(2)).await;
+        assert_eq!(
+            call_manager.check_liveness(call_id).await.unwrap(),
+            CallState::Reconnecting
+        );
+
+        // Traffic from the peer recovers the call
+        let transport = {
+            let entry = call_manager.call_entry(call_id).await.unwrap();
+            let call = entry.lock().await;
+            call.transport().cloned().unwrap()
+        };
+        transport.record_rtcp_received(16).await;
+        assert_eq!(
+            call_manager.check_liveness(call_id).await.unwrap(),
+            CallState::Connected
+        );
+
+        tokio::time::advance(std::time::Duration::from_secs(4)).await;
+        assert_eq!(
+            call_manager.check_liveness(call_id).await.unwrap(),
+            CallState::Failed
+        );
+
+        let mut saw_reconnecting = false;
+        let mut saw_failed = false;
+        while let Ok(event) = events.try_recv() {
+            match event {
+                CallEvent::Reconnecting { missed, .. } => {
+                    assert_eq!(missed, 2);
+                    saw_reconnecting = true;
+                }
+                CallEvent::ConnectionFailed { .. } => saw_failed = true,
+                _ => {}
+            }
+        }
+        assert!(saw_reconnecting);
+        assert!(saw_failed);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_keepalive_task_fails_silent_peer() {
+        let config = CallManagerConfig {
+            keepalive: KeepaliveConfig {
+                interval: std::time::Duration::from_secs(1),
+                miss_threshold: 1,
+                failure_threshold: 3,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let call_manager = CallManager::<PeerIdentityString>::new(config)
+            .await
+            .unwrap();
+
+        let call_id = call_manager
+            .initiate_quic_call(
+                PeerIdentityString::new("callee"),
+                MediaConstraints::audio_only(),
+                test_peer(),
+            )
+            .await
+            .unwrap();
+        call_manager
+            .update_state_from_transport(call_id)
+            .await
+            .unwrap();
+
+        let handle = call_manager.start_keepalive(call_id).await.unwrap();
+        handle.await.unwrap();
+
+        assert_eq!(
+            call_manager.get_call_state(call_id).await,
+            Some(CallState::Failed)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tap_media_copies_sent_and_received_packets() {
+        let call_manager = CallManager::<PeerIdentityString>::new(CallManagerConfig::default())
+            .await
+            .unwrap();
+        assert!(call_manager
+            .tap_media(CallId::new(), RtpStreamType::Audio)
+            .await
+            .is_err());
+
+        let call_id = call_manager
+            .initiate_quic_call(
+                PeerIdentityString::new("callee"),
+                MediaConstraints::audio_only(),
+                test_peer(),
+            )
+            .await
+            .unwrap();
+        let transport = call_manager.media_transport(call_id).await.unwrap();
+        transport.set_media_gate(MediaGate::Open).await;
+        let mut tap = call_manager
+            .tap_media(call_id, RtpStreamType::Audio)
+            .await
+            .unwrap();
+
+        let sent = RtpPacket::new(111, 1, 960, 7, vec![0xAA; 20], RtpStreamType::Audio).unwrap();
+        transport
+            .send_rtp(StreamType::Audio, &sent.to_bytes().unwrap())
+            .await
+            .unwrap();
+        let received =
+            RtpPacket::new(111, 9, 960, 8, vec![0xBB; 20], RtpStreamType::Audio).unwrap();
+        transport
+            .deliver_rtp(StreamType::Audio, &received.to_bytes().unwrap())
+            .await;
+
+        assert_eq!(tap.recv().await.unwrap().ssrc, 7);
+        assert_eq!(tap.recv().await.unwrap().ssrc, 8);
+        assert_eq!(transport.stats().await.packets_received, 1);
+    }
+
+    #[tokio::test]
+    async fn test_media_dump_captures_until_call_end() {
+        let call_manager = CallManager::<PeerIdentityString>::new(CallManagerConfig::default())
+            .await
+            .unwrap();
+        let call_id = call_manager
+            .initiate_quic_call(
+                PeerIdentityString::new("callee"),
+                MediaConstraints::audio_only(),
+                test_peer(),
+            )
+            .await
+            .unwrap();
+        let transport = call_manager.media_transport(call_id).await.unwrap();
+        transport.set_media_gate(MediaGate::Open).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("call.pcapng");
+        call_manager.start_media_dump(call_id, &path).await.unwrap();
+
+        let packet = RtpPacket::new(111, 1, 960, 7, vec![0; 20], RtpStreamType::Audio).unwrap();
+        transport
+            .send_rtp(StreamType::Audio, &packet.to_bytes().unwrap())
+            .await
+            .unwrap();
+        transport.deliver_rtp(StreamType::Video, &[1, 2, 3]).await;
+
+        call_manager.end_call(call_id).await.unwrap();
+        assert_eq!(call_manager.stop_media_dump(call_id).await, None);
+
+        let capture = std::fs::read(&path).unwrap();
+        assert_eq!(&capture[..4], &[0x0A, 0x0D, 0x0D, 0x0A]);
+        let comments = String::from_utf8_lossy(&capture);
+        assert!(comments.contains("sent Audio"));
+        assert!(comments.contains("received Video"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_stats_history_survives_call_end() {
+        let call_manager = CallManager::<PeerIdentityString>::new(CallManagerConfig::default())
+            .await
+            .unwrap();
+        let call_id = call_manager
+            .initiate_quic_call(
+                PeerIdentityString::new("callee"),
+                MediaConstraints::audio_only(),
+                test_peer(),
+            )
+            .await
+            .unwrap();
+        let transport = call_manager.media_transport(call_id).await.unwrap();
+
+        let handle = call_manager.start_stats_history(call_id).await.unwrap();
+        for _ in 0..3 {
+            transport.record_sent(StreamType::Audio, 100).await;
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        }
+        call_manager.end_call(call_id).await.unwrap();
+        handle.await.unwrap();
+
+        let samples = call_manager.stats_history(call_id).unwrap();
+        assert!(samples.len() >= 3);
+        assert_eq!(samples.last().map(|s| s.packets_sent), Some(3));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("call.csv");
+        call_manager.export_stats_csv(call_id, &path).unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(csv.lines().count(), samples.len() + 1);
+
+        assert!(matches!(
+            call_manager.export_stats_json(CallId::new(), &path),
+            Err(StatsHistoryError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_stats_subscription_sends_deltas() {
+        let call_manager = CallManager::<PeerIdentityString>::new(CallManagerConfig::default())
+            .await
+            .unwrap();
+        let call_id = call_manager
+            .initiate_quic_call(
+                PeerIdentityString::new("callee"),
+                MediaConstraints::audio_only(),
+                test_peer(),
+            )
+            .await
+            .unwrap();
+        let transport = call_manager.media_transport(call_id).await.unwrap();
+        let second = std::time::Duration::from_secs(1);
+
+        let mut deltas = call_manager.subscribe_stats(call_id, second).await.unwrap();
+        transport.record_sent(StreamType::Audio, 100).await;
+        transport.record_sent(StreamType::Audio, 100).await;
+        let first = deltas.recv().await.unwrap();
+        assert_eq!((first.packets_sent, first.bytes_sent), (2, 200));
+        assert_eq!((first.at, first.elapsed), (second, second));
+
+        // Idle samples are skipped and folded into the next delta
+        tokio::time::sleep(2 * second).await;
+        transport.record_sent(StreamType::Audio, 50).await;
+        let next = deltas.recv().await.unwrap();
+        assert_eq!((next.packets_sent, next.bytes_sent), (1, 50));
+        assert_eq!(next.at - first.at, next.elapsed);
+        assert!(next.elapsed >= 2 * second);
+
+        call_manager.end_call(call_id).await.unwrap();
+        assert!(deltas.recv().await.is_none());
+        assert!(call_manager.subscribe_stats(call_id, second).await.is_err());
     }
 
-    /// Get current call information
-    ///
-    /// Returns a snapshot of the call's current state, constraints, and
-    /// transport status.
-    ///
-    /// # Arguments
-    ///
-    /// * `call_id` - The call to query
-    ///
-    /// # Returns
-    ///
-    /// Returns `None` if call not found.
-    pub async fn get_call_info(
-        &self,
-        call_id: CallId,
-    ) -> Option<(CallState, MediaConstraints, bool)> {
-        let calls = self.calls.read().await;
-        calls.get(&call_id).map(|call| {
-            (
-                call.state,
-                call.constraints.clone(),
-                call.media_transport.is_some(),
+    #[tokio::test]
+    async fn test_media_prepared_while_ringing() {
+        let call_manager = CallManager::<PeerIdentityString>::new(CallManagerConfig::default())
+            .await
+            .unwrap();
+        let mut events = call_manager.subscribe_events();
+        let call_id = call_manager
+            .initiate_quic_call(
+                PeerIdentityString::new("callee"),
+                MediaConstraints::video_call(),
+                test_peer(),
             )
-        })
+            .await
+            .unwrap();
+        let transport = call_manager.media_transport(call_id).await.unwrap();
+        assert_eq!(transport.open_stream_count().await, 0);
+
+        call_manager.exchange_capabilities(call_id).await.unwrap();
+        let opened = transport.open_stream_types().await;
+        assert!(opened.contains(&StreamType::Audio));
+        assert!(opened.contains(&StreamType::Video));
+        assert!(!opened.contains(&StreamType::Screen));
+        // Nothing goes out before the call is accepted
+        assert_eq!(transport.media_gate().await, MediaGate::Closed);
+
+        let prepared = loop {
+            if let CallEvent::MediaPrepared {
+                call_id: id,
+                capture,
+                ..
+            } = events.recv().await.unwrap()
+            {
+                break (id, capture);
+            }
+        };
+        assert_eq!(prepared, (call_id, false));
     }
-}
-
-#[cfg(test)]
-#[allow(clippy::unwrap_used)]
-mod tests {
-    use super::*;
-    use crate::identity::PeerIdentityString;
 
     #[tokio::test]
-    async fn test_call_manager_initiate_call() {
-        let config = CallManagerConfig::default();
-        let call_manager = CallManager::<PeerIdentityString>::new(config)
+    async fn test_repeat_call_seeded_from_last_call() {
+        let call_manager = CallManager::<PeerIdentityString>::new(CallManagerConfig::default())
+            .await
+            .unwrap();
+        let alice = PeerIdentityString::new("alice");
+        let first = call_manager
+            .initiate_quic_call(alice.clone(), MediaConstraints::audio_only(), test_peer())
             .await
             .unwrap();
+        assert_eq!(call_manager.quality_seed(first).await, None);
 
-        let callee = PeerIdentityString::new("callee");
-        let constraints = MediaConstraints::audio_only();
+        let mut sample = StatsSample::from_stats(&TransportStats::default());
+        sample.send_bitrate_bps = 40_000.0;
+        for _ in 0..5 {
+            call_manager.stats_history.record(first, sample.clone());
+        }
+        call_manager.end_call(first).await.unwrap();
 
-        let call_id = call_manager
-            .initiate_call(callee, constraints)
+        let second = call_manager
+            .initiate_quic_call(alice, MediaConstraints::audio_only(), test_peer())
             .await
             .unwrap();
+        let seed = call_manager.quality_seed(second).await.unwrap();
+        assert_eq!(seed.bitrate_kbps, 32);
+        assert_eq!(seed.codec, codec_mime::OPUS);
+        let ramp = call_manager.startup_ramp(second).await.unwrap();
+        assert_eq!(ramp.start_kbps(), 32);
 
-        let state = call_manager.get_call_state(call_id).await;
-        assert_eq!(state, Some(CallState::Calling));
+        // The audio starts within what the path carried last time
+        call_manager
+            .confirm_connection(second, MediaCapabilities::audio_only())
+            .await
+            .unwrap();
+        let audio = call_manager.negotiated_audio(second).await.unwrap();
+        assert_eq!(audio.target_bitrate_bps, 32_000);
+
+        let stranger = call_manager
+            .initiate_quic_call(
+                PeerIdentityString::new("bob"),
+                MediaConstraints::audio_only(),
+                test_peer(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(call_manager.quality_seed(stranger).await, None);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_calls_do_not_serialize() {
+        const CALLS: usize = 100;
+
+        let config = CallManagerConfig {
+            max_concurrent_calls: CALLS,
+            ..Default::default()
+        };
+        let call_manager = Arc::new(
+            CallManager::<PeerIdentityString>::new(config)
+                .await
+                .unwrap(),
+        );
+
+        let mut call_ids = Vec::with_capacity(CALLS);
+        for i in 0..CALLS {
+            let call_id = call_manager
+                .initiate_quic_call(
+                    PeerIdentityString::new(format!("callee-{i}")),
+                    MediaConstraints::audio_only(),
+                    test_peer(),
+                )
+                .await
+                .unwrap();
+            call_ids.push(call_id);
+        }
+        assert!(call_manager
+            .initiate_quic_call(
+                PeerIdentityString::new("one-too-many"),
+                MediaConstraints::audio_only(),
+                test_peer(),
+            )
+            .await
+            .is_err());
+
+        // Simulate a long-running operation holding the first call's lock;
+        // operations on the other calls must still complete
+        let blocked = call_manager.call_entry(call_ids[0]).await.unwrap();
+        let guard = blocked.lock().await;
+
+        let mut tasks = tokio::task::JoinSet::new();
+        for &call_id in &call_ids[1..] {
+            let call_manager = Arc::clone(&call_manager);
+            tasks.spawn(async move {
+                let caps = call_manager.exchange_capabilities(call_id).await?;
+                call_manager.confirm_connection(call_id, caps).await?;
+                call_manager.check_liveness(call_id).await
+            });
+        }
+
+        let results = tokio::time::timeout(std::time::Duration::from_secs(10), async {
+            let mut states = Vec::new();
+            while let Some(result) = tasks.join_next().await {
+                states.push(result.unwrap());
+            }
+            states
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(results.len(), CALLS - 1);
+        assert!(results
+            .iter()
+            .all(|state| matches!(state, Ok(CallState::Connected))));
+
+        drop(guard);
+        assert_eq!(
+            call_manager.get_call_state(call_ids[0]).await,
+            Some(CallState::Connecting)
+        );
+        for call_id in call_ids {
+            call_manager.end_call(call_id).await.unwrap();
+        }
     }
 
     #[tokio::test]
-    async fn test_call_manager_initiate_call_creates_media_transport() {
+    async fn test_update_state_from_transport_not_found() {
         let config = CallManagerConfig::default();
         let call_manager = CallManager::<PeerIdentityString>::new(config)
             .await
             .unwrap();
 
-        let callee = PeerIdentityString::new("callee");
-        let constraints = MediaConstraints::audio_only();
+        let fake_call_id = CallId::new();
+        let result = call_manager.update_state_from_transport(fake_call_id).await;
 
-        let call_id = call_manager
-            .initiate_call(callee, constraints)
-            .await
-            .unwrap();
+        assert!(matches!(result, Err(CallError::CallNotFound(_))));
+    }
 
-        // Verify QuicMediaTransport is created for new calls
+    #[test]
+    fn test_valid_quic_transitions() {
+        // Valid transitions
+        assert!(CallManager::<PeerIdentityString>::is_valid_quic_transition(
+            CallState::Idle,
+            CallState::Calling
+        ));
+        assert!(CallManager::<PeerIdentityString>::is_valid_quic_transition(
+            CallState::Idle,
+            CallState::Connecting
+        ));
+        assert!(CallManager::<PeerIdentityString>::is_valid_quic_transition(
+            CallState::Calling,
+            CallState::Connecting
+        ));
+        assert!(CallManager::<PeerIdentityString>::is_valid_quic_transition(
+            CallState::Connecting,
+            CallState::Connected
+        ));
+        assert!(CallManager::<PeerIdentityString>::is_valid_quic_transition(
+            CallState::Connected,
+            CallState::Ending
+        ));
+        assert!(CallManager::<PeerIdentityString>::is_valid_quic_transition(
+            CallState::Ending,
+            CallState::Idle
+        ));
+
+        // Failure transitions
+        assert!(CallManager::<PeerIdentityString>::is_valid_quic_transition(
+            CallState::Calling,
+            CallState::Failed
+        ));
+        assert!(CallManager::<PeerIdentityString>::is_valid_quic_transition(
+            CallState::Connecting,
+            CallState::Failed
+        ));
+        assert!(CallManager::<PeerIdentityString>::is_valid_quic_transition(
+            CallState::Connected,
+            CallState::Failed
+        ));
+
+        // Recovery
+        assert!(CallManager::<PeerIdentityString>::is_valid_quic_transition(
+            CallState::Failed,
+            CallState::Idle
+        ));
+
+        assert!(CallManager::<PeerIdentityString>::is_valid_quic_transition(
+            CallState::Connected,
+            CallState::Reconnecting
+        ));
+        assert!(CallManager::<PeerIdentityString>::is_valid_quic_transition(
+            CallState::Reconnecting,
+            CallState::Connected
+        ));
+        assert!(CallManager::<PeerIdentityString>::is_valid_quic_transition(
+            CallState::Reconnecting,
+            CallState::Failed
+        ));
+
+        // Invalid transitions
         assert!(
-            call_manager.has_media_transport(call_id).await,
-            "New calls should have QuicMediaTransport initialized"
+            !CallManager::<PeerIdentityString>::is_valid_quic_transition(
+                CallState::Idle,
+                CallState::Connected
+            )
+        );
+        assert!(
+            !CallManager::<PeerIdentityString>::is_valid_quic_transition(
+                CallState::Connected,
+                CallState::Calling
+            )
+        );
+        assert!(
+            !CallManager::<PeerIdentityString>::is_valid_quic_transition(
+                CallState::Ending,
+                CallState::Connected
+            )
         );
     }
 
     #[tokio::test]
-    async fn test_call_manager_accept_call() {
+    async fn test_exchange_capabilities() {
         let config = CallManagerConfig::default();
         let call_manager = CallManager::<PeerIdentityString>::new(config)
             .await
             .unwrap();
 
         let callee = PeerIdentityString::new("callee");
-        let constraints = MediaConstraints::audio_only();
+        let constraints = MediaConstraints::video_call();
 
         let call_id = call_manager
-            .initiate_call(callee, constraints.clone())
+            .initiate_call(callee, constraints)
             .await
             .unwrap();
 
-        call_manager
-            .accept_call(call_id, constraints)
-            .await
-            .unwrap();
+        // Exchange capabilities
+        let capabilities = call_manager.exchange_capabilities(call_id).await.unwrap();
+
+        // Verify capabilities match constraints
+        assert!(capabilities.audio);
+        assert!(capabilities.video);
+        assert!(!capabilities.data_channel);
+        assert!(capabilities.max_bandwidth_kbps > 0);
 
+        // Verify state transitioned to Connecting
         let state = call_manager.get_call_state(call_id).await;
-        assert_eq!(state, Some(CallState::Connected));
+        assert_eq!(state, Some(CallState::Connecting));
     }
 
     #[tokio::test]
-    async fn test_call_manager_reject_call() {
+    async fn test_exchange_capabilities_audio_only() {
         let config = CallManagerConfig::default();
         let call_manager = CallManager::<PeerIdentityString>::new(config)
             .await
@@ -1249,60 +4702,71 @@ mod tests {
             .await
             .unwrap();
 
-        call_manager.reject_call(call_id).await.unwrap();
+        let capabilities = call_manager.exchange_capabilities(call_id).await.unwrap();
 
-        let state = call_manager.get_call_state(call_id).await;
-        assert_eq!(state, Some(CallState::Failed));
+        assert!(capabilities.audio);
+        assert!(!capabilities.video);
+        assert_eq!(capabilities.max_bandwidth_kbps, 128);
     }
 
     #[tokio::test]
-    async fn test_call_manager_end_call() {
-        let config = CallManagerConfig::default();
+    async fn test_latency_profile_from_constraints_or_default() {
+        let config = CallManagerConfig {
+            latency: LatencyProfile::Quality,
+            ..Default::default()
+        };
         let call_manager = CallManager::<PeerIdentityString>::new(config)
             .await
             .unwrap();
 
-        let callee = PeerIdentityString::new("callee");
-        let constraints = MediaConstraints::audio_only();
-
-        let call_id = call_manager
-            .initiate_call(callee, constraints)
+        let interactive = call_manager
+            .initiate_call(
+                PeerIdentityString::new("alice"),
+                MediaConstraints::video_call().with_latency(LatencyProfile::Interactive),
+            )
+            .await
+            .unwrap();
+        let default = call_manager
+            .initiate_call(
+                PeerIdentityString::new("bob"),
+                MediaConstraints::video_call(),
+            )
             .await
             .unwrap();
 
-        call_manager.end_call(call_id).await.unwrap();
-
-        let state = call_manager.get_call_state(call_id).await;
-        assert_eq!(state, None);
+        let tuning = call_manager.latency_tuning(interactive).await.unwrap();
+        assert_eq!(tuning, LatencyProfile::Interactive.tuning());
+        assert_eq!(tuning.max_b_frames, 0);
+        assert_eq!(
+            call_manager.latency_profile(default).await,
+            Some(LatencyProfile::Quality)
+        );
+        assert!(
+            call_manager
+                .latency_tuning(default)
+                .await
+                .unwrap()
+                .jitter_target_ms
+                > tuning.jitter_target_ms
+        );
+        assert!(call_manager.latency_tuning(CallId::new()).await.is_none());
     }
 
     #[tokio::test]
-    #[allow(deprecated)]
-    async fn test_call_manager_create_offer_legacy() {
-        // Tests legacy SDP offer creation (deprecated for QUIC-native calls)
+    async fn test_exchange_capabilities_not_found() {
         let config = CallManagerConfig::default();
         let call_manager = CallManager::<PeerIdentityString>::new(config)
             .await
             .unwrap();
 
-        let callee = PeerIdentityString::new("callee");
-        let constraints = MediaConstraints::audio_only();
-
-        let _call_id = call_manager
-            .initiate_call(callee, constraints)
-            .await
-            .unwrap();
+        let fake_call_id = CallId::new();
+        let result = call_manager.exchange_capabilities(fake_call_id).await;
 
-        // Skip the offer creation test for now since it requires proper codec setup
-        // This would need more complex WebRTC setup
-        // let offer = call_manager.create_offer(call_id).await.unwrap();
-        // assert!(!offer.is_empty());
-        // assert!(offer.contains("v=0"));
+        assert!(matches!(result, Err(CallError::CallNotFound(_))));
     }
 
     #[tokio::test]
-    #[allow(deprecated)]
-    async fn test_call_manager_add_ice_candidate_legacy() {
+    async fn test_exchange_capabilities_invalid_state() {
         let config = CallManagerConfig::default();
         let call_manager = CallManager::<PeerIdentityString>::new(config)
             .await
@@ -1312,21 +4776,24 @@ mod tests {
         let constraints = MediaConstraints::audio_only();
 
         let call_id = call_manager
-            .initiate_call(callee, constraints)
+            .initiate_call(callee, constraints.clone())
             .await
             .unwrap();
 
-        // Test with a dummy ICE candidate (legacy WebRTC only)
-        let candidate = "candidate:1 1 UDP 2122260223 192.168.1.1 12345 typ host".to_string();
-        let result = call_manager.add_ice_candidate(call_id, candidate).await;
-        // This might fail in test environment, but should not panic
-        // We just test that the method exists and handles call not found
-        assert!(result.is_ok() || matches!(result, Err(CallError::ConfigError(_))));
+        // Accept the call to move it to Connected state
+        call_manager
+            .accept_call(call_id, constraints)
+            .await
+            .unwrap();
+
+        // Try to exchange capabilities in Connected state - should fail
+        let result = call_manager.exchange_capabilities(call_id).await;
+
+        assert!(matches!(result, Err(CallError::InvalidState)));
     }
 
     #[tokio::test]
-    #[allow(deprecated)]
-    async fn test_call_manager_start_ice_gathering_legacy() {
+    async fn test_confirm_connection() {
         let config = CallManagerConfig::default();
         let call_manager = CallManager::<PeerIdentityString>::new(config)
             .await
@@ -1334,76 +4801,73 @@ mod tests {
 
         let callee = PeerIdentityString::new("callee");
         let constraints = MediaConstraints::audio_only();
+        let peer = test_peer();
 
+        // Create a QUIC call (starts in Connecting with connected transport)
         let call_id = call_manager
-            .initiate_call(callee, constraints)
+            .initiate_quic_call(callee, constraints, peer)
             .await
             .unwrap();
 
-        // Legacy WebRTC ICE gathering
-        let result = call_manager.start_ice_gathering(call_id).await;
-        // This might fail in test environment, but should not panic
-        assert!(result.is_ok() || matches!(result, Err(CallError::ConfigError(_))));
+        // Confirm connection with matching capabilities
+        let peer_caps = MediaCapabilities::audio_only();
+        let result = call_manager.confirm_connection(call_id, peer_caps).await;
+
+        assert!(result.is_ok());
+
+        // Verify state is Connected
+        let state = call_manager.get_call_state(call_id).await;
+        assert_eq!(state, Some(CallState::Connected));
     }
 
     #[tokio::test]
-    async fn test_call_manager_call_not_found() {
-        let config = CallManagerConfig::default();
+    async fn test_confirm_connection_negotiates_audio() {
+        let config = CallManagerConfig {
+            audio: AudioParameters::stereo(),
+            ..Default::default()
+        };
         let call_manager = CallManager::<PeerIdentityString>::new(config)
             .await
             .unwrap();
 
-        let fake_call_id = CallId::new();
-
-        let result = call_manager
-            .accept_call(fake_call_id, MediaConstraints::audio_only())
-            .await;
-        assert!(matches!(result, Err(CallError::CallNotFound(_))));
-
-        let result = call_manager.reject_call(fake_call_id).await;
-        assert!(matches!(result, Err(CallError::CallNotFound(_))));
-
-        let result = call_manager.end_call(fake_call_id).await;
-        assert!(matches!(result, Err(CallError::CallNotFound(_))));
-
-        #[allow(deprecated)]
-        let result = call_manager.create_offer(fake_call_id).await;
-        assert!(matches!(result, Err(CallError::CallNotFound(_))));
-
-        #[allow(deprecated)]
-        let result = call_manager
-            .handle_answer(fake_call_id, "dummy".to_string())
-            .await;
-        assert!(matches!(result, Err(CallError::CallNotFound(_))));
-
-        #[allow(deprecated)]
-        let result = call_manager
-            .add_ice_candidate(fake_call_id, "dummy".to_string())
-            .await;
-        assert!(matches!(result, Err(CallError::CallNotFound(_))));
-
-        #[allow(deprecated)]
-        let result = call_manager.start_ice_gathering(fake_call_id).await;
-        assert!(matches!(result, Err(CallError::CallNotFound(_))));
-    }
+        let call_id = call_manager
+            .initiate_quic_call(
+                PeerIdentityString::new("callee"),
+                MediaConstraints::audio_only(),
+                test_peer(),
+            )
+            .await
+            .unwrap();
+        let offered = call_manager.exchange_capabilities(call_id).await.unwrap();
+        assert_eq!(offered.audio_params, AudioParameters::stereo());
+        assert_eq!(call_manager.negotiated_audio(call_id).await, None);
+
+        let peer_caps = MediaCapabilities::audio_only().with_audio_params(AudioParameters {
+            channels: 2,
+            max_sample_rate_hz: 16_000,
+            target_bitrate_bps: 32_000,
+            redundancy: 0,
+        });
+        call_manager
+            .confirm_connection(call_id, peer_caps)
+            .await
+            .unwrap();
 
-    /// Helper to create a test PeerConnection
-    fn test_peer() -> crate::link_transport::PeerConnection {
-        crate::link_transport::PeerConnection {
-            peer_id: "test-peer".to_string(),
-            remote_addr: "127.0.0.1:9000".parse().unwrap(),
-        }
+        let agreed = call_manager.negotiated_audio(call_id).await.unwrap();
+        assert_eq!(agreed.channels, 2);
+        assert_eq!(agreed.max_sample_rate_hz, 16_000);
+        assert_eq!(agreed.target_bitrate_bps, 32_000);
     }
 
     #[tokio::test]
-    async fn test_initiate_quic_call() {
+    async fn test_confirm_connection_incompatible_caps() {
         let config = CallManagerConfig::default();
         let call_manager = CallManager::<PeerIdentityString>::new(config)
             .await
             .unwrap();
 
-        let callee = PeerIdentityString::new("quic-callee");
-        let constraints = MediaConstraints::audio_only();
+        let callee = PeerIdentityString::new("callee");
+        let constraints = MediaConstraints::video_call(); // Requires video
         let peer = test_peer();
 
         let call_id = call_manager
@@ -1411,42 +4875,32 @@ mod tests {
             .await
             .unwrap();
 
-        // QUIC calls start in Connecting state (already connected to transport)
-        let state = call_manager.get_call_state(call_id).await;
-        assert_eq!(state, Some(CallState::Connecting));
+        // Try to confirm with audio-only capabilities (missing video)
+        let peer_caps = MediaCapabilities::audio_only();
+        let result = call_manager.confirm_connection(call_id, peer_caps).await;
 
-        // Verify media transport is set and connected
-        assert!(call_manager.has_media_transport(call_id).await);
+        assert!(matches!(result, Err(CallError::ConfigError(_))));
     }
 
     #[tokio::test]
-    async fn test_initiate_quic_call_respects_max_concurrent() {
-        let config = CallManagerConfig {
-            max_concurrent_calls: 1,
-        };
+    async fn test_confirm_connection_not_found() {
+        let config = CallManagerConfig::default();
         let call_manager = CallManager::<PeerIdentityString>::new(config)
             .await
             .unwrap();
 
-        let callee = PeerIdentityString::new("callee");
-        let constraints = MediaConstraints::audio_only();
-
-        // First call should succeed
-        let _ = call_manager
-            .initiate_quic_call(callee.clone(), constraints.clone(), test_peer())
-            .await
-            .unwrap();
+        let fake_call_id = CallId::new();
+        let peer_caps = MediaCapabilities::audio_only();
 
-        // Second call should fail due to limit
         let result = call_manager
-            .initiate_quic_call(callee, constraints, test_peer())
+            .confirm_connection(fake_call_id, peer_caps)
             .await;
 
-        assert!(matches!(result, Err(CallError::ConfigError(_))));
+        assert!(matches!(result, Err(CallError::CallNotFound(_))));
     }
 
     #[tokio::test]
-    async fn test_connect_quic_transport() {
+    async fn test_confirm_connection_invalid_state() {
         let config = CallManagerConfig::default();
         let call_manager = CallManager::<PeerIdentityString>::new(config)
             .await
@@ -1455,265 +4909,307 @@ mod tests {
         let callee = PeerIdentityString::new("callee");
         let constraints = MediaConstraints::audio_only();
 
-        // Create a regular call (has unconnected media transport)
+        // Create a regular call (not QUIC, starts in Calling state)
         let call_id = call_manager
             .initiate_call(callee, constraints)
             .await
             .unwrap();
 
-        // Connect the transport
-        let peer = test_peer();
-        let result = call_manager.connect_quic_transport(call_id, peer).await;
+        // Try to confirm connection in Calling state - should fail
+        let peer_caps = MediaCapabilities::audio_only();
+        let result = call_manager.confirm_connection(call_id, peer_caps).await;
 
-        assert!(result.is_ok());
+        assert!(matches!(result, Err(CallError::InvalidState)));
     }
 
     #[tokio::test]
-    async fn test_connect_quic_transport_call_not_found() {
+    async fn test_confirm_connection_emits_event() {
         let config = CallManagerConfig::default();
         let call_manager = CallManager::<PeerIdentityString>::new(config)
             .await
             .unwrap();
 
-        let fake_call_id = CallId::new();
+        // Subscribe to events before initiating call
+        let mut event_rx = call_manager.subscribe_events();
+
+        let callee = PeerIdentityString::new("callee");
+        let constraints = MediaConstraints::audio_only();
         let peer = test_peer();
 
-        let result = call_manager
-            .connect_quic_transport(fake_call_id, peer)
-            .await;
+        let call_id = call_manager
+            .initiate_quic_call(callee, constraints, peer)
+            .await
+            .unwrap();
 
-        assert!(matches!(result, Err(CallError::CallNotFound(_))));
-    }
+        // Drain the CallInitiated event
+        let _ = event_rx.try_recv();
 
-    #[tokio::test]
-    async fn test_end_call_with_quic_transport() {
-        let config = CallManagerConfig::default();
-        let call_manager = CallManager::<PeerIdentityString>::new(config)
+        // Confirm connection
+        let peer_caps = MediaCapabilities::audio_only();
+        call_manager
+            .confirm_connection(call_id, peer_caps)
             .await
             .unwrap();
 
-        let callee = PeerIdentityString::new("quic-callee");
+        // Verify ConnectionEstablished event was emitted
+        let event = event_rx.try_recv();
+        assert!(event.is_ok());
+
+        match event {
+            Ok(CallEvent::ConnectionEstablished { call_id: eid }) => {
+                assert_eq!(eid, call_id);
+            }
+            other => {
+                unreachable!("Expected ConnectionEstablished event, got: {:?}", other);
+            }
+        }
+    }
+
+    #[test]
+    fn test_validate_remote_capabilities_audio_only() {
         let constraints = MediaConstraints::audio_only();
-        let peer = test_peer();
+        let caps = MediaCapabilities::audio_only();
 
-        // Create a QUIC call (has connected transport)
-        let call_id = call_manager
-            .initiate_quic_call(callee, constraints, peer)
-            .await
-            .unwrap();
+        let result =
+            CallManager::<PeerIdentityString>::validate_remote_capabilities(&constraints, &caps);
+        assert!(result.is_ok());
+    }
 
-        // Verify transport is present
-        assert!(call_manager.has_media_transport(call_id).await);
+    #[test]
+    fn test_validate_remote_capabilities_video_call() {
+        let constraints = MediaConstraints::video_call();
+        let video_caps = MediaCapabilities::video();
+        let audio_caps = MediaCapabilities::audio_only();
 
-        // End the call - should disconnect both transport and peer connection
-        let result = call_manager.end_call(call_id).await;
+        // Video caps should satisfy video constraints
+        let result = CallManager::<PeerIdentityString>::validate_remote_capabilities(
+            &constraints,
+            &video_caps,
+        );
         assert!(result.is_ok());
 
-        // Verify call is removed
-        let state = call_manager.get_call_state(call_id).await;
-        assert_eq!(state, None);
+        // Audio-only caps should NOT satisfy video constraints
+        let result = CallManager::<PeerIdentityString>::validate_remote_capabilities(
+            &constraints,
+            &audio_caps,
+        );
+        assert!(matches!(result, Err(CallError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_validate_remote_capabilities_missing_audio() {
+        let constraints = MediaConstraints::video_call(); // requires audio
+        let caps = MediaCapabilities {
+            audio: false,
+            video: true,
+            data_channel: false,
+            max_bandwidth_kbps: 2500,
+            ..Default::default()
+        };
+
+        let result =
+            CallManager::<PeerIdentityString>::validate_remote_capabilities(&constraints, &caps);
+        assert!(matches!(result, Err(CallError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_validate_remote_capabilities_screen_share() {
+        let constraints = MediaConstraints::screen_share();
+        let caps = MediaCapabilities::video(); // screen share requires video capability
+
+        let result =
+            CallManager::<PeerIdentityString>::validate_remote_capabilities(&constraints, &caps);
+        assert!(result.is_ok());
+
+        // Audio-only caps should NOT satisfy screen share (needs video)
+        let audio_caps = MediaCapabilities::audio_only();
+        let result = CallManager::<PeerIdentityString>::validate_remote_capabilities(
+            &constraints,
+            &audio_caps,
+        );
+        assert!(matches!(result, Err(CallError::ConfigError(_))));
     }
 
+    /// Test that verifies PeerIdentity type safety is preserved across all methods
     #[tokio::test]
-    async fn test_end_call_with_legacy_transport() {
+    async fn test_peer_identity_type_safety() {
         let config = CallManagerConfig::default();
         let call_manager = CallManager::<PeerIdentityString>::new(config)
             .await
             .unwrap();
 
-        let callee = PeerIdentityString::new("legacy-callee");
+        // Subscribe to events - should receive CallEvent<PeerIdentityString>
+        let mut event_rx = call_manager.subscribe_events();
+
+        let callee = PeerIdentityString::new("typed-callee");
         let constraints = MediaConstraints::audio_only();
+        let peer = test_peer();
 
-        // Create a legacy call (has unconnected transport)
+        // Initiate QUIC call - callee is typed as PeerIdentityString
         let call_id = call_manager
-            .initiate_call(callee, constraints)
+            .initiate_quic_call(callee.clone(), constraints, peer)
             .await
             .unwrap();
 
-        // End the call - should handle both transport paths gracefully
-        let result = call_manager.end_call(call_id).await;
-        assert!(result.is_ok());
+        // Receive event - should be properly typed
+        let event = event_rx.try_recv();
+        assert!(event.is_ok());
+
+        // Verify event contains the correctly typed callee identity
+        match event {
+            Ok(CallEvent::CallInitiated {
+                callee: event_callee,
+                ..
+            }) => {
+                // This line compiles because event_callee is PeerIdentityString
+                assert_eq!(event_callee.to_string_repr(), callee.to_string_repr());
+            }
+            other => {
+                // Test assertion - use unreachable since tests are allowed panics
+                unreachable!("Expected CallInitiated event, got: {:?}", other);
+            }
+        }
 
-        // Verify call is removed
-        let state = call_manager.get_call_state(call_id).await;
-        assert_eq!(state, None);
+        // End call cleanly
+        call_manager.end_call(call_id).await.unwrap();
     }
 
     #[tokio::test]
-    async fn test_update_state_from_transport() {
+    async fn test_fail_call() {
         let config = CallManagerConfig::default();
         let call_manager = CallManager::<PeerIdentityString>::new(config)
             .await
             .unwrap();
 
+        let mut event_rx = call_manager.subscribe_events();
+
         let callee = PeerIdentityString::new("callee");
         let constraints = MediaConstraints::audio_only();
         let peer = test_peer();
 
-        // Create a QUIC call (starts in Connecting since transport connects immediately)
         let call_id = call_manager
             .initiate_quic_call(callee, constraints, peer)
             .await
             .unwrap();
 
-        // Transport is connected, update should reflect Connected state
-        let new_state = call_manager.update_state_from_transport(call_id).await;
-        assert!(new_state.is_ok());
-        assert_eq!(new_state.unwrap(), CallState::Connected);
+        // Drain CallInitiated event
+        let _ = event_rx.try_recv();
 
-        // Verify the call state was actually updated
+        // Fail the call
+        let result = call_manager
+            .fail_call(call_id, "Network error".to_string())
+            .await;
+        assert!(result.is_ok());
+
+        // Verify state is Failed
         let state = call_manager.get_call_state(call_id).await;
-        assert_eq!(state, Some(CallState::Connected));
+        assert_eq!(state, Some(CallState::Failed));
+
+        // Verify ConnectionFailed event was emitted
+        let event = event_rx.try_recv();
+        assert!(event.is_ok());
+
+        match event {
+            Ok(CallEvent::ConnectionFailed {
+                call_id: eid,
+                error,
+            }) => {
+                assert_eq!(eid, call_id);
+                assert_eq!(error, "Network error");
+            }
+            other => {
+                unreachable!("Expected ConnectionFailed event, got: {:?}", other);
+            }
+        }
     }
 
     #[tokio::test]
-    async fn test_update_state_from_transport_not_found() {
+    async fn test_fail_call_not_found() {
         let config = CallManagerConfig::default();
         let call_manager = CallManager::<PeerIdentityString>::new(config)
             .await
             .unwrap();
 
         let fake_call_id = CallId::new();
-        let result = call_manager.update_state_from_transport(fake_call_id).await;
+        let result = call_manager
+            .fail_call(fake_call_id, "Error".to_string())
+            .await;
 
         assert!(matches!(result, Err(CallError::CallNotFound(_))));
     }
 
-    #[test]
-    fn test_valid_quic_transitions() {
-        // Valid transitions
-        assert!(CallManager::<PeerIdentityString>::is_valid_quic_transition(
-            CallState::Idle,
-            CallState::Calling
-        ));
-        assert!(CallManager::<PeerIdentityString>::is_valid_quic_transition(
-            CallState::Idle,
-            CallState::Connecting
-        ));
-        assert!(CallManager::<PeerIdentityString>::is_valid_quic_transition(
-            CallState::Calling,
-            CallState::Connecting
-        ));
-        assert!(CallManager::<PeerIdentityString>::is_valid_quic_transition(
-            CallState::Connecting,
-            CallState::Connected
-        ));
-        assert!(CallManager::<PeerIdentityString>::is_valid_quic_transition(
-            CallState::Connected,
-            CallState::Ending
-        ));
-        assert!(CallManager::<PeerIdentityString>::is_valid_quic_transition(
-            CallState::Ending,
-            CallState::Idle
-        ));
-
-        // Failure transitions
-        assert!(CallManager::<PeerIdentityString>::is_valid_quic_transition(
-            CallState::Calling,
-            CallState::Failed
-        ));
-        assert!(CallManager::<PeerIdentityString>::is_valid_quic_transition(
-            CallState::Connecting,
-            CallState::Failed
-        ));
-        assert!(CallManager::<PeerIdentityString>::is_valid_quic_transition(
-            CallState::Connected,
-            CallState::Failed
-        ));
-
-        // Recovery
-        assert!(CallManager::<PeerIdentityString>::is_valid_quic_transition(
-            CallState::Failed,
-            CallState::Idle
-        ));
-
-        // Invalid transitions
-        assert!(
-            !CallManager::<PeerIdentityString>::is_valid_quic_transition(
-                CallState::Idle,
-                CallState::Connected
-            )
-        );
-        assert!(
-            !CallManager::<PeerIdentityString>::is_valid_quic_transition(
-                CallState::Connected,
-                CallState::Calling
-            )
-        );
-        assert!(
-            !CallManager::<PeerIdentityString>::is_valid_quic_transition(
-                CallState::Ending,
-                CallState::Connected
-            )
-        );
-    }
-
     #[tokio::test]
-    async fn test_exchange_capabilities() {
+    async fn test_fail_call_from_invalid_state() {
         let config = CallManagerConfig::default();
         let call_manager = CallManager::<PeerIdentityString>::new(config)
             .await
             .unwrap();
 
         let callee = PeerIdentityString::new("callee");
-        let constraints = MediaConstraints::video_call();
+        let constraints = MediaConstraints::audio_only();
+        let peer = test_peer();
 
         let call_id = call_manager
-            .initiate_call(callee, constraints)
+            .initiate_quic_call(callee, constraints.clone(), peer)
             .await
             .unwrap();
 
-        // Exchange capabilities
-        let capabilities = call_manager.exchange_capabilities(call_id).await.unwrap();
+        // First fail the call
+        call_manager
+            .fail_call(call_id, "Error 1".to_string())
+            .await
+            .unwrap();
 
-        // Verify capabilities match constraints
-        assert!(capabilities.audio);
-        assert!(capabilities.video);
-        assert!(!capabilities.data_channel);
-        assert!(capabilities.max_bandwidth_kbps > 0);
+        // Try to fail again from Failed state - should fail
+        let result = call_manager.fail_call(call_id, "Error 2".to_string()).await;
 
-        // Verify state transitioned to Connecting
-        let state = call_manager.get_call_state(call_id).await;
-        assert_eq!(state, Some(CallState::Connecting));
+        assert!(matches!(result, Err(CallError::InvalidState)));
     }
 
     #[tokio::test]
-    async fn test_exchange_capabilities_audio_only() {
+    async fn test_get_call_info() {
         let config = CallManagerConfig::default();
         let call_manager = CallManager::<PeerIdentityString>::new(config)
             .await
             .unwrap();
 
         let callee = PeerIdentityString::new("callee");
-        let constraints = MediaConstraints::audio_only();
+        let constraints = MediaConstraints::video_call();
+        let peer = test_peer();
 
         let call_id = call_manager
-            .initiate_call(callee, constraints)
+            .initiate_quic_call(callee, constraints.clone(), peer)
             .await
             .unwrap();
 
-        let capabilities = call_manager.exchange_capabilities(call_id).await.unwrap();
+        let info = call_manager.get_call_info(call_id).await;
+        assert!(info.is_some());
 
-        assert!(capabilities.audio);
-        assert!(!capabilities.video);
-        assert_eq!(capabilities.max_bandwidth_kbps, 128);
+        let (state, call_constraints, has_transport) = info.unwrap();
+        assert_eq!(state, CallState::Connecting);
+        assert_eq!(call_constraints.video, constraints.video);
+        assert!(has_transport);
     }
 
     #[tokio::test]
-    async fn test_exchange_capabilities_not_found() {
+    async fn test_get_call_info_not_found() {
         let config = CallManagerConfig::default();
         let call_manager = CallManager::<PeerIdentityString>::new(config)
             .await
             .unwrap();
 
         let fake_call_id = CallId::new();
-        let result = call_manager.exchange_capabilities(fake_call_id).await;
+        let info = call_manager.get_call_info(fake_call_id).await;
 
-        assert!(matches!(result, Err(CallError::CallNotFound(_))));
+        assert!(info.is_none());
     }
 
+    // =========================================================================
+    // QUIC Track Tests
+    // =========================================================================
+
     #[tokio::test]
-    async fn test_exchange_capabilities_invalid_state() {
+    async fn test_call_quic_tracks_initially_empty() {
         let config = CallManagerConfig::default();
         let call_manager = CallManager::<PeerIdentityString>::new(config)
             .await
@@ -1721,26 +5217,58 @@ mod tests {
 
         let callee = PeerIdentityString::new("callee");
         let constraints = MediaConstraints::audio_only();
+        let peer = test_peer();
 
         let call_id = call_manager
-            .initiate_call(callee, constraints.clone())
+            .initiate_quic_call(callee, constraints, peer)
             .await
             .unwrap();
 
-        // Accept the call to move it to Connected state
-        call_manager
-            .accept_call(call_id, constraints)
+        let entry = call_manager.call_entry(call_id).await.unwrap();
+        let call = entry.lock().await;
+
+        assert!(call.quic_tracks.is_empty());
+        assert!(call.is_quic_call());
+        #[cfg(feature = "legacy-webrtc")]
+        assert!(call.peer_connection.is_none());
+    }
+
+    #[cfg(feature = "legacy-webrtc")]
+    #[tokio::test]
+    #[allow(deprecated)]
+    async fn test_quic_call_rejects_sdp_methods() {
+        let config = CallManagerConfig::default();
+        let call_manager = CallManager::<PeerIdentityString>::new(config)
             .await
             .unwrap();
 
-        // Try to exchange capabilities in Connected state - should fail
-        let result = call_manager.exchange_capabilities(call_id).await;
+        let call_id = call_manager
+            .initiate_quic_call(
+                PeerIdentityString::new("callee"),
+                MediaConstraints::audio_only(),
+                test_peer(),
+            )
+            .await
+            .unwrap();
 
-        assert!(matches!(result, Err(CallError::InvalidState)));
+        assert!(matches!(
+            call_manager.create_offer(call_id).await,
+            Err(CallError::ConfigError(_))
+        ));
+        assert!(matches!(
+            call_manager
+                .add_ice_candidate(call_id, "candidate".to_string())
+                .await,
+            Err(CallError::ConfigError(_))
+        ));
+
+        // Ending a call without a peer connection still cleans up
+        call_manager.end_call(call_id).await.unwrap();
+        assert_eq!(call_manager.get_call_state(call_id).await, None);
     }
 
     #[tokio::test]
-    async fn test_confirm_connection() {
+    async fn test_call_add_quic_track() {
         let config = CallManagerConfig::default();
         let call_manager = CallManager::<PeerIdentityString>::new(config)
             .await
@@ -1750,32 +5278,40 @@ mod tests {
         let constraints = MediaConstraints::audio_only();
         let peer = test_peer();
 
-        // Create a QUIC call (starts in Connecting with connected transport)
         let call_id = call_manager
             .initiate_quic_call(callee, constraints, peer)
             .await
             .unwrap();
 
-        // Confirm connection with matching capabilities
-        let peer_caps = MediaCapabilities::audio_only();
-        let result = call_manager.confirm_connection(call_id, peer_caps).await;
+        // Create a QUIC track
+        let transport = Arc::new(QuicMediaTransport::new());
+        let audio = crate::media::AudioTrack::with_quic("test-audio", transport);
+        let generic = crate::media::GenericTrack::audio(audio);
 
-        assert!(result.is_ok());
+        // Add it to the call
+        {
+            let entry = call_manager.call_entry(call_id).await.unwrap();
+            let mut call = entry.lock().await;
+            call.add_quic_track(generic);
+        }
 
-        // Verify state is Connected
-        let state = call_manager.get_call_state(call_id).await;
-        assert_eq!(state, Some(CallState::Connected));
+        // Verify it was added
+        let entry = call_manager.call_entry(call_id).await.unwrap();
+        let call = entry.lock().await;
+
+        assert_eq!(call.quic_tracks.len(), 1);
+        assert!(call.get_quic_track_by_id("test-audio").is_some());
     }
 
     #[tokio::test]
-    async fn test_confirm_connection_incompatible_caps() {
+    async fn test_call_remove_quic_track() {
         let config = CallManagerConfig::default();
         let call_manager = CallManager::<PeerIdentityString>::new(config)
             .await
             .unwrap();
 
         let callee = PeerIdentityString::new("callee");
-        let constraints = MediaConstraints::video_call(); // Requires video
+        let constraints = MediaConstraints::video_call();
         let peer = test_peer();
 
         let call_id = call_manager
@@ -1783,32 +5319,37 @@ mod tests {
             .await
             .unwrap();
 
-        // Try to confirm with audio-only capabilities (missing video)
-        let peer_caps = MediaCapabilities::audio_only();
-        let result = call_manager.confirm_connection(call_id, peer_caps).await;
-
-        assert!(matches!(result, Err(CallError::ConfigError(_))));
-    }
+        // Create and add tracks
+        let transport = Arc::new(QuicMediaTransport::new());
+        let audio = crate::media::AudioTrack::with_quic("audio-1", Arc::clone(&transport));
+        let video = crate::media::VideoTrack::with_quic("video-1", transport, 1280, 720);
 
-    #[tokio::test]
-    async fn test_confirm_connection_not_found() {
-        let config = CallManagerConfig::default();
-        let call_manager = CallManager::<PeerIdentityString>::new(config)
-            .await
-            .unwrap();
+        {
+            let entry = call_manager.call_entry(call_id).await.unwrap();
+            let mut call = entry.lock().await;
+            call.add_quic_track(crate::media::GenericTrack::audio(audio));
+            call.add_quic_track(crate::media::GenericTrack::video(video));
+        }
 
-        let fake_call_id = CallId::new();
-        let peer_caps = MediaCapabilities::audio_only();
+        // Remove one track
+        {
+            let entry = call_manager.call_entry(call_id).await.unwrap();
+            let mut call = entry.lock().await;
+            let removed = call.remove_quic_track("audio-1");
+            assert!(removed);
+        }
 
-        let result = call_manager
-            .confirm_connection(fake_call_id, peer_caps)
-            .await;
+        // Verify
+        let entry = call_manager.call_entry(call_id).await.unwrap();
+        let call = entry.lock().await;
 
-        assert!(matches!(result, Err(CallError::CallNotFound(_))));
+        assert_eq!(call.quic_tracks.len(), 1);
+        assert!(call.get_quic_track_by_id("audio-1").is_none());
+        assert!(call.get_quic_track_by_id("video-1").is_some());
     }
 
     #[tokio::test]
-    async fn test_confirm_connection_invalid_state() {
+    async fn test_call_transport_accessor() {
         let config = CallManagerConfig::default();
         let call_manager = CallManager::<PeerIdentityString>::new(config)
             .await
@@ -1816,429 +5357,965 @@ mod tests {
 
         let callee = PeerIdentityString::new("callee");
         let constraints = MediaConstraints::audio_only();
+        let peer = test_peer();
 
-        // Create a regular call (not QUIC, starts in Calling state)
         let call_id = call_manager
-            .initiate_call(callee, constraints)
+            .initiate_quic_call(callee, constraints, peer)
             .await
             .unwrap();
 
-        // Try to confirm connection in Calling state - should fail
-        let peer_caps = MediaCapabilities::audio_only();
-        let result = call_manager.confirm_connection(call_id, peer_caps).await;
+        let entry = call_manager.call_entry(call_id).await.unwrap();
+        let call = entry.lock().await;
 
-        assert!(matches!(result, Err(CallError::InvalidState)));
+        // Should have a transport since it's a QUIC call
+        assert!(call.transport().is_some());
+    }
+
+    fn offer(
+        call_id: CallId,
+        caller: &str,
+        callee: &str,
+    ) -> crate::types::CallOffer<PeerIdentityString> {
+        crate::types::CallOffer {
+            call_id,
+            caller: PeerIdentityString::new(caller),
+            callee: PeerIdentityString::new(callee),
+            sdp: String::new(),
+            media_types: MediaConstraints::video_call().to_media_types(),
+            timestamp: chrono::Utc::now(),
+        }
     }
 
     #[tokio::test]
-    async fn test_confirm_connection_emits_event() {
-        let config = CallManagerConfig::default();
-        let call_manager = CallManager::<PeerIdentityString>::new(config)
+    async fn test_incoming_call_without_glare_rings() {
+        let manager = CallManager::<PeerIdentityString>::new(CallManagerConfig::default())
             .await
             .unwrap();
+        let mut events = manager.subscribe_events();
 
-        // Subscribe to events before initiating call
-        let mut event_rx = call_manager.subscribe_events();
-
-        let callee = PeerIdentityString::new("callee");
-        let constraints = MediaConstraints::audio_only();
-        let peer = test_peer();
-
-        let call_id = call_manager
-            .initiate_quic_call(callee, constraints, peer)
+        let call_id = CallId::new();
+        let outcome = manager
+            .handle_incoming_call(offer(call_id, "alice", "bob"))
             .await
             .unwrap();
+        assert_eq!(outcome, IncomingCallOutcome::Ringing(call_id));
+        assert_eq!(
+            manager.get_call_state(call_id).await,
+            Some(CallState::Calling)
+        );
+        assert!(matches!(
+            events.recv().await.unwrap(),
+            CallEvent::IncomingCall { offer } if offer.call_id == call_id
+        ));
 
-        // Drain the CallInitiated event
-        let _ = event_rx.try_recv();
+        // The same offer delivered twice is rejected
+        assert!(manager
+            .handle_incoming_call(offer(call_id, "alice", "bob"))
+            .await
+            .is_err());
+    }
 
-        // Confirm connection
-        let peer_caps = MediaCapabilities::audio_only();
-        call_manager
-            .confirm_connection(call_id, peer_caps)
+    #[tokio::test]
+    async fn test_resolve_call_id_by_short_code() {
+        let manager = CallManager::<PeerIdentityString>::new(CallManagerConfig::default())
             .await
             .unwrap();
 
-        // Verify ConnectionEstablished event was emitted
-        let event = event_rx.try_recv();
-        assert!(event.is_ok());
-
-        match event {
-            Ok(CallEvent::ConnectionEstablished { call_id: eid }) => {
-                assert_eq!(eid, call_id);
-            }
-            other => {
-                unreachable!("Expected ConnectionEstablished event, got: {:?}", other);
-            }
-        }
-    }
+        // Two IDs whose short codes collide, found by the birthday bound
+        let mut seen = HashMap::new();
+        let (first, second) = (0u128..)
+            .map(|i| CallId(uuid::Uuid::from_u128(i.wrapping_mul(0x9e37_79b9_7f4a_7c15))))
+            .find_map(|call_id| {
+                seen.insert(call_id.short_code(), call_id)
+                    .map(|other| (other, call_id))
+            })
+            .unwrap();
+        manager
+            .handle_incoming_call(offer(first, "alice", "bob"))
+            .await
+            .unwrap();
 
-    #[test]
-    fn test_validate_remote_capabilities_audio_only() {
-        let constraints = MediaConstraints::audio_only();
-        let caps = MediaCapabilities::audio_only();
+        let code = first.short_code().to_string();
+        assert_eq!(manager.resolve_call_id(&code).await.unwrap(), first);
+        assert_eq!(
+            manager.resolve_call_id(&code.to_lowercase()).await.unwrap(),
+            first
+        );
+        assert_eq!(
+            manager.resolve_call_id(&second.to_string()).await.unwrap(),
+            second
+        );
+        assert!(matches!(
+            manager.resolve_call_id("not a call").await,
+            Err(CallError::CallNotFound(_))
+        ));
 
-        let result =
-            CallManager::<PeerIdentityString>::validate_remote_capabilities(&constraints, &caps);
-        assert!(result.is_ok());
+        manager
+            .handle_incoming_call(offer(second, "carol", "bob"))
+            .await
+            .unwrap();
+        assert!(matches!(
+            manager.resolve_call_id(&code).await,
+            Err(CallError::AmbiguousShortCode(c)) if c == first.short_code()
+        ));
     }
 
-    #[test]
-    fn test_validate_remote_capabilities_video_call() {
-        let constraints = MediaConstraints::video_call();
-        let video_caps = MediaCapabilities::video();
-        let audio_caps = MediaCapabilities::audio_only();
+    #[tokio::test]
+    async fn test_glare_resolves_to_same_call_on_both_peers() {
+        let alice = CallManager::<PeerIdentityString>::new(CallManagerConfig::default())
+            .await
+            .unwrap();
+        let bob = CallManager::<PeerIdentityString>::new(CallManagerConfig::default())
+            .await
+            .unwrap();
 
-        // Video caps should satisfy video constraints
-        let result = CallManager::<PeerIdentityString>::validate_remote_capabilities(
-            &constraints,
-            &video_caps,
-        );
-        assert!(result.is_ok());
+        let alice_call = alice
+            .initiate_call(
+                PeerIdentityString::new("bob"),
+                MediaConstraints::video_call(),
+            )
+            .await
+            .unwrap();
+        let bob_call = bob
+            .initiate_call(
+                PeerIdentityString::new("alice"),
+                MediaConstraints::video_call(),
+            )
+            .await
+            .unwrap();
+        let mut alice_events = alice.subscribe_events();
+        let mut bob_events = bob.subscribe_events();
 
-        // Audio-only caps should NOT satisfy video constraints
-        let result = CallManager::<PeerIdentityString>::validate_remote_capabilities(
-            &constraints,
-            &audio_caps,
-        );
-        assert!(matches!(result, Err(CallError::ConfigError(_))));
-    }
+        // Offers cross on the wire
+        let at_alice = alice
+            .handle_incoming_call(offer(bob_call, "bob", "alice"))
+            .await
+            .unwrap();
+        let at_bob = bob
+            .handle_incoming_call(offer(alice_call, "alice", "bob"))
+            .await
+            .unwrap();
 
-    #[test]
-    fn test_validate_remote_capabilities_missing_audio() {
-        let constraints = MediaConstraints::video_call(); // requires audio
-        let caps = MediaCapabilities {
-            audio: false,
-            video: true,
-            data_channel: false,
-            max_bandwidth_kbps: 2500,
+        let (winner, loser) = if alice_call > bob_call {
+            (alice_call, bob_call)
+        } else {
+            (bob_call, alice_call)
         };
+        for outcome in [at_alice, at_bob] {
+            let resolved = match outcome {
+                IncomingCallOutcome::OutgoingKept { kept, cancelled }
+                | IncomingCallOutcome::IncomingKept { kept, cancelled } => Some((kept, cancelled)),
+                IncomingCallOutcome::Ringing(_) | IncomingCallOutcome::DoNotDisturb { .. } => None,
+            };
+            assert_eq!(resolved, Some((winner, loser)));
+        }
 
-        let result =
-            CallManager::<PeerIdentityString>::validate_remote_capabilities(&constraints, &caps);
-        assert!(matches!(result, Err(CallError::ConfigError(_))));
+        // Exactly one call survives on each side, with the same ID
+        for manager in [&alice, &bob] {
+            assert_eq!(
+                manager.get_call_state(winner).await,
+                Some(CallState::Calling)
+            );
+            assert_eq!(manager.get_call_state(loser).await, None);
+        }
+        for events in [&mut alice_events, &mut bob_events] {
+            assert!(matches!(
+                events.recv().await.unwrap(),
+                CallEvent::GlareResolved { cancelled, kept } if kept == winner && cancelled == loser
+            ));
+        }
     }
 
-    #[test]
-    fn test_validate_remote_capabilities_screen_share() {
-        let constraints = MediaConstraints::screen_share();
-        let caps = MediaCapabilities::video(); // screen share requires video capability
-
-        let result =
-            CallManager::<PeerIdentityString>::validate_remote_capabilities(&constraints, &caps);
-        assert!(result.is_ok());
+    #[tokio::test]
+    async fn test_no_glare_once_outgoing_call_connected() {
+        let manager = CallManager::<PeerIdentityString>::new(CallManagerConfig::default())
+            .await
+            .unwrap();
+        let outgoing = manager
+            .initiate_call(
+                PeerIdentityString::new("alice"),
+                MediaConstraints::audio_only(),
+            )
+            .await
+            .unwrap();
+        manager
+            .accept_call(outgoing, MediaConstraints::audio_only())
+            .await
+            .unwrap();
 
-        // Audio-only caps should NOT satisfy screen share (needs video)
-        let audio_caps = MediaCapabilities::audio_only();
-        let result = CallManager::<PeerIdentityString>::validate_remote_capabilities(
-            &constraints,
-            &audio_caps,
+        let incoming = CallId(uuid::Uuid::max());
+        let outcome = manager
+            .handle_incoming_call(offer(incoming, "alice", "bob"))
+            .await
+            .unwrap();
+        assert_eq!(outcome, IncomingCallOutcome::Ringing(incoming));
+        assert_eq!(
+            manager.get_call_state(outgoing).await,
+            Some(CallState::Connected)
         );
-        assert!(matches!(result, Err(CallError::ConfigError(_))));
     }
 
-    /// Test that verifies PeerIdentity type safety is preserved across all methods
+    async fn call_transport(
+        manager: &CallManager<PeerIdentityString>,
+        call_id: CallId,
+    ) -> Arc<QuicMediaTransport> {
+        let entry = manager.call_entry(call_id).await.unwrap();
+        let call = entry.lock().await;
+        call.transport().cloned().unwrap()
+    }
+
     #[tokio::test]
-    async fn test_peer_identity_type_safety() {
-        let config = CallManagerConfig::default();
-        let call_manager = CallManager::<PeerIdentityString>::new(config)
+    async fn test_early_media_gates_video_until_accepted() {
+        let config = CallManagerConfig {
+            early_media: true,
+            ..Default::default()
+        };
+        let manager = CallManager::<PeerIdentityString>::new(config)
+            .await
+            .unwrap();
+        let call_id = manager
+            .initiate_quic_call(
+                PeerIdentityString::new("callee"),
+                MediaConstraints::video_call(),
+                test_peer(),
+            )
             .await
             .unwrap();
+        let transport = call_transport(&manager, call_id).await;
 
-        // Subscribe to events - should receive CallEvent<PeerIdentityString>
-        let mut event_rx = call_manager.subscribe_events();
+        // Nothing flows before acceptance without early media
+        assert!(matches!(
+            transport.send_audio(&[0x80]).await,
+            Err(MediaTransportError::MediaGated(_))
+        ));
 
-        let callee = PeerIdentityString::new("typed-callee");
-        let constraints = MediaConstraints::audio_only();
-        let peer = test_peer();
+        let local = manager.exchange_capabilities(call_id).await.unwrap();
+        assert!(local.supports_extension(MediaCapabilities::EXT_EARLY_MEDIA));
 
-        // Initiate QUIC call - callee is typed as PeerIdentityString
-        let call_id = call_manager
-            .initiate_quic_call(callee.clone(), constraints, peer)
+        let peer =
+            MediaCapabilities::video().with_extension(MediaCapabilities::EXT_EARLY_MEDIA, "1");
+        assert!(manager.negotiate_early_media(call_id, &peer).await.unwrap());
+        transport.send_audio(&[0x80]).await.unwrap();
+        assert!(matches!(
+            transport.send_video(&[0x80]).await,
+            Err(MediaTransportError::MediaGated(_))
+        ));
+
+        manager
+            .confirm_connection(call_id, peer.clone())
             .await
             .unwrap();
+        transport.send_video(&[0x80]).await.unwrap();
+        assert!(matches!(
+            manager.negotiate_early_media(call_id, &peer).await,
+            Err(CallError::InvalidState)
+        ));
+    }
 
-        // Receive event - should be properly typed
-        let event = event_rx.try_recv();
-        assert!(event.is_ok());
+    #[tokio::test]
+    async fn test_early_media_requires_local_offer() {
+        let manager = CallManager::<PeerIdentityString>::new(CallManagerConfig::default())
+            .await
+            .unwrap();
+        let call_id = manager
+            .initiate_quic_call(
+                PeerIdentityString::new("callee"),
+                MediaConstraints::audio_only(),
+                test_peer(),
+            )
+            .await
+            .unwrap();
 
-        // Verify event contains the correctly typed callee identity
-        match event {
-            Ok(CallEvent::CallInitiated {
-                callee: event_callee,
-                ..
-            }) => {
-                // This line compiles because event_callee is PeerIdentityString
-                assert_eq!(event_callee.to_string_repr(), callee.to_string_repr());
-            }
-            other => {
-                // Test assertion - use unreachable since tests are allowed panics
-                unreachable!("Expected CallInitiated event, got: {:?}", other);
-            }
-        }
+        let local = manager.exchange_capabilities(call_id).await.unwrap();
+        assert!(!local.supports_extension(MediaCapabilities::EXT_EARLY_MEDIA));
 
-        // End call cleanly
-        call_manager.end_call(call_id).await.unwrap();
+        let peer =
+            MediaCapabilities::audio_only().with_extension(MediaCapabilities::EXT_EARLY_MEDIA, "1");
+        assert!(!manager.negotiate_early_media(call_id, &peer).await.unwrap());
+        assert_eq!(
+            call_transport(&manager, call_id).await.media_gate().await,
+            MediaGate::Closed
+        );
     }
 
     #[tokio::test]
-    async fn test_fail_call() {
-        let config = CallManagerConfig::default();
+    async fn test_audit_log_records_call_lifecycle() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let config = CallManagerConfig {
+            max_concurrent_calls: 1,
+            audit: AuditConfig {
+                path: Some(path.clone()),
+            },
+            ..Default::default()
+        };
         let call_manager = CallManager::<PeerIdentityString>::new(config)
             .await
             .unwrap();
+        assert!(call_manager.audit_log().is_some());
 
-        let mut event_rx = call_manager.subscribe_events();
-
-        let callee = PeerIdentityString::new("callee");
         let constraints = MediaConstraints::audio_only();
-        let peer = test_peer();
-
         let call_id = call_manager
-            .initiate_quic_call(callee, constraints, peer)
+            .initiate_call(PeerIdentityString::new("callee"), constraints.clone())
             .await
             .unwrap();
+        assert!(call_manager
+            .initiate_call(PeerIdentityString::new("other"), constraints.clone())
+            .await
+            .is_err());
+        call_manager
+            .accept_call(call_id, constraints)
+            .await
+            .unwrap();
+        call_manager.end_call(call_id).await.unwrap();
 
-        // Drain CallInitiated event
-        let _ = event_rx.try_recv();
-
-        // Fail the call
-        let result = call_manager
-            .fail_call(call_id, "Network error".to_string())
-            .await;
-        assert!(result.is_ok());
-
-        // Verify state is Failed
-        let state = call_manager.get_call_state(call_id).await;
-        assert_eq!(state, Some(CallState::Failed));
-
-        // Verify ConnectionFailed event was emitted
-        let event = event_rx.try_recv();
-        assert!(event.is_ok());
-
-        match event {
-            Ok(CallEvent::ConnectionFailed {
-                call_id: eid,
-                error,
-            }) => {
-                assert_eq!(eid, call_id);
-                assert_eq!(error, "Network error");
-            }
-            other => {
-                unreachable!("Expected ConnectionFailed event, got: {:?}", other);
+        let records = AuditLog::read(&path).unwrap();
+        let events: Vec<_> = records.iter().map(|r| &r.event).collect();
+        assert_eq!(
+            events[0],
+            &AuditEvent::CallInitiated {
+                peer: "callee".to_string()
             }
-        }
+        );
+        assert!(matches!(
+            events[1],
+            AuditEvent::PolicyDecision { policy, allowed: false, .. } if policy == "max_concurrent_calls"
+        ));
+        assert_ne!(records[1].call_id, call_id);
+        assert_eq!(
+            events[2..],
+            [&AuditEvent::CallAccepted, &AuditEvent::CallEnded]
+        );
+        assert!(records[2..].iter().all(|r| r.call_id == call_id));
     }
 
     #[tokio::test]
-    async fn test_fail_call_not_found() {
-        let config = CallManagerConfig::default();
+    async fn test_event_capacity_and_overflow_count() {
+        let config = CallManagerConfig {
+            event_capacity: 2,
+            ..Default::default()
+        };
         let call_manager = CallManager::<PeerIdentityString>::new(config)
             .await
             .unwrap();
+        let call_id = call_manager
+            .initiate_call(
+                PeerIdentityString::new("callee"),
+                MediaConstraints::audio_only(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(call_manager.event_stats().unheard, 1);
 
-        let fake_call_id = CallId::new();
-        let result = call_manager
-            .fail_call(fake_call_id, "Error".to_string())
-            .await;
+        let mut events = call_manager.subscribe_events();
+        for value in ["a", "b", "c", "d", "e"] {
+            call_manager
+                .set_call_metadata(call_id, "step", value)
+                .await
+                .unwrap();
+        }
 
-        assert!(matches!(result, Err(CallError::CallNotFound(_))));
+        let stats = call_manager.event_stats();
+        assert_eq!((stats.capacity, stats.sent, stats.overflowed), (2, 5, 3));
+        assert!(matches!(
+            events.recv().await,
+            Err(broadcast::error::RecvError::Lagged(3))
+        ));
     }
 
     #[tokio::test]
-    async fn test_fail_call_from_invalid_state() {
-        let config = CallManagerConfig::default();
+    async fn test_call_metadata_reaches_events_audit_and_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let config = CallManagerConfig {
+            audit: AuditConfig {
+                path: Some(path.clone()),
+            },
+            ..Default::default()
+        };
         let call_manager = CallManager::<PeerIdentityString>::new(config)
             .await
             .unwrap();
-
-        let callee = PeerIdentityString::new("callee");
-        let constraints = MediaConstraints::audio_only();
-        let peer = test_peer();
-
         let call_id = call_manager
-            .initiate_quic_call(callee, constraints.clone(), peer)
+            .initiate_call(
+                PeerIdentityString::new("callee"),
+                MediaConstraints::audio_only(),
+            )
             .await
             .unwrap();
+        let mut events = call_manager.subscribe_events();
 
-        // First fail the call
         call_manager
-            .fail_call(call_id, "Error 1".to_string())
+            .set_call_metadata(call_id, "ticket", "SUP-1")
+            .await
+            .unwrap();
+        call_manager.start_stats_history(call_id).await.unwrap();
+        call_manager
+            .set_call_metadata(call_id, "queue", "billing")
             .await
             .unwrap();
 
-        // Try to fail again from Failed state - should fail
-        let result = call_manager.fail_call(call_id, "Error 2".to_string()).await;
-
-        assert!(matches!(result, Err(CallError::InvalidState)));
+        assert!(matches!(
+            events.recv().await.unwrap(),
+            CallEvent::MetadataChanged { key, value, .. } if key == "ticket" && value == "SUP-1"
+        ));
+        let metadata = call_manager.call_metadata(call_id).await.unwrap();
+        assert_eq!(metadata.len(), 2);
+        let history = call_manager.stats_history.get(call_id).unwrap();
+        assert_eq!(history.metadata(), &metadata);
+        assert!(AuditLog::read(&path).unwrap().iter().any(|r| r.event
+            == AuditEvent::MetadataSet {
+                key: "queue".to_string(),
+                value: "billing".to_string(),
+            }));
+
+        assert!(call_manager
+            .set_call_metadata(call_id, "", "x")
+            .await
+            .is_err());
+        assert!(matches!(
+            call_manager
+                .set_call_metadata(CallId::new(), "ticket", "x")
+                .await,
+            Err(CallError::CallNotFound(_))
+        ));
     }
 
     #[tokio::test]
-    async fn test_get_call_info() {
-        let config = CallManagerConfig::default();
+    async fn test_purge_peer_data() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let config = CallManagerConfig {
+            audit: AuditConfig {
+                path: Some(path.clone()),
+            },
+            ..Default::default()
+        };
         let call_manager = CallManager::<PeerIdentityString>::new(config)
             .await
             .unwrap();
 
-        let callee = PeerIdentityString::new("callee");
-        let constraints = MediaConstraints::video_call();
-        let peer = test_peer();
+        let alice = PeerIdentityString::new("alice");
+        let mut alice_calls = BTreeSet::new();
+        for peer in [alice.clone(), PeerIdentityString::new("bob"), alice.clone()] {
+            let call_id = call_manager
+                .initiate_call(peer.clone(), MediaConstraints::audio_only())
+                .await
+                .unwrap();
+            call_manager.start_stats_history(call_id).await.unwrap();
+            call_manager.end_call(call_id).await.unwrap();
+            if peer == alice {
+                alice_calls.insert(call_id);
+            }
+        }
+
+        let report = call_manager.purge_peer_data(&alice).unwrap();
+        assert_eq!(report.calls, alice_calls);
+        assert_eq!(report.audit_records, 4);
+        assert!(alice_calls
+            .iter()
+            .all(|&call_id| call_manager.stats_history(call_id).is_none()));
+
+        let records = AuditLog::read(&path).unwrap();
+        assert_eq!(records.len(), 2);
+        assert!(records.iter().all(|r| !alice_calls.contains(&r.call_id)));
+
+        // Everything left is older than now
+        let report = call_manager.purge_all(Utc::now()).unwrap();
+        assert_eq!(report.audit_records, 2);
+        assert!(report.calls.is_empty());
+        assert_eq!(AuditLog::read(&path).unwrap().len(), 0);
+    }
 
+    #[tokio::test]
+    async fn test_call_id_correlates_transport_and_errors() {
+        let call_manager = CallManager::<PeerIdentityString>::new(CallManagerConfig::default())
+            .await
+            .unwrap();
         let call_id = call_manager
-            .initiate_quic_call(callee, constraints.clone(), peer)
+            .initiate_call(
+                PeerIdentityString::new("callee"),
+                MediaConstraints::audio_only(),
+            )
             .await
             .unwrap();
 
-        let info = call_manager.get_call_info(call_id).await;
-        assert!(info.is_some());
+        let transport = call_manager.media_transport(call_id).await.unwrap();
+        assert_eq!(transport.call_id(), Some(call_id));
+        let stats = call_manager.transport_stats(call_id).await.unwrap();
+        assert_eq!(stats.call_id, Some(call_id));
 
-        let (state, call_constraints, has_transport) = info.unwrap();
-        assert_eq!(state, CallState::Connecting);
-        assert_eq!(call_constraints.video, constraints.video);
-        assert!(has_transport);
+        assert_eq!(CallError::InvalidState.call_id(), None);
+        let err = CallError::media(call_id, MediaTransportError::NotConnected);
+        assert_eq!(err.call_id(), Some(call_id));
+        assert!(err.to_string().contains(&call_id.to_string()));
+
+        let missing = CallId::new();
+        let err = call_manager.end_call(missing).await.unwrap_err();
+        assert_eq!(err.call_id(), Some(missing));
     }
 
     #[tokio::test]
-    async fn test_get_call_info_not_found() {
-        let config = CallManagerConfig::default();
-        let call_manager = CallManager::<PeerIdentityString>::new(config)
+    async fn test_call_handoff_between_devices() {
+        let desktop = CallManager::<PeerIdentityString>::new(CallManagerConfig::default())
+            .await
+            .unwrap();
+        let phone = CallManager::<PeerIdentityString>::new(CallManagerConfig::default())
+            .await
+            .unwrap();
+        let bob = CallManager::<PeerIdentityString>::new(CallManagerConfig::default())
             .await
             .unwrap();
+        let mut bob_events = bob.subscribe_events();
 
-        let fake_call_id = CallId::new();
-        let info = call_manager.get_call_info(fake_call_id).await;
+        let call_id = desktop
+            .initiate_quic_call(
+                PeerIdentityString::new("bob"),
+                MediaConstraints::video_call(),
+                test_peer(),
+            )
+            .await
+            .unwrap();
+        desktop
+            .accept_call(call_id, MediaConstraints::video_call())
+            .await
+            .unwrap();
+        bob.handle_incoming_call(offer(call_id, "alice-desktop", "bob"))
+            .await
+            .unwrap();
+        bob.accept_call(call_id, MediaConstraints::video_call())
+            .await
+            .unwrap();
 
-        assert!(info.is_none());
-    }
+        let alice_phone = PeerIdentityString::new("alice-phone");
+        let (token, announce) = desktop
+            .prepare_handoff(call_id, &alice_phone, std::time::Duration::from_secs(60))
+            .await
+            .unwrap();
+        let SignalingMessage::HandoffOffer { key, .. } = announce else {
+            unreachable!("prepare_handoff returns a handoff offer");
+        };
 
-    // =========================================================================
-    // QUIC Track Tests
-    // =========================================================================
+        // Before the offer arrives the peer refuses the join
+        let token_text = token.encode();
+        assert!(matches!(
+            bob.handle_handoff_join(call_id, alice_phone.clone(), &token_text, test_peer())
+                .await,
+            Err(CallError::Handoff(HandoffError::NotPending(_)))
+        ));
+        bob.handle_handoff_offer(call_id, &key).await.unwrap();
 
-    #[tokio::test]
-    async fn test_call_quic_tracks_initially_empty() {
-        let config = CallManagerConfig::default();
-        let call_manager = CallManager::<PeerIdentityString>::new(config)
+        // The token only admits the device it names
+        assert!(matches!(
+            bob.handle_handoff_join(
+                call_id,
+                PeerIdentityString::new("mallory"),
+                &token_text,
+                test_peer()
+            )
+            .await,
+            Err(CallError::Handoff(HandoffError::WrongDevice))
+        ));
+
+        let join = phone
+            .join_handoff(
+                &HandoffToken::decode(&token_text).unwrap(),
+                MediaConstraints::video_call(),
+                test_peer(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            phone.get_call_state(call_id).await,
+            Some(CallState::Connecting)
+        );
+        let SignalingMessage::HandoffJoin { token, .. } = join else {
+            unreachable!("join_handoff returns a handoff join");
+        };
+        let complete = bob
+            .handle_handoff_join(call_id, alice_phone.clone(), &token, test_peer())
             .await
             .unwrap();
+        assert!(matches!(complete, SignalingMessage::HandoffComplete { .. }));
+        assert_eq!(
+            bob.get_call_state(call_id).await,
+            Some(CallState::Connected)
+        );
 
-        let callee = PeerIdentityString::new("callee");
-        let constraints = MediaConstraints::audio_only();
-        let peer = test_peer();
+        let mut handed_off = false;
+        while let Ok(event) = bob_events.try_recv() {
+            if let CallEvent::PeerHandedOff { from, to, .. } = event {
+                assert_eq!(from.as_str(), "alice-desktop");
+                assert_eq!(to, alice_phone);
+                handed_off = true;
+            }
+        }
+        assert!(handed_off);
+
+        desktop.handle_handoff_complete(call_id).await.unwrap();
+        phone.handle_handoff_complete(call_id).await.unwrap();
+        assert_eq!(desktop.get_call_state(call_id).await, None);
+        assert_eq!(
+            phone.get_call_state(call_id).await,
+            Some(CallState::Connected)
+        );
+        assert!(matches!(
+            phone.handle_handoff_complete(call_id).await,
+            Err(CallError::Handoff(HandoffError::NotPending(_)))
+        ));
+    }
 
-        let call_id = call_manager
-            .initiate_quic_call(callee, constraints, peer)
+    #[tokio::test]
+    async fn test_failed_handoff_join_keeps_old_device() {
+        let desktop = CallManager::<PeerIdentityString>::new(CallManagerConfig::default())
+            .await
+            .unwrap();
+        let bob = CallManager::<PeerIdentityString>::new(CallManagerConfig::default())
+            .await
+            .unwrap();
+        let call_id = desktop
+            .initiate_quic_call(
+                PeerIdentityString::new("bob"),
+                MediaConstraints::audio_only(),
+                test_peer(),
+            )
+            .await
+            .unwrap();
+        desktop
+            .accept_call(call_id, MediaConstraints::audio_only())
+            .await
+            .unwrap();
+        bob.handle_incoming_call(offer(call_id, "alice-desktop", "bob"))
+            .await
+            .unwrap();
+        bob.connect_quic_transport(call_id, test_peer())
+            .await
+            .unwrap();
+        bob.accept_call(call_id, MediaConstraints::audio_only())
+            .await
+            .unwrap();
+        let transport = bob.media_transport(call_id).await.unwrap();
+        transport
+            .get_or_create_stream(crate::link_transport::StreamType::Audio)
             .await
             .unwrap();
 
-        let calls = call_manager.calls.read().await;
-        let call = calls.get(&call_id).unwrap();
+        let alice_phone = PeerIdentityString::new("alice-phone");
+        let (token, announce) = desktop
+            .prepare_handoff(call_id, &alice_phone, std::time::Duration::from_secs(60))
+            .await
+            .unwrap();
+        let SignalingMessage::HandoffOffer { key, .. } = announce else {
+            unreachable!("prepare_handoff returns a handoff offer");
+        };
+        bob.handle_handoff_offer(call_id, &key).await.unwrap();
 
-        assert!(call.quic_tracks.is_empty());
-        assert!(call.is_quic_call());
+        // The new device cannot be reached: the old one keeps the call
+        let unreachable = crate::link_transport::PeerConnection {
+            peer_id: String::new(),
+            remote_addr: "127.0.0.1:9001".parse().unwrap(),
+        };
+        let token = token.encode();
+        assert!(matches!(
+            bob.handle_handoff_join(call_id, alice_phone.clone(), &token, unreachable)
+                .await,
+            Err(CallError::MediaError { .. })
+        ));
+        assert!(transport.is_connected().await);
+        assert_eq!(
+            transport.peer().await.map(|peer| peer.peer_id),
+            Some(test_peer().peer_id)
+        );
+        assert_eq!(transport.active_streams().await.len(), 1);
+        assert_eq!(
+            bob.calls().await,
+            [(
+                call_id,
+                PeerIdentityString::new("alice-desktop"),
+                CallState::Connected
+            )]
+        );
+
+        // The handoff was abandoned, so the peer has to offer it again
+        assert!(matches!(
+            bob.handle_handoff_join(call_id, alice_phone, &token, test_peer())
+                .await,
+            Err(CallError::Handoff(HandoffError::NotPending(_)))
+        ));
     }
 
     #[tokio::test]
-    async fn test_call_add_quic_track() {
-        let config = CallManagerConfig::default();
-        let call_manager = CallManager::<PeerIdentityString>::new(config)
+    async fn test_callee_progress_reaches_caller() {
+        let alice = CallManager::<PeerIdentityString>::new(CallManagerConfig::default())
             .await
             .unwrap();
-
-        let callee = PeerIdentityString::new("callee");
-        let constraints = MediaConstraints::audio_only();
-        let peer = test_peer();
-
-        let call_id = call_manager
-            .initiate_quic_call(callee, constraints, peer)
+        let bob = CallManager::<PeerIdentityString>::new(CallManagerConfig::default())
             .await
             .unwrap();
+        let mut alice_events = alice.subscribe_events();
 
-        // Create a QUIC track
-        let transport = Arc::new(QuicMediaTransport::new());
-        let audio = crate::media::AudioTrack::with_quic("test-audio", transport);
-        let generic = crate::media::GenericTrack::audio(audio);
+        let call_id = alice
+            .initiate_call(
+                PeerIdentityString::new("bob"),
+                MediaConstraints::audio_only(),
+            )
+            .await
+            .unwrap();
+        // Only the callee reports progress
+        assert!(matches!(
+            alice.report_progress(call_id, CallProgress::Ringing).await,
+            Err(CallError::InvalidState)
+        ));
+        bob.handle_incoming_call(offer(call_id, "alice", "bob"))
+            .await
+            .unwrap();
 
-        // Add it to the call
-        {
-            let mut calls = call_manager.calls.write().await;
-            let call = calls.get_mut(&call_id).unwrap();
-            call.add_quic_track(generic);
+        for progress in [
+            CallProgress::Queued { position: Some(2) },
+            CallProgress::Ringing,
+            CallProgress::Ringing,
+        ] {
+            let message = bob.report_progress(call_id, progress).await.unwrap();
+            let SignalingMessage::Progress { progress, .. } = message else {
+                unreachable!("report_progress returns a progress message");
+            };
+            alice.handle_progress(call_id, progress).await.unwrap();
         }
+        assert_eq!(
+            alice.call_progress(call_id).await,
+            Some(CallProgress::Ringing)
+        );
 
-        // Verify it was added
-        let calls = call_manager.calls.read().await;
-        let call = calls.get(&call_id).unwrap();
+        let mut reported = Vec::new();
+        while let Ok(event) = alice_events.try_recv() {
+            if let CallEvent::CallProgress { progress, .. } = event {
+                reported.push(progress);
+            }
+        }
+        // Repeats raise no event
+        assert_eq!(
+            reported,
+            [
+                CallProgress::Queued { position: Some(2) },
+                CallProgress::Ringing
+            ]
+        );
 
-        assert_eq!(call.quic_tracks.len(), 1);
-        assert!(call.get_quic_track_by_id("test-audio").is_some());
+        bob.accept_call(call_id, MediaConstraints::audio_only())
+            .await
+            .unwrap();
+        assert!(matches!(
+            bob.report_progress(call_id, CallProgress::EarlyMedia).await,
+            Err(CallError::InvalidState)
+        ));
     }
 
     #[tokio::test]
-    async fn test_call_remove_quic_track() {
-        let config = CallManagerConfig::default();
-        let call_manager = CallManager::<PeerIdentityString>::new(config)
+    async fn test_call_waiting_holds_active_call() {
+        let manager = CallManager::<PeerIdentityString>::new(CallManagerConfig::default())
             .await
             .unwrap();
+        let mut events = manager.subscribe_events();
 
-        let callee = PeerIdentityString::new("callee");
-        let constraints = MediaConstraints::video_call();
-        let peer = test_peer();
-
-        let call_id = call_manager
-            .initiate_quic_call(callee, constraints, peer)
+        let first = CallId::new();
+        manager
+            .handle_incoming_call(offer(first, "alice", "bob"))
             .await
             .unwrap();
+        manager
+            .accept_call(first, MediaConstraints::audio_only())
+            .await
+            .unwrap();
+        assert_eq!(manager.active_call().await, Some(first));
 
-        // Create and add tracks
-        let transport = Arc::new(QuicMediaTransport::new());
-        let audio = crate::media::AudioTrack::with_quic("audio-1", Arc::clone(&transport));
-        let video = crate::media::VideoTrack::with_quic("video-1", transport, 1280, 720);
-
-        {
-            let mut calls = call_manager.calls.write().await;
-            let call = calls.get_mut(&call_id).unwrap();
-            call.add_quic_track(crate::media::GenericTrack::audio(audio));
-            call.add_quic_track(crate::media::GenericTrack::video(video));
+        let second = CallId::new();
+        let third = CallId::new();
+        for call_id in [second, third] {
+            manager
+                .handle_incoming_call(offer(call_id, "carol", "bob"))
+                .await
+                .unwrap();
         }
-
-        // Remove one track
-        {
-            let mut calls = call_manager.calls.write().await;
-            let call = calls.get_mut(&call_id).unwrap();
-            let removed = call.remove_quic_track("audio-1");
-            assert!(removed);
+        let mut waiting = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            if let CallEvent::CallWaiting { offer, active } = event {
+                assert_eq!(active, first);
+                waiting.push(offer.call_id);
+            }
         }
+        assert_eq!(waiting, [second, third]);
 
-        // Verify
-        let calls = call_manager.calls.read().await;
-        let call = calls.get(&call_id).unwrap();
+        let bye = manager.reject_busy(third).await.unwrap();
+        assert!(matches!(
+            bye,
+            SignalingMessage::Bye { reason: Some(ref reason), .. } if reason == BUSY_REASON
+        ));
 
-        assert_eq!(call.quic_tracks.len(), 1);
-        assert!(call.get_quic_track_by_id("audio-1").is_none());
-        assert!(call.get_quic_track_by_id("video-1").is_some());
+        let held = manager
+            .answer_waiting_call(second, MediaConstraints::audio_only())
+            .await
+            .unwrap();
+        assert_eq!(held, [first]);
+        assert_eq!(manager.active_call().await, Some(second));
+        let transport = manager.media_transport(first).await.unwrap();
+        assert_eq!(transport.media_gate().await, MediaGate::Closed);
+
+        manager.end_call(second).await.unwrap();
+        manager.resume_call(first).await.unwrap();
+        assert_eq!(manager.active_call().await, Some(first));
+        assert_eq!(transport.media_gate().await, MediaGate::Open);
     }
 
     #[tokio::test]
-    async fn test_call_transport_accessor() {
-        let config = CallManagerConfig::default();
-        let call_manager = CallManager::<PeerIdentityString>::new(config)
+    async fn test_audio_fec_follows_loss() {
+        let manager = CallManager::<PeerIdentityString>::new(CallManagerConfig::default())
+            .await
+            .unwrap();
+        let mut events = manager.subscribe_events();
+        let call_id = CallId::new();
+        manager
+            .handle_incoming_call(offer(call_id, "alice", "bob"))
+            .await
+            .unwrap();
+        manager
+            .accept_call(call_id, MediaConstraints::audio_only())
             .await
             .unwrap();
+        let transport = manager.media_transport(call_id).await.unwrap();
+        let sample = |packet_loss_percent| CallQualityMetrics {
+            rtt_ms: 80,
+            packet_loss_percent,
+            jitter_ms: 10,
+            bandwidth_kbps: 500,
+            packet_rate: 50.0,
+            timestamp: Utc::now(),
+        };
 
-        let callee = PeerIdentityString::new("callee");
-        let constraints = MediaConstraints::audio_only();
-        let peer = test_peer();
+        manager.report_quality(call_id, sample(8.0)).await.unwrap();
+        assert_eq!(transport.stats().await.audio_fec_loss_percent, 10);
+        for _ in 0..FecConfig::default().lower_after_reports {
+            manager.report_quality(call_id, sample(0.0)).await.unwrap();
+        }
+        assert_eq!(transport.stats().await.audio_fec_loss_percent, 5);
 
-        let call_id = call_manager
-            .initiate_quic_call(callee, constraints, peer)
+        let mut levels = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            if let CallEvent::AudioFecChanged {
+                expected_loss_percent,
+                ..
+            } = event
+            {
+                levels.push(expected_loss_percent);
+            }
+        }
+        assert_eq!(levels, [10, 5]);
+    }
+
+    #[tokio::test]
+    async fn test_poor_network_degrades_to_audio_only() {
+        let manager = CallManager::<PeerIdentityString>::new(CallManagerConfig::default())
             .await
             .unwrap();
+        let mut events = manager.subscribe_events();
+        let call_id = CallId::new();
+        manager
+            .handle_incoming_call(offer(call_id, "alice", "bob"))
+            .await
+            .unwrap();
+        manager
+            .accept_call(call_id, MediaConstraints::video_call())
+            .await
+            .unwrap();
+        let transport = manager.media_transport(call_id).await.unwrap();
+        let sample = |packet_loss_percent, bandwidth_kbps| CallQualityMetrics {
+            rtt_ms: 120,
+            packet_loss_percent,
+            jitter_ms: 30,
+            bandwidth_kbps,
+            packet_rate: 50.0,
+            timestamp: Utc::now(),
+        };
+
+        let config = DegradationConfig::default();
+        let mut messages = Vec::new();
+        for _ in 0..config.sustain_samples {
+            messages.push(
+                manager
+                    .report_quality(call_id, sample(15.0, 800))
+                    .await
+                    .unwrap(),
+            );
+        }
+        assert!(messages[..messages.len() - 1].iter().all(Option::is_none));
+        assert!(matches!(
+            messages.last(),
+            Some(Some(SignalingMessage::AudioOnly { active: true, .. }))
+        ));
+        assert_eq!(transport.media_gate().await, MediaGate::EarlyMedia);
 
-        let calls = call_manager.calls.read().await;
-        let call = calls.get(&call_id).unwrap();
+        // Hold and resume keep video suspended
+        manager.hold_call(call_id).await.unwrap();
+        manager.resume_call(call_id).await.unwrap();
+        assert_eq!(transport.media_gate().await, MediaGate::EarlyMedia);
 
-        // Should have a transport since it's a QUIC call
-        assert!(call.transport().is_some());
+        let mut recovered = None;
+        for _ in 0..config.recovery_samples {
+            recovered = manager
+                .report_quality(call_id, sample(0.5, 2000))
+                .await
+                .unwrap();
+        }
+        assert!(matches!(
+            recovered,
+            Some(SignalingMessage::AudioOnly { active: false, .. })
+        ));
+        assert_eq!(transport.media_gate().await, MediaGate::Open);
+
+        let mut transitions = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            match event {
+                CallEvent::VideoSuspended { reason, .. } => transitions.push(Some(reason)),
+                CallEvent::VideoResumed { .. } => transitions.push(None),
+                _ => {}
+            }
+        }
+        assert_eq!(transitions, [Some(DegradationReason::PacketLoss), None]);
+    }
+
+    #[tokio::test]
+    async fn test_transport_quality_uses_transport_rates() {
+        let manager = CallManager::<PeerIdentityString>::new(CallManagerConfig::default())
+            .await
+            .unwrap();
+        let call_id = CallId::new();
+        manager
+            .handle_incoming_call(offer(call_id, "alice", "bob"))
+            .await
+            .unwrap();
+        manager
+            .accept_call(call_id, MediaConstraints::video_call())
+            .await
+            .unwrap();
+        let transport = manager.media_transport(call_id).await.unwrap();
+
+        transport.record_received(LinkStreamType::Video, 1000).await;
+        let metrics = manager.quality_metrics(call_id).await.unwrap();
+        assert!(metrics.bandwidth_kbps > 0);
+        assert!(metrics.packet_rate > 0.0);
+
+        // Nothing more arrives, so the measured bandwidth sits far below
+        // the threshold until video is suspended
+        let mut message = None;
+        for _ in 0..DegradationConfig::default().sustain_samples {
+            message = manager.report_transport_quality(call_id).await.unwrap();
+        }
+        assert!(matches!(
+            message,
+            Some(SignalingMessage::AudioOnly { active: true, .. })
+        ));
+        assert!(matches!(
+            manager.report_transport_quality(CallId::new()).await,
+            Err(CallError::CallNotFound(_))
+        ));
     }
 }
@@ -0,0 +1,144 @@
+//! Time sources for time-based logic
+//!
+//! Keepalive timeouts, rate estimation, statistics sampling and call
+//! scheduling read time through a [`Clock`] instead of calling
+//! `Instant::now()` or `Utc::now()` directly, so tests can substitute a
+//! [`MockClock`] and move time forward deterministically.
+//!
+//! [`TokioClock`] is the default. Its monotonic time follows tokio's clock,
+//! so it also honours `tokio::time::pause` and `advance` in tests that need
+//! timers to fire.
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Source of monotonic and wall-clock time
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// Monotonic time, for timeouts, intervals and rates
+    fn now(&self) -> Instant;
+
+    /// Wall-clock time, for timestamps and schedules
+    fn utc_now(&self) -> DateTime<Utc>;
+}
+
+/// A clock shared between subsystems
+pub type SharedClock = Arc<dyn Clock>;
+
+/// The default clock, reading tokio's time and the system wall clock
+#[must_use]
+pub fn system_clock() -> SharedClock {
+    Arc::new(TokioClock)
+}
+
+/// Clock backed by tokio's time and the system wall clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioClock;
+
+impl Clock for TokioClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn utc_now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Manually advanced clock for tests
+///
+/// Time stands still until [`advance`](Self::advance) is called. Clones
+/// share the same time, so a test can keep one handle and pass another to
+/// the code under test.
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    start: Instant,
+    start_utc: DateTime<Utc>,
+    elapsed: Arc<Mutex<Duration>>,
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockClock {
+    /// Create a clock stopped at the current time
+    #[must_use]
+    pub fn new() -> Self {
+        Self::starting_at(Utc::now())
+    }
+
+    /// Create a clock whose wall-clock time starts at `start_utc`
+    #[must_use]
+    pub fn starting_at(start_utc: DateTime<Utc>) -> Self {
+        Self {
+            start: Instant::now(),
+            start_utc,
+            elapsed: Arc::new(Mutex::new(Duration::ZERO)),
+        }
+    }
+
+    /// Move time forward
+    pub fn advance(&self, by: Duration) {
+        *self.elapsed.lock() += by;
+    }
+
+    /// Time advanced since creation
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        *self.elapsed.lock()
+    }
+
+    /// This clock as a [`SharedClock`]
+    #[must_use]
+    pub fn shared(&self) -> SharedClock {
+        Arc::new(self.clone())
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed()
+    }
+
+    fn utc_now(&self) -> DateTime<Utc> {
+        // Durations beyond chrono's range cannot be reached by a test
+        self.start_utc + chrono::Duration::from_std(self.elapsed()).unwrap_or(chrono::Duration::MAX)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_clock_advances_both_times() {
+        let start = DateTime::parse_from_rfc3339("2026-01-01T09:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let clock = MockClock::starting_at(start);
+        let shared = clock.shared();
+        let before = shared.now();
+        assert_eq!(shared.now(), before);
+        assert_eq!(shared.utc_now(), start);
+
+        clock.advance(Duration::from_secs(90));
+        assert_eq!(shared.now() - before, Duration::from_secs(90));
+        assert_eq!(shared.utc_now().to_rfc3339(), "2026-01-01T09:01:30+00:00");
+        assert_eq!(clock.elapsed(), Duration::from_secs(90));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_tokio_clock_follows_paused_time() {
+        let clock = system_clock();
+        let before = clock.now();
+        tokio::time::advance(Duration::from_secs(5)).await;
+        assert_eq!(clock.now() - before, Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,214 @@
+//! Signaling payload compression
+//!
+//! Large signaling frames (capability lists, SDP interop blobs) can be
+//! compressed with zstd or deflate. Compression is negotiated per peer in the
+//! [`ProtocolHello`](crate::wire_format::ProtocolHello) handshake and applied
+//! by the transport only to frames above a size threshold, so it is
+//! transparent to `SignalingHandler` users.
+//!
+//! Compressed frames wrap an already encoded frame with a single tag byte
+//! identifying the algorithm.
+
+use crate::wire_format::WireFormatError;
+use saorsa_webrtc_proto::wire::{TAG_DEFLATE, TAG_ZSTD};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+/// Default size above which frames are compressed (1KB)
+pub const DEFAULT_COMPRESSION_THRESHOLD: usize = 1024;
+
+/// Maximum size of a decompressed frame (256KB) to guard against
+/// decompression bombs
+pub const MAX_DECOMPRESSED_SIZE: usize = 256 * 1024;
+
+/// zstd compression level used for signaling frames
+const ZSTD_LEVEL: i32 = 3;
+
+/// Compression algorithm for signaling frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    /// Zstandard
+    Zstd,
+    /// Deflate (RFC 1951)
+    Deflate,
+}
+
+impl Compression {
+    /// All supported algorithms, most preferred first
+    pub const ALL: [Compression; 2] = [Self::Zstd, Self::Deflate];
+
+    /// Frame tag byte for this algorithm
+    #[must_use]
+    pub(crate) fn tag(self) -> u8 {
+        match self {
+            Self::Zstd => TAG_ZSTD,
+            Self::Deflate => TAG_DEFLATE,
+        }
+    }
+
+    /// Compress an encoded frame, prefixing the result with the algorithm tag
+    ///
+    /// # Errors
+    ///
+    /// Returns error if compression fails
+    pub fn compress(self, frame: &[u8]) -> Result<Vec<u8>, WireFormatError> {
+        let mut out = vec![self.tag()];
+        match self {
+            Self::Zstd => {
+                zstd::stream::copy_encode(frame, &mut out, ZSTD_LEVEL)
+                    .map_err(|e| WireFormatError::Compression(e.to_string()))?;
+            }
+            Self::Deflate => {
+                let mut encoder =
+                    flate2::write::DeflateEncoder::new(&mut out, flate2::Compression::default());
+                encoder
+                    .write_all(frame)
+                    .and_then(|()| encoder.finish().map(|_| ()))
+                    .map_err(|e| WireFormatError::Compression(e.to_string()))?;
+            }
+        }
+        Ok(out)
+    }
+
+    /// Decompress a frame body (without the tag byte)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the body is corrupt or decompresses to more than
+    /// [`MAX_DECOMPRESSED_SIZE`] bytes
+    pub fn decompress(self, body: &[u8]) -> Result<Vec<u8>, WireFormatError> {
+        let limit = MAX_DECOMPRESSED_SIZE as u64 + 1;
+        let mut out = Vec::new();
+        let read = match self {
+            Self::Zstd => zstd::stream::read::Decoder::new(body)
+                .and_then(|decoder| decoder.take(limit).read_to_end(&mut out)),
+            Self::Deflate => flate2::read::DeflateDecoder::new(body)
+                .take(limit)
+                .read_to_end(&mut out),
+        };
+        read.map_err(|e| WireFormatError::Compression(e.to_string()))?;
+
+        if out.len() > MAX_DECOMPRESSED_SIZE {
+            return Err(WireFormatError::DecompressedTooLarge(MAX_DECOMPRESSED_SIZE));
+        }
+        Ok(out)
+    }
+
+    /// Get the algorithm for a frame tag, if it is a compression tag
+    #[must_use]
+    pub(crate) fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            TAG_ZSTD => Some(Self::Zstd),
+            TAG_DEFLATE => Some(Self::Deflate),
+            _ => None,
+        }
+    }
+
+    /// Pick the algorithm to use with a remote peer
+    ///
+    /// Returns the first algorithm in `local` (in preference order) that the
+    /// remote also supports, or `None` if there is no overlap.
+    #[must_use]
+    pub fn negotiate(local: &[Compression], remote: &[Compression]) -> Option<Compression> {
+        local
+            .iter()
+            .copied()
+            .find(|algorithm| remote.contains(algorithm))
+    }
+}
+
+/// Compression configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressionConfig {
+    /// Algorithms to offer during the protocol handshake, in preference order.
+    /// Empty disables compression.
+    pub algorithms: Vec<Compression>,
+    /// Frames larger than this many bytes are compressed
+    pub threshold: usize,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            algorithms: Compression::ALL.to_vec(),
+            threshold: DEFAULT_COMPRESSION_THRESHOLD,
+        }
+    }
+}
+
+impl CompressionConfig {
+    /// Configuration with compression disabled
+    #[must_use]
+    pub fn disabled() -> Self {
+        Self {
+            algorithms: Vec::new(),
+            threshold: DEFAULT_COMPRESSION_THRESHOLD,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn compressible() -> Vec<u8> {
+        "a=candidate:1 1 UDP 2130706431 192.168.1.1 9000 typ host\r\n"
+            .repeat(100)
+            .into_bytes()
+    }
+
+    #[test]
+    fn test_roundtrip_all_algorithms() {
+        let data = compressible();
+        for algorithm in Compression::ALL {
+            let frame = algorithm.compress(&data).unwrap();
+            assert_eq!(frame[0], algorithm.tag());
+            assert!(frame.len() < data.len());
+
+            let restored = algorithm.decompress(&frame[1..]).unwrap();
+            assert_eq!(restored, data);
+        }
+    }
+
+    #[test]
+    fn test_from_tag() {
+        for algorithm in Compression::ALL {
+            assert_eq!(Compression::from_tag(algorithm.tag()), Some(algorithm));
+        }
+        assert_eq!(Compression::from_tag(b'{'), None);
+    }
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(
+            Compression::negotiate(&Compression::ALL, &[Compression::Deflate]),
+            Some(Compression::Deflate)
+        );
+        assert_eq!(Compression::negotiate(&Compression::ALL, &[]), None);
+        assert_eq!(Compression::negotiate(&[], &Compression::ALL), None);
+    }
+
+    #[test]
+    fn test_decompression_limit() {
+        let bomb = vec![0u8; MAX_DECOMPRESSED_SIZE * 2];
+        for algorithm in Compression::ALL {
+            let frame = algorithm.compress(&bomb).unwrap();
+            assert_eq!(
+                algorithm.decompress(&frame[1..]),
+                Err(WireFormatError::DecompressedTooLarge(MAX_DECOMPRESSED_SIZE))
+            );
+        }
+    }
+
+    #[test]
+    fn test_corrupt_body_rejected() {
+        for algorithm in Compression::ALL {
+            assert!(matches!(
+                algorithm.decompress(&[0xde, 0xad, 0xbe, 0xef]),
+                Err(WireFormatError::Compression(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,518 @@
+//! Conference-wide chat and reactions
+//!
+//! A [`ConferenceChat`] joins the calls that make up a conference and
+//! carries text messages and reactions between all of them. Messages are
+//! framed like media: an [`RtpPacket`] on their own data stream
+//! ([`chat_key`]), so they share the media path, taps and captures.
+//!
+//! Every message has a unique id. How it reaches everyone depends on
+//! [`ChatRouting`]: in a full mesh each participant floods new messages to
+//! all its other calls, and on an SFU the hub relays them; either way the
+//! ids seen recently are remembered, so a message that comes back along
+//! another path is dropped rather than shown twice.
+
+use crate::identity::PeerIdentity;
+use crate::link_transport::StreamType;
+use crate::quic_bridge::{RtpPacket, StreamType as RtpStreamType};
+use crate::quic_media_transport::{QuicMediaTransport, StreamKey, TrackId};
+use crate::types::CallId;
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+/// Track of the chat stream among the data streams
+pub const CHAT_TRACK: TrackId = TrackId::MAX - 2;
+
+/// Payload type of chat packets
+pub const CHAT_PAYLOAD_TYPE: u8 = 126;
+
+/// Key of the stream chat is sent on
+#[must_use]
+pub const fn chat_key() -> StreamKey {
+    StreamKey::new(StreamType::Data, CHAT_TRACK)
+}
+
+/// Id of a chat message or reaction
+pub type MessageId = Uuid;
+
+/// Conference chat errors
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ConferenceError {
+    /// The message is empty or longer than allowed
+    #[error("Invalid chat message: {0}")]
+    InvalidMessage(String),
+
+    /// The message does not fit in one packet
+    #[error("Chat message too large: {0}")]
+    TooLarge(String),
+
+    /// A packet is not a chat message
+    #[error("Malformed chat packet: {0}")]
+    Malformed(String),
+}
+
+/// How messages reach the whole conference
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatRouting {
+    /// Every participant has a call with every other; new messages are
+    /// flooded to all other calls
+    Mesh,
+    /// We are the SFU: every message is relayed to all other participants
+    Relay,
+    /// We reach the conference through an SFU, which relays for us
+    Leaf,
+}
+
+impl ChatRouting {
+    /// Whether messages received on one call go out on the others
+    #[must_use]
+    pub fn forwards(self) -> bool {
+        matches!(self, ChatRouting::Mesh | ChatRouting::Relay)
+    }
+}
+
+/// Conference chat configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatConfig {
+    /// How messages reach the whole conference
+    pub routing: ChatRouting,
+    /// Number of recent message ids remembered to drop duplicates
+    pub dedupe_window: usize,
+    /// Longest chat message accepted, in characters
+    pub max_text_len: usize,
+    /// Longest reaction accepted, in characters
+    pub max_reaction_len: usize,
+}
+
+impl Default for ChatConfig {
+    fn default() -> Self {
+        Self {
+            routing: ChatRouting::Mesh,
+            dedupe_window: 1024,
+            max_text_len: 500,
+            max_reaction_len: 16,
+        }
+    }
+}
+
+/// Something said in the conference
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "I: PeerIdentity")]
+pub enum ConferenceEvent<I: PeerIdentity> {
+    /// A chat message
+    ChatMessage {
+        /// Message id
+        id: MessageId,
+        /// Who wrote it
+        from: I,
+        /// Message text
+        text: String,
+        /// When it was written
+        sent_at: DateTime<Utc>,
+    },
+    /// A reaction, to the conference or to one message
+    Reaction {
+        /// Reaction id
+        id: MessageId,
+        /// Who reacted
+        from: I,
+        /// Reaction, usually a single emoji
+        emoji: String,
+        /// Message reacted to, if any
+        target: Option<MessageId>,
+    },
+}
+
+impl<I: PeerIdentity> ConferenceEvent<I> {
+    /// Unique id of the message or reaction
+    #[must_use]
+    pub fn id(&self) -> MessageId {
+        match self {
+            ConferenceEvent::ChatMessage { id, .. } | ConferenceEvent::Reaction { id, .. } => *id,
+        }
+    }
+
+    /// Who sent it
+    #[must_use]
+    pub fn from(&self) -> &I {
+        match self {
+            ConferenceEvent::ChatMessage { from, .. } | ConferenceEvent::Reaction { from, .. } => {
+                from
+            }
+        }
+    }
+
+    /// Frame the event as a packet for [`chat_key`]
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the event does not fit in one packet
+    pub fn to_rtp(&self, ssrc: u32, sequence_number: u16) -> Result<RtpPacket, ConferenceError> {
+        let payload =
+            postcard::to_stdvec(self).map_err(|e| ConferenceError::TooLarge(e.to_string()))?;
+        RtpPacket::new(
+            CHAT_PAYLOAD_TYPE,
+            sequence_number,
+            0,
+            ssrc,
+            payload,
+            RtpStreamType::Data,
+        )
+        .map_err(|e| ConferenceError::TooLarge(e.to_string()))
+    }
+
+    /// Read an event framed by [`Self::to_rtp`]
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the packet does not hold a chat event
+    pub fn from_rtp(packet: &RtpPacket) -> Result<Self, ConferenceError> {
+        if packet.payload_type != CHAT_PAYLOAD_TYPE {
+            return Err(ConferenceError::Malformed(format!(
+                "payload type {}",
+                packet.payload_type
+            )));
+        }
+        postcard::from_bytes(&packet.payload).map_err(|e| ConferenceError::Malformed(e.to_string()))
+    }
+}
+
+/// Ids of the most recent messages
+#[derive(Debug)]
+struct SeenIds {
+    ids: HashSet<MessageId>,
+    order: VecDeque<MessageId>,
+    capacity: usize,
+}
+
+impl SeenIds {
+    fn new(capacity: usize) -> Self {
+        Self {
+            ids: HashSet::new(),
+            order: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Remember an id; false if it was already seen
+    fn insert(&mut self, id: MessageId) -> bool {
+        if !self.ids.insert(id) {
+            return false;
+        }
+        self.order.push_back(id);
+        if self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.ids.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+/// State shared with the tasks receiving from each call
+struct Shared<I: PeerIdentity> {
+    routing: ChatRouting,
+    members: Mutex<HashMap<CallId, Arc<QuicMediaTransport>>>,
+    seen: Mutex<SeenIds>,
+    events: broadcast::Sender<ConferenceEvent<I>>,
+    ssrc: u32,
+    sequence_number: AtomicU16,
+}
+
+impl<I: PeerIdentity> Shared<I> {
+    /// Send an event on every call but `except`
+    async fn fan_out(&self, event: &ConferenceEvent<I>, except: Option<CallId>) {
+        let sequence_number = self.sequence_number.fetch_add(1, Ordering::Relaxed);
+        let bytes = match event.to_rtp(self.ssrc, sequence_number).and_then(|packet| {
+            packet
+                .to_bytes()
+                .map_err(|e| ConferenceError::TooLarge(e.to_string()))
+        }) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                tracing::warn!(error = %e, "Chat message not sent");
+                return;
+            }
+        };
+        let members: Vec<(CallId, Arc<QuicMediaTransport>)> = self
+            .members
+            .lock()
+            .iter()
+            .filter(|(call_id, _)| Some(**call_id) != except)
+            .map(|(call_id, transport)| (*call_id, Arc::clone(transport)))
+            .collect();
+        for (call_id, transport) in members {
+            if let Err(e) = transport.send_track_rtp(chat_key(), &bytes).await {
+                tracing::warn!(call_id = %call_id, error = %e, "Chat message not delivered");
+            }
+        }
+    }
+
+    /// Handle a packet received on one call
+    async fn receive(&self, call_id: CallId, packet: &RtpPacket) {
+        let event = match ConferenceEvent::from_rtp(packet) {
+            Ok(event) => event,
+            Err(e) => {
+                tracing::debug!(call_id = %call_id, error = %e, "Ignoring chat packet");
+                return;
+            }
+        };
+        if !self.seen.lock().insert(event.id()) {
+            return;
+        }
+        let _ = self.events.send(event.clone());
+        if self.routing.forwards() {
+            self.fan_out(&event, Some(call_id)).await;
+        }
+    }
+}
+
+/// Chat and reactions shared by all the calls of a conference
+pub struct ConferenceChat<I: PeerIdentity> {
+    local: I,
+    config: ChatConfig,
+    shared: Arc<Shared<I>>,
+    receivers: Mutex<HashMap<CallId, JoinHandle<()>>>,
+}
+
+impl<I: PeerIdentity> ConferenceChat<I> {
+    /// Create the chat of a conference we take part in as `local`
+    #[must_use]
+    pub fn new(local: I, config: ChatConfig) -> Self {
+        let (events, _) = broadcast::channel(256);
+        let shared = Arc::new(Shared {
+            routing: config.routing,
+            members: Mutex::new(HashMap::new()),
+            seen: Mutex::new(SeenIds::new(config.dedupe_window)),
+            events,
+            ssrc: rand::random(),
+            sequence_number: AtomicU16::new(rand::random()),
+        });
+        Self {
+            local,
+            config,
+            shared,
+            receivers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Receive chat messages and reactions, including our own
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<ConferenceEvent<I>> {
+        self.shared.events.subscribe()
+    }
+
+    /// Carry the chat over a call with another participant, or the SFU
+    pub fn add_participant(&self, call_id: CallId, transport: Arc<QuicMediaTransport>) {
+        let mut packets = transport.subscribe_track(chat_key());
+        self.shared.members.lock().insert(call_id, transport);
+        let shared = Arc::clone(&self.shared);
+        let receiver = tokio::spawn(async move {
+            while let Some(packet) = packets.recv().await {
+                shared.receive(call_id, &packet).await;
+            }
+        });
+        if let Some(previous) = self.receivers.lock().insert(call_id, receiver) {
+            previous.abort();
+        }
+    }
+
+    /// Stop carrying the chat over a call
+    ///
+    /// Returns false if the call was not part of the chat.
+    pub fn remove_participant(&self, call_id: CallId) -> bool {
+        if let Some(receiver) = self.receivers.lock().remove(&call_id) {
+            receiver.abort();
+        }
+        self.shared.members.lock().remove(&call_id).is_some()
+    }
+
+    /// Calls the chat is carried over
+    #[must_use]
+    pub fn participants(&self) -> Vec<CallId> {
+        let mut calls: Vec<CallId> = self.shared.members.lock().keys().copied().collect();
+        calls.sort_unstable();
+        calls
+    }
+
+    /// Send a chat message to the conference
+    ///
+    /// Returns the message id, which reactions can target.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the message is empty or too long
+    pub async fn send_chat(&self, text: impl Into<String>) -> Result<MessageId, ConferenceError> {
+        let text = text.into();
+        check_length("message", &text, self.config.max_text_len)?;
+        let event = ConferenceEvent::ChatMessage {
+            id: Uuid::new_v4(),
+            from: self.local.clone(),
+            text,
+            sent_at: Utc::now(),
+        };
+        self.publish(event).await
+    }
+
+    /// React to the conference, or to one message
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the reaction is empty or too long
+    pub async fn react(
+        &self,
+        emoji: impl Into<String>,
+        target: Option<MessageId>,
+    ) -> Result<MessageId, ConferenceError> {
+        let emoji = emoji.into();
+        check_length("reaction", &emoji, self.config.max_reaction_len)?;
+        let event = ConferenceEvent::Reaction {
+            id: Uuid::new_v4(),
+            from: self.local.clone(),
+            emoji,
+            target,
+        };
+        self.publish(event).await
+    }
+
+    async fn publish(&self, event: ConferenceEvent<I>) -> Result<MessageId, ConferenceError> {
+        // Fail before anything is shown if the event cannot be sent
+        event.to_rtp(self.shared.ssrc, 0)?;
+        let id = event.id();
+        self.shared.seen.lock().insert(id);
+        let _ = self.shared.events.send(event.clone());
+        self.shared.fan_out(&event, None).await;
+        Ok(id)
+    }
+}
+
+impl<I: PeerIdentity> Drop for ConferenceChat<I> {
+    fn drop(&mut self) {
+        for (_, receiver) in self.receivers.lock().drain() {
+            receiver.abort();
+        }
+    }
+}
+
+fn check_length(what: &str, text: &str, max: usize) -> Result<(), ConferenceError> {
+    if text.trim().is_empty() {
+        return Err(ConferenceError::InvalidMessage(format!("empty {what}")));
+    }
+    let len = text.chars().count();
+    if len > max {
+        return Err(ConferenceError::InvalidMessage(format!(
+            "{what} of {len} characters, at most {max} allowed"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::identity::PeerIdentityString;
+    use crate::link_transport::PeerConnection;
+    use std::time::Duration;
+
+    fn peer(name: &str) -> PeerIdentityString {
+        PeerIdentityString::new(name)
+    }
+
+    async fn connected() -> Arc<QuicMediaTransport> {
+        let transport = Arc::new(QuicMediaTransport::new());
+        transport
+            .connect(PeerConnection {
+                peer_id: "test-peer".to_string(),
+                remote_addr: "127.0.0.1:8080".parse().unwrap(),
+            })
+            .await
+            .unwrap();
+        transport
+    }
+
+    async fn next(
+        events: &mut broadcast::Receiver<ConferenceEvent<PeerIdentityString>>,
+    ) -> ConferenceEvent<PeerIdentityString> {
+        tokio::time::timeout(Duration::from_secs(1), events.recv())
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_chat_packet_roundtrip() {
+        let event = ConferenceEvent::Reaction {
+            id: Uuid::new_v4(),
+            from: peer("alice"),
+            emoji: "👍".to_string(),
+            target: Some(Uuid::new_v4()),
+        };
+        let bytes = event.to_rtp(7, 1).unwrap().to_bytes().unwrap();
+        let packet = RtpPacket::from_bytes(&bytes).unwrap();
+        assert_eq!(ConferenceEvent::from_rtp(&packet).unwrap(), event);
+
+        let audio = RtpPacket::new(111, 1, 0, 7, vec![1], RtpStreamType::Audio).unwrap();
+        assert!(ConferenceEvent::<PeerIdentityString>::from_rtp(&audio).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_relay_forwards_once_and_drops_duplicates() {
+        let chat = ConferenceChat::new(
+            peer("hub"),
+            ChatConfig {
+                routing: ChatRouting::Relay,
+                ..ChatConfig::default()
+            },
+        );
+        let (alice, bob) = (connected().await, connected().await);
+        let (alice_call, bob_call) = (CallId::new(), CallId::new());
+        chat.add_participant(alice_call, Arc::clone(&alice));
+        chat.add_participant(bob_call, Arc::clone(&bob));
+        assert_eq!(chat.participants().len(), 2);
+        let mut events = chat.subscribe();
+
+        let message = ConferenceEvent::ChatMessage {
+            id: Uuid::new_v4(),
+            from: peer("alice"),
+            text: "Hi all".to_string(),
+            sent_at: Utc::now(),
+        };
+        let bytes = message.to_rtp(1, 1).unwrap().to_bytes().unwrap();
+        alice.deliver_track_rtp(chat_key(), &bytes).await;
+        assert_eq!(next(&mut events).await, message);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // Relayed to Bob only; the copy coming back is dropped
+        let stats = |transport: &Arc<QuicMediaTransport>| {
+            let transport = Arc::clone(transport);
+            async move { transport.stats().await.packets_sent }
+        };
+        assert_eq!(stats(&bob).await, 1);
+        assert_eq!(stats(&alice).await, 0);
+        bob.deliver_track_rtp(chat_key(), &bytes).await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(events.try_recv().is_err());
+        assert_eq!(stats(&alice).await, 0);
+
+        // Our own messages go to everyone and are shown locally
+        let id = chat.react("🎉", Some(message.id())).await.unwrap();
+        assert_eq!(next(&mut events).await.id(), id);
+        assert_eq!(stats(&alice).await, 1);
+        assert_eq!(stats(&bob).await, 2);
+
+        assert!(matches!(
+            chat.send_chat("  ").await,
+            Err(ConferenceError::InvalidMessage(_))
+        ));
+        assert!(chat.remove_participant(alice_call));
+        assert!(!chat.remove_participant(alice_call));
+    }
+}
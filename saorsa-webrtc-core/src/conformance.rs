@@ -0,0 +1,578 @@
+//! Interoperability test vectors for the signaling wire protocol
+//!
+//! Alternative implementations (e.g. a JavaScript or Swift port) check
+//! compatibility against a [`TestVectors`] file rather than against this
+//! crate's source. The file lists:
+//!
+//! - signaling frames as hex, with the message, hello, or rejection each
+//!   must decode to. Canonical frames must also be produced byte for byte
+//!   by an encoder; compressed frames need not be, since compressors differ.
+//! - capability negotiations: two peers' hellos and the codec they agree on.
+//!
+//! Messages are given in their JSON form, as in [`SignalingMessage`]'s
+//! serde representation. The published vectors live in
+//! `tests/vectors/wire_protocol.json`; the `saorsa-conformance` binary
+//! regenerates them and checks vector files written by other
+//! implementations against this crate:
+//!
+//! ```text
+//! saorsa-conformance generate tests/vectors/wire_protocol.json
+//! saorsa-conformance check their_vectors.json
+//! ```
+
+use crate::compression::{Compression, CompressionConfig};
+use crate::signaling::SignalingMessage;
+use crate::types::{CallProgress, MediaType, TrackInfo, TrackSource, VideoLayer};
+use crate::wire_format::{
+    decode_frame, FrameCodec, ProtocolHello, WireFormat, WireFormatError, WireFrame,
+    SIGNALING_PROTOCOL_VERSION,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use thiserror::Error;
+
+/// Version of the test vector file layout
+pub const VECTORS_FORMAT_VERSION: u16 = 1;
+
+/// Conformance errors
+#[derive(Error, Debug)]
+pub enum ConformanceError {
+    /// A vector file could not be parsed or written
+    #[error("Vector file error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// A vector could not be generated
+    #[error("Vector generation error: {0}")]
+    Generate(#[from] WireFormatError),
+}
+
+/// What a frame must decode to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "expect", rename_all = "snake_case")]
+pub enum ExpectedFrame {
+    /// A signaling message in the given format
+    Message {
+        /// Format the frame is encoded in
+        format: WireFormat,
+        /// The message, in its JSON form
+        message: SignalingMessage,
+    },
+    /// A protocol hello
+    Hello {
+        /// The hello
+        hello: ProtocolHello,
+    },
+    /// Nothing: decoders must reject the frame
+    Rejected {
+        /// Why, for people reading the file
+        reason: String,
+    },
+}
+
+/// One signaling frame
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrameVector {
+    /// Unique name of the vector
+    pub name: String,
+    /// The frame bytes, as lowercase hex
+    pub frame: String,
+    /// Whether encoders must produce exactly these bytes
+    pub canonical: bool,
+    /// What the frame decodes to
+    #[serde(flatten)]
+    pub expected: ExpectedFrame,
+}
+
+/// One capability negotiation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NegotiationVector {
+    /// Unique name of the vector
+    pub name: String,
+    /// Our hello, whose lists give our preference order
+    pub local: ProtocolHello,
+    /// The peer's hello
+    pub remote: ProtocolHello,
+    /// Format we send the peer
+    pub format: WireFormat,
+    /// Compression we apply to large frames, if any
+    pub compression: Option<Compression>,
+}
+
+/// A set of test vectors
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestVectors {
+    /// Layout version of this file
+    pub format_version: u16,
+    /// Signaling protocol version the vectors describe
+    pub protocol_version: u16,
+    /// Signaling frames
+    pub frames: Vec<FrameVector>,
+    /// Capability negotiations
+    pub negotiations: Vec<NegotiationVector>,
+}
+
+/// A vector this crate disagrees with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    /// Name of the vector
+    pub name: String,
+    /// What differed
+    pub detail: String,
+}
+
+impl TestVectors {
+    /// Generate the vectors from this crate's encoder
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a sample message cannot be encoded
+    pub fn generate() -> Result<Self, ConformanceError> {
+        let mut frames = Vec::new();
+        for (name, message) in sample_messages() {
+            for format in WireFormat::ALL {
+                frames.push(FrameVector {
+                    name: format!("{name}/{}", format_name(format)),
+                    frame: to_hex(&format.encode(&message)?),
+                    canonical: true,
+                    expected: ExpectedFrame::Message {
+                        format,
+                        message: message.clone(),
+                    },
+                });
+            }
+        }
+
+        let large = SignalingMessage::Offer {
+            session_id: "call-compressed".to_string(),
+            sdp: "a=candidate:1 1 UDP 2130706431 192.0.2.1 9 typ host\r\n".repeat(40),
+            quic_endpoint: None,
+        };
+        for algorithm in Compression::ALL {
+            let frame = algorithm.compress(&WireFormat::Cbor.encode(&large)?)?;
+            frames.push(FrameVector {
+                name: format!("compressed/{}", compression_name(algorithm)),
+                frame: to_hex(&frame),
+                canonical: false,
+                expected: ExpectedFrame::Message {
+                    format: WireFormat::Cbor,
+                    message: large.clone(),
+                },
+            });
+        }
+
+        for (name, hello) in sample_hellos() {
+            frames.push(FrameVector {
+                name: format!("hello/{name}"),
+                frame: to_hex(&hello.encode()?),
+                canonical: true,
+                expected: ExpectedFrame::Hello { hello },
+            });
+        }
+
+        for (name, frame, reason) in [
+            ("rejected/empty", Vec::new(), "empty frame"),
+            ("rejected/unknown-tag", vec![0x42, 0x00], "unknown tag byte"),
+            (
+                "rejected/truncated-cbor",
+                vec![0x01, 0xa1],
+                "truncated CBOR body",
+            ),
+            (
+                "rejected/bad-json",
+                b"{\"type\":\"nope\"}".to_vec(),
+                "unknown message type",
+            ),
+        ] {
+            frames.push(FrameVector {
+                name: name.to_string(),
+                frame: to_hex(&frame),
+                canonical: false,
+                expected: ExpectedFrame::Rejected {
+                    reason: reason.to_string(),
+                },
+            });
+        }
+
+        let negotiations = sample_negotiations()
+            .into_iter()
+            .map(|(name, local, remote)| {
+                let codec = negotiate(&local, &remote);
+                NegotiationVector {
+                    name: name.to_string(),
+                    local,
+                    remote,
+                    format: codec.format,
+                    compression: codec.compression,
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            format_version: VECTORS_FORMAT_VERSION,
+            protocol_version: SIGNALING_PROTOCOL_VERSION,
+            frames,
+            negotiations,
+        })
+    }
+
+    /// Parse a vector file
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the JSON is not a vector file
+    pub fn from_json(json: &str) -> Result<Self, ConformanceError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Serialize as pretty-printed JSON
+    ///
+    /// # Errors
+    ///
+    /// Returns error if serialization fails
+    pub fn to_json(&self) -> Result<String, ConformanceError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Check every vector against this crate
+    ///
+    /// # Returns
+    ///
+    /// The vectors this crate disagrees with; empty if all conform.
+    #[must_use]
+    pub fn check(&self) -> Vec<Mismatch> {
+        let mut mismatches = Vec::new();
+        if self.protocol_version != SIGNALING_PROTOCOL_VERSION {
+            mismatches.push(Mismatch {
+                name: "protocol_version".to_string(),
+                detail: format!(
+                    "vectors describe version {}, this crate speaks {SIGNALING_PROTOCOL_VERSION}",
+                    self.protocol_version
+                ),
+            });
+        }
+        for vector in &self.frames {
+            if let Err(detail) = check_frame(vector) {
+                mismatches.push(Mismatch {
+                    name: vector.name.clone(),
+                    detail,
+                });
+            }
+        }
+        for vector in &self.negotiations {
+            let codec = negotiate(&vector.local, &vector.remote);
+            if (codec.format, codec.compression) != (vector.format, vector.compression) {
+                mismatches.push(Mismatch {
+                    name: vector.name.clone(),
+                    detail: format!(
+                        "negotiated {:?} with {:?}, expected {:?} with {:?}",
+                        codec.format, codec.compression, vector.format, vector.compression
+                    ),
+                });
+            }
+        }
+        mismatches
+    }
+}
+
+/// Check one frame vector, describing the first difference found
+fn check_frame(vector: &FrameVector) -> Result<(), String> {
+    let frame = from_hex(&vector.frame).ok_or("frame is not valid hex")?;
+    let decoded = decode_frame(&frame);
+    let encoded = match (&vector.expected, decoded) {
+        (ExpectedFrame::Rejected { .. }, Err(_)) => return Ok(()),
+        (ExpectedFrame::Rejected { .. }, Ok(frame)) => {
+            return Err(format!("decoded to {frame:?}, expected rejection"));
+        }
+        (_, Err(e)) => return Err(format!("failed to decode: {e}")),
+        (ExpectedFrame::Message { format, message }, Ok(decoded)) => {
+            let expected = WireFrame::Message(message.clone(), *format);
+            if decoded != expected {
+                return Err(format!("decoded to {decoded:?}, expected {expected:?}"));
+            }
+            format.encode(message)
+        }
+        (ExpectedFrame::Hello { hello }, Ok(decoded)) => {
+            let expected = WireFrame::Hello(hello.clone());
+            if decoded != expected {
+                return Err(format!("decoded to {decoded:?}, expected {expected:?}"));
+            }
+            hello.encode()
+        }
+    };
+    if !vector.canonical {
+        return Ok(());
+    }
+    match encoded {
+        Ok(encoded) if encoded == frame => Ok(()),
+        Ok(encoded) => Err(format!("encodes to {}", to_hex(&encoded))),
+        Err(e) => Err(format!("failed to encode: {e}")),
+    }
+}
+
+/// Codec negotiated from our hello and the peer's
+fn negotiate(local: &ProtocolHello, remote: &ProtocolHello) -> FrameCodec {
+    let compression = CompressionConfig {
+        algorithms: local.compression.clone(),
+        ..CompressionConfig::default()
+    };
+    FrameCodec::negotiate(&local.wire_formats, &compression, remote)
+}
+
+fn format_name(format: WireFormat) -> &'static str {
+    match format {
+        WireFormat::Json => "json",
+        WireFormat::Cbor => "cbor",
+        WireFormat::Postcard => "postcard",
+    }
+}
+
+fn compression_name(algorithm: Compression) -> &'static str {
+    match algorithm {
+        Compression::Zstd => "zstd",
+        Compression::Deflate => "deflate",
+    }
+}
+
+/// One message of every type, with optional fields both set and unset
+fn sample_messages() -> Vec<(&'static str, SignalingMessage)> {
+    let session_id = || "8f6c1f4e-3b1d-4c55-9a3e-2f0d7c9b1a20".to_string();
+    let endpoint = "[2001:db8::1]:9000".parse().ok();
+    vec![
+        (
+            "offer",
+            SignalingMessage::Offer {
+                session_id: session_id(),
+                sdp: "v=0\r\no=- 0 0 IN IP4 0.0.0.0\r\n".to_string(),
+                quic_endpoint: None,
+            },
+        ),
+        (
+            "answer",
+            SignalingMessage::Answer {
+                session_id: session_id(),
+                sdp: "v=0\r\n".to_string(),
+                quic_endpoint: endpoint,
+            },
+        ),
+        (
+            "ice-candidate",
+            SignalingMessage::IceCandidate {
+                session_id: session_id(),
+                candidate: "candidate:1 1 UDP 2130706431 192.0.2.1 9 typ host".to_string(),
+                sdp_mid: Some("0".to_string()),
+                sdp_mline_index: Some(0),
+            },
+        ),
+        (
+            "ice-complete",
+            SignalingMessage::IceComplete {
+                session_id: session_id(),
+            },
+        ),
+        (
+            "capability-exchange",
+            SignalingMessage::CapabilityExchange {
+                session_id: session_id(),
+                audio: true,
+                video: true,
+                data_channel: false,
+                max_bandwidth_kbps: 2_500,
+                quic_endpoint: "192.0.2.7:4433".parse().ok(),
+            },
+        ),
+        (
+            "connection-confirm",
+            SignalingMessage::ConnectionConfirm {
+                session_id: session_id(),
+                audio: true,
+                video: false,
+                data_channel: true,
+                max_bandwidth_kbps: 64,
+                quic_endpoint: None,
+            },
+        ),
+        (
+            "connection-ready",
+            SignalingMessage::ConnectionReady {
+                session_id: session_id(),
+            },
+        ),
+        (
+            "track-update",
+            SignalingMessage::TrackUpdate {
+                session_id: session_id(),
+                tracks: vec![
+                    TrackInfo::new(0, MediaType::Audio, TrackSource::Microphone, "Mic"),
+                    TrackInfo::new(1, MediaType::ScreenShare, TrackSource::Screen, ""),
+                ],
+            },
+        ),
+        (
+            "layer-selection",
+            SignalingMessage::LayerSelection {
+                session_id: session_id(),
+                track_id: 1,
+                layer: VideoLayer::new(2, 1),
+            },
+        ),
+        (
+            "handoff-offer",
+            SignalingMessage::HandoffOffer {
+                session_id: session_id(),
+                key: "q1w2e3r4t5y6u7i8o9p0".to_string(),
+            },
+        ),
+        (
+            "handoff-join",
+            SignalingMessage::HandoffJoin {
+                session_id: session_id(),
+                token: "token.signature".to_string(),
+            },
+        ),
+        (
+            "handoff-complete",
+            SignalingMessage::HandoffComplete {
+                session_id: session_id(),
+            },
+        ),
+        (
+            "progress-queued",
+            SignalingMessage::Progress {
+                session_id: session_id(),
+                progress: CallProgress::Queued { position: Some(3) },
+            },
+        ),
+        (
+            "audio-only",
+            SignalingMessage::AudioOnly {
+                session_id: session_id(),
+                active: true,
+            },
+        ),
+        (
+            "cancel",
+            SignalingMessage::Cancel {
+                session_id: session_id(),
+                reason: Some("answered elsewhere".to_string()),
+            },
+        ),
+        (
+            "bye",
+            SignalingMessage::Bye {
+                session_id: session_id(),
+                reason: None,
+            },
+        ),
+    ]
+}
+
+fn sample_hellos() -> Vec<(&'static str, ProtocolHello)> {
+    vec![
+        ("default", ProtocolHello::default()),
+        (
+            "json-only",
+            ProtocolHello {
+                version: SIGNALING_PROTOCOL_VERSION,
+                wire_formats: vec![WireFormat::Json],
+                compression: Vec::new(),
+            },
+        ),
+    ]
+}
+
+fn sample_negotiations() -> Vec<(&'static str, ProtocolHello, ProtocolHello)> {
+    let hello = |wire_formats: &[WireFormat], compression: &[Compression]| ProtocolHello {
+        version: SIGNALING_PROTOCOL_VERSION,
+        wire_formats: wire_formats.to_vec(),
+        compression: compression.to_vec(),
+    };
+    vec![
+        (
+            "both-default",
+            ProtocolHello::default(),
+            ProtocolHello::default(),
+        ),
+        (
+            "remote-json-only",
+            ProtocolHello::default(),
+            hello(&[WireFormat::Json], &[]),
+        ),
+        (
+            "local-preference-wins",
+            hello(
+                &[WireFormat::Cbor, WireFormat::Postcard],
+                &[Compression::Deflate],
+            ),
+            hello(&WireFormat::ALL, &Compression::ALL),
+        ),
+        (
+            "no-common-format",
+            hello(&[WireFormat::Postcard], &[Compression::Zstd]),
+            hello(&[WireFormat::Cbor], &[Compression::Deflate]),
+        ),
+    ]
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+    })
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_vectors_conform() {
+        let vectors = TestVectors::generate().unwrap();
+        assert!(vectors.check().is_empty());
+        let parsed = TestVectors::from_json(&vectors.to_json().unwrap()).unwrap();
+        assert_eq!(parsed, vectors);
+    }
+
+    #[test]
+    fn test_check_reports_mismatches() {
+        let mut vectors = TestVectors::generate().unwrap();
+        vectors.frames[0].frame.push_str("00");
+        vectors.negotiations[0].format = WireFormat::Json;
+        vectors.frames.push(FrameVector {
+            name: "not-rejected".to_string(),
+            frame: to_hex(&ProtocolHello::default().encode().unwrap()),
+            canonical: false,
+            expected: ExpectedFrame::Rejected {
+                reason: String::new(),
+            },
+        });
+
+        let names: Vec<String> = vectors.check().into_iter().map(|m| m.name).collect();
+        assert_eq!(
+            names,
+            vec![
+                vectors.frames[0].name.clone(),
+                "not-rejected".to_string(),
+                vectors.negotiations[0].name.clone(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hex_round_trip() {
+        assert_eq!(to_hex(&[0x00, 0x7f, 0xff]), "007fff");
+        assert_eq!(from_hex("007fff"), Some(vec![0x00, 0x7f, 0xff]));
+        assert_eq!(from_hex("0"), None);
+        assert_eq!(from_hex("zz"), None);
+    }
+}
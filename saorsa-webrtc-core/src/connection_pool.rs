@@ -0,0 +1,303 @@
+//! Connection pooling for peer transports
+//!
+//! Calls and data channels to the same peer reuse a single underlying
+//! connection. The pool tracks how many users hold each connection and
+//! evicts connections that have been unused for longer than the idle timeout.
+
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::time::Instant;
+
+/// Default maximum number of pooled connections
+pub const DEFAULT_MAX_CONNECTIONS: usize = 64;
+
+/// Default idle timeout before an unused connection is evicted (5 minutes)
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Connection pool errors
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PoolError {
+    /// Pool is at capacity and no idle connection can be evicted
+    #[error("Connection pool full: {0} connections in use")]
+    Full(usize),
+}
+
+/// Connection pool configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectionPoolConfig {
+    /// Maximum number of pooled connections
+    pub max_connections: usize,
+    /// Time an unused connection is kept before eviction
+    pub idle_timeout: Duration,
+}
+
+impl Default for ConnectionPoolConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+        }
+    }
+}
+
+/// A pooled connection and its usage bookkeeping
+#[derive(Debug)]
+struct PoolEntry<C> {
+    connection: C,
+    last_used: Instant,
+    leases: usize,
+}
+
+impl<C> PoolEntry<C> {
+    fn is_idle(&self, now: Instant, idle_timeout: Duration) -> bool {
+        self.leases == 0 && now.duration_since(self.last_used) >= idle_timeout
+    }
+}
+
+/// Pool of reusable connections keyed by peer
+///
+/// `K` identifies the peer (e.g. remote address) and `C` is a cheap handle
+/// to the connection (e.g. an ant-quic `PeerId`).
+#[derive(Debug)]
+pub struct ConnectionPool<K, C> {
+    config: ConnectionPoolConfig,
+    entries: Mutex<HashMap<K, PoolEntry<C>>>,
+}
+
+impl<K, C> ConnectionPool<K, C>
+where
+    K: Eq + Hash + Clone,
+    C: Clone + PartialEq,
+{
+    /// Create a new connection pool
+    #[must_use]
+    pub fn new(config: ConnectionPoolConfig) -> Self {
+        Self {
+            config,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Get pool configuration
+    #[must_use]
+    pub fn config(&self) -> &ConnectionPoolConfig {
+        &self.config
+    }
+
+    /// Acquire an existing connection to a peer
+    ///
+    /// Returns `None` if no connection is pooled. Each successful acquire
+    /// must be paired with a [`release`](Self::release).
+    pub fn acquire(&self, key: &K) -> Option<C> {
+        let mut entries = self.entries.lock();
+        let entry = entries.get_mut(key)?;
+        entry.leases += 1;
+        entry.last_used = Instant::now();
+        Some(entry.connection.clone())
+    }
+
+    /// Add a new connection to the pool, leased once by the caller
+    ///
+    /// If the pool is full, the least recently used idle connection is
+    /// evicted and returned so the caller can close it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PoolError::Full` if the pool is full and every connection is
+    /// in use
+    pub fn insert(&self, key: K, connection: C) -> Result<Option<(K, C)>, PoolError> {
+        let mut entries = self.entries.lock();
+        let mut evicted = None;
+
+        if !entries.contains_key(&key) && entries.len() >= self.config.max_connections {
+            let lru = entries
+                .iter()
+                .filter(|(_, entry)| entry.leases == 0)
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone())
+                .ok_or(PoolError::Full(entries.len()))?;
+            evicted = entries.remove(&lru).map(|entry| (lru, entry.connection));
+        }
+
+        entries.insert(
+            key,
+            PoolEntry {
+                connection,
+                last_used: Instant::now(),
+                leases: 1,
+            },
+        );
+        Ok(evicted)
+    }
+
+    /// Release a lease on a peer's connection
+    ///
+    /// The connection stays pooled and becomes eligible for idle eviction
+    /// once all leases are released.
+    pub fn release(&self, key: &K) {
+        if let Some(entry) = self.entries.lock().get_mut(key) {
+            entry.leases = entry.leases.saturating_sub(1);
+            entry.last_used = Instant::now();
+        }
+    }
+
+    /// Remove a peer's connection regardless of leases
+    pub fn remove(&self, key: &K) -> Option<C> {
+        self.entries
+            .lock()
+            .remove(key)
+            .map(|entry| entry.connection)
+    }
+
+    /// Remove every pooled entry for a connection handle
+    pub fn remove_connection(&self, connection: &C) -> Vec<K> {
+        let mut entries = self.entries.lock();
+        let keys: Vec<K> = entries
+            .iter()
+            .filter(|(_, entry)| entry.connection == *connection)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &keys {
+            entries.remove(key);
+        }
+        keys
+    }
+
+    /// Find the key a connection handle is pooled under
+    #[must_use]
+    pub fn key_of(&self, connection: &C) -> Option<K> {
+        self.entries
+            .lock()
+            .iter()
+            .find(|(_, entry)| entry.connection == *connection)
+            .map(|(key, _)| key.clone())
+    }
+
+    /// Evict connections that have been unused for the idle timeout
+    ///
+    /// Returns the evicted connections so the caller can close them.
+    pub fn evict_idle(&self) -> Vec<(K, C)> {
+        let now = Instant::now();
+        let mut entries = self.entries.lock();
+        let idle: Vec<K> = entries
+            .iter()
+            .filter(|(_, entry)| entry.is_idle(now, self.config.idle_timeout))
+            .map(|(key, _)| key.clone())
+            .collect();
+        idle.into_iter()
+            .filter_map(|key| entries.remove(&key).map(|entry| (key, entry.connection)))
+            .collect()
+    }
+
+    /// Get the number of active leases on a peer's connection
+    #[must_use]
+    pub fn leases(&self, key: &K) -> usize {
+        self.entries.lock().get(key).map_or(0, |entry| entry.leases)
+    }
+
+    /// Get the number of pooled connections
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.lock().len()
+    }
+
+    /// Check if the pool is empty
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.lock().is_empty()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn pool(max_connections: usize) -> ConnectionPool<&'static str, u32> {
+        ConnectionPool::new(ConnectionPoolConfig {
+            max_connections,
+            idle_timeout: Duration::from_secs(60),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_acquire_reuses_connection() {
+        let pool = pool(4);
+        assert_eq!(pool.acquire(&"alice"), None);
+
+        pool.insert("alice", 1).unwrap();
+        assert_eq!(pool.acquire(&"alice"), Some(1));
+        assert_eq!(pool.leases(&"alice"), 2);
+
+        pool.release(&"alice");
+        pool.release(&"alice");
+        assert_eq!(pool.leases(&"alice"), 0);
+        assert_eq!(pool.len(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_evict_idle_respects_timeout_and_leases() {
+        let pool = pool(4);
+        pool.insert("idle", 1).unwrap();
+        pool.insert("busy", 2).unwrap();
+        pool.release(&"idle");
+
+        tokio::time::advance(Duration::from_secs(30)).await;
+        assert!(pool.evict_idle().is_empty());
+
+        tokio::time::advance(Duration::from_secs(30)).await;
+        assert_eq!(pool.evict_idle(), vec![("idle", 1)]);
+        assert_eq!(pool.len(), 1);
+        assert_eq!(pool.acquire(&"busy"), Some(2));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_insert_evicts_lru_idle_when_full() {
+        let pool = pool(2);
+        pool.insert("a", 1).unwrap();
+        tokio::time::advance(Duration::from_secs(1)).await;
+        pool.insert("b", 2).unwrap();
+        pool.release(&"a");
+        tokio::time::advance(Duration::from_secs(1)).await;
+        pool.release(&"b");
+
+        assert_eq!(pool.insert("c", 3).unwrap(), Some(("a", 1)));
+        assert_eq!(pool.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_insert_fails_when_all_in_use() {
+        let pool = pool(1);
+        pool.insert("a", 1).unwrap();
+        assert_eq!(pool.insert("b", 2), Err(PoolError::Full(1)));
+
+        // Replacing an existing key does not need capacity
+        assert_eq!(pool.insert("a", 3), Ok(None));
+        assert_eq!(pool.acquire(&"a"), Some(3));
+    }
+
+    #[tokio::test]
+    async fn test_remove_connection() {
+        let pool = pool(4);
+        pool.insert("addr-v4", 7).unwrap();
+        pool.insert("addr-v6", 7).unwrap();
+        pool.insert("other", 8).unwrap();
+
+        let mut removed = pool.remove_connection(&7);
+        removed.sort_unstable();
+        assert_eq!(removed, vec!["addr-v4", "addr-v6"]);
+        assert_eq!(pool.remove(&"other"), Some(8));
+        assert!(pool.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_key_of_connection() {
+        let pool = pool(4);
+        pool.insert("addr-v6", 7).unwrap();
+        assert_eq!(pool.key_of(&7), Some("addr-v6"));
+        assert_eq!(pool.key_of(&8), None);
+    }
+}
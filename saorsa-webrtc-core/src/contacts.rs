@@ -0,0 +1,460 @@
+//! Contact list
+//!
+//! A [`ContactBook`] maps the names a user knows people by to their peer
+//! identities, along with the fingerprint the user verified for each (e.g.
+//! by comparing it out of band) and free-form notes. Names are unique and
+//! looked up without regard to case.
+//!
+//! Each contact carries [`ContactPermissions`], which
+//! [`ContactPolicy`](crate::policy::ContactPolicy) applies to their calls
+//! and data requests.
+//!
+//! [`ContactBook::search`] finds contacts by a partial or slightly
+//! misspelled name, so users can call "ali" instead of typing an address.
+//!
+//! A book opened with [`ContactBook::open`] is kept in a JSON file and
+//! saved after every change; one created with [`ContactBook::new`] lives
+//! in memory only.
+
+use crate::identity::PeerIdentity;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Contact list errors
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ContactError {
+    /// A contact with the name already exists
+    #[error("Contact already exists: {0}")]
+    Duplicate(String),
+
+    /// No contact has the name
+    #[error("Contact not found: {0}")]
+    NotFound(String),
+
+    /// The name is empty
+    #[error("Contact name cannot be empty")]
+    EmptyName,
+
+    /// Reading or writing the contacts file failed
+    #[error("Contacts storage error: {0}")]
+    Storage(String),
+}
+
+/// What a contact may do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct ContactPermissions {
+    /// Answer their calls without asking
+    pub auto_accept: bool,
+    /// Let their video calls ring; audio calls always may
+    pub video_allowed: bool,
+    /// Accept files they send
+    pub file_transfer_allowed: bool,
+}
+
+impl Default for ContactPermissions {
+    fn default() -> Self {
+        Self {
+            auto_accept: false,
+            video_allowed: true,
+            file_transfer_allowed: false,
+        }
+    }
+}
+
+/// Someone the user knows
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(bound = "")]
+pub struct Contact<I: PeerIdentity> {
+    /// Name the user knows them by
+    pub name: String,
+    /// Their peer identity
+    pub identity: I,
+    /// Fingerprint the user verified, if any, e.g. a
+    /// [`KeyFingerprint`](crate::identity::KeyFingerprint) of their key
+    pub fingerprint: Option<String>,
+    /// Free-form notes
+    #[serde(default)]
+    pub notes: String,
+    /// What they may do
+    #[serde(default)]
+    pub permissions: ContactPermissions,
+    /// When the contact was added
+    pub added_at: DateTime<Utc>,
+}
+
+impl<I: PeerIdentity> Contact<I> {
+    /// Create a contact with no fingerprint or notes
+    pub fn new(name: impl Into<String>, identity: I) -> Self {
+        Self {
+            name: name.into(),
+            identity,
+            fingerprint: None,
+            notes: String::new(),
+            permissions: ContactPermissions::default(),
+            added_at: Utc::now(),
+        }
+    }
+
+    /// Check if the user verified the contact's fingerprint
+    #[must_use]
+    pub fn is_verified(&self) -> bool {
+        self.fingerprint.is_some()
+    }
+}
+
+/// Lookup key of a name
+fn key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+/// How well a name matches a search, lower is better
+///
+/// Both are lookup keys. Ranks exact matches, then prefixes of the name or
+/// of one of its words, then substrings, then the query's letters in order,
+/// then names within a typo or two of the query.
+fn match_rank(name: &str, query: &str) -> Option<u8> {
+    if name == query {
+        Some(0)
+    } else if name.starts_with(query) {
+        Some(1)
+    } else if name.split_whitespace().any(|word| word.starts_with(query)) {
+        Some(2)
+    } else if name.contains(query) {
+        Some(3)
+    } else if is_subsequence(query, name) {
+        Some(4)
+    } else if query.chars().count() >= 3 && edit_distance(name, query) <= 2 {
+        Some(5)
+    } else {
+        None
+    }
+}
+
+/// Check if the characters of `needle` appear in order in `haystack`
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars();
+    needle.chars().all(|c| haystack.any(|h| h == c))
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Named contacts, optionally kept in a file
+#[derive(Debug)]
+pub struct ContactBook<I: PeerIdentity> {
+    contacts: BTreeMap<String, Contact<I>>,
+    path: Option<PathBuf>,
+}
+
+impl<I: PeerIdentity> Default for ContactBook<I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I: PeerIdentity> ContactBook<I> {
+    /// Create an empty in-memory book
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            contacts: BTreeMap::new(),
+            path: None,
+        }
+    }
+
+    /// Open the book kept at `path`, empty if the file does not exist yet
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be read or parsed
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, ContactError> {
+        let path = path.into();
+        let contacts: Vec<Contact<I>> = match std::fs::read(&path) {
+            Ok(data) => serde_json::from_slice(&data)
+                .map_err(|e| ContactError::Storage(format!("{}: {e}", path.display())))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(ContactError::Storage(e.to_string())),
+        };
+        Ok(Self {
+            contacts: contacts
+                .into_iter()
+                .map(|contact| (key(&contact.name), contact))
+                .collect(),
+            path: Some(path),
+        })
+    }
+
+    /// File the book is kept in, if any
+    #[must_use]
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Number of contacts
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.contacts.len()
+    }
+
+    /// Check if the book has no contacts
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.contacts.is_empty()
+    }
+
+    /// All contacts, by name
+    pub fn list(&self) -> impl Iterator<Item = &Contact<I>> {
+        self.contacts.values()
+    }
+
+    /// Contact with a name, ignoring case
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&Contact<I>> {
+        self.contacts.get(&key(name))
+    }
+
+    /// Contacts whose name matches a partial or misspelled name, best first
+    ///
+    /// An exact name is the only result, so it can be called without
+    /// asking which contact was meant.
+    #[must_use]
+    pub fn search(&self, query: &str) -> Vec<&Contact<I>> {
+        let query = key(query);
+        if query.is_empty() {
+            return Vec::new();
+        }
+        if let Some(contact) = self.contacts.get(&query) {
+            return vec![contact];
+        }
+        let mut matches: Vec<(u8, &Contact<I>)> = self
+            .contacts
+            .iter()
+            .filter_map(|(name, contact)| Some((match_rank(name, &query)?, contact)))
+            .collect();
+        // Stable sort keeps names in order within a rank
+        matches.sort_by_key(|(rank, _)| *rank);
+        matches.into_iter().map(|(_, contact)| contact).collect()
+    }
+
+    /// Contact with a peer identity
+    #[must_use]
+    pub fn find_by_identity(&self, identity: &I) -> Option<&Contact<I>> {
+        self.find_by_repr(&identity.to_string_repr())
+    }
+
+    /// Contact whose identity has a string form
+    #[must_use]
+    pub fn find_by_repr(&self, identity: &str) -> Option<&Contact<I>> {
+        self.contacts
+            .values()
+            .find(|contact| contact.identity.to_string_repr() == identity)
+    }
+
+    /// Add a contact
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the name is empty or taken, or saving fails
+    pub fn add(&mut self, mut contact: Contact<I>) -> Result<(), ContactError> {
+        contact.name = contact.name.trim().to_string();
+        let key = key(&contact.name);
+        if key.is_empty() {
+            return Err(ContactError::EmptyName);
+        }
+        if self.contacts.contains_key(&key) {
+            return Err(ContactError::Duplicate(contact.name));
+        }
+        self.contacts.insert(key, contact);
+        self.save()
+    }
+
+    /// Change a contact
+    ///
+    /// The name cannot be changed this way; remove and re-add the contact.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if no contact has the name, or saving fails
+    pub fn update(
+        &mut self,
+        name: &str,
+        change: impl FnOnce(&mut Contact<I>),
+    ) -> Result<(), ContactError> {
+        let contact = self
+            .contacts
+            .get_mut(&key(name))
+            .ok_or_else(|| ContactError::NotFound(name.to_string()))?;
+        let original_name = contact.name.clone();
+        change(contact);
+        contact.name = original_name;
+        self.save()
+    }
+
+    /// Record the fingerprint the user verified for a contact
+    ///
+    /// # Errors
+    ///
+    /// Returns error if no contact has the name, or saving fails
+    pub fn verify(
+        &mut self,
+        name: &str,
+        fingerprint: impl Into<String>,
+    ) -> Result<(), ContactError> {
+        let fingerprint = fingerprint.into();
+        self.update(name, |contact| contact.fingerprint = Some(fingerprint))
+    }
+
+    /// Set what a contact may do
+    ///
+    /// # Errors
+    ///
+    /// Returns error if no contact has the name, or saving fails
+    pub fn set_permissions(
+        &mut self,
+        name: &str,
+        permissions: ContactPermissions,
+    ) -> Result<(), ContactError> {
+        self.update(name, |contact| contact.permissions = permissions)
+    }
+
+    /// Remove a contact
+    ///
+    /// # Errors
+    ///
+    /// Returns error if no contact has the name, or saving fails
+    pub fn remove(&mut self, name: &str) -> Result<Contact<I>, ContactError> {
+        let contact = self
+            .contacts
+            .remove(&key(name))
+            .ok_or_else(|| ContactError::NotFound(name.to_string()))?;
+        self.save()?;
+        Ok(contact)
+    }
+
+    /// Write the book to its file, if it has one
+    fn save(&self) -> Result<(), ContactError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let storage_error = |e: std::io::Error| ContactError::Storage(e.to_string());
+        let contacts: Vec<&Contact<I>> = self.contacts.values().collect();
+        let json = serde_json::to_vec_pretty(&contacts)
+            .map_err(|e| ContactError::Storage(e.to_string()))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(storage_error)?;
+        }
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json).map_err(storage_error)?;
+        std::fs::rename(tmp, path).map_err(storage_error)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::identity::PeerIdentityString;
+
+    #[test]
+    fn test_contact_crud() {
+        let mut book = ContactBook::new();
+        book.add(Contact::new(
+            " Alice ",
+            PeerIdentityString::new("alice-bob-charlie-david"),
+        ))
+        .unwrap();
+        assert_eq!(
+            book.add(Contact::new("ALICE", PeerIdentityString::new("other"))),
+            Err(ContactError::Duplicate("ALICE".to_string()))
+        );
+        assert_eq!(
+            book.add(Contact::new("  ", PeerIdentityString::new("other"))),
+            Err(ContactError::EmptyName)
+        );
+
+        let alice = book.get("alice").unwrap();
+        assert_eq!(alice.name, "Alice");
+        assert!(!alice.is_verified());
+
+        book.verify("Alice", "ab12 cd34").unwrap();
+        book.update("alice", |contact| {
+            contact.notes = "Met at the conference".to_string();
+            contact.name = "ignored".to_string();
+        })
+        .unwrap();
+        let alice = book
+            .find_by_identity(&PeerIdentityString::new("alice-bob-charlie-david"))
+            .unwrap();
+        assert_eq!(alice.name, "Alice");
+        assert_eq!(alice.fingerprint.as_deref(), Some("ab12 cd34"));
+        assert_eq!(alice.notes, "Met at the conference");
+
+        assert_eq!(book.remove("alice").unwrap().name, "Alice");
+        assert!(book.is_empty());
+        assert_eq!(
+            book.remove("alice"),
+            Err(ContactError::NotFound("alice".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_search_ranks_matches() {
+        let mut book = ContactBook::new();
+        for name in ["Alice Smith", "Bob", "Mallory", "Sally", "Alicia"] {
+            book.add(Contact::new(name, PeerIdentityString::new(name)))
+                .unwrap();
+        }
+        let names = |query: &str| -> Vec<String> {
+            book.search(query)
+                .into_iter()
+                .map(|contact| contact.name.clone())
+                .collect()
+        };
+
+        assert_eq!(names("bob"), vec!["Bob"]);
+        assert_eq!(names("ali"), vec!["Alice Smith", "Alicia"]);
+        assert_eq!(names("smith"), vec!["Alice Smith"]);
+        assert_eq!(names("mlry"), vec!["Mallory"]);
+        assert_eq!(names("bobb"), vec!["Bob"]);
+        assert!(names("zed").is_empty());
+        assert!(names("  ").is_empty());
+    }
+
+    #[test]
+    fn test_contacts_persist() {
+        let dir = std::env::temp_dir().join(format!("saorsa-contacts-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("contacts.json");
+
+        let mut book = ContactBook::open(&path).unwrap();
+        book.add(Contact::new("Bob", PeerIdentityString::new("bob-id")))
+            .unwrap();
+        book.add(Contact::new("Carol", PeerIdentityString::new("carol-id")))
+            .unwrap();
+        book.remove("carol").unwrap();
+
+        let reopened = ContactBook::<PeerIdentityString>::open(&path).unwrap();
+        let names: Vec<&str> = reopened.list().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["Bob"]);
+        assert_eq!(reopened.path(), Some(path.as_path()));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,390 @@
+//! Presenter cursor overlay for screen share
+//!
+//! A screen share sent at a few frames per second makes the presenter's
+//! pointer jump around. Instead of relying on the pointer drawn into the
+//! frames, the presenter samples it with a [`CursorSampler`] and sends each
+//! [`CursorSample`] as [`TimedMetadata`] of kind [`MetadataKind::Cursor`],
+//! far more often than frames and at a fraction of their size.
+//!
+//! The receiver feeds the samples to a [`CursorOverlay`] and asks it where
+//! the cursor is at the playout timestamp of each rendered picture; it
+//! interpolates between samples, so the overlay moves smoothly however low
+//! the frame rate. With [`CursorConfig::highlight_clicks`], clicks are sent
+//! too and the overlay reports a fading highlight after each one.
+//!
+//! Positions are normalized to the shared surface, `0.0..=1.0` from the
+//! left and from the top, so they do not depend on either side's
+//! resolution.
+
+use crate::quic_media_transport::TrackId;
+use crate::timed_metadata::{
+    timestamp_diff, MetadataError, MetadataKind, TimedMetadata, VIDEO_CLOCK_RATE,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Samples kept by a [`CursorOverlay`]
+const OVERLAY_CAPACITY: usize = 64;
+
+/// Cursor overlay configuration, for the presenter and the viewers
+#[derive(Debug, Clone, PartialEq)]
+pub struct CursorConfig {
+    /// Shortest time between two samples of a moving cursor
+    pub min_interval: Duration,
+    /// Longest time between two samples, even if the cursor is still
+    pub max_interval: Duration,
+    /// Smallest movement worth a sample, as a fraction of the surface
+    pub min_movement: f32,
+    /// Send clicks and highlight them on the overlay
+    pub highlight_clicks: bool,
+    /// How long a click stays highlighted
+    pub click_highlight: Duration,
+}
+
+impl Default for CursorConfig {
+    fn default() -> Self {
+        Self {
+            min_interval: Duration::from_millis(33),
+            max_interval: Duration::from_secs(1),
+            min_movement: 0.001,
+            highlight_clicks: true,
+            click_highlight: Duration::from_millis(400),
+        }
+    }
+}
+
+/// Mouse button of a click
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClickButton {
+    /// Primary button
+    Left,
+    /// Secondary button
+    Right,
+    /// Middle button or wheel
+    Middle,
+}
+
+/// The presenter's cursor at one instant
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CursorSample {
+    /// Distance from the left edge, `0.0..=1.0`
+    pub x: f32,
+    /// Distance from the top edge, `0.0..=1.0`
+    pub y: f32,
+    /// Whether the cursor is over the shared surface
+    pub visible: bool,
+    /// Button clicked at this instant, if any
+    pub click: Option<ClickButton>,
+}
+
+impl CursorSample {
+    /// Visible cursor at a position, clamped to the surface
+    #[must_use]
+    pub fn at(x: f32, y: f32) -> Self {
+        Self {
+            x: x.clamp(0.0, 1.0),
+            y: y.clamp(0.0, 1.0),
+            visible: true,
+            click: None,
+        }
+    }
+
+    /// Cursor that has left the shared surface
+    #[must_use]
+    pub fn hidden() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            visible: false,
+            click: None,
+        }
+    }
+
+    /// The same sample with a click
+    #[must_use]
+    pub fn with_click(mut self, button: ClickButton) -> Self {
+        self.click = Some(button);
+        self
+    }
+
+    /// Metadata carrying the sample for the screen-share track
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the sample cannot be encoded
+    pub fn to_metadata(
+        &self,
+        track_id: TrackId,
+        timestamp: u32,
+    ) -> Result<TimedMetadata, MetadataError> {
+        let data = postcard::to_stdvec(self).map_err(|e| MetadataError::TooLarge(e.to_string()))?;
+        Ok(TimedMetadata::new(
+            track_id,
+            timestamp,
+            MetadataKind::Cursor,
+            data,
+        ))
+    }
+
+    /// Read a sample sent by [`Self::to_metadata`]
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the metadata is not a cursor sample
+    pub fn from_metadata(metadata: &TimedMetadata) -> Result<Self, MetadataError> {
+        if metadata.kind != MetadataKind::Cursor {
+            return Err(MetadataError::Malformed(format!(
+                "{:?} metadata",
+                metadata.kind
+            )));
+        }
+        postcard::from_bytes(&metadata.data).map_err(|e| MetadataError::Malformed(e.to_string()))
+    }
+
+    fn distance(&self, other: &Self) -> f32 {
+        (self.x - other.x).hypot(self.y - other.y)
+    }
+}
+
+/// Converts a duration to RTP ticks of the video clock
+fn ticks(duration: Duration) -> i64 {
+    i64::try_from(duration.as_micros() * u128::from(VIDEO_CLOCK_RATE) / 1_000_000)
+        .unwrap_or(i64::MAX)
+}
+
+/// Decides which cursor positions the presenter sends
+///
+/// Called with every pointer position; returns the samples worth sending,
+/// so a cursor at rest costs one sample per [`CursorConfig::max_interval`]
+/// and a moving one at most one per [`CursorConfig::min_interval`]. Clicks
+/// and the cursor leaving or entering the surface are always sent.
+#[derive(Debug)]
+pub struct CursorSampler {
+    config: CursorConfig,
+    last: Option<(u32, CursorSample)>,
+}
+
+impl CursorSampler {
+    /// Create a sampler
+    #[must_use]
+    pub fn new(config: CursorConfig) -> Self {
+        Self { config, last: None }
+    }
+
+    /// Offer the pointer at the video timestamp `timestamp`
+    ///
+    /// Returns the sample to send, if any.
+    pub fn sample(&mut self, timestamp: u32, mut sample: CursorSample) -> Option<CursorSample> {
+        if !self.config.highlight_clicks {
+            sample.click = None;
+        }
+        let send = match &self.last {
+            None => true,
+            Some((at, last)) => {
+                let elapsed = i64::from(timestamp_diff(timestamp, *at));
+                sample.click.is_some()
+                    || sample.visible != last.visible
+                    || elapsed >= ticks(self.config.max_interval)
+                    || (sample.visible
+                        && elapsed >= ticks(self.config.min_interval)
+                        && sample.distance(last) >= self.config.min_movement)
+            }
+        };
+        if send {
+            self.last = Some((timestamp, sample));
+            Some(sample)
+        } else {
+            None
+        }
+    }
+}
+
+/// Highlight of a recent click
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClickHighlight {
+    /// Button clicked
+    pub button: ClickButton,
+    /// How far the highlight has faded, from 0.0 at the click to 1.0
+    pub progress: f32,
+}
+
+/// Where to draw the cursor overlay
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CursorFrame {
+    /// Distance from the left edge, `0.0..=1.0`
+    pub x: f32,
+    /// Distance from the top edge, `0.0..=1.0`
+    pub y: f32,
+    /// Highlight of the latest click, while it lasts
+    pub click: Option<ClickHighlight>,
+}
+
+/// Smooth cursor overlay for a viewer
+#[derive(Debug)]
+pub struct CursorOverlay {
+    config: CursorConfig,
+    /// Oldest timestamp first
+    samples: VecDeque<(u32, CursorSample)>,
+    last_click: Option<(u32, ClickButton)>,
+}
+
+impl CursorOverlay {
+    /// Create an overlay
+    #[must_use]
+    pub fn new(config: CursorConfig) -> Self {
+        Self {
+            config,
+            samples: VecDeque::new(),
+            last_click: None,
+        }
+    }
+
+    /// Add received metadata
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the metadata is not a cursor sample
+    pub fn push(&mut self, metadata: &TimedMetadata) -> Result<(), MetadataError> {
+        let sample = CursorSample::from_metadata(metadata)?;
+        self.push_sample(metadata.timestamp, sample);
+        Ok(())
+    }
+
+    /// Add a sample taken at `timestamp`
+    pub fn push_sample(&mut self, timestamp: u32, sample: CursorSample) {
+        if let Some(button) = sample.click {
+            let newer = self
+                .last_click
+                .is_none_or(|(at, _)| timestamp_diff(timestamp, at) >= 0);
+            if newer {
+                self.last_click = Some((timestamp, button));
+            }
+        }
+        let at = self
+            .samples
+            .iter()
+            .rposition(|(queued, _)| timestamp_diff(timestamp, *queued) >= 0)
+            .map_or(0, |index| index + 1);
+        self.samples.insert(at, (timestamp, sample));
+        if self.samples.len() > OVERLAY_CAPACITY {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Where the cursor is at the playout timestamp `timestamp`
+    ///
+    /// Interpolates between the samples around it, and holds the latest
+    /// sample once past it. Returns `None` if the cursor is hidden or no
+    /// sample has arrived yet.
+    pub fn at(&mut self, timestamp: u32) -> Option<CursorFrame> {
+        // Keep one sample at or before the timestamp to interpolate from
+        while self.samples.len() > 1 && timestamp_diff(timestamp, self.samples[1].0) >= 0 {
+            self.samples.pop_front();
+        }
+        let (from_at, from) = *self.samples.front()?;
+        let (x, y) = match self.samples.get(1) {
+            Some((to_at, to))
+                if from.visible && to.visible && timestamp_diff(timestamp, from_at) > 0 =>
+            {
+                let span = timestamp_diff(*to_at, from_at) as f32;
+                let t = (timestamp_diff(timestamp, from_at) as f32 / span).clamp(0.0, 1.0);
+                (from.x + (to.x - from.x) * t, from.y + (to.y - from.y) * t)
+            }
+            _ => (from.x, from.y),
+        };
+        if !from.visible {
+            return None;
+        }
+        Some(CursorFrame {
+            x,
+            y,
+            click: self.click_at(timestamp),
+        })
+    }
+
+    fn click_at(&self, timestamp: u32) -> Option<ClickHighlight> {
+        if !self.config.highlight_clicks {
+            return None;
+        }
+        let (at, button) = self.last_click?;
+        let elapsed = i64::from(timestamp_diff(timestamp, at));
+        let duration = ticks(self.config.click_highlight).max(1);
+        (0..duration).contains(&elapsed).then(|| ClickHighlight {
+            button,
+            progress: elapsed as f32 / duration as f32,
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    /// 10 ms in 90 kHz ticks
+    const MS10: u32 = 900;
+
+    #[test]
+    fn test_sampler_limits_rate() {
+        let mut sampler = CursorSampler::new(CursorConfig::default());
+        let base = u32::MAX - MS10; // wraps between samples
+
+        assert!(sampler.sample(base, CursorSample::at(0.1, 0.1)).is_some());
+        // Moving, but too soon
+        assert!(sampler
+            .sample(base.wrapping_add(MS10), CursorSample::at(0.2, 0.1))
+            .is_none());
+        // Clicks always go out
+        let click = CursorSample::at(0.2, 0.1).with_click(ClickButton::Left);
+        assert_eq!(
+            sampler.sample(base.wrapping_add(2 * MS10), click),
+            Some(click)
+        );
+        // At rest only once per second
+        assert!(sampler
+            .sample(base.wrapping_add(50 * MS10), CursorSample::at(0.2, 0.1))
+            .is_none());
+        assert!(sampler
+            .sample(base.wrapping_add(102 * MS10), CursorSample::at(0.2, 0.1))
+            .is_some());
+
+        let mut quiet = CursorSampler::new(CursorConfig {
+            highlight_clicks: false,
+            ..CursorConfig::default()
+        });
+        assert_eq!(quiet.sample(0, click).and_then(|sample| sample.click), None);
+    }
+
+    #[test]
+    fn test_overlay_interpolates_and_highlights_clicks() {
+        let mut overlay = CursorOverlay::new(CursorConfig::default());
+        assert_eq!(overlay.at(0), None);
+
+        let metadata = CursorSample::at(0.0, 0.5)
+            .with_click(ClickButton::Right)
+            .to_metadata(1, 0)
+            .unwrap();
+        overlay.push(&metadata).unwrap();
+        overlay.push_sample(20 * MS10, CursorSample::at(1.0, 0.5));
+
+        // Halfway between the samples, with the click fading
+        let frame = overlay.at(10 * MS10).unwrap();
+        assert!((frame.x - 0.5).abs() < 1e-6);
+        assert!((frame.y - 0.5).abs() < 1e-6);
+        let click = frame.click.unwrap();
+        assert_eq!(click.button, ClickButton::Right);
+        assert!((click.progress - 0.25).abs() < 1e-6);
+
+        // Past the last sample the cursor holds, and the highlight is over
+        let frame = overlay.at(60 * MS10).unwrap();
+        assert!((frame.x - 1.0).abs() < 1e-6);
+        assert_eq!(frame.click, None);
+
+        overlay.push_sample(70 * MS10, CursorSample::hidden());
+        assert_eq!(overlay.at(70 * MS10), None);
+
+        let caption = TimedMetadata::caption(1, 0, "Hello");
+        assert!(overlay.push(&caption).is_err());
+    }
+}
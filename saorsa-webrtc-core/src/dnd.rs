@@ -0,0 +1,229 @@
+//! Do-not-disturb and quiet hours
+//!
+//! While do-not-disturb is on, either toggled by hand or because the time
+//! falls in one of the configured quiet hours, incoming calls never ring.
+//! Depending on [`DndAction`] they are declined or left unanswered, and
+//! either way recorded as a [`MissedCall`].
+
+use crate::types::CallId;
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Missed calls kept in the history; the oldest are dropped beyond this
+pub const MAX_MISSED_CALLS: usize = 100;
+
+/// Quiet hours could not be parsed
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Invalid quiet hours '{0}': expected HH:MM-HH:MM")]
+pub struct QuietHoursParseError(String);
+
+/// What happens to calls arriving during do-not-disturb
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum DndAction {
+    /// Decline the call so the caller knows at once
+    Reject,
+    /// Leave the call unanswered without ringing
+    Divert,
+}
+
+impl Default for DndAction {
+    fn default() -> Self {
+        Self::Divert
+    }
+}
+
+/// A daily time range, in the configured local time
+///
+/// A range whose end is before its start runs past midnight, e.g.
+/// `22:00-07:00`. A range ending where it starts covers the whole day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietHours {
+    /// First minute of the range
+    pub start: NaiveTime,
+    /// First minute after the range
+    pub end: NaiveTime,
+}
+
+impl QuietHours {
+    /// Create a range
+    #[must_use]
+    pub fn new(start: NaiveTime, end: NaiveTime) -> Self {
+        Self { start, end }
+    }
+
+    /// Check if a local time of day falls in the range
+    #[must_use]
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        match self.start.cmp(&self.end) {
+            std::cmp::Ordering::Less => self.start <= time && time < self.end,
+            std::cmp::Ordering::Greater => time >= self.start || time < self.end,
+            std::cmp::Ordering::Equal => true,
+        }
+    }
+}
+
+impl FromStr for QuietHours {
+    type Err = QuietHoursParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || QuietHoursParseError(s.to_string());
+        let (start, end) = s.trim().split_once('-').ok_or_else(err)?;
+        let parse = |time: &str| NaiveTime::parse_from_str(time.trim(), "%H:%M").map_err(|_| err());
+        Ok(Self::new(parse(start)?, parse(end)?))
+    }
+}
+
+impl fmt::Display for QuietHours {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-{}",
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M")
+        )
+    }
+}
+
+/// Do-not-disturb configuration
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DndConfig {
+    /// Manual toggle; on regardless of quiet hours
+    #[serde(default)]
+    pub enabled: bool,
+    /// Daily ranges during which do-not-disturb is on
+    #[serde(default)]
+    pub quiet_hours: Vec<QuietHours>,
+    /// Offset of the local time quiet hours are given in, east of UTC
+    #[serde(default)]
+    pub utc_offset_minutes: i32,
+    /// What happens to calls arriving during do-not-disturb
+    #[serde(default)]
+    pub action: DndAction,
+}
+
+/// Why do-not-disturb is on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum DndReason {
+    /// Turned on by hand
+    Manual,
+    /// Within quiet hours
+    QuietHours,
+}
+
+/// A call that did not ring because of do-not-disturb
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct MissedCall<I> {
+    /// Call identifier
+    pub call_id: CallId,
+    /// Who called
+    pub caller: I,
+    /// When the call arrived
+    pub at: DateTime<Utc>,
+    /// Why it did not ring
+    pub reason: DndReason,
+    /// What was done with it
+    pub action: DndAction,
+}
+
+/// Do-not-disturb switch and schedule
+#[derive(Debug, Default)]
+pub struct DoNotDisturb {
+    config: parking_lot::RwLock<DndConfig>,
+}
+
+impl DoNotDisturb {
+    /// Create a switch with a configuration
+    #[must_use]
+    pub fn new(config: DndConfig) -> Self {
+        Self {
+            config: parking_lot::RwLock::new(config),
+        }
+    }
+
+    /// Current configuration
+    #[must_use]
+    pub fn config(&self) -> DndConfig {
+        self.config.read().clone()
+    }
+
+    /// Replace the configuration
+    pub fn set_config(&self, config: DndConfig) {
+        *self.config.write() = config;
+    }
+
+    /// Turn the manual toggle on or off
+    pub fn set_enabled(&self, enabled: bool) {
+        self.config.write().enabled = enabled;
+    }
+
+    /// Replace the quiet hours
+    pub fn set_quiet_hours(&self, quiet_hours: Vec<QuietHours>) {
+        self.config.write().quiet_hours = quiet_hours;
+    }
+
+    /// Check whether do-not-disturb is on now, by the system clock
+    #[must_use]
+    pub fn active_now(&self) -> Option<DndReason> {
+        self.active_at(Utc::now())
+    }
+
+    /// Check whether do-not-disturb is on at a time, and why
+    #[must_use]
+    pub fn active_at(&self, now: DateTime<Utc>) -> Option<DndReason> {
+        let config = self.config.read();
+        if config.enabled {
+            return Some(DndReason::Manual);
+        }
+        let local = (now + Duration::minutes(i64::from(config.utc_offset_minutes))).time();
+        config
+            .quiet_hours
+            .iter()
+            .any(|hours| hours.contains(local))
+            .then_some(DndReason::QuietHours)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_quiet_hours_past_midnight() {
+        let night: QuietHours = "22:00-07:00".parse().unwrap();
+        assert_eq!(night.to_string(), "22:00-07:00");
+        let at = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        assert!(night.contains(at(23, 30)));
+        assert!(night.contains(at(6, 59)));
+        assert!(!night.contains(at(7, 0)));
+        assert!(!night.contains(at(12, 0)));
+        assert!("22:00".parse::<QuietHours>().is_err());
+        assert!("25:00-07:00".parse::<QuietHours>().is_err());
+    }
+
+    #[test]
+    fn test_active_in_local_quiet_hours() {
+        let dnd = DoNotDisturb::new(DndConfig {
+            quiet_hours: vec!["22:00-07:00".parse().unwrap()],
+            utc_offset_minutes: 120,
+            ..Default::default()
+        });
+        // 21:00 UTC is 23:00 local
+        let evening = Utc.with_ymd_and_hms(2026, 3, 1, 21, 0, 0).unwrap();
+        let noon = Utc.with_ymd_and_hms(2026, 3, 1, 10, 0, 0).unwrap();
+        assert_eq!(dnd.active_at(evening), Some(DndReason::QuietHours));
+        assert_eq!(dnd.active_at(noon), None);
+
+        dnd.set_enabled(true);
+        assert_eq!(dnd.active_at(noon), Some(DndReason::Manual));
+    }
+}
@@ -0,0 +1,218 @@
+//! IPv6 and dual-stack connection helpers
+//!
+//! Peers may be reachable over IPv4, IPv6 or both. When a peer advertises
+//! several addresses, connection attempts are raced Happy-Eyeballs style
+//! (RFC 8305): addresses are ordered to alternate between families with
+//! IPv6 first, each attempt starts a short delay after the previous one (or
+//! as soon as it fails), and the first connection to succeed wins. A broken
+//! IPv6 path therefore costs a fraction of a second instead of a full
+//! connection timeout.
+
+use crate::redact;
+use futures::stream::{FuturesUnordered, StreamExt};
+use std::future::Future;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+
+/// Delay before starting the next connection attempt (RFC 8305 recommends
+/// 250ms)
+pub const CONNECTION_ATTEMPT_DELAY: Duration = Duration::from_millis(250);
+
+/// Order addresses for racing
+///
+/// Duplicates are removed, then IPv6 and IPv4 addresses alternate starting
+/// with IPv6, keeping the original order within each family.
+#[must_use]
+pub fn interleave_families(addrs: &[SocketAddr]) -> Vec<SocketAddr> {
+    let mut v6 = Vec::new();
+    let mut v4 = Vec::new();
+    for addr in addrs {
+        if v6.contains(addr) || v4.contains(addr) {
+            continue;
+        }
+        match addr {
+            SocketAddr::V6(_) => v6.push(*addr),
+            SocketAddr::V4(_) => v4.push(*addr),
+        }
+    }
+
+    let mut ordered = Vec::with_capacity(v6.len() + v4.len());
+    let (mut v6, mut v4) = (v6.into_iter(), v4.into_iter());
+    loop {
+        match (v6.next(), v4.next()) {
+            (None, None) => break,
+            (first, second) => ordered.extend(first.into_iter().chain(second)),
+        }
+    }
+    ordered
+}
+
+/// Replace an unspecified address with the loopback address of the same
+/// family
+///
+/// A socket bound to `0.0.0.0` or `[::]` reports that address as its local
+/// address, which peers on the same host cannot connect to.
+#[must_use]
+pub fn connectable(mut addr: SocketAddr) -> SocketAddr {
+    if addr.ip().is_unspecified() {
+        addr.set_ip(match addr.ip() {
+            IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::LOCALHOST),
+            IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::LOCALHOST),
+        });
+    }
+    addr
+}
+
+/// Race connection attempts to several addresses of one peer
+///
+/// Attempts start in [`interleave_families`] order, `delay` apart or as soon
+/// as the previous attempt fails. The first success is returned with the
+/// address it used; attempts still in flight are dropped.
+///
+/// # Errors
+///
+/// Returns every attempt's error, in the order the attempts failed, if none
+/// succeeds. The list is empty if no addresses were given.
+pub async fn race<T, E, F, Fut>(
+    addrs: &[SocketAddr],
+    delay: Duration,
+    mut connect: F,
+) -> Result<(SocketAddr, T), Vec<(SocketAddr, E)>>
+where
+    F: FnMut(SocketAddr) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut pending = interleave_families(addrs).into_iter();
+    let mut attempts = FuturesUnordered::new();
+    let mut errors = Vec::new();
+
+    let attempt = |addr: SocketAddr, connect: &mut F| {
+        let fut = connect(addr);
+        async move { (addr, fut.await) }
+    };
+
+    match pending.next() {
+        Some(addr) => attempts.push(attempt(addr, &mut connect)),
+        None => return Err(errors),
+    }
+
+    loop {
+        let next_start = tokio::time::sleep(delay);
+        tokio::pin!(next_start);
+        let finished = tokio::select! {
+            finished = attempts.next() => finished,
+            () = &mut next_start, if pending.len() > 0 => {
+                if let Some(addr) = pending.next() {
+                    tracing::debug!(addr = %redact::addr(addr), "Starting staggered connection attempt");
+                    attempts.push(attempt(addr, &mut connect));
+                }
+                continue;
+            }
+        };
+
+        match finished {
+            Some((addr, Ok(value))) => return Ok((addr, value)),
+            Some((addr, Err(error))) => {
+                tracing::debug!(addr = %redact::addr(addr), "Connection attempt failed");
+                errors.push((addr, error));
+                // A failure frees the slot immediately
+                if let Some(addr) = pending.next() {
+                    attempts.push(attempt(addr, &mut connect));
+                }
+            }
+            None => return Err(errors),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn addrs(list: &[&str]) -> Vec<SocketAddr> {
+        list.iter().map(|a| a.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_interleave_prefers_ipv6() {
+        let ordered = interleave_families(&addrs(&[
+            "192.0.2.1:9000",
+            "192.0.2.2:9000",
+            "[2001:db8::1]:9000",
+            "192.0.2.1:9000",
+        ]));
+        assert_eq!(
+            ordered,
+            addrs(&["[2001:db8::1]:9000", "192.0.2.1:9000", "192.0.2.2:9000"])
+        );
+    }
+
+    #[test]
+    fn test_connectable_keeps_family() {
+        assert_eq!(
+            connectable("0.0.0.0:5000".parse().unwrap()),
+            "127.0.0.1:5000".parse().unwrap()
+        );
+        assert_eq!(
+            connectable("[::]:5000".parse().unwrap()),
+            "[::1]:5000".parse().unwrap()
+        );
+        let public: SocketAddr = "[2001:db8::1]:5000".parse().unwrap();
+        assert_eq!(connectable(public), public);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_race_falls_back_to_ipv4_after_delay() {
+        let started = tokio::time::Instant::now();
+        let result = race(
+            &addrs(&["192.0.2.1:9000", "[2001:db8::1]:9000"]),
+            CONNECTION_ATTEMPT_DELAY,
+            |addr| async move {
+                if addr.is_ipv6() {
+                    // Black-holed IPv6 path
+                    std::future::pending::<()>().await;
+                }
+                Ok::<_, ()>(addr.port())
+            },
+        )
+        .await;
+
+        assert_eq!(result, Ok(("192.0.2.1:9000".parse().unwrap(), 9000)));
+        assert_eq!(started.elapsed(), CONNECTION_ATTEMPT_DELAY);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_race_moves_on_immediately_after_failure() {
+        let started = tokio::time::Instant::now();
+        let result = race(
+            &addrs(&["[2001:db8::1]:9000", "192.0.2.1:9000"]),
+            CONNECTION_ATTEMPT_DELAY,
+            |addr| async move {
+                if addr.is_ipv6() {
+                    Err("unreachable")
+                } else {
+                    Ok(())
+                }
+            },
+        )
+        .await;
+
+        assert_eq!(result.unwrap().0, "192.0.2.1:9000".parse().unwrap());
+        assert_eq!(started.elapsed(), Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_race_collects_all_errors() {
+        let result = race(
+            &addrs(&["192.0.2.1:9000", "[2001:db8::1]:9000"]),
+            CONNECTION_ATTEMPT_DELAY,
+            |addr| async move { Err::<(), _>(addr.port()) },
+        )
+        .await;
+        assert_eq!(result.unwrap_err().len(), 2);
+
+        let empty = race(&[], CONNECTION_ATTEMPT_DELAY, |_| async { Ok::<_, ()>(()) }).await;
+        assert_eq!(empty, Err(Vec::new()));
+    }
+}
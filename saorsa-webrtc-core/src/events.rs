@@ -0,0 +1,212 @@
+//! Event broadcast channels
+//!
+//! The call manager, media stream manager and service publish events on
+//! bounded broadcast channels whose capacities are set in their configs. A
+//! subscriber that falls a whole channel behind loses the oldest events it
+//! has not read, which tokio reports to that subscriber as
+//! `RecvError::Lagged`. [`EventSender`] also counts these on the sending
+//! side, so lost events are observable in [`EventChannelStats`] and the
+//! metrics recorder even when the subscriber ignores the error.
+
+use crate::metrics::{noop_metrics, SharedMetrics};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+/// Default capacity of the call event channel
+pub const DEFAULT_CALL_EVENT_CAPACITY: usize = 100;
+
+/// Default capacity of the media event channel
+pub const DEFAULT_MEDIA_EVENT_CAPACITY: usize = 100;
+
+/// Default capacity of the service event channel
+pub const DEFAULT_SERVICE_EVENT_CAPACITY: usize = 1000;
+
+/// Counters of an event channel
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct EventChannelStats {
+    /// Events the channel buffers per subscriber
+    ///
+    /// The configured capacity rounded up to a power of two, as tokio does.
+    pub capacity: usize,
+    /// Events sent while anyone was subscribed
+    pub sent: u64,
+    /// Events sent with nobody subscribed, which go nowhere
+    pub unheard: u64,
+    /// Events that pushed an unread event out of a lagging subscriber's
+    /// buffer; each is one event that subscriber lost
+    pub overflowed: u64,
+}
+
+/// Counters of the call, media and service event channels
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct EventStats {
+    /// Call manager events
+    pub call: EventChannelStats,
+    /// Media stream manager events
+    pub media: EventChannelStats,
+    /// Service events
+    pub service: EventChannelStats,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    sent: AtomicU64,
+    unheard: AtomicU64,
+    overflowed: AtomicU64,
+}
+
+/// Sending half of an event channel that counts lost events
+///
+/// Clones share the channel and its counters.
+#[derive(Debug)]
+pub struct EventSender<T> {
+    inner: broadcast::Sender<T>,
+    capacity: usize,
+    counters: Arc<Counters>,
+    metrics: SharedMetrics,
+    overflow_counter: &'static str,
+}
+
+impl<T> Clone for EventSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            capacity: self.capacity,
+            counters: Arc::clone(&self.counters),
+            metrics: Arc::clone(&self.metrics),
+            overflow_counter: self.overflow_counter,
+        }
+    }
+}
+
+impl<T: Clone> EventSender<T> {
+    /// Create a channel buffering `capacity` events per subscriber
+    ///
+    /// A capacity of zero is raised to one.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        let (inner, _) = broadcast::channel(capacity);
+        Self {
+            inner,
+            capacity: capacity.next_power_of_two(),
+            counters: Arc::new(Counters::default()),
+            metrics: noop_metrics(),
+            overflow_counter: "",
+        }
+    }
+
+    /// Report overflowed events to a metrics recorder as counter `name`
+    #[must_use]
+    pub fn with_metrics(mut self, metrics: SharedMetrics, name: &'static str) -> Self {
+        self.metrics = metrics;
+        self.overflow_counter = name;
+        self
+    }
+
+    /// Send an event to every subscriber
+    ///
+    /// # Errors
+    ///
+    /// Returns the event back if nobody is subscribed
+    pub fn send(&self, event: T) -> Result<usize, broadcast::error::SendError<T>> {
+        // The slowest subscriber's unread events; a full buffer means this
+        // event replaces one it has not read
+        let full = self.inner.len() >= self.capacity;
+        let result = self.inner.send(event);
+        if result.is_ok() {
+            self.counters.sent.fetch_add(1, Ordering::Relaxed);
+            if full {
+                self.counters.overflowed.fetch_add(1, Ordering::Relaxed);
+                self.metrics.increment_counter(self.overflow_counter, 1);
+            }
+        } else {
+            self.counters.unheard.fetch_add(1, Ordering::Relaxed);
+        }
+        result
+    }
+
+    /// Subscribe to events sent from now on
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<T> {
+        self.inner.subscribe()
+    }
+
+    /// Number of current subscribers
+    #[must_use]
+    pub fn receiver_count(&self) -> usize {
+        self.inner.receiver_count()
+    }
+
+    /// Counters of the channel
+    #[must_use]
+    pub fn stats(&self) -> EventChannelStats {
+        EventChannelStats {
+            capacity: self.capacity,
+            sent: self.counters.sent.load(Ordering::Relaxed),
+            unheard: self.counters.unheard.load(Ordering::Relaxed),
+            overflowed: self.counters.overflowed.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::metrics::MetricsRecorder;
+    use parking_lot::Mutex;
+
+    #[derive(Debug, Default)]
+    struct Recorded(Mutex<Vec<(&'static str, u64)>>);
+
+    impl MetricsRecorder for Recorded {
+        fn increment_counter(&self, name: &'static str, value: u64) {
+            self.0.lock().push((name, value));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_overflow_matches_subscriber_lag() {
+        let metrics = Arc::new(Recorded::default());
+        let sender = EventSender::new(3).with_metrics(metrics.clone(), "test_overflowed");
+        assert_eq!(sender.send(0).unwrap_err().0, 0);
+
+        let mut rx = sender.subscribe();
+        for event in 1..=7 {
+            sender.send(event).unwrap();
+        }
+
+        let stats = sender.stats();
+        assert_eq!(stats.capacity, 4);
+        assert_eq!((stats.sent, stats.unheard, stats.overflowed), (7, 1, 3));
+        assert_eq!(metrics.0.lock().len(), 3);
+        assert!(matches!(
+            rx.recv().await,
+            Err(broadcast::error::RecvError::Lagged(3))
+        ));
+        assert_eq!(rx.recv().await.unwrap(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_keeping_up_loses_nothing() {
+        let sender = EventSender::new(2);
+        let mut rx = sender.subscribe();
+        for event in 0..10 {
+            sender.send(event).unwrap();
+            assert_eq!(rx.recv().await.unwrap(), event);
+        }
+        assert_eq!(sender.stats().overflowed, 0);
+        assert_eq!(sender.clone().stats().sent, 10);
+    }
+
+    #[test]
+    fn test_zero_capacity_is_raised() {
+        let sender = EventSender::<u8>::new(0);
+        assert_eq!(sender.stats().capacity, 1);
+    }
+}
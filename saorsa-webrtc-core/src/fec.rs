@@ -0,0 +1,152 @@
+//! Adaptive Opus in-band FEC
+//!
+//! Opus can hide a lost packet by carrying a low-bitrate copy of each frame
+//! in the next packet. The copy costs bitrate, so it should be as strong as
+//! the loss requires and no stronger. [`FecController`] follows the loss
+//! rate peers report for a call and picks the expected-loss setting for the
+//! encoder (see `OpusEncoder::set_packet_loss_percent`): it rises as soon as
+//! loss does, and falls back one step at a time only after the link has
+//! stayed cleaner for a while, so a brief lull does not strip protection
+//! just before the next burst.
+
+use serde::{Deserialize, Serialize};
+
+/// Adaptive FEC configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FecConfig {
+    /// Adjust FEC to the measured loss
+    pub enabled: bool,
+    /// Loss in percent below which FEC is not worth its bitrate
+    pub min_loss_percent: f32,
+    /// Granularity of the expected-loss setting, in percent
+    pub step_percent: u8,
+    /// Highest expected-loss setting, in percent
+    pub max_expected_loss_percent: u8,
+    /// Consecutive cleaner reports before stepping down
+    pub lower_after_reports: u32,
+}
+
+impl Default for FecConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_loss_percent: 1.0,
+            step_percent: 5,
+            max_expected_loss_percent: 25,
+            lower_after_reports: 5,
+        }
+    }
+}
+
+/// Expected-loss setting of one call's audio encoder
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FecController {
+    level: u8,
+    clean_reports: u32,
+}
+
+impl FecController {
+    /// Start with FEC off
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Current expected-loss setting, in percent; 0 when FEC is off
+    #[must_use]
+    pub fn level(&self) -> u8 {
+        self.level
+    }
+
+    /// Feed a measured loss rate, in percent
+    ///
+    /// Returns the new setting when it changed.
+    pub fn update(&mut self, config: &FecConfig, loss_percent: f32) -> Option<u8> {
+        if !config.enabled {
+            self.clean_reports = 0;
+            return (self.level > 0).then(|| {
+                self.level = 0;
+                0
+            });
+        }
+        let target = Self::target(config, loss_percent);
+        if target > self.level {
+            self.clean_reports = 0;
+            self.level = target;
+            return Some(target);
+        }
+        if target == self.level {
+            self.clean_reports = 0;
+            return None;
+        }
+
+        self.clean_reports += 1;
+        if self.clean_reports < config.lower_after_reports.max(1) {
+            return None;
+        }
+        self.clean_reports = 0;
+        self.level = self
+            .level
+            .saturating_sub(config.step_percent.max(1))
+            .max(target);
+        Some(self.level)
+    }
+
+    /// Setting that covers `loss_percent`: rounded up to a whole step
+    fn target(config: &FecConfig, loss_percent: f32) -> u8 {
+        if loss_percent.is_nan() || loss_percent < config.min_loss_percent {
+            return 0;
+        }
+        let step = f32::from(config.step_percent.max(1));
+        let level = ((loss_percent / step).ceil() * step).min(100.0) as u8;
+        level.min(config.max_expected_loss_percent)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rises_with_loss_and_falls_slowly() {
+        let config = FecConfig::default();
+        let mut fec = FecController::new();
+        assert_eq!(fec.update(&config, 0.5), None);
+        assert_eq!(fec.update(&config, 3.0), Some(5));
+        assert_eq!(fec.update(&config, 12.0), Some(15));
+        // Capped
+        assert_eq!(fec.update(&config, 60.0), Some(25));
+
+        // A brief lull keeps protection
+        for _ in 0..4 {
+            assert_eq!(fec.update(&config, 0.0), None);
+        }
+        assert_eq!(fec.update(&config, 24.0), None);
+        for _ in 0..4 {
+            assert_eq!(fec.update(&config, 0.0), None);
+        }
+        assert_eq!(fec.update(&config, 0.0), Some(20));
+
+        // Steps down one at a time, never below what the loss needs
+        let mut levels = Vec::new();
+        for _ in 0..40 {
+            levels.extend(fec.update(&config, 7.0));
+        }
+        assert_eq!(levels, [15, 10]);
+        assert_eq!(fec.level(), 10);
+    }
+
+    #[test]
+    fn test_disabling_turns_fec_off_at_once() {
+        let mut config = FecConfig::default();
+        let mut fec = FecController::new();
+        assert_eq!(fec.update(&config, 30.0), Some(25));
+
+        config.enabled = false;
+        assert_eq!(fec.update(&config, 30.0), Some(0));
+        assert_eq!(fec.update(&config, 30.0), None);
+        assert_eq!(fec.level(), 0);
+    }
+}
@@ -0,0 +1,499 @@
+//! Battery- and CPU-aware performance governor
+//!
+//! On laptops and phones a video call competes with everything else for CPU
+//! and battery. The optional [`PerformanceGovernor`] samples process CPU use
+//! and, through an embedder-supplied [`PowerSource`], the battery state, and
+//! steps the [`PerformanceLevel`] down when either is under pressure: lower
+//! capture resolution and frame rate, and a preference for hardware codecs.
+//! It steps back up once CPU use has stayed low for a while and the power
+//! state allows it.
+//!
+//! Every change is published as a [`GovernorEvent`]; the capture pipeline
+//! applies the new [`PerformanceSettings`].
+//!
+//! CPU pressure must be sustained for [`GovernorConfig::sustain_samples`]
+//! samples before the level changes, so brief spikes do not make the video
+//! flap. Power changes apply at the next sample.
+
+use crate::types::VideoResolution;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+/// Default interval between samples (2 seconds)
+pub const DEFAULT_SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Battery state reported by the platform
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PowerState {
+    /// Running on battery rather than mains power
+    pub on_battery: bool,
+    /// Remaining charge, if known
+    pub battery_percent: Option<u8>,
+    /// The user or OS asked apps to save power
+    pub low_power_mode: bool,
+}
+
+/// Platform callback reporting the battery state
+///
+/// The crate has no portable way to read the battery, so embedders
+/// implement this over their platform API.
+pub trait PowerSource: fmt::Debug + Send + Sync {
+    /// Current battery state
+    fn power_state(&self) -> PowerState;
+}
+
+/// Power source shared with the governor
+pub type SharedPowerSource = Arc<dyn PowerSource>;
+
+/// Source of CPU usage samples
+pub trait CpuSampler: fmt::Debug + Send + Sync {
+    /// CPU used since the previous call, as a percentage of all cores
+    ///
+    /// `None` if usage cannot be measured.
+    fn cpu_percent(&self) -> Option<f32>;
+}
+
+/// CPU sampler shared with the governor
+pub type SharedCpuSampler = Arc<dyn CpuSampler>;
+
+/// CPU usage of this process, read from `/proc/self/stat`
+///
+/// Measures nothing on platforms without procfs.
+#[derive(Debug, Default)]
+pub struct ProcessCpu {
+    last: Mutex<Option<(Instant, Duration)>>,
+}
+
+impl ProcessCpu {
+    /// Clock ticks per second of `/proc` CPU times on Linux
+    const TICKS_PER_SECOND: u64 = 100;
+
+    fn cpu_time() -> Option<Duration> {
+        let stat = std::fs::read_to_string("/proc/self/stat").ok()?;
+        // Fields after the parenthesised command name, which may hold spaces
+        let fields: Vec<&str> = stat.rsplit_once(')')?.1.split_whitespace().collect();
+        let utime: u64 = fields.get(11)?.parse().ok()?;
+        let stime: u64 = fields.get(12)?.parse().ok()?;
+        Some(Duration::from_millis(
+            (utime + stime) * 1000 / Self::TICKS_PER_SECOND,
+        ))
+    }
+}
+
+impl CpuSampler for ProcessCpu {
+    fn cpu_percent(&self) -> Option<f32> {
+        let now = (Instant::now(), Self::cpu_time()?);
+        let (at, used) = self.last.lock().replace(now)?;
+        let wall = now.0.duration_since(at).as_secs_f32();
+        let cores = std::thread::available_parallelism().map_or(1, usize::from) as f32;
+        (wall > 0.0).then(|| (now.1.saturating_sub(used).as_secs_f32() / wall / cores) * 100.0)
+    }
+}
+
+/// How much media work the device should take on, most degraded first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PerformanceLevel {
+    /// Lowest resolution and frame rate that keep a call usable
+    Minimal,
+    /// Reduced resolution and frame rate
+    Reduced,
+    /// No restriction
+    Full,
+}
+
+impl PerformanceLevel {
+    /// Capture and codec settings for the level
+    #[must_use]
+    pub fn settings(self) -> PerformanceSettings {
+        match self {
+            Self::Full => PerformanceSettings {
+                max_resolution: VideoResolution::HD1080,
+                max_fps: 30,
+                prefer_hardware_codecs: false,
+            },
+            Self::Reduced => PerformanceSettings {
+                max_resolution: VideoResolution::SD480,
+                max_fps: 24,
+                prefer_hardware_codecs: true,
+            },
+            Self::Minimal => PerformanceSettings {
+                max_resolution: VideoResolution::QVGA240,
+                max_fps: 15,
+                prefer_hardware_codecs: true,
+            },
+        }
+    }
+
+    fn lower(self) -> Self {
+        match self {
+            Self::Full => Self::Reduced,
+            Self::Reduced | Self::Minimal => Self::Minimal,
+        }
+    }
+
+    fn higher(self) -> Self {
+        match self {
+            Self::Minimal => Self::Reduced,
+            Self::Reduced | Self::Full => Self::Full,
+        }
+    }
+}
+
+/// Capture and codec limits for a [`PerformanceLevel`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PerformanceSettings {
+    /// Largest capture resolution
+    pub max_resolution: VideoResolution,
+    /// Highest capture frame rate
+    pub max_fps: u32,
+    /// Prefer hardware encoders and decoders where registered
+    pub prefer_hardware_codecs: bool,
+}
+
+impl PerformanceSettings {
+    /// Fit a capture format within the limits, keeping its aspect ratio
+    ///
+    /// # Returns
+    ///
+    /// `(width, height, fps)` no larger than the limits.
+    #[must_use]
+    pub fn clamp(&self, width: u32, height: u32, fps: u32) -> (u32, u32, u32) {
+        let (max_w, max_h) = (self.max_resolution.width(), self.max_resolution.height());
+        let fps = fps.min(self.max_fps);
+        if width <= max_w && height <= max_h {
+            return (width, height, fps);
+        }
+        // Scale by the tighter of the two bounds
+        let (num, den) =
+            if u64::from(width) * u64::from(max_h) > u64::from(height) * u64::from(max_w) {
+                (max_w, width)
+            } else {
+                (max_h, height)
+            };
+        let scale = |v: u32| ((u64::from(v) * u64::from(num)) / u64::from(den)).max(1) as u32;
+        (scale(width), scale(height), fps)
+    }
+}
+
+/// Why the governor changed level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GovernorReason {
+    /// CPU use stayed above the high threshold
+    HighCpu,
+    /// Battery charge fell to the low threshold
+    LowBattery,
+    /// The platform is in low power mode
+    LowPowerMode,
+}
+
+/// Level change announced by the governor
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum GovernorEvent {
+    /// Media work was reduced
+    Downgraded {
+        /// Previous level
+        from: PerformanceLevel,
+        /// New level
+        to: PerformanceLevel,
+        /// What triggered it
+        reason: GovernorReason,
+    },
+    /// Media work was restored
+    Upgraded {
+        /// Previous level
+        from: PerformanceLevel,
+        /// New level
+        to: PerformanceLevel,
+    },
+}
+
+/// Governor configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct GovernorConfig {
+    /// Run the governor; off by default
+    pub enabled: bool,
+    /// Interval between samples
+    pub interval: Duration,
+    /// CPU percentage above which to step down
+    pub cpu_high_percent: f32,
+    /// CPU percentage below which to step back up
+    pub cpu_low_percent: f32,
+    /// Consecutive samples past a CPU threshold before acting
+    pub sustain_samples: u32,
+    /// Battery percentage at or below which to drop to minimal
+    pub low_battery_percent: u8,
+}
+
+impl Default for GovernorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval: DEFAULT_SAMPLE_INTERVAL,
+            cpu_high_percent: 80.0,
+            cpu_low_percent: 50.0,
+            sustain_samples: 3,
+            low_battery_percent: 20,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct GovernorState {
+    /// Level CPU use allows
+    cpu_level: PerformanceLevel,
+    /// Level in effect, after power limits
+    level: PerformanceLevel,
+    high_streak: u32,
+    low_streak: u32,
+}
+
+/// Adjusts media work to CPU and battery pressure
+#[derive(Debug)]
+pub struct PerformanceGovernor {
+    config: GovernorConfig,
+    power: Option<SharedPowerSource>,
+    cpu: SharedCpuSampler,
+    state: Mutex<GovernorState>,
+    events: broadcast::Sender<GovernorEvent>,
+}
+
+impl PerformanceGovernor {
+    /// Create a governor sampling this process's CPU use and no battery
+    #[must_use]
+    pub fn new(config: GovernorConfig) -> Self {
+        let (events, _) = broadcast::channel(16);
+        Self {
+            config,
+            power: None,
+            cpu: Arc::new(ProcessCpu::default()),
+            state: Mutex::new(GovernorState {
+                cpu_level: PerformanceLevel::Full,
+                level: PerformanceLevel::Full,
+                high_streak: 0,
+                low_streak: 0,
+            }),
+            events,
+        }
+    }
+
+    /// Read the battery state from a platform callback
+    #[must_use]
+    pub fn with_power_source(mut self, power: SharedPowerSource) -> Self {
+        self.power = Some(power);
+        self
+    }
+
+    /// Replace the CPU usage source
+    #[must_use]
+    pub fn with_cpu_sampler(mut self, cpu: SharedCpuSampler) -> Self {
+        self.cpu = cpu;
+        self
+    }
+
+    /// Governor configuration
+    #[must_use]
+    pub fn config(&self) -> &GovernorConfig {
+        &self.config
+    }
+
+    /// Level in effect
+    #[must_use]
+    pub fn level(&self) -> PerformanceLevel {
+        self.state.lock().level
+    }
+
+    /// Settings for the level in effect
+    #[must_use]
+    pub fn settings(&self) -> PerformanceSettings {
+        self.level().settings()
+    }
+
+    /// Subscribe to level changes
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<GovernorEvent> {
+        self.events.subscribe()
+    }
+
+    /// Take one sample from the CPU and power sources and act on it
+    pub fn sample(&self) -> Option<GovernorEvent> {
+        let cpu = self.cpu.cpu_percent();
+        let power = self
+            .power
+            .as_ref()
+            .map(|p| p.power_state())
+            .unwrap_or_default();
+        self.evaluate(cpu, power)
+    }
+
+    /// Act on a CPU and power sample
+    ///
+    /// Returns the change made, if any, which is also published to
+    /// subscribers.
+    pub fn evaluate(&self, cpu_percent: Option<f32>, power: PowerState) -> Option<GovernorEvent> {
+        let mut state = self.state.lock();
+
+        match cpu_percent {
+            Some(cpu) if cpu > self.config.cpu_high_percent => {
+                state.high_streak += 1;
+                state.low_streak = 0;
+            }
+            Some(cpu) if cpu < self.config.cpu_low_percent => {
+                state.low_streak += 1;
+                state.high_streak = 0;
+            }
+            _ => {
+                state.high_streak = 0;
+                state.low_streak = 0;
+            }
+        }
+        let sustain = self.config.sustain_samples.max(1);
+        if state.high_streak >= sustain {
+            state.cpu_level = state.cpu_level.lower();
+            state.high_streak = 0;
+        } else if state.low_streak >= sustain {
+            state.cpu_level = state.cpu_level.higher();
+            state.low_streak = 0;
+        }
+
+        let (cap, power_reason) = self.power_cap(power);
+        let from = state.level;
+        let to = state.cpu_level.min(cap);
+        // Power is the cause when it holds the level below what CPU allows
+        let power_bound = cap < state.cpu_level;
+        state.level = to;
+        drop(state);
+
+        let event = if to < from {
+            let reason = match power_reason {
+                Some(reason) if power_bound => reason,
+                _ => GovernorReason::HighCpu,
+            };
+            tracing::warn!(?from, ?to, ?reason, "Reducing media performance");
+            GovernorEvent::Downgraded { from, to, reason }
+        } else if to > from {
+            tracing::info!(?from, ?to, "Restoring media performance");
+            GovernorEvent::Upgraded { from, to }
+        } else {
+            return None;
+        };
+        let _ = self.events.send(event.clone());
+        Some(event)
+    }
+
+    /// Highest level the power state allows, and why it is limited
+    fn power_cap(&self, power: PowerState) -> (PerformanceLevel, Option<GovernorReason>) {
+        let low_battery = power.on_battery
+            && power
+                .battery_percent
+                .is_some_and(|percent| percent <= self.config.low_battery_percent);
+        if low_battery {
+            (PerformanceLevel::Minimal, Some(GovernorReason::LowBattery))
+        } else if power.low_power_mode {
+            (
+                PerformanceLevel::Reduced,
+                Some(GovernorReason::LowPowerMode),
+            )
+        } else {
+            (PerformanceLevel::Full, None)
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn governor() -> PerformanceGovernor {
+        PerformanceGovernor::new(GovernorConfig {
+            enabled: true,
+            sustain_samples: 2,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_sustained_cpu_steps_down_and_recovers() {
+        let governor = governor();
+        let mut events = governor.subscribe();
+        let mains = PowerState::default();
+
+        // A single spike is ignored
+        assert_eq!(governor.evaluate(Some(95.0), mains), None);
+        assert_eq!(governor.evaluate(Some(30.0), mains), None);
+
+        assert_eq!(governor.evaluate(Some(95.0), mains), None);
+        let event = governor.evaluate(Some(95.0), mains).unwrap();
+        assert_eq!(
+            event,
+            GovernorEvent::Downgraded {
+                from: PerformanceLevel::Full,
+                to: PerformanceLevel::Reduced,
+                reason: GovernorReason::HighCpu,
+            }
+        );
+        assert_eq!(events.try_recv().unwrap(), event);
+        assert!(governor.settings().prefer_hardware_codecs);
+
+        governor.evaluate(Some(20.0), mains);
+        assert!(matches!(
+            governor.evaluate(Some(20.0), mains),
+            Some(GovernorEvent::Upgraded {
+                to: PerformanceLevel::Full,
+                ..
+            })
+        ));
+        // Unknown CPU use changes nothing
+        assert_eq!(governor.evaluate(None, mains), None);
+    }
+
+    #[test]
+    fn test_battery_caps_level() {
+        let governor = governor();
+        let low = PowerState {
+            on_battery: true,
+            battery_percent: Some(15),
+            low_power_mode: false,
+        };
+        assert_eq!(
+            governor.evaluate(Some(10.0), low),
+            Some(GovernorEvent::Downgraded {
+                from: PerformanceLevel::Full,
+                to: PerformanceLevel::Minimal,
+                reason: GovernorReason::LowBattery,
+            })
+        );
+
+        // Plugged in but in low power mode
+        let saver = PowerState {
+            low_power_mode: true,
+            ..PowerState::default()
+        };
+        assert_eq!(
+            governor.evaluate(Some(10.0), saver),
+            Some(GovernorEvent::Upgraded {
+                from: PerformanceLevel::Minimal,
+                to: PerformanceLevel::Reduced,
+            })
+        );
+        assert_eq!(governor.level(), PerformanceLevel::Reduced);
+    }
+
+    #[test]
+    fn test_settings_clamp_capture() {
+        let settings = PerformanceLevel::Reduced.settings();
+        assert_eq!(settings.clamp(1280, 720, 30), (640, 360, 24));
+        assert_eq!(settings.clamp(320, 240, 15), (320, 240, 15));
+        assert_eq!(
+            PerformanceLevel::Minimal.settings().clamp(1080, 1920, 60),
+            (135, 240, 15)
+        );
+    }
+}
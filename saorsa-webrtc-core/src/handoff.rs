@@ -0,0 +1,230 @@
+//! Moving a live call between our own devices
+//!
+//! A handoff moves an active call from one of our devices (say a desktop) to
+//! another (a phone) without the remote peer hanging up:
+//!
+//! ```text
+//!   old device                 remote peer                 new device
+//!       │ ── HandoffOffer(key) ──► │                            │
+//!       │ ─────────────── token, out of band (QR, sync) ──────► │
+//!       │                          │ ◄── HandoffJoin(token) ─── │
+//!       │                          │  switches media over       │
+//!       │ ◄── HandoffComplete ──── │ ──── HandoffComplete ────► │
+//!   drops the call                                       call connected
+//! ```
+//!
+//! The old device creates a one-time key and a [`HandoffToken`] naming the
+//! new device, authenticated with that key. The key goes to the remote peer
+//! over the call's signaling channel; the token goes to the new device by any
+//! channel the user already trusts. The remote peer accepts a join only with
+//! a token that verifies against the key, names the joining device and has
+//! not expired.
+
+use crate::types::CallId;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine as _;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use thiserror::Error;
+
+/// Call handoff errors
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum HandoffError {
+    /// A token or key could not be decoded
+    #[error("Malformed handoff data: {0}")]
+    Malformed(String),
+
+    /// The token was not issued with the key the peer announced
+    #[error("Handoff token signature is invalid")]
+    BadSignature,
+
+    /// The token is past its expiry
+    #[error("Handoff token expired at {0}")]
+    Expired(DateTime<Utc>),
+
+    /// The token was issued for a different device
+    #[error("Handoff token was issued for another device")]
+    WrongDevice,
+
+    /// The call has no handoff in progress
+    #[error("No handoff pending on call {0}")]
+    NotPending(CallId),
+}
+
+/// One-time key authenticating the tokens of a single handoff
+#[derive(Clone, PartialEq, Eq)]
+pub(crate) struct HandoffKey([u8; 32]);
+
+impl HandoffKey {
+    /// Create a random key
+    pub(crate) fn generate() -> Self {
+        Self(rand::random())
+    }
+
+    /// Encode the key for a signaling message
+    pub(crate) fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(self.0)
+    }
+
+    /// Decode a key received in a signaling message
+    pub(crate) fn decode(encoded: &str) -> Result<Self, HandoffError> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(encoded)
+            .map_err(|e| HandoffError::Malformed(e.to_string()))?;
+        let key = bytes
+            .try_into()
+            .map_err(|_| HandoffError::Malformed("handoff key must be 32 bytes".to_string()))?;
+        Ok(Self(key))
+    }
+}
+
+impl fmt::Debug for HandoffKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("HandoffKey(..)")
+    }
+}
+
+/// Permission for one device to take over a call
+///
+/// Issued by [`crate::call::CallManager::prepare_handoff`] on the device
+/// holding the call, and presented to the remote peer by the new device.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HandoffToken {
+    /// Call being handed off
+    pub call_id: CallId,
+    /// Identity of the device allowed to take over the call
+    pub to_device: String,
+    /// Identity of the remote peer the new device connects to
+    pub remote_peer: String,
+    /// Time after which the token is refused
+    pub expires_at: DateTime<Utc>,
+    /// Keyed BLAKE3 hash of the fields above, hex encoded
+    signature: String,
+}
+
+impl HandoffToken {
+    /// Issue a token signed with a handoff key
+    pub(crate) fn sign(
+        key: &HandoffKey,
+        call_id: CallId,
+        to_device: String,
+        remote_peer: String,
+        expires_at: DateTime<Utc>,
+    ) -> Self {
+        let mut token = Self {
+            call_id,
+            to_device,
+            remote_peer,
+            expires_at,
+            signature: String::new(),
+        };
+        token.signature = token.mac(key).to_hex().to_string();
+        token
+    }
+
+    /// Check the token against the key announced by the old device
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the signature does not match or the token expired
+    pub(crate) fn verify(&self, key: &HandoffKey, now: DateTime<Utc>) -> Result<(), HandoffError> {
+        let signature =
+            blake3::Hash::from_hex(&self.signature).map_err(|_| HandoffError::BadSignature)?;
+        // blake3::Hash compares in constant time
+        if signature != self.mac(key) {
+            return Err(HandoffError::BadSignature);
+        }
+        if now >= self.expires_at {
+            return Err(HandoffError::Expired(self.expires_at));
+        }
+        Ok(())
+    }
+
+    /// Encode the token as URL-safe text, for QR codes and links
+    #[must_use]
+    pub fn encode(&self) -> String {
+        // Serializing plain strings and timestamps cannot fail
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    /// Decode a token produced by [`Self::encode`]
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the text is not an encoded token
+    pub fn decode(encoded: &str) -> Result<Self, HandoffError> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(encoded.trim())
+            .map_err(|e| HandoffError::Malformed(e.to_string()))?;
+        serde_json::from_slice(&bytes).map_err(|e| HandoffError::Malformed(e.to_string()))
+    }
+
+    fn mac(&self, key: &HandoffKey) -> blake3::Hash {
+        // A JSON array keeps the field boundaries unambiguous
+        let fields = serde_json::json!([
+            "saorsa-handoff-v1",
+            self.call_id,
+            self.to_device,
+            self.remote_peer,
+            self.expires_at.timestamp_millis(),
+        ]);
+        blake3::keyed_hash(&key.0, fields.to_string().as_bytes())
+    }
+}
+
+/// Handoff progress of a call on one device
+#[derive(Debug, Clone)]
+pub(crate) enum HandoffState<I> {
+    /// We offered the call to another of our devices
+    Offered {
+        /// The device taking over
+        to_device: I,
+    },
+    /// The peer announced a handoff; a joining device must present a token
+    /// signed with this key
+    Expected {
+        /// Key of the announced handoff
+        key: HandoffKey,
+    },
+    /// We are the device taking over the call
+    Joining,
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_roundtrip_and_verify() {
+        let key = HandoffKey::generate();
+        let now = Utc::now();
+        let token = HandoffToken::sign(
+            &key,
+            CallId::new(),
+            "alice-phone".to_string(),
+            "bob".to_string(),
+            now + chrono::Duration::seconds(60),
+        );
+
+        let decoded = HandoffToken::decode(&token.encode()).unwrap();
+        assert_eq!(decoded, token);
+        decoded.verify(&key, now).unwrap();
+
+        let announced = HandoffKey::decode(&key.encode()).unwrap();
+        decoded.verify(&announced, now).unwrap();
+        assert_eq!(
+            decoded.verify(&HandoffKey::generate(), now),
+            Err(HandoffError::BadSignature)
+        );
+        assert_eq!(
+            decoded.verify(&key, token.expires_at),
+            Err(HandoffError::Expired(token.expires_at))
+        );
+
+        let mut forged = decoded;
+        forged.to_device = "mallory".to_string();
+        assert_eq!(forged.verify(&key, now), Err(HandoffError::BadSignature));
+    }
+}
@@ -0,0 +1,277 @@
+//! Service health and readiness
+//!
+//! [`HealthReport`] is a snapshot of everything a call depends on: the media
+//! transports of ongoing calls, the signaling transport, the codecs and the
+//! media devices, plus the supervised background tasks. Each part carries a
+//! [`HealthStatus`]; the report's own status is the worst of them, and
+//! [`HealthReport::is_ready`] says whether the service can place and take
+//! calls at all. Apps show it on diagnostics screens.
+
+use crate::events::EventStats;
+use crate::supervisor::{ServiceHealth, TaskStatus};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Consecutive signaling receive errors at which signaling counts as down
+pub const SIGNALING_ERROR_LIMIT: u32 = 5;
+
+/// How well a part of the service works, from best to worst
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    /// Working normally
+    Healthy,
+    /// Working, but with problems the user may notice
+    Degraded,
+    /// Not working
+    Unhealthy,
+}
+
+impl fmt::Display for HealthStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Healthy => "healthy",
+            Self::Degraded => "degraded",
+            Self::Unhealthy => "unhealthy",
+        })
+    }
+}
+
+/// Media transports of ongoing calls
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TransportHealth {
+    /// Calls with a media transport
+    pub calls: usize,
+    /// Transports connected to their peer
+    pub connected: usize,
+    /// Transports still connecting
+    pub connecting: usize,
+    /// Transports whose connection failed
+    pub failed: usize,
+}
+
+impl TransportHealth {
+    /// Degraded if any call's transport failed
+    #[must_use]
+    pub fn status(&self) -> HealthStatus {
+        if self.failed > 0 {
+            HealthStatus::Degraded
+        } else {
+            HealthStatus::Healthy
+        }
+    }
+}
+
+/// Signaling transport liveness
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SignalingHealth {
+    /// Milliseconds since the last message arrived, if any has
+    pub last_message_ms: Option<u64>,
+    /// Receive errors since the last message arrived
+    pub consecutive_errors: u32,
+}
+
+impl SignalingHealth {
+    /// Unhealthy at [`SIGNALING_ERROR_LIMIT`] consecutive errors, degraded
+    /// after any
+    ///
+    /// A long silence alone is not a problem: nobody may be calling.
+    #[must_use]
+    pub fn status(&self) -> HealthStatus {
+        match self.consecutive_errors {
+            0 => HealthStatus::Healthy,
+            errors if errors < SIGNALING_ERROR_LIMIT => HealthStatus::Degraded,
+            _ => HealthStatus::Unhealthy,
+        }
+    }
+}
+
+/// Codecs registered with the service
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CodecHealth {
+    /// Video MIME types with an encoder and a decoder, preferred first
+    pub video: Vec<String>,
+    /// Audio MIME types with an encoder and a decoder, preferred first
+    pub audio: Vec<String>,
+}
+
+impl CodecHealth {
+    /// Unhealthy without audio codecs, degraded without video codecs
+    #[must_use]
+    pub fn status(&self) -> HealthStatus {
+        if self.audio.is_empty() {
+            HealthStatus::Unhealthy
+        } else if self.video.is_empty() {
+            HealthStatus::Degraded
+        } else {
+            HealthStatus::Healthy
+        }
+    }
+}
+
+/// Media devices found
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DeviceHealth {
+    /// Names of the audio devices
+    pub audio: Vec<String>,
+    /// Names of the video devices
+    pub video: Vec<String>,
+}
+
+impl DeviceHealth {
+    /// Degraded if a kind of device is missing
+    ///
+    /// Never unhealthy: calls still work without devices, e.g. with
+    /// synthetic media on a headless machine.
+    #[must_use]
+    pub fn status(&self) -> HealthStatus {
+        if self.audio.is_empty() || self.video.is_empty() {
+            HealthStatus::Degraded
+        } else {
+            HealthStatus::Healthy
+        }
+    }
+}
+
+/// Snapshot of the health of the whole service
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct HealthReport {
+    /// When the snapshot was taken
+    pub generated_at: DateTime<Utc>,
+    /// Media transports of ongoing calls
+    pub transport: TransportHealth,
+    /// Signaling transport
+    pub signaling: SignalingHealth,
+    /// Registered codecs
+    pub codecs: CodecHealth,
+    /// Media devices
+    pub devices: DeviceHealth,
+    /// Supervised background tasks
+    pub tasks: ServiceHealth,
+    /// Event channels, including events lost by lagging subscribers
+    #[serde(default)]
+    pub events: EventStats,
+}
+
+impl HealthReport {
+    /// Unhealthy if a task was given up, degraded while one restarts
+    #[must_use]
+    pub fn task_status(&self) -> HealthStatus {
+        self.tasks
+            .tasks
+            .iter()
+            .map(|task| match task.status {
+                TaskStatus::Running => HealthStatus::Healthy,
+                TaskStatus::Restarting { .. } => HealthStatus::Degraded,
+                TaskStatus::Failed => HealthStatus::Unhealthy,
+            })
+            .max()
+            .unwrap_or(HealthStatus::Healthy)
+    }
+
+    /// Worst status of all parts
+    #[must_use]
+    pub fn status(&self) -> HealthStatus {
+        [
+            self.transport.status(),
+            self.signaling.status(),
+            self.codecs.status(),
+            self.devices.status(),
+            self.task_status(),
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or(HealthStatus::Healthy)
+    }
+
+    /// Check if the service can place and take calls
+    ///
+    /// Degraded parts still allow calls; an unhealthy part other than the
+    /// media transports of calls already under way does not.
+    #[must_use]
+    pub fn is_ready(&self) -> bool {
+        [
+            self.signaling.status(),
+            self.codecs.status(),
+            self.task_status(),
+        ]
+        .iter()
+        .all(|status| *status < HealthStatus::Unhealthy)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::supervisor::TaskHealth;
+
+    fn report() -> HealthReport {
+        HealthReport {
+            generated_at: Utc::now(),
+            transport: TransportHealth {
+                calls: 1,
+                connected: 1,
+                ..Default::default()
+            },
+            signaling: SignalingHealth::default(),
+            codecs: CodecHealth {
+                video: vec!["video/H264".to_string()],
+                audio: vec!["audio/opus".to_string()],
+            },
+            devices: DeviceHealth {
+                audio: vec!["Microphone".to_string()],
+                video: vec!["Camera".to_string()],
+            },
+            tasks: ServiceHealth::default(),
+            events: EventStats::default(),
+        }
+    }
+
+    #[test]
+    fn test_status_is_worst_part() {
+        let mut report = report();
+        assert_eq!(report.status(), HealthStatus::Healthy);
+        assert!(report.is_ready());
+
+        report.devices.video.clear();
+        report.transport.failed = 1;
+        assert_eq!(report.status(), HealthStatus::Degraded);
+        assert!(report.is_ready());
+
+        report.signaling.consecutive_errors = SIGNALING_ERROR_LIMIT;
+        assert_eq!(report.status(), HealthStatus::Unhealthy);
+        assert!(!report.is_ready());
+    }
+
+    #[test]
+    fn test_failed_task_is_not_ready() {
+        let mut report = report();
+        report.tasks.tasks.push(TaskHealth {
+            name: "scheduler".to_string(),
+            status: TaskStatus::Restarting {
+                attempt: 1,
+                retry_in_ms: 500,
+            },
+            restarts: 0,
+            last_failure: None,
+            since: Utc::now(),
+        });
+        assert_eq!(report.task_status(), HealthStatus::Degraded);
+        assert!(report.is_ready());
+
+        report.tasks.tasks[0].status = TaskStatus::Failed;
+        assert_eq!(report.status(), HealthStatus::Unhealthy);
+        assert!(!report.is_ready());
+
+        let json = serde_json::to_string(&report).unwrap();
+        assert_eq!(serde_json::from_str::<HealthReport>(&json).unwrap(), report);
+    }
+}
@@ -0,0 +1,255 @@
+//! Hold music and comfort noise
+//!
+//! Holding a call closes its media gate, so the peer hears dead air and
+//! may think the call dropped. [`HoldAudioConfig`], set in
+//! [`WebRtcConfig`](crate::service::WebRtcConfig), picks what a call held
+//! through the service sends instead: nothing, comfort noise (RFC 3389), or
+//! an Ogg Opus file played in a loop. Hold audio goes out on its own audio
+//! track, [`HOLD_TRACK`], which the gate leaves open, so nothing the app
+//! captures leaks to the peer while the call is held.
+
+use crate::bot::{OPUS_CLOCK_RATE, OPUS_PAYLOAD_TYPE};
+use crate::link_transport::StreamType as LinkStreamType;
+use crate::playback::{self, MediaFile, MediaFileFormat, PlaybackError};
+use crate::quic_bridge::{RtpPacket, StreamType};
+use crate::quic_media_transport::{QuicMediaTransport, StreamKey, TrackId};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Track of the audio stream hold audio is sent on
+pub const HOLD_TRACK: TrackId = TrackId::MAX - 1;
+
+/// Payload type of comfort noise packets (RFC 3389)
+pub const COMFORT_NOISE_PAYLOAD_TYPE: u8 = 13;
+
+/// RTP clock rate of comfort noise
+pub const COMFORT_NOISE_CLOCK_RATE: u32 = 8_000;
+
+/// Key of the stream hold audio is sent on
+#[must_use]
+pub const fn hold_key() -> StreamKey {
+    StreamKey::new(LinkStreamType::Audio, HOLD_TRACK)
+}
+
+/// What a held call sends
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum HoldAudio {
+    /// Nothing
+    #[default]
+    Silence,
+    /// Comfort noise at [`HoldAudioConfig::noise_level_dbov`]
+    ComfortNoise,
+    /// An Ogg Opus file played in a loop; comfort noise is sent instead if
+    /// the file cannot be played
+    Music(PathBuf),
+}
+
+/// Hold audio configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HoldAudioConfig {
+    /// What a held call sends
+    pub audio: HoldAudio,
+    /// Comfort noise level, in -dBov (0 is loudest, 127 is silence)
+    pub noise_level_dbov: u8,
+    /// Interval between comfort noise packets
+    pub noise_interval: Duration,
+}
+
+impl Default for HoldAudioConfig {
+    fn default() -> Self {
+        Self {
+            audio: HoldAudio::default(),
+            noise_level_dbov: 70,
+            noise_interval: Duration::from_millis(200),
+        }
+    }
+}
+
+/// RTP numbering of one hold audio stream
+struct HoldStream {
+    payload_type: u8,
+    clock_rate: u32,
+    ssrc: u32,
+    sequence: u16,
+    base_timestamp: u32,
+}
+
+impl HoldStream {
+    fn new(payload_type: u8, clock_rate: u32) -> Self {
+        Self {
+            payload_type,
+            clock_rate,
+            ssrc: rand::random(),
+            sequence: rand::random(),
+            base_timestamp: rand::random(),
+        }
+    }
+
+    /// Send a payload captured `at` after hold audio started
+    async fn send(
+        &mut self,
+        transport: &QuicMediaTransport,
+        at: Duration,
+        payload: Vec<u8>,
+    ) -> Result<(), PlaybackError> {
+        let timestamp = self
+            .base_timestamp
+            .wrapping_add(playback::ticks(at, self.clock_rate));
+        let bytes = RtpPacket::new(
+            self.payload_type,
+            self.sequence,
+            timestamp,
+            self.ssrc,
+            payload,
+            StreamType::Audio,
+        )
+        .and_then(|packet| packet.to_bytes())
+        .map_err(|e| PlaybackError::Format(e.to_string()))?;
+        self.sequence = self.sequence.wrapping_add(1);
+        transport
+            .send_track_rtp(hold_key(), &bytes)
+            .await
+            .map_err(|e| PlaybackError::Send(e.to_string()))
+    }
+}
+
+/// Send hold audio through a call's media transport
+///
+/// Runs until a packet cannot be sent, typically because the call ended;
+/// abort the task running it to stop hold audio. Returns at once for
+/// [`HoldAudio::Silence`].
+///
+/// # Errors
+///
+/// Returns the error that stopped hold audio
+pub async fn play(
+    config: &HoldAudioConfig,
+    transport: &QuicMediaTransport,
+) -> Result<(), PlaybackError> {
+    match config.audio {
+        HoldAudio::Silence => Ok(()),
+        HoldAudio::ComfortNoise => play_comfort_noise(config, transport).await,
+        HoldAudio::Music(ref path) => match load_music(path) {
+            Ok(file) => play_music(&file, transport).await,
+            Err(e) => {
+                tracing::warn!(
+                    path = %path.display(),
+                    error = %e,
+                    "Hold music unavailable, sending comfort noise"
+                );
+                play_comfort_noise(config, transport).await
+            }
+        },
+    }
+}
+
+fn load_music(path: &std::path::Path) -> Result<MediaFile, PlaybackError> {
+    let file = MediaFile::open(path)?;
+    if file.format() != MediaFileFormat::OggOpus {
+        return Err(PlaybackError::Unsupported(path.display().to_string()));
+    }
+    if file.duration().is_zero() {
+        return Err(PlaybackError::Format("no playing time".to_string()));
+    }
+    Ok(file)
+}
+
+async fn play_music(file: &MediaFile, transport: &QuicMediaTransport) -> Result<(), PlaybackError> {
+    let mut stream = HoldStream::new(OPUS_PAYLOAD_TYPE, OPUS_CLOCK_RATE);
+    let start = tokio::time::Instant::now();
+    let mut lap = Duration::ZERO;
+    loop {
+        for frame in file.frames() {
+            let at = lap + frame.offset;
+            tokio::time::sleep_until(start + at).await;
+            stream.send(transport, at, frame.data.clone()).await?;
+        }
+        lap += file.duration();
+    }
+}
+
+async fn play_comfort_noise(
+    config: &HoldAudioConfig,
+    transport: &QuicMediaTransport,
+) -> Result<(), PlaybackError> {
+    let mut stream = HoldStream::new(COMFORT_NOISE_PAYLOAD_TYPE, COMFORT_NOISE_CLOCK_RATE);
+    let start = tokio::time::Instant::now();
+    let mut interval = tokio::time::interval(config.noise_interval.max(Duration::from_millis(1)));
+    loop {
+        let at = interval.tick().await.duration_since(start);
+        // Noise level only, no spectral information
+        stream
+            .send(transport, at, vec![config.noise_level_dbov.min(127)])
+            .await?;
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::service::WebRtcConfig;
+    use crate::testing::{LoopbackHarness, Role};
+    use crate::types::MediaConstraints;
+
+    async fn packets_sent(harness: &LoopbackHarness, call_id: crate::types::CallId) -> u64 {
+        harness
+            .caller()
+            .media_transport(call_id)
+            .await
+            .unwrap()
+            .stats()
+            .await
+            .packets_sent
+    }
+
+    #[tokio::test]
+    async fn test_comfort_noise_while_held() {
+        let caller_config = WebRtcConfig {
+            hold_audio: HoldAudioConfig {
+                audio: HoldAudio::ComfortNoise,
+                noise_interval: Duration::from_millis(20),
+                ..HoldAudioConfig::default()
+            },
+            ..WebRtcConfig::default()
+        };
+        let harness = LoopbackHarness::with_config(caller_config, WebRtcConfig::default())
+            .await
+            .unwrap();
+        let call_id = harness
+            .connect_call(MediaConstraints::audio_only())
+            .await
+            .unwrap();
+        let service = harness.caller().service();
+        let transport = harness.caller().media_transport(call_id).await.unwrap();
+
+        service.hold_call(call_id).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        let held = packets_sent(&harness, call_id).await;
+        assert!(held >= 3, "{held} comfort noise packets");
+        // Only hold audio passes the closed gate
+        assert!(transport.send_audio(&[0x80, 111, 0, 1]).await.is_err());
+
+        service.resume_call(call_id).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let resumed = packets_sent(&harness, call_id).await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(packets_sent(&harness, call_id).await, resumed);
+
+        harness.hang_up(Role::Caller, call_id).await.unwrap();
+        harness.assert_no_leaks().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_silence_by_default() {
+        let harness = LoopbackHarness::new().await.unwrap();
+        let call_id = harness
+            .connect_call(MediaConstraints::audio_only())
+            .await
+            .unwrap();
+        harness.caller().service().hold_call(call_id).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(packets_sent(&harness, call_id).await, 0);
+        harness.hang_up(Role::Caller, call_id).await.unwrap();
+    }
+}
@@ -3,10 +3,27 @@
 //! This module provides traits and types for peer identity in the WebRTC system.
 //! It allows the library to work with any identity system, including FourWordAddress
 //! from saorsa-core or custom identity implementations.
+//!
+//! [`FourWordIdentity`] is the built-in human-friendly identity: four words
+//! from a fixed list of 256, such as `amber-falcon-harbor-zinc`, each word
+//! standing for one byte of the identity.
+//!
+//! The local identity keypair, used to sign signaling messages and to
+//! derive end-to-end keys, is kept by a [`KeyStore`]: in a file or in
+//! memory, and encrypted at rest under a passphrase if one is given.
 
+use chacha20poly1305::aead::Aead;
+use chacha20poly1305::{ChaCha20Poly1305, Key, KeyInit, Nonce};
+use saorsa_pqc::api::kdf::helpers as kdf;
+use saorsa_pqc::api::sig::{MlDsa, MlDsaPublicKey, MlDsaSecretKey, MlDsaSignature, MlDsaVariant};
 use serde::{Deserialize, Serialize};
 use std::fmt::{Debug, Display};
 use std::hash::Hash;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use thiserror::Error;
+use zeroize::Zeroizing;
 
 /// Trait for peer identity in WebRTC system
 ///
@@ -35,6 +52,7 @@ pub trait PeerIdentity:
 /// Suitable for testing or simple applications. For production use, consider
 /// using more robust identity systems like FourWordAddress from saorsa-core.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct PeerIdentityString(pub String);
 
 impl PeerIdentityString {
@@ -77,6 +95,714 @@ impl From<String> for PeerIdentityString {
     }
 }
 
+/// Words of a [`FourWordIdentity`], in byte order
+const WORDS: [&str; 256] = [
+    "acid", "acorn", "actor", "adobe", "agent", "alarm", "album", "alpha", "amber", "anchor",
+    "angel", "apple", "apron", "arena", "arrow", "aspen", "atlas", "attic", "autumn", "badge",
+    "bagel", "baker", "bamboo", "banjo", "barrel", "basil", "beacon", "beaver", "bench", "berry",
+    "bison", "blade", "blanket", "bonus", "boulder", "bravo", "breeze", "brick", "bridge",
+    "bronze", "bucket", "buffalo", "button", "cabin", "cactus", "camel", "canal", "candle",
+    "canyon", "captain", "carbon", "cargo", "carpet", "castle", "cedar", "cello", "chalk",
+    "charlie", "cherry", "chess", "cider", "cinema", "circus", "citrus", "clover", "cobalt",
+    "cocoa", "comet", "copper", "coral", "cotton", "crater", "crayon", "cricket", "crystal",
+    "dagger", "daisy", "delta", "denim", "desert", "diamond", "dingo", "dolphin", "domino",
+    "dragon", "drum", "eagle", "easel", "echo", "eclipse", "elbow", "ember", "emerald", "engine",
+    "falcon", "feather", "fiddle", "fjord", "flame", "flute", "forest", "fossil", "fox", "frost",
+    "galaxy", "garden", "garnet", "gecko", "ginger", "glacier", "globe", "golf", "granite",
+    "grape", "gravel", "guitar", "hammer", "harbor", "harvest", "hazel", "helmet", "heron",
+    "honey", "hotel", "icarus", "igloo", "india", "indigo", "island", "ivory", "jacket", "jade",
+    "jaguar", "jasmine", "jelly", "jewel", "juliet", "jungle", "juniper", "kayak", "kettle",
+    "kilo", "kiwi", "koala", "ladder", "lagoon", "lantern", "laser", "lemon", "lima", "linen",
+    "lizard", "lotus", "lunar", "magnet", "mango", "maple", "marble", "meadow", "melon", "meteor",
+    "mike", "mint", "mirror", "mocha", "mosaic", "nebula", "nectar", "needle", "nickel", "noodle",
+    "november", "nutmeg", "oasis", "ocean", "olive", "onyx", "opal", "orbit", "orchid", "oscar",
+    "otter", "oyster", "paddle", "panda", "papa", "parrot", "pebble", "pepper", "phoenix", "piano",
+    "pilot", "pine", "planet", "plaza", "plum", "polar", "poppy", "prism", "puffin", "pumpkin",
+    "quartz", "quasar", "quebec", "quill", "quiver", "rabbit", "radar", "radish", "raven", "reef",
+    "ribbon", "river", "robin", "rocket", "romeo", "ruby", "saddle", "saffron", "salmon", "sierra",
+    "silver", "sketch", "sonnet", "spruce", "stellar", "summit", "sunset", "tango", "teapot",
+    "thistle", "thunder", "tiger", "timber", "titan", "topaz", "tulip", "tundra", "turtle",
+    "uniform", "velvet", "victor", "violet", "vortex", "walnut", "whiskey", "willow", "window",
+    "wizard", "xray", "yankee", "yarrow", "zebra", "zephyr", "zinc", "zulu",
+];
+
+/// Separators accepted between the words of a four-word identity
+const SEPARATORS: [char; 4] = ['-', ' ', '.', '_'];
+
+/// Four-word identity errors
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum IdentityError {
+    /// Not four words, or four words and a checksum word
+    #[error("Expected 4 or 5 words, got {0}")]
+    WordCount(usize),
+
+    /// A word is not in the word list
+    #[error("Unknown word: {0}")]
+    UnknownWord(String),
+
+    /// The checksum word does not match the four words
+    #[error("Checksum word mismatch: expected {expected}, got {actual}")]
+    Checksum {
+        /// Checksum word of the four words
+        expected: String,
+        /// Checksum word given
+        actual: String,
+    },
+}
+
+/// Four-word peer identity
+///
+/// The canonical form is four lowercase words joined by hyphens. Parsing
+/// is forgiving: case is ignored, words may be separated by hyphens,
+/// spaces, dots or underscores, and a fifth checksum word catches typos in
+/// identities that are read out or typed in.
+///
+/// The four words encode four bytes. [`Self::from_key`] derives them from
+/// a public key, which makes the identity a label people can read out,
+/// not proof of who holds the key: finding another key with the same four
+/// words takes only about 2^32 tries. Authenticate peers against the
+/// [`KeyFingerprint`] of their key instead.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct FourWordIdentity([u8; 4]);
+
+impl FourWordIdentity {
+    /// Identity encoded by four bytes
+    #[must_use]
+    pub const fn from_bytes(bytes: [u8; 4]) -> Self {
+        Self(bytes)
+    }
+
+    /// Bytes the identity encodes
+    #[must_use]
+    pub const fn to_bytes(self) -> [u8; 4] {
+        self.0
+    }
+
+    /// Identity derived from a public key
+    #[must_use]
+    pub fn from_key(public_key: &[u8]) -> Self {
+        let hash = blake3::hash(public_key);
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(&hash.as_bytes()[..4]);
+        Self(bytes)
+    }
+
+    /// Check if the identity could have been derived from a public key
+    ///
+    /// Compares 32 bits, enough to catch a mix-up but not to show that a
+    /// peer owns the identity; use [`KeyFingerprint::matches_key`] for that.
+    #[must_use]
+    pub fn matches_key(&self, public_key: &[u8]) -> bool {
+        Self::from_key(public_key) == *self
+    }
+
+    /// Random identity
+    #[must_use]
+    pub fn generate() -> Self {
+        Self(rand::random())
+    }
+
+    /// The four words
+    #[must_use]
+    pub fn words(&self) -> [&'static str; 4] {
+        self.0.map(|byte| WORDS[usize::from(byte)])
+    }
+
+    /// Word that checks the four words
+    #[must_use]
+    pub fn checksum_word(&self) -> &'static str {
+        WORDS[usize::from(blake3::hash(&self.0).as_bytes()[0])]
+    }
+
+    /// Canonical form followed by the checksum word
+    #[must_use]
+    pub fn to_string_with_checksum(&self) -> String {
+        format!("{self}-{}", self.checksum_word())
+    }
+
+    /// Parse four words, optionally followed by the checksum word
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a word is unknown, there are not four or five words,
+    /// or the checksum word does not match
+    pub fn parse(s: &str) -> Result<Self, IdentityError> {
+        let words: Vec<String> = s
+            .split(SEPARATORS)
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+        if !matches!(words.len(), 4 | 5) {
+            return Err(IdentityError::WordCount(words.len()));
+        }
+
+        let mut bytes = [0u8; 4];
+        for (byte, word) in bytes.iter_mut().zip(&words) {
+            *byte = word_index(word).ok_or_else(|| IdentityError::UnknownWord(word.clone()))?;
+        }
+        let identity = Self(bytes);
+
+        if let Some(checksum) = words.get(4) {
+            let expected = identity.checksum_word();
+            if checksum != expected {
+                return Err(IdentityError::Checksum {
+                    expected: expected.to_string(),
+                    actual: checksum.clone(),
+                });
+            }
+        }
+        Ok(identity)
+    }
+}
+
+/// Fingerprint of a public key: its full 256-bit BLAKE3 hash
+///
+/// Unlike a [`FourWordIdentity`], which keeps only 32 bits of the same hash,
+/// a fingerprint cannot feasibly be matched by another key, so it is what
+/// peers are authenticated against. Displayed as 16 groups of four hex
+/// digits for comparing out of band.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyFingerprint(blake3::Hash);
+
+impl KeyFingerprint {
+    /// Fingerprint of a public key
+    #[must_use]
+    pub fn of(public_key: &[u8]) -> Self {
+        Self(blake3::hash(public_key))
+    }
+
+    /// Check in constant time if this is the fingerprint of a public key
+    #[must_use]
+    pub fn matches_key(&self, public_key: &[u8]) -> bool {
+        Self::of(public_key) == *self
+    }
+
+    /// Four-word identity of the key
+    #[must_use]
+    pub fn identity(&self) -> FourWordIdentity {
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(&self.0.as_bytes()[..4]);
+        FourWordIdentity(bytes)
+    }
+
+    /// The hash bytes
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        self.0.as_bytes()
+    }
+}
+
+impl Display for KeyFingerprint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let hex = self.0.to_hex();
+        for (i, group) in hex.as_bytes().chunks(4).enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            // Hex digits are ASCII
+            f.write_str(std::str::from_utf8(group).unwrap_or_default())?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for KeyFingerprint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "KeyFingerprint({self})")
+    }
+}
+
+/// Byte a word stands for
+fn word_index(word: &str) -> Option<u8> {
+    WORDS
+        .binary_search(&word)
+        .ok()
+        .and_then(|index| u8::try_from(index).ok())
+}
+
+impl Display for FourWordIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.words().join("-"))
+    }
+}
+
+impl Debug for FourWordIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "FourWordIdentity({self})")
+    }
+}
+
+impl FromStr for FourWordIdentity {
+    type Err = IdentityError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for FourWordIdentity {
+    type Error = IdentityError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::parse(&s)
+    }
+}
+
+impl From<FourWordIdentity> for String {
+    fn from(identity: FourWordIdentity) -> Self {
+        identity.to_string()
+    }
+}
+
+impl PeerIdentity for FourWordIdentity {
+    fn to_string_repr(&self) -> String {
+        self.to_string()
+    }
+
+    fn from_string_repr(s: &str) -> anyhow::Result<Self> {
+        Ok(Self::parse(s)?)
+    }
+}
+
+/// Signature scheme of identity keypairs
+const KEYPAIR_VARIANT: MlDsaVariant = MlDsaVariant::MlDsa65;
+
+/// Format version of stored keypairs
+const KEY_FILE_VERSION: u8 = 1;
+
+/// Default PBKDF2 iterations turning a passphrase into a key
+pub const DEFAULT_KDF_ITERATIONS: u32 = 600_000;
+
+/// Most PBKDF2 iterations a stored keypair may ask for
+///
+/// Bounds the work a tampered key file can make [`KeyStore::load`] do.
+pub const MAX_KDF_ITERATIONS: u32 = 10_000_000;
+
+/// Key store errors
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum KeyStoreError {
+    /// Generating, signing or verifying failed
+    #[error("Crypto error: {0}")]
+    Crypto(String),
+
+    /// Reading or writing the storage failed
+    #[error("Storage error: {0}")]
+    Storage(String),
+
+    /// The stored keypair cannot be read
+    #[error("Corrupt key data: {0}")]
+    Corrupt(String),
+
+    /// The keypair is encrypted and no passphrase was given
+    #[error("Keypair is passphrase protected")]
+    PassphraseRequired,
+
+    /// The passphrase does not decrypt the keypair
+    #[error("Wrong passphrase")]
+    WrongPassphrase,
+}
+
+/// Local identity keypair
+///
+/// An ML-DSA-65 keypair. The public key determines the
+/// [`FourWordIdentity`] peers know us by and the [`KeyFingerprint`] they
+/// authenticate us by.
+#[derive(Clone)]
+pub struct IdentityKeypair {
+    public: MlDsaPublicKey,
+    secret: MlDsaSecretKey,
+}
+
+impl Debug for IdentityKeypair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IdentityKeypair")
+            .field("identity", &self.identity())
+            .finish_non_exhaustive()
+    }
+}
+
+impl IdentityKeypair {
+    /// Generate a fresh keypair
+    ///
+    /// # Errors
+    ///
+    /// Returns error if key generation fails
+    pub fn generate() -> Result<Self, KeyStoreError> {
+        let (public, secret) = MlDsa::new(KEYPAIR_VARIANT)
+            .generate_keypair()
+            .map_err(|e| KeyStoreError::Crypto(e.to_string()))?;
+        Ok(Self { public, secret })
+    }
+
+    /// Encoded public key
+    #[must_use]
+    pub fn public_key(&self) -> Vec<u8> {
+        self.public.to_bytes()
+    }
+
+    /// Identity derived from the public key
+    #[must_use]
+    pub fn identity(&self) -> FourWordIdentity {
+        FourWordIdentity::from_key(&self.public.to_bytes())
+    }
+
+    /// Fingerprint of the public key, to authenticate us by
+    #[must_use]
+    pub fn fingerprint(&self) -> KeyFingerprint {
+        KeyFingerprint::of(&self.public.to_bytes())
+    }
+
+    /// Sign a message
+    ///
+    /// # Errors
+    ///
+    /// Returns error if signing fails
+    pub fn sign(&self, message: &[u8]) -> Result<Vec<u8>, KeyStoreError> {
+        MlDsa::new(KEYPAIR_VARIANT)
+            .sign(&self.secret, message)
+            .map(|signature| signature.to_bytes())
+            .map_err(|e| KeyStoreError::Crypto(e.to_string()))
+    }
+
+    /// Check a signature made with the secret key of `public_key`
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the key or signature is malformed
+    pub fn verify(
+        public_key: &[u8],
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<bool, KeyStoreError> {
+        let public = MlDsaPublicKey::from_bytes(KEYPAIR_VARIANT, public_key)
+            .map_err(|e| KeyStoreError::Crypto(e.to_string()))?;
+        let signature = MlDsaSignature::from_bytes(KEYPAIR_VARIANT, signature)
+            .map_err(|e| KeyStoreError::Crypto(e.to_string()))?;
+        MlDsa::new(KEYPAIR_VARIANT)
+            .verify(&public, message, &signature)
+            .map_err(|e| KeyStoreError::Crypto(e.to_string()))
+    }
+
+    /// Derive a 32-byte key for `context` from the secret key
+    ///
+    /// The same keypair and context always give the same key, e.g. for
+    /// end-to-end encryption; different contexts give unrelated keys.
+    #[must_use]
+    pub fn derive_key(&self, context: &str) -> Zeroizing<[u8; 32]> {
+        let secret = Zeroizing::new(self.secret.to_bytes());
+        Zeroizing::new(blake3::derive_key(context, &secret))
+    }
+
+    fn from_parts(public_key: &[u8], secret_key: &[u8]) -> Result<Self, KeyStoreError> {
+        let public = MlDsaPublicKey::from_bytes(KEYPAIR_VARIANT, public_key)
+            .map_err(|e| KeyStoreError::Corrupt(e.to_string()))?;
+        let secret = MlDsaSecretKey::from_bytes(KEYPAIR_VARIANT, secret_key)
+            .map_err(|e| KeyStoreError::Corrupt(e.to_string()))?;
+        Ok(Self { public, secret })
+    }
+}
+
+/// Where a [`KeyStore`] keeps the encoded keypair
+pub trait KeyStorage: Send + Sync {
+    /// Read the stored data, `None` if nothing is stored
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the storage cannot be read
+    fn load(&self) -> Result<Option<Vec<u8>>, KeyStoreError>;
+
+    /// Replace the stored data
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the storage cannot be written
+    fn save(&self, data: &[u8]) -> Result<(), KeyStoreError>;
+}
+
+/// Keeps the keypair in a file, readable only by its owner on Unix
+#[derive(Debug, Clone)]
+pub struct FileKeyStorage {
+    path: PathBuf,
+}
+
+impl FileKeyStorage {
+    /// Storage at `path`; parent directories are created on save
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Path of the key file
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl KeyStorage for FileKeyStorage {
+    fn load(&self) -> Result<Option<Vec<u8>>, KeyStoreError> {
+        match std::fs::read(&self.path) {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(KeyStoreError::Storage(e.to_string())),
+        }
+    }
+
+    fn save(&self, data: &[u8]) -> Result<(), KeyStoreError> {
+        use std::io::Write;
+
+        let storage_error = |e: std::io::Error| KeyStoreError::Storage(e.to_string());
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent).map_err(storage_error)?;
+        }
+        // Write aside and rename, so a crash never leaves half a key
+        let temp = self.path.with_extension("tmp");
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&temp).map_err(storage_error)?;
+        file.write_all(data).map_err(storage_error)?;
+        file.sync_all().map_err(storage_error)?;
+        std::fs::rename(&temp, &self.path).map_err(storage_error)
+    }
+}
+
+/// Keeps the keypair in memory, e.g. for tests and ephemeral identities
+#[derive(Debug, Default)]
+pub struct MemoryKeyStorage {
+    data: parking_lot::Mutex<Option<Zeroizing<Vec<u8>>>>,
+}
+
+impl KeyStorage for MemoryKeyStorage {
+    fn load(&self) -> Result<Option<Vec<u8>>, KeyStoreError> {
+        Ok(self.data.lock().as_ref().map(|data| data.to_vec()))
+    }
+
+    fn save(&self, data: &[u8]) -> Result<(), KeyStoreError> {
+        *self.data.lock() = Some(Zeroizing::new(data.to_vec()));
+        Ok(())
+    }
+}
+
+/// Keypair as stored
+#[derive(Serialize, Deserialize)]
+struct StoredKeypair {
+    version: u8,
+    public_key: Vec<u8>,
+    secret: StoredSecret,
+}
+
+/// Secret key as stored
+#[derive(Serialize, Deserialize)]
+enum StoredSecret {
+    /// Unencrypted, protected only by the storage
+    Plain(Vec<u8>),
+    /// ChaCha20-Poly1305 under a PBKDF2 key, the public key as associated data
+    Encrypted {
+        salt: [u8; 16],
+        iterations: u32,
+        nonce: [u8; 12],
+        ciphertext: Vec<u8>,
+    },
+}
+
+/// Generates, stores and loads the local identity keypair
+///
+/// Without a passphrase the secret key is stored as is and protected only
+/// by the storage, e.g. the key file's permissions. With one, it is
+/// encrypted with ChaCha20-Poly1305 under a key stretched from the
+/// passphrase with PBKDF2.
+pub struct KeyStore {
+    storage: Arc<dyn KeyStorage>,
+    passphrase: Option<Zeroizing<String>>,
+    kdf_iterations: u32,
+    /// Fewest iterations a stored keypair may ask for; lowered only by
+    /// tests, which cannot afford a real KDF
+    min_kdf_iterations: u32,
+}
+
+impl Debug for KeyStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyStore")
+            .field("protected", &self.passphrase.is_some())
+            .field("kdf_iterations", &self.kdf_iterations)
+            .finish_non_exhaustive()
+    }
+}
+
+impl KeyStore {
+    /// Key store on a storage backend
+    #[must_use]
+    pub fn new(storage: Arc<dyn KeyStorage>) -> Self {
+        Self {
+            storage,
+            passphrase: None,
+            kdf_iterations: DEFAULT_KDF_ITERATIONS,
+            min_kdf_iterations: DEFAULT_KDF_ITERATIONS,
+        }
+    }
+
+    /// Key store backed by a file
+    pub fn file(path: impl Into<PathBuf>) -> Self {
+        Self::new(Arc::new(FileKeyStorage::new(path)))
+    }
+
+    /// Key store held in memory
+    #[must_use]
+    pub fn memory() -> Self {
+        Self::new(Arc::new(MemoryKeyStorage::default()))
+    }
+
+    /// Encrypt the keypair at rest under a passphrase
+    #[must_use]
+    pub fn with_passphrase(mut self, passphrase: impl Into<String>) -> Self {
+        self.passphrase = Some(Zeroizing::new(passphrase.into()));
+        self
+    }
+
+    /// PBKDF2 iterations for keypairs saved from now on
+    ///
+    /// Clamped to between [`DEFAULT_KDF_ITERATIONS`] and
+    /// [`MAX_KDF_ITERATIONS`]. Loading uses the iterations stored with the
+    /// keypair, and rejects counts outside that range.
+    #[must_use]
+    pub fn with_kdf_iterations(mut self, iterations: u32) -> Self {
+        self.kdf_iterations = iterations.clamp(DEFAULT_KDF_ITERATIONS, MAX_KDF_ITERATIONS);
+        self
+    }
+
+    /// Save and accept keypairs with a cheap KDF, for tests only
+    #[cfg(test)]
+    pub(crate) fn with_test_kdf_iterations(mut self, iterations: u32) -> Self {
+        self.kdf_iterations = iterations;
+        self.min_kdf_iterations = iterations;
+        self
+    }
+
+    /// Load the stored keypair, `None` if there is none
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the storage fails, the data is corrupt or asks for
+    /// out of range KDF iterations, or the passphrase is missing or wrong
+    pub fn load(&self) -> Result<Option<IdentityKeypair>, KeyStoreError> {
+        let Some(data) = self.storage.load()? else {
+            return Ok(None);
+        };
+        let stored: StoredKeypair =
+            postcard::from_bytes(&data).map_err(|e| KeyStoreError::Corrupt(e.to_string()))?;
+        if stored.version != KEY_FILE_VERSION {
+            return Err(KeyStoreError::Corrupt(format!(
+                "unsupported version {}",
+                stored.version
+            )));
+        }
+
+        let secret_key = match stored.secret {
+            StoredSecret::Plain(secret_key) => Zeroizing::new(secret_key),
+            StoredSecret::Encrypted {
+                salt,
+                iterations,
+                nonce,
+                ciphertext,
+            } => {
+                if !(self.min_kdf_iterations..=MAX_KDF_ITERATIONS).contains(&iterations) {
+                    return Err(KeyStoreError::Corrupt(format!(
+                        "{iterations} KDF iterations, expected {} to {MAX_KDF_ITERATIONS}",
+                        self.min_kdf_iterations
+                    )));
+                }
+                let passphrase = self
+                    .passphrase
+                    .as_ref()
+                    .ok_or(KeyStoreError::PassphraseRequired)?;
+                let cipher = self.cipher(passphrase, &salt, iterations)?;
+                let payload = chacha20poly1305::aead::Payload {
+                    msg: &ciphertext,
+                    aad: &stored.public_key,
+                };
+                Zeroizing::new(
+                    cipher
+                        .decrypt(Nonce::from_slice(&nonce), payload)
+                        .map_err(|_| KeyStoreError::WrongPassphrase)?,
+                )
+            }
+        };
+        IdentityKeypair::from_parts(&stored.public_key, &secret_key).map(Some)
+    }
+
+    /// Store a keypair, replacing any stored one
+    ///
+    /// # Errors
+    ///
+    /// Returns error if encryption or the storage fails
+    pub fn save(&self, keypair: &IdentityKeypair) -> Result<(), KeyStoreError> {
+        let public_key = keypair.public_key();
+        let secret_key = Zeroizing::new(keypair.secret.to_bytes());
+
+        let secret = match &self.passphrase {
+            None => StoredSecret::Plain(secret_key.to_vec()),
+            Some(passphrase) => {
+                let salt: [u8; 16] = rand::random();
+                let nonce: [u8; 12] = rand::random();
+                let cipher = self.cipher(passphrase, &salt, self.kdf_iterations)?;
+                let payload = chacha20poly1305::aead::Payload {
+                    msg: &secret_key,
+                    aad: &public_key,
+                };
+                let ciphertext = cipher
+                    .encrypt(Nonce::from_slice(&nonce), payload)
+                    .map_err(|e| KeyStoreError::Crypto(e.to_string()))?;
+                StoredSecret::Encrypted {
+                    salt,
+                    iterations: self.kdf_iterations,
+                    nonce,
+                    ciphertext,
+                }
+            }
+        };
+
+        let data = Zeroizing::new(
+            postcard::to_stdvec(&StoredKeypair {
+                version: KEY_FILE_VERSION,
+                public_key,
+                secret,
+            })
+            .map_err(|e| KeyStoreError::Corrupt(e.to_string()))?,
+        );
+        self.storage.save(&data)
+    }
+
+    /// Load the stored keypair, generating and storing one if there is none
+    ///
+    /// # Errors
+    ///
+    /// Returns error if loading, generating or saving fails
+    pub fn load_or_generate(&self) -> Result<IdentityKeypair, KeyStoreError> {
+        if let Some(keypair) = self.load()? {
+            return Ok(keypair);
+        }
+        let keypair = IdentityKeypair::generate()?;
+        self.save(&keypair)?;
+        Ok(keypair)
+    }
+
+    fn cipher(
+        &self,
+        passphrase: &str,
+        salt: &[u8],
+        iterations: u32,
+    ) -> Result<ChaCha20Poly1305, KeyStoreError> {
+        let key = kdf::derive_key_from_password(passphrase.as_bytes(), salt, iterations)
+            .map_err(|e| KeyStoreError::Crypto(e.to_string()))?;
+        Ok(ChaCha20Poly1305::new(Key::from_slice(key.as_slice())))
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
@@ -104,4 +830,150 @@ mod tests {
         let deserialized: PeerIdentityString = serde_json::from_str(&json).ok().unwrap();
         assert_eq!(id, deserialized);
     }
+
+    #[test]
+    fn test_four_word_identity_parse_and_format() {
+        let identity = FourWordIdentity::from_bytes([0, 1, 254, 255]);
+        assert_eq!(identity.to_string(), "acid-acorn-zinc-zulu");
+        assert_eq!(identity.to_bytes(), [0, 1, 254, 255]);
+        assert_eq!(
+            FourWordIdentity::parse(" Acid acorn.ZINC_zulu ").unwrap(),
+            identity
+        );
+
+        let with_checksum = identity.to_string_with_checksum();
+        assert_eq!(FourWordIdentity::parse(&with_checksum).unwrap(), identity);
+        let wrong = if identity.checksum_word() == "acid" {
+            "acorn"
+        } else {
+            "acid"
+        };
+        assert!(matches!(
+            FourWordIdentity::parse(&format!("{identity}-{wrong}")),
+            Err(IdentityError::Checksum { .. })
+        ));
+        assert_eq!(
+            FourWordIdentity::parse("acid-acorn-zinc"),
+            Err(IdentityError::WordCount(3))
+        );
+        assert_eq!(
+            FourWordIdentity::parse("acid-acorn-zinc-aardvark"),
+            Err(IdentityError::UnknownWord("aardvark".to_string()))
+        );
+
+        let json = serde_json::to_string(&identity).unwrap();
+        assert_eq!(json, "\"acid-acorn-zinc-zulu\"");
+        assert_eq!(
+            serde_json::from_str::<FourWordIdentity>(&json).unwrap(),
+            identity
+        );
+        assert!(serde_json::from_str::<FourWordIdentity>("\"alice-bob\"").is_err());
+    }
+
+    #[test]
+    fn test_four_word_identity_from_key() {
+        let key = [7u8; 32];
+        let identity = FourWordIdentity::from_key(&key);
+        assert!(identity.matches_key(&key));
+        assert!(!identity.matches_key(&[8u8; 32]));
+        assert_eq!(
+            FourWordIdentity::from_string_repr(&identity.to_string_repr()).unwrap(),
+            identity
+        );
+        let generated = FourWordIdentity::generate();
+        assert_eq!(generated.to_string().split('-').count(), 4);
+    }
+
+    #[test]
+    fn test_fingerprint_rejects_key_with_same_words() {
+        // Birthday search for two keys whose hashes share the four bytes the
+        // words encode
+        let mut seen = std::collections::HashMap::new();
+        let (key, other) = (0u64..)
+            .find_map(|i| {
+                let key = i.to_le_bytes().to_vec();
+                seen.insert(FourWordIdentity::from_key(&key), key.clone())
+                    .map(|other| (key, other))
+            })
+            .unwrap();
+
+        let fingerprint = KeyFingerprint::of(&key);
+        assert_eq!(fingerprint.identity(), FourWordIdentity::from_key(&key));
+        assert!(FourWordIdentity::from_key(&key).matches_key(&other));
+        assert!(fingerprint.matches_key(&key));
+        assert!(!fingerprint.matches_key(&other));
+        assert_ne!(fingerprint, KeyFingerprint::of(&other));
+        assert_eq!(fingerprint.to_string().split(' ').count(), 16);
+    }
+
+    #[test]
+    fn test_key_store_roundtrip() {
+        let store = KeyStore::memory();
+        assert!(store.load().unwrap().is_none());
+        let keypair = store.load_or_generate().unwrap();
+        let loaded = store.load().unwrap().unwrap();
+        assert_eq!(loaded.public_key(), keypair.public_key());
+        assert_eq!(loaded.identity(), keypair.identity());
+        assert_eq!(*loaded.derive_key("test"), *keypair.derive_key("test"));
+        assert_ne!(*keypair.derive_key("test"), *keypair.derive_key("other"));
+
+        let signature = loaded.sign(b"hello").unwrap();
+        assert!(IdentityKeypair::verify(&keypair.public_key(), b"hello", &signature).unwrap());
+        assert!(!IdentityKeypair::verify(&keypair.public_key(), b"hullo", &signature).unwrap());
+        assert!(keypair.identity().matches_key(&keypair.public_key()));
+        assert!(keypair.fingerprint().matches_key(&keypair.public_key()));
+        assert_eq!(keypair.fingerprint().identity(), keypair.identity());
+    }
+
+    #[test]
+    fn test_key_store_passphrase() {
+        let dir = std::env::temp_dir().join(format!("saorsa-keys-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("identity.key");
+        let store = || KeyStore::file(&path).with_test_kdf_iterations(1000);
+        let protected = || store().with_passphrase("correct horse");
+        let keypair = protected().load_or_generate().unwrap();
+
+        let loaded = protected().load().unwrap().unwrap();
+        assert_eq!(loaded.public_key(), keypair.public_key());
+        assert_eq!(
+            store().load().unwrap_err(),
+            KeyStoreError::PassphraseRequired
+        );
+        assert_eq!(
+            store()
+                .with_passphrase("battery staple")
+                .load()
+                .unwrap_err(),
+            KeyStoreError::WrongPassphrase
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_key_store_rejects_out_of_range_iterations() {
+        let storage = Arc::new(MemoryKeyStorage::default());
+        let store = KeyStore::new(storage.clone()).with_passphrase("correct horse");
+        for iterations in [1000, MAX_KDF_ITERATIONS + 1] {
+            let stored = StoredKeypair {
+                version: KEY_FILE_VERSION,
+                public_key: vec![0; 32],
+                secret: StoredSecret::Encrypted {
+                    salt: [0; 16],
+                    iterations,
+                    nonce: [0; 12],
+                    ciphertext: vec![0; 48],
+                },
+            };
+            storage
+                .save(&postcard::to_stdvec(&stored).unwrap())
+                .unwrap();
+            assert!(matches!(store.load(), Err(KeyStoreError::Corrupt(_))));
+        }
+    }
 }
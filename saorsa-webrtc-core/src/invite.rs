@@ -0,0 +1,240 @@
+//! Call invitation links
+//!
+//! An invitation is a deep link that non-technical users can click or scan
+//! as a QR code to call someone:
+//!
+//! ```text
+//! saorsa://call?peer=alpha-bravo-charlie-delta&caps=audio,video
+//! ```
+//!
+//! `peer` is the identity to call and is required. `caps` lists the media
+//! the call should use (`audio`, `video`, `screen`); without it a video call
+//! is assumed. Unknown parameters and capabilities are ignored so older
+//! clients can still open links made by newer ones.
+
+use crate::types::MediaConstraints;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Scheme and path that start every invitation link
+pub const INVITE_PREFIX: &str = "saorsa://call";
+
+/// Invitation errors
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum InviteError {
+    /// Link does not start with `saorsa://call`
+    #[error("Not a call invitation: {0}")]
+    NotAnInvite(String),
+
+    /// Link has no peer to call
+    #[error("Invitation has no peer")]
+    MissingPeer,
+
+    /// Link is not correctly encoded
+    #[error("Malformed invitation: {0}")]
+    Malformed(String),
+}
+
+/// Invitation to call a peer
+#[derive(Debug, Clone)]
+pub struct CallInvite {
+    /// Identity of the peer to call
+    pub peer: String,
+    /// Media the call should use
+    pub constraints: MediaConstraints,
+}
+
+impl CallInvite {
+    /// Create an invitation to call `peer`
+    #[must_use]
+    pub fn new(peer: impl Into<String>, constraints: MediaConstraints) -> Self {
+        Self {
+            peer: peer.into(),
+            constraints,
+        }
+    }
+
+    /// Encode the invitation as a `saorsa://call` link
+    #[must_use]
+    pub fn to_uri(&self) -> String {
+        let mut caps = Vec::new();
+        if self.constraints.audio {
+            caps.push("audio");
+        }
+        if self.constraints.video {
+            caps.push("video");
+        }
+        if self.constraints.screen_share {
+            caps.push("screen");
+        }
+        format!(
+            "{INVITE_PREFIX}?peer={}&caps={}",
+            percent_encode(&self.peer),
+            caps.join(",")
+        )
+    }
+
+    /// Parse a `saorsa://call` link
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the link is not an invitation, has no peer or is
+    /// badly encoded
+    pub fn parse(uri: &str) -> Result<Self, InviteError> {
+        let uri = uri.trim();
+        let query = uri
+            .get(..INVITE_PREFIX.len())
+            .filter(|prefix| prefix.eq_ignore_ascii_case(INVITE_PREFIX))
+            .and_then(|_| uri.get(INVITE_PREFIX.len()..))
+            .and_then(|rest| rest.strip_prefix('?').or(rest.is_empty().then_some("")))
+            .ok_or_else(|| InviteError::NotAnInvite(uri.to_string()))?;
+
+        let mut peer = None;
+        let mut constraints = MediaConstraints::video_call();
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            match key {
+                "peer" => peer = Some(percent_decode(value)?),
+                "caps" => {
+                    let caps = percent_decode(value)?;
+                    let caps: Vec<&str> = caps.split(',').map(str::trim).collect();
+                    constraints = MediaConstraints {
+                        audio: caps.contains(&"audio"),
+                        video: caps.contains(&"video"),
+                        screen_share: caps.contains(&"screen"),
+                        latency: None,
+                    };
+                }
+                _ => {}
+            }
+        }
+
+        let peer = peer
+            .filter(|peer| !peer.is_empty())
+            .ok_or(InviteError::MissingPeer)?;
+        Ok(Self { peer, constraints })
+    }
+}
+
+impl fmt::Display for CallInvite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_uri())
+    }
+}
+
+impl FromStr for CallInvite {
+    type Err = InviteError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Percent-encode everything but RFC 3986 unreserved characters
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+/// Decode `%XX` escapes and `+` as space
+fn percent_decode(value: &str) -> Result<String, InviteError> {
+    let malformed = || InviteError::Malformed(value.to_string());
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut input = value.bytes();
+    while let Some(byte) = input.next() {
+        match byte {
+            b'%' => {
+                let hex = [input.next(), input.next()];
+                let [Some(high), Some(low)] = hex else {
+                    return Err(malformed());
+                };
+                let digits = std::str::from_utf8(&[high, low])
+                    .ok()
+                    .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+                    .ok_or_else(malformed)?;
+                bytes.push(digits);
+            }
+            b'+' => bytes.push(b' '),
+            _ => bytes.push(byte),
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| malformed())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invite_roundtrip() {
+        let invite = CallInvite::new("alpha-bravo-charlie-delta", MediaConstraints::audio_only());
+        let uri = invite.to_uri();
+        assert_eq!(
+            uri,
+            "saorsa://call?peer=alpha-bravo-charlie-delta&caps=audio"
+        );
+
+        let parsed: CallInvite = uri.parse().unwrap();
+        assert_eq!(parsed.peer, "alpha-bravo-charlie-delta");
+        assert!(parsed.constraints.audio);
+        assert!(!parsed.constraints.video);
+        assert!(!parsed.constraints.screen_share);
+    }
+
+    #[test]
+    fn test_invite_escapes_peer() {
+        let invite = CallInvite::new("Zoë & co?", MediaConstraints::screen_share());
+        let uri = invite.to_uri();
+        assert!(uri.ends_with("peer=Zo%C3%AB%20%26%20co%3F&caps=audio,screen"));
+        assert_eq!(CallInvite::parse(&uri).unwrap().peer, "Zoë & co?");
+    }
+
+    #[test]
+    fn test_invite_defaults_and_unknown_fields() {
+        let invite =
+            CallInvite::parse("SAORSA://call?peer=bob&name=Bob&caps=video,hologram").unwrap();
+        assert_eq!(invite.peer, "bob");
+        assert!(invite.constraints.video);
+        assert!(!invite.constraints.audio);
+
+        let invite = CallInvite::parse("saorsa://call?peer=bob").unwrap();
+        assert!(invite.constraints.audio && invite.constraints.video);
+    }
+
+    #[test]
+    fn test_invalid_invites() {
+        assert!(matches!(
+            CallInvite::parse("https://example.com/call?peer=bob"),
+            Err(InviteError::NotAnInvite(_))
+        ));
+        assert!(matches!(
+            CallInvite::parse("saorsa://caller?peer=bob"),
+            Err(InviteError::NotAnInvite(_))
+        ));
+        assert_eq!(
+            CallInvite::parse("saorsa://call?caps=audio").unwrap_err(),
+            InviteError::MissingPeer
+        );
+        assert_eq!(
+            CallInvite::parse("saorsa://call").unwrap_err(),
+            InviteError::MissingPeer
+        );
+        assert!(matches!(
+            CallInvite::parse("saorsa://call?peer=b%4"),
+            Err(InviteError::Malformed(_))
+        ));
+        assert!(matches!(
+            CallInvite::parse("saorsa://call?peer=%FF"),
+            Err(InviteError::Malformed(_))
+        ));
+    }
+}
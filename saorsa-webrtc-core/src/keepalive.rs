@@ -0,0 +1,333 @@
+//! Application-level keepalives and dead-peer detection
+//!
+//! QUIC idle timeouts only notice a vanished peer once the whole connection
+//! times out, and media streams may legitimately be silent (muted audio,
+//! paused video). Calls therefore exchange small keepalive pings on the RTCP
+//! stream and track when the peer was last heard from.
+//!
+//! Keepalives are encoded as RTCP APP packets (RFC 3550 §6.7) named `PING`
+//! and `PONG`, so peers that do not understand them simply ignore them as
+//! unknown application packets.
+//!
+//! After [`KeepaliveConfig::miss_threshold`] intervals without any traffic the
+//! call moves to `Reconnecting`; after
+//! [`KeepaliveConfig::failure_threshold`] intervals it is marked `Failed`.
+
+use crate::clock::{system_clock, SharedClock};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Default interval between keepalive pings (5 seconds)
+pub const DEFAULT_KEEPALIVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Default number of missed intervals before a call is `Reconnecting`
+pub const DEFAULT_MISS_THRESHOLD: u32 = 3;
+
+/// Default number of missed intervals before a call is `Failed`
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 6;
+
+/// RTCP packet type for application-defined packets
+const RTCP_PT_APP: u8 = 204;
+
+/// First header byte: version 2, no padding, subtype 0
+const RTCP_APP_HEADER: u8 = 0x80;
+
+/// Encoded keepalive packet size in bytes
+pub const KEEPALIVE_PACKET_SIZE: usize = 16;
+
+/// Keepalive configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeepaliveConfig {
+    /// Whether keepalives are sent and liveness is enforced
+    pub enabled: bool,
+    /// Interval between keepalive pings
+    pub interval: Duration,
+    /// Missed intervals before the call transitions to `Reconnecting`
+    pub miss_threshold: u32,
+    /// Missed intervals before the call transitions to `Failed`
+    pub failure_threshold: u32,
+}
+
+impl Default for KeepaliveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval: DEFAULT_KEEPALIVE_INTERVAL,
+            miss_threshold: DEFAULT_MISS_THRESHOLD,
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+        }
+    }
+}
+
+/// Keepalive packet kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeepaliveKind {
+    /// Request for the peer to answer with a pong
+    Ping,
+    /// Answer to a ping
+    Pong,
+}
+
+impl KeepaliveKind {
+    fn name(self) -> &'static [u8; 4] {
+        match self {
+            Self::Ping => b"PING",
+            Self::Pong => b"PONG",
+        }
+    }
+}
+
+/// A keepalive ping or pong
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeepalivePacket {
+    /// Packet kind
+    pub kind: KeepaliveKind,
+    /// Sequence number; a pong echoes the sequence of its ping
+    pub seq: u32,
+}
+
+impl KeepalivePacket {
+    /// Encode as an RTCP APP packet
+    #[must_use]
+    pub fn encode(&self) -> [u8; KEEPALIVE_PACKET_SIZE] {
+        let mut out = [0u8; KEEPALIVE_PACKET_SIZE];
+        out[0] = RTCP_APP_HEADER;
+        out[1] = RTCP_PT_APP;
+        // Length in 32-bit words minus one
+        out[2..4].copy_from_slice(&((KEEPALIVE_PACKET_SIZE / 4 - 1) as u16).to_be_bytes());
+        // SSRC is unused for keepalives
+        out[8..12].copy_from_slice(self.kind.name());
+        out[12..16].copy_from_slice(&self.seq.to_be_bytes());
+        out
+    }
+
+    /// Parse an RTCP packet as a keepalive
+    ///
+    /// Returns `None` if the packet is not a keepalive.
+    #[must_use]
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        if data.len() != KEEPALIVE_PACKET_SIZE
+            || data[0] & 0xC0 != RTCP_APP_HEADER
+            || data[1] != RTCP_PT_APP
+        {
+            return None;
+        }
+        let kind = match &data[8..12] {
+            name if name == KeepaliveKind::Ping.name() => KeepaliveKind::Ping,
+            name if name == KeepaliveKind::Pong.name() => KeepaliveKind::Pong,
+            _ => return None,
+        };
+        let seq = u32::from_be_bytes([data[12], data[13], data[14], data[15]]);
+        Some(Self { kind, seq })
+    }
+
+    /// Build the pong answering this ping
+    #[must_use]
+    pub fn pong(&self) -> Self {
+        Self {
+            kind: KeepaliveKind::Pong,
+            seq: self.seq,
+        }
+    }
+}
+
+/// Peer liveness as seen by the keepalive monitor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Liveness {
+    /// Peer was heard from recently
+    Alive,
+    /// Peer missed at least `miss_threshold` intervals
+    Suspect {
+        /// Missed intervals
+        missed: u32,
+    },
+    /// Peer missed at least `failure_threshold` intervals
+    Dead {
+        /// Missed intervals
+        missed: u32,
+    },
+}
+
+/// Tracks when a peer was last heard from
+#[derive(Debug)]
+pub struct KeepaliveMonitor {
+    config: KeepaliveConfig,
+    clock: SharedClock,
+    last_heard: Instant,
+    next_seq: u32,
+}
+
+impl Default for KeepaliveMonitor {
+    fn default() -> Self {
+        Self::new(KeepaliveConfig::default())
+    }
+}
+
+impl KeepaliveMonitor {
+    /// Create a monitor that considers the peer heard from now
+    #[must_use]
+    pub fn new(config: KeepaliveConfig) -> Self {
+        Self::with_clock(config, system_clock())
+    }
+
+    /// Create a monitor that reads time from `clock`
+    #[must_use]
+    pub fn with_clock(config: KeepaliveConfig, clock: SharedClock) -> Self {
+        Self {
+            config,
+            last_heard: clock.now(),
+            clock,
+            next_seq: 0,
+        }
+    }
+
+    /// Get the keepalive configuration
+    #[must_use]
+    pub fn config(&self) -> &KeepaliveConfig {
+        &self.config
+    }
+
+    /// Replace the configuration and restart the miss count
+    pub fn set_config(&mut self, config: KeepaliveConfig) {
+        self.config = config;
+        self.last_heard = self.clock.now();
+    }
+
+    /// Record that something was received from the peer
+    pub fn record_activity(&mut self) {
+        self.last_heard = self.clock.now();
+    }
+
+    /// Build the next ping to send
+    pub fn next_ping(&mut self) -> KeepalivePacket {
+        let seq = self.next_seq;
+        self.next_seq = self.next_seq.wrapping_add(1);
+        KeepalivePacket {
+            kind: KeepaliveKind::Ping,
+            seq,
+        }
+    }
+
+    /// Number of whole intervals since the peer was last heard from
+    #[must_use]
+    pub fn missed(&self) -> u32 {
+        let interval = self.config.interval.as_millis().max(1);
+        let elapsed = self
+            .clock
+            .now()
+            .saturating_duration_since(self.last_heard)
+            .as_millis();
+        u32::try_from(elapsed / interval).unwrap_or(u32::MAX)
+    }
+
+    /// Current liveness of the peer
+    ///
+    /// Always `Alive` when keepalives are disabled.
+    #[must_use]
+    pub fn liveness(&self) -> Liveness {
+        if !self.config.enabled {
+            return Liveness::Alive;
+        }
+        let missed = self.missed();
+        if missed >= self.config.failure_threshold {
+            Liveness::Dead { missed }
+        } else if missed >= self.config.miss_threshold {
+            Liveness::Suspect { missed }
+        } else {
+            Liveness::Alive
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+
+    #[test]
+    fn test_packet_roundtrip() {
+        let ping = KeepalivePacket {
+            kind: KeepaliveKind::Ping,
+            seq: 0xDEAD_BEEF,
+        };
+        let encoded = ping.encode();
+        assert_eq!(encoded[1], RTCP_PT_APP);
+        assert_eq!(KeepalivePacket::parse(&encoded), Some(ping));
+
+        let pong = ping.pong();
+        assert_eq!(pong.kind, KeepaliveKind::Pong);
+        assert_eq!(KeepalivePacket::parse(&pong.encode()), Some(pong));
+    }
+
+    #[test]
+    fn test_parse_rejects_other_rtcp() {
+        // Receiver report header
+        assert_eq!(KeepalivePacket::parse(&[0x80, 201, 0, 1, 0, 0, 0, 1]), None);
+
+        let mut app = KeepalivePacket {
+            kind: KeepaliveKind::Ping,
+            seq: 1,
+        }
+        .encode();
+        app[8..12].copy_from_slice(b"OTHR");
+        assert_eq!(KeepalivePacket::parse(&app), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_liveness_thresholds() {
+        let mut monitor = KeepaliveMonitor::new(KeepaliveConfig {
+            interval: Duration::from_secs(1),
+            miss_threshold: 2,
+            failure_threshold: 4,
+            ..Default::default()
+        });
+        assert_eq!(monitor.liveness(), Liveness::Alive);
+
+        tokio::time::advance(Duration::from_secs(2)).await;
+        assert_eq!(monitor.liveness(), Liveness::Suspect { missed: 2 });
+
+        monitor.record_activity();
+        assert_eq!(monitor.liveness(), Liveness::Alive);
+
+        tokio::time::advance(Duration::from_secs(4)).await;
+        assert_eq!(monitor.liveness(), Liveness::Dead { missed: 4 });
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_disabled_is_always_alive() {
+        let monitor = KeepaliveMonitor::new(KeepaliveConfig {
+            enabled: false,
+            ..Default::default()
+        });
+        tokio::time::advance(Duration::from_secs(3600)).await;
+        assert_eq!(monitor.liveness(), Liveness::Alive);
+    }
+
+    #[test]
+    fn test_liveness_with_mock_clock() {
+        let clock = MockClock::new();
+        let mut monitor = KeepaliveMonitor::with_clock(
+            KeepaliveConfig {
+                interval: Duration::from_secs(1),
+                miss_threshold: 2,
+                failure_threshold: 4,
+                ..Default::default()
+            },
+            clock.shared(),
+        );
+        clock.advance(Duration::from_millis(2_500));
+        assert_eq!(monitor.liveness(), Liveness::Suspect { missed: 2 });
+        monitor.record_activity();
+        clock.advance(Duration::from_secs(4));
+        assert_eq!(monitor.liveness(), Liveness::Dead { missed: 4 });
+    }
+
+    #[test]
+    fn test_ping_sequence_increments() {
+        let mut monitor = KeepaliveMonitor::default();
+        assert_eq!(monitor.next_ping().seq, 0);
+        assert_eq!(monitor.next_ping().seq, 1);
+    }
+}
@@ -54,16 +54,13 @@
 /// Core WebRTC types and data structures
 pub mod types;
 
-/// WebRTC service and configuration (requires legacy-webrtc feature)
-#[cfg(feature = "legacy-webrtc")]
+/// WebRTC service and configuration
 pub mod service;
 
-/// Media stream management (requires legacy-webrtc feature)
-#[cfg(feature = "legacy-webrtc")]
+/// Media stream management
 pub mod media;
 
-/// Call management and state (requires legacy-webrtc feature)
-#[cfg(feature = "legacy-webrtc")]
+/// Call management and state
 pub mod call;
 
 /// Signaling protocol and handlers
@@ -90,42 +87,288 @@ pub mod link_transport;
 /// QUIC-based media transport for RTP/RTCP over QUIC streams
 pub mod quic_media_transport;
 
+/// Declarative call and transport state machines
+pub mod state_machine;
+
+/// Pluggable wire formats for signaling messages
+pub mod wire_format;
+
+/// Compression for large signaling payloads
+pub mod compression;
+
+/// Event broadcast channels with configurable capacities and overflow counters
+pub mod events;
+
+/// Recording and deterministic replay of signaling sessions
+pub mod replay;
+
+/// Interoperability test vectors for the wire protocol
+pub mod conformance;
+
+/// Connection pooling and reuse across calls
+pub mod connection_pool;
+
+/// Application-level keepalives and dead-peer detection
+pub mod keepalive;
+
+/// Auto-answer and voicemail recording
+pub mod voicemail;
+
+/// Audio level metering for VU meters
+pub mod audio_level;
+
+/// Windowed bitrate and packet-rate estimation
+pub mod bitrate;
+
+/// Historical per-call statistics and export
+pub mod stats_history;
+
+/// Opus audio pipeline configured from negotiated parameters
+pub mod audio_pipeline;
+
+/// Polyphase sample-rate conversion
+pub mod resample;
+
+/// Conference audio mixing with per-participant stereo placement
+pub mod mixer;
+
+/// IPv6 and dual-stack address handling with Happy-Eyeballs racing
+pub mod dual_stack;
+
+/// Deep-link call invitations (`saorsa://call?...`)
+pub mod invite;
+
+/// JSON Schemas of public message types
+#[cfg(feature = "schema")]
+pub mod schema;
+
+/// Scheduled calls with reminders and auto-dial
+pub mod scheduler;
+
+/// Read-only taps on sent and received media packets
+pub mod media_tap;
+
+/// Pcapng capture of media packets for debugging
+pub mod pcap;
+
+/// Synthetic tone and test-pattern sources for headless testing
+pub mod synthetic;
+
+/// Content verification for end-to-end media tests
+pub mod verify;
+
+/// Redaction of peer identities and addresses in logs
+pub mod redact;
+
+/// Live resource gauges for leak detection
+pub mod resources;
+
+/// Append-only audit log of call lifecycle events
+pub mod audit;
+
+/// Injectable time sources for deterministic tests
+pub mod clock;
+
+/// Call admission policies
+pub mod policy;
+
+/// Metrics hooks for call lifecycle counters
+pub mod metrics;
+
+/// Dedicated worker threads for CPU-heavy encode and decode
+pub mod media_workers;
+
+/// Battery- and CPU-aware performance governor
+pub mod governor;
+
+/// Adaptive Opus in-band FEC
+pub mod fec;
+
+/// Moving a live call between our own devices
+pub mod handoff;
+
+/// Do-not-disturb and quiet hours
+pub mod dnd;
+
+/// Authenticated encryption of media frames
+pub mod media_crypto;
+
+/// Video packetization with layer marking
+pub mod packetizer;
+
+/// Retransmission of important media packets
+pub mod rtx;
+
+/// Execution model for multi-threaded and single-threaded runtimes
+pub mod runtime;
+
+/// Supervision of background tasks
+pub mod supervisor;
+
+/// Service health and readiness
+pub mod health;
+
+/// Contact list
+pub mod contacts;
+
+/// Timed metadata synchronized with video
+pub mod timed_metadata;
+
+/// Presenter cursor overlay for screen share
+pub mod cursor;
+
+/// Low-bandwidth slides mode for screen share
+pub mod slides;
+
+/// Call notification webhooks
+pub mod webhooks;
+
+/// Bots and auto-responders
+pub mod bot;
+
+/// Conference-wide chat and reactions
+pub mod conference;
+
+/// Per-peer bandwidth and quality learning
+pub mod peer_quality;
+
+/// Startup bitrate ramp and keyframe strategy
+pub mod ramp;
+
+/// Redundant audio (RED) for lossy links
+pub mod red;
+
+/// Pre-encoded media file playback into calls
+pub mod playback;
+
+/// Hold music and comfort noise
+pub mod hold;
+
+/// Talk-burst statistics and silence suppression accounting
+pub mod talk;
+
+/// Network quality monitoring and audio-only fallback
+pub mod quality;
+
+/// Loopback harness running two in-process peers for end-to-end tests
+#[cfg(any(test, feature = "test-utils"))]
+pub mod testing;
+
 // Re-export main types at crate root
-#[cfg(feature = "legacy-webrtc")]
-pub use call::{CallManager, CallManagerConfig};
-pub use identity::{PeerIdentity, PeerIdentityString};
+pub use audio_level::{AudioDirection, AudioLevel, AudioLevelMeter};
+pub use audio_pipeline::{AudioPipeline, DeviceFormat};
+pub use audit::{AuditConfig, AuditError, AuditEvent, AuditLog, AuditRecord};
+pub use bitrate::{RateEstimator, Rates, StreamRates};
+pub use bot::{BotCall, BotConfig, BotDecision, BotError, BotRunner, CallBot};
+pub use call::{
+    CallManager, CallManagerConfig, IncomingCallOutcome, PurgeReport, RemoteTrack,
+    SpeculativeMediaConfig,
+};
+pub use clock::{system_clock, Clock, MockClock, SharedClock, TokioClock};
+pub use compression::{Compression, CompressionConfig};
+pub use conference::{
+    ChatConfig, ChatRouting, ConferenceChat, ConferenceError, ConferenceEvent, MessageId,
+};
+pub use conformance::{ConformanceError, TestVectors};
+pub use connection_pool::{ConnectionPool, ConnectionPoolConfig, PoolError};
+pub use contacts::{Contact, ContactBook, ContactError, ContactPermissions};
+pub use cursor::{
+    ClickButton, ClickHighlight, CursorConfig, CursorFrame, CursorOverlay, CursorSample,
+    CursorSampler,
+};
+pub use dnd::{DndAction, DndConfig, DndReason, DoNotDisturb, MissedCall, QuietHours};
+pub use events::{EventChannelStats, EventSender, EventStats};
+pub use fec::{FecConfig, FecController};
+pub use governor::{
+    CpuSampler, GovernorConfig, GovernorEvent, GovernorReason, PerformanceGovernor,
+    PerformanceLevel, PerformanceSettings, PowerSource, PowerState, ProcessCpu, SharedCpuSampler,
+    SharedPowerSource,
+};
+pub use handoff::{HandoffError, HandoffToken};
+pub use health::{
+    CodecHealth, DeviceHealth, HealthReport, HealthStatus, SignalingHealth, TransportHealth,
+};
+pub use hold::{HoldAudio, HoldAudioConfig};
+pub use identity::{
+    FileKeyStorage, FourWordIdentity, IdentityError, IdentityKeypair, KeyFingerprint, KeyStorage,
+    KeyStore, KeyStoreError, MemoryKeyStorage, PeerIdentity, PeerIdentityString,
+};
+pub use invite::{CallInvite, InviteError};
+pub use keepalive::{KeepaliveConfig, KeepaliveMonitor, KeepalivePacket, Liveness};
 pub use link_transport::{
     LinkTransport, LinkTransportError, PeerConnection, StreamType as LinkStreamType,
 };
-#[cfg(feature = "legacy-webrtc")]
 pub use media::{
     AudioDevice, AudioTrack, MediaEvent, MediaStream, MediaStreamManager, VideoDevice, VideoTrack,
 };
+pub use media_crypto::{FrameOpener, FrameSealer, MediaCryptoError, MediaKey};
+pub use media_tap::{MediaTaps, TrackReceivers};
+pub use media_workers::{MediaWorkerConfig, MediaWorkerError, MediaWorkers};
+pub use metrics::{noop_metrics, MetricsRecorder, NoopMetrics, SharedMetrics};
+pub use mixer::{ConferenceMixer, MixerError};
+pub use packetizer::{LayerDescriptor, PacketizerError, VideoPacketizer};
+pub use pcap::{PacketDirection, PcapWriter};
+pub use peer_quality::{
+    PeerQuality, PeerQualityCache, PeerQualityConfig, PeerQualityError, QualitySeed,
+};
+pub use playback::{FileFrame, MediaFile, MediaFileFormat, PlaybackError};
+pub use policy::{CallPolicy, ContactPolicy, DataRequest, SharedCallPolicy};
 pub use protocol_handler::{
-    WebRtcHandlerConfig, WebRtcHandlerError, WebRtcIncoming, WebRtcProtocolHandler,
-    WebRtcProtocolHandlerBuilder,
+    AuthDecision, ConnectionAuthorizer, SubProtocolHandler, WebRtcHandlerConfig,
+    WebRtcHandlerError, WebRtcIncoming, WebRtcProtocolHandler, WebRtcProtocolHandlerBuilder,
 };
+pub use quality::{DegradationConfig, DegradationReason, QualityMonitor, QualityTransition};
 pub use quic_bridge::{RtpPacket, StreamConfig, StreamType, WebRtcQuicBridge};
 pub use quic_media_transport::{
-    MediaTransportError, MediaTransportState, QuicMediaTransport, StreamHandle, StreamPriority,
-    TransportStats,
+    MediaGate, MediaTransportError, MediaTransportState, QuicMediaTransport, StreamHandle,
+    StreamKey, StreamPriority, TrackId, TransportStats, PRIMARY_TRACK,
+};
+pub use ramp::{BitrateRamp, RampConfig};
+pub use red::{RedDecoder, RedEncoder, RedError, RedFrame};
+pub use redact::{Redaction, RedactionConfig};
+pub use replay::{ReplayError, ReplayReport, Replayer, SignalingRecorder, SignalingRecording};
+pub use resample::Resampler;
+pub use resources::{ResourceCounts, ResourceGauges};
+pub use rtx::{RtxConfig, RtxError, RtxMapping, RtxReceiver, RtxSender, RtxStats};
+pub use runtime::ExecutionModel;
+pub use scheduler::{
+    CallScheduler, ScheduleEvent, ScheduleId, ScheduledCall, SchedulerConfig, SchedulerError,
 };
 pub use service::{WebRtcConfig, WebRtcEvent, WebRtcService, WebRtcServiceBuilder};
 pub use signaling::{
-    SignalingHandler, SignalingMessage as SignalingMessageType, SignalingTransport,
+    InterceptorDecision, SignalingHandler, SignalingInterceptor,
+    SignalingMessage as SignalingMessageType, SignalingTransport,
+};
+pub use slides::{
+    SlidesConfig, SlidesDecision, SlidesDetector, SlidesError, Still, StillAssembler,
 };
-pub use transport::{AntQuicTransport, TransportConfig};
+pub use state_machine::{InvalidTransition, StateMachine};
+pub use stats_history::{
+    HistoryStore, SharedHistoryStore, StatsDelta, StatsHistory, StatsHistoryConfig,
+    StatsHistoryError, StatsSample,
+};
+pub use supervisor::{ServiceHealth, SupervisorConfig, TaskHealth, TaskStatus, TaskSupervisor};
+pub use synthetic::{TestPatternSource, ToneSource};
+pub use talk::{TalkConfig, TalkStats, TalkTracker};
+pub use timed_metadata::{MetadataBuffer, MetadataError, MetadataKind, TimedMetadata};
+pub use transport::{AntQuicTransport, PortRange, TransportConfig};
 pub use types::*;
+pub use verify::{decode_frame_counter, FlowReport, FrameTracker, ToneDetector};
+pub use voicemail::{AutoAnswer, AutoAnswerConfig, VoicemailError, VoicemailRecorder};
+#[cfg(feature = "webhooks")]
+pub use webhooks::HttpWebhookSender;
+pub use webhooks::{
+    CallActivity, MissedReason, SharedWebhookSender, WebhookConfig, WebhookError, WebhookEvent,
+    WebhookNotifier, WebhookPayload, WebhookSender,
+};
+pub use wire_format::{FrameCodec, ProtocolHello, WireFormat, WireFormatError};
 
 /// Prelude module for convenient imports
 pub mod prelude {
-    #[cfg(feature = "legacy-webrtc")]
     pub use crate::call::{CallManager, CallManagerConfig};
-    pub use crate::identity::{PeerIdentity, PeerIdentityString};
-    #[cfg(feature = "legacy-webrtc")]
+    pub use crate::identity::{FourWordIdentity, PeerIdentity, PeerIdentityString};
     pub use crate::media::{MediaEvent, MediaStreamManager};
     pub use crate::protocol_handler::{WebRtcHandlerConfig, WebRtcIncoming, WebRtcProtocolHandler};
-    #[cfg(feature = "legacy-webrtc")]
     pub use crate::service::{WebRtcConfig, WebRtcEvent, WebRtcService, WebRtcServiceBuilder};
     pub use crate::signaling::{SignalingHandler, SignalingMessage, SignalingTransport};
     pub use crate::transport::{AntQuicTransport, TransportConfig};
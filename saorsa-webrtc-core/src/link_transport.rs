@@ -7,48 +7,7 @@ use async_trait::async_trait;
 use std::net::SocketAddr;
 use thiserror::Error;
 
-/// Stream type identifiers for WebRTC media multiplexing
-///
-/// Allocates stream IDs in the 0x20-0x2F range to enable multiple
-/// concurrent media streams over a single QUIC connection.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-#[repr(u8)]
-pub enum StreamType {
-    /// Audio RTP stream (0x20)
-    Audio = 0x20,
-    /// Video RTP stream (0x21)
-    Video = 0x21,
-    /// Screen share RTP stream (0x22)
-    Screen = 0x22,
-    /// RTCP feedback stream (0x23)
-    RtcpFeedback = 0x23,
-    /// Data channel (0x24)
-    Data = 0x24,
-}
-
-impl StreamType {
-    /// Get stream type as byte value
-    #[must_use]
-    pub fn as_u8(self) -> u8 {
-        self as u8
-    }
-
-    /// Try to convert byte value to StreamType
-    ///
-    /// # Errors
-    ///
-    /// Returns None if byte is not a valid StreamType
-    pub fn try_from_u8(val: u8) -> Option<Self> {
-        match val {
-            0x20 => Some(StreamType::Audio),
-            0x21 => Some(StreamType::Video),
-            0x22 => Some(StreamType::Screen),
-            0x23 => Some(StreamType::RtcpFeedback),
-            0x24 => Some(StreamType::Data),
-            _ => None,
-        }
-    }
-}
+pub use saorsa_webrtc_proto::stream::StreamType;
 
 /// Link transport errors
 #[derive(Error, Debug, Clone)]
@@ -143,6 +102,20 @@ pub trait LinkTransport: Send + Sync {
     /// Returns error on accept failure
     async fn accept(&mut self) -> Result<Option<PeerConnection>, LinkTransportError>;
 
+    /// Release a connection once the call or link using it ends
+    ///
+    /// Pooling transports give back the lease [`connect`](Self::connect)
+    /// took, so the connection can be evicted once idle. Defaults to doing
+    /// nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the connection cannot be released
+    async fn disconnect(&mut self, peer: &PeerConnection) -> Result<(), LinkTransportError> {
+        let _ = peer;
+        Ok(())
+    }
+
     /// Send data to a specific peer on the specified stream
     ///
     /// # Errors
@@ -14,18 +14,30 @@
 //! **Note:** The legacy-webrtc feature is deprecated and will be removed.
 //! New code should use `QuicTrackBackend` for all media transport.
 
+use crate::audio_level::{AudioDirection, AudioLevel, AudioLevelMeter};
+use crate::events::{EventChannelStats, EventSender, DEFAULT_MEDIA_EVENT_CAPACITY};
 use crate::link_transport::StreamType;
-use crate::quic_media_transport::QuicMediaTransport;
-use crate::types::MediaType;
+use crate::media_workers::{MediaWorkerConfig, MediaWorkers};
+use crate::quic_media_transport::{
+    MediaTransportState, QuicMediaTransport, StreamKey, TrackId, PRIMARY_TRACK,
+};
+use crate::types::{CallId, LayerStructure, MediaType, VideoLayer};
 use async_trait::async_trait;
+use parking_lot::RwLock;
 use saorsa_webrtc_codecs::{
-    OpenH264Decoder, OpenH264Encoder, VideoCodec, VideoDecoder, VideoEncoder, VideoFrame,
+    mime, CodecPool, CodecRegistry, OpenH264Decoder, OpenH264Encoder, PoolConfig, TemporalLayers,
+    VideoDecoder, VideoEncoder, VideoFrame,
 };
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
 use thiserror::Error;
-use tokio::sync::{broadcast, RwLock};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+#[cfg(feature = "legacy-webrtc")]
 use webrtc::rtp_transceiver::rtp_codec::RTCRtpCodecCapability;
+#[cfg(feature = "legacy-webrtc")]
 use webrtc::track::track_local::track_local_static_sample::TrackLocalStaticSample;
 
 /// Media-related errors
@@ -175,6 +187,8 @@ pub struct QuicTrackBackend {
     transport: Arc<QuicMediaTransport>,
     /// The stream type for this track
     stream_type: StreamType,
+    /// Track within the stream type
+    track_id: TrackId,
     /// Track statistics (protected by RwLock for interior mutability)
     stats: Arc<RwLock<TrackStats>>,
 }
@@ -195,6 +209,7 @@ impl QuicTrackBackend {
         Self {
             transport,
             stream_type: Self::media_type_to_stream_type(media_type),
+            track_id: PRIMARY_TRACK,
             stats: Arc::new(RwLock::new(TrackStats::default())),
         }
     }
@@ -209,9 +224,19 @@ impl QuicTrackBackend {
     /// * `stream_type` - The specific stream type to use
     #[must_use]
     pub fn with_stream_type(transport: Arc<QuicMediaTransport>, stream_type: StreamType) -> Self {
+        Self::with_track(transport, StreamKey::primary(stream_type))
+    }
+
+    /// Create a QUIC track backend for one track of a stream type
+    ///
+    /// Use distinct track IDs for several tracks of the same type, such as
+    /// two cameras.
+    #[must_use]
+    pub fn with_track(transport: Arc<QuicMediaTransport>, key: StreamKey) -> Self {
         Self {
             transport,
-            stream_type,
+            stream_type: key.stream_type,
+            track_id: key.track_id,
             stats: Arc::new(RwLock::new(TrackStats::default())),
         }
     }
@@ -227,12 +252,7 @@ impl QuicTrackBackend {
     /// The corresponding stream type
     #[must_use]
     pub fn media_type_to_stream_type(media_type: MediaType) -> StreamType {
-        match media_type {
-            MediaType::Audio => StreamType::Audio,
-            MediaType::Video => StreamType::Video,
-            MediaType::ScreenShare => StreamType::Screen,
-            MediaType::DataChannel => StreamType::Data,
-        }
+        StreamType::for_media(&media_type)
     }
 
     /// Get the stream type for this backend
@@ -241,6 +261,18 @@ impl QuicTrackBackend {
         self.stream_type
     }
 
+    /// Get the track within the stream type
+    #[must_use]
+    pub fn track_id(&self) -> TrackId {
+        self.track_id
+    }
+
+    /// Get the key of the stream this track sends on
+    #[must_use]
+    pub fn stream_key(&self) -> StreamKey {
+        StreamKey::new(self.stream_type, self.track_id)
+    }
+
     /// Get a reference to the underlying transport
     #[must_use]
     pub fn transport(&self) -> &Arc<QuicMediaTransport> {
@@ -266,12 +298,13 @@ impl QuicTrackBackend {
 
         // Open the appropriate stream for this track type
         self.transport
-            .open_stream(self.stream_type)
+            .open_track(self.stream_key())
             .await
             .map_err(|e| MediaError::StreamError(format!("Failed to open stream: {}", e)))?;
 
         tracing::debug!(
             stream_type = ?self.stream_type,
+            track_id = self.track_id,
             "Stream opened for track backend"
         );
         Ok(())
@@ -286,11 +319,12 @@ impl QuicTrackBackend {
     ///
     /// `true` if the stream was closed, `false` if it wasn't open.
     pub async fn close_stream(&self) -> bool {
-        let closed = self.transport.close_stream(self.stream_type).await;
+        let closed = self.transport.close_track(self.stream_key()).await;
 
         if closed {
             tracing::debug!(
                 stream_type = ?self.stream_type,
+                track_id = self.track_id,
                 "Stream closed for track backend"
             );
         }
@@ -299,30 +333,25 @@ impl QuicTrackBackend {
 
     /// Check if the stream is currently open
     ///
-    /// Uses `ensure_stream_open` to check if the stream exists.
-    ///
     /// # Returns
     ///
-    /// `true` if the stream is open and ready, `false` otherwise.
+    /// `true` if this track's stream is open and ready, `false` otherwise.
     pub async fn is_stream_open(&self) -> bool {
-        // Check by looking at the open stream types
-        let open_types = self.transport.open_stream_types().await;
-        open_types.contains(&self.stream_type)
+        self.transport.is_track_open(self.stream_key()).await
     }
 }
 
 #[async_trait]
 impl TrackBackend for QuicTrackBackend {
     async fn send(&self, data: &[u8]) -> Result<(), MediaError> {
-        // Use the transport's send_rtp method
         self.transport
-            .send_rtp(self.stream_type, data)
+            .send_track_rtp(self.stream_key(), data)
             .await
             .map_err(|e| MediaError::SendFailed(e.to_string()))?;
 
         // Update statistics
         {
-            let mut stats = self.stats.write().await;
+            let mut stats = self.stats.write();
             stats.bytes_sent += data.len() as u64;
             stats.packets_sent += 1;
         }
@@ -346,9 +375,7 @@ impl TrackBackend for QuicTrackBackend {
     }
 
     fn is_connected(&self) -> bool {
-        // Check if the transport is connected synchronously
-        // We use try_read to avoid blocking
-        futures::executor::block_on(async { self.transport.is_connected().await })
+        self.transport.current_state() == MediaTransportState::Connected
     }
 
     fn backend_type(&self) -> &'static str {
@@ -356,9 +383,7 @@ impl TrackBackend for QuicTrackBackend {
     }
 
     fn stats(&self) -> TrackStats {
-        // Use try_read to get stats without blocking
-        // If we can't acquire the lock, return default stats
-        futures::executor::block_on(async { self.stats.read().await.clone() })
+        self.stats.read().clone()
     }
 }
 
@@ -386,11 +411,11 @@ const _: () = {
 ///
 /// - **Receive not supported**: WebRTC tracks in this mode are send-only.
 ///   Calling `recv()` will return `MediaError::ReceiveNotSupported`.
-/// - **Blocking on async**: Some operations use blocking synchronization.
 #[deprecated(
     since = "0.3.0",
     note = "Use QuicTrackBackend for new code. Legacy WebRTC will be removed."
 )]
+#[cfg(feature = "legacy-webrtc")]
 pub struct LegacyWebRtcBackend {
     /// The underlying WebRTC track
     track: Arc<TrackLocalStaticSample>,
@@ -402,6 +427,7 @@ pub struct LegacyWebRtcBackend {
     connected: bool,
 }
 
+#[cfg(feature = "legacy-webrtc")]
 #[allow(deprecated)]
 impl LegacyWebRtcBackend {
     /// Create a new legacy WebRTC backend
@@ -437,6 +463,7 @@ impl LegacyWebRtcBackend {
     }
 }
 
+#[cfg(feature = "legacy-webrtc")]
 #[allow(deprecated)]
 #[async_trait]
 impl TrackBackend for LegacyWebRtcBackend {
@@ -462,7 +489,7 @@ impl TrackBackend for LegacyWebRtcBackend {
 
         // Update statistics
         {
-            let mut stats = self.stats.write().await;
+            let mut stats = self.stats.write();
             stats.bytes_sent += data.len() as u64;
             stats.packets_sent += 1;
         }
@@ -485,11 +512,12 @@ impl TrackBackend for LegacyWebRtcBackend {
     }
 
     fn stats(&self) -> TrackStats {
-        futures::executor::block_on(async { self.stats.read().await.clone() })
+        self.stats.read().clone()
     }
 }
 
 // Ensure LegacyWebRtcBackend is Send + Sync at compile time
+#[cfg(feature = "legacy-webrtc")]
 #[allow(deprecated)]
 const _: () = {
     const fn assert_send_sync<T: Send + Sync>() {}
@@ -519,6 +547,15 @@ pub enum MediaEvent {
         /// Stream identifier
         stream_id: String,
     },
+    /// Periodic audio level of a track, for VU meters
+    AudioLevel {
+        /// Track identifier
+        track_id: String,
+        /// Captured or played-back audio
+        direction: AudioDirection,
+        /// Level over the metering window
+        level: AudioLevel,
+    },
 }
 
 /// Audio device
@@ -559,6 +596,10 @@ pub struct AudioTrack {
     pub id: String,
     /// Transport backend (QUIC or legacy WebRTC)
     backend: Arc<dyn TrackBackend>,
+    /// Level of captured audio
+    capture_level: Arc<parking_lot::Mutex<AudioLevelMeter>>,
+    /// Level of played-back audio
+    playback_level: Arc<parking_lot::Mutex<AudioLevelMeter>>,
 }
 
 impl AudioTrack {
@@ -570,7 +611,12 @@ impl AudioTrack {
     /// * `backend` - The transport backend to use
     #[must_use]
     pub fn new_with_backend(id: String, backend: Arc<dyn TrackBackend>) -> Self {
-        Self { id, backend }
+        Self {
+            id,
+            backend,
+            capture_level: Arc::default(),
+            playback_level: Arc::default(),
+        }
     }
 
     /// Create a new audio track with QUIC backend
@@ -590,6 +636,7 @@ impl AudioTrack {
     /// Create a new audio track with legacy WebRTC backend
     ///
     /// **Deprecated**: Use `with_quic` for new code.
+    #[cfg(feature = "legacy-webrtc")]
     #[deprecated(since = "0.3.0", note = "Use with_quic for new code")]
     #[allow(deprecated)]
     #[must_use]
@@ -637,6 +684,71 @@ impl AudioTrack {
     pub async fn recv_audio(&self) -> Result<Vec<u8>, MediaError> {
         self.backend.recv().await
     }
+
+    /// Meter captured PCM before it is encoded
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - Interleaved 16-bit PCM samples
+    pub fn meter_capture(&self, samples: &[i16]) {
+        self.capture_level.lock().process(samples);
+    }
+
+    /// Meter decoded PCM before it is played back
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - Interleaved 16-bit PCM samples
+    pub fn meter_playback(&self, samples: &[i16]) {
+        self.playback_level.lock().process(samples);
+    }
+
+    /// Get the current audio level in one direction
+    #[must_use]
+    pub fn audio_level(&self, direction: AudioDirection) -> AudioLevel {
+        self.meter(direction).lock().level()
+    }
+
+    fn meter(&self, direction: AudioDirection) -> &Arc<parking_lot::Mutex<AudioLevelMeter>> {
+        match direction {
+            AudioDirection::Capture => &self.capture_level,
+            AudioDirection::Playback => &self.playback_level,
+        }
+    }
+
+    /// Periodically publish this track's audio levels as `MediaEvent::AudioLevel`
+    ///
+    /// The task ends once the track is dropped.
+    pub fn report_levels(
+        &self,
+        events: EventSender<MediaEvent>,
+        interval: Duration,
+    ) -> JoinHandle<()> {
+        let track_id = self.id.clone();
+        let meters: Vec<(AudioDirection, Weak<parking_lot::Mutex<AudioLevelMeter>>)> =
+            [AudioDirection::Capture, AudioDirection::Playback]
+                .into_iter()
+                .map(|direction| (direction, Arc::downgrade(self.meter(direction))))
+                .collect();
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                for (direction, meter) in &meters {
+                    let Some(meter) = meter.upgrade() else {
+                        return;
+                    };
+                    let level = meter.lock().level();
+                    let _ = events.send(MediaEvent::AudioLevel {
+                        track_id: track_id.clone(),
+                        direction: *direction,
+                        level,
+                    });
+                }
+            }
+        })
+    }
 }
 
 /// Video track with backend abstraction
@@ -668,6 +780,12 @@ pub struct VideoTrack {
     pub width: u32,
     /// Track height
     pub height: u32,
+    /// How the encoder's simulcast or SVC layers relate
+    layer_structure: LayerStructure,
+    /// Highest layer each subscriber chose to receive
+    layer_selections: HashMap<String, VideoLayer>,
+    /// Highest temporal layer sent to anyone, set by congestion control
+    max_temporal_layer: Option<u8>,
 }
 
 impl VideoTrack {
@@ -693,6 +811,9 @@ impl VideoTrack {
             decoder: None,
             width,
             height,
+            layer_structure: LayerStructure::default(),
+            layer_selections: HashMap::new(),
+            max_temporal_layer: None,
         }
     }
 
@@ -720,6 +841,7 @@ impl VideoTrack {
     /// Create a new video track with legacy WebRTC backend
     ///
     /// **Deprecated**: Use `with_quic` for new code.
+    #[cfg(feature = "legacy-webrtc")]
     #[deprecated(since = "0.3.0", note = "Use with_quic for new code")]
     #[allow(deprecated)]
     #[must_use]
@@ -736,6 +858,7 @@ impl VideoTrack {
     /// Create a new video track (legacy compatibility)
     ///
     /// **Deprecated**: Use `with_quic` or `new_with_backend` instead.
+    #[cfg(feature = "legacy-webrtc")]
     #[deprecated(since = "0.3.0", note = "Use with_quic or new_with_backend instead")]
     #[allow(deprecated)]
     pub fn new(
@@ -785,8 +908,126 @@ impl VideoTrack {
         self.backend.recv().await
     }
 
+    /// Set how the layers passed to [`Self::send_layer`] relate
+    #[must_use]
+    pub fn with_layer_structure(mut self, structure: LayerStructure) -> Self {
+        self.layer_structure = structure;
+        self
+    }
+
+    /// How the track's layers relate
+    #[must_use]
+    pub fn layer_structure(&self) -> LayerStructure {
+        self.layer_structure
+    }
+
+    /// Apply a subscriber's choice of the highest layer it receives
+    ///
+    /// Usually the remote peer of a call, from
+    /// [`CallEvent::LayerSelected`](crate::types::CallEvent::LayerSelected).
+    pub fn select_layer(&mut self, subscriber: impl Into<String>, layer: VideoLayer) {
+        self.layer_selections.insert(subscriber.into(), layer);
+    }
+
+    /// Forget a subscriber's layer choice, e.g. when it leaves
+    pub fn clear_layer_selection(&mut self, subscriber: &str) -> Option<VideoLayer> {
+        self.layer_selections.remove(subscriber)
+    }
+
+    /// Highest layer a subscriber chose to receive
+    #[must_use]
+    pub fn selected_layer(&self, subscriber: &str) -> Option<VideoLayer> {
+        self.layer_selections.get(subscriber).copied()
+    }
+
+    /// Subscribers that receive packets of a layer
+    #[must_use]
+    pub fn subscribers_for(&self, layer: VideoLayer) -> Vec<&str> {
+        self.layer_selections
+            .iter()
+            .filter(|(_, selected)| self.layer_structure.forwards(**selected, layer))
+            .map(|(subscriber, _)| subscriber.as_str())
+            .collect()
+    }
+
+    /// Cap the temporal layers sent to every subscriber
+    ///
+    /// For congestion control: with the encoder producing
+    /// [`TemporalLayers::L3`], a cap of 1 halves the frame rate and 0
+    /// quarters it, without a keyframe. `None` lifts the cap.
+    pub fn set_max_temporal_layer(&mut self, layer: Option<u8>) {
+        self.max_temporal_layer = layer;
+    }
+
+    /// Cap on the temporal layers sent, if any
+    #[must_use]
+    pub fn max_temporal_layer(&self) -> Option<u8> {
+        self.max_temporal_layer
+    }
+
+    /// Have the encoder produce temporal layers
+    ///
+    /// Frames sent with [`Self::encode_and_send`] are then sent as their
+    /// temporal layer, so subscribers and the congestion controller can
+    /// drop to a lower frame rate.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the track has no encoder or it does not support
+    /// temporal layers
+    pub fn set_temporal_layers(&mut self, layers: TemporalLayers) -> anyhow::Result<()> {
+        let encoder = self
+            .encoder
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("Track has no encoder"))?;
+        Ok(encoder.set_temporal_layers(layers)?)
+    }
+
+    /// Check if any subscriber receives packets of a layer
+    ///
+    /// Every layer is wanted until some subscriber makes a choice.
+    #[must_use]
+    pub fn is_layer_wanted(&self, layer: VideoLayer) -> bool {
+        if self
+            .max_temporal_layer
+            .is_some_and(|max| layer.temporal > max)
+        {
+            return false;
+        }
+        self.layer_selections.is_empty()
+            || self
+                .layer_selections
+                .values()
+                .any(|selected| self.layer_structure.forwards(*selected, layer))
+    }
+
+    /// Send an encoded frame of one layer, if any subscriber receives it
+    ///
+    /// # Returns
+    ///
+    /// Whether the frame was sent; layers no subscriber wants are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if backend is not connected or send fails.
+    pub async fn send_layer(
+        &self,
+        layer: VideoLayer,
+        frame_data: &[u8],
+    ) -> Result<bool, MediaError> {
+        if !self.is_layer_wanted(layer) {
+            return Ok(false);
+        }
+        self.backend.send(frame_data).await?;
+        Ok(true)
+    }
+
     /// Encode a frame and send it
     ///
+    /// The frame is sent as the temporal layer the encoder put it in, so it
+    /// is dropped if no subscriber receives that layer (see
+    /// [`Self::send_layer`]).
+    ///
     /// # Errors
     ///
     /// Returns error if encoding fails or backend send fails.
@@ -794,7 +1035,13 @@ impl VideoTrack {
         let encoded = self
             .encode_frame(raw_frame)
             .map_err(|e| MediaError::ConfigError(format!("Encoding failed: {}", e)))?;
-        self.backend.send(&encoded).await
+        let temporal = self
+            .encoder
+            .as_ref()
+            .map_or(0, |encoder| encoder.temporal_layer());
+        self.send_layer(VideoLayer::new(0, temporal), &encoded)
+            .await
+            .map(|_| ())
     }
 
     /// Add H.264 encoder to this track
@@ -814,6 +1061,26 @@ impl VideoTrack {
         Ok(self)
     }
 
+    /// Add the best encoder and decoder registered for a MIME type to this track
+    pub fn with_codec(mut self, codecs: &CodecRegistry, mime_type: &str) -> anyhow::Result<Self> {
+        self.encoder = Some(codecs.video_encoder(mime_type, self.width, self.height)?);
+        self.decoder = Some(codecs.video_decoder(mime_type)?);
+        Ok(self)
+    }
+
+    /// Add an encoder and decoder for a MIME type taken from a pool
+    ///
+    /// They go back to the pool when the track is dropped.
+    pub fn with_pooled_codec(mut self, pool: &CodecPool, mime_type: &str) -> anyhow::Result<Self> {
+        self.encoder = Some(Box::new(pool.video_encoder(
+            mime_type,
+            self.width,
+            self.height,
+        )?));
+        self.decoder = Some(Box::new(pool.video_decoder(mime_type)?));
+        Ok(self)
+    }
+
     /// Encode a video frame
     pub fn encode_frame(&mut self, frame_data: &[u8]) -> anyhow::Result<Vec<u8>> {
         if let Some(encoder) = &mut self.encoder {
@@ -841,6 +1108,69 @@ impl VideoTrack {
             Ok(encoded_data.to_vec())
         }
     }
+
+    /// Encode a video frame on a media worker thread
+    ///
+    /// `timestamp` is the frame's capture time in milliseconds, passed on to
+    /// the encoder. The encoder is lent to the worker for the duration of the
+    /// job. If the job panics the encoder is lost and later frames go out
+    /// unencoded.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if encoding fails or the job cannot run
+    pub async fn encode_frame_offloaded(
+        &mut self,
+        workers: &MediaWorkers,
+        call_id: CallId,
+        frame_data: Vec<u8>,
+        timestamp: u64,
+    ) -> anyhow::Result<Vec<u8>> {
+        let Some(mut encoder) = self.encoder.take() else {
+            return Ok(frame_data);
+        };
+        let frame = VideoFrame {
+            data: frame_data,
+            width: self.width,
+            height: self.height,
+            timestamp,
+        };
+        let (encoder, encoded) = workers
+            .run(call_id, move || {
+                let encoded = encoder.encode(&frame);
+                (encoder, encoded)
+            })
+            .await?;
+        self.encoder = Some(encoder);
+        Ok(encoded?.to_vec())
+    }
+
+    /// Decode a video frame on a media worker thread
+    ///
+    /// The decoder is lent to the worker for the duration of the job. If the
+    /// job panics the decoder is lost and later frames pass through as is.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if decoding fails or the job cannot run
+    pub async fn decode_frame_offloaded(
+        &mut self,
+        workers: &MediaWorkers,
+        call_id: CallId,
+        encoded_data: Vec<u8>,
+    ) -> anyhow::Result<Vec<u8>> {
+        let Some(mut decoder) = self.decoder.take() else {
+            return Ok(encoded_data);
+        };
+        let (decoder, decoded) = workers
+            .run(call_id, move || {
+                let decoded = decoder.decode(&encoded_data);
+                (decoder, decoded)
+            })
+            .await?;
+        self.decoder = Some(decoder);
+        Ok(decoded?.data)
+    }
 }
 
 // ============================================================================
@@ -1040,6 +1370,7 @@ impl GenericTrack {
 }
 
 /// WebRTC media track wrapper
+#[cfg(feature = "legacy-webrtc")]
 #[derive(Debug, Clone)]
 pub struct WebRtcTrack {
     /// Local WebRTC track
@@ -1072,30 +1403,43 @@ pub struct MediaStream {
 /// let video = manager.create_quic_video_track(1280, 720).await?;
 /// ```
 pub struct MediaStreamManager {
-    event_sender: broadcast::Sender<MediaEvent>,
+    event_sender: EventSender<MediaEvent>,
     #[allow(dead_code)]
     audio_devices: Vec<AudioDevice>,
     #[allow(dead_code)]
     video_devices: Vec<VideoDevice>,
+    #[cfg(feature = "legacy-webrtc")]
     webrtc_tracks: Vec<WebRtcTrack>,
     /// QUIC transport for creating QUIC-backed tracks
     quic_transport: Option<Arc<QuicMediaTransport>>,
     /// Generic tracks (QUIC-backed)
     tracks: Vec<GenericTrack>,
+    /// Next QUIC track ID per stream type
+    next_track_ids: HashMap<StreamType, TrackId>,
+    /// Warm codec instances for new tracks, over the codec registry
+    codec_pool: Arc<CodecPool>,
+    /// Threads running encode and decode jobs
+    workers: Arc<MediaWorkers>,
 }
 
 impl MediaStreamManager {
     /// Create new media stream manager
     #[must_use]
     pub fn new() -> Self {
-        let (event_sender, _) = broadcast::channel(100);
         Self {
-            event_sender,
+            event_sender: EventSender::new(DEFAULT_MEDIA_EVENT_CAPACITY),
             audio_devices: Vec::new(),
             video_devices: Vec::new(),
+            #[cfg(feature = "legacy-webrtc")]
             webrtc_tracks: Vec::new(),
             quic_transport: None,
             tracks: Vec::new(),
+            next_track_ids: HashMap::new(),
+            codec_pool: Arc::new(CodecPool::new(
+                Arc::new(CodecRegistry::with_defaults()),
+                PoolConfig::default(),
+            )),
+            workers: Arc::new(MediaWorkers::default()),
         }
     }
 
@@ -1104,14 +1448,20 @@ impl MediaStreamManager {
     /// This is the preferred constructor for new code.
     #[must_use]
     pub fn with_quic_transport(transport: Arc<QuicMediaTransport>) -> Self {
-        let (event_sender, _) = broadcast::channel(100);
         Self {
-            event_sender,
+            event_sender: EventSender::new(DEFAULT_MEDIA_EVENT_CAPACITY),
             audio_devices: Vec::new(),
             video_devices: Vec::new(),
+            #[cfg(feature = "legacy-webrtc")]
             webrtc_tracks: Vec::new(),
             quic_transport: Some(transport),
             tracks: Vec::new(),
+            next_track_ids: HashMap::new(),
+            codec_pool: Arc::new(CodecPool::new(
+                Arc::new(CodecRegistry::with_defaults()),
+                PoolConfig::default(),
+            )),
+            workers: Arc::new(MediaWorkers::default()),
         }
     }
 
@@ -1128,6 +1478,80 @@ impl MediaStreamManager {
         self.quic_transport.is_some()
     }
 
+    /// Replace the codec implementations used for new tracks
+    ///
+    /// Idle instances pooled from the previous registry are dropped.
+    pub fn set_codec_registry(&mut self, codecs: Arc<CodecRegistry>) {
+        let config = self.codec_pool.config().clone();
+        self.codec_pool = Arc::new(CodecPool::new(codecs, config));
+    }
+
+    /// Replace the pool sizing, dropping any idle instances
+    pub fn set_codec_pool_config(&mut self, config: PoolConfig) {
+        let codecs = Arc::clone(self.codec_pool.registry());
+        self.codec_pool = Arc::new(CodecPool::new(codecs, config));
+    }
+
+    /// Get the pool of warm codec instances
+    #[must_use]
+    pub fn codec_pool(&self) -> &Arc<CodecPool> {
+        &self.codec_pool
+    }
+
+    /// Replace the media worker configuration
+    ///
+    /// Jobs already running finish on the previous workers.
+    pub fn set_media_worker_config(&mut self, config: MediaWorkerConfig) {
+        self.workers = Arc::new(MediaWorkers::new(config));
+    }
+
+    /// Get the threads running encode and decode jobs
+    #[must_use]
+    pub fn media_workers(&self) -> &Arc<MediaWorkers> {
+        &self.workers
+    }
+
+    /// Get the codec implementations used for new tracks
+    #[must_use]
+    pub fn codec_registry(&self) -> &Arc<CodecRegistry> {
+        self.codec_pool.registry()
+    }
+
+    /// Total number of tracks, used to allocate track IDs
+    fn track_count(&self) -> usize {
+        #[cfg(feature = "legacy-webrtc")]
+        let legacy = self.webrtc_tracks.len();
+        #[cfg(not(feature = "legacy-webrtc"))]
+        let legacy = 0;
+        self.tracks.len() + legacy
+    }
+
+    /// Create a backend on the next free track of a stream type
+    ///
+    /// The first track of each type is the primary track; further tracks of
+    /// the same type, such as a second camera, get their own streams.
+    fn next_quic_backend(
+        &mut self,
+        stream_type: StreamType,
+    ) -> Result<Arc<QuicTrackBackend>, MediaError> {
+        let transport = self
+            .quic_transport
+            .as_ref()
+            .ok_or_else(|| MediaError::ConfigError("QUIC transport not configured".to_string()))?;
+        let next = self
+            .next_track_ids
+            .entry(stream_type)
+            .or_insert(PRIMARY_TRACK);
+        let key = StreamKey::new(stream_type, *next);
+        *next = next
+            .checked_add(1)
+            .ok_or_else(|| MediaError::ConfigError(format!("Too many {stream_type:?} tracks")))?;
+        Ok(Arc::new(QuicTrackBackend::with_track(
+            Arc::clone(transport),
+            key,
+        )))
+    }
+
     /// Get all generic tracks (QUIC-backed)
     #[must_use]
     pub fn get_tracks(&self) -> &[GenericTrack] {
@@ -1198,6 +1622,7 @@ impl MediaStreamManager {
     /// # Errors
     ///
     /// Returns error if track creation fails
+    #[cfg(feature = "legacy-webrtc")]
     pub async fn create_audio_track(&mut self) -> Result<&WebRtcTrack, MediaError> {
         let track_id = format!("audio-{}", self.webrtc_tracks.len());
         tracing::info!(track_id = %track_id, "Creating audio track");
@@ -1236,6 +1661,7 @@ impl MediaStreamManager {
     /// # Errors
     ///
     /// Returns error if track creation fails
+    #[cfg(feature = "legacy-webrtc")]
     pub async fn create_video_track(&mut self) -> Result<&WebRtcTrack, MediaError> {
         let track_id = format!("video-{}", self.webrtc_tracks.len());
         tracing::info!(track_id = %track_id, "Creating video track");
@@ -1269,7 +1695,7 @@ impl MediaStreamManager {
         ))
     }
 
-    /// Create a new video track with codec support
+    /// Create a new video track with the best encoder registered for a MIME type
     ///
     /// **Note**: This method creates a legacy WebRTC-backed video track.
     /// For QUIC-native tracks, use `create_quic_video_track`.
@@ -1277,24 +1703,18 @@ impl MediaStreamManager {
     /// # Errors
     ///
     /// Returns error if track creation fails
+    #[cfg(feature = "legacy-webrtc")]
     #[allow(deprecated)]
     pub async fn create_video_track_with_codec(
         &mut self,
-        codec: VideoCodec,
+        mime_type: &str,
         width: u32,
         height: u32,
     ) -> Result<VideoTrack, MediaError> {
         let track_id = format!("video-{}", self.webrtc_tracks.len());
 
-        // Use H.264 codec for WebRTC when encoding is enabled
-        let mime_type = match codec {
-            VideoCodec::H264 => "video/H264".to_string(),
-            // VideoCodec::VP8 => "video/VP8".to_string(),
-            // VideoCodec::VP9 => "video/VP9".to_string(),
-        };
-
         let codec_capability = RTCRtpCodecCapability {
-            mime_type,
+            mime_type: mime_type.to_string(),
             clock_rate: 90000,
             channels: 0,
             sdp_fmtp_line: "".to_string(),
@@ -1310,19 +1730,18 @@ impl MediaStreamManager {
         // Use deprecated legacy constructor (this method is for backward compatibility)
         let mut video_track = VideoTrack::new(track_id, webrtc_track, width, height);
 
-        // Add encoder based on codec
-        match codec {
-            VideoCodec::H264 => {
-                video_track = video_track
-                    .with_h264_encoder()
-                    .map_err(|e| MediaError::ConfigError(e.to_string()))?;
-            }
-        }
+        // Add a pooled encoder for the codec
+        video_track.encoder = Some(Box::new(
+            self.codec_pool
+                .video_encoder(mime_type, width, height)
+                .map_err(|e| MediaError::ConfigError(e.to_string()))?,
+        ));
 
         Ok(video_track)
     }
 
     /// Get all WebRTC tracks
+    #[cfg(feature = "legacy-webrtc")]
     #[must_use]
     pub fn get_webrtc_tracks(&self) -> &[WebRtcTrack] {
         &self.webrtc_tracks
@@ -1334,11 +1753,39 @@ impl MediaStreamManager {
         self.event_sender.subscribe()
     }
 
+    /// Replace the media event channel
+    ///
+    /// Existing subscribers stop receiving events, so set this before
+    /// subscribing.
+    pub fn set_event_sender(&mut self, sender: EventSender<MediaEvent>) {
+        self.event_sender = sender;
+    }
+
+    /// Counters of the media event channel, including events lost by
+    /// lagging subscribers
+    #[must_use]
+    pub fn event_stats(&self) -> EventChannelStats {
+        self.event_sender.stats()
+    }
+
+    /// Publish audio levels of all audio tracks every `interval`
+    ///
+    /// Returns one reporting task per audio track; each ends when its track
+    /// is removed.
+    pub fn report_audio_levels(&self, interval: Duration) -> Vec<JoinHandle<()>> {
+        self.tracks
+            .iter()
+            .filter_map(GenericTrack::as_audio)
+            .map(|track| track.report_levels(self.event_sender.clone(), interval))
+            .collect()
+    }
+
     /// Remove a track by ID
     ///
     /// Returns true if the track was found and removed
     pub fn remove_track(&mut self, track_id: &str) -> bool {
         // First try to remove from webrtc_tracks
+        #[cfg(feature = "legacy-webrtc")]
         if let Some(pos) = self.webrtc_tracks.iter().position(|t| t.id == track_id) {
             let track = &self.webrtc_tracks[pos];
             tracing::info!(track_id = %track_id, track_type = ?track.track_type, "Removing WebRTC track");
@@ -1370,15 +1817,12 @@ impl MediaStreamManager {
     ///
     /// Returns error if QUIC transport is not configured.
     pub fn create_quic_audio_track(&mut self) -> Result<&GenericTrack, MediaError> {
-        let transport = self
-            .quic_transport
-            .as_ref()
-            .ok_or_else(|| MediaError::ConfigError("QUIC transport not configured".to_string()))?;
+        let backend = self.next_quic_backend(StreamType::Audio)?;
 
-        let track_id = format!("audio-{}", self.tracks.len() + self.webrtc_tracks.len());
+        let track_id = format!("audio-{}", self.track_count());
         tracing::info!(track_id = %track_id, "Creating QUIC audio track");
 
-        let audio_track = AudioTrack::with_quic(&track_id, Arc::clone(transport));
+        let audio_track = AudioTrack::new_with_backend(track_id.clone(), backend);
         let generic = GenericTrack::audio(audio_track);
 
         self.tracks.push(generic);
@@ -1411,15 +1855,12 @@ impl MediaStreamManager {
         width: u32,
         height: u32,
     ) -> Result<&GenericTrack, MediaError> {
-        let transport = self
-            .quic_transport
-            .as_ref()
-            .ok_or_else(|| MediaError::ConfigError("QUIC transport not configured".to_string()))?;
+        let backend = self.next_quic_backend(StreamType::Video)?;
 
-        let track_id = format!("video-{}", self.tracks.len() + self.webrtc_tracks.len());
+        let track_id = format!("video-{}", self.track_count());
         tracing::info!(track_id = %track_id, width = width, height = height, "Creating QUIC video track");
 
-        let video_track = VideoTrack::with_quic(&track_id, Arc::clone(transport), width, height);
+        let video_track = VideoTrack::new_with_backend(track_id.clone(), backend, width, height);
         let generic = GenericTrack::video(video_track);
 
         self.tracks.push(generic);
@@ -1452,19 +1893,12 @@ impl MediaStreamManager {
         width: u32,
         height: u32,
     ) -> Result<&GenericTrack, MediaError> {
-        let transport = self
-            .quic_transport
-            .as_ref()
-            .ok_or_else(|| MediaError::ConfigError("QUIC transport not configured".to_string()))?;
+        // Use QuicTrackBackend with Screen stream type directly
+        let backend = self.next_quic_backend(StreamType::Screen)?;
 
-        let track_id = format!("screen-{}", self.tracks.len() + self.webrtc_tracks.len());
+        let track_id = format!("screen-{}", self.track_count());
         tracing::info!(track_id = %track_id, width = width, height = height, "Creating QUIC screen track");
 
-        // Use QuicTrackBackend with Screen stream type directly
-        let backend = Arc::new(QuicTrackBackend::with_stream_type(
-            Arc::clone(transport),
-            StreamType::Screen,
-        ));
         let video_track = VideoTrack::new_with_backend(track_id.clone(), backend, width, height);
         let generic = GenericTrack::screen(video_track);
 
@@ -1496,16 +1930,13 @@ impl MediaStreamManager {
         width: u32,
         height: u32,
     ) -> Result<VideoTrack, MediaError> {
-        let transport = self
-            .quic_transport
-            .as_ref()
-            .ok_or_else(|| MediaError::ConfigError("QUIC transport not configured".to_string()))?;
+        let backend = self.next_quic_backend(StreamType::Video)?;
 
-        let track_id = format!("video-{}", self.tracks.len() + self.webrtc_tracks.len());
+        let track_id = format!("video-{}", self.track_count());
         tracing::info!(track_id = %track_id, codec = "H264", "Creating QUIC video track with H.264");
 
-        let video_track = VideoTrack::with_quic(&track_id, Arc::clone(transport), width, height)
-            .with_h264_encoder()
+        let video_track = VideoTrack::new_with_backend(track_id, backend, width, height)
+            .with_pooled_codec(&self.codec_pool, mime::H264)
             .map_err(|e| {
                 MediaError::ConfigError(format!("H.264 encoder creation failed: {}", e))
             })?;
@@ -1517,6 +1948,7 @@ impl MediaStreamManager {
     #[must_use]
     pub fn get_track_by_id(&self, track_id: &str) -> Option<TrackRef<'_>> {
         // Check webrtc tracks first
+        #[cfg(feature = "legacy-webrtc")]
         if let Some(track) = self.webrtc_tracks.iter().find(|t| t.id == track_id) {
             return Some(TrackRef::WebRtc(track));
         }
@@ -1533,6 +1965,7 @@ impl MediaStreamManager {
 /// Reference to either a WebRTC track or a generic track
 pub enum TrackRef<'a> {
     /// Legacy WebRTC track
+    #[cfg(feature = "legacy-webrtc")]
     WebRtc(&'a WebRtcTrack),
     /// Generic track (QUIC-backed)
     Generic(&'a GenericTrack),
@@ -1543,6 +1976,7 @@ impl TrackRef<'_> {
     #[must_use]
     pub fn id(&self) -> &str {
         match self {
+            #[cfg(feature = "legacy-webrtc")]
             Self::WebRtc(t) => &t.id,
             Self::Generic(t) => t.id(),
         }
@@ -1552,6 +1986,7 @@ impl TrackRef<'_> {
     #[must_use]
     pub fn media_type(&self) -> MediaType {
         match self {
+            #[cfg(feature = "legacy-webrtc")]
             Self::WebRtc(t) => t.track_type.clone(),
             Self::Generic(t) => t.media_type(),
         }
@@ -1560,7 +1995,7 @@ impl TrackRef<'_> {
     /// Check if this is a WebRTC track
     #[must_use]
     pub fn is_webrtc(&self) -> bool {
-        matches!(self, Self::WebRtc(_))
+        !self.is_generic()
     }
 
     /// Check if this is a generic (QUIC) track
@@ -1864,6 +2299,7 @@ mod quic_track_backend_tests {
     }
 }
 
+#[cfg(feature = "legacy-webrtc")]
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 #[allow(deprecated)]
@@ -1963,6 +2399,7 @@ mod video_track_tests {
         }
     }
 
+    #[cfg(feature = "legacy-webrtc")]
     fn create_webrtc_video_track() -> Arc<TrackLocalStaticSample> {
         let codec_capability = RTCRtpCodecCapability {
             mime_type: "video/H264".to_string(),
@@ -1989,6 +2426,7 @@ mod video_track_tests {
         assert_eq!(track.backend().backend_type(), "quic");
     }
 
+    #[cfg(feature = "legacy-webrtc")]
     #[test]
     #[allow(deprecated)]
     fn test_video_track_with_webrtc_backend() {
@@ -2056,6 +2494,64 @@ mod video_track_tests {
         assert_eq!(stats.packets_sent, 1);
     }
 
+    #[tokio::test]
+    async fn test_video_track_sends_selected_layers() {
+        let transport = Arc::new(QuicMediaTransport::new());
+        transport.connect(test_peer()).await.unwrap();
+        let mut track = VideoTrack::with_quic("video-1", transport, 1280, 720);
+        let (low, mid, high) = (
+            VideoLayer::BASE,
+            VideoLayer::new(1, 0),
+            VideoLayer::new(2, 1),
+        );
+
+        // Everything goes out until a subscriber chooses
+        assert!(track.send_layer(high, &[1]).await.unwrap());
+
+        track.select_layer("alice", mid);
+        assert!(!track.send_layer(low, &[1]).await.unwrap());
+        assert!(track.send_layer(mid, &[1]).await.unwrap());
+        assert!(!track.send_layer(high, &[1]).await.unwrap());
+        assert_eq!(track.stats().packets_sent, 2);
+
+        track.select_layer("bob", high);
+        assert_eq!(track.subscribers_for(high), vec!["bob"]);
+        assert_eq!(track.clear_layer_selection("bob"), Some(high));
+
+        let mut svc = track.with_layer_structure(LayerStructure::Svc);
+        assert!(svc.is_layer_wanted(low));
+        assert!(!svc.is_layer_wanted(VideoLayer::new(1, 1)));
+        svc.clear_layer_selection("alice");
+        assert_eq!(svc.selected_layer("alice"), None);
+        assert!(svc.is_layer_wanted(high));
+    }
+
+    #[tokio::test]
+    async fn test_video_track_drops_temporal_layers() {
+        let transport = Arc::new(QuicMediaTransport::new());
+        transport.connect(test_peer()).await.unwrap();
+        let mut track = VideoTrack::with_quic("video-1", transport, 16, 16)
+            .with_codec(&CodecRegistry::with_defaults(), mime::H264)
+            .unwrap();
+        track.set_temporal_layers(TemporalLayers::L3).unwrap();
+        let raw = vec![9u8; 16 * 16 * 3];
+
+        // Congestion control caps the frame rate at a quarter
+        track.set_max_temporal_layer(Some(0));
+        for _ in 0..8 {
+            track.encode_and_send(&raw).await.unwrap();
+        }
+        assert_eq!(track.stats().packets_sent, 2);
+
+        // A subscriber at half the frame rate once the cap is lifted
+        track.set_max_temporal_layer(None);
+        track.select_layer("alice", VideoLayer::new(0, 1));
+        for _ in 0..8 {
+            track.encode_and_send(&raw).await.unwrap();
+        }
+        assert_eq!(track.stats().packets_sent, 6);
+    }
+
     #[test]
     fn test_video_track_encode_decode_without_codecs() {
         let transport = Arc::new(QuicMediaTransport::new());
@@ -2080,6 +2576,7 @@ mod video_track_tests {
         assert_eq!(backend.backend_type(), "quic");
     }
 
+    #[cfg(feature = "legacy-webrtc")]
     #[test]
     #[allow(deprecated)]
     fn test_legacy_constructor_still_works() {
@@ -2107,6 +2604,7 @@ mod audio_track_tests {
         }
     }
 
+    #[cfg(feature = "legacy-webrtc")]
     fn create_webrtc_audio_track() -> Arc<TrackLocalStaticSample> {
         let codec_capability = RTCRtpCodecCapability {
             mime_type: "audio/opus".to_string(),
@@ -2131,6 +2629,7 @@ mod audio_track_tests {
         assert_eq!(track.backend().backend_type(), "quic");
     }
 
+    #[cfg(feature = "legacy-webrtc")]
     #[test]
     #[allow(deprecated)]
     fn test_audio_track_with_webrtc_backend() {
@@ -2212,6 +2711,45 @@ mod audio_track_tests {
         let backend = track.backend();
         assert_eq!(backend.backend_type(), "quic");
     }
+
+    #[test]
+    fn test_audio_track_levels_per_direction() {
+        let track = AudioTrack::with_quic("audio-1", Arc::new(QuicMediaTransport::new()));
+        track.meter_capture(&[i16::MAX, i16::MIN]);
+
+        assert!(track.audio_level(AudioDirection::Capture).peak > 0.99);
+        assert_eq!(
+            track.audio_level(AudioDirection::Playback),
+            AudioLevel::default()
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_audio_track_reports_levels_until_dropped() {
+        let events = EventSender::new(16);
+        let mut rx = events.subscribe();
+        let track = AudioTrack::with_quic("audio-1", Arc::new(QuicMediaTransport::new()));
+        track.meter_playback(&[8192; 480]);
+        let reporter = track.report_levels(events, Duration::from_millis(100));
+
+        let mut playback = None;
+        for _ in 0..2 {
+            if let MediaEvent::AudioLevel {
+                track_id,
+                direction: AudioDirection::Playback,
+                level,
+            } = rx.recv().await.unwrap()
+            {
+                assert_eq!(track_id, "audio-1");
+                playback = Some(level);
+            }
+        }
+        assert!((playback.unwrap().peak - 0.25).abs() < 0.001);
+
+        drop(track);
+        tokio::time::advance(Duration::from_millis(100)).await;
+        reporter.await.unwrap();
+    }
 }
 
 #[cfg(test)]
@@ -2383,6 +2921,37 @@ mod tests {
         // but for now this is a basic structure test
     }
 
+    #[tokio::test]
+    async fn test_second_camera_gets_its_own_track() {
+        let transport = Arc::new(QuicMediaTransport::new());
+        transport
+            .connect(crate::link_transport::PeerConnection {
+                peer_id: "test-peer".to_string(),
+                remote_addr: "127.0.0.1:8080".parse().unwrap(),
+            })
+            .await
+            .unwrap();
+        let mut manager = MediaStreamManager::with_quic_transport(Arc::clone(&transport));
+
+        manager.create_quic_video_track(1280, 720).unwrap();
+        manager.create_quic_video_track(640, 360).unwrap();
+        manager.create_quic_screen_track(1920, 1080).unwrap();
+        for track in manager.get_tracks() {
+            track.send(&[0x80, 0x60, 0x00, 0x01]).await.unwrap();
+        }
+
+        assert_eq!(transport.open_tracks(StreamType::Video).await, vec![0, 1]);
+        assert_eq!(transport.open_tracks(StreamType::Screen).await, vec![0]);
+
+        // Closing the second camera leaves the first one streaming
+        assert!(
+            transport
+                .close_track(StreamKey::new(StreamType::Video, 1))
+                .await
+        );
+        assert_eq!(transport.open_tracks(StreamType::Video).await, vec![0]);
+    }
+
     #[tokio::test]
     async fn test_media_stream_manager_get_devices() {
         let manager = MediaStreamManager::new();
@@ -2394,6 +2963,7 @@ mod tests {
         assert!(video_devices.is_empty());
     }
 
+    #[cfg(feature = "legacy-webrtc")]
     #[tokio::test]
     async fn test_media_stream_manager_create_audio_track() {
         let mut manager = MediaStreamManager::new();
@@ -2407,6 +2977,7 @@ mod tests {
         assert_eq!(tracks[0].track_type, MediaType::Audio);
     }
 
+    #[cfg(feature = "legacy-webrtc")]
     #[tokio::test]
     async fn test_media_stream_manager_create_video_track() {
         let mut manager = MediaStreamManager::new();
@@ -2420,12 +2991,13 @@ mod tests {
         assert_eq!(tracks[0].track_type, MediaType::Video);
     }
 
+    #[cfg(feature = "legacy-webrtc")]
     #[tokio::test]
     async fn test_media_stream_manager_create_video_track_with_codec() {
         let mut manager = MediaStreamManager::new();
 
         let track = manager
-            .create_video_track_with_codec(VideoCodec::H264, 640, 480)
+            .create_video_track_with_codec(saorsa_webrtc_codecs::mime::H264, 640, 480)
             .await
             .unwrap();
 
@@ -2435,6 +3007,7 @@ mod tests {
         assert!(track.encoder.is_some()); // Should have H.264 encoder
     }
 
+    #[cfg(feature = "legacy-webrtc")]
     #[tokio::test]
     async fn test_media_stream_manager_multiple_tracks() {
         let mut manager = MediaStreamManager::new();
@@ -2486,6 +3059,60 @@ mod tests {
         assert!(manager.has_quic_transport());
     }
 
+    #[test]
+    fn test_h264_tracks_reuse_pooled_codecs() {
+        let transport = Arc::new(QuicMediaTransport::new());
+        let mut manager = MediaStreamManager::with_quic_transport(transport);
+        manager
+            .codec_pool()
+            .warm_video_encoders(mime::H264, 640, 480, 1)
+            .unwrap();
+
+        let track = manager.create_quic_video_track_h264(640, 480).unwrap();
+        assert!(track.encoder.is_some() && track.decoder.is_some());
+        assert_eq!(manager.codec_pool().stats().reused, 1);
+        drop(track);
+
+        // The encoder and decoder went back to the pool for the next track
+        assert_eq!(manager.codec_pool().stats().idle, 2);
+        let _track = manager.create_quic_video_track_h264(640, 480).unwrap();
+        let stats = manager.codec_pool().stats();
+        assert_eq!((stats.created, stats.reused, stats.idle), (2, 3, 0));
+
+        manager.set_codec_pool_config(PoolConfig { max_idle: 0 });
+        drop(manager.create_quic_video_track_h264(640, 480).unwrap());
+        assert_eq!(manager.codec_pool().stats().idle, 0);
+    }
+
+    #[tokio::test]
+    async fn test_offloaded_encode_decode_round_trip() {
+        let mut manager =
+            MediaStreamManager::with_quic_transport(Arc::new(QuicMediaTransport::new()));
+        manager.set_media_worker_config(MediaWorkerConfig {
+            threads: 1,
+            max_jobs_per_call: 1,
+        });
+        let workers = Arc::clone(manager.media_workers());
+        let mut track = manager.create_quic_video_track_h264(4, 2).unwrap();
+        let call_id = CallId::new();
+        let raw = vec![7u8; 4 * 2 * 3];
+
+        let encoded = track
+            .encode_frame_offloaded(&workers, call_id, raw.clone(), 40)
+            .await
+            .unwrap();
+        // The capture timestamp went into the stream
+        let decoder = track.decoder.as_mut().unwrap();
+        assert_eq!(decoder.decode(&encoded).unwrap().timestamp, 40);
+        let decoded = track
+            .decode_frame_offloaded(&workers, call_id, encoded.clone())
+            .await
+            .unwrap();
+        assert_eq!(decoded, track.decode_frame(&encoded).unwrap());
+        // The codecs came back from the worker
+        assert!(track.encoder.is_some() && track.decoder.is_some());
+    }
+
     #[test]
     fn test_create_quic_audio_track_without_transport() {
         let mut manager = MediaStreamManager::new();
@@ -2567,6 +3194,7 @@ mod tests {
         assert!(found.unwrap().is_generic());
     }
 
+    #[cfg(feature = "legacy-webrtc")]
     #[tokio::test]
     async fn test_get_track_by_id_webrtc() {
         let mut manager = MediaStreamManager::new();
@@ -2605,6 +3233,7 @@ mod tests {
         assert_eq!(manager.get_tracks().len(), 2);
 
         // Still no WebRTC tracks
+        #[cfg(feature = "legacy-webrtc")]
         assert_eq!(manager.get_webrtc_tracks().len(), 0);
     }
 }
@@ -0,0 +1,443 @@
+//! Authenticated encryption of media frames
+//!
+//! With end-to-end encryption every media packet is sealed with
+//! ChaCha20-Poly1305 before it reaches the transport, which makes the cipher
+//! the per-packet hot path. The `chacha20` backend picks a SIMD
+//! implementation (AVX2 or SSE2 on x86) at runtime; this module keeps that
+//! backend busy rather than the allocator:
+//!
+//! - frames are sealed and opened in place, into buffers the caller reuses;
+//! - [`FrameSealer::seal_batch`] reserves the nonces of a whole batch at
+//!   once and steps them in a single buffer, so a burst of packets (one
+//!   video frame split for the MTU) costs one exhaustion check and no
+//!   per-packet setup.
+//!
+//! # Frame layout
+//!
+//! ```text
+//! ┌────────┬──────────────┬────────────────────┬──────────┐
+//! │ key id │ counter (BE) │ ciphertext         │ tag      │
+//! │ 1 byte │ 8 bytes      │ len(payload) bytes │ 16 bytes │
+//! └────────┴──────────────┴────────────────────┴──────────┘
+//! ```
+//!
+//! The nonce is a per-key salt followed by the counter, so it never repeats
+//! under one key. The header is authenticated as associated data. Openers
+//! reject frames replayed within [`REPLAY_WINDOW`] packets.
+//!
+//! `cargo bench -p saorsa-webrtc-core --bench media_crypto` compares the
+//! per-packet overhead across packet sizes.
+
+use chacha20poly1305::aead::AeadInPlace;
+use chacha20poly1305::{ChaCha20Poly1305, Key, KeyInit, Nonce, Tag};
+use thiserror::Error;
+use zeroize::Zeroize;
+
+/// Bytes of header before the ciphertext: key id and counter
+pub const FRAME_HEADER_LEN: usize = 9;
+
+/// Bytes of authentication tag after the ciphertext
+pub const TAG_LEN: usize = 16;
+
+/// Bytes a sealed frame adds to its payload
+pub const FRAME_OVERHEAD: usize = FRAME_HEADER_LEN + TAG_LEN;
+
+/// Packets behind the newest one that may still arrive out of order
+pub const REPLAY_WINDOW: u64 = 64;
+
+/// Key derivation context for the cipher key
+const KEY_CONTEXT: &str = "saorsa-webrtc 2024 media frame key v1";
+
+/// Key derivation context for the nonce salt
+const SALT_CONTEXT: &str = "saorsa-webrtc 2024 media frame salt v1";
+
+/// Media frame encryption errors
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum MediaCryptoError {
+    /// Frame is shorter than its header and tag
+    #[error("Frame of {0} bytes is too short")]
+    Truncated(usize),
+
+    /// Frame was sealed with a key we do not hold
+    #[error("Unknown media key id {0}")]
+    UnknownKey(u8),
+
+    /// Frame was altered or sealed with another key
+    #[error("Frame failed authentication")]
+    Authentication,
+
+    /// Frame was already received or is too old to tell
+    #[error("Replayed frame {0}")]
+    Replay(u64),
+
+    /// Every nonce of the key is used; rotate the key
+    #[error("Media key exhausted; rotate the key")]
+    Exhausted,
+}
+
+/// Key for sealing and opening media frames
+///
+/// Derived from a shared 32-byte secret, e.g. one agreed during the call's
+/// key exchange. Both directions of a call must use different secrets or
+/// key ids, since the counters are independent.
+#[derive(Clone)]
+pub struct MediaKey {
+    id: u8,
+    key: [u8; 32],
+    salt: [u8; 4],
+}
+
+impl MediaKey {
+    /// Derive a key from a shared secret
+    #[must_use]
+    pub fn from_secret(id: u8, secret: &[u8; 32]) -> Self {
+        let key = blake3::derive_key(KEY_CONTEXT, secret);
+        let salt_bytes = blake3::derive_key(SALT_CONTEXT, secret);
+        let mut salt = [0u8; 4];
+        salt.copy_from_slice(&salt_bytes[..4]);
+        Self { id, key, salt }
+    }
+
+    /// Key id carried in every frame sealed with the key
+    #[must_use]
+    pub fn id(&self) -> u8 {
+        self.id
+    }
+
+    fn cipher(&self) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305::new(Key::from_slice(&self.key))
+    }
+
+    fn nonce(&self, counter: u64) -> [u8; 12] {
+        let mut nonce = [0u8; 12];
+        nonce[..4].copy_from_slice(&self.salt);
+        nonce[4..].copy_from_slice(&counter.to_be_bytes());
+        nonce
+    }
+}
+
+impl Drop for MediaKey {
+    fn drop(&mut self) {
+        self.key.zeroize();
+        self.salt.zeroize();
+    }
+}
+
+impl std::fmt::Debug for MediaKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MediaKey")
+            .field("id", &self.id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Seals outgoing media frames under one key
+pub struct FrameSealer {
+    key: MediaKey,
+    cipher: ChaCha20Poly1305,
+    next_counter: u64,
+}
+
+impl FrameSealer {
+    /// Create a sealer starting at counter 0
+    #[must_use]
+    pub fn new(key: MediaKey) -> Self {
+        Self {
+            cipher: key.cipher(),
+            key,
+            next_counter: 0,
+        }
+    }
+
+    /// Counter the next frame will carry
+    #[must_use]
+    pub fn next_counter(&self) -> u64 {
+        self.next_counter
+    }
+
+    /// Seal one payload, appending the frame to `out`
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the key is exhausted
+    pub fn seal(&mut self, payload: &[u8], out: &mut Vec<u8>) -> Result<(), MediaCryptoError> {
+        let counter = self.reserve(1)?;
+        self.seal_at(counter, &self.key.nonce(counter), payload, out)
+    }
+
+    /// Seal several payloads, appending their frames to `out` in order
+    ///
+    /// Frame `i` is `payloads[i].len() + FRAME_OVERHEAD` bytes long. The
+    /// batch's counters are reserved up front, so either every payload is
+    /// sealed or none is.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the key has too few nonces left for the batch
+    pub fn seal_batch(
+        &mut self,
+        payloads: &[&[u8]],
+        out: &mut Vec<u8>,
+    ) -> Result<(), MediaCryptoError> {
+        let first = self.reserve(payloads.len() as u64)?;
+        out.reserve(payloads.iter().map(|p| p.len() + FRAME_OVERHEAD).sum());
+        let mut nonce = self.key.nonce(first);
+        for (counter, payload) in (first..).zip(payloads) {
+            nonce[4..].copy_from_slice(&counter.to_be_bytes());
+            self.seal_at(counter, &nonce, payload, out)?;
+        }
+        Ok(())
+    }
+
+    fn reserve(&mut self, count: u64) -> Result<u64, MediaCryptoError> {
+        let first = self.next_counter;
+        self.next_counter = first
+            .checked_add(count)
+            .ok_or(MediaCryptoError::Exhausted)?;
+        Ok(first)
+    }
+
+    fn seal_at(
+        &self,
+        counter: u64,
+        nonce: &[u8; 12],
+        payload: &[u8],
+        out: &mut Vec<u8>,
+    ) -> Result<(), MediaCryptoError> {
+        let start = out.len();
+        out.push(self.key.id);
+        out.extend_from_slice(&counter.to_be_bytes());
+        out.extend_from_slice(payload);
+        let (header, body) = out[start..].split_at_mut(FRAME_HEADER_LEN);
+        let tag = self
+            .cipher
+            .encrypt_in_place_detached(Nonce::from_slice(nonce), header, body)
+            // Only fails for payloads beyond 256 GiB
+            .map_err(|_| MediaCryptoError::Exhausted)?;
+        out.extend_from_slice(&tag);
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for FrameSealer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FrameSealer")
+            .field("key", &self.key)
+            .field("next_counter", &self.next_counter)
+            .finish()
+    }
+}
+
+/// Opens incoming media frames sealed under one key
+pub struct FrameOpener {
+    key: MediaKey,
+    cipher: ChaCha20Poly1305,
+    /// Highest counter opened so far
+    highest: Option<u64>,
+    /// Bit `n` set if counter `highest - n` was opened
+    window: u64,
+}
+
+impl FrameOpener {
+    /// Create an opener that has seen no frames
+    #[must_use]
+    pub fn new(key: MediaKey) -> Self {
+        Self {
+            cipher: key.cipher(),
+            key,
+            highest: None,
+            window: 0,
+        }
+    }
+
+    /// Open a frame, appending its payload to `out`
+    ///
+    /// Returns the frame's counter.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the frame is malformed, fails authentication or was
+    /// replayed; `out` is left unchanged
+    pub fn open(&mut self, frame: &[u8], out: &mut Vec<u8>) -> Result<u64, MediaCryptoError> {
+        if frame.len() < FRAME_OVERHEAD {
+            return Err(MediaCryptoError::Truncated(frame.len()));
+        }
+        let (header, rest) = frame.split_at(FRAME_HEADER_LEN);
+        if header[0] != self.key.id {
+            return Err(MediaCryptoError::UnknownKey(header[0]));
+        }
+        let mut counter_bytes = [0u8; 8];
+        counter_bytes.copy_from_slice(&header[1..]);
+        let counter = u64::from_be_bytes(counter_bytes);
+        self.check_replay(counter)?;
+
+        let (ciphertext, tag) = rest.split_at(rest.len() - TAG_LEN);
+        let start = out.len();
+        out.extend_from_slice(ciphertext);
+        let opened = self.cipher.decrypt_in_place_detached(
+            Nonce::from_slice(&self.key.nonce(counter)),
+            header,
+            &mut out[start..],
+            Tag::from_slice(tag),
+        );
+        if opened.is_err() {
+            out.truncate(start);
+            return Err(MediaCryptoError::Authentication);
+        }
+        self.mark_seen(counter);
+        Ok(counter)
+    }
+
+    fn check_replay(&self, counter: u64) -> Result<(), MediaCryptoError> {
+        let Some(highest) = self.highest else {
+            return Ok(());
+        };
+        if counter > highest {
+            return Ok(());
+        }
+        let age = highest - counter;
+        if age >= REPLAY_WINDOW || self.window & (1 << age) != 0 {
+            return Err(MediaCryptoError::Replay(counter));
+        }
+        Ok(())
+    }
+
+    fn mark_seen(&mut self, counter: u64) {
+        match self.highest {
+            Some(highest) if counter <= highest => {
+                self.window |= 1 << (highest - counter);
+            }
+            Some(highest) => {
+                let shift = counter - highest;
+                self.window = if shift >= REPLAY_WINDOW {
+                    0
+                } else {
+                    self.window << shift
+                } | 1;
+                self.highest = Some(counter);
+            }
+            None => {
+                self.window = 1;
+                self.highest = Some(counter);
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for FrameOpener {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FrameOpener")
+            .field("key", &self.key)
+            .field("highest", &self.highest)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn pair() -> (FrameSealer, FrameOpener) {
+        let key = MediaKey::from_secret(7, &[42u8; 32]);
+        (FrameSealer::new(key.clone()), FrameOpener::new(key))
+    }
+
+    #[test]
+    fn test_seal_open_and_reject_tampering() {
+        let (mut sealer, mut opener) = pair();
+        let mut frame = Vec::new();
+        sealer.seal(b"opus packet", &mut frame).unwrap();
+        assert_eq!(frame.len(), b"opus packet".len() + FRAME_OVERHEAD);
+        assert_eq!(frame[0], 7);
+
+        let mut payload = Vec::new();
+        assert_eq!(opener.open(&frame, &mut payload), Ok(0));
+        assert_eq!(payload, b"opus packet");
+        assert_eq!(
+            opener.open(&frame, &mut payload),
+            Err(MediaCryptoError::Replay(0))
+        );
+
+        // Flipping a header bit breaks authentication too
+        let mut forged = Vec::new();
+        sealer.seal(b"opus packet", &mut forged).unwrap();
+        forged[FRAME_HEADER_LEN] ^= 1;
+        payload.clear();
+        assert_eq!(
+            opener.open(&forged, &mut payload),
+            Err(MediaCryptoError::Authentication)
+        );
+        assert!(payload.is_empty());
+        assert_eq!(
+            opener.open(&forged[..10], &mut payload),
+            Err(MediaCryptoError::Truncated(10))
+        );
+
+        let mut stranger = FrameOpener::new(MediaKey::from_secret(8, &[42u8; 32]));
+        assert_eq!(
+            stranger.open(&frame, &mut payload),
+            Err(MediaCryptoError::UnknownKey(7))
+        );
+    }
+
+    #[test]
+    fn test_batch_matches_single_seals() {
+        let payloads: Vec<Vec<u8>> = (0..5u8).map(|i| vec![i; 100 + usize::from(i)]).collect();
+        let refs: Vec<&[u8]> = payloads.iter().map(Vec::as_slice).collect();
+
+        let (mut batch_sealer, mut opener) = pair();
+        let mut batch = Vec::new();
+        batch_sealer.seal_batch(&refs, &mut batch).unwrap();
+
+        let (mut sealer, _) = pair();
+        let mut single = Vec::new();
+        for payload in &refs {
+            sealer.seal(payload, &mut single).unwrap();
+        }
+        assert_eq!(batch, single);
+        assert_eq!(batch_sealer.next_counter(), 5);
+
+        // Out-of-order delivery within the window is fine
+        let mut frames = Vec::new();
+        let mut offset = 0;
+        for payload in &refs {
+            let len = payload.len() + FRAME_OVERHEAD;
+            frames.push(&batch[offset..offset + len]);
+            offset += len;
+        }
+        let mut out = Vec::new();
+        for index in [4, 0, 2, 1, 3] {
+            out.clear();
+            assert_eq!(opener.open(frames[index], &mut out), Ok(index as u64));
+            assert_eq!(out, payloads[index]);
+        }
+    }
+
+    #[test]
+    fn test_replay_window_and_exhaustion() {
+        let (mut sealer, mut opener) = pair();
+        let mut frames = Vec::new();
+        for _ in 0..=REPLAY_WINDOW {
+            let mut frame = Vec::new();
+            sealer.seal(b"x", &mut frame).unwrap();
+            frames.push(frame);
+        }
+        let mut out = Vec::new();
+        opener
+            .open(&frames[REPLAY_WINDOW as usize], &mut out)
+            .unwrap();
+        // Counter 0 is now too old to tell apart from a replay
+        assert_eq!(
+            opener.open(&frames[0], &mut out),
+            Err(MediaCryptoError::Replay(0))
+        );
+        opener.open(&frames[1], &mut out).unwrap();
+
+        sealer.next_counter = u64::MAX;
+        assert_eq!(
+            sealer.seal_batch(&[b"a", b"b"], &mut out),
+            Err(MediaCryptoError::Exhausted)
+        );
+    }
+}
@@ -0,0 +1,215 @@
+//! Read-only taps on a call's media packets
+//!
+//! A tap receives a copy of every RTP packet of one stream type that a call
+//! sends or receives, for external recorders, analyzers or ML pipelines.
+//! Taps never slow down or alter the media path: copies are offered with
+//! `try_send`, so a tap that falls behind misses packets rather than
+//! stalling the call, and a dropped receiver is removed on the next packet.
+//!
+//! [`TrackReceivers`] deliver the packets of one remote track, by
+//! [`StreamKey`], on the same terms.
+
+use crate::quic_bridge::{RtpPacket, StreamType};
+use crate::quic_media_transport::StreamKey;
+use parking_lot::Mutex;
+use tokio::sync::mpsc;
+
+/// Packets buffered per tap before further copies are dropped
+pub const DEFAULT_TAP_CAPACITY: usize = 256;
+
+/// Set of taps on one media transport
+#[derive(Debug, Default)]
+pub struct MediaTaps {
+    taps: Mutex<Vec<(StreamType, mpsc::Sender<RtpPacket>)>>,
+}
+
+impl MediaTaps {
+    /// Create an empty set of taps
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a tap on packets of `stream_type`
+    #[must_use]
+    pub fn subscribe(&self, stream_type: StreamType) -> mpsc::Receiver<RtpPacket> {
+        let (sender, receiver) = mpsc::channel(DEFAULT_TAP_CAPACITY);
+        self.taps.lock().push((stream_type, sender));
+        receiver
+    }
+
+    /// Check if any tap is attached
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        !self.taps.lock().is_empty()
+    }
+
+    /// Packets copied to taps and not yet received
+    #[must_use]
+    pub fn buffered(&self) -> usize {
+        self.taps
+            .lock()
+            .iter()
+            .map(|(_, sender)| sender.max_capacity() - sender.capacity())
+            .sum()
+    }
+
+    /// Offer a copy of a packet to the taps on its stream type
+    pub fn publish(&self, packet: &RtpPacket) {
+        self.taps.lock().retain(|(stream_type, sender)| {
+            if *stream_type != packet.stream_type {
+                return !sender.is_closed();
+            }
+            match sender.try_send(packet.clone()) {
+                Ok(()) => true,
+                Err(mpsc::error::TrySendError::Full(_)) => {
+                    tracing::trace!(?stream_type, "Media tap full, packet dropped");
+                    true
+                }
+                Err(mpsc::error::TrySendError::Closed(_)) => false,
+            }
+        });
+    }
+
+    /// Decode and publish a serialized packet if any tap is attached
+    ///
+    /// Data that is not a serialized [`RtpPacket`] is ignored.
+    pub fn publish_bytes(&self, data: &[u8]) {
+        if !self.is_active() {
+            return;
+        }
+        match RtpPacket::from_bytes(data) {
+            Ok(packet) => self.publish(&packet),
+            Err(e) => tracing::trace!(error = %e, "Untappable media packet"),
+        }
+    }
+}
+
+/// Receivers of the packets of individual remote tracks
+#[derive(Debug, Default)]
+pub struct TrackReceivers {
+    receivers: Mutex<Vec<(StreamKey, mpsc::Sender<RtpPacket>)>>,
+}
+
+impl TrackReceivers {
+    /// Create an empty set of receivers
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a receiver of the packets received on `key`
+    #[must_use]
+    pub fn subscribe(&self, key: StreamKey) -> mpsc::Receiver<RtpPacket> {
+        let (sender, receiver) = mpsc::channel(DEFAULT_TAP_CAPACITY);
+        self.receivers.lock().push((key, sender));
+        receiver
+    }
+
+    /// End every receiver of `key`; they yield `None` once drained
+    pub fn close(&self, key: StreamKey) {
+        self.receivers.lock().retain(|(k, _)| *k != key);
+    }
+
+    /// Decode a serialized packet received on `key` and offer it to the
+    /// track's receivers
+    ///
+    /// Data that is not a serialized [`RtpPacket`] is ignored.
+    pub fn publish_bytes(&self, key: StreamKey, data: &[u8]) {
+        let mut receivers = self.receivers.lock();
+        if !receivers.iter().any(|(k, _)| *k == key) {
+            return;
+        }
+        let packet = match RtpPacket::from_bytes(data) {
+            Ok(packet) => packet,
+            Err(e) => {
+                tracing::trace!(error = %e, "Undeliverable track packet");
+                return;
+            }
+        };
+        receivers.retain(|(k, sender)| {
+            if *k != key {
+                return !sender.is_closed();
+            }
+            match sender.try_send(packet.clone()) {
+                Ok(()) => true,
+                Err(mpsc::error::TrySendError::Full(_)) => {
+                    tracing::trace!(
+                        track_id = key.track_id,
+                        "Track receiver full, packet dropped"
+                    );
+                    true
+                }
+                Err(mpsc::error::TrySendError::Closed(_)) => false,
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn packet(stream_type: StreamType, sequence_number: u16) -> RtpPacket {
+        RtpPacket::new(111, sequence_number, 0, 1, vec![1, 2, 3], stream_type).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_taps_filter_by_stream_type() {
+        let taps = MediaTaps::new();
+        assert!(!taps.is_active());
+        let mut audio = taps.subscribe(StreamType::Audio);
+        let mut video = taps.subscribe(StreamType::Video);
+
+        taps.publish(&packet(StreamType::Audio, 1));
+        taps.publish_bytes(&packet(StreamType::Video, 2).to_bytes().unwrap());
+        taps.publish_bytes(b"not a packet");
+
+        assert_eq!(audio.recv().await.unwrap().sequence_number, 1);
+        assert_eq!(video.recv().await.unwrap().sequence_number, 2);
+        assert!(audio.try_recv().is_err());
+        assert!(video.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_slow_and_closed_taps() {
+        let taps = MediaTaps::new();
+        let mut slow = taps.subscribe(StreamType::Audio);
+        let closed = taps.subscribe(StreamType::Audio);
+        drop(closed);
+
+        for seq in 0..(DEFAULT_TAP_CAPACITY as u16 + 10) {
+            taps.publish(&packet(StreamType::Audio, seq));
+        }
+        // Closed tap is gone, the slow one kept the oldest packets
+        assert_eq!(taps.taps.lock().len(), 1);
+        assert_eq!(slow.recv().await.unwrap().sequence_number, 0);
+
+        drop(slow);
+        taps.publish(&packet(StreamType::Video, 0));
+        assert!(!taps.is_active());
+    }
+
+    #[tokio::test]
+    async fn test_track_receivers_filter_by_track() {
+        use crate::link_transport::StreamType as LinkStreamType;
+
+        let receivers = TrackReceivers::new();
+        let camera = StreamKey::new(LinkStreamType::Video, 0);
+        let slides = StreamKey::new(LinkStreamType::Video, 1);
+        let mut camera_rx = receivers.subscribe(camera);
+        let mut slides_rx = receivers.subscribe(slides);
+
+        let bytes = |seq| packet(StreamType::Video, seq).to_bytes().unwrap();
+        receivers.publish_bytes(camera, &bytes(1));
+        receivers.publish_bytes(slides, &bytes(2));
+        assert_eq!(camera_rx.recv().await.unwrap().sequence_number, 1);
+        assert_eq!(slides_rx.recv().await.unwrap().sequence_number, 2);
+        assert!(camera_rx.try_recv().is_err());
+
+        receivers.close(slides);
+        receivers.publish_bytes(slides, &bytes(3));
+        assert!(slides_rx.recv().await.is_none());
+    }
+}
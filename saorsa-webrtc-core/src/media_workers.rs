@@ -0,0 +1,220 @@
+//! Dedicated worker threads for codec work
+//!
+//! Encoding and decoding are CPU-bound and can take milliseconds per frame.
+//! Run on the tokio runtime, a burst of them starves signaling, keepalives
+//! and packet forwarding on the same threads. [`MediaWorkers`] runs such jobs
+//! on its own rayon thread pool and hands the result back to the awaiting
+//! task, so the runtime threads only ever wait.
+//!
+//! Each call may run at most [`MediaWorkerConfig::max_jobs_per_call`] jobs at
+//! once; further jobs for the call wait their turn, so one call's spike
+//! cannot take every worker from the others.
+//!
+//! The threads are started on first use.
+
+use crate::types::CallId;
+use once_cell::sync::OnceCell;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Weak};
+use thiserror::Error;
+use tokio::sync::{oneshot, Semaphore};
+
+/// Media worker errors
+#[derive(Error, Debug)]
+pub enum MediaWorkerError {
+    /// The worker threads could not be started
+    #[error("Failed to start media workers: {0}")]
+    Start(String),
+
+    /// The job panicked
+    #[error("Media job panicked")]
+    Panicked,
+}
+
+/// Media worker configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MediaWorkerConfig {
+    /// Worker threads; 0 uses one per CPU
+    pub threads: usize,
+    /// Jobs one call may run at once
+    pub max_jobs_per_call: usize,
+}
+
+impl Default for MediaWorkerConfig {
+    fn default() -> Self {
+        Self {
+            threads: 0,
+            max_jobs_per_call: 2,
+        }
+    }
+}
+
+/// Thread pool for CPU-heavy media jobs
+#[derive(Debug)]
+pub struct MediaWorkers {
+    config: MediaWorkerConfig,
+    pool: OnceCell<rayon::ThreadPool>,
+    /// Per-call job slots, alive while a job of the call holds one
+    calls: Mutex<HashMap<CallId, Weak<Semaphore>>>,
+}
+
+impl Default for MediaWorkers {
+    fn default() -> Self {
+        Self::new(MediaWorkerConfig::default())
+    }
+}
+
+impl MediaWorkers {
+    /// Create workers; the threads start on the first job
+    #[must_use]
+    pub fn new(config: MediaWorkerConfig) -> Self {
+        Self {
+            config,
+            pool: OnceCell::new(),
+            calls: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Worker configuration
+    #[must_use]
+    pub fn config(&self) -> &MediaWorkerConfig {
+        &self.config
+    }
+
+    /// Run a job for a call on a worker thread and wait for its result
+    ///
+    /// Waits first for a free job slot of the call. If the awaiting task is
+    /// dropped the job still runs to completion and its result is discarded.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the worker threads cannot be started or the job panics
+    pub async fn run<F, R>(&self, call_id: CallId, job: F) -> Result<R, MediaWorkerError>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let pool = self.pool()?;
+        let slot = self
+            .call_slots(call_id)
+            .acquire_owned()
+            .await
+            // The semaphore is never closed
+            .map_err(|_| MediaWorkerError::Panicked)?;
+
+        let (tx, rx) = oneshot::channel();
+        pool.spawn(move || {
+            let result = std::panic::catch_unwind(AssertUnwindSafe(job));
+            drop(slot);
+            let _ = tx.send(result);
+        });
+        match rx.await {
+            Ok(Ok(result)) => Ok(result),
+            Ok(Err(_)) | Err(_) => Err(MediaWorkerError::Panicked),
+        }
+    }
+
+    /// Jobs currently running for a call
+    #[must_use]
+    pub fn jobs_in_flight(&self, call_id: CallId) -> usize {
+        self.calls
+            .lock()
+            .get(&call_id)
+            .and_then(Weak::upgrade)
+            .map_or(0, |slots| {
+                self.config.max_jobs_per_call.max(1) - slots.available_permits()
+            })
+    }
+
+    fn pool(&self) -> Result<&rayon::ThreadPool, MediaWorkerError> {
+        self.pool.get_or_try_init(|| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(self.config.threads)
+                .thread_name(|i| format!("saorsa-media-{i}"))
+                .build()
+                .map_err(|e| MediaWorkerError::Start(e.to_string()))
+        })
+    }
+
+    fn call_slots(&self, call_id: CallId) -> Arc<Semaphore> {
+        let mut calls = self.calls.lock();
+        if let Some(slots) = calls.get(&call_id).and_then(Weak::upgrade) {
+            return slots;
+        }
+        // Forget calls with no job left before adding this one
+        calls.retain(|_, slots| slots.strong_count() > 0);
+        let slots = Arc::new(Semaphore::new(self.config.max_jobs_per_call.max(1)));
+        calls.insert(call_id, Arc::downgrade(&slots));
+        slots
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_jobs_run_off_the_runtime() {
+        let workers = MediaWorkers::new(MediaWorkerConfig {
+            threads: 2,
+            max_jobs_per_call: 1,
+        });
+        let name = workers
+            .run(CallId::new(), || {
+                std::thread::current().name().map(str::to_string)
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            name.as_deref().map(|n| n.starts_with("saorsa-media-")),
+            Some(true)
+        );
+
+        let result = workers
+            .run(CallId::new(), || "boom".parse::<u32>().unwrap())
+            .await;
+        assert!(matches!(result, Err(MediaWorkerError::Panicked)));
+    }
+
+    #[tokio::test]
+    async fn test_per_call_limit() {
+        let workers = Arc::new(MediaWorkers::new(MediaWorkerConfig {
+            threads: 4,
+            max_jobs_per_call: 2,
+        }));
+        let call_id = CallId::new();
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let jobs: Vec<_> = (0..6)
+            .map(|_| {
+                let (workers, running, peak) = (
+                    Arc::clone(&workers),
+                    Arc::clone(&running),
+                    Arc::clone(&peak),
+                );
+                tokio::spawn(async move {
+                    workers
+                        .run(call_id, move || {
+                            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                            peak.fetch_max(now, Ordering::SeqCst);
+                            std::thread::sleep(Duration::from_millis(20));
+                            running.fetch_sub(1, Ordering::SeqCst);
+                        })
+                        .await
+                })
+            })
+            .collect();
+        for job in jobs {
+            job.await.unwrap().unwrap();
+        }
+
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        assert_eq!(workers.jobs_in_flight(call_id), 0);
+    }
+}
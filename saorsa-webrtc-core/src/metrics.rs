@@ -0,0 +1,64 @@
+//! Metrics hooks
+//!
+//! The call manager reports call lifecycle counts to a [`MetricsRecorder`],
+//! which embedders implement to forward them to their metrics system. The
+//! default recorder discards them.
+
+use std::fmt;
+use std::sync::Arc;
+
+/// Calls placed
+pub const CALLS_INITIATED: &str = "calls_initiated";
+
+/// Calls received
+pub const CALLS_INCOMING: &str = "calls_incoming";
+
+/// Calls answered
+pub const CALLS_ACCEPTED: &str = "calls_accepted";
+
+/// Calls declined before connecting
+pub const CALLS_REJECTED: &str = "calls_rejected";
+
+/// Calls hung up
+pub const CALLS_ENDED: &str = "calls_ended";
+
+/// Calls that failed
+pub const CALLS_FAILED: &str = "calls_failed";
+
+/// Calls moved to another device
+pub const CALLS_HANDED_OFF: &str = "calls_handed_off";
+
+/// Calls refused by a policy, including call limits and glare
+pub const POLICY_DENIALS: &str = "policy_denials";
+
+/// Call events lost by lagging subscribers
+pub const CALL_EVENTS_OVERFLOWED: &str = "call_events_overflowed";
+
+/// Media events lost by lagging subscribers
+pub const MEDIA_EVENTS_OVERFLOWED: &str = "media_events_overflowed";
+
+/// Service events lost by lagging subscribers
+pub const SERVICE_EVENTS_OVERFLOWED: &str = "service_events_overflowed";
+
+/// Sink for metrics
+pub trait MetricsRecorder: fmt::Debug + Send + Sync {
+    /// Add `value` to the counter `name`
+    fn increment_counter(&self, name: &'static str, value: u64);
+}
+
+/// A recorder shared between subsystems
+pub type SharedMetrics = Arc<dyn MetricsRecorder>;
+
+/// The default recorder, which discards metrics
+#[must_use]
+pub fn noop_metrics() -> SharedMetrics {
+    Arc::new(NoopMetrics)
+}
+
+/// Recorder that discards metrics
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopMetrics;
+
+impl MetricsRecorder for NoopMetrics {
+    fn increment_counter(&self, _name: &'static str, _value: u64) {}
+}
@@ -0,0 +1,265 @@
+//! Conference audio mixer with stereo placement
+//!
+//! Mixes decoded mono audio from each conference participant into one
+//! stereo output. Every participant has a pan position from -1.0 (hard
+//! left) to 1.0 (hard right); participants who have not been placed
+//! explicitly are spread evenly across the stereo field in join order, so
+//! each remote speaker comes from a distinct direction.
+//!
+//! Panning uses a constant-power law, so a participant sounds equally loud
+//! wherever they are placed.
+
+use crate::identity::PeerIdentity;
+use std::collections::{HashMap, VecDeque};
+use std::f32::consts::FRAC_PI_4;
+use thiserror::Error;
+
+/// Most audio buffered per participant (200ms at 48 kHz) before the oldest
+/// is dropped
+pub const MAX_PENDING_FRAMES: usize = 9_600;
+
+/// Widest automatic placement, leaving hard left and right for explicit use
+const AUTO_PAN_WIDTH: f32 = 0.8;
+
+/// Mixer errors
+#[derive(Error, Debug, PartialEq)]
+pub enum MixerError {
+    /// Participant has not been added to the mixer
+    #[error("Unknown participant: {0}")]
+    UnknownParticipant(String),
+
+    /// Pan position outside -1.0..=1.0
+    #[error("Invalid pan {0}, expected -1.0 to 1.0")]
+    InvalidPan(f32),
+}
+
+#[derive(Debug)]
+struct Participant<I: PeerIdentity> {
+    peer: I,
+    /// Order of joining, used for automatic placement
+    joined: u64,
+    pan: f32,
+    /// Whether the pan was set by the application
+    pinned: bool,
+    pending: VecDeque<i16>,
+}
+
+/// Stereo mixer for conference participants
+#[derive(Debug)]
+pub struct ConferenceMixer<I: PeerIdentity> {
+    participants: HashMap<String, Participant<I>>,
+    next_join: u64,
+}
+
+impl<I: PeerIdentity> Default for ConferenceMixer<I> {
+    fn default() -> Self {
+        Self {
+            participants: HashMap::new(),
+            next_join: 0,
+        }
+    }
+}
+
+impl<I: PeerIdentity> ConferenceMixer<I> {
+    /// Create an empty mixer
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a participant, placing them automatically
+    ///
+    /// Adding a participant that is already present keeps their placement.
+    pub fn add_participant(&mut self, peer: I) {
+        let key = peer.unique_id();
+        if self.participants.contains_key(&key) {
+            return;
+        }
+        self.participants.insert(
+            key,
+            Participant {
+                peer,
+                joined: self.next_join,
+                pan: 0.0,
+                pinned: false,
+                pending: VecDeque::new(),
+            },
+        );
+        self.next_join += 1;
+        self.spread();
+    }
+
+    /// Remove a participant, returning whether they were present
+    pub fn remove_participant(&mut self, peer: &I) -> bool {
+        let removed = self.participants.remove(&peer.unique_id()).is_some();
+        if removed {
+            self.spread();
+        }
+        removed
+    }
+
+    /// Participants currently in the mix
+    pub fn participants(&self) -> impl Iterator<Item = &I> {
+        self.participants.values().map(|p| &p.peer)
+    }
+
+    /// Place a participant at a stereo position, -1.0 (left) to 1.0 (right)
+    ///
+    /// The position sticks until changed; other participants are no longer
+    /// moved around it.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the participant is unknown or the pan is out of
+    /// range.
+    pub fn set_participant_pan(&mut self, peer: &I, pan: f32) -> Result<(), MixerError> {
+        if !(-1.0..=1.0).contains(&pan) {
+            return Err(MixerError::InvalidPan(pan));
+        }
+        let participant = self
+            .participants
+            .get_mut(&peer.unique_id())
+            .ok_or_else(|| MixerError::UnknownParticipant(peer.to_string()))?;
+        participant.pan = pan;
+        participant.pinned = true;
+        Ok(())
+    }
+
+    /// Current stereo position of a participant
+    #[must_use]
+    pub fn participant_pan(&self, peer: &I) -> Option<f32> {
+        self.participants.get(&peer.unique_id()).map(|p| p.pan)
+    }
+
+    /// Queue decoded mono audio from a participant
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the participant is unknown.
+    pub fn push(&mut self, peer: &I, samples: &[i16]) -> Result<(), MixerError> {
+        let participant = self
+            .participants
+            .get_mut(&peer.unique_id())
+            .ok_or_else(|| MixerError::UnknownParticipant(peer.to_string()))?;
+        participant.pending.extend(samples);
+        let excess = participant.pending.len().saturating_sub(MAX_PENDING_FRAMES);
+        participant.pending.drain(..excess);
+        Ok(())
+    }
+
+    /// Mix up to `frames` frames of queued audio into interleaved stereo
+    ///
+    /// Participants with less audio queued contribute silence for the rest.
+    #[must_use]
+    pub fn mix(&mut self, frames: usize) -> Vec<i16> {
+        let mut mixed = vec![0.0f32; frames * 2];
+        for participant in self.participants.values_mut() {
+            let (left, right) = pan_gains(participant.pan);
+            let ready = frames.min(participant.pending.len());
+            for (frame, sample) in participant.pending.drain(..ready).enumerate() {
+                let sample = f32::from(sample);
+                mixed[frame * 2] += sample * left;
+                mixed[frame * 2 + 1] += sample * right;
+            }
+        }
+        mixed
+            .into_iter()
+            .map(|s| s.round().clamp(f32::from(i16::MIN), f32::from(i16::MAX)) as i16)
+            .collect()
+    }
+
+    /// Spread participants without a pinned pan evenly, in join order
+    fn spread(&mut self) {
+        let mut unpinned: Vec<_> = self
+            .participants
+            .values_mut()
+            .filter(|p| !p.pinned)
+            .collect();
+        unpinned.sort_by_key(|p| p.joined);
+        let count = unpinned.len();
+        for (position, participant) in unpinned.into_iter().enumerate() {
+            participant.pan = if count == 1 {
+                0.0
+            } else {
+                -AUTO_PAN_WIDTH + 2.0 * AUTO_PAN_WIDTH * position as f32 / (count - 1) as f32
+            };
+        }
+    }
+}
+
+/// Constant-power left and right gains for a pan position
+fn pan_gains(pan: f32) -> (f32, f32) {
+    let angle = (pan + 1.0) * FRAC_PI_4;
+    (angle.cos(), angle.sin())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::identity::PeerIdentityString;
+
+    fn peer(name: &str) -> PeerIdentityString {
+        PeerIdentityString::new(name)
+    }
+
+    #[test]
+    fn test_participants_spread_across_stereo_field() {
+        let mut mixer = ConferenceMixer::new();
+        mixer.add_participant(peer("alice"));
+        assert_eq!(mixer.participant_pan(&peer("alice")), Some(0.0));
+
+        mixer.add_participant(peer("bob"));
+        mixer.add_participant(peer("carol"));
+        assert_eq!(mixer.participant_pan(&peer("alice")), Some(-0.8));
+        assert_eq!(mixer.participant_pan(&peer("bob")), Some(0.0));
+        assert_eq!(mixer.participant_pan(&peer("carol")), Some(0.8));
+
+        assert!(mixer.remove_participant(&peer("bob")));
+        assert_eq!(mixer.participant_pan(&peer("carol")), Some(0.8));
+        assert!(!mixer.remove_participant(&peer("bob")));
+    }
+
+    #[test]
+    fn test_set_participant_pan() {
+        let mut mixer = ConferenceMixer::new();
+        mixer.add_participant(peer("alice"));
+        mixer.add_participant(peer("bob"));
+
+        mixer.set_participant_pan(&peer("alice"), 0.5).unwrap();
+        mixer.add_participant(peer("carol"));
+        // Pinned participants stay put while the rest are spread
+        assert_eq!(mixer.participant_pan(&peer("alice")), Some(0.5));
+        assert_eq!(mixer.participant_pan(&peer("bob")), Some(-0.8));
+
+        assert_eq!(
+            mixer.set_participant_pan(&peer("alice"), 1.5),
+            Err(MixerError::InvalidPan(1.5))
+        );
+        assert!(mixer.set_participant_pan(&peer("alice"), f32::NAN).is_err());
+        assert_eq!(
+            mixer.set_participant_pan(&peer("dave"), 0.0),
+            Err(MixerError::UnknownParticipant("dave".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_mix_places_speakers() {
+        let mut mixer = ConferenceMixer::new();
+        mixer.add_participant(peer("left"));
+        mixer.add_participant(peer("right"));
+        mixer.set_participant_pan(&peer("left"), -1.0).unwrap();
+        mixer.set_participant_pan(&peer("right"), 1.0).unwrap();
+
+        mixer.push(&peer("left"), &[1000, 1000]).unwrap();
+        mixer.push(&peer("right"), &[-500]).unwrap();
+        assert_eq!(mixer.mix(3), vec![1000, -500, 1000, 0, 0, 0]);
+
+        // Centred audio is split at equal power
+        mixer.set_participant_pan(&peer("left"), 0.0).unwrap();
+        mixer.push(&peer("left"), &[1000]).unwrap();
+        assert_eq!(mixer.mix(1), vec![707, 707]);
+
+        assert!(mixer.push(&peer("dave"), &[1]).is_err());
+    }
+}
@@ -0,0 +1,253 @@
+//! Video packetization with layer marking
+//!
+//! [`VideoPacketizer`] splits encoded video frames into RTP packets. Every
+//! payload starts with a one-byte [`LayerDescriptor`] naming the frame's
+//! spatial and temporal layer, much like the AV1 dependency descriptor, so
+//! a forwarder or receiver can drop the layers it does not want by looking
+//! at that byte alone, without parsing the codec bitstream.
+//!
+//! ```text
+//!   7   6   5   4   3   2   1   0
+//! ┌───┬───┬───┬───────┬───────────┐
+//! │ S │ E │ K │spatial│ temporal  │
+//! └───┴───┴───┴───────┴───────────┘
+//! ```
+//!
+//! `S` and `E` mark the first and last packet of a frame and `K` a
+//! keyframe, from which a receiver switching layers can start decoding.
+
+use crate::quic_bridge::{RtpPacket, StreamType};
+use crate::types::VideoLayer;
+use thiserror::Error;
+
+/// Bytes the layer descriptor adds to each packet
+pub const DESCRIPTOR_LEN: usize = 1;
+
+/// Highest spatial layer a descriptor can carry
+pub const MAX_SPATIAL_LAYER: u8 = 3;
+
+/// Highest temporal layer a descriptor can carry
+pub const MAX_TEMPORAL_LAYER: u8 = 7;
+
+/// Largest RTP payload, as accepted by [`RtpPacket::new`]
+const MAX_RTP_PAYLOAD: usize = 1188;
+
+const START: u8 = 0x80;
+const END: u8 = 0x40;
+const KEYFRAME: u8 = 0x20;
+
+/// Packetization errors
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PacketizerError {
+    /// The layer does not fit in a descriptor
+    #[error("Layer {spatial}/{temporal} cannot be marked")]
+    LayerOutOfRange {
+        /// Spatial layer
+        spatial: u8,
+        /// Temporal layer
+        temporal: u8,
+    },
+
+    /// The frame has no data
+    #[error("Cannot packetize an empty frame")]
+    EmptyFrame,
+}
+
+/// Layer marking at the start of each video payload
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayerDescriptor {
+    /// First packet of a frame
+    pub start_of_frame: bool,
+    /// Last packet of a frame
+    pub end_of_frame: bool,
+    /// The frame is a keyframe
+    pub keyframe: bool,
+    /// Layer of the frame
+    pub layer: VideoLayer,
+}
+
+impl LayerDescriptor {
+    /// Encode the descriptor byte
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the layer is above [`MAX_SPATIAL_LAYER`] or
+    /// [`MAX_TEMPORAL_LAYER`]
+    pub fn to_byte(self) -> Result<u8, PacketizerError> {
+        let VideoLayer { spatial, temporal } = self.layer;
+        if spatial > MAX_SPATIAL_LAYER || temporal > MAX_TEMPORAL_LAYER {
+            return Err(PacketizerError::LayerOutOfRange { spatial, temporal });
+        }
+        let flag = |set: bool, bit: u8| if set { bit } else { 0 };
+        Ok(flag(self.start_of_frame, START)
+            | flag(self.end_of_frame, END)
+            | flag(self.keyframe, KEYFRAME)
+            | spatial << 3
+            | temporal)
+    }
+
+    /// Decode a descriptor byte
+    #[must_use]
+    pub fn from_byte(byte: u8) -> Self {
+        Self {
+            start_of_frame: byte & START != 0,
+            end_of_frame: byte & END != 0,
+            keyframe: byte & KEYFRAME != 0,
+            layer: VideoLayer::new((byte >> 3) & MAX_SPATIAL_LAYER, byte & MAX_TEMPORAL_LAYER),
+        }
+    }
+
+    /// Split a payload into its descriptor and the frame data
+    ///
+    /// Returns `None` for an empty payload.
+    #[must_use]
+    pub fn parse(payload: &[u8]) -> Option<(Self, &[u8])> {
+        let (&byte, data) = payload.split_first()?;
+        Some((Self::from_byte(byte), data))
+    }
+}
+
+/// Splits encoded video frames into layer-marked RTP packets
+#[derive(Debug)]
+pub struct VideoPacketizer {
+    ssrc: u32,
+    payload_type: u8,
+    next_sequence: u16,
+    max_payload: usize,
+}
+
+impl VideoPacketizer {
+    /// Create a packetizer for one RTP stream
+    #[must_use]
+    pub fn new(ssrc: u32, payload_type: u8) -> Self {
+        Self {
+            ssrc,
+            payload_type,
+            next_sequence: rand::random(),
+            max_payload: MAX_RTP_PAYLOAD,
+        }
+    }
+
+    /// Limit the payload of each packet, descriptor included
+    ///
+    /// Clamped to what a packet can carry.
+    #[must_use]
+    pub fn with_max_payload(mut self, max_payload: usize) -> Self {
+        self.max_payload = max_payload.clamp(DESCRIPTOR_LEN + 1, MAX_RTP_PAYLOAD);
+        self
+    }
+
+    /// Sequence number of the next packet
+    #[must_use]
+    pub fn next_sequence(&self) -> u16 {
+        self.next_sequence
+    }
+
+    /// Split one encoded frame into packets
+    ///
+    /// All packets carry the frame's timestamp and layer; the marker bit is
+    /// set on the last one.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the frame is empty or the layer cannot be marked
+    pub fn packetize(
+        &mut self,
+        frame: &[u8],
+        timestamp: u32,
+        layer: VideoLayer,
+        keyframe: bool,
+    ) -> Result<Vec<RtpPacket>, PacketizerError> {
+        if frame.is_empty() {
+            return Err(PacketizerError::EmptyFrame);
+        }
+        let chunks: Vec<&[u8]> = frame.chunks(self.max_payload - DESCRIPTOR_LEN).collect();
+        let last = chunks.len() - 1;
+
+        let mut packets = Vec::with_capacity(chunks.len());
+        for (index, chunk) in chunks.into_iter().enumerate() {
+            let descriptor = LayerDescriptor {
+                start_of_frame: index == 0,
+                end_of_frame: index == last,
+                keyframe,
+                layer,
+            };
+            let mut payload = Vec::with_capacity(DESCRIPTOR_LEN + chunk.len());
+            payload.push(descriptor.to_byte()?);
+            payload.extend_from_slice(chunk);
+
+            packets.push(RtpPacket {
+                version: 2,
+                padding: false,
+                extension: false,
+                csrc_count: 0,
+                marker: index == last,
+                payload_type: self.payload_type,
+                sequence_number: self.next_sequence,
+                timestamp,
+                ssrc: self.ssrc,
+                payload,
+                stream_type: StreamType::Video,
+            });
+            self.next_sequence = self.next_sequence.wrapping_add(1);
+        }
+        Ok(packets)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_descriptor_roundtrip() {
+        for spatial in 0..=MAX_SPATIAL_LAYER {
+            for temporal in 0..=MAX_TEMPORAL_LAYER {
+                let descriptor = LayerDescriptor {
+                    start_of_frame: temporal % 2 == 0,
+                    end_of_frame: spatial % 2 == 0,
+                    keyframe: spatial == temporal,
+                    layer: VideoLayer::new(spatial, temporal),
+                };
+                let byte = descriptor.to_byte().unwrap();
+                assert_eq!(LayerDescriptor::from_byte(byte), descriptor);
+            }
+        }
+        let too_high = LayerDescriptor {
+            start_of_frame: true,
+            end_of_frame: true,
+            keyframe: false,
+            layer: VideoLayer::new(0, 8),
+        };
+        assert!(too_high.to_byte().is_err());
+        assert_eq!(LayerDescriptor::parse(&[]), None);
+    }
+
+    #[test]
+    fn test_packetize_marks_every_packet() {
+        let mut packetizer = VideoPacketizer::new(42, 96).with_max_payload(101);
+        let first = packetizer.next_sequence();
+        let frame = vec![7u8; 250];
+        let layer = VideoLayer::new(0, 2);
+
+        let packets = packetizer.packetize(&frame, 3000, layer, false).unwrap();
+        assert_eq!(packets.len(), 3);
+        let mut data = Vec::new();
+        for (index, packet) in packets.iter().enumerate() {
+            let (descriptor, chunk) = LayerDescriptor::parse(&packet.payload).unwrap();
+            assert_eq!(descriptor.layer, layer);
+            assert_eq!(descriptor.start_of_frame, index == 0);
+            assert_eq!(descriptor.end_of_frame, index == 2);
+            assert_eq!(packet.marker, index == 2);
+            assert_eq!(packet.sequence_number, first.wrapping_add(index as u16));
+            assert_eq!(packet.timestamp, 3000);
+            data.extend_from_slice(chunk);
+        }
+        assert_eq!(data, frame);
+        assert_eq!(
+            packetizer.packetize(&[], 0, layer, false).unwrap_err(),
+            PacketizerError::EmptyFrame
+        );
+    }
+}
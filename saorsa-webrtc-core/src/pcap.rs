@@ -0,0 +1,363 @@
+//! Pcapng dumps of a call's media packets
+//!
+//! When enabled for a call, every media packet it sends or receives is
+//! written to a pcapng file that opens directly in Wireshark. Media travels
+//! over QUIC streams, so each packet is wrapped in a synthetic IPv4/UDP
+//! header: the local side is `10.0.0.1`, the peer `10.0.0.2`, and each
+//! stream type uses its own UDP port (see [`udp_port`]). Serialized
+//! [`RtpPacket`]s are re-encoded as standard RTP so Wireshark's RTP
+//! dissector and stream analysis work (use *Decode As… → RTP* on the
+//! ports). Each packet also carries a comment naming its direction and
+//! stream type.
+
+use crate::link_transport::StreamType;
+use crate::quic_bridge::RtpPacket;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::net::Ipv4Addr;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Synthetic address of the local side
+pub const LOCAL_ADDR: Ipv4Addr = Ipv4Addr::new(10, 0, 0, 1);
+
+/// Synthetic address of the remote peer
+pub const REMOTE_ADDR: Ipv4Addr = Ipv4Addr::new(10, 0, 0, 2);
+
+/// Link type for raw IPv4 packets
+const LINKTYPE_IPV4: u16 = 228;
+
+const SECTION_HEADER_BLOCK: u32 = 0x0A0D_0D0A;
+const INTERFACE_DESCRIPTION_BLOCK: u32 = 0x0000_0001;
+const ENHANCED_PACKET_BLOCK: u32 = 0x0000_0006;
+const BYTE_ORDER_MAGIC: u32 = 0x1A2B_3C4D;
+const OPT_COMMENT: u16 = 1;
+
+/// IPv4 and UDP header size
+const IP_UDP_HEADER_LEN: usize = 28;
+
+/// Whether a packet was sent or received
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacketDirection {
+    /// Sent to the peer
+    Sent,
+    /// Received from the peer
+    Received,
+}
+
+/// UDP port a stream type is shown on
+#[must_use]
+pub fn udp_port(stream_type: StreamType) -> u16 {
+    match stream_type {
+        StreamType::Audio => 5004,
+        StreamType::RtcpFeedback => 5005,
+        StreamType::Video => 5006,
+        StreamType::Screen => 5008,
+        StreamType::Data => 5010,
+    }
+}
+
+/// Encode a packet as RFC 3550 RTP (fixed header and payload)
+#[must_use]
+pub fn rtp_wire_bytes(packet: &RtpPacket) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(12 + packet.payload.len());
+    bytes.push(
+        (packet.version & 0x03) << 6
+            | u8::from(packet.padding) << 5
+            | u8::from(packet.extension) << 4
+            | (packet.csrc_count & 0x0F),
+    );
+    bytes.push(u8::from(packet.marker) << 7 | (packet.payload_type & 0x7F));
+    bytes.extend_from_slice(&packet.sequence_number.to_be_bytes());
+    bytes.extend_from_slice(&packet.timestamp.to_be_bytes());
+    bytes.extend_from_slice(&packet.ssrc.to_be_bytes());
+    bytes.extend_from_slice(&packet.payload);
+    bytes
+}
+
+/// Writes media packets as a pcapng capture
+#[derive(Debug)]
+pub struct PcapWriter<W: Write> {
+    writer: W,
+    packets: u64,
+}
+
+impl PcapWriter<BufWriter<File>> {
+    /// Create a capture file
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be created or written
+    pub fn create(path: &Path) -> std::io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        Self::new(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<W: Write> PcapWriter<W> {
+    /// Start a capture, writing the section and interface headers
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the headers cannot be written
+    pub fn new(mut writer: W) -> std::io::Result<Self> {
+        let mut body = Vec::with_capacity(16);
+        body.extend_from_slice(&BYTE_ORDER_MAGIC.to_le_bytes());
+        body.extend_from_slice(&1u16.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        // Section length not specified
+        body.extend_from_slice(&(-1i64).to_le_bytes());
+        write_block(&mut writer, SECTION_HEADER_BLOCK, &body)?;
+
+        let mut body = Vec::with_capacity(8);
+        body.extend_from_slice(&LINKTYPE_IPV4.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        // No snapshot length limit
+        body.extend_from_slice(&0u32.to_le_bytes());
+        write_block(&mut writer, INTERFACE_DESCRIPTION_BLOCK, &body)?;
+
+        Ok(Self { writer, packets: 0 })
+    }
+
+    /// Number of packets written
+    #[must_use]
+    pub fn packets(&self) -> u64 {
+        self.packets
+    }
+
+    /// Write a packet as it was sent or received on a stream
+    ///
+    /// `data` is re-encoded as RTP if it is a serialized [`RtpPacket`] and
+    /// written as is otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the packet cannot be written
+    pub fn write_packet(
+        &mut self,
+        direction: PacketDirection,
+        stream_type: StreamType,
+        data: &[u8],
+        at: SystemTime,
+    ) -> std::io::Result<()> {
+        let payload = RtpPacket::from_bytes(data)
+            .map(|packet| rtp_wire_bytes(&packet))
+            .unwrap_or_else(|_| data.to_vec());
+        let datagram = ipv4_udp(direction, udp_port(stream_type), &payload);
+
+        let micros = at.duration_since(UNIX_EPOCH).map_or(0, |since| {
+            u64::try_from(since.as_micros()).unwrap_or(u64::MAX)
+        });
+        let comment = format!(
+            "{} {:?}",
+            match direction {
+                PacketDirection::Sent => "sent",
+                PacketDirection::Received => "received",
+            },
+            stream_type
+        );
+        let length = u32::try_from(datagram.len()).unwrap_or(u32::MAX);
+
+        let mut body = Vec::with_capacity(20 + datagram.len() + comment.len() + 12);
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&((micros >> 32) as u32).to_le_bytes());
+        body.extend_from_slice(&(micros as u32).to_le_bytes());
+        body.extend_from_slice(&length.to_le_bytes());
+        body.extend_from_slice(&length.to_le_bytes());
+        body.extend_from_slice(&datagram);
+        pad(&mut body);
+        body.extend_from_slice(&OPT_COMMENT.to_le_bytes());
+        body.extend_from_slice(&(comment.len() as u16).to_le_bytes());
+        body.extend_from_slice(comment.as_bytes());
+        pad(&mut body);
+        // End of options
+        body.extend_from_slice(&[0; 4]);
+        write_block(&mut self.writer, ENHANCED_PACKET_BLOCK, &body)?;
+
+        self.packets += 1;
+        Ok(())
+    }
+
+    /// Flush buffered packets to the underlying writer
+    ///
+    /// # Errors
+    ///
+    /// Returns error if flushing fails
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Finish the capture and return the underlying writer
+    ///
+    /// # Errors
+    ///
+    /// Returns error if flushing fails
+    pub fn finish(mut self) -> std::io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Write a block: type, total length, body, total length
+fn write_block<W: Write>(writer: &mut W, block_type: u32, body: &[u8]) -> std::io::Result<()> {
+    let total = u32::try_from(body.len() + 12).unwrap_or(u32::MAX);
+    writer.write_all(&block_type.to_le_bytes())?;
+    writer.write_all(&total.to_le_bytes())?;
+    writer.write_all(body)?;
+    writer.write_all(&total.to_le_bytes())
+}
+
+/// Pad to a 32-bit boundary
+fn pad(body: &mut Vec<u8>) {
+    body.resize(body.len().next_multiple_of(4), 0);
+}
+
+/// Wrap a payload in IPv4 and UDP headers between the synthetic endpoints
+fn ipv4_udp(direction: PacketDirection, port: u16, payload: &[u8]) -> Vec<u8> {
+    let payload = &payload[..payload.len().min(usize::from(u16::MAX) - IP_UDP_HEADER_LEN)];
+    let total_len = (IP_UDP_HEADER_LEN + payload.len()) as u16;
+    let (source, destination) = match direction {
+        PacketDirection::Sent => (LOCAL_ADDR, REMOTE_ADDR),
+        PacketDirection::Received => (REMOTE_ADDR, LOCAL_ADDR),
+    };
+
+    let mut packet = Vec::with_capacity(usize::from(total_len));
+    // Version 4, 20-byte header, no DSCP, don't fragment, TTL 64, UDP
+    packet.extend_from_slice(&[0x45, 0x00]);
+    packet.extend_from_slice(&total_len.to_be_bytes());
+    packet.extend_from_slice(&[0x00, 0x00, 0x40, 0x00, 64, 17, 0x00, 0x00]);
+    packet.extend_from_slice(&source.octets());
+    packet.extend_from_slice(&destination.octets());
+    let checksum = ipv4_checksum(&packet);
+    packet[10..12].copy_from_slice(&checksum.to_be_bytes());
+
+    packet.extend_from_slice(&port.to_be_bytes());
+    packet.extend_from_slice(&port.to_be_bytes());
+    packet.extend_from_slice(&(total_len - 20).to_be_bytes());
+    // UDP checksum is optional over IPv4
+    packet.extend_from_slice(&[0x00, 0x00]);
+    packet.extend_from_slice(payload);
+    packet
+}
+
+fn ipv4_checksum(header: &[u8]) -> u16 {
+    let mut sum: u32 = header
+        .chunks(2)
+        .map(|word| {
+            u32::from(u16::from_be_bytes([
+                word[0],
+                word.get(1).copied().unwrap_or(0),
+            ]))
+        })
+        .sum();
+    while sum > 0xFFFF {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::quic_bridge::StreamType as RtpStreamType;
+    use std::time::Duration;
+
+    fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_rtp_wire_bytes() {
+        let mut packet = RtpPacket::new(
+            111,
+            0x1234,
+            0xDEAD_BEEF,
+            42,
+            vec![9, 8],
+            RtpStreamType::Audio,
+        )
+        .unwrap();
+        packet.marker = true;
+        assert_eq!(
+            rtp_wire_bytes(&packet),
+            vec![0x80, 0xEF, 0x12, 0x34, 0xDE, 0xAD, 0xBE, 0xEF, 0, 0, 0, 42, 9, 8]
+        );
+    }
+
+    #[test]
+    fn test_ipv4_header_checksum_verifies() {
+        let packet = ipv4_udp(PacketDirection::Received, 5006, &[1, 2, 3]);
+        assert_eq!(packet.len(), 31);
+        assert_eq!(&packet[12..16], &REMOTE_ADDR.octets());
+        // Summing a header including its checksum gives zero
+        assert_eq!(ipv4_checksum(&packet[..20]), 0);
+        assert_eq!(u16::from_be_bytes([packet[24], packet[25]]), 11);
+    }
+
+    #[test]
+    fn test_capture_layout() {
+        let mut writer = PcapWriter::new(Vec::new()).unwrap();
+        let packet = RtpPacket::new(111, 1, 960, 7, vec![0xAA; 3], RtpStreamType::Audio).unwrap();
+        let at = UNIX_EPOCH + Duration::from_micros(0x1_0000_0002);
+        writer
+            .write_packet(
+                PacketDirection::Sent,
+                StreamType::Audio,
+                &packet.to_bytes().unwrap(),
+                at,
+            )
+            .unwrap();
+        assert_eq!(writer.packets(), 1);
+        let bytes = writer.finish().unwrap();
+
+        // Section header, then interface description
+        assert_eq!(u32_at(&bytes, 0), SECTION_HEADER_BLOCK);
+        let shb_len = u32_at(&bytes, 4) as usize;
+        assert_eq!(u32_at(&bytes, 8), BYTE_ORDER_MAGIC);
+        assert_eq!(u32_at(&bytes, 12), 1);
+        let idb = shb_len;
+        assert_eq!(u32_at(&bytes, idb), INTERFACE_DESCRIPTION_BLOCK);
+        assert_eq!(u32_at(&bytes, idb + 8) & 0xFFFF, u32::from(LINKTYPE_IPV4));
+
+        let epb = idb + u32_at(&bytes, idb + 4) as usize;
+        assert_eq!(u32_at(&bytes, epb), ENHANCED_PACKET_BLOCK);
+        let epb_len = u32_at(&bytes, epb + 4) as usize;
+        assert_eq!(epb_len % 4, 0);
+        assert_eq!(u32_at(&bytes, epb + epb_len - 4) as usize, epb_len);
+        assert_eq!(epb + epb_len, bytes.len());
+        assert_eq!(u32_at(&bytes, epb + 12), 1);
+        assert_eq!(u32_at(&bytes, epb + 16), 2);
+
+        // 28 bytes of IPv4/UDP, then 12 bytes of RTP header and the payload
+        let captured = u32_at(&bytes, epb + 20) as usize;
+        assert_eq!(captured, 28 + 12 + 3);
+        let data = &bytes[epb + 28..epb + 28 + captured];
+        assert_eq!(u16::from_be_bytes([data[22], data[23]]), 5004);
+        assert_eq!(&data[28..30], &[0x80, 111]);
+        assert_eq!(&data[40..], &[0xAA; 3]);
+
+        let comment = &bytes[epb + 28 + captured.next_multiple_of(4)..];
+        assert_eq!(u16::from_le_bytes([comment[0], comment[1]]), OPT_COMMENT);
+        let comment_len = usize::from(u16::from_le_bytes([comment[2], comment[3]]));
+        assert_eq!(&comment[4..4 + comment_len], b"sent Audio");
+    }
+
+    #[test]
+    fn test_raw_payloads_are_kept() {
+        let mut writer = PcapWriter::new(Vec::new()).unwrap();
+        writer
+            .write_packet(
+                PacketDirection::Received,
+                StreamType::Data,
+                b"hello",
+                SystemTime::now(),
+            )
+            .unwrap();
+        let bytes = writer.finish().unwrap();
+        let found = bytes.windows(5).any(|window| window == b"hello");
+        assert!(found);
+    }
+}
@@ -0,0 +1,339 @@
+//! Per-peer bandwidth and quality learning
+//!
+//! Every call starts out not knowing what the path to the peer can carry,
+//! so media starts low and ramps up. Calls to the same peer usually take
+//! the same path, though: when a call ends, the bitrate it sustained and
+//! the codec it used are remembered for the peer in a
+//! [`PeerQualityCache`]. The next call to that peer is seeded from them
+//! with a [`QualitySeed`], starting near the known rate instead of from
+//! scratch.
+//!
+//! What is learned is blended with what was known, so one bad call does
+//! not wipe out the history, and it is forgotten after
+//! [`PeerQualityConfig::max_age_days`], when the network may well have
+//! changed. The cache is kept in a JSON file when
+//! [`PeerQualityConfig::path`] is set.
+
+use crate::stats_history::StatsSample;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Peer quality errors
+#[derive(Error, Debug)]
+pub enum PeerQualityError {
+    /// The cache file could not be read or written
+    #[error("Peer quality storage error: {0}")]
+    Storage(String),
+}
+
+/// Per-peer quality learning configuration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PeerQualityConfig {
+    /// Learn from calls and seed new ones
+    pub enabled: bool,
+    /// File the cache is kept in; in memory only when unset
+    pub path: Option<PathBuf>,
+    /// Days after which what was learned about a peer is ignored
+    pub max_age_days: u32,
+    /// Share of the learned bitrate a new call starts at, in percent
+    pub headroom_percent: u8,
+    /// Most peers remembered; the least recently updated are dropped
+    pub max_peers: usize,
+}
+
+impl Default for PeerQualityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            path: None,
+            max_age_days: 30,
+            headroom_percent: 80,
+            max_peers: 256,
+        }
+    }
+}
+
+/// What was learned about the path to one peer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerQuality {
+    /// Bitrate calls to the peer sustained, in kbps
+    pub bitrate_kbps: u32,
+    /// MIME type of the codec last used with the peer
+    pub codec: String,
+    /// Number of calls learned from
+    pub calls: u32,
+    /// When the last call ended
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Starting point for a new call to a known peer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QualitySeed {
+    /// Bitrate to start at, in kbps
+    pub bitrate_kbps: u32,
+    /// MIME type of the codec to prefer
+    pub codec: String,
+}
+
+/// Bitrate a call sustained, in kbps
+///
+/// The 90th percentile of the outbound bitrate over the samples with any
+/// traffic: what the path carried most of the time, ignoring short peaks.
+/// Returns `None` if nothing was sent.
+#[must_use]
+pub fn sustained_bitrate_kbps(samples: &[StatsSample]) -> Option<u32> {
+    let mut rates: Vec<f64> = samples
+        .iter()
+        .map(|sample| sample.send_bitrate_bps)
+        .filter(|rate| *rate > 0.0)
+        .collect();
+    if rates.is_empty() {
+        return None;
+    }
+    rates.sort_by(f64::total_cmp);
+    let index = (rates.len() - 1) * 9 / 10;
+    Some((rates[index] / 1000.0).round() as u32)
+}
+
+/// What is known about each peer
+#[derive(Debug, Default)]
+pub struct PeerQualityCache {
+    peers: HashMap<String, PeerQuality>,
+    path: Option<PathBuf>,
+}
+
+impl PeerQualityCache {
+    /// Create an empty cache kept in memory only
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open the cache kept in `path`, creating it on first update
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file exists but cannot be read or parsed
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, PeerQualityError> {
+        let path = path.into();
+        let peers = match std::fs::read(&path) {
+            Ok(data) => serde_json::from_slice(&data)
+                .map_err(|e| PeerQualityError::Storage(format!("{}: {e}", path.display())))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(PeerQualityError::Storage(e.to_string())),
+        };
+        Ok(Self {
+            peers,
+            path: Some(path),
+        })
+    }
+
+    /// File the cache is kept in, if any
+    #[must_use]
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// What is known about a peer, by its string representation
+    #[must_use]
+    pub fn get(&self, peer: &str) -> Option<&PeerQuality> {
+        self.peers.get(peer)
+    }
+
+    /// Number of peers known
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.peers.len()
+    }
+
+    /// Check if nothing is known
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.peers.is_empty()
+    }
+
+    /// Starting point for a call to `peer`, if anything recent is known
+    #[must_use]
+    pub fn seed(
+        &self,
+        peer: &str,
+        config: &PeerQualityConfig,
+        now: DateTime<Utc>,
+    ) -> Option<QualitySeed> {
+        let known = self.peers.get(peer)?;
+        if now - known.updated_at > chrono::Duration::days(i64::from(config.max_age_days)) {
+            return None;
+        }
+        let bitrate_kbps = u64::from(known.bitrate_kbps) * u64::from(config.headroom_percent) / 100;
+        Some(QualitySeed {
+            bitrate_kbps: u32::try_from(bitrate_kbps).unwrap_or(u32::MAX),
+            codec: known.codec.clone(),
+        })
+    }
+
+    /// Learn from a call to `peer` that sustained `bitrate_kbps`
+    ///
+    /// The bitrate is averaged with what was known, if that is still
+    /// recent. Returns what is now known.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the cache file cannot be written
+    pub fn learn(
+        &mut self,
+        peer: &str,
+        bitrate_kbps: u32,
+        codec: &str,
+        config: &PeerQualityConfig,
+        now: DateTime<Utc>,
+    ) -> Result<PeerQuality, PeerQualityError> {
+        let max_age = chrono::Duration::days(i64::from(config.max_age_days));
+        let quality = match self.peers.get(peer) {
+            Some(known) if now - known.updated_at <= max_age => PeerQuality {
+                bitrate_kbps: ((u64::from(known.bitrate_kbps) + u64::from(bitrate_kbps)) / 2)
+                    as u32,
+                codec: codec.to_string(),
+                calls: known.calls.saturating_add(1),
+                updated_at: now,
+            },
+            _ => PeerQuality {
+                bitrate_kbps,
+                codec: codec.to_string(),
+                calls: 1,
+                updated_at: now,
+            },
+        };
+        self.peers.insert(peer.to_string(), quality.clone());
+
+        // Drop expired peers, then the least recently updated
+        self.peers
+            .retain(|_, known| now - known.updated_at <= max_age);
+        while self.peers.len() > config.max_peers {
+            let oldest = self
+                .peers
+                .iter()
+                .min_by_key(|(_, known)| known.updated_at)
+                .map(|(peer, _)| peer.clone());
+            match oldest {
+                Some(oldest) => self.peers.remove(&oldest),
+                None => break,
+            };
+        }
+
+        self.save()?;
+        Ok(quality)
+    }
+
+    /// Forget a peer
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the cache file cannot be written
+    pub fn forget(&mut self, peer: &str) -> Result<bool, PeerQualityError> {
+        let removed = self.peers.remove(peer).is_some();
+        if removed {
+            self.save()?;
+        }
+        Ok(removed)
+    }
+
+    fn save(&self) -> Result<(), PeerQualityError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let storage_error = |e: std::io::Error| PeerQualityError::Storage(e.to_string());
+        let json = serde_json::to_vec_pretty(&self.peers)
+            .map_err(|e| PeerQualityError::Storage(e.to_string()))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(storage_error)?;
+        }
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json).map_err(storage_error)?;
+        std::fs::rename(tmp, path).map_err(storage_error)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn sample(send_bitrate_bps: f64) -> StatsSample {
+        StatsSample {
+            timestamp: Utc::now(),
+            packets_sent: 0,
+            packets_received: 0,
+            bytes_sent: 0,
+            bytes_received: 0,
+            stream_errors: 0,
+            send_bitrate_bps,
+            recv_bitrate_bps: 0.0,
+            send_packet_rate: 0.0,
+            recv_packet_rate: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_sustained_bitrate_ignores_peaks_and_silence() {
+        let mut samples: Vec<StatsSample> = (0..9).map(|_| sample(1_000_000.0)).collect();
+        samples.push(sample(5_000_000.0));
+        samples.push(sample(0.0));
+        assert_eq!(sustained_bitrate_kbps(&samples), Some(1000));
+        assert_eq!(sustained_bitrate_kbps(&[sample(0.0)]), None);
+    }
+
+    #[test]
+    fn test_learn_seed_and_persist() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("quality.json");
+        let config = PeerQualityConfig::default();
+        let now = Utc::now();
+
+        let mut cache = PeerQualityCache::open(&path).unwrap();
+        assert_eq!(cache.seed("alice", &config, now), None);
+        cache
+            .learn("alice", 2000, "video/H264", &config, now)
+            .unwrap();
+        let known = cache
+            .learn("alice", 1000, "video/H264", &config, now)
+            .unwrap();
+        assert_eq!(known.bitrate_kbps, 1500);
+        assert_eq!(known.calls, 2);
+
+        // Survives a restart
+        let cache = PeerQualityCache::open(&path).unwrap();
+        assert_eq!(
+            cache.seed("alice", &config, now),
+            Some(QualitySeed {
+                bitrate_kbps: 1200,
+                codec: "video/H264".to_string(),
+            })
+        );
+
+        // Too old to trust
+        let later = now + chrono::Duration::days(31);
+        assert_eq!(cache.seed("alice", &config, later), None);
+    }
+
+    #[test]
+    fn test_oldest_peers_dropped() {
+        let config = PeerQualityConfig {
+            max_peers: 2,
+            ..PeerQualityConfig::default()
+        };
+        let now = Utc::now();
+        let mut cache = PeerQualityCache::new();
+        for (i, peer) in ["a", "b", "c"].iter().enumerate() {
+            let at = now + chrono::Duration::seconds(i as i64);
+            cache.learn(peer, 100, "audio/opus", &config, at).unwrap();
+        }
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("a").is_none());
+        assert!(cache.forget("b").unwrap());
+        assert!(!cache.forget("b").unwrap());
+    }
+}
@@ -0,0 +1,494 @@
+//! Pre-encoded media file playback
+//!
+//! [`CallManager::play_media_file`](crate::call::CallManager::play_media_file)
+//! injects the packets of an encoded file into a call's send path, paced as
+//! they were recorded: hold music, announcements, or scripted media for
+//! automated tests. Nothing is decoded or re-encoded, so the file must
+//! already be in the call's codec.
+//!
+//! The format follows the file extension:
+//!
+//! - Ogg Opus (`.opus`, `.ogg`): the Opus packets of the first logical
+//!   stream, each lasting as long as its TOC byte says
+//! - H.264 Annex B (`.h264`, `.264`): NAL units behind start codes, grouped
+//!   into access units played at [`H264_FILE_FPS`]
+//!
+//! Audio goes out as Opus RTP packets and video through a
+//! [`VideoPacketizer`], each on a fresh SSRC.
+
+use crate::bot::{OPUS_CLOCK_RATE, OPUS_PAYLOAD_TYPE};
+use crate::packetizer::VideoPacketizer;
+use crate::quic_bridge::{RtpPacket, StreamType};
+use crate::quic_media_transport::QuicMediaTransport;
+use crate::timed_metadata::VIDEO_CLOCK_RATE;
+use crate::types::VideoLayer;
+use std::path::Path;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Payload type of H.264 video played from a file
+pub const H264_PAYLOAD_TYPE: u8 = 96;
+
+/// Frame rate H.264 files are played at; Annex B carries no timing
+pub const H264_FILE_FPS: u32 = 30;
+
+/// Length of an Ogg page header before its segment table
+const OGG_HEADER_LEN: usize = 27;
+
+/// Annex B start code written before each NAL unit
+const START_CODE: [u8; 4] = [0, 0, 0, 1];
+
+/// Playback errors
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PlaybackError {
+    /// Reading the file failed
+    #[error("I/O error: {0}")]
+    Io(String),
+
+    /// The file is not in the format its extension names
+    #[error("Invalid media file: {0}")]
+    Format(String),
+
+    /// The file extension names no supported format
+    #[error("Unsupported media file: {0}")]
+    Unsupported(String),
+
+    /// The call's media transport refused a packet; the rest are not sent
+    #[error("Playback stopped: {0}")]
+    Send(String),
+}
+
+impl From<std::io::Error> for PlaybackError {
+    fn from(err: std::io::Error) -> Self {
+        PlaybackError::Io(err.to_string())
+    }
+}
+
+/// Container and codec of a media file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaFileFormat {
+    /// Opus audio in Ogg
+    OggOpus,
+    /// H.264 video as an Annex B byte stream
+    H264,
+}
+
+impl MediaFileFormat {
+    /// Format named by a file's extension
+    #[must_use]
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "opus" | "ogg" => Some(Self::OggOpus),
+            "h264" | "264" => Some(Self::H264),
+            _ => None,
+        }
+    }
+}
+
+/// One encoded frame of a media file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileFrame {
+    /// When the frame plays, from the start of the file
+    pub offset: Duration,
+    /// Opus packet, or H.264 access unit with start codes
+    pub data: Vec<u8>,
+    /// The frame can be decoded on its own
+    pub keyframe: bool,
+}
+
+/// A demuxed media file ready to be played into a call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MediaFile {
+    format: MediaFileFormat,
+    frames: Vec<FileFrame>,
+    duration: Duration,
+}
+
+impl MediaFile {
+    /// Read and demux a file in the format its extension names
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the extension is not supported or the file cannot
+    /// be read or demuxed
+    pub fn open(path: &Path) -> Result<Self, PlaybackError> {
+        let format = MediaFileFormat::from_path(path)
+            .ok_or_else(|| PlaybackError::Unsupported(path.display().to_string()))?;
+        Self::parse(format, &std::fs::read(path)?)
+    }
+
+    /// Demux the contents of a file
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the data is malformed or holds no frames
+    pub fn parse(format: MediaFileFormat, data: &[u8]) -> Result<Self, PlaybackError> {
+        let mut frames = Vec::new();
+        let mut offset = Duration::ZERO;
+        match format {
+            MediaFileFormat::OggOpus => {
+                let packets = ogg_packets(data)?;
+                if !packets.first().is_some_and(|p| p.starts_with(b"OpusHead")) {
+                    return Err(PlaybackError::Format("missing OpusHead".to_string()));
+                }
+                // The first two packets are the ID and comment headers
+                for data in packets.into_iter().skip(2) {
+                    let duration = opus_packet_duration(&data).ok_or_else(|| {
+                        PlaybackError::Format("malformed Opus packet".to_string())
+                    })?;
+                    frames.push(FileFrame {
+                        offset,
+                        data,
+                        keyframe: true,
+                    });
+                    offset += duration;
+                }
+            }
+            MediaFileFormat::H264 => {
+                let frame_duration = Duration::from_secs(1) / H264_FILE_FPS;
+                for (data, keyframe) in access_units(data) {
+                    frames.push(FileFrame {
+                        offset,
+                        data,
+                        keyframe,
+                    });
+                    offset += frame_duration;
+                }
+            }
+        }
+        if frames.is_empty() {
+            return Err(PlaybackError::Format("no media frames".to_string()));
+        }
+        Ok(Self {
+            format,
+            frames,
+            duration: offset,
+        })
+    }
+
+    /// Format of the file
+    #[must_use]
+    pub fn format(&self) -> MediaFileFormat {
+        self.format
+    }
+
+    /// Frames in play order
+    #[must_use]
+    pub fn frames(&self) -> &[FileFrame] {
+        &self.frames
+    }
+
+    /// Playing time of the whole file
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Send the frames through a call's media transport at their pace
+    ///
+    /// Returns the number of frames sent once the last one is out.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error sending a packet; the rest are not sent
+    pub async fn play(&self, transport: &QuicMediaTransport) -> Result<u64, PlaybackError> {
+        let start = tokio::time::Instant::now();
+        let ssrc = rand::random();
+        let base_timestamp: u32 = rand::random();
+        let mut sequence: u16 = rand::random();
+        let mut packetizer = VideoPacketizer::new(ssrc, H264_PAYLOAD_TYPE);
+
+        let mut sent = 0;
+        for frame in &self.frames {
+            tokio::time::sleep_until(start + frame.offset).await;
+            match self.format {
+                MediaFileFormat::OggOpus => {
+                    let timestamp =
+                        base_timestamp.wrapping_add(ticks(frame.offset, OPUS_CLOCK_RATE));
+                    let bytes = RtpPacket::new(
+                        OPUS_PAYLOAD_TYPE,
+                        sequence,
+                        timestamp,
+                        ssrc,
+                        frame.data.clone(),
+                        StreamType::Audio,
+                    )
+                    .and_then(|packet| packet.to_bytes())
+                    .map_err(|e| PlaybackError::Format(e.to_string()))?;
+                    sequence = sequence.wrapping_add(1);
+                    transport
+                        .send_audio(&bytes)
+                        .await
+                        .map_err(|e| PlaybackError::Send(e.to_string()))?;
+                }
+                MediaFileFormat::H264 => {
+                    let timestamp =
+                        base_timestamp.wrapping_add(ticks(frame.offset, VIDEO_CLOCK_RATE));
+                    let packets = packetizer
+                        .packetize(&frame.data, timestamp, VideoLayer::BASE, frame.keyframe)
+                        .map_err(|e| PlaybackError::Format(e.to_string()))?;
+                    for packet in packets {
+                        let bytes = packet
+                            .to_bytes()
+                            .map_err(|e| PlaybackError::Format(e.to_string()))?;
+                        transport
+                            .send_video(&bytes)
+                            .await
+                            .map_err(|e| PlaybackError::Send(e.to_string()))?;
+                    }
+                }
+            }
+            sent += 1;
+        }
+        Ok(sent)
+    }
+}
+
+/// RTP timestamp ticks in `offset` at `clock_rate`
+pub(crate) fn ticks(offset: Duration, clock_rate: u32) -> u32 {
+    (offset.as_micros() * u128::from(clock_rate) / 1_000_000) as u32
+}
+
+/// Packets of the first logical stream of an Ogg file
+///
+/// Pages of other streams are skipped. Page checksums are not verified.
+fn ogg_packets(data: &[u8]) -> Result<Vec<Vec<u8>>, PlaybackError> {
+    let truncated = || PlaybackError::Format("truncated Ogg page".to_string());
+    let mut packets = Vec::new();
+    let mut partial = Vec::new();
+    let mut serial = None;
+    let mut pos = 0;
+    while pos < data.len() {
+        let header = data.get(pos..pos + OGG_HEADER_LEN).ok_or_else(truncated)?;
+        if !header.starts_with(b"OggS") {
+            return Err(PlaybackError::Format(
+                "missing Ogg capture pattern".to_string(),
+            ));
+        }
+        let page_serial = u32::from_le_bytes([header[14], header[15], header[16], header[17]]);
+        let segments = usize::from(header[26]);
+        let table = data
+            .get(pos + OGG_HEADER_LEN..pos + OGG_HEADER_LEN + segments)
+            .ok_or_else(truncated)?;
+        let mut body = pos + OGG_HEADER_LEN + segments;
+
+        let ours = *serial.get_or_insert(page_serial) == page_serial;
+        for &len in table {
+            let segment = data
+                .get(body..body + usize::from(len))
+                .ok_or_else(truncated)?;
+            body += usize::from(len);
+            if !ours {
+                continue;
+            }
+            // A packet ends with the first segment shorter than 255 bytes
+            partial.extend_from_slice(segment);
+            if len < 255 {
+                packets.push(std::mem::take(&mut partial));
+            }
+        }
+        pos = body;
+    }
+    Ok(packets)
+}
+
+/// Playing time of an Opus packet, from its TOC byte (RFC 6716 §3.1)
+fn opus_packet_duration(packet: &[u8]) -> Option<Duration> {
+    let toc = *packet.first()?;
+    let config = usize::from(toc >> 3);
+    let frame_us: u64 = match config {
+        // SILK
+        0..=11 => [10_000, 20_000, 40_000, 60_000][config % 4],
+        // Hybrid
+        12..=15 => [10_000, 20_000][config % 2],
+        // CELT
+        _ => [2_500, 5_000, 10_000, 20_000][config % 4],
+    };
+    let frames = match toc & 0x03 {
+        0 => 1,
+        1 | 2 => 2,
+        _ => u64::from(*packet.get(1)? & 0x3F),
+    };
+    Some(Duration::from_micros(frame_us * frames))
+}
+
+/// NAL units of an Annex B byte stream, without start codes
+fn nal_units(data: &[u8]) -> Vec<&[u8]> {
+    let mut starts = Vec::new();
+    let mut i = 0;
+    while i + 3 <= data.len() {
+        if data[i..i + 3] == [0, 0, 1] {
+            starts.push(i + 3);
+            i += 3;
+        } else {
+            i += 1;
+        }
+    }
+    starts
+        .iter()
+        .enumerate()
+        .map(|(n, &start)| {
+            let end = starts.get(n + 1).map_or(data.len(), |&next| next - 3);
+            let nal = &data[start..end];
+            // Trailing zeros belong to the next four-byte start code
+            let len = nal.iter().rposition(|&b| b != 0).map_or(0, |last| last + 1);
+            &nal[..len]
+        })
+        .filter(|nal| !nal.is_empty())
+        .collect()
+}
+
+/// Group NAL units into access units, flagging those holding an IDR slice
+///
+/// A unit ends before a delimiter, parameter set or SEI, or before the
+/// first slice of the next picture (`first_mb_in_slice` of zero).
+fn access_units(data: &[u8]) -> Vec<(Vec<u8>, bool)> {
+    let mut units = Vec::new();
+    let mut current = Vec::new();
+    let (mut has_slice, mut keyframe) = (false, false);
+    for nal in nal_units(data) {
+        let nal_type = nal[0] & 0x1F;
+        let slice = matches!(nal_type, 1 | 5);
+        let first_slice = slice && nal.get(1).is_some_and(|b| b & 0x80 != 0);
+        if has_slice && (first_slice || matches!(nal_type, 6..=9)) {
+            units.push((std::mem::take(&mut current), keyframe));
+            has_slice = false;
+            keyframe = false;
+        }
+        current.extend_from_slice(&START_CODE);
+        current.extend_from_slice(nal);
+        has_slice |= slice;
+        keyframe |= nal_type == 5;
+    }
+    if has_slice {
+        units.push((current, keyframe));
+    }
+    units
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::testing::{LoopbackHarness, Role};
+    use crate::types::MediaConstraints;
+
+    /// An Ogg page of `packets`, all of one logical stream
+    fn ogg_page(serial: u32, packets: &[&[u8]]) -> Vec<u8> {
+        let mut table = Vec::new();
+        let mut body = Vec::new();
+        for packet in packets {
+            let mut left = packet.len();
+            while left >= 255 {
+                table.push(255);
+                left -= 255;
+            }
+            table.push(left as u8);
+            body.extend_from_slice(packet);
+        }
+        let mut page = b"OggS".to_vec();
+        page.extend_from_slice(&[0; 10]);
+        page.extend_from_slice(&serial.to_le_bytes());
+        page.extend_from_slice(&[0; 8]);
+        page.push(table.len() as u8);
+        page.extend(table);
+        page.extend(body);
+        page
+    }
+
+    /// 20 ms CELT fullband frame, one per packet
+    const OPUS_20MS: u8 = 31 << 3;
+
+    fn ogg_opus(audio: &[&[u8]]) -> Vec<u8> {
+        let mut file = ogg_page(7, &[b"OpusHead\x01\x02"]);
+        // A page of another stream is skipped
+        file.extend(ogg_page(8, &[b"Theora"]));
+        file.extend(ogg_page(7, &[b"OpusTags"]));
+        file.extend(ogg_page(7, audio));
+        file
+    }
+
+    #[test]
+    fn test_ogg_opus_packets_and_timing() {
+        let long = [OPUS_20MS; 300];
+        let two_frames = [OPUS_20MS | 1, 0xAA];
+        let data = ogg_opus(&[&[OPUS_20MS, 1], &long, &two_frames]);
+        let file = MediaFile::parse(MediaFileFormat::OggOpus, &data).unwrap();
+
+        let offsets: Vec<u128> = file.frames().iter().map(|f| f.offset.as_millis()).collect();
+        assert_eq!(offsets, [0, 20, 40]);
+        assert_eq!(file.frames()[1].data.len(), 300);
+        assert_eq!(file.duration(), Duration::from_millis(80));
+
+        assert!(MediaFile::parse(MediaFileFormat::OggOpus, &data[..40]).is_err());
+        assert!(MediaFile::parse(MediaFileFormat::OggOpus, &ogg_page(1, &[b"x"])).is_err());
+        assert_eq!(
+            MediaFileFormat::from_path(Path::new("hold.OPUS")),
+            Some(MediaFileFormat::OggOpus)
+        );
+        assert_eq!(MediaFileFormat::from_path(Path::new("clip.mp4")), None);
+    }
+
+    #[test]
+    fn test_h264_access_units() {
+        let mut data = Vec::new();
+        // SPS, PPS and a two-slice IDR picture, then a P picture
+        for nal in [
+            &[0x67, 0x42][..],
+            &[0x68, 0xCE],
+            &[0x65, 0x88, 0x01],
+            &[0x65, 0x20, 0x02],
+            &[0x41, 0x9A, 0x03],
+        ] {
+            data.extend_from_slice(&START_CODE);
+            data.extend_from_slice(nal);
+        }
+        // Three-byte start codes work too
+        data.extend_from_slice(&[0, 0, 1, 0x41, 0x9A, 0x04]);
+
+        let file = MediaFile::parse(MediaFileFormat::H264, &data).unwrap();
+        let frames = file.frames();
+        assert_eq!(frames.len(), 3);
+        assert!(frames[0].keyframe && !frames[1].keyframe);
+        assert_eq!(nal_units(&frames[0].data).len(), 4);
+        assert_eq!(frames[2].data, [0, 0, 0, 1, 0x41, 0x9A, 0x04]);
+        assert_eq!(frames[1].offset, Duration::from_secs(1) / H264_FILE_FPS);
+
+        assert!(MediaFile::parse(MediaFileFormat::H264, &[0x67, 0x42]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_play_media_file_into_call() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hold.opus");
+        std::fs::write(
+            &path,
+            ogg_opus(&[&[OPUS_20MS, 1], &[OPUS_20MS, 2], &[OPUS_20MS, 3]]),
+        )
+        .unwrap();
+
+        let harness = LoopbackHarness::new().await.unwrap();
+        let call_id = harness
+            .connect_call(MediaConstraints::audio_only())
+            .await
+            .unwrap();
+        let manager = harness.caller().service().call_manager();
+        let playback = manager.play_media_file(call_id, &path).await.unwrap();
+        assert_eq!(playback.await.unwrap(), Ok(3));
+
+        let stats = manager
+            .media_transport(call_id)
+            .await
+            .unwrap()
+            .stats()
+            .await;
+        assert_eq!(stats.packets_sent, 3);
+
+        assert!(manager
+            .play_media_file(call_id, dir.path().join("clip.mp4"))
+            .await
+            .is_err());
+        harness.hang_up(Role::Caller, call_id).await.unwrap();
+    }
+}
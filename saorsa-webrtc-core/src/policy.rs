@@ -0,0 +1,243 @@
+//! Call admission policies
+//!
+//! A [`CallPolicy`] is consulted before a call is placed and before an
+//! incoming call is allowed to ring. Denials are logged, recorded in the
+//! audit log under the policy's [`name`](CallPolicy::name), and fail the
+//! call with [`CallError::PolicyDenied`]. A policy may also answer
+//! incoming calls without asking and vet data channel and file transfer
+//! requests.
+//!
+//! [`ContactPolicy`] applies the [`ContactPermissions`] kept with each
+//! contact of a [`ContactBook`].
+//!
+//! [`CallError::PolicyDenied`]: crate::call::CallError::PolicyDenied
+
+use crate::contacts::{ContactBook, ContactPermissions};
+use crate::identity::PeerIdentity;
+use crate::protocol_handler::AuthDecision;
+use crate::types::MediaConstraints;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+
+/// What a peer asks to open during a call
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataRequest {
+    /// A data channel
+    DataChannel,
+    /// A file transfer
+    FileTransfer,
+}
+
+/// Decides which calls may proceed
+///
+/// Peers are identified by their string form. Both checks allow every call
+/// unless overridden.
+#[async_trait]
+pub trait CallPolicy: fmt::Debug + Send + Sync {
+    /// Name recorded with the policy's decisions in the audit log
+    fn name(&self) -> &str {
+        "call_policy"
+    }
+
+    /// Decide whether to place a call to `peer`
+    async fn outgoing(&self, _peer: &str, _constraints: &MediaConstraints) -> AuthDecision {
+        AuthDecision::Allow
+    }
+
+    /// Decide whether a call from `peer` may ring
+    async fn incoming(&self, _peer: &str, _constraints: &MediaConstraints) -> AuthDecision {
+        AuthDecision::Allow
+    }
+
+    /// Decide whether a ringing call from `peer` is answered without asking
+    async fn auto_accept(&self, _peer: &str, _constraints: &MediaConstraints) -> bool {
+        false
+    }
+
+    /// Decide whether `peer` may open a data channel or send a file
+    async fn data_request(&self, _peer: &str, _request: DataRequest) -> AuthDecision {
+        AuthDecision::Allow
+    }
+}
+
+/// A policy shared between subsystems
+pub type SharedCallPolicy = Arc<dyn CallPolicy>;
+
+/// Applies the permissions of contacts in a contact book
+///
+/// Peers not in the book get the `strangers` permissions; with
+/// `strangers_may_call` off their calls do not ring at all. The book is
+/// shared, so permission changes apply to the next call or request.
+pub struct ContactPolicy<I: PeerIdentity> {
+    contacts: Arc<parking_lot::RwLock<ContactBook<I>>>,
+    strangers: ContactPermissions,
+    strangers_may_call: bool,
+}
+
+impl<I: PeerIdentity> fmt::Debug for ContactPolicy<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ContactPolicy")
+            .field("contacts", &self.contacts.read().len())
+            .field("strangers", &self.strangers)
+            .field("strangers_may_call", &self.strangers_may_call)
+            .finish()
+    }
+}
+
+impl<I: PeerIdentity> ContactPolicy<I> {
+    /// Policy over a contact book; strangers may call with the default
+    /// permissions
+    #[must_use]
+    pub fn new(contacts: Arc<parking_lot::RwLock<ContactBook<I>>>) -> Self {
+        Self {
+            contacts,
+            strangers: ContactPermissions::default(),
+            strangers_may_call: true,
+        }
+    }
+
+    /// Permissions of peers not in the book
+    #[must_use]
+    pub fn with_strangers(mut self, permissions: ContactPermissions) -> Self {
+        self.strangers = permissions;
+        self
+    }
+
+    /// Only let calls from contacts ring
+    #[must_use]
+    pub fn contacts_only(mut self) -> Self {
+        self.strangers_may_call = false;
+        self
+    }
+
+    /// Permissions that apply to `peer`, `None` for a stranger
+    fn permissions(&self, peer: &str) -> Option<ContactPermissions> {
+        self.contacts
+            .read()
+            .find_by_repr(peer)
+            .map(|contact| contact.permissions)
+    }
+}
+
+#[async_trait]
+impl<I: PeerIdentity> CallPolicy for ContactPolicy<I> {
+    fn name(&self) -> &str {
+        "contact_policy"
+    }
+
+    async fn incoming(&self, peer: &str, constraints: &MediaConstraints) -> AuthDecision {
+        let permissions = match self.permissions(peer) {
+            Some(permissions) => permissions,
+            None if self.strangers_may_call => self.strangers,
+            None => return AuthDecision::Deny("caller is not a contact".to_string()),
+        };
+        if (constraints.video || constraints.screen_share) && !permissions.video_allowed {
+            return AuthDecision::Deny("video calls not allowed from this peer".to_string());
+        }
+        AuthDecision::Allow
+    }
+
+    async fn auto_accept(&self, peer: &str, _constraints: &MediaConstraints) -> bool {
+        // Strangers are never answered unasked
+        self.permissions(peer)
+            .is_some_and(|permissions| permissions.auto_accept)
+    }
+
+    async fn data_request(&self, peer: &str, request: DataRequest) -> AuthDecision {
+        let known = self.permissions(peer);
+        let permissions = known.unwrap_or(self.strangers);
+        match request {
+            DataRequest::DataChannel if known.is_some() || self.strangers_may_call => {
+                AuthDecision::Allow
+            }
+            DataRequest::DataChannel => {
+                AuthDecision::Deny("data channels only from contacts".to_string())
+            }
+            DataRequest::FileTransfer if permissions.file_transfer_allowed => AuthDecision::Allow,
+            DataRequest::FileTransfer => {
+                AuthDecision::Deny("file transfer not allowed from this peer".to_string())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::contacts::Contact;
+    use crate::identity::PeerIdentityString;
+
+    fn policy() -> ContactPolicy<PeerIdentityString> {
+        let mut book = ContactBook::new();
+        let mut alice = Contact::new("Alice", PeerIdentityString::new("alice"));
+        alice.permissions = ContactPermissions {
+            auto_accept: true,
+            video_allowed: true,
+            file_transfer_allowed: true,
+        };
+        book.add(alice).unwrap();
+        let mut bob = Contact::new("Bob", PeerIdentityString::new("bob"));
+        bob.permissions.video_allowed = false;
+        book.add(bob).unwrap();
+        ContactPolicy::new(Arc::new(parking_lot::RwLock::new(book)))
+    }
+
+    #[tokio::test]
+    async fn test_contact_permissions_apply() {
+        let policy = policy();
+        let video = MediaConstraints::video_call();
+        let audio = MediaConstraints::audio_only();
+
+        assert_eq!(policy.incoming("alice", &video).await, AuthDecision::Allow);
+        assert!(policy.auto_accept("alice", &video).await);
+        assert!(matches!(
+            policy.incoming("bob", &video).await,
+            AuthDecision::Deny(_)
+        ));
+        assert_eq!(policy.incoming("bob", &audio).await, AuthDecision::Allow);
+        assert!(!policy.auto_accept("bob", &audio).await);
+
+        assert_eq!(
+            policy
+                .data_request("alice", DataRequest::FileTransfer)
+                .await,
+            AuthDecision::Allow
+        );
+        assert!(matches!(
+            policy.data_request("bob", DataRequest::FileTransfer).await,
+            AuthDecision::Deny(_)
+        ));
+        assert_eq!(
+            policy.data_request("bob", DataRequest::DataChannel).await,
+            AuthDecision::Allow
+        );
+    }
+
+    #[tokio::test]
+    async fn test_strangers() {
+        let audio = MediaConstraints::audio_only();
+        let open = policy().with_strangers(ContactPermissions {
+            auto_accept: true,
+            ..Default::default()
+        });
+        assert_eq!(open.incoming("mallory", &audio).await, AuthDecision::Allow);
+        assert!(!open.auto_accept("mallory", &audio).await);
+
+        let closed = policy().contacts_only();
+        assert!(matches!(
+            closed.incoming("mallory", &audio).await,
+            AuthDecision::Deny(_)
+        ));
+        assert!(matches!(
+            closed
+                .data_request("mallory", DataRequest::DataChannel)
+                .await,
+            AuthDecision::Deny(_)
+        ));
+        assert_eq!(closed.incoming("alice", &audio).await, AuthDecision::Allow);
+    }
+}
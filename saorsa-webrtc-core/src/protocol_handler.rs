@@ -9,12 +9,16 @@ use ant_quic::{
 use async_trait::async_trait;
 use bytes::Bytes;
 use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
 use thiserror::Error;
 use tokio::sync::{mpsc, RwLock};
 use tracing::{debug, error, trace, warn};
 
 use crate::quic_bridge::RtpPacket;
+use crate::redact;
 use crate::signaling::SignalingMessage;
+use crate::wire_format::{decode_frame, WireFrame};
 
 /// Errors specific to WebRTC protocol handling.
 #[derive(Debug, Error)]
@@ -34,6 +38,54 @@ pub enum WebRtcHandlerError {
     /// Channel send error.
     #[error("failed to send to channel: {0}")]
     ChannelSend(String),
+
+    /// Sub-protocol identifier is empty or longer than 255 bytes.
+    #[error("invalid sub-protocol id: {0:?}")]
+    InvalidSubProtocol(String),
+}
+
+/// Data channel ID reserved for sub-protocol frames.
+///
+/// Frames on this channel carry a length-prefixed sub-protocol identifier
+/// followed by the payload: `[u8 id_len][id][payload]`.
+pub const SUBPROTOCOL_CHANNEL_ID: u32 = u32::MAX;
+
+/// Handler for a logical service multiplexed over the WebRTC data stream.
+///
+/// Sub-protocols (presence, file transfer, ...) are registered by id on
+/// [`WebRtcProtocolHandlerBuilder::subprotocol`] and receive only the frames
+/// addressed to them, much like ALPN selects a protocol on a TLS connection.
+#[async_trait]
+pub trait SubProtocolHandler: Send + Sync {
+    /// Handle a payload addressed to this sub-protocol.
+    ///
+    /// Returns an optional response to send back on the stream.
+    async fn handle(&self, peer: PeerId, data: Bytes) -> TransportResult<Option<Bytes>>;
+}
+
+/// Encode a payload for a sub-protocol.
+///
+/// The result is a complete data stream frame, including the reserved
+/// channel ID prefix.
+///
+/// # Errors
+///
+/// Returns error if the protocol id is empty or longer than 255 bytes.
+pub fn encode_subprotocol_frame(
+    protocol: &str,
+    payload: &[u8],
+) -> Result<Bytes, WebRtcHandlerError> {
+    let id_len = u8::try_from(protocol.len())
+        .ok()
+        .filter(|len| *len > 0)
+        .ok_or_else(|| WebRtcHandlerError::InvalidSubProtocol(protocol.to_string()))?;
+
+    let mut frame = Vec::with_capacity(5 + protocol.len() + payload.len());
+    frame.extend_from_slice(&SUBPROTOCOL_CHANNEL_ID.to_be_bytes());
+    frame.push(id_len);
+    frame.extend_from_slice(protocol.as_bytes());
+    frame.extend_from_slice(payload);
+    Ok(Bytes::from(frame))
 }
 
 /// Incoming WebRTC message types.
@@ -64,8 +116,31 @@ pub enum WebRtcIncoming {
     },
 }
 
+/// Outcome of an incoming connection authorization check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthDecision {
+    /// Accept the peer's session.
+    Allow,
+    /// Reject the peer with a reason.
+    Deny(String),
+}
+
+/// Authorization hook for incoming WebRTC sessions.
+///
+/// Invoked once per peer before the first stream or datagram from that peer
+/// is accepted, so embedders can enforce authentication and ACLs at the
+/// transport boundary rather than after call setup.
+#[async_trait]
+pub trait ConnectionAuthorizer: Send + Sync {
+    /// Decide whether to accept a session from `peer`.
+    ///
+    /// `remote_addr` is the peer's address if it has been recorded with
+    /// [`WebRtcProtocolHandler::note_remote_addr`].
+    async fn authorize(&self, peer: &PeerId, remote_addr: Option<SocketAddr>) -> AuthDecision;
+}
+
 /// Configuration for the WebRTC protocol handler.
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct WebRtcHandlerConfig {
     /// Buffer size for incoming signal messages.
     pub signal_buffer_size: usize,
@@ -73,6 +148,19 @@ pub struct WebRtcHandlerConfig {
     pub media_buffer_size: usize,
     /// Buffer size for incoming data channel messages.
     pub data_buffer_size: usize,
+    /// Optional authorization hook for incoming sessions.
+    pub authorizer: Option<Arc<dyn ConnectionAuthorizer>>,
+}
+
+impl std::fmt::Debug for WebRtcHandlerConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebRtcHandlerConfig")
+            .field("signal_buffer_size", &self.signal_buffer_size)
+            .field("media_buffer_size", &self.media_buffer_size)
+            .field("data_buffer_size", &self.data_buffer_size)
+            .field("authorizer", &self.authorizer.is_some())
+            .finish()
+    }
 }
 
 impl Default for WebRtcHandlerConfig {
@@ -81,6 +169,7 @@ impl Default for WebRtcHandlerConfig {
             signal_buffer_size: 256,
             media_buffer_size: 1024,
             data_buffer_size: 512,
+            authorizer: None,
         }
     }
 }
@@ -103,6 +192,15 @@ pub struct WebRtcProtocolHandler {
     /// Per-peer session state.
     sessions: RwLock<HashMap<PeerId, PeerSession>>,
 
+    /// Registered sub-protocol handlers by id.
+    subprotocols: HashMap<String, Arc<dyn SubProtocolHandler>>,
+
+    /// Authorization hook for new peer sessions.
+    authorizer: Option<Arc<dyn ConnectionAuthorizer>>,
+
+    /// Known remote addresses, passed to the authorizer.
+    remote_addrs: RwLock<HashMap<PeerId, SocketAddr>>,
+
     /// Shutdown flag.
     shutdown: RwLock<bool>,
 }
@@ -139,6 +237,9 @@ impl WebRtcProtocolHandler {
             media_tx,
             data_tx,
             sessions: RwLock::new(HashMap::new()),
+            subprotocols: HashMap::new(),
+            authorizer: config.authorizer,
+            remote_addrs: RwLock::new(HashMap::new()),
             shutdown: RwLock::new(false),
         };
 
@@ -157,16 +258,24 @@ impl WebRtcProtocolHandler {
 
     /// Handle incoming signaling message.
     async fn handle_signal(&self, peer: PeerId, data: Bytes) -> TransportResult<Option<Bytes>> {
-        trace!(peer = ?peer, size = data.len(), "Processing WebRTC signal");
+        trace!(peer = %redact::identity(format_args!("{peer:?}")), size = data.len(), "Processing WebRTC signal");
 
-        // Deserialize the signaling message
-        let message: SignalingMessage = serde_json::from_slice(&data).map_err(|e| {
+        // Deserialize the signaling message in whichever wire format the peer used
+        let frame = decode_frame(&data).map_err(|e| {
             TransportError::Internal(format!("Failed to deserialize signaling message: {}", e))
         })?;
+        let message = match frame {
+            WireFrame::Message(message, _) => message,
+            WireFrame::Hello(hello) => {
+                debug!(peer = %redact::identity(format_args!("{peer:?}")), version = hello.version, "Ignoring protocol hello");
+                return Ok(None);
+            }
+        };
 
         debug!(
-            peer = ?peer,
+            peer = %redact::identity(format_args!("{peer:?}")),
             session_id = %message.session_id(),
+            call_id = message.call_id().map(tracing::field::display),
             "Received signaling message"
         );
 
@@ -192,7 +301,7 @@ impl WebRtcProtocolHandler {
 
     /// Handle incoming media packet.
     async fn handle_media(&self, peer: PeerId, data: Bytes) -> TransportResult<Option<Bytes>> {
-        trace!(peer = ?peer, size = data.len(), "Processing WebRTC media");
+        trace!(peer = %redact::identity(format_args!("{peer:?}")), size = data.len(), "Processing WebRTC media");
 
         // Deserialize the RTP packet
         let packet = RtpPacket::from_bytes(&data).map_err(|e| {
@@ -200,7 +309,7 @@ impl WebRtcProtocolHandler {
         })?;
 
         trace!(
-            peer = ?peer,
+            peer = %redact::identity(format_args!("{peer:?}")),
             stream_type = ?packet.stream_type,
             seq = packet.sequence_number,
             "Received media packet"
@@ -221,7 +330,7 @@ impl WebRtcProtocolHandler {
         {
             Ok(()) => {}
             Err(mpsc::error::TrySendError::Full(_)) => {
-                warn!(peer = ?peer, "Media channel full, dropping packet");
+                warn!(peer = %redact::identity(format_args!("{peer:?}")), "Media channel full, dropping packet");
             }
             Err(mpsc::error::TrySendError::Closed(_)) => {
                 return Err(TransportError::Shutdown);
@@ -234,7 +343,7 @@ impl WebRtcProtocolHandler {
 
     /// Handle incoming data channel message.
     async fn handle_data(&self, peer: PeerId, data: Bytes) -> TransportResult<Option<Bytes>> {
-        trace!(peer = ?peer, size = data.len(), "Processing WebRTC data");
+        trace!(peer = %redact::identity(format_args!("{peer:?}")), size = data.len(), "Processing WebRTC data");
 
         // Data channel format: 4-byte channel ID + payload
         if data.len() < 4 {
@@ -246,8 +355,12 @@ impl WebRtcProtocolHandler {
         let channel_id = u32::from_be_bytes([data[0], data[1], data[2], data[3]]);
         let payload = data.slice(4..);
 
+        if channel_id == SUBPROTOCOL_CHANNEL_ID {
+            return self.handle_subprotocol(peer, payload).await;
+        }
+
         debug!(
-            peer = ?peer,
+            peer = %redact::identity(format_args!("{peer:?}")),
             channel_id = channel_id,
             payload_size = payload.len(),
             "Received data channel message"
@@ -280,6 +393,83 @@ impl WebRtcProtocolHandler {
         Ok(None)
     }
 
+    /// Route a sub-protocol frame to its registered handler.
+    async fn handle_subprotocol(
+        &self,
+        peer: PeerId,
+        data: Bytes,
+    ) -> TransportResult<Option<Bytes>> {
+        let id_len = usize::from(
+            *data
+                .first()
+                .ok_or_else(|| TransportError::Internal("Sub-protocol frame missing id".into()))?,
+        );
+        if data.len() < 1 + id_len {
+            return Err(TransportError::Internal(
+                "Sub-protocol frame too short".into(),
+            ));
+        }
+
+        let protocol = std::str::from_utf8(&data[1..=id_len])
+            .map_err(|e| TransportError::Internal(format!("Invalid sub-protocol id: {}", e)))?;
+        let handler = self.subprotocols.get(protocol).ok_or_else(|| {
+            TransportError::Internal(format!("Unknown sub-protocol: {}", protocol))
+        })?;
+
+        trace!(peer = %redact::identity(format_args!("{peer:?}")), protocol = protocol, "Routing sub-protocol frame");
+
+        {
+            let mut sessions = self.sessions.write().await;
+            let session = sessions.entry(peer).or_default();
+            session.messages_received += 1;
+            session.last_activity = Some(std::time::Instant::now());
+        }
+
+        handler.handle(peer, data.slice(1 + id_len..)).await
+    }
+
+    /// Record the remote address of a peer for use by the authorizer.
+    ///
+    /// The `ProtocolHandler` interface does not carry remote addresses, so
+    /// embedders that know them (e.g. from connection events) can supply them
+    /// here before the peer's first stream arrives.
+    pub async fn note_remote_addr(&self, peer: PeerId, addr: SocketAddr) {
+        self.remote_addrs.write().await.insert(peer, addr);
+    }
+
+    /// Run the authorization hook for a peer without an active session.
+    ///
+    /// Allowed peers get a session, so the hook runs once per session.
+    async fn ensure_authorized(&self, peer: PeerId) -> TransportResult<()> {
+        let Some(authorizer) = &self.authorizer else {
+            return Ok(());
+        };
+        if self.sessions.read().await.contains_key(&peer) {
+            return Ok(());
+        }
+
+        let remote_addr = self.remote_addrs.read().await.get(&peer).copied();
+        match authorizer.authorize(&peer, remote_addr).await {
+            AuthDecision::Allow => {
+                debug!(peer = %redact::identity(format_args!("{peer:?}")), "Authorized WebRTC session");
+                self.sessions.write().await.entry(peer).or_default();
+                Ok(())
+            }
+            AuthDecision::Deny(reason) => {
+                warn!(peer = %redact::identity(format_args!("{peer:?}")), reason = %reason, "Rejected unauthorized WebRTC session");
+                Err(TransportError::ConnectionFailed(format!(
+                    "Peer not authorized: {}",
+                    reason
+                )))
+            }
+        }
+    }
+
+    /// Get the ids of registered sub-protocols.
+    pub fn subprotocols(&self) -> Vec<&str> {
+        self.subprotocols.keys().map(String::as_str).collect()
+    }
+
     /// Get number of active sessions.
     pub async fn session_count(&self) -> usize {
         self.sessions.read().await.len()
@@ -297,7 +487,7 @@ impl WebRtcProtocolHandler {
     pub async fn remove_session(&self, peer: &PeerId) {
         let mut sessions = self.sessions.write().await;
         if sessions.remove(peer).is_some() {
-            debug!(peer = ?peer, "Removed WebRTC session");
+            debug!(peer = %redact::identity(format_args!("{peer:?}")), "Removed WebRTC session");
         }
     }
 }
@@ -319,6 +509,8 @@ impl ProtocolHandler for WebRtcProtocolHandler {
             return Err(TransportError::Shutdown);
         }
 
+        self.ensure_authorized(peer).await?;
+
         match stream_type {
             StreamType::WebRtcSignal => self.handle_signal(peer, data).await,
             StreamType::WebRtcMedia => self.handle_media(peer, data).await,
@@ -341,7 +533,12 @@ impl ProtocolHandler for WebRtcProtocolHandler {
     ) -> TransportResult<()> {
         // Datagrams are used for unreliable media (e.g., low-priority video frames)
         if stream_type == StreamType::WebRtcMedia {
-            trace!(peer = ?peer, size = data.len(), "Received media datagram");
+            trace!(peer = %redact::identity(format_args!("{peer:?}")), size = data.len(), "Received media datagram");
+
+            // Drop datagrams from unauthorized peers without failing
+            if self.ensure_authorized(peer).await.is_err() {
+                return Ok(());
+            }
 
             // Try to deserialize and forward, but do not fail on errors for datagrams
             if let Ok(packet) = RtpPacket::from_bytes(&data) {
@@ -361,6 +558,7 @@ impl ProtocolHandler for WebRtcProtocolHandler {
 
         // Clear sessions
         self.sessions.write().await.clear();
+        self.remote_addrs.write().await.clear();
 
         Ok(())
     }
@@ -373,6 +571,7 @@ impl ProtocolHandler for WebRtcProtocolHandler {
 /// Builder for creating WebRtcProtocolHandler with custom configuration.
 pub struct WebRtcProtocolHandlerBuilder {
     config: WebRtcHandlerConfig,
+    subprotocols: HashMap<String, Arc<dyn SubProtocolHandler>>,
 }
 
 impl WebRtcProtocolHandlerBuilder {
@@ -380,9 +579,28 @@ impl WebRtcProtocolHandlerBuilder {
     pub fn new() -> Self {
         Self {
             config: WebRtcHandlerConfig::default(),
+            subprotocols: HashMap::new(),
         }
     }
 
+    /// Register a handler for a sub-protocol.
+    ///
+    /// Ids must be 1-255 bytes; invalid ids are ignored with a warning.
+    /// Registering the same id twice replaces the earlier handler.
+    pub fn subprotocol(
+        mut self,
+        protocol: impl Into<String>,
+        handler: Arc<dyn SubProtocolHandler>,
+    ) -> Self {
+        let protocol = protocol.into();
+        if protocol.is_empty() || protocol.len() > usize::from(u8::MAX) {
+            warn!(protocol = %protocol, "Ignoring sub-protocol with invalid id");
+            return self;
+        }
+        self.subprotocols.insert(protocol, handler);
+        self
+    }
+
     /// Set signal buffer size.
     pub fn signal_buffer_size(mut self, size: usize) -> Self {
         self.config.signal_buffer_size = size;
@@ -401,6 +619,12 @@ impl WebRtcProtocolHandlerBuilder {
         self
     }
 
+    /// Set the authorization hook for incoming sessions.
+    pub fn authorizer(mut self, authorizer: Arc<dyn ConnectionAuthorizer>) -> Self {
+        self.config.authorizer = Some(authorizer);
+        self
+    }
+
     /// Build the handler and return receivers.
     pub fn build(
         self,
@@ -410,7 +634,9 @@ impl WebRtcProtocolHandlerBuilder {
         mpsc::Receiver<WebRtcIncoming>,
         mpsc::Receiver<WebRtcIncoming>,
     ) {
-        WebRtcProtocolHandler::new(self.config)
+        let (mut handler, signal_rx, media_rx, data_rx) = WebRtcProtocolHandler::new(self.config);
+        handler.subprotocols = self.subprotocols;
+        (handler, signal_rx, media_rx, data_rx)
     }
 }
 
@@ -476,6 +702,42 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_handle_signal_binary_wire_formats() {
+        let (handler, mut signal_rx, _, _) = WebRtcProtocolHandler::with_defaults();
+
+        let peer = PeerId::from([1u8; 32]);
+        let message = SignalingMessage::ConnectionReady {
+            session_id: "compact-session".to_string(),
+        };
+
+        for format in [
+            crate::wire_format::WireFormat::Cbor,
+            crate::wire_format::WireFormat::Postcard,
+        ] {
+            let data = Bytes::from(format.encode(&message).unwrap());
+            handler
+                .handle_stream(peer, StreamType::WebRtcSignal, data)
+                .await
+                .unwrap();
+
+            match signal_rx.try_recv().unwrap() {
+                WebRtcIncoming::Signal { message: m, .. } => assert_eq!(m, message),
+                _ => panic!("Expected Signal message"),
+            }
+        }
+
+        // Hello frames are consumed without being forwarded
+        let hello = crate::wire_format::ProtocolHello::default()
+            .encode()
+            .unwrap();
+        handler
+            .handle_stream(peer, StreamType::WebRtcSignal, Bytes::from(hello))
+            .await
+            .unwrap();
+        assert!(signal_rx.try_recv().is_err());
+    }
+
     #[tokio::test]
     async fn test_handle_media_packet() {
         let (handler, _, mut media_rx, _) = WebRtcProtocolHandler::with_defaults();
@@ -621,6 +883,196 @@ mod tests {
         assert_eq!(handler.name(), "WebRtcProtocolHandler");
     }
 
+    struct EchoProtocol(&'static str);
+
+    #[async_trait]
+    impl SubProtocolHandler for EchoProtocol {
+        async fn handle(&self, _peer: PeerId, data: Bytes) -> TransportResult<Option<Bytes>> {
+            let mut response = self.0.as_bytes().to_vec();
+            response.extend_from_slice(&data);
+            Ok(Some(Bytes::from(response)))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subprotocol_routing() {
+        let (handler, _, _, mut data_rx) = WebRtcProtocolHandlerBuilder::new()
+            .subprotocol("presence/1", Arc::new(EchoProtocol("presence:")))
+            .subprotocol("file-transfer/1", Arc::new(EchoProtocol("file:")))
+            .build();
+
+        let mut ids = handler.subprotocols();
+        ids.sort_unstable();
+        assert_eq!(ids, vec!["file-transfer/1", "presence/1"]);
+
+        let peer = PeerId::from([9u8; 32]);
+        let frame = encode_subprotocol_frame("presence/1", b"online").unwrap();
+        let response = handler
+            .handle_stream(peer, StreamType::WebRtcData, frame)
+            .await
+            .unwrap();
+        assert_eq!(response, Some(Bytes::from_static(b"presence:online")));
+
+        let frame = encode_subprotocol_frame("file-transfer/1", b"chunk").unwrap();
+        let response = handler
+            .handle_stream(peer, StreamType::WebRtcData, frame)
+            .await
+            .unwrap();
+        assert_eq!(response, Some(Bytes::from_static(b"file:chunk")));
+
+        // Sub-protocol frames are not forwarded as data channel messages
+        assert!(data_rx.try_recv().is_err());
+        assert_eq!(handler.get_session_stats(&peer).await.unwrap().0, 2);
+    }
+
+    #[tokio::test]
+    async fn test_subprotocol_unknown_and_malformed() {
+        let (handler, _, _, _) = WebRtcProtocolHandlerBuilder::new()
+            .subprotocol("presence/1", Arc::new(EchoProtocol("")))
+            .build();
+        let peer = PeerId::from([9u8; 32]);
+
+        let frame = encode_subprotocol_frame("chat/1", b"hi").unwrap();
+        assert!(handler
+            .handle_stream(peer, StreamType::WebRtcData, frame)
+            .await
+            .is_err());
+
+        // Declared id length runs past the end of the frame
+        let mut frame = SUBPROTOCOL_CHANNEL_ID.to_be_bytes().to_vec();
+        frame.extend_from_slice(&[20, b'p']);
+        assert!(handler
+            .handle_stream(peer, StreamType::WebRtcData, Bytes::from(frame))
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_subprotocol_invalid_ids() {
+        assert!(matches!(
+            encode_subprotocol_frame("", b""),
+            Err(WebRtcHandlerError::InvalidSubProtocol(_))
+        ));
+        assert!(matches!(
+            encode_subprotocol_frame(&"x".repeat(256), b""),
+            Err(WebRtcHandlerError::InvalidSubProtocol(_))
+        ));
+
+        let (handler, _, _, _) = WebRtcProtocolHandlerBuilder::new()
+            .subprotocol("", Arc::new(EchoProtocol("")))
+            .build();
+        assert!(handler.subprotocols().is_empty());
+    }
+
+    struct AllowList {
+        allowed: Vec<PeerId>,
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl ConnectionAuthorizer for AllowList {
+        async fn authorize(&self, peer: &PeerId, remote_addr: Option<SocketAddr>) -> AuthDecision {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if remote_addr.is_some_and(|addr| addr.ip().is_loopback())
+                || self.allowed.contains(peer)
+            {
+                AuthDecision::Allow
+            } else {
+                AuthDecision::Deny("not on allow list".to_string())
+            }
+        }
+    }
+
+    fn ready_signal() -> Bytes {
+        let message = SignalingMessage::ConnectionReady {
+            session_id: "auth".to_string(),
+        };
+        Bytes::from(serde_json::to_vec(&message).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_authorizer_allows_and_denies() {
+        let allowed = PeerId::from([10u8; 32]);
+        let denied = PeerId::from([11u8; 32]);
+        let authorizer = Arc::new(AllowList {
+            allowed: vec![allowed],
+            calls: std::sync::atomic::AtomicUsize::new(0),
+        });
+        let (handler, mut signal_rx, _, _) = WebRtcProtocolHandlerBuilder::new()
+            .authorizer(authorizer.clone())
+            .build();
+
+        let result = handler
+            .handle_stream(denied, StreamType::WebRtcSignal, ready_signal())
+            .await;
+        assert!(matches!(result, Err(TransportError::ConnectionFailed(_))));
+        assert!(signal_rx.try_recv().is_err());
+        assert_eq!(handler.session_count().await, 0);
+
+        for _ in 0..2 {
+            handler
+                .handle_stream(allowed, StreamType::WebRtcSignal, ready_signal())
+                .await
+                .unwrap();
+            assert!(signal_rx.try_recv().is_ok());
+        }
+
+        // Authorized once per session, denied peers are re-checked
+        assert_eq!(
+            authorizer.calls.load(std::sync::atomic::Ordering::SeqCst),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_authorizer_receives_remote_addr() {
+        let peer = PeerId::from([12u8; 32]);
+        let authorizer = Arc::new(AllowList {
+            allowed: Vec::new(),
+            calls: std::sync::atomic::AtomicUsize::new(0),
+        });
+        let (handler, mut signal_rx, _, _) = WebRtcProtocolHandlerBuilder::new()
+            .authorizer(authorizer)
+            .build();
+
+        handler
+            .note_remote_addr(peer, "127.0.0.1:9000".parse().unwrap())
+            .await;
+        handler
+            .handle_stream(peer, StreamType::WebRtcSignal, ready_signal())
+            .await
+            .unwrap();
+        assert!(signal_rx.try_recv().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_authorizer_drops_unauthorized_datagrams() {
+        let peer = PeerId::from([13u8; 32]);
+        let authorizer = Arc::new(AllowList {
+            allowed: Vec::new(),
+            calls: std::sync::atomic::AtomicUsize::new(0),
+        });
+        let (handler, _, mut media_rx, _) = WebRtcProtocolHandlerBuilder::new()
+            .authorizer(authorizer)
+            .build();
+
+        let packet = RtpPacket::new(
+            96,
+            1,
+            1,
+            1,
+            vec![0; 4],
+            crate::quic_bridge::StreamType::Audio,
+        )
+        .unwrap();
+        let data = Bytes::from(packet.to_bytes().unwrap());
+        handler
+            .handle_datagram(peer, StreamType::WebRtcMedia, data)
+            .await
+            .unwrap();
+        assert!(media_rx.try_recv().is_err());
+    }
+
     #[tokio::test]
     async fn test_invalid_signal_message() {
         let (handler, _, _, _) = WebRtcProtocolHandler::with_defaults();
@@ -0,0 +1,207 @@
+//! Network quality monitoring and audio-only fallback
+//!
+//! On a poor network video is the first thing to go: it needs far more
+//! bandwidth than audio and suffers visibly from loss. Each call keeps a
+//! [`QualityMonitor`] fed with [`CallQualityMetrics`]. When packet loss stays
+//! above [`DegradationConfig::max_loss_percent`], or bandwidth below
+//! [`DegradationConfig::min_bandwidth_kbps`], for
+//! [`DegradationConfig::sustain_samples`] samples in a row, outgoing video is
+//! suspended and the call carries audio only. Video comes back once the
+//! network has stayed within the stricter recovery thresholds for
+//! [`DegradationConfig::recovery_samples`] samples.
+//!
+//! The gap between the degrade and recovery thresholds keeps a network on
+//! the edge from switching video on and off every few seconds.
+//!
+//! Bandwidth and packet rate can come from the call's own media transport:
+//! [`CallQualityMetrics::from_transport_stats`] builds a sample from its
+//! windowed rates, and `report_transport_quality` feeds one to the monitor.
+
+use crate::types::CallQualityMetrics;
+use serde::{Deserialize, Serialize};
+
+/// Audio-only fallback configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DegradationConfig {
+    /// Suspend video on poor networks
+    pub enabled: bool,
+    /// Packet loss percentage above which video is suspended
+    pub max_loss_percent: f32,
+    /// Bandwidth in kbps below which video is suspended
+    pub min_bandwidth_kbps: u32,
+    /// Consecutive poor samples before suspending video
+    pub sustain_samples: u32,
+    /// Packet loss percentage below which video may resume
+    pub recovery_loss_percent: f32,
+    /// Bandwidth in kbps above which video may resume
+    pub recovery_bandwidth_kbps: u32,
+    /// Consecutive good samples before resuming video
+    pub recovery_samples: u32,
+}
+
+impl Default for DegradationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_loss_percent: 10.0,
+            min_bandwidth_kbps: 150,
+            sustain_samples: 3,
+            recovery_loss_percent: 3.0,
+            recovery_bandwidth_kbps: 400,
+            recovery_samples: 5,
+        }
+    }
+}
+
+/// Why video was suspended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum DegradationReason {
+    /// Packet loss stayed too high
+    PacketLoss,
+    /// Bandwidth stayed too low
+    LowBandwidth,
+}
+
+/// Change of a call's media made by the monitor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QualityTransition {
+    /// Outgoing video was suspended
+    Degraded(DegradationReason),
+    /// Outgoing video may flow again
+    Recovered,
+}
+
+/// Per-call quality tracking behind the audio-only fallback
+#[derive(Debug, Clone, Default)]
+pub struct QualityMonitor {
+    degraded: Option<DegradationReason>,
+    poor_streak: u32,
+    good_streak: u32,
+}
+
+impl QualityMonitor {
+    /// Why video is suspended, if it is
+    #[must_use]
+    pub fn degraded(&self) -> Option<DegradationReason> {
+        self.degraded
+    }
+
+    /// Act on a quality sample
+    ///
+    /// Returns the change to make to the call's media, if any.
+    pub fn evaluate(
+        &mut self,
+        config: &DegradationConfig,
+        metrics: &CallQualityMetrics,
+    ) -> Option<QualityTransition> {
+        if !config.enabled {
+            return None;
+        }
+
+        match self.degraded {
+            None => {
+                let reason = if metrics.packet_loss_percent > config.max_loss_percent {
+                    DegradationReason::PacketLoss
+                } else if metrics.bandwidth_kbps < config.min_bandwidth_kbps {
+                    DegradationReason::LowBandwidth
+                } else {
+                    self.poor_streak = 0;
+                    return None;
+                };
+                self.poor_streak += 1;
+                if self.poor_streak < config.sustain_samples.max(1) {
+                    return None;
+                }
+                self.poor_streak = 0;
+                self.degraded = Some(reason);
+                Some(QualityTransition::Degraded(reason))
+            }
+            Some(_) => {
+                let good = metrics.packet_loss_percent < config.recovery_loss_percent
+                    && metrics.bandwidth_kbps > config.recovery_bandwidth_kbps;
+                if !good {
+                    self.good_streak = 0;
+                    return None;
+                }
+                self.good_streak += 1;
+                if self.good_streak < config.recovery_samples.max(1) {
+                    return None;
+                }
+                self.good_streak = 0;
+                self.degraded = None;
+                Some(QualityTransition::Recovered)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn sample(packet_loss_percent: f32, bandwidth_kbps: u32) -> CallQualityMetrics {
+        CallQualityMetrics {
+            rtt_ms: 80,
+            packet_loss_percent,
+            jitter_ms: 10,
+            bandwidth_kbps,
+            packet_rate: 50.0,
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_sustained_loss_degrades_then_recovers() {
+        let config = DegradationConfig {
+            sustain_samples: 2,
+            recovery_samples: 2,
+            ..Default::default()
+        };
+        let mut monitor = QualityMonitor::default();
+
+        // A single bad sample is a blip
+        assert_eq!(monitor.evaluate(&config, &sample(20.0, 1000)), None);
+        assert_eq!(monitor.evaluate(&config, &sample(0.0, 1000)), None);
+        assert_eq!(monitor.evaluate(&config, &sample(20.0, 1000)), None);
+        assert_eq!(
+            monitor.evaluate(&config, &sample(20.0, 1000)),
+            Some(QualityTransition::Degraded(DegradationReason::PacketLoss))
+        );
+        assert_eq!(monitor.degraded(), Some(DegradationReason::PacketLoss));
+
+        // Between the thresholds is not good enough to recover
+        assert_eq!(monitor.evaluate(&config, &sample(5.0, 1000)), None);
+        assert_eq!(monitor.evaluate(&config, &sample(5.0, 1000)), None);
+        assert_eq!(monitor.evaluate(&config, &sample(1.0, 1000)), None);
+        assert_eq!(
+            monitor.evaluate(&config, &sample(1.0, 1000)),
+            Some(QualityTransition::Recovered)
+        );
+        assert_eq!(monitor.degraded(), None);
+    }
+
+    #[test]
+    fn test_low_bandwidth_and_disabled() {
+        let config = DegradationConfig {
+            sustain_samples: 1,
+            ..Default::default()
+        };
+        let mut monitor = QualityMonitor::default();
+        assert_eq!(
+            monitor.evaluate(&config, &sample(0.0, 100)),
+            Some(QualityTransition::Degraded(DegradationReason::LowBandwidth))
+        );
+
+        let disabled = DegradationConfig {
+            enabled: false,
+            ..config
+        };
+        let mut monitor = QualityMonitor::default();
+        assert_eq!(monitor.evaluate(&disabled, &sample(50.0, 10)), None);
+    }
+}
@@ -636,3 +636,54 @@ mod tests {
         assert_eq!(restored.ssrc, 0x12345678);
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn stream_type_strategy() -> impl Strategy<Value = StreamType> {
+        prop_oneof![
+            Just(StreamType::Audio),
+            Just(StreamType::Video),
+            Just(StreamType::Data),
+            Just(StreamType::ScreenShare),
+            Just(StreamType::RtcpFeedback),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn prop_packet_roundtrip(
+            payload_type in 0u8..128,
+            sequence_number in any::<u16>(),
+            timestamp in any::<u32>(),
+            ssrc in any::<u32>(),
+            payload in prop::collection::vec(any::<u8>(), 0..1100),
+            stream_type in stream_type_strategy(),
+        ) {
+            let packet =
+                RtpPacket::new(payload_type, sequence_number, timestamp, ssrc, payload, stream_type)
+                    .unwrap();
+            let restored = RtpPacket::from_tagged_bytes(&packet.to_tagged_bytes().unwrap()).unwrap();
+            prop_assert_eq!(restored.payload_type, payload_type);
+            prop_assert_eq!(restored.sequence_number, sequence_number);
+            prop_assert_eq!(restored.timestamp, timestamp);
+            prop_assert_eq!(restored.ssrc, ssrc);
+            prop_assert_eq!(restored.stream_type, stream_type);
+            prop_assert_eq!(restored.payload, packet.payload);
+        }
+
+        #[test]
+        fn prop_decode_arbitrary_input(data in prop::collection::vec(any::<u8>(), 0..1500)) {
+            // Whatever decodes must re-encode to a packet that decodes the same
+            if let Ok(packet) = RtpPacket::from_bytes(&data) {
+                let bytes = packet.to_bytes().unwrap();
+                let again = RtpPacket::from_bytes(&bytes).unwrap();
+                prop_assert_eq!(again.to_bytes().unwrap(), bytes);
+            }
+            let _ = RtpPacket::from_tagged_bytes(&data);
+        }
+    }
+}
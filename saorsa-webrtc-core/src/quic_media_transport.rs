@@ -22,12 +22,34 @@
 //! transport.send_rtp(StreamType::Audio, &rtp_packet).await?;
 //! ```
 
+use crate::bitrate::{Rates, StreamRateEstimator, StreamRates};
+use crate::clock::{system_clock, SharedClock};
+use crate::hold::hold_key;
+use crate::keepalive::{
+    KeepaliveConfig, KeepaliveKind, KeepaliveMonitor, KeepalivePacket, Liveness,
+};
 use crate::link_transport::{LinkTransportError, PeerConnection, StreamType};
+use crate::media_tap::{MediaTaps, TrackReceivers};
+use crate::pcap::{PacketDirection, PcapWriter};
+use crate::quic_bridge::{RtpPacket, StreamType as RtpStreamType};
+use crate::resources::ResourceGauges;
+use crate::rtx::rtx_key;
+use crate::state_machine::StateMachine;
+use crate::timed_metadata::metadata_key;
+use crate::types::CallId;
 use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
 use std::sync::Arc;
+use std::time::SystemTime;
 use thiserror::Error;
 use tokio::sync::RwLock;
 
+pub use saorsa_webrtc_proto::framing;
+pub use saorsa_webrtc_proto::stream::{StreamKey, TrackId, PRIMARY_TRACK};
+pub use saorsa_webrtc_proto::types::MediaTransportState;
+
 /// Error type for media transport operations
 #[derive(Error, Debug, Clone)]
 pub enum MediaTransportError {
@@ -56,35 +78,22 @@ pub enum MediaTransportError {
     #[error("Framing error: {0}")]
     FramingError(String),
 
+    /// Media type not allowed by the transport's media gate
+    #[error("{0:?} media is not allowed before the call is accepted")]
+    MediaGated(StreamType),
+
     /// Underlying transport error
     #[error("Transport error: {0}")]
     TransportError(#[from] LinkTransportError),
 }
 
-/// Connection state for the media transport
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum MediaTransportState {
-    /// Not connected to any peer
-    Disconnected,
-    /// Connecting to a peer
-    Connecting,
-    /// Connected and ready for media
-    Connected,
-    /// Connection failed
-    Failed,
-}
-
-impl Default for MediaTransportState {
-    fn default() -> Self {
-        Self::Disconnected
-    }
-}
-
 /// Handle to an active QUIC stream
 #[derive(Debug, Clone)]
 pub struct StreamHandle {
     /// Stream type
     pub stream_type: StreamType,
+    /// Track within the stream type
+    pub track_id: TrackId,
     /// Whether the stream is open
     pub is_open: bool,
     /// Bytes sent on this stream
@@ -95,9 +104,10 @@ pub struct StreamHandle {
 
 impl StreamHandle {
     /// Create a new stream handle
-    fn new(stream_type: StreamType) -> Self {
+    fn new(key: StreamKey) -> Self {
         Self {
-            stream_type,
+            stream_type: key.stream_type,
+            track_id: key.track_id,
             is_open: true,
             bytes_sent: 0,
             bytes_received: 0,
@@ -128,6 +138,35 @@ impl From<StreamType> for StreamPriority {
     }
 }
 
+/// Which media may be sent on a transport
+///
+/// Calls keep the gate closed until they are accepted, so media never flows
+/// to a peer that has not answered. Early media opens it for audio only, so
+/// video never flows before acceptance. RTCP and data are always allowed,
+/// as is hold audio on [`hold_key`], which is what a held call plays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MediaGate {
+    /// All media may flow
+    #[default]
+    Open,
+    /// Only audio may flow (ringback tones, announcements)
+    EarlyMedia,
+    /// No audio, video or screen share may flow
+    Closed,
+}
+
+impl MediaGate {
+    /// Check if a stream type may flow through this gate
+    #[must_use]
+    pub fn permits(self, stream_type: StreamType) -> bool {
+        match stream_type {
+            StreamType::Audio => self != Self::Closed,
+            StreamType::Video | StreamType::Screen => self == Self::Open,
+            StreamType::RtcpFeedback | StreamType::Data => true,
+        }
+    }
+}
+
 /// QUIC-based media transport for WebRTC
 ///
 /// Provides dedicated QUIC streams for each media type (audio, video, screen, RTCP).
@@ -139,18 +178,38 @@ impl From<StreamType> for StreamPriority {
 /// from multiple async tasks.
 pub struct QuicMediaTransport {
     /// Current connection state
-    state: Arc<RwLock<MediaTransportState>>,
-    /// Active stream handles by type
-    streams: Arc<RwLock<HashMap<StreamType, StreamHandle>>>,
+    pub(crate) state: Arc<parking_lot::RwLock<MediaTransportState>>,
+    /// Active stream handles by stream type and track
+    streams: Arc<RwLock<HashMap<StreamKey, StreamHandle>>>,
     /// Remote peer connection
     peer: Arc<RwLock<Option<PeerConnection>>>,
     /// Transport statistics
     stats: Arc<RwLock<TransportStats>>,
+    /// Windowed rate estimators by stream type
+    rates: Arc<RwLock<HashMap<StreamType, StreamRateEstimator>>>,
+    /// Peer liveness tracking
+    keepalive: Arc<RwLock<KeepaliveMonitor>>,
+    /// Media allowed to flow before/after call acceptance
+    media_gate: Arc<RwLock<MediaGate>>,
+    /// Read-only copies of sent and received packets
+    taps: Arc<MediaTaps>,
+    /// Receivers of individual remote tracks
+    track_receivers: TrackReceivers,
+    /// Debug capture of sent and received packets
+    dump: Arc<parking_lot::Mutex<Option<PcapWriter<BufWriter<File>>>>>,
+    /// Time source for rates and keepalives
+    clock: SharedClock,
+    /// Leak-detection gauges shared with the owning call manager
+    resources: Arc<ResourceGauges>,
+    /// Call this transport carries, for correlating logs and stats
+    call_id: Option<CallId>,
 }
 
 /// Statistics for the media transport
 #[derive(Debug, Clone, Default)]
 pub struct TransportStats {
+    /// Call the transport carries, if known
+    pub call_id: Option<CallId>,
     /// Total packets sent
     pub packets_sent: u64,
     /// Total packets received
@@ -169,6 +228,33 @@ pub struct TransportStats {
     pub rtcp_bytes_sent: u64,
     /// RTCP bytes received
     pub rtcp_bytes_received: u64,
+    /// Packets resent on retransmission streams
+    pub retransmitted_packets: u64,
+    /// Bytes resent on retransmission streams
+    pub retransmitted_bytes: u64,
+    /// Expected loss the audio encoder's in-band FEC is set for, in
+    /// percent; 0 when FEC is off
+    pub audio_fec_loss_percent: u8,
+    /// Bitrate and packet rate per stream, as of the snapshot
+    pub stream_rates: HashMap<StreamType, StreamRates>,
+}
+
+impl TransportStats {
+    /// Combined outbound rates of all streams
+    #[must_use]
+    pub fn sent_rates(&self) -> Rates {
+        self.stream_rates
+            .values()
+            .fold(Rates::default(), |total, rates| total + rates.sent)
+    }
+
+    /// Combined inbound rates of all streams
+    #[must_use]
+    pub fn received_rates(&self) -> Rates {
+        self.stream_rates
+            .values()
+            .fold(Rates::default(), |total, rates| total + rates.received)
+    }
 }
 
 impl Default for QuicMediaTransport {
@@ -187,12 +273,76 @@ impl QuicMediaTransport {
     /// A new `QuicMediaTransport` instance ready for connection.
     #[must_use]
     pub fn new() -> Self {
+        Self::with_clock(system_clock())
+    }
+
+    /// Create a new QUIC media transport that reads time from `clock`
+    #[must_use]
+    pub fn with_clock(clock: SharedClock) -> Self {
         Self {
-            state: Arc::new(RwLock::new(MediaTransportState::Disconnected)),
+            state: Arc::new(parking_lot::RwLock::new(MediaTransportState::Disconnected)),
             streams: Arc::new(RwLock::new(HashMap::new())),
             peer: Arc::new(RwLock::new(None)),
             stats: Arc::new(RwLock::new(TransportStats::default())),
+            rates: Arc::new(RwLock::new(HashMap::new())),
+            keepalive: Arc::new(RwLock::new(KeepaliveMonitor::with_clock(
+                KeepaliveConfig::default(),
+                clock.clone(),
+            ))),
+            media_gate: Arc::new(RwLock::new(MediaGate::default())),
+            taps: Arc::new(MediaTaps::new()),
+            track_receivers: TrackReceivers::new(),
+            dump: Arc::new(parking_lot::Mutex::new(None)),
+            clock,
+            resources: ResourceGauges::new(),
+            call_id: None,
+        }
+    }
+
+    /// Report open streams and buffered packets to shared gauges
+    ///
+    /// Call on a new transport, before any stream is opened.
+    #[must_use]
+    pub fn with_resources(mut self, resources: Arc<ResourceGauges>) -> Self {
+        resources.watch_taps(&self.taps);
+        self.resources = resources;
+        self
+    }
+
+    /// Tag the transport with the call it carries
+    ///
+    /// The call ID is included in the transport's log events and stats.
+    #[must_use]
+    pub fn with_call_id(mut self, call_id: CallId) -> Self {
+        self.call_id = Some(call_id);
+        self
+    }
+
+    /// Call this transport carries, if tagged
+    #[must_use]
+    pub fn call_id(&self) -> Option<CallId> {
+        self.call_id
+    }
+
+    /// Call ID as a log field, omitted when untagged
+    fn call_field(&self) -> Option<tracing::field::DisplayValue<CallId>> {
+        self.call_id.map(tracing::field::display)
+    }
+
+    /// Mark a stream open or closed, keeping the open-stream gauge in step
+    fn set_stream_open(&self, handle: &mut StreamHandle, open: bool) {
+        match (handle.is_open, open) {
+            (false, true) => self.resources.stream_opened(),
+            (true, false) => self.resources.stream_closed(),
+            _ => {}
         }
+        handle.is_open = open;
+    }
+
+    /// Create a handle for a new stream, which starts open
+    fn new_stream(&self, key: StreamKey) -> StreamHandle {
+        self.resources.stream_opened();
+        StreamHandle::new(key)
     }
 
     /// Get the current connection state
@@ -201,7 +351,18 @@ impl QuicMediaTransport {
     ///
     /// The current `MediaTransportState`.
     pub async fn state(&self) -> MediaTransportState {
-        *self.state.read().await
+        *self.state.read()
+    }
+
+    /// Get the current connection state without awaiting
+    ///
+    /// For synchronous callers such as [`TrackBackend::is_connected`]; the
+    /// state lock is never held across an await, so this does not block.
+    ///
+    /// [`TrackBackend::is_connected`]: crate::media::TrackBackend::is_connected
+    #[must_use]
+    pub fn current_state(&self) -> MediaTransportState {
+        *self.state.read()
     }
 
     /// Check if the transport is connected
@@ -210,7 +371,7 @@ impl QuicMediaTransport {
     ///
     /// `true` if the transport is in the `Connected` state.
     pub async fn is_connected(&self) -> bool {
-        *self.state.read().await == MediaTransportState::Connected
+        *self.state.read() == MediaTransportState::Connected
     }
 
     /// Set the connection state
@@ -221,33 +382,14 @@ impl QuicMediaTransport {
     ///
     /// # Errors
     ///
-    /// Returns error if the state transition is invalid.
+    /// Returns error if
+    /// [`TRANSPORT_TRANSITIONS`](crate::state_machine::TRANSPORT_TRANSITIONS)
+    /// does not allow the transition.
     async fn set_state(&self, new_state: MediaTransportState) -> Result<(), MediaTransportError> {
-        let mut state = self.state.write().await;
+        let mut state = self.state.write();
         let current = *state;
 
-        // Validate state transitions
-        let valid = match (current, new_state) {
-            // From Disconnected
-            (MediaTransportState::Disconnected, MediaTransportState::Connecting) => true,
-            (MediaTransportState::Disconnected, MediaTransportState::Disconnected) => true,
-            // From Connecting
-            (MediaTransportState::Connecting, MediaTransportState::Connected) => true,
-            (MediaTransportState::Connecting, MediaTransportState::Failed) => true,
-            (MediaTransportState::Connecting, MediaTransportState::Disconnected) => true,
-            // From Connected
-            (MediaTransportState::Connected, MediaTransportState::Disconnected) => true,
-            (MediaTransportState::Connected, MediaTransportState::Failed) => true,
-            // From Failed
-            (MediaTransportState::Failed, MediaTransportState::Disconnected) => true,
-            (MediaTransportState::Failed, MediaTransportState::Connecting) => true,
-            // Same state is always valid
-            (a, b) if a == b => true,
-            // All other transitions are invalid
-            _ => false,
-        };
-
-        if !valid {
+        if !current.can_transition_to(new_state) {
             return Err(MediaTransportError::InvalidStateTransition {
                 from: current,
                 to: new_state,
@@ -278,8 +420,47 @@ impl QuicMediaTransport {
 
         // Transition to connected
         self.set_state(MediaTransportState::Connected).await?;
+        self.keepalive.write().await.record_activity();
 
-        tracing::info!("QuicMediaTransport connected");
+        tracing::info!(call_id = self.call_field(), "QuicMediaTransport connected");
+        Ok(())
+    }
+
+    /// Move the transport over to a new peer
+    ///
+    /// Used when the peer hands a call off to another of its devices. The
+    /// new peer is checked before anything changes, so on error the
+    /// transport keeps its current peer and streams. On success streams
+    /// opened to the old peer are closed; a transport that was not
+    /// connected simply connects to `peer`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the peer ID is empty or the transport cannot connect.
+    pub async fn switch_peer(&self, peer: PeerConnection) -> Result<(), MediaTransportError> {
+        if peer.peer_id.is_empty() {
+            return Err(MediaTransportError::ConnectionFailed(
+                "Peer ID cannot be empty".to_string(),
+            ));
+        }
+        if !self.is_connected().await {
+            return self.connect(peer).await;
+        }
+
+        *self.peer.write().await = Some(peer);
+        {
+            let mut streams = self.streams.write().await;
+            for (_, stream) in streams.iter_mut() {
+                self.set_stream_open(stream, false);
+            }
+            streams.clear();
+        }
+        self.keepalive.write().await.record_activity();
+
+        tracing::info!(
+            call_id = self.call_field(),
+            "QuicMediaTransport switched peer"
+        );
         Ok(())
     }
 
@@ -295,7 +476,7 @@ impl QuicMediaTransport {
         {
             let mut streams = self.streams.write().await;
             for (_, stream) in streams.iter_mut() {
-                stream.is_open = false;
+                self.set_stream_open(stream, false);
             }
             streams.clear();
         }
@@ -308,8 +489,12 @@ impl QuicMediaTransport {
 
         // Transition to disconnected
         self.set_state(MediaTransportState::Disconnected).await?;
+        self.stop_dump();
 
-        tracing::info!("QuicMediaTransport disconnected");
+        tracing::info!(
+            call_id = self.call_field(),
+            "QuicMediaTransport disconnected"
+        );
         Ok(())
     }
 
@@ -334,10 +519,9 @@ impl QuicMediaTransport {
             return Err(MediaTransportError::NotConnected);
         }
 
+        let key = StreamKey::primary(stream_type);
         let mut streams = self.streams.write().await;
-        let handle = streams
-            .entry(stream_type)
-            .or_insert_with(|| StreamHandle::new(stream_type));
+        let handle = streams.entry(key).or_insert_with(|| self.new_stream(key));
 
         Ok(handle.clone())
     }
@@ -355,9 +539,25 @@ impl QuicMediaTransport {
     ///
     /// # Returns
     ///
-    /// A clone of the current transport statistics.
+    /// A clone of the current transport statistics, with per-stream
+    /// rates evaluated at the time of the call.
     pub async fn stats(&self) -> TransportStats {
-        self.stats.read().await.clone()
+        let mut stats = self.stats.read().await.clone();
+        stats.call_id = self.call_id;
+        let now = self.clock.now();
+        stats.stream_rates = self
+            .rates
+            .read()
+            .await
+            .iter()
+            .map(|(stream_type, estimator)| (*stream_type, estimator.snapshot_at(now)))
+            .collect();
+        stats
+    }
+
+    /// Record the expected loss the audio encoder's in-band FEC is set for
+    pub async fn set_audio_fec(&self, loss_percent: u8) {
+        self.stats.write().await.audio_fec_loss_percent = loss_percent;
     }
 
     /// Get the priority for a stream type
@@ -386,6 +586,8 @@ impl QuicMediaTransport {
 
     /// Close a specific stream
     ///
+    /// Closes the primary track of the stream type.
+    ///
     /// # Arguments
     ///
     /// * `stream_type` - The type of stream to close
@@ -394,9 +596,18 @@ impl QuicMediaTransport {
     ///
     /// `true` if the stream was closed, `false` if it wasn't open.
     pub async fn close_stream(&self, stream_type: StreamType) -> bool {
+        self.close_track(StreamKey::primary(stream_type)).await
+    }
+
+    /// Close the stream of one track
+    ///
+    /// # Returns
+    ///
+    /// `true` if the stream was closed, `false` if it wasn't open.
+    pub async fn close_track(&self, key: StreamKey) -> bool {
         let mut streams = self.streams.write().await;
-        if let Some(handle) = streams.get_mut(&stream_type) {
-            handle.is_open = false;
+        if let Some(handle) = streams.get_mut(&key) {
+            self.set_stream_open(handle, false);
             true
         } else {
             false
@@ -410,10 +621,17 @@ impl QuicMediaTransport {
     /// * `stream_type` - The stream type
     /// * `bytes` - Number of bytes sent
     pub async fn record_sent(&self, stream_type: StreamType, bytes: u64) {
+        self.record_track_sent(StreamKey::primary(stream_type), bytes)
+            .await;
+    }
+
+    /// Update statistics after sending on one track
+    async fn record_track_sent(&self, key: StreamKey, bytes: u64) {
+        let stream_type = key.stream_type;
         // Update stream stats
         {
             let mut streams = self.streams.write().await;
-            if let Some(handle) = streams.get_mut(&stream_type) {
+            if let Some(handle) = streams.get_mut(&key) {
                 handle.bytes_sent += bytes;
             }
         }
@@ -424,6 +642,15 @@ impl QuicMediaTransport {
             stats.packets_sent += 1;
             stats.bytes_sent += bytes;
         }
+
+        let now = self.clock.now();
+        self.rates
+            .write()
+            .await
+            .entry(stream_type)
+            .or_insert_with(|| StreamRateEstimator::new_at(now))
+            .sent
+            .record_at(bytes, now);
     }
 
     /// Update stream statistics after receiving
@@ -433,10 +660,17 @@ impl QuicMediaTransport {
     /// * `stream_type` - The stream type
     /// * `bytes` - Number of bytes received
     pub async fn record_received(&self, stream_type: StreamType, bytes: u64) {
+        self.record_track_received(StreamKey::primary(stream_type), bytes)
+            .await;
+    }
+
+    /// Update statistics after receiving on one track
+    async fn record_track_received(&self, key: StreamKey, bytes: u64) {
+        let stream_type = key.stream_type;
         // Update stream stats
         {
             let mut streams = self.streams.write().await;
-            if let Some(handle) = streams.get_mut(&stream_type) {
+            if let Some(handle) = streams.get_mut(&key) {
                 handle.bytes_received += bytes;
             }
         }
@@ -447,6 +681,17 @@ impl QuicMediaTransport {
             stats.packets_received += 1;
             stats.bytes_received += bytes;
         }
+
+        let now = self.clock.now();
+        self.rates
+            .write()
+            .await
+            .entry(stream_type)
+            .or_insert_with(|| StreamRateEstimator::new_at(now))
+            .received
+            .record_at(bytes, now);
+
+        self.keepalive.write().await.record_activity();
     }
 
     /// Record a stream error
@@ -464,6 +709,16 @@ impl QuicMediaTransport {
         let mut stats = self.stats.write().await;
         stats.rtcp_packets_sent += 1;
         stats.rtcp_bytes_sent += bytes;
+        drop(stats);
+
+        let now = self.clock.now();
+        self.rates
+            .write()
+            .await
+            .entry(StreamType::RtcpFeedback)
+            .or_insert_with(|| StreamRateEstimator::new_at(now))
+            .sent
+            .record_at(bytes, now);
     }
 
     /// Record RTCP packet received
@@ -475,6 +730,18 @@ impl QuicMediaTransport {
         let mut stats = self.stats.write().await;
         stats.rtcp_packets_received += 1;
         stats.rtcp_bytes_received += bytes;
+        drop(stats);
+
+        let now = self.clock.now();
+        self.rates
+            .write()
+            .await
+            .entry(StreamType::RtcpFeedback)
+            .or_insert_with(|| StreamRateEstimator::new_at(now))
+            .received
+            .record_at(bytes, now);
+
+        self.keepalive.write().await.record_activity();
     }
 }
 
@@ -488,6 +755,7 @@ const _: () = {
 #[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
+    use crate::clock::MockClock;
     use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
     fn test_peer() -> PeerConnection {
@@ -646,6 +914,79 @@ mod tests {
         assert_eq!(stats.bytes_received, 50);
     }
 
+    #[tokio::test(start_paused = true)]
+    async fn test_stream_rates_in_stats() {
+        let transport = QuicMediaTransport::new();
+        assert!(transport.stats().await.stream_rates.is_empty());
+
+        // 50 audio packets/s of 200 bytes = 80 kbps out, video only inbound
+        for _ in 0..500 {
+            tokio::time::advance(std::time::Duration::from_millis(20)).await;
+            transport.record_sent(StreamType::Audio, 200).await;
+            transport.record_received(StreamType::Video, 1000).await;
+        }
+
+        let stats = transport.stats().await;
+        let audio = stats.stream_rates[&StreamType::Audio];
+        assert!((audio.sent.bitrate_1s_bps - 80_000.0).abs() < 3_000.0);
+        assert!((audio.sent.packet_rate_1s - 50.0).abs() < 2.0);
+        assert_eq!(audio.received, crate::bitrate::Rates::default());
+        assert!(
+            stats.stream_rates[&StreamType::Video]
+                .received
+                .bitrate_1s_bps
+                > 350_000.0
+        );
+        assert_eq!(stats.sent_rates().bitrate_kbps() / 10, 8);
+        assert_eq!(stats.received_rates().bitrate_kbps() / 100, 4);
+    }
+
+    #[tokio::test]
+    async fn test_keepalive_with_mock_clock() {
+        let clock = MockClock::new();
+        let transport = QuicMediaTransport::with_clock(clock.shared());
+        transport.connect(test_peer()).await.unwrap();
+
+        let interval = KeepaliveConfig::default().interval;
+        clock.advance(interval * 3);
+        assert!(matches!(
+            transport.liveness().await,
+            Liveness::Suspect { missed: 3 }
+        ));
+
+        transport.record_received(StreamType::Audio, 100).await;
+        assert_eq!(transport.liveness().await, Liveness::Alive);
+        clock.advance(std::time::Duration::from_millis(500));
+        let rates = transport.stats().await.stream_rates[&StreamType::Audio];
+        assert!(rates.received.bitrate_1s_bps > 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_switch_peer() {
+        let transport = QuicMediaTransport::new();
+        transport.connect(test_peer()).await.unwrap();
+        transport
+            .get_or_create_stream(StreamType::Audio)
+            .await
+            .unwrap();
+
+        let empty = PeerConnection {
+            peer_id: String::new(),
+            ..test_peer()
+        };
+        assert!(transport.switch_peer(empty).await.is_err());
+        assert_eq!(transport.active_streams().await.len(), 1);
+
+        let phone = PeerConnection {
+            peer_id: "phone".to_string(),
+            ..test_peer()
+        };
+        transport.switch_peer(phone).await.unwrap();
+        assert!(transport.is_connected().await);
+        assert_eq!(transport.peer().await.unwrap().peer_id, "phone");
+        assert!(transport.active_streams().await.is_empty());
+    }
+
     #[tokio::test]
     async fn test_invalid_state_transition() {
         let transport = QuicMediaTransport::new();
@@ -655,6 +996,30 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_set_state_follows_transition_table() {
+        for &from in MediaTransportState::STATES {
+            for &to in MediaTransportState::STATES {
+                let transport = QuicMediaTransport::new();
+                *transport.state.write() = from;
+                let result = transport.set_state(to).await;
+                if from.can_transition_to(to) {
+                    assert!(result.is_ok(), "{from:?} -> {to:?}");
+                    assert_eq!(transport.state().await, to);
+                } else {
+                    assert!(
+                        matches!(
+                            result,
+                            Err(MediaTransportError::InvalidStateTransition { .. })
+                        ),
+                        "{from:?} -> {to:?}"
+                    );
+                    assert_eq!(transport.state().await, from);
+                }
+            }
+        }
+    }
+
     #[tokio::test]
     async fn test_streams_cleared_on_disconnect() {
         let transport = QuicMediaTransport::new();
@@ -692,19 +1057,34 @@ impl QuicMediaTransport {
     ///
     /// Returns error if not connected or stream opening fails.
     pub async fn open_stream(&self, stream_type: StreamType) -> Result<(), MediaTransportError> {
+        self.open_track(StreamKey::primary(stream_type)).await
+    }
+
+    /// Open the stream of one track
+    ///
+    /// Tracks of the same stream type are opened independently, each on its
+    /// own stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if not connected.
+    pub async fn open_track(&self, key: StreamKey) -> Result<(), MediaTransportError> {
         if !self.is_connected().await {
             return Err(MediaTransportError::NotConnected);
         }
 
         // Update stream to mark it as open
         let mut streams = self.streams.write().await;
-        let handle = streams
-            .entry(stream_type)
-            .or_insert_with(|| StreamHandle::new(stream_type));
+        let handle = streams.entry(key).or_insert_with(|| self.new_stream(key));
 
-        handle.is_open = true;
+        self.set_stream_open(handle, true);
 
-        tracing::debug!("Opened stream for type {:?}", stream_type);
+        tracing::debug!(
+            call_id = self.call_field(),
+            track_id = key.track_id,
+            "Opened stream for type {:?}",
+            key.stream_type
+        );
         Ok(())
     }
 
@@ -731,7 +1111,7 @@ impl QuicMediaTransport {
             self.open_stream(stream_type).await?;
         }
 
-        tracing::info!("All media streams opened");
+        tracing::info!(call_id = self.call_field(), "All media streams opened");
         Ok(())
     }
 
@@ -781,8 +1161,8 @@ impl QuicMediaTransport {
         }
 
         let mut streams = self.streams.write().await;
-        if let Some(handle) = streams.get_mut(&stream_type) {
-            handle.is_open = true;
+        if let Some(handle) = streams.get_mut(&StreamKey::primary(stream_type)) {
+            self.set_stream_open(handle, true);
             Ok(())
         } else {
             Err(MediaTransportError::StreamError(format!(
@@ -806,14 +1186,37 @@ impl QuicMediaTransport {
     ///
     /// # Returns
     ///
-    /// A vector of open stream types.
+    /// A vector of stream types with at least one open track.
     pub async fn open_stream_types(&self) -> Vec<StreamType> {
         let streams = self.streams.read().await;
-        streams
+        let mut types = Vec::new();
+        for handle in streams.values().filter(|h| h.is_open) {
+            if !types.contains(&handle.stream_type) {
+                types.push(handle.stream_type);
+            }
+        }
+        types
+    }
+
+    /// Get the open tracks of a stream type, in track order
+    pub async fn open_tracks(&self, stream_type: StreamType) -> Vec<TrackId> {
+        let streams = self.streams.read().await;
+        let mut tracks: Vec<TrackId> = streams
             .values()
-            .filter(|h| h.is_open)
-            .map(|h| h.stream_type)
-            .collect()
+            .filter(|h| h.is_open && h.stream_type == stream_type)
+            .map(|h| h.track_id)
+            .collect();
+        tracks.sort_unstable();
+        tracks
+    }
+
+    /// Check whether one track's stream is open
+    pub async fn is_track_open(&self, key: StreamKey) -> bool {
+        self.streams
+            .read()
+            .await
+            .get(&key)
+            .is_some_and(|h| h.is_open)
     }
 }
 
@@ -979,229 +1382,6 @@ mod stream_tests {
     }
 }
 
-/// RTP packet framing utilities for QUIC streams
-pub mod framing {
-
-    /// Frame an RTP packet with 2-byte length prefix (big-endian u16)
-    ///
-    /// # Arguments
-    ///
-    /// * `packet` - The RTP packet bytes to frame
-    ///
-    /// # Returns
-    ///
-    /// A vector with 2-byte length prefix followed by packet data
-    ///
-    /// # Errors
-    ///
-    /// Returns error if packet is too large (> 65535 bytes)
-    pub fn frame_rtp(packet: &[u8]) -> Result<Vec<u8>, String> {
-        if packet.len() > u16::MAX as usize {
-            return Err(format!("RTP packet too large: {} bytes", packet.len()));
-        }
-
-        let mut framed = Vec::with_capacity(2 + packet.len());
-        framed.extend_from_slice(&(packet.len() as u16).to_be_bytes());
-        framed.extend_from_slice(packet);
-        Ok(framed)
-    }
-
-    /// Unframe an RTP packet, extracting length prefix and validating
-    ///
-    /// # Arguments
-    ///
-    /// * `data` - The framed data (length prefix + packet)
-    ///
-    /// # Returns
-    ///
-    /// A tuple of (expected_length, remaining_data) or error
-    ///
-    /// # Errors
-    ///
-    /// Returns error if frame is too small or length mismatches
-    pub fn unframe_rtp(data: &[u8]) -> Result<(u16, &[u8]), String> {
-        if data.len() < 2 {
-            return Err(format!("Frame too small: {} bytes (need >= 2)", data.len()));
-        }
-
-        // Parse length prefix from first 2 bytes
-        let len_bytes = &data[0..2];
-        let expected_len = u16::from_be_bytes([len_bytes[0], len_bytes[1]]);
-
-        let packet_data = &data[2..];
-
-        if packet_data.len() < expected_len as usize {
-            return Err(format!(
-                "Incomplete packet: {} bytes (expected {})",
-                packet_data.len(),
-                expected_len
-            ));
-        }
-
-        Ok((expected_len, packet_data))
-    }
-
-    /// Split a buffer into complete frames
-    ///
-    /// # Arguments
-    ///
-    /// * `data` - The data buffer containing one or more frames
-    ///
-    /// # Returns
-    ///
-    /// A vector of (frame_data, remaining_data) or error
-    pub fn split_frames(data: &[u8]) -> Result<Vec<&[u8]>, String> {
-        let mut frames = Vec::new();
-        let mut offset = 0;
-
-        while offset < data.len() {
-            if offset + 2 > data.len() {
-                return Err("Incomplete length header".to_string());
-            }
-
-            let len_bytes = &data[offset..offset + 2];
-            let frame_len = u16::from_be_bytes([len_bytes[0], len_bytes[1]]) as usize;
-
-            let frame_start = offset + 2;
-            let frame_end = frame_start + frame_len;
-
-            if frame_end > data.len() {
-                return Err(format!(
-                    "Incomplete frame: {} bytes (expected {})",
-                    data.len() - frame_start,
-                    frame_len
-                ));
-            }
-
-            frames.push(&data[frame_start..frame_end]);
-            offset = frame_end;
-        }
-
-        Ok(frames)
-    }
-
-    #[cfg(test)]
-    #[allow(clippy::unwrap_used)]
-    mod framing_tests {
-        use super::*;
-
-        #[test]
-        fn test_frame_rtp_empty() {
-            let packet = &[];
-            let framed = frame_rtp(packet).unwrap();
-            assert_eq!(framed.len(), 2);
-            assert_eq!(framed[0..2], [0, 0]);
-        }
-
-        #[test]
-        fn test_frame_rtp_small() {
-            let packet = &[0x80, 0x60, 0x00, 0x01];
-            let framed = frame_rtp(packet).unwrap();
-            assert_eq!(framed.len(), 6);
-            assert_eq!(framed[0..2], [0, 4]); // length = 4
-            assert_eq!(&framed[2..], packet);
-        }
-
-        #[test]
-        fn test_frame_rtp_large() {
-            let packet = vec![0x42; 1000];
-            let framed = frame_rtp(&packet).unwrap();
-            assert_eq!(framed.len(), 1002);
-            assert_eq!(framed[0..2], [3, 232]); // 1000 in big-endian
-        }
-
-        #[test]
-        fn test_frame_rtp_max_size() {
-            let packet = vec![0x42; 65535];
-            let framed = frame_rtp(&packet).unwrap();
-            assert_eq!(framed.len(), 65537);
-            assert_eq!(framed[0..2], [255, 255]);
-        }
-
-        #[test]
-        fn test_frame_rtp_too_large() {
-            let packet = vec![0x42; 65536];
-            let result = frame_rtp(&packet);
-            assert!(result.is_err());
-        }
-
-        #[test]
-        fn test_unframe_rtp_empty_frame() {
-            let data = &[0, 0];
-            let (len, packet) = unframe_rtp(data).unwrap();
-            assert_eq!(len, 0);
-            assert!(packet.is_empty());
-        }
-
-        #[test]
-        fn test_unframe_rtp_valid() {
-            let data = &[0, 4, 0x80, 0x60, 0x00, 0x01];
-            let (len, packet) = unframe_rtp(data).unwrap();
-            assert_eq!(len, 4);
-            assert_eq!(packet, &[0x80, 0x60, 0x00, 0x01]);
-        }
-
-        #[test]
-        fn test_unframe_rtp_too_small() {
-            let data = &[0];
-            let result = unframe_rtp(data);
-            assert!(result.is_err());
-        }
-
-        #[test]
-        fn test_unframe_rtp_incomplete_packet() {
-            let data = &[0, 4, 0x80, 0x60]; // Says 4 bytes but only 2
-            let result = unframe_rtp(data);
-            assert!(result.is_err());
-        }
-
-        #[test]
-        fn test_roundtrip_frame_unframe() {
-            let original = &[0x80, 0x60, 0x00, 0x01, 0xAA, 0xBB, 0xCC, 0xDD];
-            let framed = frame_rtp(original).unwrap();
-            let (len, packet) = unframe_rtp(&framed).unwrap();
-
-            assert_eq!(len as usize, original.len());
-            assert_eq!(packet, original);
-        }
-
-        #[test]
-        fn test_split_frames_single() {
-            let packet = &[0x80, 0x60, 0x00, 0x01];
-            let framed = frame_rtp(packet).unwrap();
-
-            let frames = split_frames(&framed).unwrap();
-            assert_eq!(frames.len(), 1);
-            assert_eq!(frames[0], packet);
-        }
-
-        #[test]
-        fn test_split_frames_multiple() {
-            let packet1 = &[0x80, 0x60];
-            let packet2 = &[0x81, 0x61, 0xAA, 0xBB];
-
-            let mut combined = Vec::new();
-            combined.extend_from_slice(&frame_rtp(packet1).unwrap());
-            combined.extend_from_slice(&frame_rtp(packet2).unwrap());
-
-            let frames = split_frames(&combined).unwrap();
-            assert_eq!(frames.len(), 2);
-            assert_eq!(frames[0], packet1);
-            assert_eq!(frames[1], packet2);
-        }
-
-        #[test]
-        fn test_split_frames_incomplete() {
-            let packet = &[0x80, 0x60];
-            let framed = frame_rtp(packet).unwrap();
-
-            let incomplete = &framed[0..3]; // Missing 1 byte of payload
-            let result = split_frames(incomplete);
-            assert!(result.is_err());
-        }
-    }
-}
-
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod rtp_tests {
@@ -1247,6 +1427,7 @@ impl QuicMediaTransport {
     ///
     /// Returns error if:
     /// - Transport is not connected
+    /// - The media gate does not permit this stream type
     /// - Stream is not open
     /// - Packet is too large (> 65535 bytes)
     /// - Send operation fails
@@ -1255,24 +1436,187 @@ impl QuicMediaTransport {
         stream_type: StreamType,
         packet: &[u8],
     ) -> Result<(), MediaTransportError> {
+        self.send_track_rtp(StreamKey::primary(stream_type), packet)
+            .await
+    }
+
+    /// Send an RTP packet on one track
+    ///
+    /// Like [`send_rtp`](Self::send_rtp), opening the track's stream if
+    /// needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error under the same conditions as `send_rtp`.
+    pub async fn send_track_rtp(
+        &self,
+        key: StreamKey,
+        packet: &[u8],
+    ) -> Result<(), MediaTransportError> {
+        let stream_type = key.stream_type;
         if !self.is_connected().await {
             return Err(MediaTransportError::NotConnected);
         }
 
+        if key != hold_key() && !self.media_gate.read().await.permits(stream_type) {
+            return Err(MediaTransportError::MediaGated(stream_type));
+        }
+
         // Ensure stream is open
-        self.ensure_stream_open(stream_type).await?;
+        self.open_track(key).await?;
 
         // Frame the packet with length prefix
         let framed = framing::frame_rtp(packet).map_err(MediaTransportError::FramingError)?;
 
         // Record statistics
-        self.record_sent(stream_type, framed.len() as u64).await;
+        self.record_track_sent(key, framed.len() as u64).await;
+        self.taps.publish_bytes(packet);
+        self.dump_packet(PacketDirection::Sent, stream_type, packet);
+
+        tracing::debug!(
+            call_id = self.call_field(),
+            track_id = key.track_id,
+            "Sent {} bytes on stream {:?}",
+            framed.len(),
+            stream_type
+        );
 
-        tracing::debug!("Sent {} bytes on stream {:?}", framed.len(), stream_type);
+        Ok(())
+    }
 
+    /// Resend a packet on the retransmission stream of a stream type
+    ///
+    /// `packet` is an RTX packet built by
+    /// [`RtxSender::retransmit`](crate::rtx::RtxSender::retransmit). It goes
+    /// out on [`rtx_key`], so it never queues behind fresh media, and is
+    /// counted in the retransmission statistics as well as the totals.
+    ///
+    /// # Errors
+    ///
+    /// Returns error under the same conditions as `send_rtp`.
+    pub async fn send_rtx(
+        &self,
+        stream_type: StreamType,
+        packet: &[u8],
+    ) -> Result<(), MediaTransportError> {
+        self.send_track_rtp(rtx_key(stream_type), packet).await?;
+        let mut stats = self.stats.write().await;
+        stats.retransmitted_packets += 1;
+        stats.retransmitted_bytes += packet.len() as u64;
         Ok(())
     }
 
+    /// Send a timed-metadata packet
+    ///
+    /// `packet` is framed by
+    /// [`TimedMetadata::to_rtp`](crate::timed_metadata::TimedMetadata::to_rtp)
+    /// and goes out on [`metadata_key`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error under the same conditions as `send_rtp`.
+    pub async fn send_metadata(&self, packet: &[u8]) -> Result<(), MediaTransportError> {
+        self.send_track_rtp(metadata_key(), packet).await
+    }
+
+    /// Hand over a packet received from the peer
+    ///
+    /// Updates receive statistics and offers the packet to any taps.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream_type` - The stream the packet arrived on
+    /// * `packet` - The unframed packet bytes
+    pub async fn deliver_rtp(&self, stream_type: StreamType, packet: &[u8]) {
+        self.deliver_track_rtp(StreamKey::primary(stream_type), packet)
+            .await;
+    }
+
+    /// Hand over a packet received from the peer on one track
+    pub async fn deliver_track_rtp(&self, key: StreamKey, packet: &[u8]) {
+        self.record_track_received(key, packet.len() as u64).await;
+        self.taps.publish_bytes(packet);
+        self.track_receivers.publish_bytes(key, packet);
+        self.dump_packet(PacketDirection::Received, key.stream_type, packet);
+    }
+
+    /// Start writing sent and received packets to a pcapng file
+    ///
+    /// Replaces any capture already running. See [`pcap`](crate::pcap) for
+    /// the file layout.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be created
+    pub fn start_dump(&self, path: &Path) -> Result<(), MediaTransportError> {
+        let writer = PcapWriter::create(path)
+            .map_err(|e| MediaTransportError::StreamError(format!("Cannot create dump: {e}")))?;
+        self.stop_dump();
+        *self.dump.lock() = Some(writer);
+        tracing::info!(call_id = self.call_field(), path = %path.display(), "Media dump started");
+        Ok(())
+    }
+
+    /// Stop the packet capture, returning the number of packets written
+    ///
+    /// Returns `None` if no capture was running.
+    pub fn stop_dump(&self) -> Option<u64> {
+        let writer = self.dump.lock().take()?;
+        let packets = writer.packets();
+        if let Err(e) = writer.finish() {
+            tracing::warn!(call_id = self.call_field(), error = %e, "Failed to flush media dump");
+        }
+        Some(packets)
+    }
+
+    /// Write a packet to the capture, if one is running
+    ///
+    /// A failing capture is stopped; media keeps flowing.
+    fn dump_packet(&self, direction: PacketDirection, stream_type: StreamType, packet: &[u8]) {
+        let mut dump = self.dump.lock();
+        let Some(writer) = dump.as_mut() else {
+            return;
+        };
+        if let Err(e) = writer.write_packet(direction, stream_type, packet, SystemTime::now()) {
+            tracing::warn!(call_id = self.call_field(), error = %e, "Media dump failed, stopping capture");
+            *dump = None;
+        }
+    }
+
+    /// Tap the packets of one stream type sent or received on this transport
+    ///
+    /// The receiver gets a copy of each [`RtpPacket`]; see
+    /// [`media_tap`](crate::media_tap) for delivery guarantees.
+    #[must_use]
+    pub fn tap(&self, stream_type: RtpStreamType) -> tokio::sync::mpsc::Receiver<RtpPacket> {
+        self.taps.subscribe(stream_type)
+    }
+
+    /// Receive the packets the peer sends on one track
+    ///
+    /// Unlike [`Self::tap`], only received packets of the given track are
+    /// delivered. The receiver ends when the transport is dropped or
+    /// [`Self::close_track_receivers`] is called for the track.
+    #[must_use]
+    pub fn subscribe_track(&self, key: StreamKey) -> tokio::sync::mpsc::Receiver<RtpPacket> {
+        self.track_receivers.subscribe(key)
+    }
+
+    /// Receive the timed metadata the peer sends
+    ///
+    /// Packets are those of [`Self::subscribe_track`] on [`metadata_key`];
+    /// read them with
+    /// [`TimedMetadata::from_rtp`](crate::timed_metadata::TimedMetadata::from_rtp).
+    #[must_use]
+    pub fn subscribe_metadata(&self) -> tokio::sync::mpsc::Receiver<RtpPacket> {
+        self.subscribe_track(metadata_key())
+    }
+
+    /// End every receiver of one remote track
+    pub fn close_track_receivers(&self, key: StreamKey) {
+        self.track_receivers.close(key);
+    }
+
     /// Receive an RTP packet from any open stream
     ///
     /// Blocks until a packet is available.
@@ -1407,7 +1751,7 @@ impl QuicMediaTransport {
         // Check if RTCP stream is open
         let streams = self.streams.read().await;
         let rtcp_open = streams
-            .get(&StreamType::RtcpFeedback)
+            .get(&StreamKey::primary(StreamType::RtcpFeedback))
             .map(|h| h.is_open)
             .unwrap_or(false);
 
@@ -1448,7 +1792,7 @@ impl QuicMediaTransport {
         // Check if data stream is open
         let streams = self.streams.read().await;
         let data_open = streams
-            .get(&StreamType::Data)
+            .get(&StreamKey::primary(StreamType::Data))
             .map(|h| h.is_open)
             .unwrap_or(false);
 
@@ -1498,6 +1842,35 @@ mod send_recv_tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_tracks_of_same_type_are_independent() {
+        let transport = QuicMediaTransport::new();
+        transport.connect(test_peer()).await.unwrap();
+        let camera = StreamKey::primary(StreamType::Video);
+        let second_camera = StreamKey::new(StreamType::Video, 1);
+
+        transport
+            .send_track_rtp(camera, &[0x80, 0x60])
+            .await
+            .unwrap();
+        transport
+            .send_track_rtp(second_camera, &[0x80, 0x60, 0x00])
+            .await
+            .unwrap();
+        transport.deliver_track_rtp(second_camera, &[1, 2, 3]).await;
+
+        assert_eq!(transport.open_stream_count().await, 2);
+        assert_eq!(transport.open_stream_types().await, vec![StreamType::Video]);
+        let mut handles = transport.active_streams().await;
+        handles.sort_by_key(|h| h.track_id);
+        assert_eq!(handles[0].bytes_sent, 4);
+        assert_eq!((handles[1].bytes_sent, handles[1].bytes_received), (5, 3));
+
+        assert!(transport.close_stream(StreamType::Video).await);
+        assert!(!transport.is_track_open(camera).await);
+        assert_eq!(transport.open_tracks(StreamType::Video).await, vec![1]);
+    }
+
     #[tokio::test]
     async fn test_send_rtp_when_connected() {
         let transport = QuicMediaTransport::new();
@@ -1596,6 +1969,46 @@ mod send_recv_tests {
         assert!(stats.bytes_sent > 0);
     }
 
+    #[tokio::test]
+    async fn test_metadata_reaches_subscriber() {
+        use crate::timed_metadata::TimedMetadata;
+
+        let sender = QuicMediaTransport::new();
+        sender.connect(test_peer()).await.unwrap();
+        let receiver = QuicMediaTransport::new();
+        let mut metadata = receiver.subscribe_metadata();
+
+        let caption = TimedMetadata::caption(0, 90_000, "Hello");
+        let packet = caption.to_rtp(1, 1).unwrap().to_bytes().unwrap();
+        sender.send_metadata(&packet).await.unwrap();
+        assert!(sender.is_track_open(metadata_key()).await);
+
+        receiver.deliver_track_rtp(metadata_key(), &packet).await;
+        let received = metadata.recv().await.unwrap();
+        assert_eq!(TimedMetadata::from_rtp(&received).unwrap(), caption);
+    }
+
+    #[tokio::test]
+    async fn test_send_rtx_uses_own_stream() {
+        let transport = QuicMediaTransport::new();
+        transport.connect(test_peer()).await.unwrap();
+
+        let packet = &[0x80, 0x61, 0x00, 0x02, 0x00, 0x01];
+        transport.send_rtp(StreamType::Video, packet).await.unwrap();
+        transport.send_rtx(StreamType::Video, packet).await.unwrap();
+
+        assert_eq!(
+            transport.open_tracks(StreamType::Video).await.len(),
+            2,
+            "retransmissions must not share the media stream"
+        );
+        assert!(transport.is_track_open(rtx_key(StreamType::Video)).await);
+        let stats = transport.stats().await;
+        assert_eq!(stats.packets_sent, 2);
+        assert_eq!(stats.retransmitted_packets, 1);
+        assert_eq!(stats.retransmitted_bytes, packet.len() as u64);
+    }
+
     #[tokio::test]
     async fn test_recv_rtp_when_disconnected() {
         let transport = QuicMediaTransport::new();
@@ -1904,7 +2317,7 @@ impl QuicMediaTransport {
     ///
     /// `true` if the transport is in a healthy state.
     pub async fn is_healthy(&self) -> bool {
-        matches!(*self.state.read().await, MediaTransportState::Connected)
+        matches!(*self.state.read(), MediaTransportState::Connected)
     }
 
     /// Get the current health status with details
@@ -1913,7 +2326,7 @@ impl QuicMediaTransport {
     ///
     /// A tuple of (is_healthy, state, stats)
     pub async fn health_check(&self) -> (bool, MediaTransportState, TransportStats) {
-        let state = *self.state.read().await;
+        let state = *self.state.read();
         let stats = self.stats.read().await.clone();
         let is_healthy = matches!(state, MediaTransportState::Connected);
         (is_healthy, state, stats)
@@ -2074,3 +2487,199 @@ mod priority_tests {
         assert_eq!(rtcp_prio, StreamPriority::High);
     }
 }
+
+// ============================================================================
+// Keepalives
+// ============================================================================
+
+impl QuicMediaTransport {
+    /// Set the keepalive configuration
+    ///
+    /// Restarts the miss count so a reconfigured transport is not immediately
+    /// considered dead.
+    pub async fn set_keepalive_config(&self, config: KeepaliveConfig) {
+        self.keepalive.write().await.set_config(config);
+    }
+
+    /// Get the keepalive configuration
+    pub async fn keepalive_config(&self) -> KeepaliveConfig {
+        *self.keepalive.read().await.config()
+    }
+
+    /// Send a keepalive ping on the RTCP stream
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the transport is not connected or the send fails.
+    pub async fn send_keepalive(&self) -> Result<(), MediaTransportError> {
+        let ping = self.keepalive.write().await.next_ping();
+        self.send_rtcp(&ping.encode()).await
+    }
+
+    /// Handle a received RTCP packet if it is a keepalive
+    ///
+    /// Any keepalive counts as activity from the peer; pings are answered
+    /// with a pong.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the packet was a keepalive and has been consumed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if answering a ping fails.
+    pub async fn handle_keepalive(&self, packet: &[u8]) -> Result<bool, MediaTransportError> {
+        let Some(keepalive) = KeepalivePacket::parse(packet) else {
+            return Ok(false);
+        };
+
+        self.record_rtcp_received(packet.len() as u64).await;
+        if keepalive.kind == KeepaliveKind::Ping {
+            self.send_rtcp(&keepalive.pong().encode()).await?;
+        }
+        Ok(true)
+    }
+
+    /// Get the liveness of the remote peer
+    pub async fn liveness(&self) -> Liveness {
+        self.keepalive.read().await.liveness()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod keepalive_tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn test_peer() -> PeerConnection {
+        PeerConnection {
+            peer_id: "test-peer".to_string(),
+            remote_addr: "127.0.0.1:8080".parse().unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_keepalive_requires_connection() {
+        let transport = QuicMediaTransport::new();
+        assert!(matches!(
+            transport.send_keepalive().await,
+            Err(MediaTransportError::NotConnected)
+        ));
+
+        transport.connect(test_peer()).await.unwrap();
+        transport.send_keepalive().await.unwrap();
+        assert_eq!(transport.stats().await.packets_sent, 1);
+    }
+
+    #[tokio::test]
+    async fn test_handle_keepalive_answers_ping() {
+        let transport = QuicMediaTransport::new();
+        transport.connect(test_peer()).await.unwrap();
+
+        let ping = KeepalivePacket {
+            kind: KeepaliveKind::Ping,
+            seq: 7,
+        };
+        assert!(transport.handle_keepalive(&ping.encode()).await.unwrap());
+        assert!(transport
+            .handle_keepalive(&ping.pong().encode())
+            .await
+            .unwrap());
+
+        let stats = transport.stats().await;
+        assert_eq!(stats.rtcp_packets_received, 2);
+        // Only the ping is answered
+        assert_eq!(stats.packets_sent, 1);
+
+        assert!(!transport
+            .handle_keepalive(&[0x80, 201, 0, 0])
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_liveness_recovers_on_activity() {
+        let transport = QuicMediaTransport::new();
+        transport
+            .set_keepalive_config(KeepaliveConfig {
+                interval: Duration::from_secs(1),
+                miss_threshold: 1,
+                failure_threshold: 3,
+                ..Default::default()
+            })
+            .await;
+        transport.connect(test_peer()).await.unwrap();
+
+        tokio::time::advance(Duration::from_secs(3)).await;
+        assert_eq!(transport.liveness().await, Liveness::Dead { missed: 3 });
+
+        transport.record_received(StreamType::Audio, 100).await;
+        assert_eq!(transport.liveness().await, Liveness::Alive);
+    }
+}
+
+// ============================================================================
+// Media gate
+// ============================================================================
+
+impl QuicMediaTransport {
+    /// Set which media may be sent on this transport
+    pub async fn set_media_gate(&self, gate: MediaGate) {
+        *self.media_gate.write().await = gate;
+    }
+
+    /// Get the current media gate
+    pub async fn media_gate(&self) -> MediaGate {
+        *self.media_gate.read().await
+    }
+
+    /// Check if media of a stream type may flow
+    ///
+    /// Receivers use this to drop media a peer sent before acceptance.
+    pub async fn permits(&self, stream_type: StreamType) -> bool {
+        self.media_gate.read().await.permits(stream_type)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod media_gate_tests {
+    use super::*;
+    use std::net::SocketAddr;
+
+    fn peer() -> PeerConnection {
+        PeerConnection {
+            peer_id: "peer".to_string(),
+            remote_addr: "127.0.0.1:9000".parse::<SocketAddr>().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_gate_permits() {
+        assert!(MediaGate::Open.permits(StreamType::Video));
+        assert!(MediaGate::EarlyMedia.permits(StreamType::Audio));
+        assert!(!MediaGate::EarlyMedia.permits(StreamType::Video));
+        assert!(!MediaGate::EarlyMedia.permits(StreamType::Screen));
+        assert!(!MediaGate::Closed.permits(StreamType::Audio));
+        assert!(MediaGate::Closed.permits(StreamType::RtcpFeedback));
+        assert!(MediaGate::Closed.permits(StreamType::Data));
+    }
+
+    #[tokio::test]
+    async fn test_send_respects_gate() {
+        let transport = QuicMediaTransport::new();
+        transport.connect(peer()).await.unwrap();
+
+        transport.set_media_gate(MediaGate::EarlyMedia).await;
+        transport.send_audio(&[0x80, 0x0E]).await.unwrap();
+        assert!(matches!(
+            transport.send_video(&[0x80, 0x60]).await,
+            Err(MediaTransportError::MediaGated(StreamType::Video))
+        ));
+        assert_eq!(transport.stats().await.packets_sent, 1);
+
+        transport.set_media_gate(MediaGate::Open).await;
+        transport.send_video(&[0x80, 0x60]).await.unwrap();
+    }
+}
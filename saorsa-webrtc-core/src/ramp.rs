@@ -0,0 +1,210 @@
+//! Startup bitrate ramp and keyframe strategy
+//!
+//! A new call does not know what the path can carry. Starting video at
+//! full rate gets the first frames sharp but risks congesting a thin link
+//! before feedback arrives; starting low is safe but the picture takes a
+//! while to clear up. [`RampConfig`] picks the trade-off: the bitrate video
+//! starts at, how fast it climbs to the ceiling, and how often keyframes
+//! are sent while it does and afterwards.
+//!
+//! The default is conservative. Calls to a peer the
+//! [`peer_quality`](crate::peer_quality) cache knows start at the seeded
+//! rate instead, which skips most of the ramp.
+
+use crate::peer_quality::QualitySeed;
+use serde::{Deserialize, Serialize};
+
+/// Startup bitrate and keyframe configuration for video
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RampConfig {
+    /// Bitrate video starts at, in kbps
+    pub initial_bitrate_kbps: u32,
+    /// Bitrate the ramp climbs to, in kbps
+    pub max_bitrate_kbps: u32,
+    /// Increase per step, in percent of the current bitrate
+    pub step_percent: u32,
+    /// Time between steps, in milliseconds
+    pub step_interval_ms: u32,
+    /// Keyframe interval once the ramp is done, in milliseconds; keyframes
+    /// are only sent on request when zero
+    pub keyframe_interval_ms: u32,
+    /// Keyframe interval while ramping, in milliseconds, so receivers
+    /// that missed the first keyframe get a picture quickly; the regular
+    /// interval applies when zero
+    pub startup_keyframe_interval_ms: u32,
+}
+
+impl Default for RampConfig {
+    fn default() -> Self {
+        Self {
+            initial_bitrate_kbps: 300,
+            max_bitrate_kbps: 1_000,
+            step_percent: 20,
+            step_interval_ms: 1_000,
+            keyframe_interval_ms: 4_000,
+            startup_keyframe_interval_ms: 1_000,
+        }
+    }
+}
+
+impl RampConfig {
+    /// Start at full rate with frequent keyframes, for fast first frames
+    /// on links known to be good
+    #[must_use]
+    pub fn fast_start() -> Self {
+        Self {
+            initial_bitrate_kbps: 1_000,
+            startup_keyframe_interval_ms: 500,
+            ..Self::default()
+        }
+    }
+}
+
+/// Video bitrate and keyframe schedule of one call from its start
+///
+/// Times are milliseconds since media started flowing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitrateRamp {
+    config: RampConfig,
+    start_kbps: u32,
+}
+
+impl BitrateRamp {
+    /// Ramp from the configured initial bitrate, or from what the path
+    /// carried last time if `seed` is given
+    #[must_use]
+    pub fn new(config: RampConfig, seed: Option<&QualitySeed>) -> Self {
+        let start_kbps = seed
+            .map_or(config.initial_bitrate_kbps, |seed| seed.bitrate_kbps)
+            .min(config.max_bitrate_kbps);
+        Self { config, start_kbps }
+    }
+
+    /// Bitrate the ramp starts at, in kbps
+    #[must_use]
+    pub fn start_kbps(&self) -> u32 {
+        self.start_kbps
+    }
+
+    /// Target bitrate at `elapsed_ms`, in kbps
+    #[must_use]
+    pub fn bitrate_kbps(&self, elapsed_ms: u64) -> u32 {
+        let max = u64::from(self.config.max_bitrate_kbps);
+        let steps = elapsed_ms / u64::from(self.config.step_interval_ms.max(1));
+        let mut kbps = u64::from(self.start_kbps);
+        for _ in 0..steps {
+            if kbps >= max {
+                break;
+            }
+            // Always climb at least 1 kbps so small rates are not stuck
+            let step = (kbps * u64::from(self.config.step_percent) / 100).max(1);
+            kbps += step;
+        }
+        u32::try_from(kbps.min(max)).unwrap_or(u32::MAX)
+    }
+
+    /// Check if the bitrate is still climbing at `elapsed_ms`
+    #[must_use]
+    pub fn is_ramping(&self, elapsed_ms: u64) -> bool {
+        self.config.step_percent > 0 && self.bitrate_kbps(elapsed_ms) < self.config.max_bitrate_kbps
+    }
+
+    /// Keyframe interval at `elapsed_ms`, in milliseconds; `None` when
+    /// keyframes are only sent on request
+    #[must_use]
+    pub fn keyframe_interval_ms(&self, elapsed_ms: u64) -> Option<u32> {
+        let interval =
+            if self.is_ramping(elapsed_ms) && self.config.startup_keyframe_interval_ms > 0 {
+                self.config.startup_keyframe_interval_ms
+            } else {
+                self.config.keyframe_interval_ms
+            };
+        (interval > 0).then_some(interval)
+    }
+
+    /// Check if a keyframe is due at `elapsed_ms`, given when the last one
+    /// was sent
+    ///
+    /// The first frame is always a keyframe.
+    #[must_use]
+    pub fn keyframe_due(&self, elapsed_ms: u64, last_keyframe_ms: Option<u64>) -> bool {
+        let Some(last) = last_keyframe_ms else {
+            return true;
+        };
+        self.keyframe_interval_ms(elapsed_ms)
+            .is_some_and(|interval| elapsed_ms.saturating_sub(last) >= u64::from(interval))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_ramp_climbs_to_max() {
+        let ramp = BitrateRamp::new(RampConfig::default(), None);
+        assert_eq!(ramp.bitrate_kbps(0), 300);
+        assert_eq!(ramp.bitrate_kbps(999), 300);
+        assert_eq!(ramp.bitrate_kbps(1_000), 360);
+        assert_eq!(ramp.bitrate_kbps(2_000), 432);
+
+        let mut previous = 0;
+        for second in 0..10 {
+            let kbps = ramp.bitrate_kbps(second * 1_000);
+            assert!(kbps >= previous);
+            previous = kbps;
+        }
+        assert_eq!(ramp.bitrate_kbps(10_000), 1_000);
+        assert!(!ramp.is_ramping(10_000));
+        assert!(ramp.is_ramping(0));
+    }
+
+    #[test]
+    fn test_seed_skips_ramp() {
+        let seed = QualitySeed {
+            bitrate_kbps: 800,
+            codec: "video/H264".to_string(),
+        };
+        let ramp = BitrateRamp::new(RampConfig::default(), Some(&seed));
+        assert_eq!(ramp.start_kbps(), 800);
+        assert_eq!(ramp.bitrate_kbps(2_000), 1_000);
+
+        // A known slow path starts below the default
+        let seed = QualitySeed {
+            bitrate_kbps: 100,
+            codec: "video/H264".to_string(),
+        };
+        let ramp = BitrateRamp::new(RampConfig::default(), Some(&seed));
+        assert_eq!(ramp.start_kbps(), 100);
+
+        let ramp = BitrateRamp::new(RampConfig::fast_start(), None);
+        assert!(!ramp.is_ramping(0));
+    }
+
+    #[test]
+    fn test_keyframes_frequent_while_ramping() {
+        let ramp = BitrateRamp::new(RampConfig::default(), None);
+        assert!(ramp.keyframe_due(0, None));
+        assert_eq!(ramp.keyframe_interval_ms(0), Some(1_000));
+        assert!(ramp.keyframe_due(1_000, Some(0)));
+        assert!(!ramp.keyframe_due(1_500, Some(1_000)));
+
+        // Settled: the regular interval
+        assert_eq!(ramp.keyframe_interval_ms(20_000), Some(4_000));
+        assert!(!ramp.keyframe_due(21_000, Some(20_000)));
+        assert!(ramp.keyframe_due(24_000, Some(20_000)));
+
+        let on_request = BitrateRamp::new(
+            RampConfig {
+                keyframe_interval_ms: 0,
+                startup_keyframe_interval_ms: 0,
+                ..RampConfig::default()
+            },
+            None,
+        );
+        assert_eq!(on_request.keyframe_interval_ms(0), None);
+        assert!(!on_request.keyframe_due(60_000, Some(0)));
+    }
+}
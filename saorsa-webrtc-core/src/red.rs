@@ -0,0 +1,287 @@
+//! Redundant audio (RED) for lossy links
+//!
+//! On links that lose packets, a lost Opus frame is a gap the listener
+//! hears. With redundancy enabled, each audio packet also carries copies of
+//! the previous one or two frames in the RFC 2198 format, so a lost packet
+//! is usually repaired by the next one that arrives.
+//!
+//! Redundancy is part of the negotiated [`AudioParameters`]: it is used
+//! only when both peers ask for it, at the lower of their settings, and the
+//! copies come out of the audio bitrate budget rather than on top of it
+//! (see [`AudioParameters::encoder_config`]). [`RedEncoder`] builds the
+//! packets and [`RedDecoder`] turns them back into frames, skipping the
+//! copies of frames that already arrived.
+
+use crate::types::AudioParameters;
+use std::collections::VecDeque;
+use thiserror::Error;
+
+/// Payload type of RED audio packets
+pub const RED_PAYLOAD_TYPE: u8 = 63;
+
+/// Most previous frames repeated in one packet
+pub const MAX_REDUNDANCY: u8 = 2;
+
+/// Largest timestamp offset a redundant block header can express
+const MAX_TIMESTAMP_OFFSET: u32 = (1 << 14) - 1;
+
+/// Largest redundant block a header can describe, in bytes
+const MAX_BLOCK_LEN: usize = (1 << 10) - 1;
+
+/// Bytes of a redundant block header
+const BLOCK_HEADER_LEN: usize = 4;
+
+/// RED errors
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RedError {
+    /// A packet is not in the RED format
+    #[error("Malformed RED packet: {0}")]
+    Malformed(String),
+}
+
+/// One audio frame taken out of a RED packet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedFrame {
+    /// RTP timestamp of the frame
+    pub timestamp: u32,
+    /// Payload type of the frame, e.g. Opus
+    pub payload_type: u8,
+    /// The encoded frame
+    pub data: Vec<u8>,
+    /// The frame was a copy repairing an earlier packet
+    pub recovered: bool,
+}
+
+/// Builds RED packets from consecutive encoded frames
+#[derive(Debug, Clone)]
+pub struct RedEncoder {
+    payload_type: u8,
+    redundancy: u8,
+    history: VecDeque<(u32, Vec<u8>)>,
+}
+
+impl RedEncoder {
+    /// Repeat `redundancy` previous frames of `payload_type` in each packet
+    ///
+    /// At most [`MAX_REDUNDANCY`] frames are repeated.
+    #[must_use]
+    pub fn new(payload_type: u8, redundancy: u8) -> Self {
+        let redundancy = redundancy.min(MAX_REDUNDANCY);
+        Self {
+            payload_type,
+            redundancy,
+            history: VecDeque::with_capacity(usize::from(redundancy)),
+        }
+    }
+
+    /// Encoder for negotiated parameters, if they use redundancy
+    #[must_use]
+    pub fn for_params(payload_type: u8, params: &AudioParameters) -> Option<Self> {
+        (params.redundancy > 0).then(|| Self::new(payload_type, params.redundancy))
+    }
+
+    /// Previous frames repeated in each packet
+    #[must_use]
+    pub fn redundancy(&self) -> u8 {
+        self.redundancy
+    }
+
+    /// Build the RED payload for the frame captured at `timestamp`
+    ///
+    /// Previous frames too old or too large for a block header are left
+    /// out, as are empty (DTX) frames.
+    pub fn encode(&mut self, timestamp: u32, frame: &[u8]) -> Vec<u8> {
+        let blocks: Vec<(u32, &[u8])> = self
+            .history
+            .iter()
+            .filter(|(_, data)| !data.is_empty() && data.len() <= MAX_BLOCK_LEN)
+            .map(|(previous, data)| (timestamp.wrapping_sub(*previous), data.as_slice()))
+            .filter(|(offset, _)| (1..=MAX_TIMESTAMP_OFFSET).contains(offset))
+            .collect();
+
+        let data_len: usize = blocks.iter().map(|(_, data)| data.len()).sum();
+        let mut payload =
+            Vec::with_capacity(blocks.len() * BLOCK_HEADER_LEN + 1 + data_len + frame.len());
+        for (offset, data) in &blocks {
+            // F=1 | PT (7) | timestamp offset (14) | block length (10)
+            let header = (1 << 31)
+                | (u32::from(self.payload_type & 0x7F) << 24)
+                | (offset << 10)
+                | data.len() as u32;
+            payload.extend_from_slice(&header.to_be_bytes());
+        }
+        payload.push(self.payload_type & 0x7F);
+        for (_, data) in &blocks {
+            payload.extend_from_slice(data);
+        }
+        payload.extend_from_slice(frame);
+
+        if self.redundancy > 0 {
+            if self.history.len() == usize::from(self.redundancy) {
+                self.history.pop_front();
+            }
+            self.history.push_back((timestamp, frame.to_vec()));
+        }
+        payload
+    }
+}
+
+/// Split a RED payload into its frames, oldest first
+///
+/// `timestamp` is the RTP timestamp of the packet, which is that of its
+/// primary frame.
+///
+/// # Errors
+///
+/// Returns error if the headers do not describe the payload
+pub fn parse(timestamp: u32, payload: &[u8]) -> Result<Vec<RedFrame>, RedError> {
+    let mut headers = Vec::new();
+    let mut offset = 0;
+    loop {
+        let Some(&first) = payload.get(offset) else {
+            return Err(RedError::Malformed("missing primary header".to_string()));
+        };
+        if first & 0x80 == 0 {
+            offset += 1;
+            break;
+        }
+        let Some(header) = payload.get(offset..offset + BLOCK_HEADER_LEN) else {
+            return Err(RedError::Malformed("truncated block header".to_string()));
+        };
+        let header = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+        headers.push((
+            ((header >> 24) & 0x7F) as u8,
+            (header >> 10) & MAX_TIMESTAMP_OFFSET,
+            (header & MAX_BLOCK_LEN as u32) as usize,
+        ));
+        offset += BLOCK_HEADER_LEN;
+    }
+    let primary_type = payload[offset - 1] & 0x7F;
+
+    let mut frames = Vec::with_capacity(headers.len() + 1);
+    for (payload_type, timestamp_offset, len) in headers {
+        let Some(data) = payload.get(offset..offset + len) else {
+            return Err(RedError::Malformed(format!(
+                "block of {len} bytes past the end of the packet"
+            )));
+        };
+        frames.push(RedFrame {
+            timestamp: timestamp.wrapping_sub(timestamp_offset),
+            payload_type,
+            data: data.to_vec(),
+            recovered: true,
+        });
+        offset += len;
+    }
+    frames.push(RedFrame {
+        timestamp,
+        payload_type: primary_type,
+        data: payload[offset..].to_vec(),
+        recovered: false,
+    });
+    Ok(frames)
+}
+
+/// Turns received RED packets back into a stream of frames
+///
+/// Copies of frames already delivered are dropped, so each frame comes out
+/// once whether its own packet or a later one carried it.
+#[derive(Debug, Clone, Default)]
+pub struct RedDecoder {
+    last_timestamp: Option<u32>,
+    recovered: u64,
+}
+
+impl RedDecoder {
+    /// Create a decoder that has seen nothing yet
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Frames of a received packet not delivered before, oldest first
+    ///
+    /// A packet older than what was already delivered yields nothing: its
+    /// frame was either repaired already or is too late to play.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the payload is not in the RED format
+    pub fn decode(&mut self, timestamp: u32, payload: &[u8]) -> Result<Vec<RedFrame>, RedError> {
+        let frames: Vec<RedFrame> = parse(timestamp, payload)?
+            .into_iter()
+            .filter(|frame| {
+                self.last_timestamp
+                    .is_none_or(|last| (frame.timestamp.wrapping_sub(last) as i32) > 0)
+            })
+            .collect();
+        if let Some(newest) = frames.last() {
+            self.last_timestamp = Some(newest.timestamp);
+        }
+        self.recovered += frames.iter().filter(|frame| frame.recovered).count() as u64;
+        Ok(frames)
+    }
+
+    /// Frames repaired from copies so far
+    #[must_use]
+    pub fn recovered(&self) -> u64 {
+        self.recovered
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const OPUS: u8 = 111;
+
+    #[test]
+    fn test_encode_parse_roundtrip() {
+        let mut encoder = RedEncoder::new(OPUS, 2);
+        assert_eq!(parse(0, &encoder.encode(0, b"a")).unwrap().len(), 1);
+        encoder.encode(960, b"bb");
+        let payload = encoder.encode(1920, b"ccc");
+
+        // Two 4-byte headers, the primary header, then the frames
+        assert_eq!(payload.len(), 2 * 4 + 1 + 1 + 2 + 3);
+        let frames = parse(1920, &payload).unwrap();
+        let timestamps: Vec<u32> = frames.iter().map(|frame| frame.timestamp).collect();
+        assert_eq!(timestamps, [0, 960, 1920]);
+        assert_eq!(frames[0].data, b"a");
+        assert_eq!(frames[2].data, b"ccc");
+        assert!(frames[0].recovered && !frames[2].recovered);
+        assert!(frames.iter().all(|frame| frame.payload_type == OPUS));
+
+        // Without redundancy only the primary header is added
+        let mut plain = RedEncoder::new(OPUS, 0);
+        plain.encode(0, b"a");
+        assert_eq!(plain.encode(960, b"bb"), [OPUS, b'b', b'b']);
+
+        assert!(parse(0, &[]).is_err());
+        assert!(parse(0, &payload[..6]).is_err());
+        assert!(parse(1920, &payload[..10]).is_err());
+    }
+
+    #[test]
+    fn test_decoder_repairs_lost_packet_once() {
+        let mut encoder = RedEncoder::new(OPUS, 1);
+        let packets: Vec<(u32, Vec<u8>)> = (0..4u32)
+            .map(|i| (i * 960, encoder.encode(i * 960, &[i as u8; 10])))
+            .collect();
+
+        let mut decoder = RedDecoder::new();
+        let mut played = Vec::new();
+        // The second packet is lost
+        for (timestamp, payload) in [&packets[0], &packets[2], &packets[3]] {
+            played.extend(decoder.decode(*timestamp, payload).unwrap());
+        }
+        let timestamps: Vec<u32> = played.iter().map(|frame| frame.timestamp).collect();
+        assert_eq!(timestamps, [0, 960, 1920, 2880]);
+        assert_eq!(played[1].data, [1; 10]);
+        assert_eq!(decoder.recovered(), 1);
+
+        // A late duplicate is not played again
+        assert!(decoder.decode(960, &packets[1].1).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,230 @@
+//! Redaction of peer identities and addresses in logs
+//!
+//! Peer identities and socket addresses can identify people, so log fields
+//! carrying them go through [`identity`] and [`addr`]. The redaction applied
+//! is process-wide, set from [`WebRtcConfig::redaction`] when a service is
+//! created or directly with [`set_config`]:
+//!
+//! - [`Redaction::None`] logs values unchanged (the default).
+//! - [`Redaction::Truncate`] keeps the start of an identity and the network
+//!   prefix of an address (/24 for IPv4, /48 for IPv6), dropping the port.
+//! - [`Redaction::Hash`] replaces the value with a short BLAKE3 digest, so
+//!   the same peer can still be followed through one log.
+//!
+//! [`WebRtcConfig::redaction`]: crate::service::WebRtcConfig::redaction
+
+use ant_quic::transport::TransportAddr;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Characters of an identity kept by [`Redaction::Truncate`]
+pub const TRUNCATED_IDENTITY_CHARS: usize = 4;
+
+/// Hex digits of the digest logged by [`Redaction::Hash`]
+pub const HASH_HEX_DIGITS: usize = 8;
+
+/// How a kind of value is written to logs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Redaction {
+    /// Log the value unchanged
+    None,
+    /// Log a shortened, less identifying form
+    Truncate,
+    /// Log a short digest of the value
+    Hash,
+}
+
+impl Default for Redaction {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+impl Redaction {
+    const fn to_u8(self) -> u8 {
+        match self {
+            Self::None => 0,
+            Self::Truncate => 1,
+            Self::Hash => 2,
+        }
+    }
+
+    const fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::Truncate,
+            2 => Self::Hash,
+            _ => Self::None,
+        }
+    }
+}
+
+/// Redaction of identities and addresses in log fields
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedactionConfig {
+    /// Peer identities
+    #[serde(default)]
+    pub identities: Redaction,
+    /// Socket and IP addresses
+    #[serde(default)]
+    pub addresses: Redaction,
+}
+
+impl RedactionConfig {
+    /// Apply the same redaction to identities and addresses
+    #[must_use]
+    pub fn all(redaction: Redaction) -> Self {
+        Self {
+            identities: redaction,
+            addresses: redaction,
+        }
+    }
+}
+
+static IDENTITIES: AtomicU8 = AtomicU8::new(0);
+static ADDRESSES: AtomicU8 = AtomicU8::new(0);
+
+/// Set the process-wide redaction of log fields
+pub fn set_config(config: RedactionConfig) {
+    IDENTITIES.store(config.identities.to_u8(), Ordering::Relaxed);
+    ADDRESSES.store(config.addresses.to_u8(), Ordering::Relaxed);
+}
+
+/// The process-wide redaction of log fields
+#[must_use]
+pub fn config() -> RedactionConfig {
+    RedactionConfig {
+        identities: Redaction::from_u8(IDENTITIES.load(Ordering::Relaxed)),
+        addresses: Redaction::from_u8(ADDRESSES.load(Ordering::Relaxed)),
+    }
+}
+
+/// A log field value after redaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redacted(String);
+
+impl fmt::Display for Redacted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Redact a peer identity for logging
+pub fn identity(value: impl fmt::Display) -> Redacted {
+    redact_identity(&value.to_string(), config().identities)
+}
+
+/// Redact a socket address for logging
+pub fn addr(addr: SocketAddr) -> Redacted {
+    redact_addr(addr, config().addresses)
+}
+
+/// Redact a transport address for logging
+///
+/// Non-IP addresses, such as Bluetooth device addresses, are shortened or
+/// hashed like identities.
+pub fn transport_addr(addr: &TransportAddr) -> Redacted {
+    let redaction = config().addresses;
+    match addr.as_socket_addr() {
+        Some(socket_addr) => redact_addr(socket_addr, redaction),
+        None => redact_identity(&format!("{addr:?}"), redaction),
+    }
+}
+
+fn redact_identity(value: &str, redaction: Redaction) -> Redacted {
+    Redacted(match redaction {
+        Redaction::None => value.to_string(),
+        Redaction::Truncate if value.chars().count() <= TRUNCATED_IDENTITY_CHARS => "…".to_string(),
+        Redaction::Truncate => {
+            let kept: String = value.chars().take(TRUNCATED_IDENTITY_CHARS).collect();
+            format!("{kept}…")
+        }
+        Redaction::Hash => digest(value),
+    })
+}
+
+fn redact_addr(addr: SocketAddr, redaction: Redaction) -> Redacted {
+    Redacted(match redaction {
+        Redaction::None => addr.to_string(),
+        Redaction::Truncate => match addr.ip().to_canonical() {
+            IpAddr::V4(ip) => {
+                let [a, b, c, _] = ip.octets();
+                format!("{a}.{b}.{c}.0/24")
+            }
+            IpAddr::V6(ip) => {
+                let [a, b, c, ..] = ip.segments();
+                format!("{a:x}:{b:x}:{c:x}::/48")
+            }
+        },
+        Redaction::Hash => digest(&addr.to_string()),
+    })
+}
+
+fn digest(value: &str) -> String {
+    let hash = blake3::hash(value.as_bytes()).to_hex();
+    format!("#{}", &hash[..HASH_HEX_DIGITS])
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identity_redaction() {
+        let peer = "alpha-bravo-charlie-delta";
+        assert_eq!(redact_identity(peer, Redaction::None).to_string(), peer);
+        assert_eq!(
+            redact_identity(peer, Redaction::Truncate).to_string(),
+            "alph…"
+        );
+        assert_eq!(redact_identity("abc", Redaction::Truncate).to_string(), "…");
+
+        let hashed = redact_identity(peer, Redaction::Hash);
+        assert_eq!(hashed.to_string().len(), 1 + HASH_HEX_DIGITS);
+        assert_eq!(hashed, redact_identity(peer, Redaction::Hash));
+        assert_ne!(hashed, redact_identity("echo", Redaction::Hash));
+    }
+
+    #[test]
+    fn test_address_redaction() {
+        let v4: SocketAddr = "203.0.113.77:5000".parse().unwrap();
+        let v6: SocketAddr = "[2001:db8:aa:bb::1]:443".parse().unwrap();
+        let mapped: SocketAddr = "[::ffff:203.0.113.77]:5000".parse().unwrap();
+
+        assert_eq!(
+            redact_addr(v4, Redaction::None).to_string(),
+            "203.0.113.77:5000"
+        );
+        assert_eq!(
+            redact_addr(v4, Redaction::Truncate).to_string(),
+            "203.0.113.0/24"
+        );
+        assert_eq!(
+            redact_addr(v6, Redaction::Truncate).to_string(),
+            "2001:db8:aa::/48"
+        );
+        assert_eq!(
+            redact_addr(mapped, Redaction::Truncate).to_string(),
+            "203.0.113.0/24"
+        );
+        assert!(!redact_addr(v4, Redaction::Hash).to_string().contains("203"));
+    }
+
+    #[test]
+    fn test_config_round_trip() {
+        let config = RedactionConfig {
+            identities: Redaction::Hash,
+            addresses: Redaction::Truncate,
+        };
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(json, r#"{"identities":"hash","addresses":"truncate"}"#);
+        assert_eq!(Redaction::from_u8(Redaction::Hash.to_u8()), Redaction::Hash);
+        assert_eq!(
+            RedactionConfig::all(Redaction::None),
+            RedactionConfig::default()
+        );
+    }
+}
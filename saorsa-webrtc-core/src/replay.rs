@@ -0,0 +1,643 @@
+//! Recording and deterministic replay of signaling sessions
+//!
+//! A [`SignalingRecorder`] is a [`SignalingInterceptor`] that notes every
+//! message a [`SignalingHandler`](crate::signaling::SignalingHandler) sends
+//! and receives, with the time since recording started. It can append each
+//! message to a file as it happens, one JSON object per line, so a session
+//! from the field survives a crash:
+//!
+//! ```text
+//! {"at_ms":0,"direction":"inbound","peer":"alice","message":{"type":"capability_exchange",…}}
+//! {"at_ms":412,"direction":"inbound","peer":"alice","message":{"type":"cancel",…}}
+//! ```
+//!
+//! A [`Replayer`] feeds the inbound messages of a [`SignalingRecording`] to a
+//! fresh [`WebRtcService`] built on a [`MockClock`], advancing the clock to
+//! each message's recorded time before handing it over. The service sees
+//! the same messages in the same order at the same times on every run, so
+//! a race reported from the field reproduces in a test from its log file:
+//!
+//! ```rust,no_run
+//! use saorsa_webrtc_core::replay::{Replayer, SignalingRecording};
+//! # use saorsa_webrtc_core::{MockClock, PeerIdentityString, WebRtcService};
+//! # use saorsa_webrtc_core::signaling::SignalingTransport;
+//! # async fn example<T: SignalingTransport>(
+//! #     service: WebRtcService<PeerIdentityString, T>,
+//! #     clock: MockClock,
+//! # ) -> Result<(), saorsa_webrtc_core::replay::ReplayError> {
+//! let recording = SignalingRecording::load("field-session.jsonl".as_ref())?;
+//! let report = Replayer::new(&service, PeerIdentityString::new("bob"), clock)
+//!     .run(&recording)
+//!     .await;
+//! assert!(report.failures.is_empty(), "{:?}", report.failures);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Outbound messages are kept in the recording for reading alongside the
+//! inbound ones, but are not replayed: the service under test sends its
+//! own. Legacy WebRTC messages and handoff joins, which need a media link,
+//! are skipped.
+
+use crate::clock::{Clock, MockClock, SharedClock};
+use crate::identity::PeerIdentity;
+use crate::service::WebRtcService;
+use crate::signaling::{
+    InterceptorDecision, SignalingInterceptor, SignalingMessage, SignalingTransport,
+};
+use crate::types::{CallId, CallOffer, MediaCapabilities, MediaConstraints};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::time::Instant;
+
+/// Recording and replay errors
+#[derive(Error, Debug)]
+pub enum ReplayError {
+    /// Reading or writing the recording file failed
+    #[error("Recording I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// A recorded message could not be encoded or decoded
+    #[error("Recording format error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Which way a recorded message went
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    /// Received from the peer
+    Inbound,
+    /// Sent to the peer
+    Outbound,
+}
+
+/// One line of a recording
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedMessage {
+    /// Milliseconds since recording started
+    pub at_ms: u64,
+    /// Which way the message went
+    pub direction: Direction,
+    /// Peer the message came from or went to
+    pub peer: String,
+    /// The message
+    pub message: SignalingMessage,
+}
+
+impl RecordedMessage {
+    /// Time since recording started
+    #[must_use]
+    pub fn at(&self) -> Duration {
+        Duration::from_millis(self.at_ms)
+    }
+}
+
+/// The messages of a recorded signaling session, oldest first
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SignalingRecording {
+    /// Recorded messages
+    pub messages: Vec<RecordedMessage>,
+}
+
+impl SignalingRecording {
+    /// Parse a recording from JSON lines
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a line is not a recorded message
+    pub fn from_jsonl(jsonl: &str) -> Result<Self, ReplayError> {
+        let messages = jsonl
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?;
+        Ok(Self { messages })
+    }
+
+    /// Encode the recording as JSON lines
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a message cannot be encoded
+    pub fn to_jsonl(&self) -> Result<String, ReplayError> {
+        let mut out = String::new();
+        for message in &self.messages {
+            out.push_str(&serde_json::to_string(message)?);
+            out.push('\n');
+        }
+        Ok(out)
+    }
+
+    /// Read a recording file
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be read or a line is not a recorded
+    /// message
+    pub fn load(path: &Path) -> Result<Self, ReplayError> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut messages = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                messages.push(serde_json::from_str(&line)?);
+            }
+        }
+        Ok(Self { messages })
+    }
+
+    /// Write the recording to a file, replacing it
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be written
+    pub fn save(&self, path: &Path) -> Result<(), ReplayError> {
+        let mut out = BufWriter::new(File::create(path)?);
+        out.write_all(self.to_jsonl()?.as_bytes())?;
+        out.flush()?;
+        Ok(())
+    }
+
+    /// Messages received from peers
+    pub fn inbound(&self) -> impl Iterator<Item = &RecordedMessage> {
+        self.messages
+            .iter()
+            .filter(|m| m.direction == Direction::Inbound)
+    }
+}
+
+/// Signaling interceptor that records every message passing through it
+///
+/// Interceptors run in registration order for outbound messages and in
+/// reverse for inbound ones, so register the recorder first to record the
+/// messages the application sends and receives, after other interceptors
+/// (e.g. encryption) have undone their changes.
+pub struct SignalingRecorder {
+    clock: SharedClock,
+    started: Instant,
+    messages: Mutex<Vec<RecordedMessage>>,
+    file: Option<Mutex<File>>,
+}
+
+impl SignalingRecorder {
+    /// Record in memory
+    #[must_use]
+    pub fn new(clock: SharedClock) -> Self {
+        Self {
+            started: clock.now(),
+            clock,
+            messages: Mutex::new(Vec::new()),
+            file: None,
+        }
+    }
+
+    /// Record in memory and append each message to a file as it passes
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be opened
+    pub fn to_file(path: &Path, clock: SharedClock) -> Result<Self, ReplayError> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            file: Some(Mutex::new(file)),
+            ..Self::new(clock)
+        })
+    }
+
+    /// Messages recorded so far
+    #[must_use]
+    pub fn recording(&self) -> SignalingRecording {
+        SignalingRecording {
+            messages: self.messages.lock().clone(),
+        }
+    }
+
+    /// Number of messages recorded so far
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.messages.lock().len()
+    }
+
+    /// Check if nothing has been recorded yet
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.messages.lock().is_empty()
+    }
+
+    fn record(&self, direction: Direction, peer: String, message: &SignalingMessage) {
+        let elapsed = self.clock.now().saturating_duration_since(self.started);
+        let recorded = RecordedMessage {
+            at_ms: u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+            direction,
+            peer,
+            message: message.clone(),
+        };
+        if let Some(file) = &self.file {
+            let written = serde_json::to_string(&recorded)
+                .map_err(std::io::Error::from)
+                .and_then(|line| writeln!(file.lock(), "{line}"));
+            if let Err(e) = written {
+                tracing::warn!(error = %e, "Failed to append to signaling recording");
+            }
+        }
+        self.messages.lock().push(recorded);
+    }
+}
+
+impl fmt::Debug for SignalingRecorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SignalingRecorder")
+            .field("messages", &self.len())
+            .field("to_file", &self.file.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl<P> SignalingInterceptor<P> for SignalingRecorder
+where
+    P: fmt::Display + Send + Sync,
+{
+    async fn on_outbound(&self, peer: &P, message: SignalingMessage) -> InterceptorDecision {
+        self.record(Direction::Outbound, peer.to_string(), &message);
+        InterceptorDecision::Continue(message)
+    }
+
+    async fn on_inbound(&self, peer: &P, message: SignalingMessage) -> InterceptorDecision {
+        self.record(Direction::Inbound, peer.to_string(), &message);
+        InterceptorDecision::Continue(message)
+    }
+}
+
+/// A recorded message the service refused during replay
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayFailure {
+    /// Position of the message in the recording
+    pub index: usize,
+    /// Recorded time of the message
+    pub at: Duration,
+    /// What the service returned
+    pub error: String,
+}
+
+/// Outcome of replaying a recording
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReplayReport {
+    /// Inbound messages the service accepted
+    pub applied: usize,
+    /// Outbound and unreplayable messages passed over
+    pub skipped: usize,
+    /// Inbound messages the service refused, in recording order
+    pub failures: Vec<ReplayFailure>,
+}
+
+/// Replays recorded signaling into a service
+///
+/// The service must have been built with the replayer's clock (see
+/// [`WebRtcServiceBuilder::with_clock`](crate::service::WebRtcServiceBuilder::with_clock))
+/// and not have seen any calls, so that each run starts from the same
+/// state.
+pub struct Replayer<'a, I: PeerIdentity, T: SignalingTransport> {
+    service: &'a WebRtcService<I, T>,
+    local: I,
+    clock: MockClock,
+}
+
+impl<'a, I: PeerIdentity, T: SignalingTransport> Replayer<'a, I, T> {
+    /// Create a replayer for a service answering as `local`
+    #[must_use]
+    pub fn new(service: &'a WebRtcService<I, T>, local: I, clock: MockClock) -> Self {
+        Self {
+            service,
+            local,
+            clock,
+        }
+    }
+
+    /// Replay every inbound message of a recording, in order
+    ///
+    /// Before each message the clock is advanced to the message's recorded
+    /// time, and after it background tasks get a chance to run. A message
+    /// the service refuses is reported and replay carries on, as the live
+    /// session did.
+    pub async fn run(&self, recording: &SignalingRecording) -> ReplayReport {
+        let mut report = ReplayReport::default();
+        for (index, recorded) in recording.messages.iter().enumerate() {
+            let behind = recorded.at().saturating_sub(self.clock.elapsed());
+            self.clock.advance(behind);
+            if recorded.direction == Direction::Outbound {
+                report.skipped += 1;
+                continue;
+            }
+            match self.dispatch(&recorded.peer, &recorded.message).await {
+                Ok(true) => report.applied += 1,
+                Ok(false) => report.skipped += 1,
+                Err(error) => {
+                    tracing::debug!(index, error = %error, "Replayed message refused");
+                    report.failures.push(ReplayFailure {
+                        index,
+                        at: recorded.at(),
+                        error,
+                    });
+                }
+            }
+            tokio::task::yield_now().await;
+        }
+        report
+    }
+
+    /// Hand one inbound message to the service
+    ///
+    /// Returns `Ok(false)` for messages that are not replayed.
+    async fn dispatch(&self, peer: &str, message: &SignalingMessage) -> Result<bool, String> {
+        if message.is_legacy_webrtc()
+            || matches!(
+                message,
+                SignalingMessage::ConnectionReady { .. } | SignalingMessage::HandoffJoin { .. }
+            )
+        {
+            return Ok(false);
+        }
+
+        let call_id: CallId = message
+            .call_id()
+            .ok_or_else(|| format!("Invalid session ID: {}", message.session_id()))?;
+        let service = self.service;
+        let manager = service.call_manager();
+        match message.clone() {
+            SignalingMessage::CapabilityExchange { audio, video, .. } => {
+                let caller = I::from_string_repr(peer).map_err(|e| e.to_string())?;
+                let offered = MediaConstraints {
+                    audio,
+                    video,
+                    screen_share: false,
+                    latency: None,
+                };
+                service
+                    .handle_incoming_call(CallOffer {
+                        call_id,
+                        caller,
+                        callee: self.local.clone(),
+                        sdp: String::new(),
+                        media_types: offered.to_media_types(),
+                        timestamp: self.clock.utc_now(),
+                    })
+                    .await
+                    .map(drop)
+                    .map_err(|e| e.to_string())
+            }
+            SignalingMessage::ConnectionConfirm {
+                audio,
+                video,
+                data_channel,
+                max_bandwidth_kbps,
+                ..
+            } => manager
+                .confirm_connection(
+                    call_id,
+                    MediaCapabilities {
+                        audio,
+                        video,
+                        data_channel,
+                        max_bandwidth_kbps,
+                        ..Default::default()
+                    },
+                )
+                .await
+                .map_err(|e| e.to_string()),
+            SignalingMessage::TrackUpdate { tracks, .. } => manager
+                .handle_track_update(call_id, tracks)
+                .await
+                .map_err(|e| e.to_string()),
+            SignalingMessage::LayerSelection {
+                track_id, layer, ..
+            } => service
+                .handle_layer_selection(call_id, track_id, layer)
+                .await
+                .map_err(|e| e.to_string()),
+            SignalingMessage::HandoffOffer { key, .. } => manager
+                .handle_handoff_offer(call_id, &key)
+                .await
+                .map_err(|e| e.to_string()),
+            SignalingMessage::HandoffComplete { .. } => manager
+                .handle_handoff_complete(call_id)
+                .await
+                .map_err(|e| e.to_string()),
+            SignalingMessage::Progress { progress, .. } => service
+                .handle_progress(call_id, progress)
+                .await
+                .map_err(|e| e.to_string()),
+            SignalingMessage::AudioOnly { active, .. } => service
+                .handle_audio_only(call_id, active)
+                .await
+                .map_err(|e| e.to_string()),
+            SignalingMessage::Cancel { reason, .. } => service
+                .handle_cancel(call_id, reason)
+                .await
+                .map_err(|e| e.to_string()),
+            SignalingMessage::Bye { .. } => {
+                service.end_call(call_id).await.map_err(|e| e.to_string())
+            }
+            SignalingMessage::Offer { .. }
+            | SignalingMessage::Answer { .. }
+            | SignalingMessage::IceCandidate { .. }
+            | SignalingMessage::IceComplete { .. }
+            | SignalingMessage::ConnectionReady { .. }
+            | SignalingMessage::HandoffJoin { .. } => return Ok(false),
+        }
+        .map(|()| true)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::identity::PeerIdentityString;
+    use crate::service::WebRtcService;
+    use crate::signaling::SignalingHandler;
+    use crate::testing::MemorySignaling;
+    use crate::types::CallState;
+    use std::sync::Arc;
+
+    fn capability_exchange(call_id: CallId) -> SignalingMessage {
+        SignalingMessage::CapabilityExchange {
+            session_id: call_id.to_string(),
+            audio: true,
+            video: false,
+            data_channel: false,
+            max_bandwidth_kbps: 1000,
+            quic_endpoint: None,
+        }
+    }
+
+    async fn fresh_service(
+        clock: &MockClock,
+    ) -> WebRtcService<PeerIdentityString, MemorySignaling> {
+        let (signaling, _peer) = MemorySignaling::pair("bob", "alice");
+        let service = WebRtcService::builder(Arc::new(SignalingHandler::new(Arc::new(signaling))))
+            .with_clock(clock.shared())
+            .build()
+            .await
+            .unwrap();
+        service.start().await.unwrap();
+        service
+    }
+
+    /// A caller that cancels and hangs up at once: the `Bye` loses the race
+    /// with the `Cancel` that already dropped the call
+    fn cancel_then_bye(call_id: CallId) -> SignalingRecording {
+        let clock = MockClock::new();
+        let recorder = SignalingRecorder::new(clock.shared());
+        let alice = "alice".to_string();
+        let inbound = |message: SignalingMessage| {
+            recorder.record(Direction::Inbound, alice.clone(), &message);
+        };
+        inbound(capability_exchange(call_id));
+        clock.advance(Duration::from_millis(1500));
+        recorder.record(
+            Direction::Outbound,
+            alice.clone(),
+            &SignalingMessage::Progress {
+                session_id: call_id.to_string(),
+                progress: crate::types::CallProgress::Ringing,
+            },
+        );
+        clock.advance(Duration::from_millis(500));
+        inbound(SignalingMessage::Cancel {
+            session_id: call_id.to_string(),
+            reason: Some("timeout".to_string()),
+        });
+        inbound(SignalingMessage::Bye {
+            session_id: call_id.to_string(),
+            reason: None,
+        });
+        recorder.recording()
+    }
+
+    #[tokio::test]
+    async fn test_recorder_records_both_directions() {
+        let clock = MockClock::new();
+        let recorder = Arc::new(SignalingRecorder::new(clock.shared()));
+        let (a, b) = MemorySignaling::pair("alice", "bob");
+        let alice = SignalingHandler::new(Arc::new(a)).with_interceptor(recorder.clone());
+        let bob = SignalingHandler::new(Arc::new(b));
+        let call_id = CallId::new();
+
+        alice
+            .send_message(&"bob".to_string(), capability_exchange(call_id))
+            .await
+            .unwrap();
+        bob.receive_message().await.unwrap();
+        clock.advance(Duration::from_millis(250));
+        bob.send_message(
+            &"alice".to_string(),
+            SignalingMessage::ConnectionReady {
+                session_id: call_id.to_string(),
+            },
+        )
+        .await
+        .unwrap();
+        alice.receive_message().await.unwrap();
+
+        let recording = recorder.recording();
+        let summary: Vec<_> = recording
+            .messages
+            .iter()
+            .map(|m| (m.at_ms, m.direction, m.peer.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (0, Direction::Outbound, "bob"),
+                (250, Direction::Inbound, "bob")
+            ]
+        );
+        assert_eq!(recording.inbound().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_recording_file_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        let clock = MockClock::new();
+        let call_id = CallId::new();
+
+        let recorder = SignalingRecorder::to_file(&path, clock.shared()).unwrap();
+        let inbound: &dyn SignalingInterceptor<String> = &recorder;
+        inbound
+            .on_inbound(&"alice".to_string(), capability_exchange(call_id))
+            .await;
+        clock.advance(Duration::from_secs(2));
+        inbound
+            .on_inbound(
+                &"alice".to_string(),
+                SignalingMessage::Bye {
+                    session_id: call_id.to_string(),
+                    reason: None,
+                },
+            )
+            .await;
+
+        let loaded = SignalingRecording::load(&path).unwrap();
+        assert_eq!(loaded, recorder.recording());
+        assert_eq!(loaded.messages[1].at(), Duration::from_secs(2));
+        assert_eq!(
+            SignalingRecording::from_jsonl(&loaded.to_jsonl().unwrap()).unwrap(),
+            loaded
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replay_reproduces_session() {
+        let call_id = CallId::new();
+        let recording = cancel_then_bye(call_id);
+
+        let mut reports = Vec::new();
+        for _ in 0..2 {
+            let clock = MockClock::new();
+            let service = fresh_service(&clock).await;
+            let report = Replayer::new(&service, PeerIdentityString::new("bob"), clock.clone())
+                .run(&recording)
+                .await;
+            assert_eq!(clock.elapsed(), Duration::from_secs(2));
+            assert_eq!(service.get_call_state(call_id).await, None);
+            reports.push(report);
+        }
+
+        // The second run fails the same way as the first
+        assert_eq!(reports[0], reports[1]);
+        let report = &reports[0];
+        assert_eq!(report.applied, 2);
+        assert_eq!(report.skipped, 1);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].index, 3);
+        assert_eq!(report.failures[0].at, Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_replay_stops_at_ringing_call() {
+        let call_id = CallId::new();
+        let mut recording = cancel_then_bye(call_id);
+        recording.messages.truncate(1);
+
+        let clock = MockClock::new();
+        let service = fresh_service(&clock).await;
+        let report = Replayer::new(&service, PeerIdentityString::new("bob"), clock)
+            .run(&recording)
+            .await;
+        assert!(report.failures.is_empty(), "{:?}", report.failures);
+        assert_eq!(
+            service.get_call_state(call_id).await,
+            Some(CallState::Calling)
+        );
+    }
+}
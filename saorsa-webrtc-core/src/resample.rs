@@ -0,0 +1,262 @@
+//! Polyphase sample-rate conversion
+//!
+//! Converts interleaved 16-bit PCM between arbitrary rates, such as a
+//! 44.1 kHz capture device feeding a 48 kHz Opus encoder. A windowed-sinc
+//! filter is precomputed into a bank of phases; the read position is kept
+//! as an exact fraction so long streams never drift.
+
+use std::f64::consts::PI;
+use std::time::Duration;
+
+/// Filter taps on each side of the interpolated point
+const HALF_TAPS: usize = 16;
+
+/// Upper bound on precomputed filter phases
+///
+/// Ratios needing more phases (e.g. 44 101 Hz to 48 kHz) use the nearest
+/// precomputed phase, which keeps memory bounded at a negligible cost in
+/// accuracy.
+const MAX_PHASES: usize = 512;
+
+/// Fraction of the lower Nyquist frequency kept by the anti-aliasing filter
+const ROLLOFF: f64 = 0.95;
+
+/// Streaming resampler for interleaved PCM
+#[derive(Debug, Clone)]
+pub struct Resampler {
+    from_hz: u32,
+    to_hz: u32,
+    channels: usize,
+    /// Output step in input frames is `down / up`
+    up: u64,
+    down: u64,
+    /// Filter coefficients, `HALF_TAPS * 2` per phase
+    bank: Vec<f32>,
+    phases: u64,
+    /// Buffered input frames, interleaved
+    buffer: Vec<f32>,
+    /// Integer part of the read position, in buffered frames
+    index: usize,
+    /// Fractional part of the read position, in units of `1 / up`
+    phase: u64,
+}
+
+impl Resampler {
+    /// Create a resampler between two rates
+    ///
+    /// Returns `None` if either rate or the channel count is zero.
+    #[must_use]
+    pub fn new(from_hz: u32, to_hz: u32, channels: usize) -> Option<Self> {
+        if from_hz == 0 || to_hz == 0 || channels == 0 {
+            return None;
+        }
+        let divisor = gcd(u64::from(from_hz), u64::from(to_hz));
+        let up = u64::from(to_hz) / divisor;
+        let down = u64::from(from_hz) / divisor;
+        let phases = up.min(MAX_PHASES as u64);
+
+        // Downsampling must also cut everything above the output Nyquist
+        let cutoff = ROLLOFF * (up as f64 / down as f64).min(1.0);
+        let mut bank = Vec::with_capacity((phases as usize + 1) * HALF_TAPS * 2);
+        // One row past the last phase absorbs rounding to the nearest phase
+        for row in 0..=phases {
+            let fraction = row as f64 / phases as f64;
+            let start = bank.len();
+            for tap in 0..HALF_TAPS * 2 {
+                let offset = tap as f64 - (HALF_TAPS as f64 - 1.0);
+                bank.push(kernel(offset - fraction, cutoff) as f32);
+            }
+            // Unity gain at DC for every phase
+            let sum: f32 = bank[start..].iter().sum();
+            if sum != 0.0 {
+                bank[start..].iter_mut().for_each(|c| *c /= sum);
+            }
+        }
+
+        Some(Self {
+            from_hz,
+            to_hz,
+            channels,
+            up,
+            down,
+            bank,
+            phases,
+            buffer: vec![0.0; (HALF_TAPS - 1) * channels],
+            index: HALF_TAPS - 1,
+            phase: 0,
+        })
+    }
+
+    /// Input rate in Hz
+    #[must_use]
+    pub fn from_hz(&self) -> u32 {
+        self.from_hz
+    }
+
+    /// Output rate in Hz
+    #[must_use]
+    pub fn to_hz(&self) -> u32 {
+        self.to_hz
+    }
+
+    /// Delay added by the filter
+    ///
+    /// Input is held back until the filter has seen enough frames past each
+    /// output point, so this much audio is always in flight.
+    #[must_use]
+    pub fn latency(&self) -> Duration {
+        Duration::from_secs_f64(HALF_TAPS as f64 / f64::from(self.from_hz))
+    }
+
+    /// Delay added by the filter, in output frames (rounded up)
+    #[must_use]
+    pub fn latency_frames(&self) -> usize {
+        (HALF_TAPS as u64 * self.up).div_ceil(self.down) as usize
+    }
+
+    /// Convert a buffer of interleaved input, returning the output ready so
+    /// far
+    ///
+    /// Trailing samples that do not fill a whole frame are ignored.
+    pub fn process(&mut self, input: &[i16]) -> Vec<i16> {
+        let frames_in = input.len() / self.channels;
+        self.buffer.extend(
+            input[..frames_in * self.channels]
+                .iter()
+                .map(|&s| f32::from(s)),
+        );
+        let buffered = self.buffer.len() / self.channels;
+
+        let expected = (frames_in as u64 * self.up / self.down) as usize + 1;
+        let mut output = Vec::with_capacity(expected * self.channels);
+        while self.index + HALF_TAPS < buffered {
+            let row = self.row();
+            let taps = &self.bank[row * HALF_TAPS * 2..(row + 1) * HALF_TAPS * 2];
+            let first = self.index + 1 - HALF_TAPS;
+            for channel in 0..self.channels {
+                let value: f32 = taps
+                    .iter()
+                    .enumerate()
+                    .map(|(tap, c)| c * self.buffer[(first + tap) * self.channels + channel])
+                    .sum();
+                output.push(
+                    value
+                        .round()
+                        .clamp(f32::from(i16::MIN), f32::from(i16::MAX)) as i16,
+                );
+            }
+
+            self.phase += self.down;
+            self.index += (self.phase / self.up) as usize;
+            self.phase %= self.up;
+        }
+
+        // Drop frames no future output can reach
+        let consumed = (self.index + 1).saturating_sub(HALF_TAPS).min(buffered);
+        self.buffer.drain(..consumed * self.channels);
+        self.index -= consumed;
+        output
+    }
+
+    /// Filter bank row for the current fractional position
+    fn row(&self) -> usize {
+        if self.phases == self.up {
+            self.phase as usize
+        } else {
+            ((self.phase * self.phases + self.up / 2) / self.up) as usize
+        }
+    }
+}
+
+/// Blackman-windowed sinc low-pass, `cutoff` relative to Nyquist
+fn kernel(x: f64, cutoff: f64) -> f64 {
+    let extent = HALF_TAPS as f64;
+    if x.abs() >= extent {
+        return 0.0;
+    }
+    let sinc = if x == 0.0 {
+        1.0
+    } else {
+        (PI * cutoff * x).sin() / (PI * cutoff * x)
+    };
+    let u = x / extent;
+    let window = 0.42 + 0.5 * (PI * u).cos() + 0.08 * (2.0 * PI * u).cos();
+    cutoff * sinc * window
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn sine(rate: u32, frequency: f64, start: usize, frames: usize) -> Vec<i16> {
+        (start..start + frames)
+            .map(|n| (8000.0 * (2.0 * PI * frequency * n as f64 / f64::from(rate)).sin()) as i16)
+            .collect()
+    }
+
+    #[test]
+    fn test_rejects_zero_rates() {
+        assert!(Resampler::new(0, 48_000, 1).is_none());
+        assert!(Resampler::new(44_100, 48_000, 0).is_none());
+    }
+
+    #[test]
+    fn test_streams_without_drift() {
+        let mut resampler = Resampler::new(44_100, 48_000, 2).unwrap();
+        let mut produced = 0;
+        for _ in 0..100 {
+            produced += resampler.process(&[500; 882]).len() / 2;
+        }
+        // One second of input, less what the filter still holds
+        let expected = 48_000 - resampler.latency_frames();
+        assert!(produced.abs_diff(expected) <= 2, "{produced} vs {expected}");
+    }
+
+    #[test]
+    fn test_sine_survives_conversion() {
+        let mut resampler = Resampler::new(44_100, 48_000, 1).unwrap();
+        let mut output = Vec::new();
+        for chunk in 0..10 {
+            output.extend(resampler.process(&sine(44_100, 1000.0, chunk * 441, 441)));
+        }
+        // Output frame n lines up with input time n / 48 kHz
+        let reference = sine(48_000, 1000.0, 0, output.len());
+        let worst = output
+            .iter()
+            .zip(&reference)
+            .skip(resampler.latency_frames() * 2)
+            .map(|(a, b)| (i32::from(*a) - i32::from(*b)).abs())
+            .max()
+            .unwrap();
+        assert!(worst < 40, "worst error {worst}");
+    }
+
+    #[test]
+    fn test_downsampling_removes_aliases() {
+        let mut resampler = Resampler::new(48_000, 16_000, 1).unwrap();
+        // 12 kHz is above the 8 kHz output Nyquist and must not fold back
+        let output = resampler.process(&sine(48_000, 12_000.0, 0, 4800));
+        let peak = output
+            .iter()
+            .skip(resampler.latency_frames() * 2)
+            .map(|s| s.unsigned_abs())
+            .max()
+            .unwrap();
+        assert!(peak < 100, "alias peak {peak}");
+    }
+
+    #[test]
+    fn test_latency_reporting() {
+        let resampler = Resampler::new(44_100, 48_000, 1).unwrap();
+        assert_eq!(resampler.latency().as_micros(), 362);
+        assert_eq!(resampler.latency_frames(), 18);
+    }
+}
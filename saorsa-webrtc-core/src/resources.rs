@@ -0,0 +1,190 @@
+//! Live resource gauges for leak detection
+//!
+//! A call manager and the media transports it creates share one
+//! [`ResourceGauges`]. It counts live [`Call`](crate::call::Call) objects,
+//! open media streams, per-call background tasks and packets buffered in
+//! media taps. Once every call has ended and its tasks have noticed, all
+//! counts return to zero; anything left over points at a cleanup
+//! regression.
+//!
+//! Calls and tasks are counted with [`ResourceGuard`]s that decrement on
+//! drop, so a `Call` kept alive by a stray `Arc` still shows up.
+
+use crate::media_tap::MediaTaps;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+
+/// Snapshot of live resources
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceCounts {
+    /// `Call` objects alive, including ones no longer held by the manager
+    pub calls: usize,
+    /// Media streams open on transports that were not disconnected
+    pub open_streams: usize,
+    /// Per-call background tasks still running
+    pub tasks: usize,
+    /// Packets waiting in media tap buffers
+    pub buffered_packets: usize,
+}
+
+impl ResourceCounts {
+    /// Check that nothing is left alive
+    #[must_use]
+    pub fn is_idle(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Gauges shared by a call manager and its transports
+#[derive(Debug, Default)]
+pub struct ResourceGauges {
+    calls: AtomicUsize,
+    open_streams: AtomicUsize,
+    tasks: AtomicUsize,
+    taps: Mutex<Vec<Weak<MediaTaps>>>,
+}
+
+/// Kind of resource a [`ResourceGuard`] counts
+#[derive(Debug, Clone, Copy)]
+enum Gauge {
+    Call,
+    Task,
+}
+
+/// Keeps one call or task counted until dropped
+#[derive(Debug)]
+pub struct ResourceGuard {
+    gauges: Arc<ResourceGauges>,
+    gauge: Gauge,
+}
+
+impl Drop for ResourceGuard {
+    fn drop(&mut self) {
+        self.gauges
+            .counter(self.gauge)
+            .fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl ResourceGauges {
+    /// Create gauges with nothing counted
+    #[must_use]
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    fn counter(&self, gauge: Gauge) -> &AtomicUsize {
+        match gauge {
+            Gauge::Call => &self.calls,
+            Gauge::Task => &self.tasks,
+        }
+    }
+
+    fn guard(self: &Arc<Self>, gauge: Gauge) -> ResourceGuard {
+        self.counter(gauge).fetch_add(1, Ordering::Relaxed);
+        ResourceGuard {
+            gauges: Arc::clone(self),
+            gauge,
+        }
+    }
+
+    /// Count a call until the guard is dropped
+    #[must_use]
+    pub fn track_call(self: &Arc<Self>) -> ResourceGuard {
+        self.guard(Gauge::Call)
+    }
+
+    /// Count a background task until the guard is dropped
+    ///
+    /// Move the guard into the task so it is released when the task ends.
+    #[must_use]
+    pub fn track_task(self: &Arc<Self>) -> ResourceGuard {
+        self.guard(Gauge::Task)
+    }
+
+    /// Record a stream opening
+    pub fn stream_opened(&self) {
+        self.open_streams.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a stream closing
+    pub fn stream_closed(&self) {
+        // Saturate rather than wrap if a close is reported twice
+        let _ = self
+            .open_streams
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+                Some(n.saturating_sub(1))
+            });
+    }
+
+    /// Include a transport's media taps in the buffered packet count
+    ///
+    /// Taps are held weakly and stop counting once their transport is gone.
+    pub fn watch_taps(&self, taps: &Arc<MediaTaps>) {
+        self.taps.lock().push(Arc::downgrade(taps));
+    }
+
+    /// Current counts
+    #[must_use]
+    pub fn snapshot(&self) -> ResourceCounts {
+        let buffered_packets = {
+            let mut taps = self.taps.lock();
+            taps.retain(|taps| taps.strong_count() > 0);
+            taps.iter()
+                .filter_map(Weak::upgrade)
+                .map(|taps| taps.buffered())
+                .sum()
+        };
+        ResourceCounts {
+            calls: self.calls.load(Ordering::Relaxed),
+            open_streams: self.open_streams.load(Ordering::Relaxed),
+            tasks: self.tasks.load(Ordering::Relaxed),
+            buffered_packets,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::quic_bridge::{RtpPacket, StreamType};
+
+    #[test]
+    fn test_guards_count_until_dropped() {
+        let gauges = ResourceGauges::new();
+        let call = gauges.track_call();
+        let tasks = [gauges.track_task(), gauges.track_task()];
+        gauges.stream_opened();
+
+        let counts = gauges.snapshot();
+        assert_eq!((counts.calls, counts.tasks, counts.open_streams), (1, 2, 1));
+        assert!(!counts.is_idle());
+
+        drop(call);
+        drop(tasks);
+        gauges.stream_closed();
+        gauges.stream_closed();
+        assert!(gauges.snapshot().is_idle());
+    }
+
+    #[tokio::test]
+    async fn test_buffered_packets_from_taps() {
+        let gauges = ResourceGauges::new();
+        let taps = Arc::new(MediaTaps::new());
+        gauges.watch_taps(&taps);
+
+        let mut receiver = taps.subscribe(StreamType::Audio);
+        let packet = RtpPacket::new(111, 1, 0, 1, vec![0; 4], StreamType::Audio).unwrap();
+        taps.publish(&packet);
+        taps.publish(&packet);
+        assert_eq!(gauges.snapshot().buffered_packets, 2);
+
+        receiver.recv().await.unwrap();
+        assert_eq!(gauges.snapshot().buffered_packets, 1);
+        drop(taps);
+        assert!(gauges.snapshot().is_idle());
+    }
+}
@@ -0,0 +1,376 @@
+//! Retransmission (RTX) of important media packets
+//!
+//! Packets that matter, such as those of a video keyframe, are worth sending
+//! again when the receiver reports them lost. Retransmissions travel on a
+//! stream of their own per media type (see [`rtx_key`]) rather than on the
+//! media stream, so a burst of repairs never holds up fresh media queued
+//! behind it on the same QUIC stream.
+//!
+//! As in RFC 4588, a retransmission is a packet in its own right: it carries
+//! the RTX SSRC and payload type of its stream's [`RtxMapping`], a sequence
+//! number from the RTX stream's own space, and a payload that starts with
+//! the original sequence number. [`RtxReceiver`] turns it back into the
+//! original packet.
+
+use crate::link_transport::StreamType;
+use crate::quic_bridge::RtpPacket;
+use crate::quic_media_transport::{StreamKey, TrackId};
+use std::collections::{HashMap, VecDeque};
+use thiserror::Error;
+
+/// Track of the retransmission stream of each stream type
+pub const RTX_TRACK: TrackId = TrackId::MAX;
+
+/// Bytes the original sequence number adds to a retransmission
+pub const RTX_HEADER_LEN: usize = 2;
+
+/// Key of the stream retransmissions of a stream type are sent on
+#[must_use]
+pub const fn rtx_key(stream_type: StreamType) -> StreamKey {
+    StreamKey::new(stream_type, RTX_TRACK)
+}
+
+/// Retransmission errors
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RtxError {
+    /// No RTX stream is mapped to the SSRC
+    #[error("No RTX stream for SSRC {0:#010x}")]
+    UnknownSsrc(u32),
+
+    /// A retransmission is too short to hold the original sequence number
+    #[error("RTX packet of {0} bytes has no original sequence number")]
+    Truncated(usize),
+}
+
+/// Retransmission configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RtxConfig {
+    /// Keep packets for retransmission
+    pub enabled: bool,
+    /// Packets kept per media stream; the oldest are dropped beyond this
+    pub history_packets: usize,
+}
+
+impl Default for RtxConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            history_packets: 256,
+        }
+    }
+}
+
+/// Pairing of a media stream with its retransmission stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RtxMapping {
+    /// SSRC of the media packets
+    pub media_ssrc: u32,
+    /// SSRC of the retransmissions
+    pub rtx_ssrc: u32,
+    /// Payload type of the retransmissions
+    pub rtx_payload_type: u8,
+    /// Track the media packets are sent on
+    pub track: StreamKey,
+}
+
+impl RtxMapping {
+    /// Key of the stream the retransmissions are sent on
+    #[must_use]
+    pub const fn rtx_key(&self) -> StreamKey {
+        rtx_key(self.track.stream_type)
+    }
+}
+
+/// Retransmission counters of one media stream
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RtxStats {
+    /// Packets retransmitted
+    pub packets: u64,
+    /// Bytes retransmitted, including the original sequence numbers
+    pub bytes: u64,
+    /// Requested packets no longer in the history
+    pub missed: u64,
+}
+
+#[derive(Debug)]
+struct SenderStream {
+    mapping: RtxMapping,
+    next_sequence: u16,
+    history: VecDeque<RtpPacket>,
+    stats: RtxStats,
+}
+
+/// Sending side: remembers important packets and wraps them for resending
+#[derive(Debug, Default)]
+pub struct RtxSender {
+    config: RtxConfig,
+    streams: HashMap<u32, SenderStream>,
+}
+
+impl RtxSender {
+    /// Create a sender with a configuration
+    #[must_use]
+    pub fn new(config: RtxConfig) -> Self {
+        Self {
+            config,
+            streams: HashMap::new(),
+        }
+    }
+
+    /// Start keeping packets of a media stream
+    ///
+    /// Replaces any earlier mapping of the same media SSRC.
+    pub fn add_stream(&mut self, mapping: RtxMapping) {
+        self.streams.insert(
+            mapping.media_ssrc,
+            SenderStream {
+                mapping,
+                next_sequence: rand::random(),
+                history: VecDeque::new(),
+                stats: RtxStats::default(),
+            },
+        );
+    }
+
+    /// Stop keeping packets of a media stream
+    pub fn remove_stream(&mut self, media_ssrc: u32) -> Option<RtxMapping> {
+        self.streams
+            .remove(&media_ssrc)
+            .map(|stream| stream.mapping)
+    }
+
+    /// Mapping of a media stream
+    #[must_use]
+    pub fn mapping(&self, media_ssrc: u32) -> Option<RtxMapping> {
+        self.streams.get(&media_ssrc).map(|stream| stream.mapping)
+    }
+
+    /// Mappings of all media streams
+    #[must_use]
+    pub fn mappings(&self) -> Vec<RtxMapping> {
+        self.streams.values().map(|stream| stream.mapping).collect()
+    }
+
+    /// Keep a sent packet in case it has to be resent
+    ///
+    /// Only packets worth repairing should be kept, e.g. keyframes; the
+    /// history is short and ordinary packets would crowd them out. Returns
+    /// false if retransmission is disabled or the stream is not mapped.
+    pub fn remember(&mut self, packet: &RtpPacket) -> bool {
+        if !self.config.enabled || self.config.history_packets == 0 {
+            return false;
+        }
+        let Some(stream) = self.streams.get_mut(&packet.ssrc) else {
+            return false;
+        };
+        if stream.history.len() == self.config.history_packets {
+            stream.history.pop_front();
+        }
+        stream.history.push_back(packet.clone());
+        true
+    }
+
+    /// Build retransmissions of packets the receiver reported lost
+    ///
+    /// Packets no longer in the history are skipped and counted as missed.
+    /// The result is in request order, ready for the stream's
+    /// [`RtxMapping::rtx_key`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the media stream is not mapped
+    pub fn retransmit(
+        &mut self,
+        media_ssrc: u32,
+        sequence_numbers: &[u16],
+    ) -> Result<Vec<RtpPacket>, RtxError> {
+        let stream = self
+            .streams
+            .get_mut(&media_ssrc)
+            .ok_or(RtxError::UnknownSsrc(media_ssrc))?;
+
+        let mut packets = Vec::with_capacity(sequence_numbers.len());
+        for &sequence_number in sequence_numbers {
+            let Some(original) = stream
+                .history
+                .iter()
+                .rev()
+                .find(|packet| packet.sequence_number == sequence_number)
+            else {
+                stream.stats.missed += 1;
+                continue;
+            };
+
+            let mut payload = Vec::with_capacity(RTX_HEADER_LEN + original.payload.len());
+            payload.extend_from_slice(&sequence_number.to_be_bytes());
+            payload.extend_from_slice(&original.payload);
+
+            let packet = RtpPacket {
+                payload_type: stream.mapping.rtx_payload_type,
+                sequence_number: stream.next_sequence,
+                ssrc: stream.mapping.rtx_ssrc,
+                payload,
+                ..original.clone()
+            };
+            stream.next_sequence = stream.next_sequence.wrapping_add(1);
+            stream.stats.packets += 1;
+            stream.stats.bytes += packet.payload.len() as u64;
+            packets.push(packet);
+        }
+        Ok(packets)
+    }
+
+    /// Retransmission counters of a media stream
+    #[must_use]
+    pub fn stats(&self, media_ssrc: u32) -> Option<RtxStats> {
+        self.streams.get(&media_ssrc).map(|stream| stream.stats)
+    }
+}
+
+/// Receiving side: restores retransmissions to the original packets
+#[derive(Debug, Default)]
+pub struct RtxReceiver {
+    mappings: HashMap<u32, (RtxMapping, u8)>,
+}
+
+impl RtxReceiver {
+    /// Create a receiver with no streams
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expect retransmissions of a media stream
+    ///
+    /// `media_payload_type` is restored on the original packets.
+    pub fn add_stream(&mut self, mapping: RtxMapping, media_payload_type: u8) {
+        self.mappings
+            .insert(mapping.rtx_ssrc, (mapping, media_payload_type));
+    }
+
+    /// Mapping of a retransmission stream
+    #[must_use]
+    pub fn mapping(&self, rtx_ssrc: u32) -> Option<RtxMapping> {
+        self.mappings.get(&rtx_ssrc).map(|(mapping, _)| *mapping)
+    }
+
+    /// Turn a retransmission back into the packet it repairs
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the SSRC is not a known RTX stream or the payload
+    /// is too short
+    pub fn restore(&self, mut packet: RtpPacket) -> Result<RtpPacket, RtxError> {
+        let (mapping, media_payload_type) = self
+            .mappings
+            .get(&packet.ssrc)
+            .ok_or(RtxError::UnknownSsrc(packet.ssrc))?;
+        let Some(&[high, low]) = packet.payload.get(..RTX_HEADER_LEN) else {
+            return Err(RtxError::Truncated(packet.payload.len()));
+        };
+
+        packet.payload.drain(..RTX_HEADER_LEN);
+        packet.sequence_number = u16::from_be_bytes([high, low]);
+        packet.ssrc = mapping.media_ssrc;
+        packet.payload_type = *media_payload_type;
+        Ok(packet)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::quic_bridge::StreamType as RtpStreamType;
+
+    fn mapping() -> RtxMapping {
+        RtxMapping {
+            media_ssrc: 0x1111,
+            rtx_ssrc: 0x2222,
+            rtx_payload_type: 97,
+            track: StreamKey::new(StreamType::Video, 1),
+        }
+    }
+
+    fn packet(sequence_number: u16) -> RtpPacket {
+        let mut packet = RtpPacket::new(
+            96,
+            sequence_number,
+            9000,
+            0x1111,
+            vec![sequence_number as u8; 40],
+            RtpStreamType::Video,
+        )
+        .unwrap();
+        packet.marker = true;
+        packet
+    }
+
+    #[test]
+    fn test_retransmit_and_restore() {
+        let mut sender = RtxSender::new(RtxConfig::default());
+        sender.add_stream(mapping());
+        assert!(sender.remember(&packet(10)));
+        assert!(sender.remember(&packet(11)));
+
+        let resent = sender.retransmit(0x1111, &[11, 12, 10]).unwrap();
+        assert_eq!(resent.len(), 2);
+        assert_eq!(resent[0].ssrc, 0x2222);
+        assert_eq!(resent[0].payload_type, 97);
+        assert_eq!(
+            resent[1].sequence_number,
+            resent[0].sequence_number.wrapping_add(1)
+        );
+        assert_eq!(
+            sender.stats(0x1111).unwrap(),
+            RtxStats {
+                packets: 2,
+                bytes: 84,
+                missed: 1
+            }
+        );
+        assert_eq!(mapping().rtx_key(), rtx_key(StreamType::Video));
+
+        let mut receiver = RtxReceiver::new();
+        receiver.add_stream(mapping(), 96);
+        let restored = receiver.restore(resent[0].clone()).unwrap();
+        let original = packet(11);
+        assert_eq!(restored.ssrc, original.ssrc);
+        assert_eq!(restored.sequence_number, original.sequence_number);
+        assert_eq!(restored.payload_type, original.payload_type);
+        assert_eq!(restored.timestamp, original.timestamp);
+        assert!(restored.marker);
+        assert_eq!(restored.payload, original.payload);
+
+        assert_eq!(
+            receiver.restore(packet(1)).unwrap_err(),
+            RtxError::UnknownSsrc(0x1111)
+        );
+        assert_eq!(
+            sender.retransmit(0x3333, &[1]).unwrap_err(),
+            RtxError::UnknownSsrc(0x3333)
+        );
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut sender = RtxSender::new(RtxConfig {
+            history_packets: 2,
+            ..Default::default()
+        });
+        assert!(!sender.remember(&packet(1)));
+        sender.add_stream(mapping());
+        for sequence_number in 1..=3 {
+            sender.remember(&packet(sequence_number));
+        }
+        assert_eq!(sender.retransmit(0x1111, &[1, 2, 3]).unwrap().len(), 2);
+        assert_eq!(sender.stats(0x1111).unwrap().missed, 1);
+
+        let mut disabled = RtxSender::new(RtxConfig {
+            enabled: false,
+            ..Default::default()
+        });
+        disabled.add_stream(mapping());
+        assert!(!disabled.remember(&packet(1)));
+    }
+}
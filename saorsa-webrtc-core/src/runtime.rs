@@ -0,0 +1,75 @@
+//! Execution model
+//!
+//! The core runs on either tokio runtime flavour. Nothing in it blocks a
+//! runtime thread waiting for another task: synchronous accessors such as
+//! [`TrackBackend::stats`](crate::media::TrackBackend::stats) and
+//! [`QuicMediaTransport::current_state`](crate::quic_media_transport::QuicMediaTransport::current_state)
+//! read locks that are never held across an await, and CPU-heavy codec jobs
+//! go to [`MediaWorkers`](crate::media_workers::MediaWorkers). A
+//! single-threaded `current_thread` runtime is therefore enough, which suits
+//! embedded and mobile targets that avoid a thread pool.
+//!
+//! [`ExecutionModel`] sizes what the core runs beside the runtime. It is
+//! detected from the runtime the service is created on unless set in
+//! [`WebRtcConfig::execution_model`](crate::service::WebRtcConfig::execution_model).
+
+use serde::{Deserialize, Serialize};
+use tokio::runtime::{Handle, RuntimeFlavor};
+
+/// How the runtime the core runs on schedules tasks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionModel {
+    /// A multi-threaded runtime with a worker per CPU
+    MultiThread,
+    /// A single-threaded runtime, for constrained targets
+    ///
+    /// Media workers default to one thread instead of one per CPU.
+    CurrentThread,
+}
+
+impl ExecutionModel {
+    /// Execution model of the runtime the caller runs on
+    ///
+    /// Multi-threaded outside a runtime.
+    #[must_use]
+    pub fn detect() -> Self {
+        match Handle::try_current().map(|handle| handle.runtime_flavor()) {
+            Ok(RuntimeFlavor::CurrentThread) => Self::CurrentThread,
+            _ => Self::MultiThread,
+        }
+    }
+
+    /// Media worker threads to start for a configured count, where 0 asks
+    /// for the default
+    #[must_use]
+    pub fn media_worker_threads(self, configured: usize) -> usize {
+        match (self, configured) {
+            (Self::CurrentThread, 0) => 1,
+            (_, threads) => threads,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_detect_current_thread() {
+        assert_eq!(ExecutionModel::detect(), ExecutionModel::CurrentThread);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_detect_multi_thread() {
+        assert_eq!(ExecutionModel::detect(), ExecutionModel::MultiThread);
+    }
+
+    #[test]
+    fn test_media_worker_threads() {
+        assert_eq!(ExecutionModel::detect(), ExecutionModel::MultiThread);
+        assert_eq!(ExecutionModel::CurrentThread.media_worker_threads(0), 1);
+        assert_eq!(ExecutionModel::CurrentThread.media_worker_threads(3), 3);
+        assert_eq!(ExecutionModel::MultiThread.media_worker_threads(0), 0);
+    }
+}
@@ -0,0 +1,387 @@
+//! Scheduled calls
+//!
+//! A scheduled call dials a peer at a set time. Shortly before that time a
+//! reminder is raised so the user can get ready; at the time the call is
+//! placed automatically. Schedules can be kept in a JSON file so they
+//! survive restarts: calls that fell due while the application was not
+//! running are dialed as soon as it starts again.
+
+use crate::identity::PeerIdentity;
+use crate::types::{CallId, MediaConstraints};
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Default time before a scheduled call that the reminder is raised
+/// (1 minute)
+pub const DEFAULT_REMINDER_LEAD: Duration = Duration::from_secs(60);
+
+/// Default interval at which pending schedules are checked (1 second)
+pub const DEFAULT_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Scheduler errors
+#[derive(Error, Debug)]
+pub enum SchedulerError {
+    /// No pending schedule with this ID
+    #[error("Scheduled call not found: {0}")]
+    NotFound(ScheduleId),
+
+    /// Reading or writing the schedule file failed
+    #[error("I/O error: {0}")]
+    Io(String),
+
+    /// Schedule file could not be (de)serialized
+    #[error("Serialization error: {0}")]
+    Serialization(String),
+}
+
+impl From<std::io::Error> for SchedulerError {
+    fn from(err: std::io::Error) -> Self {
+        SchedulerError::Io(err.to_string())
+    }
+}
+
+/// Unique identifier of a scheduled call
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ScheduleId(pub Uuid);
+
+impl ScheduleId {
+    /// Create a new random schedule ID
+    #[must_use]
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+impl Default for ScheduleId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for ScheduleId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Scheduler configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchedulerConfig {
+    /// How long before the call the reminder is raised
+    pub reminder_lead: Duration,
+    /// Interval at which pending schedules are checked
+    pub check_interval: Duration,
+    /// JSON file schedules are persisted to; in memory only if `None`
+    pub store_path: Option<PathBuf>,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            reminder_lead: DEFAULT_REMINDER_LEAD,
+            check_interval: DEFAULT_CHECK_INTERVAL,
+            store_path: None,
+        }
+    }
+}
+
+/// A call scheduled for later
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "I: PeerIdentity")]
+pub struct ScheduledCall<I: PeerIdentity> {
+    /// Schedule ID
+    pub id: ScheduleId,
+    /// Peer to call
+    pub peer: I,
+    /// Media to call with
+    pub constraints: MediaConstraints,
+    /// When to place the call
+    pub at: DateTime<Utc>,
+    /// Whether the reminder has been raised
+    pub reminded: bool,
+}
+
+/// Scheduled-call notifications
+#[derive(Debug, Clone)]
+pub enum ScheduleEvent<I: PeerIdentity> {
+    /// A scheduled call is coming up
+    Reminder {
+        /// The upcoming call
+        call: ScheduledCall<I>,
+    },
+    /// A scheduled call was placed
+    Dialed {
+        /// Schedule ID
+        id: ScheduleId,
+        /// Peer called
+        peer: I,
+        /// The placed call
+        call_id: CallId,
+    },
+    /// A scheduled call could not be placed
+    Failed {
+        /// Schedule ID
+        id: ScheduleId,
+        /// Peer that could not be called
+        peer: I,
+        /// Why the call failed
+        error: String,
+    },
+}
+
+/// Schedules that need attention at a point in time
+#[derive(Debug)]
+pub struct DueSchedules<I: PeerIdentity> {
+    /// Calls whose reminder is due
+    pub reminders: Vec<ScheduledCall<I>>,
+    /// Calls that should be dialed now
+    pub dials: Vec<ScheduledCall<I>>,
+}
+
+/// Store of pending scheduled calls
+#[derive(Debug)]
+pub struct CallScheduler<I: PeerIdentity> {
+    config: SchedulerConfig,
+    pending: Mutex<Vec<ScheduledCall<I>>>,
+}
+
+impl<I: PeerIdentity> CallScheduler<I> {
+    /// Create a scheduler, loading persisted schedules if a store file is
+    /// configured and exists
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the store file cannot be read or parsed
+    pub fn new(config: SchedulerConfig) -> Result<Self, SchedulerError> {
+        let pending = match config.store_path {
+            Some(ref path) if path.exists() => load(path)?,
+            _ => Vec::new(),
+        };
+        Ok(Self {
+            config,
+            pending: Mutex::new(pending),
+        })
+    }
+
+    /// Get the configuration
+    #[must_use]
+    pub fn config(&self) -> &SchedulerConfig {
+        &self.config
+    }
+
+    /// Schedule a call to `peer` at `at`
+    ///
+    /// A time in the past dials on the next check.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the schedule cannot be persisted
+    pub fn schedule(
+        &self,
+        peer: I,
+        constraints: MediaConstraints,
+        at: DateTime<Utc>,
+    ) -> Result<ScheduleId, SchedulerError> {
+        let id = ScheduleId::new();
+        let mut pending = self.pending.lock();
+        pending.push(ScheduledCall {
+            id,
+            peer,
+            constraints,
+            at,
+            reminded: false,
+        });
+        pending.sort_by_key(|call| call.at);
+        self.persist(&pending)?;
+        Ok(id)
+    }
+
+    /// Cancel a pending scheduled call
+    ///
+    /// # Errors
+    ///
+    /// Returns error if no such call is pending or the change cannot be
+    /// persisted
+    pub fn cancel(&self, id: ScheduleId) -> Result<ScheduledCall<I>, SchedulerError> {
+        let mut pending = self.pending.lock();
+        let index = pending
+            .iter()
+            .position(|call| call.id == id)
+            .ok_or(SchedulerError::NotFound(id))?;
+        let call = pending.remove(index);
+        self.persist(&pending)?;
+        Ok(call)
+    }
+
+    /// Pending scheduled calls, soonest first
+    #[must_use]
+    pub fn pending(&self) -> Vec<ScheduledCall<I>> {
+        self.pending.lock().clone()
+    }
+
+    /// Take the reminders and dials that are due at `now`
+    ///
+    /// Reminders are returned once; calls to dial are removed from the
+    /// pending list. A call that is due to be dialed without having been
+    /// reminded is only dialed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the change cannot be persisted
+    pub fn take_due(&self, now: DateTime<Utc>) -> Result<DueSchedules<I>, SchedulerError> {
+        let lead = chrono::Duration::from_std(self.config.reminder_lead)
+            .unwrap_or_else(|_| chrono::Duration::zero());
+        let mut pending = self.pending.lock();
+
+        let (dials, rest): (Vec<_>, Vec<_>) = pending.drain(..).partition(|call| call.at <= now);
+        *pending = rest;
+
+        let mut reminders = Vec::new();
+        for call in pending.iter_mut() {
+            let remind_at = call.at.checked_sub_signed(lead).unwrap_or(call.at);
+            if !call.reminded && remind_at <= now {
+                call.reminded = true;
+                reminders.push(call.clone());
+            }
+        }
+
+        if !dials.is_empty() || !reminders.is_empty() {
+            self.persist(&pending)?;
+        }
+        Ok(DueSchedules { reminders, dials })
+    }
+
+    fn persist(&self, pending: &[ScheduledCall<I>]) -> Result<(), SchedulerError> {
+        let Some(ref path) = self.config.store_path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_vec_pretty(pending)
+            .map_err(|e| SchedulerError::Serialization(e.to_string()))?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+}
+
+fn load<I: PeerIdentity>(path: &Path) -> Result<Vec<ScheduledCall<I>>, SchedulerError> {
+    let data = std::fs::read(path)?;
+    let mut pending: Vec<ScheduledCall<I>> =
+        serde_json::from_slice(&data).map_err(|e| SchedulerError::Serialization(e.to_string()))?;
+    pending.sort_by_key(|call| call.at);
+    Ok(pending)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::identity::PeerIdentityString;
+
+    fn scheduler(store_path: Option<PathBuf>) -> CallScheduler<PeerIdentityString> {
+        CallScheduler::new(SchedulerConfig {
+            store_path,
+            ..SchedulerConfig::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_reminder_then_dial() {
+        let scheduler = scheduler(None);
+        let at = Utc::now() + chrono::Duration::minutes(10);
+        let id = scheduler
+            .schedule(
+                PeerIdentityString::new("bob"),
+                MediaConstraints::audio_only(),
+                at,
+            )
+            .unwrap();
+
+        let due = scheduler
+            .take_due(at - chrono::Duration::minutes(5))
+            .unwrap();
+        assert!(due.reminders.is_empty() && due.dials.is_empty());
+
+        let due = scheduler
+            .take_due(at - chrono::Duration::seconds(30))
+            .unwrap();
+        assert_eq!(due.reminders.len(), 1);
+        assert_eq!(due.reminders[0].id, id);
+        assert!(due.dials.is_empty());
+
+        // Reminders fire once
+        let due = scheduler
+            .take_due(at - chrono::Duration::seconds(10))
+            .unwrap();
+        assert!(due.reminders.is_empty());
+
+        let due = scheduler.take_due(at).unwrap();
+        assert_eq!(due.dials.len(), 1);
+        assert_eq!(due.dials[0].peer, PeerIdentityString::new("bob"));
+        assert!(scheduler.pending().is_empty());
+    }
+
+    #[test]
+    fn test_cancel_and_order() {
+        let scheduler = scheduler(None);
+        let now = Utc::now();
+        let later = scheduler
+            .schedule(
+                PeerIdentityString::new("carol"),
+                MediaConstraints::video_call(),
+                now + chrono::Duration::hours(2),
+            )
+            .unwrap();
+        let sooner = scheduler
+            .schedule(
+                PeerIdentityString::new("bob"),
+                MediaConstraints::audio_only(),
+                now + chrono::Duration::hours(1),
+            )
+            .unwrap();
+
+        let ids: Vec<ScheduleId> = scheduler.pending().iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec![sooner, later]);
+
+        assert_eq!(scheduler.cancel(later).unwrap().id, later);
+        assert!(matches!(
+            scheduler.cancel(later),
+            Err(SchedulerError::NotFound(id)) if id == later
+        ));
+        assert_eq!(scheduler.pending().len(), 1);
+    }
+
+    #[test]
+    fn test_schedules_persist_across_restarts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("schedules.json");
+        let at = Utc::now() + chrono::Duration::minutes(30);
+
+        let id = scheduler(Some(path.clone()))
+            .schedule(
+                PeerIdentityString::new("bob"),
+                MediaConstraints::audio_only(),
+                at,
+            )
+            .unwrap();
+
+        let restarted = scheduler(Some(path.clone()));
+        let pending = restarted.pending();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].id, id);
+        assert_eq!(pending[0].at, at);
+
+        // Missed while not running: dialed on the first check
+        let due = restarted.take_due(at + chrono::Duration::hours(1)).unwrap();
+        assert_eq!(due.dials.len(), 1);
+        assert!(scheduler(Some(path)).pending().is_empty());
+    }
+}
@@ -0,0 +1,158 @@
+//! JSON Schemas of public message types
+//!
+//! Frontends generate their types from these schemas and validate payloads
+//! against them. The schemas are published in `schemas/` at the repository
+//! root, one file per type, and each crate's tests check its files against
+//! its types. After an intended change to a type, regenerate the files by
+//! running the tests with `SAORSA_UPDATE_SCHEMAS` set:
+//!
+//! ```text
+//! SAORSA_UPDATE_SCHEMAS=1 cargo test -p saorsa-webrtc-core --features schema --test json_schema
+//! ```
+
+use crate::identity::PeerIdentityString;
+use crate::signaling::SignalingMessage;
+use crate::types::CallEvent;
+use schemars::{schema_for, Schema};
+use std::path::Path;
+
+/// Environment variable that makes [`check_published`] rewrite stale files
+pub const UPDATE_SCHEMAS_ENV: &str = "SAORSA_UPDATE_SCHEMAS";
+
+/// A schema and the name of the file it is published in, without the
+/// `.schema.json` extension
+pub type NamedSchema = (&'static str, Schema);
+
+/// Schemas of the message types this crate publishes
+///
+/// Identities in [`CallEvent`] are [`PeerIdentityString`]s, which serialize
+/// as plain strings.
+#[must_use]
+pub fn schemas() -> Vec<NamedSchema> {
+    vec![
+        ("signaling-message", schema_for!(SignalingMessage)),
+        ("call-event", schema_for!(CallEvent<PeerIdentityString>)),
+    ]
+}
+
+/// Path of a schema's file in a directory of published schemas
+#[must_use]
+pub fn schema_path(dir: &Path, name: &str) -> std::path::PathBuf {
+    dir.join(format!("{name}.schema.json"))
+}
+
+/// Check schemas against the files published in `dir`
+///
+/// Returns the names of schemas whose file is missing or differs. With
+/// [`UPDATE_SCHEMAS_ENV`] set, those files are rewritten instead and
+/// nothing is reported.
+///
+/// # Errors
+///
+/// Returns error if a file cannot be read, parsed or written
+pub fn check_published(dir: &Path, schemas: &[NamedSchema]) -> std::io::Result<Vec<String>> {
+    let update = std::env::var_os(UPDATE_SCHEMAS_ENV).is_some();
+    let mut stale = Vec::new();
+    for (name, schema) in schemas {
+        let path = schema_path(dir, name);
+        let generated = serde_json::to_value(schema)?;
+        let published = match std::fs::read_to_string(&path) {
+            Ok(json) => Some(serde_json::from_str::<serde_json::Value>(&json)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+        if published.as_ref() == Some(&generated) {
+            continue;
+        }
+        if update {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut json = serde_json::to_string_pretty(&generated)?;
+            json.push('\n');
+            std::fs::write(&path, json)?;
+        } else {
+            stale.push((*name).to_string());
+        }
+    }
+    Ok(stale)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::types::{CallId, MediaConstraints};
+
+    /// Values of every `const` in a schema
+    fn consts(value: &serde_json::Value, found: &mut Vec<String>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                if let Some(serde_json::Value::String(c)) = map.get("const") {
+                    found.push(c.clone());
+                }
+                map.values().for_each(|v| consts(v, found));
+            }
+            serde_json::Value::Array(values) => values.iter().for_each(|v| consts(v, found)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_signaling_schema_matches_serialized_tags() {
+        let schema = serde_json::to_value(schema_for!(SignalingMessage)).unwrap();
+        let mut tags = Vec::new();
+        consts(&schema, &mut tags);
+
+        let session_id = CallId::new().to_string();
+        let messages = [
+            SignalingMessage::ConnectionReady {
+                session_id: session_id.clone(),
+            },
+            SignalingMessage::IceComplete {
+                session_id: session_id.clone(),
+            },
+            SignalingMessage::Bye {
+                session_id,
+                reason: None,
+            },
+        ];
+        for message in messages {
+            let value = serde_json::to_value(&message).unwrap();
+            let tag = value["type"].as_str().unwrap();
+            assert!(tags.iter().any(|t| t == tag), "{tag} not in schema");
+        }
+    }
+
+    #[test]
+    fn test_call_event_schema_is_externally_tagged() {
+        let event = CallEvent::<PeerIdentityString>::CallInitiated {
+            call_id: CallId::new(),
+            callee: PeerIdentityString::new("bob"),
+            constraints: MediaConstraints::audio_only(),
+        };
+        let value = serde_json::to_value(&event).unwrap();
+        let schema = serde_json::to_value(schema_for!(CallEvent<PeerIdentityString>)).unwrap();
+        let variants = schema["oneOf"].as_array().unwrap();
+        assert!(variants
+            .iter()
+            .any(|v| v["required"] == serde_json::json!(["CallInitiated"])));
+        assert!(value.get("CallInitiated").is_some());
+    }
+
+    #[test]
+    fn test_check_published_reports_stale_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let schemas = schemas();
+        let stale = check_published(dir.path(), &schemas).unwrap();
+        assert_eq!(stale, ["signaling-message", "call-event"]);
+
+        let mut json = serde_json::to_string_pretty(&schemas[0].1).unwrap();
+        json.push('\n');
+        std::fs::write(schema_path(dir.path(), "signaling-message"), json).unwrap();
+        assert_eq!(
+            check_published(dir.path(), &schemas).unwrap(),
+            ["call-event"]
+        );
+    }
+}
@@ -1,17 +1,47 @@
 //! WebRTC service orchestration
 //!
-//! **Note:** This module requires the `legacy-webrtc` feature. In Phase 2,
-//! a QUIC-native variant will be available.
+//! Calls are QUIC-native unless the `legacy-webrtc` feature is enabled, in
+//! which case `initiate_call` also sets up a WebRTC peer connection.
 
-use crate::call::{CallManager, CallManagerConfig};
+use crate::call::{CallManager, CallManagerConfig, IncomingCallOutcome, PurgeReport};
+use crate::clock::SharedClock;
+use crate::dnd::{DndConfig, DoNotDisturb, MissedCall, MAX_MISSED_CALLS};
+use crate::events::{
+    EventSender, EventStats, DEFAULT_MEDIA_EVENT_CAPACITY, DEFAULT_SERVICE_EVENT_CAPACITY,
+};
+use crate::governor::{GovernorConfig, GovernorEvent, PerformanceGovernor, SharedPowerSource};
+use crate::health::{CodecHealth, DeviceHealth, HealthReport, SignalingHealth, TransportHealth};
+use crate::hold::{self, HoldAudio, HoldAudioConfig};
 use crate::identity::PeerIdentity;
 use crate::media::MediaStreamManager;
-use crate::signaling::{SignalingHandler, SignalingTransport};
-use crate::types::{CallEvent, CallId, CallState, MediaConstraints, NativeQuicConfiguration};
+use crate::media_workers::MediaWorkerConfig;
+use crate::metrics::{self, SharedMetrics};
+use crate::policy::{DataRequest, SharedCallPolicy};
+use crate::quic_bridge::{RtpPacket, StreamType};
+use crate::quic_media_transport::{MediaTransportState, TrackId, TransportStats};
+use crate::redact::{self, RedactionConfig};
+use crate::resources::ResourceCounts;
+use crate::runtime::ExecutionModel;
+use crate::scheduler::{
+    CallScheduler, ScheduleEvent, ScheduleId, ScheduledCall, SchedulerConfig, SchedulerError,
+};
+use crate::signaling::{SignalingHandler, SignalingMessage, SignalingTransport};
+use crate::stats_history::{SharedHistoryStore, StatsDelta, StatsHistoryError, StatsSample};
+use crate::supervisor::{ServiceHealth, SupervisorConfig, TaskHealth, TaskSupervisor};
+use crate::types::{
+    CallEvent, CallId, CallMetadata, CallOffer, CallProgress, CallQualityMetrics, CallState,
+    MediaConstraints, NativeQuicConfiguration, VideoLayer,
+};
+use crate::voicemail::{self, AutoAnswer, AutoAnswerConfig};
+use crate::webhooks::{CallActivity, SharedWebhookSender, WebhookConfig, WebhookNotifier};
+use chrono::{DateTime, Utc};
+use saorsa_webrtc_codecs::{CodecRegistry, PoolConfig};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
 use std::sync::Arc;
 use thiserror::Error;
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, mpsc};
 
 /// Service errors
 #[derive(Error, Debug)]
@@ -23,6 +53,23 @@ pub enum ServiceError {
     /// Call error
     #[error("Call error: {0}")]
     CallError(String),
+
+    /// Signaling error
+    #[error("Signaling error: {0}")]
+    Signaling(String),
+}
+
+/// Reason sent to devices that rang for a call answered on another device
+const ANSWERED_ELSEWHERE: &str = "answered elsewhere";
+
+/// Reason sent to devices still ringing when the caller hangs up
+const CALLER_HUNG_UP: &str = "caller hung up";
+
+/// Devices rung for one outgoing call
+struct RingFork<P> {
+    devices: Vec<P>,
+    /// Device that answered first, if any
+    answered_by: Option<String>,
 }
 
 /// Top-level WebRTC events
@@ -34,6 +81,14 @@ pub enum WebRtcEvent<I: PeerIdentity> {
     Media(crate::media::MediaEvent),
     /// Call event
     Call(CallEvent<I>),
+    /// Scheduled call reminder or auto-dial
+    Schedule(ScheduleEvent<I>),
+    /// The performance governor changed level
+    Performance(GovernorEvent),
+    /// An incoming call did not ring because of do-not-disturb
+    MissedCall(MissedCall<I>),
+    /// A background task ended, was restarted or was given up
+    ServiceHealth(TaskHealth),
 }
 
 /// Signaling event (placeholder)
@@ -54,6 +109,41 @@ pub struct WebRtcConfig {
     pub default_constraints: MediaConstraints,
     /// Call manager config
     pub call_config: CallManagerConfig,
+    /// Auto-answer and voicemail for headless deployments
+    pub auto_answer: AutoAnswerConfig,
+    /// What calls send while on hold
+    pub hold_audio: HoldAudioConfig,
+    /// Scheduled calls
+    pub scheduler: SchedulerConfig,
+    /// Redaction of identities and addresses in logs
+    pub redaction: RedactionConfig,
+    /// Codec implementations for media tracks
+    pub codecs: Arc<CodecRegistry>,
+    /// Sizing of the pool of warm codec instances
+    pub codec_pool: PoolConfig,
+    /// Threads for encode and decode jobs
+    pub media_workers: MediaWorkerConfig,
+    /// CPU- and battery-aware performance governor
+    pub governor: GovernorConfig,
+    /// Battery state for the governor; it watches CPU use only when unset
+    pub power_source: Option<SharedPowerSource>,
+    /// Do-not-disturb toggle and quiet hours
+    pub dnd: DndConfig,
+    /// Restarting of background tasks that panic or exit
+    pub supervisor: SupervisorConfig,
+    /// Call notification webhooks; none are sent when unset
+    pub webhooks: Option<WebhookConfig>,
+    /// Sender for webhooks; the `webhooks` feature provides one over HTTP
+    pub webhook_sender: Option<SharedWebhookSender>,
+    /// Service events buffered per subscriber before a lagging subscriber
+    /// loses the oldest
+    pub event_capacity: usize,
+    /// Media events buffered per subscriber before a lagging subscriber
+    /// loses the oldest
+    pub media_event_capacity: usize,
+    /// Runtime the service runs on; detected when the service is created
+    /// if unset
+    pub execution_model: Option<ExecutionModel>,
 }
 
 impl Default for WebRtcConfig {
@@ -62,16 +152,58 @@ impl Default for WebRtcConfig {
             quic_config: NativeQuicConfiguration::default(),
             default_constraints: MediaConstraints::audio_only(),
             call_config: CallManagerConfig::default(),
+            auto_answer: AutoAnswerConfig::default(),
+            hold_audio: HoldAudioConfig::default(),
+            scheduler: SchedulerConfig::default(),
+            redaction: RedactionConfig::default(),
+            codecs: Arc::new(CodecRegistry::with_defaults()),
+            codec_pool: PoolConfig::default(),
+            media_workers: MediaWorkerConfig::default(),
+            governor: GovernorConfig::default(),
+            power_source: None,
+            dnd: DndConfig::default(),
+            supervisor: SupervisorConfig::default(),
+            webhooks: None,
+            webhook_sender: None,
+            event_capacity: DEFAULT_SERVICE_EVENT_CAPACITY,
+            media_event_capacity: DEFAULT_MEDIA_EVENT_CAPACITY,
+            execution_model: None,
         }
     }
 }
 
+/// Supervised task running the call scheduler
+pub const SCHEDULER_TASK: &str = "scheduler";
+
+/// Supervised task running the performance governor
+pub const GOVERNOR_TASK: &str = "governor";
+
+/// Supervised task sending call notification webhooks
+pub const WEBHOOK_TASK: &str = "webhooks";
+
 /// Main WebRTC service
 pub struct WebRtcService<I: PeerIdentity, T: SignalingTransport> {
-    _signaling: Arc<SignalingHandler<T>>,
+    signaling: Arc<SignalingHandler<T>>,
+    forks: parking_lot::Mutex<HashMap<CallId, RingFork<T::PeerId>>>,
     media: Arc<MediaStreamManager>,
     call_manager: Arc<CallManager<I>>,
-    event_sender: broadcast::Sender<WebRtcEvent<I>>,
+    auto_answer: Option<AutoAnswer<I>>,
+    hold_audio: HoldAudioConfig,
+    hold_players: parking_lot::Mutex<HashMap<CallId, tokio::task::JoinHandle<()>>>,
+    scheduler: Arc<CallScheduler<I>>,
+    governor: Option<Arc<PerformanceGovernor>>,
+    webhooks: Option<WebhookNotifier>,
+    supervisor: TaskSupervisor,
+    dnd: DoNotDisturb,
+    missed_calls: parking_lot::Mutex<VecDeque<MissedCall<I>>>,
+    event_sender: EventSender<WebRtcEvent<I>>,
+    execution_model: ExecutionModel,
+}
+
+impl<I: PeerIdentity, T: SignalingTransport> Drop for WebRtcService<I, T> {
+    fn drop(&mut self) {
+        self.supervisor.stop_all();
+    }
 }
 
 impl<I: PeerIdentity, T: SignalingTransport> WebRtcService<I, T> {
@@ -84,20 +216,88 @@ impl<I: PeerIdentity, T: SignalingTransport> WebRtcService<I, T> {
         signaling: Arc<SignalingHandler<T>>,
         config: WebRtcConfig,
     ) -> Result<Self, ServiceError> {
-        let (event_sender, _) = broadcast::channel(1000);
+        redact::set_config(config.redaction);
+        let metrics = config.call_config.metrics.clone();
+        let event_sender = EventSender::new(config.event_capacity)
+            .with_metrics(metrics.clone(), metrics::SERVICE_EVENTS_OVERFLOWED);
 
-        let media = Arc::new(MediaStreamManager::new());
+        let mut media = MediaStreamManager::new();
+        media.set_event_sender(
+            EventSender::new(config.media_event_capacity)
+                .with_metrics(metrics, metrics::MEDIA_EVENTS_OVERFLOWED),
+        );
+        media.set_codec_pool_config(config.codec_pool);
+        let execution_model = config
+            .execution_model
+            .unwrap_or_else(ExecutionModel::detect);
+        media.set_media_worker_config(MediaWorkerConfig {
+            threads: execution_model.media_worker_threads(config.media_workers.threads),
+            ..config.media_workers
+        });
+        media.set_codec_registry(config.codecs);
+        let media = Arc::new(media);
         let call_manager = Arc::new(
             CallManager::new(config.call_config)
                 .await
                 .map_err(|e| ServiceError::InitError(e.to_string()))?,
         );
 
+        let auto_answer = config
+            .auto_answer
+            .enabled
+            .then(|| AutoAnswer::new(config.auto_answer, Arc::clone(&call_manager)));
+
+        let scheduler = Arc::new(
+            CallScheduler::new(config.scheduler)
+                .map_err(|e| ServiceError::InitError(e.to_string()))?,
+        );
+
+        let governor = config.governor.enabled.then(|| {
+            let governor = PerformanceGovernor::new(config.governor);
+            Arc::new(match config.power_source {
+                Some(power) => governor.with_power_source(power),
+                None => governor,
+            })
+        });
+
+        let webhooks = match (config.webhooks, config.webhook_sender) {
+            (Some(webhooks), Some(sender)) => Some(WebhookNotifier::new(webhooks, sender)),
+            #[cfg(feature = "webhooks")]
+            (Some(webhooks), None) => Some(WebhookNotifier::new(
+                webhooks,
+                Arc::new(crate::webhooks::HttpWebhookSender::new()),
+            )),
+            #[cfg(not(feature = "webhooks"))]
+            (Some(_), None) => {
+                return Err(ServiceError::InitError(
+                    "webhooks need a webhook_sender or the webhooks feature".to_string(),
+                ))
+            }
+            (None, _) => None,
+        };
+
+        let health_events = event_sender.clone();
+        let supervisor =
+            TaskSupervisor::new(config.supervisor).with_listener(Arc::new(move |health| {
+                let _ = health_events.send(WebRtcEvent::ServiceHealth(health.clone()));
+            }));
+
         Ok(Self {
-            _signaling: signaling,
+            signaling,
+            forks: parking_lot::Mutex::new(HashMap::new()),
             media,
             call_manager,
+            auto_answer,
+            hold_audio: config.hold_audio,
+            hold_players: parking_lot::Mutex::new(HashMap::new()),
+            scheduler,
+            governor,
+            webhooks,
+            supervisor,
+            dnd: DoNotDisturb::new(config.dnd),
+            missed_calls: parking_lot::Mutex::new(VecDeque::new()),
             event_sender,
+            execution_model,
         })
     }
 
@@ -120,6 +320,38 @@ impl<I: PeerIdentity, T: SignalingTransport> WebRtcService<I, T> {
             .await
             .map_err(|e| ServiceError::InitError(e.to_string()))?;
 
+        let scheduler = Arc::clone(&self.scheduler);
+        let call_manager = Arc::clone(&self.call_manager);
+        let events = self.event_sender.clone();
+        self.supervisor.supervise(SCHEDULER_TASK, move || {
+            run_scheduler(
+                Arc::clone(&scheduler),
+                Arc::clone(&call_manager),
+                events.clone(),
+            )
+        });
+
+        if let Some(governor) = &self.governor {
+            let governor = Arc::clone(governor);
+            let events = self.event_sender.clone();
+            self.supervisor.supervise(GOVERNOR_TASK, move || {
+                run_governor(Arc::clone(&governor), events.clone())
+            });
+        }
+
+        if let Some(notifier) = &self.webhooks {
+            let notifier = notifier.clone();
+            let call_manager = Arc::clone(&self.call_manager);
+            let events = self.event_sender.clone();
+            self.supervisor.supervise(WEBHOOK_TASK, move || {
+                run_webhooks(
+                    notifier.clone(),
+                    call_manager.subscribe_events(),
+                    events.subscribe(),
+                )
+            });
+        }
+
         tracing::info!("WebRTC service started successfully");
         Ok(())
     }
@@ -129,7 +361,7 @@ impl<I: PeerIdentity, T: SignalingTransport> WebRtcService<I, T> {
     /// # Errors
     ///
     /// Returns error if call cannot be initiated
-    #[tracing::instrument(skip(self), fields(peer = %callee.to_string_repr()))]
+    #[tracing::instrument(skip(self), fields(peer = %redact::identity(callee.to_string_repr())))]
     pub async fn initiate_call(
         &self,
         callee: I,
@@ -147,6 +379,473 @@ impl<I: PeerIdentity, T: SignalingTransport> WebRtcService<I, T> {
         Ok(call_id)
     }
 
+    /// Schedule a call to `callee` at `at`
+    ///
+    /// Once the service is started, a [`ScheduleEvent::Reminder`] is emitted
+    /// shortly before the call and the call is placed at the scheduled time,
+    /// with its statistics history recorded like any other call.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the schedule cannot be persisted
+    #[tracing::instrument(skip(self), fields(peer = %redact::identity(callee.to_string_repr())))]
+    pub fn schedule_call(
+        &self,
+        callee: I,
+        constraints: MediaConstraints,
+        at: DateTime<Utc>,
+    ) -> Result<ScheduleId, SchedulerError> {
+        let id = self.scheduler.schedule(callee, constraints, at)?;
+        tracing::info!(schedule_id = %id, "Call scheduled");
+        Ok(id)
+    }
+
+    /// Cancel a scheduled call that has not been placed yet
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the call is not pending or the change cannot be
+    /// persisted
+    pub fn cancel_scheduled_call(&self, id: ScheduleId) -> Result<(), SchedulerError> {
+        self.scheduler.cancel(id)?;
+        Ok(())
+    }
+
+    /// Get pending scheduled calls, soonest first
+    #[must_use]
+    pub fn scheduled_calls(&self) -> Vec<ScheduledCall<I>> {
+        self.scheduler.pending()
+    }
+
+    /// Handle an incoming call offer
+    ///
+    /// Resolves glare with any outgoing call to the same peer; see
+    /// [`CallManager::handle_incoming_call`]. While do-not-disturb is on the
+    /// call is not registered and does not ring; it is added to the missed
+    /// calls and [`WebRtcEvent::MissedCall`] is emitted instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the call cannot be registered
+    #[tracing::instrument(skip(self, offer), fields(call_id = %offer.call_id))]
+    pub async fn handle_incoming_call(
+        &self,
+        offer: CallOffer<I>,
+    ) -> Result<IncomingCallOutcome, ServiceError> {
+        let now = self.call_manager.clock().utc_now();
+        if let Some(reason) = self.dnd.active_at(now) {
+            let action = self.dnd.config().action;
+            self.call_manager.record_policy_decision(
+                offer.call_id,
+                "do_not_disturb",
+                false,
+                Some(format!("{reason:?}")),
+            );
+            let missed = MissedCall {
+                call_id: offer.call_id,
+                caller: offer.caller,
+                at: now,
+                reason,
+                action,
+            };
+            {
+                let mut missed_calls = self.missed_calls.lock();
+                if missed_calls.len() >= MAX_MISSED_CALLS {
+                    missed_calls.pop_front();
+                }
+                missed_calls.push_back(missed.clone());
+            }
+            let _ = self.event_sender.send(WebRtcEvent::MissedCall(missed));
+            tracing::info!(reason = ?reason, action = ?action, "Incoming call held back by do-not-disturb");
+            return Ok(IncomingCallOutcome::DoNotDisturb {
+                call_id: offer.call_id,
+                action,
+            });
+        }
+
+        let caller = offer.caller.clone();
+        let constraints = MediaConstraints::from_media_types(&offer.media_types);
+        let outcome = self
+            .call_manager
+            .handle_incoming_call(offer)
+            .await
+            .map_err(|e| ServiceError::CallError(e.to_string()))?;
+
+        let ringing = match outcome {
+            IncomingCallOutcome::Ringing(call_id)
+            | IncomingCallOutcome::IncomingKept { kept: call_id, .. } => Some(call_id),
+            IncomingCallOutcome::OutgoingKept { .. } | IncomingCallOutcome::DoNotDisturb { .. } => {
+                None
+            }
+        };
+        // A waiting call is left to the user rather than answered beside
+        // the call they are on
+        let waiting = self.call_manager.active_call().await.is_some();
+        if let (Some(call_id), false) = (ringing, waiting) {
+            if self
+                .call_manager
+                .should_auto_accept(&caller, &constraints)
+                .await
+            {
+                tracing::info!(call_id = %call_id, "Auto-accepting call allowed by policy");
+                self.accept_call(call_id, constraints).await?;
+            } else if let Some(auto_answer) = &self.auto_answer {
+                auto_answer.schedule(call_id);
+            }
+        }
+
+        tracing::info!(outcome = ?outcome, "Incoming call handled");
+        Ok(outcome)
+    }
+
+    /// Check with the call policy whether a peer may open a data channel or
+    /// send a file during a call
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the policy denies the request
+    pub async fn authorize_data_request(
+        &self,
+        call_id: CallId,
+        peer: &I,
+        request: DataRequest,
+    ) -> Result<(), ServiceError> {
+        self.call_manager
+            .check_data_request(call_id, peer, request)
+            .await
+            .map_err(|e| ServiceError::CallError(e.to_string()))
+    }
+
+    /// Register one of the devices a peer can be reached at
+    ///
+    /// Calls to the peer placed through [`Self::ring_devices`] ring every
+    /// registered device.
+    pub fn register_device(&self, identity: &I, endpoint: T::PeerId) {
+        self.signaling
+            .register_device(&identity.to_string_repr(), endpoint);
+    }
+
+    /// Forget a device of a peer
+    ///
+    /// Returns `false` if the endpoint was not registered.
+    pub fn unregister_device(&self, identity: &I, endpoint: &T::PeerId) -> bool {
+        self.signaling
+            .unregister_device(&identity.to_string_repr(), endpoint)
+    }
+
+    /// Ring every device of a callee with a call's opening message
+    ///
+    /// The first device to answer wins; report answers with
+    /// [`Self::handle_device_answer`] so the other devices are told to stop
+    /// ringing. Returns the number of devices rung.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the callee has no known device or none could be
+    /// reached
+    #[tracing::instrument(skip(self, message), fields(peer = %redact::identity(callee.to_string_repr())))]
+    pub async fn ring_devices(
+        &self,
+        call_id: CallId,
+        callee: &I,
+        message: SignalingMessage,
+    ) -> Result<usize, ServiceError> {
+        let devices = self.signaling.devices(&callee.to_string_repr());
+        if devices.is_empty() {
+            return Err(ServiceError::Signaling(
+                "No device known for callee".to_string(),
+            ));
+        }
+        let rung = self
+            .signaling
+            .fork_message(&devices, message)
+            .await
+            .map_err(|e| ServiceError::Signaling(e.to_string()))?;
+        self.forks.lock().insert(
+            call_id,
+            RingFork {
+                devices,
+                answered_by: None,
+            },
+        );
+        tracing::info!(call_id = %call_id, devices = rung, "Ringing callee devices");
+        Ok(rung)
+    }
+
+    /// Handle an answer to a call from one of the callee's devices
+    ///
+    /// The first device to answer a call rung with [`Self::ring_devices`]
+    /// wins and every other device is sent a
+    /// [`SignalingMessage::Cancel`]; a device answering later is cancelled
+    /// too. Returns whether `device` won. Answers to calls that were not
+    /// forked always win.
+    pub async fn handle_device_answer(&self, call_id: CallId, device: &T::PeerId) -> bool {
+        let device_id = device.to_string();
+        let (cancel, won) = {
+            let mut forks = self.forks.lock();
+            let Some(fork) = forks.get_mut(&call_id) else {
+                return true;
+            };
+            match &fork.answered_by {
+                Some(winner) if *winner == device_id => return true,
+                Some(_) => (vec![device.clone()], false),
+                None => {
+                    fork.answered_by = Some(device_id.clone());
+                    let others = fork
+                        .devices
+                        .iter()
+                        .filter(|other| other.to_string() != device_id)
+                        .cloned()
+                        .collect();
+                    (others, true)
+                }
+            }
+        };
+        tracing::info!(
+            call_id = %call_id,
+            device = %redact::identity(&device_id),
+            won,
+            "Device answered"
+        );
+        self.cancel_devices(call_id, &cancel, ANSWERED_ELSEWHERE)
+            .await;
+        won
+    }
+
+    /// Handle a caller cancelling a call that is ringing here
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the call is not ringing
+    #[tracing::instrument(skip(self), fields(call_id = %call_id))]
+    pub async fn handle_cancel(
+        &self,
+        call_id: CallId,
+        reason: Option<String>,
+    ) -> Result<(), ServiceError> {
+        self.call_manager
+            .cancel_incoming_call(call_id, reason)
+            .await
+            .map_err(|e| ServiceError::CallError(e.to_string()))
+    }
+
+    /// Report progress on a ringing call to the caller
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the call is not ringing here or the message cannot
+    /// be sent
+    #[tracing::instrument(skip(self, caller), fields(call_id = %call_id))]
+    pub async fn send_progress(
+        &self,
+        call_id: CallId,
+        caller: &T::PeerId,
+        progress: CallProgress,
+    ) -> Result<(), ServiceError> {
+        let message = self
+            .call_manager
+            .report_progress(call_id, progress)
+            .await
+            .map_err(|e| ServiceError::CallError(e.to_string()))?;
+        self.signaling
+            .send_message(caller, message)
+            .await
+            .map_err(|e| ServiceError::Signaling(e.to_string()))
+    }
+
+    /// Feed a call's network quality to the audio-only fallback
+    ///
+    /// When video is suspended or resumed the peer is told over signaling.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the call is not connected or the peer cannot be
+    /// reached
+    #[tracing::instrument(skip(self, peer, metrics), fields(call_id = %call_id))]
+    pub async fn report_quality(
+        &self,
+        call_id: CallId,
+        peer: &T::PeerId,
+        metrics: CallQualityMetrics,
+    ) -> Result<(), ServiceError> {
+        let message = self
+            .call_manager
+            .report_quality(call_id, metrics)
+            .await
+            .map_err(|e| ServiceError::CallError(e.to_string()))?;
+        if let Some(message) = message {
+            self.signaling
+                .send_message(peer, message)
+                .await
+                .map_err(|e| ServiceError::Signaling(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Feed the quality measured by a call's media transport to the
+    /// audio-only fallback
+    ///
+    /// See [`CallManager::report_transport_quality`]. When video is
+    /// suspended or resumed the peer is told over signaling.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the call is not connected or has no media
+    /// transport, or the peer cannot be reached
+    #[tracing::instrument(skip(self, peer), fields(call_id = %call_id))]
+    pub async fn report_transport_quality(
+        &self,
+        call_id: CallId,
+        peer: &T::PeerId,
+    ) -> Result<(), ServiceError> {
+        let message = self
+            .call_manager
+            .report_transport_quality(call_id)
+            .await
+            .map_err(|e| ServiceError::CallError(e.to_string()))?;
+        if let Some(message) = message {
+            self.signaling
+                .send_message(peer, message)
+                .await
+                .map_err(|e| ServiceError::Signaling(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Handle the peer suspending or resuming its video
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the call is not found
+    pub async fn handle_audio_only(
+        &self,
+        call_id: CallId,
+        active: bool,
+    ) -> Result<(), ServiceError> {
+        self.call_manager
+            .handle_audio_only(call_id, active)
+            .await
+            .map_err(|e| ServiceError::CallError(e.to_string()))
+    }
+
+    /// Choose the highest layer to receive of one of the peer's video tracks
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the peer sends no such video track or cannot be
+    /// reached
+    #[tracing::instrument(skip(self, peer), fields(call_id = %call_id))]
+    pub async fn select_layer(
+        &self,
+        call_id: CallId,
+        peer: &T::PeerId,
+        track_id: TrackId,
+        layer: VideoLayer,
+    ) -> Result<(), ServiceError> {
+        let message = self
+            .call_manager
+            .select_layer(call_id, track_id, layer)
+            .await
+            .map_err(|e| ServiceError::CallError(e.to_string()))?;
+        self.signaling
+            .send_message(peer, message)
+            .await
+            .map_err(|e| ServiceError::Signaling(e.to_string()))
+    }
+
+    /// Handle the peer choosing layers of one of our video tracks
+    ///
+    /// # Errors
+    ///
+    /// Returns error if we send no such video track
+    pub async fn handle_layer_selection(
+        &self,
+        call_id: CallId,
+        track_id: TrackId,
+        layer: VideoLayer,
+    ) -> Result<(), ServiceError> {
+        self.call_manager
+            .handle_layer_selection(call_id, track_id, layer)
+            .await
+            .map_err(|e| ServiceError::CallError(e.to_string()))
+    }
+
+    /// Handle progress reported by the callee of an outgoing call
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the call is not an outgoing call
+    pub async fn handle_progress(
+        &self,
+        call_id: CallId,
+        progress: CallProgress,
+    ) -> Result<(), ServiceError> {
+        self.call_manager
+            .handle_progress(call_id, progress)
+            .await
+            .map_err(|e| ServiceError::CallError(e.to_string()))
+    }
+
+    /// Tell devices to stop ringing for a call
+    async fn cancel_devices(&self, call_id: CallId, devices: &[T::PeerId], reason: &str) {
+        if devices.is_empty() {
+            return;
+        }
+        let cancel = SignalingMessage::Cancel {
+            session_id: call_id.to_string(),
+            reason: Some(reason.to_string()),
+        };
+        if let Err(e) = self.signaling.fork_message(devices, cancel).await {
+            tracing::warn!(call_id = %call_id, error = %e, "Failed to cancel ringing devices");
+        }
+    }
+
+    /// Do-not-disturb toggle and quiet hours
+    #[must_use]
+    pub fn dnd(&self) -> &DoNotDisturb {
+        &self.dnd
+    }
+
+    /// Turn do-not-disturb on or off by hand
+    pub fn set_do_not_disturb(&self, enabled: bool) {
+        tracing::info!(enabled, "Do-not-disturb toggled");
+        self.dnd.set_enabled(enabled);
+    }
+
+    /// Calls held back by do-not-disturb, oldest first
+    #[must_use]
+    pub fn missed_calls(&self) -> Vec<MissedCall<I>> {
+        self.missed_calls.lock().iter().cloned().collect()
+    }
+
+    /// Forget the missed calls
+    pub fn clear_missed_calls(&self) {
+        self.missed_calls.lock().clear();
+    }
+
+    /// Record a packet of a caller's audio if the call went to voicemail
+    ///
+    /// # Returns
+    ///
+    /// `false` if the call is not being recorded.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the recording cannot be written
+    pub async fn record_voicemail_audio(
+        &self,
+        call_id: CallId,
+        packet: &[u8],
+    ) -> Result<bool, ServiceError> {
+        match self.auto_answer {
+            Some(ref auto_answer) => auto_answer
+                .record(call_id, packet)
+                .await
+                .map_err(|e| ServiceError::CallError(e.to_string())),
+            None => Ok(false),
+        }
+    }
+
     /// Accept a call
     ///
     /// # Errors
@@ -187,6 +886,138 @@ impl<I: PeerIdentity, T: SignalingTransport> WebRtcService<I, T> {
         Ok(())
     }
 
+    /// Answer a waiting call, putting the call we are on on hold
+    ///
+    /// Returns the calls put on hold.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the call is not ringing
+    #[tracing::instrument(skip(self), fields(call_id = %call_id))]
+    pub async fn answer_waiting_call(
+        &self,
+        call_id: CallId,
+        constraints: MediaConstraints,
+    ) -> Result<Vec<CallId>, ServiceError> {
+        self.call_manager
+            .answer_waiting_call(call_id, constraints)
+            .await
+            .map_err(|e| ServiceError::CallError(e.to_string()))
+    }
+
+    /// Decline a call because we are busy, telling the caller why
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the call cannot be rejected or the caller cannot be
+    /// reached
+    #[tracing::instrument(skip(self, caller), fields(call_id = %call_id))]
+    pub async fn reject_busy(&self, call_id: CallId, caller: &I) -> Result<(), ServiceError> {
+        let devices = self.signaling.devices(&caller.to_string_repr());
+        if devices.is_empty() {
+            return Err(ServiceError::Signaling(
+                "No device known for caller".to_string(),
+            ));
+        }
+        let bye = self
+            .call_manager
+            .reject_busy(call_id)
+            .await
+            .map_err(|e| ServiceError::CallError(e.to_string()))?;
+        self.signaling
+            .fork_message(&devices, bye)
+            .await
+            .map_err(|e| ServiceError::Signaling(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Put a call on hold
+    ///
+    /// The call sends the configured hold audio until it is resumed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the call is not connected
+    pub async fn hold_call(&self, call_id: CallId) -> Result<(), ServiceError> {
+        self.call_manager
+            .hold_call(call_id)
+            .await
+            .map_err(|e| ServiceError::CallError(e.to_string()))?;
+        self.start_hold_audio(call_id).await;
+        Ok(())
+    }
+
+    /// Take a call off hold
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the call is not found
+    pub async fn resume_call(&self, call_id: CallId) -> Result<(), ServiceError> {
+        self.stop_hold_audio(call_id);
+        self.call_manager
+            .resume_call(call_id)
+            .await
+            .map_err(|e| ServiceError::CallError(e.to_string()))
+    }
+
+    /// Attach a metadata value to a call, e.g. a support ticket ID
+    ///
+    /// See [`CallManager::set_call_metadata`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the call is not found or the metadata is refused
+    pub async fn set_call_metadata(
+        &self,
+        call_id: CallId,
+        key: &str,
+        value: &str,
+    ) -> Result<(), ServiceError> {
+        self.call_manager
+            .set_call_metadata(call_id, key, value)
+            .await
+            .map_err(|e| ServiceError::CallError(e.to_string()))
+    }
+
+    /// Get the metadata attached to a call
+    pub async fn get_call_metadata(&self, call_id: CallId) -> Option<CallMetadata> {
+        self.call_manager.call_metadata(call_id).await
+    }
+
+    /// Start sending hold audio on a held call, unless it already is
+    async fn start_hold_audio(&self, call_id: CallId) {
+        if self.hold_audio.audio == HoldAudio::Silence {
+            return;
+        }
+        let Some(transport) = self.call_manager.media_transport(call_id).await else {
+            return;
+        };
+
+        let mut players = self.hold_players.lock();
+        players.retain(|_, player| !player.is_finished());
+        if players.contains_key(&call_id) {
+            return;
+        }
+        let config = self.hold_audio.clone();
+        let task_guard = self.call_manager.resources().track_task();
+        players.insert(
+            call_id,
+            tokio::spawn(async move {
+                let _task_guard = task_guard;
+                if let Err(e) = hold::play(&config, &transport).await {
+                    tracing::debug!(call_id = %call_id, error = %e, "Hold audio stopped");
+                }
+            }),
+        );
+    }
+
+    /// Stop sending hold audio on a call
+    fn stop_hold_audio(&self, call_id: CallId) {
+        if let Some(player) = self.hold_players.lock().remove(&call_id) {
+            player.abort();
+        }
+    }
+
     /// End a call
     ///
     /// # Errors
@@ -195,12 +1026,27 @@ impl<I: PeerIdentity, T: SignalingTransport> WebRtcService<I, T> {
     #[tracing::instrument(skip(self), fields(call_id = %call_id))]
     pub async fn end_call(&self, call_id: CallId) -> Result<(), ServiceError> {
         tracing::info!("Ending call");
+        self.stop_hold_audio(call_id);
 
         self.call_manager
             .end_call(call_id)
             .await
             .map_err(|e| ServiceError::CallError(e.to_string()))?;
 
+        let fork = self.forks.lock().remove(&call_id);
+        if let Some(fork) = fork.filter(|fork| fork.answered_by.is_none()) {
+            self.cancel_devices(call_id, &fork.devices, CALLER_HUNG_UP)
+                .await;
+        }
+
+        if let Some(ref auto_answer) = self.auto_answer {
+            match auto_answer.finish(call_id).await {
+                Ok(Some(path)) => tracing::info!(path = %path.display(), "Voicemail recorded"),
+                Ok(None) => {}
+                Err(e) => tracing::warn!(error = %e, "Failed to finish voicemail recording"),
+            }
+        }
+
         tracing::info!("Call ended");
         Ok(())
     }
@@ -211,12 +1057,308 @@ impl<I: PeerIdentity, T: SignalingTransport> WebRtcService<I, T> {
         self.call_manager.get_call_state(call_id).await
     }
 
+    /// Resolve a call ID or a call's short code, as typed by a person
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the input names no call or a short code is ambiguous
+    pub async fn resolve_call_id(&self, id: &str) -> Result<CallId, ServiceError> {
+        self.call_manager
+            .resolve_call_id(id)
+            .await
+            .map_err(|e| ServiceError::CallError(e.to_string()))
+    }
+
+    /// Get the ID, remote peer and state of every call
+    pub async fn calls(&self) -> Vec<(CallId, I, CallState)> {
+        self.call_manager.calls().await
+    }
+
+    /// Get transport statistics of a call, including per-stream rates
+    #[must_use]
+    pub async fn get_call_stats(&self, call_id: CallId) -> Option<TransportStats> {
+        self.call_manager.transport_stats(call_id).await
+    }
+
+    /// Get quality metrics of a call measured by its media transport
+    #[must_use]
+    pub async fn get_quality_metrics(&self, call_id: CallId) -> Option<CallQualityMetrics> {
+        self.call_manager.quality_metrics(call_id).await
+    }
+
+    /// Get live resource counts across all calls
+    ///
+    /// Calls, open streams, per-call tasks and buffered packets all return
+    /// to zero once every call has ended and been cleaned up.
+    #[must_use]
+    pub fn resource_counts(&self) -> ResourceCounts {
+        self.call_manager.resource_counts()
+    }
+
+    /// Remove stored data about calls with `peer`
+    ///
+    /// Drops the statistics histories, audit log records and voicemail
+    /// recordings of the peer's calls. Calls are matched to the peer through
+    /// their statistics histories and the audit log, so recordings made
+    /// before a restart are only found when the audit log is enabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the audit log cannot be rewritten or a recording
+    /// cannot be deleted
+    pub fn purge_peer_data(&self, peer: &I) -> Result<PurgeReport, ServiceError> {
+        let mut report = self
+            .call_manager
+            .purge_peer_data(peer)
+            .map_err(|e| ServiceError::CallError(e.to_string()))?;
+        if let Some(dir) = self.recording_dir() {
+            report.recordings = voicemail::purge_recordings(dir, report.calls.iter().copied())
+                .map_err(|e| ServiceError::CallError(e.to_string()))?;
+        }
+        Ok(report)
+    }
+
+    /// Remove stored data recorded before `before`
+    ///
+    /// Drops older statistics samples and audit log records, and voicemail
+    /// recordings last written before `before`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the audit log cannot be rewritten or a recording
+    /// cannot be deleted
+    pub fn purge_all(&self, before: DateTime<Utc>) -> Result<PurgeReport, ServiceError> {
+        let mut report = self
+            .call_manager
+            .purge_all(before)
+            .map_err(|e| ServiceError::CallError(e.to_string()))?;
+        if let Some(dir) = self.recording_dir() {
+            report.recordings = voicemail::purge_recordings_before(dir, before)
+                .map_err(|e| ServiceError::CallError(e.to_string()))?;
+        }
+        Ok(report)
+    }
+
+    /// Directory voicemail is recorded to, if any
+    fn recording_dir(&self) -> Option<&Path> {
+        self.auto_answer
+            .as_ref()
+            .and_then(|auto_answer| auto_answer.config().recording_dir.as_deref())
+    }
+
+    /// Tap a call's sent and received media packets of one stream type
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the call does not exist or has no media transport
+    pub async fn tap_media(
+        &self,
+        call_id: CallId,
+        stream_type: StreamType,
+    ) -> Result<mpsc::Receiver<RtpPacket>, ServiceError> {
+        self.call_manager
+            .tap_media(call_id, stream_type)
+            .await
+            .map_err(|e| ServiceError::CallError(e.to_string()))
+    }
+
+    /// Write a call's media packets to a pcapng file for debugging
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the call does not exist or the file cannot be
+    /// created
+    pub async fn start_media_dump(&self, call_id: CallId, path: &Path) -> Result<(), ServiceError> {
+        self.call_manager
+            .start_media_dump(call_id, path)
+            .await
+            .map_err(|e| ServiceError::CallError(e.to_string()))
+    }
+
+    /// Stop a call's media capture, returning the number of packets written
+    pub async fn stop_media_dump(&self, call_id: CallId) -> Option<u64> {
+        self.call_manager.stop_media_dump(call_id).await
+    }
+
+    /// Start recording a call's statistics history
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the call does not exist or has no media transport
+    pub async fn start_stats_history(&self, call_id: CallId) -> Result<(), ServiceError> {
+        self.call_manager
+            .start_stats_history(call_id)
+            .await
+            .map_err(|e| ServiceError::CallError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Subscribe to changes in a call's statistics every `interval`
+    ///
+    /// See [`CallManager::subscribe_stats`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the call does not exist or has no media transport
+    pub async fn subscribe_stats(
+        &self,
+        call_id: CallId,
+        interval: std::time::Duration,
+    ) -> Result<mpsc::Receiver<StatsDelta>, ServiceError> {
+        self.call_manager
+            .subscribe_stats(call_id, interval)
+            .await
+            .map_err(|e| ServiceError::CallError(e.to_string()))
+    }
+
+    /// Get the recorded statistics samples of a call, oldest first
+    #[must_use]
+    pub fn get_stats_history(&self, call_id: CallId) -> Option<Vec<StatsSample>> {
+        self.call_manager.stats_history(call_id)
+    }
+
+    /// Export a call's statistics history as CSV
+    ///
+    /// # Errors
+    ///
+    /// Returns error if no history exists or the file cannot be written
+    pub fn export_stats_csv(&self, call_id: CallId, path: &Path) -> Result<(), StatsHistoryError> {
+        self.call_manager.export_stats_csv(call_id, path)
+    }
+
+    /// Export a call's statistics history as JSON
+    ///
+    /// # Errors
+    ///
+    /// Returns error if no history exists or the file cannot be written
+    pub fn export_stats_json(&self, call_id: CallId, path: &Path) -> Result<(), StatsHistoryError> {
+        self.call_manager.export_stats_json(call_id, path)
+    }
+
+    /// Get the media stream manager
+    #[must_use]
+    pub fn media(&self) -> &Arc<MediaStreamManager> {
+        &self.media
+    }
+
+    /// Run an application task under the service's supervisor
+    ///
+    /// Use it for the loops an application runs next to the service, such
+    /// as receiving signaling messages or dispatching events: if the loop
+    /// panics or returns it is restarted with backoff, and
+    /// [`WebRtcEvent::ServiceHealth`] reports it. `task` creates the loop's
+    /// future, again on every restart.
+    pub fn supervise<F, Fut>(&self, name: impl Into<String>, task: F)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = ()> + Send + 'static,
+    {
+        self.supervisor.supervise(name, task);
+    }
+
+    /// Health of the service's background tasks
+    #[must_use]
+    pub fn task_health(&self) -> ServiceHealth {
+        self.supervisor.health()
+    }
+
+    /// Snapshot of the health of the whole service
+    ///
+    /// Covers the media transports of ongoing calls, signaling liveness,
+    /// registered codecs, media devices and background tasks.
+    pub async fn health(&self) -> HealthReport {
+        let mut transport = TransportHealth::default();
+        for (_, call_transport) in self.call_manager.media_transports().await {
+            transport.calls += 1;
+            match call_transport.state().await {
+                MediaTransportState::Connected => transport.connected += 1,
+                MediaTransportState::Connecting => transport.connecting += 1,
+                MediaTransportState::Failed => transport.failed += 1,
+                MediaTransportState::Disconnected => {}
+            }
+        }
+
+        let signaling = SignalingHealth {
+            last_message_ms: self
+                .signaling
+                .last_message_age()
+                .map(|age| u64::try_from(age.as_millis()).unwrap_or(u64::MAX)),
+            consecutive_errors: self.signaling.consecutive_errors().await,
+        };
+
+        let codecs = self.media.codec_registry();
+        let codecs = CodecHealth {
+            video: codecs.video_mime_types(),
+            audio: codecs.audio_mime_types(),
+        };
+
+        let devices = DeviceHealth {
+            audio: self
+                .media
+                .get_audio_devices()
+                .iter()
+                .map(|device| device.name.clone())
+                .collect(),
+            video: self
+                .media
+                .get_video_devices()
+                .iter()
+                .map(|device| device.name.clone())
+                .collect(),
+        };
+
+        HealthReport {
+            generated_at: Utc::now(),
+            transport,
+            signaling,
+            codecs,
+            devices,
+            tasks: self.supervisor.health(),
+            events: self.event_stats(),
+        }
+    }
+
+    /// Runtime the service was set up for
+    #[must_use]
+    pub fn execution_model(&self) -> ExecutionModel {
+        self.execution_model
+    }
+
+    /// Get the performance governor, if enabled
+    #[must_use]
+    pub fn governor(&self) -> Option<&Arc<PerformanceGovernor>> {
+        self.governor.as_ref()
+    }
+
+    /// Get the call manager, for driving call setup and media directly
+    #[must_use]
+    pub fn call_manager(&self) -> &Arc<CallManager<I>> {
+        &self.call_manager
+    }
+
     /// Subscribe to events
     #[must_use]
     pub fn subscribe_events(&self) -> broadcast::Receiver<WebRtcEvent<I>> {
         self.event_sender.subscribe()
     }
 
+    /// Counters of the call, media and service event channels, including
+    /// events lost by lagging subscribers
+    #[must_use]
+    pub fn event_stats(&self) -> EventStats {
+        EventStats {
+            call: self.call_manager.event_stats(),
+            media: self.media.event_stats(),
+            service: self.event_sender.stats(),
+        }
+    }
+
+    /// Subscribe to call lifecycle events (incoming calls, accepts, ends)
+    #[must_use]
+    pub fn subscribe_call_events(&self) -> broadcast::Receiver<CallEvent<I>> {
+        self.call_manager.subscribe_events()
+    }
+
     /// Create a builder
     #[must_use]
     pub fn builder(signaling: Arc<SignalingHandler<T>>) -> WebRtcServiceBuilder<I, T> {
@@ -224,10 +1366,118 @@ impl<I: PeerIdentity, T: SignalingTransport> WebRtcService<I, T> {
     }
 }
 
+/// Raise reminders and place scheduled calls as they fall due
+/// Sample the governor until the service is dropped, forwarding changes
+async fn run_governor<I: PeerIdentity>(
+    governor: Arc<PerformanceGovernor>,
+    events: EventSender<WebRtcEvent<I>>,
+) {
+    let mut interval = tokio::time::interval(governor.config().interval);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        if let Some(event) = governor.sample() {
+            let _ = events.send(WebRtcEvent::Performance(event));
+        }
+    }
+}
+
+async fn run_webhooks<I: PeerIdentity>(
+    notifier: WebhookNotifier,
+    mut call_events: broadcast::Receiver<CallEvent<I>>,
+    mut events: broadcast::Receiver<WebRtcEvent<I>>,
+) {
+    let mut activity = CallActivity::new();
+    loop {
+        let event = tokio::select! {
+            event = call_events.recv() => match event {
+                Ok(event) => activity.on_call_event(&event),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "Webhooks missed call events");
+                    None
+                }
+                Err(broadcast::error::RecvError::Closed) => return,
+            },
+            event = events.recv() => match event {
+                Ok(WebRtcEvent::MissedCall(missed)) => Some(activity.on_missed_call(&missed)),
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => None,
+                Err(broadcast::error::RecvError::Closed) => return,
+            },
+        };
+        if let Some(event) = event {
+            notifier.notify(event);
+        }
+    }
+}
+
+async fn run_scheduler<I: PeerIdentity>(
+    scheduler: Arc<CallScheduler<I>>,
+    call_manager: Arc<CallManager<I>>,
+    events: EventSender<WebRtcEvent<I>>,
+) {
+    let clock = call_manager.clock().clone();
+    let mut interval = tokio::time::interval(scheduler.config().check_interval);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        let due = match scheduler.take_due(clock.utc_now()) {
+            Ok(due) => due,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to update scheduled calls");
+                continue;
+            }
+        };
+
+        for call in due.reminders {
+            tracing::info!(schedule_id = %call.id, at = %call.at, "Scheduled call reminder");
+            let _ = events.send(WebRtcEvent::Schedule(ScheduleEvent::Reminder { call }));
+        }
+
+        for call in due.dials {
+            let event = match call_manager
+                .initiate_call(call.peer.clone(), call.constraints)
+                .await
+            {
+                Ok(call_id) => {
+                    tracing::info!(schedule_id = %call.id, call_id = %call_id, "Scheduled call placed");
+                    // Stats history is best effort; the call proceeds without it
+                    if let Err(e) = call_manager.start_stats_history(call_id).await {
+                        tracing::debug!(call_id = %call_id, error = %e, "No stats history for scheduled call");
+                    }
+                    ScheduleEvent::Dialed {
+                        id: call.id,
+                        peer: call.peer,
+                        call_id,
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(schedule_id = %call.id, error = %e, "Scheduled call failed");
+                    ScheduleEvent::Failed {
+                        id: call.id,
+                        peer: call.peer,
+                        error: e.to_string(),
+                    }
+                }
+            };
+            let _ = events.send(WebRtcEvent::Schedule(event));
+        }
+    }
+}
+
 /// WebRTC service builder
+///
+/// Components set with the `with_*` methods take precedence over those in
+/// the configuration, whichever order they are set in.
 pub struct WebRtcServiceBuilder<I: PeerIdentity, T: SignalingTransport> {
     signaling: Arc<SignalingHandler<T>>,
     config: WebRtcConfig,
+    codecs: Option<Arc<CodecRegistry>>,
+    policy: Option<SharedCallPolicy>,
+    history_store: Option<SharedHistoryStore>,
+    clock: Option<SharedClock>,
+    metrics: Option<SharedMetrics>,
+    power_source: Option<SharedPowerSource>,
+    webhook_sender: Option<SharedWebhookSender>,
     _phantom: std::marker::PhantomData<I>,
 }
 
@@ -238,6 +1488,13 @@ impl<I: PeerIdentity, T: SignalingTransport> WebRtcServiceBuilder<I, T> {
         Self {
             signaling,
             config: WebRtcConfig::default(),
+            codecs: None,
+            policy: None,
+            history_store: None,
+            clock: None,
+            metrics: None,
+            power_source: None,
+            webhook_sender: None,
             _phantom: std::marker::PhantomData,
         }
     }
@@ -249,12 +1506,83 @@ impl<I: PeerIdentity, T: SignalingTransport> WebRtcServiceBuilder<I, T> {
         self
     }
 
+    /// Use these codec implementations for media tracks
+    #[must_use]
+    pub fn with_codec_registry(mut self, codecs: Arc<CodecRegistry>) -> Self {
+        self.codecs = Some(codecs);
+        self
+    }
+
+    /// Consult this policy before placing calls and letting calls ring
+    #[must_use]
+    pub fn with_call_policy(mut self, policy: SharedCallPolicy) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Keep call statistics histories in this store
+    #[must_use]
+    pub fn with_history_store(mut self, store: SharedHistoryStore) -> Self {
+        self.history_store = Some(store);
+        self
+    }
+
+    /// Read time from this clock
+    #[must_use]
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Report call lifecycle counters to this recorder
+    #[must_use]
+    pub fn with_metrics(mut self, metrics: SharedMetrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Give the performance governor the platform's battery state
+    #[must_use]
+    pub fn with_power_source(mut self, power: SharedPowerSource) -> Self {
+        self.power_source = Some(power);
+        self
+    }
+
+    /// Send call notification webhooks through this sender
+    #[must_use]
+    pub fn with_webhook_sender(mut self, sender: SharedWebhookSender) -> Self {
+        self.webhook_sender = Some(sender);
+        self
+    }
+
     /// Build the service
     ///
     /// # Errors
     ///
     /// Returns error if service creation fails
     pub async fn build(self) -> Result<WebRtcService<I, T>, ServiceError> {
-        WebRtcService::new(self.signaling, self.config).await
+        let mut config = self.config;
+        if let Some(codecs) = self.codecs {
+            config.codecs = codecs;
+        }
+        if let Some(policy) = self.policy {
+            config.call_config.policy = Some(policy);
+        }
+        if let Some(store) = self.history_store {
+            config.call_config.history_store = Some(store);
+        }
+        if let Some(clock) = self.clock {
+            config.call_config.clock = clock;
+        }
+        if let Some(metrics) = self.metrics {
+            config.call_config.metrics = metrics;
+        }
+        if let Some(power) = self.power_source {
+            config.power_source = Some(power);
+        }
+        if let Some(sender) = self.webhook_sender {
+            config.webhook_sender = Some(sender);
+        }
+        WebRtcService::new(self.signaling, config).await
     }
 }
@@ -2,8 +2,9 @@
 //!
 //! Handles SDP exchange and ICE candidate gathering for WebRTC connections.
 
+use crate::redact;
 use async_trait::async_trait;
-use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt;
 use std::net::SocketAddr;
 use std::str::FromStr;
@@ -66,6 +67,23 @@ pub trait SignalingTransport: Send + Sync {
         peer: &Self::PeerId,
     ) -> Result<Option<SocketAddr>, Self::Error>;
 
+    /// Discover every endpoint a peer is reachable at
+    ///
+    /// Dual-stack peers may be reachable over both IPv4 and IPv6; callers
+    /// race the returned addresses (see [`crate::dual_stack::race`]).
+    /// Defaults to the single address from
+    /// [`discover_peer_endpoint`](Self::discover_peer_endpoint).
+    async fn discover_peer_endpoints(
+        &self,
+        peer: &Self::PeerId,
+    ) -> Result<Vec<SocketAddr>, Self::Error> {
+        Ok(self
+            .discover_peer_endpoint(peer)
+            .await?
+            .into_iter()
+            .collect())
+    }
+
     /// Get the underlying QUIC connection handle for connection sharing
     ///
     /// This method allows media transport handlers to share the signaling connection,
@@ -86,154 +104,41 @@ pub trait SignalingTransport: Send + Sync {
     }
 }
 
-/// Signaling message types
-///
-/// Supports both legacy WebRTC (SDP/ICE) and QUIC-native (capability exchange) signaling.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
-#[serde(tag = "type", rename_all = "lowercase")]
-pub enum SignalingMessage {
-    // === Legacy WebRTC Messages (deprecated for new calls) ===
-    /// SDP offer (legacy WebRTC)
-    #[serde(rename = "offer")]
-    Offer {
-        /// Session ID
-        session_id: String,
-        /// SDP content
-        sdp: String,
-        /// Optional QUIC endpoint
-        quic_endpoint: Option<SocketAddr>,
-    },
-
-    /// SDP answer (legacy WebRTC)
-    #[serde(rename = "answer")]
-    Answer {
-        /// Session ID
-        session_id: String,
-        /// SDP content
-        sdp: String,
-        /// Optional QUIC endpoint
-        quic_endpoint: Option<SocketAddr>,
-    },
-
-    /// ICE candidate (legacy WebRTC)
-    #[serde(rename = "ice_candidate")]
-    IceCandidate {
-        /// Session ID
-        session_id: String,
-        /// Candidate string
-        candidate: String,
-        /// SDP mid
-        sdp_mid: Option<String>,
-        /// SDP mline index
-        sdp_mline_index: Option<u16>,
-    },
-
-    /// ICE gathering complete (legacy WebRTC)
-    #[serde(rename = "ice_complete")]
-    IceComplete {
-        /// Session ID
-        session_id: String,
-    },
-
-    // === QUIC-Native Messages ===
-    /// Capability exchange (QUIC-native)
-    ///
-    /// Sent instead of SDP offer. Contains local media capabilities.
-    #[serde(rename = "capability_exchange")]
-    CapabilityExchange {
-        /// Session/call ID
-        session_id: String,
-        /// Local media capabilities
-        audio: bool,
-        /// Video capability
-        video: bool,
-        /// Data channel capability
-        data_channel: bool,
-        /// Maximum bandwidth in kbps
-        max_bandwidth_kbps: u32,
-        /// QUIC endpoint for direct connection
-        quic_endpoint: Option<SocketAddr>,
-    },
-
-    /// Connection confirmation (QUIC-native)
-    ///
-    /// Sent in response to CapabilityExchange to confirm connection is ready.
-    #[serde(rename = "connection_confirm")]
-    ConnectionConfirm {
-        /// Session/call ID
-        session_id: String,
-        /// Peer's media capabilities (for mutual agreement)
-        audio: bool,
-        /// Video capability
-        video: bool,
-        /// Data channel capability
-        data_channel: bool,
-        /// Maximum bandwidth in kbps
-        max_bandwidth_kbps: u32,
-        /// QUIC endpoint for direct connection
-        quic_endpoint: Option<SocketAddr>,
-    },
-
-    /// Connection ready notification (QUIC-native)
-    ///
-    /// Sent when QUIC connection is established and media can flow.
-    #[serde(rename = "connection_ready")]
-    ConnectionReady {
-        /// Session/call ID
-        session_id: String,
-    },
-
-    // === Common Messages ===
-    /// Close session
-    #[serde(rename = "bye")]
-    Bye {
-        /// Session ID
-        session_id: String,
-        /// Optional reason
-        reason: Option<String>,
-    },
-}
+pub use saorsa_webrtc_proto::signaling::SignalingMessage;
 
-impl SignalingMessage {
-    /// Get the session ID
-    #[must_use]
-    pub fn session_id(&self) -> &str {
-        match self {
-            // Legacy WebRTC
-            Self::Offer { session_id, .. }
-            | Self::Answer { session_id, .. }
-            | Self::IceCandidate { session_id, .. }
-            | Self::IceComplete { session_id }
-            // QUIC-native
-            | Self::CapabilityExchange { session_id, .. }
-            | Self::ConnectionConfirm { session_id, .. }
-            | Self::ConnectionReady { session_id }
-            // Common
-            | Self::Bye { session_id, .. } => session_id,
-        }
-    }
+/// Outcome of running a signaling message through an interceptor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InterceptorDecision {
+    /// Pass the (possibly modified) message to the next interceptor
+    Continue(SignalingMessage),
+    /// Drop the message with the given reason
+    Veto(String),
+}
 
-    /// Check if this is a QUIC-native message
-    #[must_use]
-    pub fn is_quic_native(&self) -> bool {
-        matches!(
-            self,
-            Self::CapabilityExchange { .. }
-                | Self::ConnectionConfirm { .. }
-                | Self::ConnectionReady { .. }
-        )
+/// Signaling interceptor
+///
+/// Interceptors observe, mutate, or veto signaling messages as they pass
+/// through a `SignalingHandler`. They are run in registration order for
+/// outbound messages and in reverse order for inbound messages, so an
+/// interceptor that transforms outbound data (e.g. encryption) sees the
+/// inbound data first to undo it.
+///
+/// Both methods default to passing the message through unchanged.
+#[async_trait]
+pub trait SignalingInterceptor<P>: Send + Sync
+where
+    P: Send + Sync,
+{
+    /// Called before a message is handed to the transport
+    async fn on_outbound(&self, peer: &P, message: SignalingMessage) -> InterceptorDecision {
+        let _ = peer;
+        InterceptorDecision::Continue(message)
     }
 
-    /// Check if this is a legacy WebRTC message
-    #[must_use]
-    pub fn is_legacy_webrtc(&self) -> bool {
-        matches!(
-            self,
-            Self::Offer { .. }
-                | Self::Answer { .. }
-                | Self::IceCandidate { .. }
-                | Self::IceComplete { .. }
-        )
+    /// Called after a message is received from the transport
+    async fn on_inbound(&self, peer: &P, message: SignalingMessage) -> InterceptorDecision {
+        let _ = peer;
+        InterceptorDecision::Continue(message)
     }
 }
 
@@ -245,6 +150,11 @@ pub struct SignalingHandler<T: SignalingTransport> {
     transport: std::sync::Arc<T>,
     last_receive_time: std::sync::Arc<tokio::sync::Mutex<Instant>>,
     error_count: std::sync::Arc<tokio::sync::Mutex<u32>>,
+    /// When the transport last delivered a message
+    last_message: parking_lot::Mutex<Option<Instant>>,
+    interceptors: Vec<std::sync::Arc<dyn SignalingInterceptor<T::PeerId>>>,
+    /// Endpoints registered for identities with several devices
+    devices: parking_lot::RwLock<HashMap<String, Vec<T::PeerId>>>,
 }
 
 impl<T: SignalingTransport> SignalingHandler<T> {
@@ -255,31 +165,116 @@ impl<T: SignalingTransport> SignalingHandler<T> {
             transport,
             last_receive_time: std::sync::Arc::new(tokio::sync::Mutex::new(Instant::now())),
             error_count: std::sync::Arc::new(tokio::sync::Mutex::new(0)),
+            last_message: parking_lot::Mutex::new(None),
+            interceptors: Vec::new(),
+            devices: parking_lot::RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Add an interceptor to the end of the chain
+    #[must_use]
+    pub fn with_interceptor(
+        mut self,
+        interceptor: std::sync::Arc<dyn SignalingInterceptor<T::PeerId>>,
+    ) -> Self {
+        self.interceptors.push(interceptor);
+        self
+    }
+
+    /// Get the number of registered interceptors
+    #[must_use]
+    pub fn interceptor_count(&self) -> usize {
+        self.interceptors.len()
+    }
+
+    /// Run the outbound interceptor chain
+    ///
+    /// Returns `None` if an interceptor vetoed the message.
+    async fn intercept_outbound(
+        &self,
+        peer: &T::PeerId,
+        mut message: SignalingMessage,
+    ) -> Option<SignalingMessage> {
+        for interceptor in &self.interceptors {
+            match interceptor.on_outbound(peer, message).await {
+                InterceptorDecision::Continue(next) => message = next,
+                InterceptorDecision::Veto(reason) => {
+                    tracing::debug!(reason = %reason, "Outbound signaling message vetoed");
+                    return None;
+                }
+            }
+        }
+        Some(message)
+    }
+
+    /// Run the inbound interceptor chain
+    ///
+    /// Returns `None` if an interceptor vetoed the message.
+    async fn intercept_inbound(
+        &self,
+        peer: &T::PeerId,
+        mut message: SignalingMessage,
+    ) -> Option<SignalingMessage> {
+        for interceptor in self.interceptors.iter().rev() {
+            match interceptor.on_inbound(peer, message).await {
+                InterceptorDecision::Continue(next) => message = next,
+                InterceptorDecision::Veto(reason) => {
+                    tracing::debug!(reason = %reason, "Inbound signaling message vetoed");
+                    return None;
+                }
+            }
         }
+        Some(message)
     }
 
     /// Send a signaling message to a peer
     ///
+    /// The message is passed through the interceptor chain first. A message
+    /// vetoed by an interceptor is dropped and `Ok(())` is returned.
+    ///
     /// # Errors
     ///
     /// Returns error if sending fails
-    #[tracing::instrument(skip(self, message), fields(peer = %peer, message_type = ?message_type(&message)))]
+    #[tracing::instrument(
+        skip(self, message),
+        fields(
+            peer = %redact::identity(peer),
+            message_type = ?message_type(&message),
+            call_id = message.call_id().map(tracing::field::display),
+        )
+    )]
     pub async fn send_message(
         &self,
         peer: &T::PeerId,
         message: SignalingMessage,
     ) -> Result<(), T::Error> {
+        let Some(message) = self.intercept_outbound(peer, message).await else {
+            return Ok(());
+        };
         tracing::debug!("Sending signaling message");
         self.transport.send_message(peer, message).await
     }
 
     /// Receive a signaling message with rate limiting and backpressure
     ///
+    /// Received messages are passed through the interceptor chain. Messages
+    /// vetoed by an interceptor are dropped and the next message is awaited.
+    ///
     /// # Errors
     ///
     /// Returns error if receiving fails
     #[tracing::instrument(skip(self))]
     pub async fn receive_message(&self) -> Result<(T::PeerId, SignalingMessage), T::Error> {
+        loop {
+            let (peer, message) = self.receive_raw().await?;
+            if let Some(message) = self.intercept_inbound(&peer, message).await {
+                return Ok((peer, message));
+            }
+        }
+    }
+
+    /// Receive a single message from the transport, bypassing interceptors
+    async fn receive_raw(&self) -> Result<(T::PeerId, SignalingMessage), T::Error> {
         let mut last_time = self.last_receive_time.lock().await;
         let now = Instant::now();
         let elapsed = now.duration_since(*last_time);
@@ -305,8 +300,9 @@ impl<T: SignalingTransport> SignalingHandler<T> {
                 let mut error_count = self.error_count.lock().await;
                 *error_count = 0;
                 drop(error_count);
+                *self.last_message.lock() = Some(Instant::now());
 
-                tracing::debug!(peer = %result.0, message_type = ?message_type(&result.1), "Received signaling message");
+                tracing::debug!(peer = %redact::identity(&result.0), message_type = ?message_type(&result.1), "Received signaling message");
                 Ok(result)
             }
             Err(e) => {
@@ -328,12 +324,108 @@ impl<T: SignalingTransport> SignalingHandler<T> {
         }
     }
 
+    /// Time since the transport last delivered a message
+    ///
+    /// `None` until the first message arrives.
+    #[must_use]
+    pub fn last_message_age(&self) -> Option<Duration> {
+        self.last_message.lock().map(|at| at.elapsed())
+    }
+
+    /// Receive errors since the last message was delivered
+    pub async fn consecutive_errors(&self) -> u32 {
+        *self.error_count.lock().await
+    }
+
+    /// Register one of the devices an identity can be reached at
+    ///
+    /// Registering the same endpoint again has no effect.
+    pub fn register_device(&self, identity: &str, endpoint: T::PeerId) {
+        let mut devices = self.devices.write();
+        let endpoints = devices.entry(identity.to_string()).or_default();
+        if !endpoints
+            .iter()
+            .any(|known| known.to_string() == endpoint.to_string())
+        {
+            tracing::debug!(
+                identity = %redact::identity(identity),
+                endpoint = %redact::identity(&endpoint),
+                "Device registered"
+            );
+            endpoints.push(endpoint);
+        }
+    }
+
+    /// Forget a device of an identity
+    ///
+    /// Returns `false` if the endpoint was not registered.
+    pub fn unregister_device(&self, identity: &str, endpoint: &T::PeerId) -> bool {
+        let mut devices = self.devices.write();
+        let Some(endpoints) = devices.get_mut(identity) else {
+            return false;
+        };
+        let before = endpoints.len();
+        endpoints.retain(|known| known.to_string() != endpoint.to_string());
+        let removed = endpoints.len() < before;
+        if endpoints.is_empty() {
+            devices.remove(identity);
+        }
+        removed
+    }
+
+    /// Every endpoint an identity can be reached at
+    ///
+    /// An identity with no registered devices is reached at the peer ID of
+    /// the same name, if it parses as one.
+    #[must_use]
+    pub fn devices(&self, identity: &str) -> Vec<T::PeerId> {
+        if let Some(endpoints) = self.devices.read().get(identity) {
+            return endpoints.clone();
+        }
+        identity.parse().ok().into_iter().collect()
+    }
+
+    /// Send the same message to several endpoints, e.g. every device of
+    /// a callee
+    ///
+    /// A device that cannot be reached does not stop the others. Returns
+    /// the number of endpoints the message was sent to.
+    ///
+    /// # Errors
+    ///
+    /// Returns the last send error if no endpoint could be reached
+    pub async fn fork_message(
+        &self,
+        peers: &[T::PeerId],
+        message: SignalingMessage,
+    ) -> Result<usize, T::Error> {
+        let mut sent = 0;
+        let mut last_error = None;
+        for peer in peers {
+            match self.send_message(peer, message.clone()).await {
+                Ok(()) => sent += 1,
+                Err(e) => {
+                    tracing::warn!(
+                        peer = %redact::identity(peer),
+                        error = %e,
+                        "Failed to reach device"
+                    );
+                    last_error = Some(e);
+                }
+            }
+        }
+        match last_error {
+            Some(e) if sent == 0 => Err(e),
+            _ => Ok(sent),
+        }
+    }
+
     /// Discover endpoint for a peer
     ///
     /// # Errors
     ///
     /// Returns error if discovery fails
-    #[tracing::instrument(skip(self), fields(peer = %peer))]
+    #[tracing::instrument(skip(self), fields(peer = %redact::identity(peer)))]
     pub async fn discover_peer_endpoint(
         &self,
         peer: &T::PeerId,
@@ -341,13 +433,29 @@ impl<T: SignalingTransport> SignalingHandler<T> {
         tracing::info!("Discovering peer endpoint");
         let endpoint = self.transport.discover_peer_endpoint(peer).await?;
         if let Some(addr) = &endpoint {
-            tracing::info!(endpoint = %addr, "Peer endpoint discovered");
+            tracing::info!(endpoint = %redact::addr(*addr), "Peer endpoint discovered");
         } else {
             tracing::debug!("No endpoint found for peer");
         }
         Ok(endpoint)
     }
 
+    /// Discover every endpoint for a peer, in the order they should be
+    /// raced
+    ///
+    /// # Errors
+    ///
+    /// Returns error if discovery fails
+    #[tracing::instrument(skip(self), fields(peer = %redact::identity(peer)))]
+    pub async fn discover_peer_endpoints(
+        &self,
+        peer: &T::PeerId,
+    ) -> Result<Vec<std::net::SocketAddr>, T::Error> {
+        let endpoints = self.transport.discover_peer_endpoints(peer).await?;
+        tracing::debug!(count = endpoints.len(), "Peer endpoints discovered");
+        Ok(crate::dual_stack::interleave_families(&endpoints))
+    }
+
     /// Get connection handle for sharing with media transport
     ///
     /// This allows media transport to use the same underlying connection
@@ -383,7 +491,15 @@ fn message_type(msg: &SignalingMessage) -> &'static str {
         SignalingMessage::CapabilityExchange { .. } => "CapabilityExchange",
         SignalingMessage::ConnectionConfirm { .. } => "ConnectionConfirm",
         SignalingMessage::ConnectionReady { .. } => "ConnectionReady",
+        SignalingMessage::TrackUpdate { .. } => "TrackUpdate",
+        SignalingMessage::LayerSelection { .. } => "LayerSelection",
+        SignalingMessage::HandoffOffer { .. } => "HandoffOffer",
+        SignalingMessage::HandoffJoin { .. } => "HandoffJoin",
+        SignalingMessage::HandoffComplete { .. } => "HandoffComplete",
         // Common
+        SignalingMessage::Progress { .. } => "Progress",
+        SignalingMessage::AudioOnly { .. } => "AudioOnly",
+        SignalingMessage::Cancel { .. } => "Cancel",
         SignalingMessage::Bye { .. } => "Bye",
     }
 }
@@ -392,6 +508,7 @@ fn message_type(msg: &SignalingMessage) -> &'static str {
 #[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
+    use crate::types::CallId;
     use async_trait::async_trait;
     use std::collections::VecDeque;
     use std::sync::{Arc, Mutex};
@@ -498,6 +615,28 @@ mod tests {
         assert_eq!(received_message, message);
     }
 
+    #[tokio::test(start_paused = true)]
+    async fn test_signaling_handler_tracks_liveness() {
+        let transport = Arc::new(MockTransport::new());
+        let handler = SignalingHandler::new(transport.clone());
+        assert_eq!(handler.last_message_age(), None);
+
+        assert!(handler.receive_message().await.is_err());
+        assert!(handler.receive_message().await.is_err());
+        assert_eq!(handler.consecutive_errors().await, 2);
+
+        transport.add_message(
+            "peer1".to_string(),
+            SignalingMessage::Bye {
+                session_id: "test-session".to_string(),
+                reason: None,
+            },
+        );
+        handler.receive_message().await.unwrap();
+        assert_eq!(handler.consecutive_errors().await, 0);
+        assert_eq!(handler.last_message_age(), Some(Duration::ZERO));
+    }
+
     #[tokio::test]
     async fn test_signaling_handler_discover_endpoint() {
         let transport = Arc::new(MockTransport::new());
@@ -508,6 +647,36 @@ mod tests {
         assert_eq!(result.unwrap(), Some("127.0.0.1:8080".parse().unwrap()));
     }
 
+    #[tokio::test]
+    async fn test_signaling_handler_discover_endpoints_defaults_to_single() {
+        let transport = Arc::new(MockTransport::new());
+        let handler = SignalingHandler::new(transport);
+
+        let endpoints = handler
+            .discover_peer_endpoints(&"peer1".to_string())
+            .await
+            .unwrap();
+        assert_eq!(endpoints, vec!["127.0.0.1:8080".parse().unwrap()]);
+    }
+
+    #[test]
+    fn test_ipv6_endpoint_roundtrip() {
+        let message = SignalingMessage::CapabilityExchange {
+            session_id: "call-1".to_string(),
+            audio: true,
+            video: false,
+            data_channel: false,
+            max_bandwidth_kbps: 512,
+            quic_endpoint: Some("[2001:db8::1]:9000".parse().unwrap()),
+        };
+        let json = serde_json::to_string(&message).unwrap();
+        assert!(json.contains("[2001:db8::1]:9000"));
+        assert_eq!(
+            serde_json::from_str::<SignalingMessage>(&json).unwrap(),
+            message
+        );
+    }
+
     #[tokio::test]
     async fn test_signaling_handler_get_connection_handle() {
         let transport = Arc::new(MockTransport::new());
@@ -628,6 +797,20 @@ mod tests {
         assert!(!bye.is_quic_native());
     }
 
+    #[test]
+    fn test_call_id_from_session_id() {
+        let call_id = CallId::new();
+        let ready = SignalingMessage::ConnectionReady {
+            session_id: call_id.to_string(),
+        };
+        assert_eq!(ready.call_id(), Some(call_id));
+
+        let legacy = SignalingMessage::IceComplete {
+            session_id: "legacy-1".to_string(),
+        };
+        assert_eq!(legacy.call_id(), None);
+    }
+
     #[test]
     fn test_capability_exchange_serialization() {
         let msg = SignalingMessage::CapabilityExchange {
@@ -648,4 +831,135 @@ mod tests {
         let deserialized: SignalingMessage = serde_json::from_str(&serialized).unwrap();
         assert_eq!(deserialized, msg);
     }
+
+    struct VetoBye;
+
+    #[async_trait]
+    impl SignalingInterceptor<String> for VetoBye {
+        async fn on_outbound(
+            &self,
+            _peer: &String,
+            message: SignalingMessage,
+        ) -> InterceptorDecision {
+            match message {
+                SignalingMessage::Bye { .. } => {
+                    InterceptorDecision::Veto("bye blocked".to_string())
+                }
+                other => InterceptorDecision::Continue(other),
+            }
+        }
+
+        async fn on_inbound(
+            &self,
+            _peer: &String,
+            message: SignalingMessage,
+        ) -> InterceptorDecision {
+            match message {
+                SignalingMessage::Bye { .. } => {
+                    InterceptorDecision::Veto("bye blocked".to_string())
+                }
+                other => InterceptorDecision::Continue(other),
+            }
+        }
+    }
+
+    struct Tag(&'static str);
+
+    #[async_trait]
+    impl SignalingInterceptor<String> for Tag {
+        async fn on_outbound(
+            &self,
+            _peer: &String,
+            message: SignalingMessage,
+        ) -> InterceptorDecision {
+            match message {
+                SignalingMessage::ConnectionReady { session_id } => {
+                    InterceptorDecision::Continue(SignalingMessage::ConnectionReady {
+                        session_id: format!("{session_id}+{}", self.0),
+                    })
+                }
+                other => InterceptorDecision::Continue(other),
+            }
+        }
+
+        async fn on_inbound(
+            &self,
+            _peer: &String,
+            message: SignalingMessage,
+        ) -> InterceptorDecision {
+            match message {
+                SignalingMessage::ConnectionReady { session_id } => {
+                    InterceptorDecision::Continue(SignalingMessage::ConnectionReady {
+                        session_id: format!("{session_id}-{}", self.0),
+                    })
+                }
+                other => InterceptorDecision::Continue(other),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_interceptor_chain_order() {
+        let transport = Arc::new(MockTransport::new());
+        let handler = SignalingHandler::new(transport.clone())
+            .with_interceptor(Arc::new(Tag("a")))
+            .with_interceptor(Arc::new(Tag("b")));
+        assert_eq!(handler.interceptor_count(), 2);
+
+        let message = SignalingMessage::ConnectionReady {
+            session_id: "s".to_string(),
+        };
+        handler
+            .send_message(&"peer1".to_string(), message)
+            .await
+            .unwrap();
+
+        // Outbound runs in registration order
+        let (_, sent) = transport.messages.lock().unwrap().pop_front().unwrap();
+        assert_eq!(sent.session_id(), "s+a+b");
+
+        // Inbound runs in reverse order
+        transport.add_message("peer1".to_string(), sent);
+        let (_, received) = handler.receive_message().await.unwrap();
+        assert_eq!(received.session_id(), "s+a+b-b-a");
+    }
+
+    #[tokio::test]
+    async fn test_interceptor_veto_outbound() {
+        let transport = Arc::new(MockTransport::new());
+        let handler = SignalingHandler::new(transport.clone()).with_interceptor(Arc::new(VetoBye));
+
+        let bye = SignalingMessage::Bye {
+            session_id: "s".to_string(),
+            reason: None,
+        };
+        assert!(handler
+            .send_message(&"peer1".to_string(), bye)
+            .await
+            .is_ok());
+        assert!(transport.messages.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_interceptor_veto_inbound_skips_message() {
+        let transport = Arc::new(MockTransport::new());
+        let handler = SignalingHandler::new(transport.clone()).with_interceptor(Arc::new(VetoBye));
+
+        transport.add_message(
+            "peer1".to_string(),
+            SignalingMessage::Bye {
+                session_id: "s1".to_string(),
+                reason: None,
+            },
+        );
+        transport.add_message(
+            "peer1".to_string(),
+            SignalingMessage::ConnectionReady {
+                session_id: "s2".to_string(),
+            },
+        );
+
+        let (_, received) = handler.receive_message().await.unwrap();
+        assert_eq!(received.session_id(), "s2");
+    }
 }
@@ -0,0 +1,512 @@
+//! Low-bandwidth "slides mode" for screen share
+//!
+//! A shared document or slide deck barely changes, yet continuous video
+//! keeps paying for every frame. [`SlidesDetector`] watches the frames of a
+//! screen share: once they have been the same for a while it switches to
+//! slides mode, where each new page is sent once as a lossless PNG
+//! [`Still`] and nothing at all is sent in between. When the content starts
+//! moving for real (scrolling, a video playing) it switches back to video.
+//!
+//! Stills go over the data stream on their own track ([`slides_key`]),
+//! split into RTP packets by [`Still::to_rtp`] and put back together by a
+//! [`StillAssembler`] on the receiving side.
+
+use crate::link_transport::StreamType;
+use crate::quic_bridge::{RtpPacket, StreamType as RtpStreamType};
+use crate::quic_media_transport::{StreamKey, TrackId};
+use saorsa_webrtc_codecs::VideoFrame;
+use std::io::Cursor;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Track of the slides stream among the data streams
+pub const SLIDES_TRACK: TrackId = TrackId::MAX - 3;
+
+/// Payload type of still packets
+pub const SLIDES_PAYLOAD_TYPE: u8 = 125;
+
+/// Bytes of still data per packet, leaving room for the chunk header
+const CHUNK_LEN: usize = 1180;
+
+/// Chunk header: still id, chunk index, chunk count
+const HEADER_LEN: usize = 8;
+
+/// Largest still accepted by a [`StillAssembler`]
+const MAX_CHUNKS: u16 = 4096;
+
+/// Key of the stream stills are sent on
+#[must_use]
+pub const fn slides_key() -> StreamKey {
+    StreamKey::new(StreamType::Data, SLIDES_TRACK)
+}
+
+/// Slides mode errors
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SlidesError {
+    /// The frame is not RGB of its stated size
+    #[error("Invalid frame: {0}")]
+    InvalidFrame(String),
+
+    /// Encoding or decoding the image failed
+    #[error("Still image error: {0}")]
+    Image(String),
+
+    /// A packet is not part of a still
+    #[error("Malformed still packet: {0}")]
+    Malformed(String),
+}
+
+/// Slides mode configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlidesConfig {
+    /// Fraction of sampled pixels that must change for a frame to count
+    /// as different
+    pub change_threshold: f32,
+    /// Difference in any color channel that counts as a changed pixel
+    pub pixel_tolerance: u8,
+    /// Every how many pixels, in each direction, are compared
+    pub sample_stride: usize,
+    /// Frames the content must stay the same to enter slides mode, and to
+    /// count a new page as settled
+    pub static_frames: u32,
+    /// Frames the content must keep changing to go back to video
+    pub motion_frames: u32,
+    /// The current still is sent again after this long, for receivers that
+    /// joined late or lost it
+    pub refresh_interval: Duration,
+}
+
+impl Default for SlidesConfig {
+    fn default() -> Self {
+        Self {
+            change_threshold: 0.002,
+            pixel_tolerance: 8,
+            sample_stride: 4,
+            static_frames: 30,
+            motion_frames: 15,
+            refresh_interval: Duration::from_secs(10),
+        }
+    }
+}
+
+/// What to do with a screen-share frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SlidesDecision {
+    /// Encode and send it as video
+    Video,
+    /// Send it as a still instead of video
+    Still,
+    /// Send nothing: the receiver already shows this content
+    Skip,
+}
+
+/// Decides frame by frame between video and slides mode
+#[derive(Debug)]
+pub struct SlidesDetector {
+    config: SlidesConfig,
+    previous: Option<VideoFrame>,
+    slides: bool,
+    /// Consecutive frames equal to the one before
+    still_run: u32,
+    /// Consecutive frames different from the one before
+    motion_run: u32,
+    /// In slides mode, whether the content changed since the last still
+    changed: bool,
+    /// Timestamp (ms) the last still was sent at
+    last_still: Option<u64>,
+}
+
+impl SlidesDetector {
+    /// Create a detector, starting in video mode
+    #[must_use]
+    pub fn new(config: SlidesConfig) -> Self {
+        Self {
+            config,
+            previous: None,
+            slides: false,
+            still_run: 0,
+            motion_run: 0,
+            changed: false,
+            last_still: None,
+        }
+    }
+
+    /// Whether slides mode is on
+    #[must_use]
+    pub fn is_slides(&self) -> bool {
+        self.slides
+    }
+
+    /// Look at the next frame and decide how to send it
+    pub fn push(&mut self, frame: &VideoFrame) -> SlidesDecision {
+        let moved = self
+            .previous
+            .as_ref()
+            .is_none_or(|previous| self.differs(previous, frame));
+        self.previous = Some(frame.clone());
+        if moved {
+            self.still_run = 0;
+            self.motion_run = self.motion_run.saturating_add(1);
+        } else {
+            self.still_run = self.still_run.saturating_add(1);
+            self.motion_run = 0;
+        }
+
+        if !self.slides {
+            if self.still_run >= self.config.static_frames {
+                tracing::debug!("Screen content static, switching to slides mode");
+                self.slides = true;
+                return self.still(frame);
+            }
+            return SlidesDecision::Video;
+        }
+
+        if self.motion_run >= self.config.motion_frames {
+            tracing::debug!("Screen content moving, switching back to video");
+            self.slides = false;
+            self.changed = false;
+            self.last_still = None;
+            return SlidesDecision::Video;
+        }
+        if moved {
+            self.changed = true;
+            return SlidesDecision::Skip;
+        }
+        // Send a new page once it has settled, and refresh the old one now
+        // and then
+        let settled = self.changed && self.still_run >= self.config.static_frames;
+        let refresh_ms =
+            u64::try_from(self.config.refresh_interval.as_millis()).unwrap_or(u64::MAX);
+        let stale = self
+            .last_still
+            .is_some_and(|at| frame.timestamp.saturating_sub(at) >= refresh_ms);
+        if settled || (stale && !self.changed) {
+            return self.still(frame);
+        }
+        SlidesDecision::Skip
+    }
+
+    fn still(&mut self, frame: &VideoFrame) -> SlidesDecision {
+        self.changed = false;
+        self.last_still = Some(frame.timestamp);
+        SlidesDecision::Still
+    }
+
+    /// Whether two frames differ by more than the threshold
+    fn differs(&self, a: &VideoFrame, b: &VideoFrame) -> bool {
+        if a.width != b.width || a.height != b.height || a.data.len() != b.data.len() {
+            return true;
+        }
+        let stride = self.config.sample_stride.max(1);
+        let (width, height) = (a.width as usize, a.height as usize);
+        let (mut sampled, mut changed) = (0usize, 0usize);
+        for y in (0..height).step_by(stride) {
+            for x in (0..width).step_by(stride) {
+                let at = (y * width + x) * 3;
+                let (Some(pa), Some(pb)) = (a.data.get(at..at + 3), b.data.get(at..at + 3)) else {
+                    continue;
+                };
+                sampled += 1;
+                if pa
+                    .iter()
+                    .zip(pb)
+                    .any(|(ca, cb)| ca.abs_diff(*cb) > self.config.pixel_tolerance)
+                {
+                    changed += 1;
+                }
+            }
+        }
+        sampled > 0 && changed as f32 / sampled as f32 > self.config.change_threshold
+    }
+}
+
+/// A screen-share picture sent as a PNG
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Still {
+    /// Identifies the still among those of the share; newer stills have
+    /// higher ids, across wraps
+    pub id: u32,
+    /// PNG image
+    pub png: Vec<u8>,
+}
+
+impl Still {
+    /// Encode an RGB frame
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the frame is not RGB of its stated size
+    pub fn encode(id: u32, frame: &VideoFrame) -> Result<Self, SlidesError> {
+        let expected = frame.width as usize * frame.height as usize * 3;
+        if frame.width == 0 || frame.height == 0 || frame.data.len() != expected {
+            return Err(SlidesError::InvalidFrame(format!(
+                "{} bytes for {}x{} RGB",
+                frame.data.len(),
+                frame.width,
+                frame.height
+            )));
+        }
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, frame.width, frame.height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_compression(png::Compression::High);
+        let mut writer = encoder
+            .write_header()
+            .map_err(|e| SlidesError::Image(e.to_string()))?;
+        writer
+            .write_image_data(&frame.data)
+            .map_err(|e| SlidesError::Image(e.to_string()))?;
+        writer
+            .finish()
+            .map_err(|e| SlidesError::Image(e.to_string()))?;
+        Ok(Self { id, png })
+    }
+
+    /// Decode to an RGB frame
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the image is not an 8-bit RGB PNG
+    pub fn decode(&self, timestamp: u64) -> Result<VideoFrame, SlidesError> {
+        let decoder = png::Decoder::new(Cursor::new(&self.png));
+        let mut reader = decoder
+            .read_info()
+            .map_err(|e| SlidesError::Image(e.to_string()))?;
+        let size = reader
+            .output_buffer_size()
+            .ok_or_else(|| SlidesError::Image("image too large".to_string()))?;
+        let mut data = vec![0; size];
+        let info = reader
+            .next_frame(&mut data)
+            .map_err(|e| SlidesError::Image(e.to_string()))?;
+        if info.color_type != png::ColorType::Rgb || info.bit_depth != png::BitDepth::Eight {
+            return Err(SlidesError::Image(format!(
+                "{:?} {:?} image",
+                info.color_type, info.bit_depth
+            )));
+        }
+        data.truncate(info.buffer_size());
+        Ok(VideoFrame {
+            data,
+            width: info.width,
+            height: info.height,
+            timestamp,
+        })
+    }
+
+    /// Split into packets for [`slides_key`], numbered from `sequence_number`
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the still is too large to send
+    pub fn to_rtp(&self, ssrc: u32, sequence_number: u16) -> Result<Vec<RtpPacket>, SlidesError> {
+        let count = self.png.len().div_ceil(CHUNK_LEN);
+        let count = u16::try_from(count)
+            .ok()
+            .filter(|count| (1..=MAX_CHUNKS).contains(count))
+            .ok_or_else(|| SlidesError::InvalidFrame(format!("{} byte still", self.png.len())))?;
+        self.png
+            .chunks(CHUNK_LEN)
+            .zip(0u16..)
+            .map(|(chunk, index)| {
+                let mut payload = Vec::with_capacity(HEADER_LEN + chunk.len());
+                payload.extend_from_slice(&self.id.to_be_bytes());
+                payload.extend_from_slice(&index.to_be_bytes());
+                payload.extend_from_slice(&count.to_be_bytes());
+                payload.extend_from_slice(chunk);
+                let mut packet = RtpPacket::new(
+                    SLIDES_PAYLOAD_TYPE,
+                    sequence_number.wrapping_add(index),
+                    self.id,
+                    ssrc,
+                    payload,
+                    RtpStreamType::Data,
+                )
+                .map_err(|e| SlidesError::Malformed(e.to_string()))?;
+                packet.marker = index + 1 == count;
+                Ok(packet)
+            })
+            .collect()
+    }
+}
+
+/// Puts stills back together from their packets
+///
+/// Only the newest still is assembled: packets of an older one are
+/// dropped, since it has already been replaced on screen.
+#[derive(Debug, Default)]
+pub struct StillAssembler {
+    id: Option<u32>,
+    chunks: Vec<Option<Vec<u8>>>,
+    received: usize,
+}
+
+impl StillAssembler {
+    /// Create an assembler
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a received packet
+    ///
+    /// Returns the still once its last missing packet arrives.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the packet is not part of a still
+    pub fn push(&mut self, packet: &RtpPacket) -> Result<Option<Still>, SlidesError> {
+        if packet.payload_type != SLIDES_PAYLOAD_TYPE {
+            return Err(SlidesError::Malformed(format!(
+                "payload type {}",
+                packet.payload_type
+            )));
+        }
+        let header = packet
+            .payload
+            .get(..HEADER_LEN)
+            .ok_or_else(|| SlidesError::Malformed("short packet".to_string()))?;
+        let id = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+        let index = usize::from(u16::from_be_bytes([header[4], header[5]]));
+        let count = u16::from_be_bytes([header[6], header[7]]);
+        if count == 0 || count > MAX_CHUNKS || index >= usize::from(count) {
+            return Err(SlidesError::Malformed(format!("chunk {index} of {count}")));
+        }
+
+        match self.id {
+            Some(current) if current == id => {}
+            // Older than the still being assembled
+            Some(current) if (id.wrapping_sub(current) as i32) < 0 => return Ok(None),
+            _ => {
+                self.id = Some(id);
+                self.chunks = vec![None; usize::from(count)];
+                self.received = 0;
+            }
+        }
+        if self.chunks.len() != usize::from(count) {
+            return Err(SlidesError::Malformed(format!(
+                "still {id} has {} chunks, packet says {count}",
+                self.chunks.len()
+            )));
+        }
+        if self.chunks[index].is_none() {
+            self.chunks[index] = Some(packet.payload[HEADER_LEN..].to_vec());
+            self.received += 1;
+        }
+        if self.received < self.chunks.len() {
+            return Ok(None);
+        }
+
+        let png = self.chunks.drain(..).flatten().flatten().collect();
+        self.received = 0;
+        Ok(Some(Still { id, png }))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::synthetic::TestPatternSource;
+
+    fn page(shade: u8, timestamp: u64) -> VideoFrame {
+        VideoFrame {
+            data: vec![shade; 64 * 48 * 3],
+            width: 64,
+            height: 48,
+            timestamp,
+        }
+    }
+
+    /// Push frames 33 ms apart, returning the decisions
+    fn feed(
+        detector: &mut SlidesDetector,
+        at: &mut u64,
+        frames: impl IntoIterator<Item = VideoFrame>,
+    ) -> Vec<SlidesDecision> {
+        frames
+            .into_iter()
+            .map(|frame| {
+                *at += 33;
+                detector.push(&VideoFrame {
+                    timestamp: *at,
+                    ..frame
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_static_content_switches_to_stills() {
+        use SlidesDecision::{Skip, Still, Video};
+        let config = SlidesConfig {
+            static_frames: 3,
+            motion_frames: 3,
+            refresh_interval: Duration::from_millis(500),
+            ..SlidesConfig::default()
+        };
+        let mut detector = SlidesDetector::new(config);
+        let mut at = 0;
+
+        // A page held long enough is sent once as a still, and the next
+        // page when it has settled
+        let pages = (0..6)
+            .map(|_| page(200, 0))
+            .chain((0..5).map(|_| page(100, 0)));
+        assert_eq!(
+            feed(&mut detector, &mut at, pages),
+            vec![Video, Video, Video, Still, Skip, Skip, Skip, Skip, Skip, Still, Skip]
+        );
+        assert!(detector.is_slides());
+
+        // Held long enough, the page is sent again
+        let decisions = feed(&mut detector, &mut at, (0..15).map(|_| page(100, 0)));
+        assert_eq!(decisions.iter().filter(|d| **d == Still).count(), 1);
+
+        // Moving content goes back to video
+        let mut shade = 0u8;
+        let moving = (0..3).map(|_| {
+            shade = shade.wrapping_add(50);
+            page(shade, 0)
+        });
+        assert_eq!(
+            feed(&mut detector, &mut at, moving),
+            vec![Skip, Skip, Video]
+        );
+        assert!(!detector.is_slides());
+    }
+
+    #[test]
+    fn test_still_roundtrip_over_packets() {
+        let frame = TestPatternSource::new(320, 240, 30).unwrap().next_frame();
+        let still = Still::encode(7, &frame).unwrap();
+        assert!(still.png.len() < frame.data.len() / 10);
+        let packets = still.to_rtp(1, u16::MAX).unwrap();
+        assert!(packets.last().unwrap().marker);
+
+        let mut assembler = StillAssembler::new();
+        // An older still arriving late is ignored
+        let old = Still::encode(6, &page(0, 0)).unwrap().to_rtp(1, 0).unwrap();
+        let mut assembled = None;
+        for (i, packet) in packets.iter().enumerate().rev() {
+            let bytes = packet.to_bytes().unwrap();
+            let packet = RtpPacket::from_bytes(&bytes).unwrap();
+            assembled = assembler.push(&packet).unwrap();
+            if i == packets.len() - 1 {
+                assert_eq!(assembler.push(&old[0]).unwrap(), None);
+            }
+        }
+        let assembled = assembled.unwrap();
+        assert_eq!(assembled, still);
+        let decoded = assembled.decode(frame.timestamp).unwrap();
+        assert_eq!(decoded.data, frame.data);
+        assert_eq!((decoded.width, decoded.height), (320, 240));
+
+        let bad = VideoFrame {
+            data: vec![0; 10],
+            ..frame
+        };
+        assert!(Still::encode(8, &bad).is_err());
+    }
+}
@@ -0,0 +1,285 @@
+//! Call and media transport state machines
+//!
+//! Each machine is declared once, as a table of allowed transitions in
+//! [`CALL_TRANSITIONS`] and [`TRANSPORT_TRANSITIONS`]. The call manager and
+//! the media transport validate state changes against these tables,
+//! and the tests enumerate every pair of states from them, so the documented
+//! machine and the code cannot drift apart.
+//!
+//! The two machines are linked by [`call_state_for_transport`], the call
+//! state a transport state change drives a call to. Every transport
+//! transition must drive an allowed call transition.
+
+use crate::quic_media_transport::MediaTransportState;
+use crate::types::CallState;
+use std::fmt;
+use thiserror::Error;
+
+/// A state machine declared by a transition table
+pub trait StateMachine: Copy + Eq + fmt::Debug + 'static {
+    /// Every state
+    const STATES: &'static [Self];
+
+    /// Allowed `(from, to)` transitions; staying in a state is only allowed
+    /// where listed
+    const TRANSITIONS: &'static [(Self, Self)];
+
+    /// Check if moving from this state to `to` is allowed
+    #[must_use]
+    fn can_transition_to(self, to: Self) -> bool {
+        Self::TRANSITIONS.contains(&(self, to))
+    }
+
+    /// Move to `to`, returning the previous state
+    ///
+    /// # Errors
+    ///
+    /// Returns error, leaving the state unchanged, if the transition is not
+    /// allowed
+    fn transition(&mut self, to: Self) -> Result<Self, InvalidTransition<Self>> {
+        let from = *self;
+        if !from.can_transition_to(to) {
+            return Err(InvalidTransition { from, to });
+        }
+        *self = to;
+        Ok(from)
+    }
+}
+
+/// A transition the state machine does not allow
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("Invalid state transition from {from:?} to {to:?}")]
+pub struct InvalidTransition<S: fmt::Debug> {
+    /// State before the transition
+    pub from: S,
+    /// Requested state
+    pub to: S,
+}
+
+/// Allowed call state transitions
+///
+/// - **Setup**: Idle → Calling → Connecting → Connected, or Idle →
+///   Connecting for QUIC with a pre-established transport. Calling →
+///   Connected when answered before the transport reports connecting.
+/// - **Teardown**: Connected → Ending → Idle
+/// - **Keepalive**: Connected ⇄ Reconnecting → Failed
+/// - **Failure**: any active state → Failed, and a setup whose transport
+///   closed
+/// - **Recovery**: Failed → Idle
+pub const CALL_TRANSITIONS: &[(CallState, CallState)] = &[
+    (CallState::Idle, CallState::Calling),
+    (CallState::Idle, CallState::Connecting),
+    (CallState::Calling, CallState::Connecting),
+    (CallState::Calling, CallState::Connected),
+    (CallState::Connecting, CallState::Connected),
+    (CallState::Connected, CallState::Ending),
+    (CallState::Reconnecting, CallState::Ending),
+    (CallState::Ending, CallState::Idle),
+    (CallState::Connected, CallState::Reconnecting),
+    (CallState::Reconnecting, CallState::Connected),
+    (CallState::Calling, CallState::Failed),
+    (CallState::Connecting, CallState::Failed),
+    (CallState::Connected, CallState::Failed),
+    (CallState::Reconnecting, CallState::Failed),
+    (CallState::Failed, CallState::Idle),
+];
+
+/// Allowed media transport state transitions
+///
+/// Connects and disconnects in any order, may fail while connecting or
+/// connected, and retries from failure. Staying in a state is always
+/// allowed.
+pub const TRANSPORT_TRANSITIONS: &[(MediaTransportState, MediaTransportState)] = &[
+    (
+        MediaTransportState::Disconnected,
+        MediaTransportState::Disconnected,
+    ),
+    (
+        MediaTransportState::Disconnected,
+        MediaTransportState::Connecting,
+    ),
+    (
+        MediaTransportState::Connecting,
+        MediaTransportState::Connecting,
+    ),
+    (
+        MediaTransportState::Connecting,
+        MediaTransportState::Connected,
+    ),
+    (MediaTransportState::Connecting, MediaTransportState::Failed),
+    (
+        MediaTransportState::Connecting,
+        MediaTransportState::Disconnected,
+    ),
+    (
+        MediaTransportState::Connected,
+        MediaTransportState::Connected,
+    ),
+    (
+        MediaTransportState::Connected,
+        MediaTransportState::Disconnected,
+    ),
+    (MediaTransportState::Connected, MediaTransportState::Failed),
+    (MediaTransportState::Failed, MediaTransportState::Failed),
+    (
+        MediaTransportState::Failed,
+        MediaTransportState::Disconnected,
+    ),
+    (MediaTransportState::Failed, MediaTransportState::Connecting),
+];
+
+impl StateMachine for CallState {
+    const STATES: &'static [Self] = &[
+        CallState::Idle,
+        CallState::Calling,
+        CallState::Connecting,
+        CallState::Connected,
+        CallState::Reconnecting,
+        CallState::Ending,
+        CallState::Failed,
+    ];
+
+    const TRANSITIONS: &'static [(Self, Self)] = CALL_TRANSITIONS;
+}
+
+impl StateMachine for MediaTransportState {
+    const STATES: &'static [Self] = &[
+        MediaTransportState::Disconnected,
+        MediaTransportState::Connecting,
+        MediaTransportState::Connected,
+        MediaTransportState::Failed,
+    ];
+
+    const TRANSITIONS: &'static [(Self, Self)] = TRANSPORT_TRANSITIONS;
+}
+
+/// Call state a call in `call` state moves to when its transport is in
+/// `transport` state
+///
+/// A call that is ending or failed only follows its transport to
+/// disconnected; it does not come back when the transport reconnects. A
+/// call that has not started connecting ignores a disconnected transport.
+#[must_use]
+pub fn call_state_for_transport(call: CallState, transport: MediaTransportState) -> CallState {
+    match (call, transport) {
+        (CallState::Connected | CallState::Reconnecting, MediaTransportState::Disconnected) => {
+            CallState::Ending
+        }
+        (CallState::Connecting, MediaTransportState::Disconnected) => CallState::Failed,
+        (CallState::Calling, MediaTransportState::Disconnected) => CallState::Calling,
+        (CallState::Reconnecting, MediaTransportState::Connected) => CallState::Reconnecting,
+        (CallState::Ending | CallState::Failed, MediaTransportState::Disconnected) => {
+            CallState::Idle
+        }
+        (CallState::Ending | CallState::Failed, _) => call,
+        (_, transport) => CallState::from_transport_state(transport),
+    }
+}
+
+/// Transport state a call in `call` state runs over
+#[must_use]
+pub fn transport_state_for_call(call: CallState) -> MediaTransportState {
+    match call {
+        CallState::Idle | CallState::Calling | CallState::Ending => {
+            MediaTransportState::Disconnected
+        }
+        CallState::Connecting => MediaTransportState::Connecting,
+        CallState::Connected | CallState::Reconnecting => MediaTransportState::Connected,
+        CallState::Failed => MediaTransportState::Failed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// Every `(from, to)` pair of a machine's states
+    fn all_pairs<S: StateMachine>() -> impl Iterator<Item = (S, S)> {
+        S::STATES
+            .iter()
+            .flat_map(|&from| S::STATES.iter().map(move |&to| (from, to)))
+    }
+
+    /// States reachable from `start`
+    fn reachable<S: StateMachine>(start: S) -> Vec<S> {
+        let mut seen = vec![start];
+        let mut queue = VecDeque::from([start]);
+        while let Some(state) = queue.pop_front() {
+            for &(_, to) in S::TRANSITIONS.iter().filter(|(from, _)| *from == state) {
+                if !seen.contains(&to) {
+                    seen.push(to);
+                    queue.push_back(to);
+                }
+            }
+        }
+        seen
+    }
+
+    fn check_table<S: StateMachine>(initial: S) {
+        for (i, transition) in S::TRANSITIONS.iter().enumerate() {
+            assert!(
+                !S::TRANSITIONS[..i].contains(transition),
+                "duplicate transition {transition:?}"
+            );
+        }
+        assert!(S::TRANSITIONS
+            .iter()
+            .all(|(from, to)| S::STATES.contains(from) && S::STATES.contains(to)));
+        assert_eq!(
+            reachable(initial).len(),
+            S::STATES.len(),
+            "unreachable state"
+        );
+
+        for (from, to) in all_pairs::<S>() {
+            let mut state = from;
+            match state.transition(to) {
+                Ok(previous) => {
+                    assert!(S::TRANSITIONS.contains(&(from, to)));
+                    assert_eq!((previous, state), (from, to));
+                }
+                Err(e) => {
+                    assert!(!S::TRANSITIONS.contains(&(from, to)));
+                    assert_eq!(e, InvalidTransition { from, to });
+                    assert_eq!(state, from);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_call_table_is_well_formed() {
+        check_table(CallState::Idle);
+        // Every state can get back to idle
+        for &state in CallState::STATES {
+            assert!(reachable(state).contains(&CallState::Idle), "{state:?}");
+        }
+    }
+
+    #[test]
+    fn test_transport_table_is_well_formed() {
+        check_table(MediaTransportState::Disconnected);
+        for &state in MediaTransportState::STATES {
+            assert!(state.can_transition_to(state), "{state:?}");
+        }
+    }
+
+    #[test]
+    fn test_transport_transitions_drive_allowed_call_transitions() {
+        for &call in CallState::STATES {
+            let transport = transport_state_for_call(call);
+            // Includes staying put, which moves an ending call on to idle
+            for &(_, to) in TRANSPORT_TRANSITIONS
+                .iter()
+                .filter(|(from, _)| *from == transport)
+            {
+                let next = call_state_for_transport(call, to);
+                assert!(
+                    next == call || call.can_transition_to(next),
+                    "{call:?} over {transport:?} -> {to:?} moves to {next:?}"
+                );
+            }
+        }
+    }
+}
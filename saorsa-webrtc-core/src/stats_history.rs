@@ -0,0 +1,648 @@
+//! Historical per-call statistics
+//!
+//! While a call runs, its transport statistics are sampled once per
+//! interval into a bounded ring buffer. Histories outlive the call so they
+//! can be charted or exported as CSV/JSON for analysis afterwards; only the
+//! most recent [`StatsHistoryConfig::retained_calls`] histories are kept.
+//!
+//! Live dashboards can instead subscribe to [`StatsDelta`]s, which carry
+//! only what changed since the previous sample.
+
+use crate::quic_media_transport::TransportStats;
+use crate::types::{CallId, CallMetadata};
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Default interval between samples (1 second)
+pub const DEFAULT_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Default span of samples kept per call (10 minutes)
+pub const DEFAULT_RETENTION: Duration = Duration::from_secs(600);
+
+/// Default number of call histories kept
+pub const DEFAULT_RETAINED_CALLS: usize = 16;
+
+/// Header line of CSV exports
+pub const CSV_HEADER: &str = "timestamp,packets_sent,packets_received,bytes_sent,bytes_received,\
+stream_errors,send_bitrate_bps,recv_bitrate_bps,send_packet_rate,recv_packet_rate";
+
+/// Stats history errors
+#[derive(Error, Debug)]
+pub enum StatsHistoryError {
+    /// No history recorded for the call
+    #[error("No stats history for call: {0}")]
+    NotFound(String),
+
+    /// Export file could not be written
+    #[error("I/O error: {0}")]
+    Io(String),
+
+    /// Samples could not be serialized
+    #[error("Serialization error: {0}")]
+    Serialization(String),
+}
+
+impl From<std::io::Error> for StatsHistoryError {
+    fn from(err: std::io::Error) -> Self {
+        StatsHistoryError::Io(err.to_string())
+    }
+}
+
+/// Stats history configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatsHistoryConfig {
+    /// Interval between samples
+    pub interval: Duration,
+    /// Span of samples kept per call
+    pub retention: Duration,
+    /// Number of call histories kept, oldest dropped first
+    pub retained_calls: usize,
+}
+
+impl Default for StatsHistoryConfig {
+    fn default() -> Self {
+        Self {
+            interval: DEFAULT_SAMPLE_INTERVAL,
+            retention: DEFAULT_RETENTION,
+            retained_calls: DEFAULT_RETAINED_CALLS,
+        }
+    }
+}
+
+impl StatsHistoryConfig {
+    /// Number of samples kept per call
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        let interval = self.interval.as_millis().max(1);
+        usize::try_from(self.retention.as_millis() / interval)
+            .unwrap_or(usize::MAX)
+            .max(1)
+    }
+}
+
+/// One statistics sample
+///
+/// Counters are cumulative; rates are the 1s averages across all streams.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct StatsSample {
+    /// When the sample was taken
+    pub timestamp: DateTime<Utc>,
+    /// Total packets sent
+    pub packets_sent: u64,
+    /// Total packets received
+    pub packets_received: u64,
+    /// Total bytes sent
+    pub bytes_sent: u64,
+    /// Total bytes received
+    pub bytes_received: u64,
+    /// Number of stream errors
+    pub stream_errors: u64,
+    /// Outbound bitrate in bits per second
+    pub send_bitrate_bps: f64,
+    /// Inbound bitrate in bits per second
+    pub recv_bitrate_bps: f64,
+    /// Outbound packets per second
+    pub send_packet_rate: f64,
+    /// Inbound packets per second
+    pub recv_packet_rate: f64,
+}
+
+impl StatsSample {
+    /// Sample a transport stats snapshot now
+    #[must_use]
+    pub fn from_stats(stats: &TransportStats) -> Self {
+        Self::from_stats_at(stats, Utc::now())
+    }
+
+    /// Sample a transport stats snapshot taken at `timestamp`
+    #[must_use]
+    pub fn from_stats_at(stats: &TransportStats, timestamp: DateTime<Utc>) -> Self {
+        let sent = stats.sent_rates();
+        let received = stats.received_rates();
+        Self {
+            timestamp,
+            packets_sent: stats.packets_sent,
+            packets_received: stats.packets_received,
+            bytes_sent: stats.bytes_sent,
+            bytes_received: stats.bytes_received,
+            stream_errors: stats.stream_errors,
+            send_bitrate_bps: sent.bitrate_1s_bps,
+            recv_bitrate_bps: received.bitrate_1s_bps,
+            send_packet_rate: sent.packet_rate_1s,
+            recv_packet_rate: received.packet_rate_1s,
+        }
+    }
+
+    fn write_csv_row(&self, out: &mut impl Write) -> std::io::Result<()> {
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{:.0},{:.0},{:.1},{:.1}",
+            self.timestamp.to_rfc3339(),
+            self.packets_sent,
+            self.packets_received,
+            self.bytes_sent,
+            self.bytes_received,
+            self.stream_errors,
+            self.send_bitrate_bps,
+            self.recv_bitrate_bps,
+            self.send_packet_rate,
+            self.recv_packet_rate,
+        )
+    }
+}
+
+/// Change in a call's statistics between two samples
+///
+/// Produced by [`CallManager::subscribe_stats`](crate::call::CallManager::subscribe_stats).
+/// Timestamps are monotonic and measured from when the subscription
+/// started, so they never jump with the wall clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct StatsDelta {
+    /// Monotonic time of this sample, since the subscription started
+    pub at: Duration,
+    /// Time since the previous delta, or since the subscription started
+    pub elapsed: Duration,
+    /// Packets sent since the previous delta
+    pub packets_sent: u64,
+    /// Packets received since the previous delta
+    pub packets_received: u64,
+    /// Bytes sent since the previous delta
+    pub bytes_sent: u64,
+    /// Bytes received since the previous delta
+    pub bytes_received: u64,
+    /// Stream errors since the previous delta
+    pub stream_errors: u64,
+}
+
+impl StatsDelta {
+    /// Change from `previous` to `current`, sampled `at` after the start
+    ///
+    /// Counters that went backwards, e.g. after a transport was replaced,
+    /// count as unchanged.
+    #[must_use]
+    pub fn between(
+        previous: &TransportStats,
+        current: &TransportStats,
+        at: Duration,
+        elapsed: Duration,
+    ) -> Self {
+        Self {
+            at,
+            elapsed,
+            packets_sent: current.packets_sent.saturating_sub(previous.packets_sent),
+            packets_received: current
+                .packets_received
+                .saturating_sub(previous.packets_received),
+            bytes_sent: current.bytes_sent.saturating_sub(previous.bytes_sent),
+            bytes_received: current
+                .bytes_received
+                .saturating_sub(previous.bytes_received),
+            stream_errors: current.stream_errors.saturating_sub(previous.stream_errors),
+        }
+    }
+
+    /// Check if no counter changed
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.packets_sent == 0
+            && self.packets_received == 0
+            && self.bytes_sent == 0
+            && self.bytes_received == 0
+            && self.stream_errors == 0
+    }
+}
+
+/// Ring buffer of samples for one call
+#[derive(Debug, Clone)]
+pub struct StatsHistory {
+    capacity: usize,
+    samples: VecDeque<StatsSample>,
+    peer: Option<String>,
+    metadata: CallMetadata,
+}
+
+impl StatsHistory {
+    /// Create an empty history holding at most `capacity` samples
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            samples: VecDeque::new(),
+            peer: None,
+            metadata: CallMetadata::new(),
+        }
+    }
+
+    /// Remote peer of the call, if known
+    #[must_use]
+    pub fn peer(&self) -> Option<&str> {
+        self.peer.as_deref()
+    }
+
+    /// Note the remote peer of the call
+    pub fn set_peer(&mut self, peer: String) {
+        self.peer = Some(peer);
+    }
+
+    /// Metadata the application attached to the call
+    #[must_use]
+    pub fn metadata(&self) -> &CallMetadata {
+        &self.metadata
+    }
+
+    /// Note a metadata value of the call
+    pub fn set_metadata(&mut self, key: String, value: String) {
+        self.metadata.insert(key, value);
+    }
+
+    /// Append a sample, dropping the oldest when full
+    pub fn push(&mut self, sample: StatsSample) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// Samples, oldest first
+    #[must_use]
+    pub fn samples(&self) -> Vec<StatsSample> {
+        self.samples.iter().cloned().collect()
+    }
+
+    /// Drop samples taken before `before`
+    ///
+    /// # Returns
+    ///
+    /// Number of samples dropped.
+    pub fn purge_before(&mut self, before: DateTime<Utc>) -> usize {
+        let held = self.samples.len();
+        self.samples.retain(|sample| sample.timestamp >= before);
+        held - self.samples.len()
+    }
+
+    /// Number of samples held
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Check if no samples are held
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Write the samples as CSV, with a header line
+    ///
+    /// # Errors
+    ///
+    /// Returns error if writing fails
+    pub fn write_csv(&self, out: &mut impl Write) -> Result<(), StatsHistoryError> {
+        writeln!(out, "{CSV_HEADER}")?;
+        for sample in &self.samples {
+            sample.write_csv_row(out)?;
+        }
+        Ok(())
+    }
+
+    /// Serialize the samples as a JSON array
+    ///
+    /// # Errors
+    ///
+    /// Returns error if serialization fails
+    pub fn to_json(&self) -> Result<String, StatsHistoryError> {
+        serde_json::to_string_pretty(&self.samples)
+            .map_err(|e| StatsHistoryError::Serialization(e.to_string()))
+    }
+
+    /// Export the samples to a CSV file
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be written
+    pub fn export_csv(&self, path: &Path) -> Result<(), StatsHistoryError> {
+        let mut out = std::io::BufWriter::new(std::fs::File::create(path)?);
+        self.write_csv(&mut out)?;
+        out.flush()?;
+        Ok(())
+    }
+
+    /// Export the samples to a JSON file
+    ///
+    /// # Errors
+    ///
+    /// Returns error if serialization fails or the file cannot be written
+    pub fn export_json(&self, path: &Path) -> Result<(), StatsHistoryError> {
+        std::fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+}
+
+/// Histories of recent calls
+#[derive(Debug)]
+pub struct StatsHistoryStore {
+    config: StatsHistoryConfig,
+    histories: HashMap<CallId, StatsHistory>,
+    order: VecDeque<CallId>,
+}
+
+impl Default for StatsHistoryStore {
+    fn default() -> Self {
+        Self::new(StatsHistoryConfig::default())
+    }
+}
+
+impl StatsHistoryStore {
+    /// Create an empty store
+    #[must_use]
+    pub fn new(config: StatsHistoryConfig) -> Self {
+        Self {
+            config,
+            histories: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Get the configuration
+    #[must_use]
+    pub fn config(&self) -> &StatsHistoryConfig {
+        &self.config
+    }
+
+    /// Record a sample for a call
+    ///
+    /// Starting a new history evicts the oldest one once
+    /// `retained_calls` histories are held.
+    pub fn record(&mut self, call_id: CallId, sample: StatsSample) {
+        self.history_mut(call_id).push(sample);
+    }
+
+    /// Note the remote peer of a call's history
+    ///
+    /// Starts the history if needed, like [`record`](Self::record).
+    pub fn set_peer(&mut self, call_id: CallId, peer: String) {
+        self.history_mut(call_id).set_peer(peer);
+    }
+
+    /// Note a metadata value of a call's history
+    ///
+    /// Unlike [`set_peer`](Self::set_peer), this does not start a history;
+    /// calls without one are ignored.
+    pub fn set_metadata(&mut self, call_id: CallId, key: String, value: String) {
+        if let Some(history) = self.histories.get_mut(&call_id) {
+            history.set_metadata(key, value);
+        }
+    }
+
+    /// Get a call's history, starting it if needed
+    fn history_mut(&mut self, call_id: CallId) -> &mut StatsHistory {
+        if !self.histories.contains_key(&call_id) {
+            while self.order.len() >= self.config.retained_calls.max(1) {
+                if let Some(oldest) = self.order.pop_front() {
+                    self.histories.remove(&oldest);
+                }
+            }
+            self.order.push_back(call_id);
+        }
+        let capacity = self.config.capacity();
+        self.histories
+            .entry(call_id)
+            .or_insert_with(|| StatsHistory::new(capacity))
+    }
+
+    /// Get a call's history
+    #[must_use]
+    pub fn get(&self, call_id: CallId) -> Option<&StatsHistory> {
+        self.histories.get(&call_id)
+    }
+
+    /// Drop a call's history
+    pub fn remove(&mut self, call_id: CallId) -> Option<StatsHistory> {
+        self.order.retain(|id| *id != call_id);
+        self.histories.remove(&call_id)
+    }
+
+    /// Calls whose history belongs to `peer`
+    #[must_use]
+    pub fn calls_with_peer(&self, peer: &str) -> Vec<CallId> {
+        self.order
+            .iter()
+            .copied()
+            .filter(|id| {
+                self.histories
+                    .get(id)
+                    .is_some_and(|history| history.peer() == Some(peer))
+            })
+            .collect()
+    }
+
+    /// Drop samples taken before `before` from every history
+    ///
+    /// Histories emptied by the purge are removed.
+    ///
+    /// # Returns
+    ///
+    /// Number of samples dropped.
+    pub fn purge_before(&mut self, before: DateTime<Utc>) -> usize {
+        let mut purged = 0;
+        self.histories.retain(|_, history| {
+            let dropped = history.purge_before(before);
+            purged += dropped;
+            dropped == 0 || !history.is_empty()
+        });
+        let histories = &self.histories;
+        self.order.retain(|id| histories.contains_key(id));
+        purged
+    }
+}
+
+/// Storage for call statistics histories
+///
+/// The call manager reads and writes histories through this trait, so
+/// embedders can keep them elsewhere, e.g. in a database. A
+/// [`StatsHistoryStore`] behind a mutex is the in-memory default.
+pub trait HistoryStore: fmt::Debug + Send + Sync {
+    /// Record a sample for a call
+    fn record(&self, call_id: CallId, sample: StatsSample);
+
+    /// Note the remote peer of a call's history
+    fn set_peer(&self, call_id: CallId, peer: String);
+
+    /// Note a metadata value of a call's history, if it has one
+    fn set_metadata(&self, call_id: CallId, key: String, value: String);
+
+    /// Get a copy of a call's history
+    fn get(&self, call_id: CallId) -> Option<StatsHistory>;
+
+    /// Drop a call's history
+    fn remove(&self, call_id: CallId) -> Option<StatsHistory>;
+
+    /// Calls whose history belongs to `peer`
+    fn calls_with_peer(&self, peer: &str) -> Vec<CallId>;
+
+    /// Drop samples taken before `before`, returning how many were dropped
+    fn purge_before(&self, before: DateTime<Utc>) -> usize;
+}
+
+/// A history store shared between subsystems
+pub type SharedHistoryStore = Arc<dyn HistoryStore>;
+
+impl HistoryStore for Mutex<StatsHistoryStore> {
+    fn record(&self, call_id: CallId, sample: StatsSample) {
+        self.lock().record(call_id, sample);
+    }
+
+    fn set_peer(&self, call_id: CallId, peer: String) {
+        self.lock().set_peer(call_id, peer);
+    }
+
+    fn set_metadata(&self, call_id: CallId, key: String, value: String) {
+        self.lock().set_metadata(call_id, key, value);
+    }
+
+    fn get(&self, call_id: CallId) -> Option<StatsHistory> {
+        self.lock().get(call_id).cloned()
+    }
+
+    fn remove(&self, call_id: CallId) -> Option<StatsHistory> {
+        self.lock().remove(call_id)
+    }
+
+    fn calls_with_peer(&self, peer: &str) -> Vec<CallId> {
+        self.lock().calls_with_peer(peer)
+    }
+
+    fn purge_before(&self, before: DateTime<Utc>) -> usize {
+        self.lock().purge_before(before)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn sample(packets_sent: u64) -> StatsSample {
+        StatsSample {
+            packets_sent,
+            ..StatsSample::from_stats(&TransportStats::default())
+        }
+    }
+
+    #[test]
+    fn test_ring_buffer_drops_oldest() {
+        let mut history = StatsHistory::new(3);
+        for i in 0..5 {
+            history.push(sample(i));
+        }
+        let sent: Vec<u64> = history.samples().iter().map(|s| s.packets_sent).collect();
+        assert_eq!(sent, vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn test_capacity_from_config() {
+        assert_eq!(StatsHistoryConfig::default().capacity(), 600);
+        let config = StatsHistoryConfig {
+            interval: Duration::from_millis(500),
+            retention: Duration::from_secs(60),
+            ..Default::default()
+        };
+        assert_eq!(config.capacity(), 120);
+    }
+
+    #[test]
+    fn test_csv_and_json_export() {
+        let mut history = StatsHistory::new(10);
+        history.push(sample(1));
+        history.push(sample(2));
+
+        let mut csv = Vec::new();
+        history.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(lines[2].split(',').nth(1), Some("2"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("stats.json");
+        history.export_json(&path).unwrap();
+        let parsed: Vec<StatsSample> =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(parsed, history.samples());
+    }
+
+    #[test]
+    fn test_delta_between_snapshots() {
+        let previous = TransportStats {
+            packets_sent: 10,
+            bytes_sent: 1_000,
+            packets_received: 5,
+            ..TransportStats::default()
+        };
+        let current = TransportStats {
+            packets_sent: 15,
+            bytes_sent: 1_600,
+            packets_received: 3,
+            ..TransportStats::default()
+        };
+        let second = Duration::from_secs(1);
+        let delta = StatsDelta::between(&previous, &current, 2 * second, second);
+        assert_eq!(delta.at, 2 * second);
+        assert_eq!(delta.packets_sent, 5);
+        assert_eq!(delta.bytes_sent, 600);
+        assert_eq!(delta.packets_received, 0);
+        assert!(!delta.is_empty());
+        assert!(StatsDelta::between(&current, &current, second, second).is_empty());
+    }
+
+    #[test]
+    fn test_store_evicts_oldest_call() {
+        let mut store = StatsHistoryStore::new(StatsHistoryConfig {
+            retained_calls: 2,
+            ..Default::default()
+        });
+        let calls = [CallId::new(), CallId::new(), CallId::new()];
+        for call_id in calls {
+            store.record(call_id, sample(0));
+        }
+        store.record(calls[2], sample(1));
+
+        assert!(store.get(calls[0]).is_none());
+        assert_eq!(store.get(calls[1]).map(StatsHistory::len), Some(1));
+        assert_eq!(store.get(calls[2]).map(StatsHistory::len), Some(2));
+        assert!(store.remove(calls[1]).is_some());
+        assert!(store.get(calls[1]).is_none());
+    }
+
+    #[test]
+    fn test_purge_by_peer_and_age() {
+        let mut store = StatsHistoryStore::default();
+        let (alice, bob) = (CallId::new(), CallId::new());
+        let start = Utc::now();
+        store.set_peer(alice, "alice".to_string());
+        store.set_peer(bob, "bob".to_string());
+        for (call_id, offset) in [(alice, 0), (alice, 10), (bob, 0)] {
+            let sample = StatsSample {
+                timestamp: start + chrono::Duration::seconds(offset),
+                ..sample(0)
+            };
+            store.record(call_id, sample);
+        }
+        assert_eq!(store.calls_with_peer("alice"), vec![alice]);
+
+        assert_eq!(store.purge_before(start + chrono::Duration::seconds(5)), 2);
+        assert_eq!(store.get(alice).unwrap().len(), 1);
+        assert!(store.get(bob).is_none());
+        assert!(store.calls_with_peer("bob").is_empty());
+    }
+}
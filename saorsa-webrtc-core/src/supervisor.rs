@@ -0,0 +1,395 @@
+//! Supervision of background tasks
+//!
+//! The service runs loops in the background, such as the call scheduler and
+//! the performance governor, and applications add their own (signaling
+//! receive, event dispatch). Left alone, a loop that panics or returns is
+//! simply gone and its feature silently stops working. A [`TaskSupervisor`]
+//! runs each loop under a watchdog instead: when the loop ends it is
+//! restarted after a backoff that doubles with each consecutive failure, and
+//! every change is reported as a [`TaskHealth`] to the supervisor's
+//! listener.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+/// Task supervision configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SupervisorConfig {
+    /// Wait before the first restart of a failed task
+    pub initial_backoff: Duration,
+    /// Longest wait between restarts
+    pub max_backoff: Duration,
+    /// A task that ran this long before failing starts over at the initial
+    /// backoff
+    pub stable_after: Duration,
+    /// Consecutive failures after which a task is given up; `None` restarts
+    /// forever
+    pub max_restarts: Option<u32>,
+}
+
+impl Default for SupervisorConfig {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            stable_after: Duration::from_secs(60),
+            max_restarts: None,
+        }
+    }
+}
+
+impl SupervisorConfig {
+    /// Wait before restart number `attempt`, counting from 1
+    #[must_use]
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1u32 << attempt.saturating_sub(1).min(16);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// State of a supervised task
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case", tag = "status")]
+pub enum TaskStatus {
+    /// The task is running
+    Running,
+    /// The task ended and is restarted after a backoff
+    Restarting {
+        /// Consecutive failures so far
+        attempt: u32,
+        /// Milliseconds until the restart
+        retry_in_ms: u64,
+    },
+    /// The task failed too often and was given up
+    Failed,
+}
+
+/// Health of one supervised task
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TaskHealth {
+    /// Task name
+    pub name: String,
+    /// Current state
+    pub status: TaskStatus,
+    /// Restarts since the task was first started
+    pub restarts: u32,
+    /// How the task last ended, e.g. its panic message
+    pub last_failure: Option<String>,
+    /// When the task entered its current state
+    pub since: DateTime<Utc>,
+}
+
+/// Health of every supervised task
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ServiceHealth {
+    /// Supervised tasks, by name
+    pub tasks: Vec<TaskHealth>,
+}
+
+impl ServiceHealth {
+    /// Check if every task is running
+    #[must_use]
+    pub fn is_healthy(&self) -> bool {
+        self.tasks
+            .iter()
+            .all(|task| task.status == TaskStatus::Running)
+    }
+
+    /// Health of one task
+    #[must_use]
+    pub fn task(&self, name: &str) -> Option<&TaskHealth> {
+        self.tasks.iter().find(|task| task.name == name)
+    }
+}
+
+/// Called with a task's health whenever it changes
+pub type HealthListener = Arc<dyn Fn(&TaskHealth) + Send + Sync>;
+
+/// Aborts the task when dropped, so aborting a watchdog ends its task too
+struct AbortOnDrop(JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Shared between the supervisor and its watchdogs
+struct Registry {
+    tasks: parking_lot::Mutex<BTreeMap<String, TaskHealth>>,
+    listener: Option<HealthListener>,
+}
+
+impl Registry {
+    fn update(&self, name: &str, change: impl FnOnce(&mut TaskHealth)) {
+        let health = {
+            let mut tasks = self.tasks.lock();
+            let Some(health) = tasks.get_mut(name) else {
+                return;
+            };
+            change(health);
+            health.since = Utc::now();
+            health.clone()
+        };
+        if let Some(listener) = &self.listener {
+            listener(&health);
+        }
+    }
+}
+
+/// Restarts background tasks that panic or exit
+pub struct TaskSupervisor {
+    config: SupervisorConfig,
+    registry: Arc<Registry>,
+    watchdogs: parking_lot::Mutex<BTreeMap<String, AbortOnDrop>>,
+}
+
+impl Default for TaskSupervisor {
+    fn default() -> Self {
+        Self::new(SupervisorConfig::default())
+    }
+}
+
+impl std::fmt::Debug for TaskSupervisor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TaskSupervisor")
+            .field("config", &self.config)
+            .field("health", &self.health())
+            .finish()
+    }
+}
+
+impl TaskSupervisor {
+    /// Create a supervisor
+    #[must_use]
+    pub fn new(config: SupervisorConfig) -> Self {
+        Self {
+            config,
+            registry: Arc::new(Registry {
+                tasks: parking_lot::Mutex::new(BTreeMap::new()),
+                listener: None,
+            }),
+            watchdogs: parking_lot::Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Report every change of task health to `listener`
+    ///
+    /// Set it before supervising tasks; tasks already running keep
+    /// reporting to the previous listener.
+    #[must_use]
+    pub fn with_listener(mut self, listener: HealthListener) -> Self {
+        let tasks = std::mem::take(&mut *self.registry.tasks.lock());
+        self.registry = Arc::new(Registry {
+            tasks: parking_lot::Mutex::new(tasks),
+            listener: Some(listener),
+        });
+        self
+    }
+
+    /// Run a task under supervision
+    ///
+    /// `task` creates the task's future, once at the start and again on
+    /// every restart. A task already supervised under the same name is
+    /// stopped and replaced. Must be called within a Tokio runtime.
+    pub fn supervise<F, Fut>(&self, name: impl Into<String>, task: F)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let name = name.into();
+        self.registry.tasks.lock().insert(
+            name.clone(),
+            TaskHealth {
+                name: name.clone(),
+                status: TaskStatus::Running,
+                restarts: 0,
+                last_failure: None,
+                since: Utc::now(),
+            },
+        );
+        let watchdog = tokio::spawn(watch(
+            name.clone(),
+            task,
+            self.config.clone(),
+            Arc::clone(&self.registry),
+        ));
+        // Dropping the previous watchdog stops it and its task
+        self.watchdogs.lock().insert(name, AbortOnDrop(watchdog));
+    }
+
+    /// Stop supervising a task, ending it
+    ///
+    /// Returns false if no task has that name.
+    pub fn stop(&self, name: &str) -> bool {
+        self.registry.tasks.lock().remove(name);
+        self.watchdogs.lock().remove(name).is_some()
+    }
+
+    /// Stop every task
+    pub fn stop_all(&self) {
+        self.registry.tasks.lock().clear();
+        self.watchdogs.lock().clear();
+    }
+
+    /// Health of every supervised task
+    #[must_use]
+    pub fn health(&self) -> ServiceHealth {
+        ServiceHealth {
+            tasks: self.registry.tasks.lock().values().cloned().collect(),
+        }
+    }
+}
+
+/// Keep a task running, restarting it with backoff when it ends
+async fn watch<F, Fut>(name: String, task: F, config: SupervisorConfig, registry: Arc<Registry>)
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let mut attempt = 0u32;
+    loop {
+        let started = Instant::now();
+        let mut running = AbortOnDrop(tokio::spawn(task()));
+        let failure = match (&mut running.0).await {
+            Ok(()) => "task exited".to_string(),
+            Err(e) if e.is_panic() => panic_message(e.into_panic()),
+            Err(_) => "task cancelled".to_string(),
+        };
+
+        if started.elapsed() >= config.stable_after {
+            attempt = 0;
+        }
+        attempt += 1;
+        if config.max_restarts.is_some_and(|max| attempt > max) {
+            tracing::error!(task = %name, failure = %failure, "Background task failed, giving up");
+            registry.update(&name, |health| {
+                health.status = TaskStatus::Failed;
+                health.last_failure = Some(failure);
+            });
+            return;
+        }
+
+        let retry_in = config.backoff(attempt);
+        tracing::warn!(
+            task = %name,
+            failure = %failure,
+            attempt,
+            retry_in_ms = retry_in.as_millis() as u64,
+            "Background task ended, restarting"
+        );
+        registry.update(&name, |health| {
+            health.status = TaskStatus::Restarting {
+                attempt,
+                retry_in_ms: retry_in.as_millis() as u64,
+            };
+            health.last_failure = Some(failure);
+        });
+        tokio::time::sleep(retry_in).await;
+        registry.update(&name, |health| {
+            health.status = TaskStatus::Running;
+            health.restarts += 1;
+        });
+    }
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    let message = payload
+        .downcast_ref::<&str>()
+        .map(|s| (*s).to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string());
+    format!("panicked: {message}")
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::panic)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn config() -> SupervisorConfig {
+        SupervisorConfig {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(300),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let config = config();
+        let waits: Vec<u128> = (1..=4).map(|a| config.backoff(a).as_millis()).collect();
+        assert_eq!(waits, vec![100, 200, 300, 300]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_panicked_task_is_restarted() {
+        let changes = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let seen = Arc::clone(&changes);
+        let supervisor = TaskSupervisor::new(config()).with_listener(Arc::new(move |health| {
+            seen.lock().push(health.status.clone());
+        }));
+
+        let runs = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&runs);
+        supervisor.supervise("flaky", move || {
+            let run = counter.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if run < 2 {
+                    panic!("boom {run}");
+                }
+                std::future::pending::<()>().await;
+            }
+        });
+
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+        let health = supervisor.health();
+        assert!(health.is_healthy());
+        let task = health.task("flaky").unwrap();
+        assert_eq!(task.restarts, 2);
+        assert_eq!(task.last_failure.as_deref(), Some("panicked: boom 1"));
+        assert_eq!(
+            changes.lock()[..2],
+            [
+                TaskStatus::Restarting {
+                    attempt: 1,
+                    retry_in_ms: 100
+                },
+                TaskStatus::Running
+            ]
+        );
+
+        assert!(supervisor.stop("flaky"));
+        assert!(supervisor.health().tasks.is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_gives_up_after_max_restarts() {
+        let supervisor = TaskSupervisor::new(SupervisorConfig {
+            max_restarts: Some(1),
+            ..config()
+        });
+        supervisor.supervise("exits", || async {});
+
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        let health = supervisor.health();
+        assert!(!health.is_healthy());
+        let task = health.task("exits").unwrap();
+        assert_eq!(task.status, TaskStatus::Failed);
+        assert_eq!(task.restarts, 1);
+        assert_eq!(task.last_failure.as_deref(), Some("task exited"));
+    }
+}
@@ -0,0 +1,303 @@
+//! Synthetic media sources
+//!
+//! Built-in sources that stand in for a microphone and camera so the CLI
+//! and integration tests can run headless with content that can be checked
+//! at the far end:
+//!
+//! - [`ToneSource`] generates a sine tone of a known frequency.
+//! - [`TestPatternSource`] generates RGB frames of SMPTE-style colour bars
+//!   with a bar sweeping across below them and the frame number encoded in
+//!   a strip along the bottom edge: [`COUNTER_BITS`] equal-width cells,
+//!   most significant bit first, white for 1 and black for 0.
+//!
+//! Both are selectable like devices via [`tone_device`] and
+//! [`test_pattern_device`].
+
+use crate::audio_pipeline::DeviceFormat;
+use crate::media::{AudioDevice, VideoDevice};
+use saorsa_webrtc_codecs::VideoFrame;
+use std::f64::consts::TAU;
+
+/// Device ID of the synthetic tone source
+pub const TONE_DEVICE_ID: &str = "synthetic:tone";
+
+/// Device ID of the synthetic test-pattern source
+pub const TEST_PATTERN_DEVICE_ID: &str = "synthetic:test-pattern";
+
+/// Default tone frequency (A4)
+pub const DEFAULT_TONE_HZ: f32 = 440.0;
+
+/// Default tone amplitude (about -12 dBFS)
+pub const DEFAULT_TONE_AMPLITUDE: i16 = 8_192;
+
+/// Number of cells in the test pattern's frame counter
+pub const COUNTER_BITS: u32 = 32;
+
+/// Smallest test pattern, leaving at least two pixels per counter cell
+pub const MIN_PATTERN_WIDTH: u32 = 2 * COUNTER_BITS;
+
+/// Smallest test pattern height
+pub const MIN_PATTERN_HEIGHT: u32 = 16;
+
+/// 75% colour bars: white, yellow, cyan, green, magenta, red, blue
+const BARS: [[u8; 3]; 7] = [
+    [191, 191, 191],
+    [191, 191, 0],
+    [0, 191, 191],
+    [0, 191, 0],
+    [191, 0, 191],
+    [191, 0, 0],
+    [0, 0, 191],
+];
+
+/// Frames for the moving bar to cross the picture once
+const SWEEP_FRAMES: u64 = 60;
+
+/// Synthetic tone device
+#[must_use]
+pub fn tone_device() -> AudioDevice {
+    AudioDevice {
+        id: TONE_DEVICE_ID.to_string(),
+        name: "Test Tone".to_string(),
+    }
+}
+
+/// Synthetic test-pattern device
+#[must_use]
+pub fn test_pattern_device() -> VideoDevice {
+    VideoDevice {
+        id: TEST_PATTERN_DEVICE_ID.to_string(),
+        name: "Test Pattern".to_string(),
+    }
+}
+
+/// Check if a device ID names a synthetic source
+#[must_use]
+pub fn is_synthetic(device_id: &str) -> bool {
+    device_id == TONE_DEVICE_ID || device_id == TEST_PATTERN_DEVICE_ID
+}
+
+/// Sine tone generator
+#[derive(Debug, Clone)]
+pub struct ToneSource {
+    frequency_hz: f32,
+    amplitude: i16,
+    format: DeviceFormat,
+    position: u64,
+}
+
+impl ToneSource {
+    /// Create a tone at `frequency_hz` in the given device format
+    #[must_use]
+    pub fn new(frequency_hz: f32, format: DeviceFormat) -> Self {
+        Self {
+            frequency_hz,
+            amplitude: DEFAULT_TONE_AMPLITUDE,
+            format,
+            position: 0,
+        }
+    }
+
+    /// Set the peak amplitude
+    #[must_use]
+    pub fn with_amplitude(mut self, amplitude: i16) -> Self {
+        self.amplitude = amplitude;
+        self
+    }
+
+    /// Tone frequency
+    #[must_use]
+    pub fn frequency_hz(&self) -> f32 {
+        self.frequency_hz
+    }
+
+    /// Output format
+    #[must_use]
+    pub fn format(&self) -> DeviceFormat {
+        self.format
+    }
+
+    /// Time of the next frame in milliseconds
+    #[must_use]
+    pub fn timestamp_ms(&self) -> u64 {
+        self.position * 1000 / u64::from(self.format.sample_rate_hz.max(1))
+    }
+
+    /// Generate the next `frames` frames, interleaved if multi-channel
+    ///
+    /// The phase continues from the previous call, so consecutive buffers
+    /// join without clicks.
+    pub fn next_frames(&mut self, frames: usize) -> Vec<i16> {
+        let channels = usize::from(self.format.channels.max(1));
+        let rate = f64::from(self.format.sample_rate_hz.max(1));
+        let step = TAU * f64::from(self.frequency_hz) / rate;
+        let mut samples = Vec::with_capacity(frames * channels);
+        for _ in 0..frames {
+            let phase = step * self.position as f64;
+            let sample = (phase.sin() * f64::from(self.amplitude)).round() as i16;
+            samples.extend(std::iter::repeat_n(sample, channels));
+            self.position += 1;
+        }
+        samples
+    }
+}
+
+/// Moving test-pattern generator
+#[derive(Debug, Clone)]
+pub struct TestPatternSource {
+    width: u32,
+    height: u32,
+    fps: u32,
+    frame: u64,
+}
+
+impl TestPatternSource {
+    /// Create a test pattern of `width`×`height` at `fps` frames per second
+    ///
+    /// Returns `None` if the size is below [`MIN_PATTERN_WIDTH`] ×
+    /// [`MIN_PATTERN_HEIGHT`] or `fps` is zero.
+    #[must_use]
+    pub fn new(width: u32, height: u32, fps: u32) -> Option<Self> {
+        (width >= MIN_PATTERN_WIDTH && height >= MIN_PATTERN_HEIGHT && fps > 0).then_some(Self {
+            width,
+            height,
+            fps,
+            frame: 0,
+        })
+    }
+
+    /// Frame width in pixels
+    #[must_use]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Frame height in pixels
+    #[must_use]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Frames per second
+    #[must_use]
+    pub fn fps(&self) -> u32 {
+        self.fps
+    }
+
+    /// Number of the next frame
+    #[must_use]
+    pub fn frame_number(&self) -> u64 {
+        self.frame
+    }
+
+    /// Generate the next RGB frame
+    pub fn next_frame(&mut self) -> VideoFrame {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let counter_top = counter_top(self.height) as usize;
+        let bars_bottom = counter_top * 3 / 4;
+        let sweep_width = (width / 16).max(1);
+        let sweep_x = (self.frame % SWEEP_FRAMES) as usize * (width - sweep_width)
+            / (SWEEP_FRAMES as usize - 1);
+        // The counter wraps after 2^32 frames
+        let counter = self.frame as u32;
+
+        let mut data = Vec::with_capacity(width * height * 3);
+        for y in 0..height {
+            for x in 0..width {
+                let pixel = if y < bars_bottom {
+                    BARS[x * BARS.len() / width]
+                } else if y < counter_top {
+                    if (sweep_x..sweep_x + sweep_width).contains(&x) {
+                        [255; 3]
+                    } else {
+                        [16; 3]
+                    }
+                } else {
+                    let bit = (x as u32 * COUNTER_BITS / self.width).min(COUNTER_BITS - 1);
+                    if counter >> (COUNTER_BITS - 1 - bit) & 1 == 1 {
+                        [255; 3]
+                    } else {
+                        [0; 3]
+                    }
+                };
+                data.extend_from_slice(&pixel);
+            }
+        }
+
+        let timestamp = self.frame * 1000 / u64::from(self.fps);
+        self.frame += 1;
+        VideoFrame {
+            data,
+            width: self.width,
+            height: self.height,
+            timestamp,
+        }
+    }
+}
+
+/// First row of the frame-counter strip, the bottom eighth of the frame
+pub(crate) fn counter_top(height: u32) -> u32 {
+    height - (height / 8).max(2)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tone_is_continuous_and_interleaved() {
+        let format = DeviceFormat {
+            sample_rate_hz: 8_000,
+            channels: 2,
+        };
+        let mut tone = ToneSource::new(1_000.0, format).with_amplitude(1_000);
+        let first = tone.next_frames(4);
+        // 1 kHz at 8 kHz: 0, sin(45°), sin(90°), sin(135°)
+        assert_eq!(first, vec![0, 0, 707, 707, 1000, 1000, 707, 707]);
+        assert_eq!(tone.timestamp_ms(), 0);
+
+        let next = tone.next_frames(4);
+        assert_eq!(next[0], 0);
+        assert_eq!(next[4], -1000);
+        assert_eq!(tone.timestamp_ms(), 1);
+    }
+
+    #[test]
+    fn test_pattern_layout() {
+        assert!(TestPatternSource::new(32, 32, 30).is_none());
+        assert!(TestPatternSource::new(64, 8, 30).is_none());
+        assert!(TestPatternSource::new(64, 32, 0).is_none());
+
+        let mut pattern = TestPatternSource::new(320, 240, 30).unwrap();
+        let first = pattern.next_frame();
+        assert_eq!(first.data.len(), 320 * 240 * 3);
+        assert_eq!(first.timestamp, 0);
+        let pixel = |frame: &VideoFrame, x: usize, y: usize| {
+            let offset = (y * frame.width as usize + x) * 3;
+            [
+                frame.data[offset],
+                frame.data[offset + 1],
+                frame.data[offset + 2],
+            ]
+        };
+        assert_eq!(pixel(&first, 0, 0), BARS[0]);
+        assert_eq!(pixel(&first, 319, 0), BARS[6]);
+        // Counter of frame 0 is all black
+        assert_eq!(pixel(&first, 319, 239), [0; 3]);
+
+        let second = pattern.next_frame();
+        assert_eq!(second.timestamp, 33);
+        // Lowest bit of the counter set on frame 1
+        assert_eq!(pixel(&second, 319, 239), [255; 3]);
+        assert_eq!(pixel(&second, 300, 239), [0; 3]);
+        assert_ne!(first.data, second.data);
+    }
+
+    #[test]
+    fn test_synthetic_devices() {
+        assert!(is_synthetic(&tone_device().id));
+        assert!(is_synthetic(&test_pattern_device().id));
+        assert!(!is_synthetic("default-audio"));
+    }
+}
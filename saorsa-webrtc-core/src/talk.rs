@@ -0,0 +1,206 @@
+//! Talk-burst statistics and silence suppression accounting
+//!
+//! Most of a conversation is one side listening. [`TalkTracker`] classifies
+//! each captured audio frame as speech or silence by its level, keeps a
+//! short hangover after speech so the tails of words are not clipped, and
+//! accounts for the frames discontinuous transmission (DTX) leaves unsent.
+//! The resulting [`TalkStats`] give apps talk time, silence ratio and the
+//! audio bytes DTX saved, for billing and engagement analytics.
+
+use crate::audio_level::AudioLevel;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Speech detection configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TalkConfig {
+    /// RMS level above which a frame is speech, in dBFS
+    pub speech_threshold_dbfs: f32,
+    /// Time speech is assumed to continue after the level drops, in
+    /// milliseconds
+    pub hangover_ms: u32,
+}
+
+impl Default for TalkConfig {
+    fn default() -> Self {
+        Self {
+            speech_threshold_dbfs: -45.0,
+            hangover_ms: 300,
+        }
+    }
+}
+
+/// Talk statistics of one call's outgoing audio
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TalkStats {
+    /// Time spent talking, hangover included, in milliseconds
+    pub talk_ms: u64,
+    /// Time spent silent, in milliseconds
+    pub silence_ms: u64,
+    /// Number of separate talk bursts
+    pub talk_bursts: u64,
+    /// Audio bytes not sent because DTX suppressed silent frames
+    pub dtx_bytes_saved: u64,
+}
+
+impl TalkStats {
+    /// Fraction of the audio that was silence, in `0.0..=1.0`
+    #[must_use]
+    pub fn silence_ratio(&self) -> f64 {
+        let total = self.talk_ms + self.silence_ms;
+        if total == 0 {
+            return 0.0;
+        }
+        self.silence_ms as f64 / total as f64
+    }
+}
+
+/// Classifies captured audio frames and accumulates [`TalkStats`]
+#[derive(Debug, Clone, Default)]
+pub struct TalkTracker {
+    config: TalkConfig,
+    talk: Duration,
+    silence: Duration,
+    talk_bursts: u64,
+    dtx_bytes_saved: u64,
+    /// Time since the level last crossed the threshold, while talking
+    quiet: Option<Duration>,
+}
+
+impl TalkTracker {
+    /// Create a tracker that has heard nothing yet
+    #[must_use]
+    pub fn new(config: TalkConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    /// Check if the last frame counted as speech
+    #[must_use]
+    pub fn is_talking(&self) -> bool {
+        self.quiet.is_some()
+    }
+
+    /// Record a captured frame of `duration` at `level_dbfs`
+    ///
+    /// `frame_bytes` is what sending the frame costs; with `dtx` on, silent
+    /// frames are counted as saved. Returns whether the frame should be
+    /// sent: always without DTX, and with DTX only while talking.
+    pub fn record(
+        &mut self,
+        duration: Duration,
+        level_dbfs: f32,
+        frame_bytes: u64,
+        dtx: bool,
+    ) -> bool {
+        let hangover = Duration::from_millis(u64::from(self.config.hangover_ms));
+        if level_dbfs >= self.config.speech_threshold_dbfs {
+            if self.quiet.is_none() {
+                self.talk_bursts += 1;
+            }
+            self.quiet = Some(Duration::ZERO);
+        } else if let Some(quiet) = self.quiet {
+            let quiet = quiet + duration;
+            self.quiet = (quiet <= hangover).then_some(quiet);
+        }
+
+        if self.is_talking() {
+            self.talk += duration;
+            return true;
+        }
+        self.silence += duration;
+        if dtx {
+            self.dtx_bytes_saved += frame_bytes;
+        }
+        !dtx
+    }
+
+    /// Record a frame of interleaved 16-bit PCM
+    ///
+    /// The duration follows from the sample count and format, and the cost
+    /// of the frame is taken to be its PCM size. See [`Self::record`].
+    pub fn record_pcm(
+        &mut self,
+        samples: &[i16],
+        sample_rate: u32,
+        channels: u32,
+        dtx: bool,
+    ) -> bool {
+        let frames = samples.len() as u64 / u64::from(channels.max(1));
+        let duration = Duration::from_micros(frames * 1_000_000 / u64::from(sample_rate.max(1)));
+        let bytes = std::mem::size_of_val(samples) as u64;
+        self.record(
+            duration,
+            AudioLevel::of_samples(samples).rms_dbfs(),
+            bytes,
+            dtx,
+        )
+    }
+
+    /// Statistics so far
+    #[must_use]
+    pub fn stats(&self) -> TalkStats {
+        TalkStats {
+            talk_ms: self.talk.as_millis() as u64,
+            silence_ms: self.silence.as_millis() as u64,
+            talk_bursts: self.talk_bursts,
+            dtx_bytes_saved: self.dtx_bytes_saved,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const FRAME: Duration = Duration::from_millis(20);
+
+    #[test]
+    fn test_talk_bursts_with_hangover() {
+        let mut tracker = TalkTracker::new(TalkConfig::default());
+        // 200 ms of speech, 1 s of silence, 100 ms of speech
+        for _ in 0..10 {
+            assert!(tracker.record(FRAME, -20.0, 80, false));
+        }
+        for _ in 0..50 {
+            assert!(tracker.record(FRAME, -60.0, 80, false));
+        }
+        for _ in 0..5 {
+            tracker.record(FRAME, -20.0, 80, false);
+        }
+
+        let stats = tracker.stats();
+        assert_eq!(stats.talk_bursts, 2);
+        // The hangover counts 300 ms of the silence as talk
+        assert_eq!(stats.talk_ms, 200 + 300 + 100);
+        assert_eq!(stats.silence_ms, 700);
+        assert_eq!(stats.dtx_bytes_saved, 0);
+        assert!((stats.silence_ratio() - 0.7 / 1.3).abs() < 1e-9);
+        assert_eq!(TalkStats::default().silence_ratio(), 0.0);
+    }
+
+    #[test]
+    fn test_dtx_suppresses_silence() {
+        let mut tracker = TalkTracker::new(TalkConfig {
+            hangover_ms: 0,
+            ..TalkConfig::default()
+        });
+        let speech = vec![8_000i16; 1_920];
+        let silence = vec![0i16; 1_920];
+
+        assert!(!tracker.record_pcm(&silence, 48_000, 2, true));
+        assert!(tracker.record_pcm(&speech, 48_000, 2, true));
+        assert!(!tracker.record_pcm(&silence, 48_000, 2, true));
+        assert!(tracker.record_pcm(&silence, 48_000, 2, false));
+
+        let stats = tracker.stats();
+        assert_eq!((stats.talk_ms, stats.silence_ms), (20, 60));
+        assert_eq!(stats.dtx_bytes_saved, 2 * 1_920 * 2);
+        assert!(!tracker.is_talking());
+    }
+}
@@ -0,0 +1,1316 @@
+//! Loopback harness for end-to-end call tests
+//!
+//! Runs two [`WebRtcService`] peers in one process, joined by in-memory
+//! signaling ([`MemorySignaling`]) and media links ([`MemoryLink`]), and
+//! drives a complete QUIC-native call between them:
+//!
+//! 1. [`LoopbackHarness::offer`]: the caller places the call and sends a
+//!    capability exchange; the callee registers the incoming call.
+//! 2. [`LoopbackHarness::accept`]: the callee answers with a connection
+//!    confirmation and both sides reach `Connected`.
+//! 3. [`LoopbackHarness::send_media`]: RTP packets are sent by one peer's
+//!    media transport and delivered to the other's, so taps, dumps and
+//!    statistics see them on both sides.
+//! 4. [`LoopbackHarness::hang_up`]: either side ends the call with `Bye`.
+//! 5. [`LoopbackHarness::assert_no_leaks`]: both peers release every call,
+//!    stream, task and buffered packet.
+//!
+//! [`NetworkSimulator`] decides when, or whether, each packet of a call
+//! would arrive over a [`NetworkProfile`] such as 3G or lossy Wi-Fi, for
+//! tests of behaviour under delay and loss.
+//!
+//! Available with the `test-utils` feature so downstream crates can reuse
+//! it in their own tests:
+//!
+//! ```rust,no_run
+//! use saorsa_webrtc_core::testing::{LoopbackHarness, Role};
+//! use saorsa_webrtc_core::MediaConstraints;
+//!
+//! # async fn example() -> Result<(), saorsa_webrtc_core::testing::HarnessError> {
+//! let harness = LoopbackHarness::new().await?;
+//! let call_id = harness.connect_call(MediaConstraints::audio_only()).await?;
+//! harness.hang_up(Role::Caller, call_id).await?;
+//! harness.assert_no_leaks().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::call::CallError;
+use crate::identity::{PeerIdentity, PeerIdentityString};
+use crate::link_transport::{LinkTransport, LinkTransportError, PeerConnection, StreamType};
+use crate::quic_bridge::{RtpPacket, StreamType as RtpStreamType};
+use crate::quic_media_transport::{MediaTransportError, QuicMediaTransport, TrackId};
+use crate::resources::ResourceCounts;
+use crate::service::{ServiceError, WebRtcConfig, WebRtcService};
+use crate::signaling::{SignalingHandler, SignalingMessage, SignalingTransport};
+use crate::types::{CallId, CallOffer, MediaCapabilities, VideoLayer};
+use crate::MediaConstraints;
+use async_trait::async_trait;
+use chrono::Utc;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::{mpsc, Mutex};
+use uuid::Uuid;
+
+/// How long the harness waits for a signaling message or media packet
+pub const DELIVERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Identity of the calling peer
+pub const CALLER_ID: &str = "loopback-caller";
+
+/// Identity of the called peer
+pub const CALLEE_ID: &str = "loopback-callee";
+
+/// Harness errors
+#[derive(Error, Debug)]
+pub enum HarnessError {
+    /// Message addressed to a peer the transport is not joined to
+    #[error("Unknown peer: {0}")]
+    UnknownPeer(String),
+
+    /// The other end of an in-memory transport was dropped
+    #[error("Peer disconnected")]
+    Disconnected,
+
+    /// Nothing arrived within [`DELIVERY_TIMEOUT`]
+    #[error("Timed out waiting for {0}")]
+    Timeout(&'static str),
+
+    /// A different signaling message arrived than the call flow expects
+    #[error("Unexpected signaling message: {0}")]
+    UnexpectedMessage(String),
+
+    /// Service error
+    #[error("Service error: {0}")]
+    Service(#[from] ServiceError),
+
+    /// Call error
+    #[error("Call error: {0}")]
+    Call(#[from] CallError),
+
+    /// Link error
+    #[error("Link error: {0}")]
+    Link(#[from] LinkTransportError),
+
+    /// Media transport error
+    #[error("Media transport error: {0}")]
+    Transport(#[from] MediaTransportError),
+
+    /// Packet could not be serialized
+    #[error("RTP error: {0}")]
+    Rtp(String),
+
+    /// A peer still holds call resources after its calls ended
+    #[error("{peer} leaked resources: {counts:?}")]
+    Leaked {
+        /// Identity of the leaking peer
+        peer: &'static str,
+        /// What it still holds
+        counts: ResourceCounts,
+    },
+}
+
+/// One end of an in-memory signaling channel
+#[derive(Debug)]
+pub struct MemorySignaling {
+    local: String,
+    remote: String,
+    remote_addr: SocketAddr,
+    outgoing: mpsc::UnboundedSender<(String, SignalingMessage)>,
+    incoming: Mutex<mpsc::UnboundedReceiver<(String, SignalingMessage)>>,
+}
+
+impl MemorySignaling {
+    /// Create two joined ends for peers `a` and `b`
+    ///
+    /// Each end reports the other at the address of the matching
+    /// [`MemoryLink::pair`] end.
+    #[must_use]
+    pub fn pair(a: &str, b: &str) -> (Self, Self) {
+        let (a_tx, b_rx) = mpsc::unbounded_channel();
+        let (b_tx, a_rx) = mpsc::unbounded_channel();
+        let (a_addr, b_addr) = link_addrs();
+        (
+            Self {
+                local: a.to_string(),
+                remote: b.to_string(),
+                remote_addr: b_addr,
+                outgoing: a_tx,
+                incoming: Mutex::new(a_rx),
+            },
+            Self {
+                local: b.to_string(),
+                remote: a.to_string(),
+                remote_addr: a_addr,
+                outgoing: b_tx,
+                incoming: Mutex::new(b_rx),
+            },
+        )
+    }
+}
+
+#[async_trait]
+impl SignalingTransport for MemorySignaling {
+    type PeerId = String;
+    type Error = HarnessError;
+
+    async fn send_message(
+        &self,
+        peer: &String,
+        message: SignalingMessage,
+    ) -> Result<(), HarnessError> {
+        if *peer != self.remote {
+            return Err(HarnessError::UnknownPeer(peer.clone()));
+        }
+        self.outgoing
+            .send((self.local.clone(), message))
+            .map_err(|_| HarnessError::Disconnected)
+    }
+
+    async fn receive_message(&self) -> Result<(String, SignalingMessage), HarnessError> {
+        self.incoming
+            .lock()
+            .await
+            .recv()
+            .await
+            .ok_or(HarnessError::Disconnected)
+    }
+
+    async fn discover_peer_endpoint(
+        &self,
+        peer: &String,
+    ) -> Result<Option<SocketAddr>, HarnessError> {
+        Ok((*peer == self.remote).then_some(self.remote_addr))
+    }
+}
+
+/// One end of an in-memory media link
+#[derive(Debug)]
+pub struct MemoryLink {
+    local_addr: SocketAddr,
+    remote: PeerConnection,
+    running: bool,
+    default_peer: Option<PeerConnection>,
+    outgoing: mpsc::UnboundedSender<(StreamType, Vec<u8>)>,
+    incoming: Mutex<mpsc::UnboundedReceiver<(StreamType, Vec<u8>)>>,
+}
+
+impl MemoryLink {
+    /// Create two joined ends for peers `a` and `b`
+    #[must_use]
+    pub fn pair(a: &str, b: &str) -> (Self, Self) {
+        let (a_tx, b_rx) = mpsc::unbounded_channel();
+        let (b_tx, a_rx) = mpsc::unbounded_channel();
+        let (a_addr, b_addr) = link_addrs();
+        let end = |local_addr, remote: &str, remote_addr, outgoing, incoming| Self {
+            local_addr,
+            remote: PeerConnection {
+                peer_id: remote.to_string(),
+                remote_addr,
+            },
+            running: false,
+            default_peer: None,
+            outgoing,
+            incoming: Mutex::new(incoming),
+        };
+        (
+            end(a_addr, b, b_addr, a_tx, a_rx),
+            end(b_addr, a, a_addr, b_tx, b_rx),
+        )
+    }
+
+    /// Address of the other end
+    #[must_use]
+    pub fn remote_addr(&self) -> SocketAddr {
+        self.remote.remote_addr
+    }
+
+    fn check_running(&self) -> Result<(), LinkTransportError> {
+        if self.running {
+            Ok(())
+        } else {
+            Err(LinkTransportError::NotConnected)
+        }
+    }
+}
+
+#[async_trait]
+impl LinkTransport for MemoryLink {
+    async fn start(&mut self) -> Result<(), LinkTransportError> {
+        self.running = true;
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<(), LinkTransportError> {
+        self.running = false;
+        self.default_peer = None;
+        Ok(())
+    }
+
+    async fn is_running(&self) -> bool {
+        self.running
+    }
+
+    async fn local_addr(&self) -> Result<SocketAddr, LinkTransportError> {
+        self.check_running()?;
+        Ok(self.local_addr)
+    }
+
+    async fn connect(&mut self, addr: SocketAddr) -> Result<PeerConnection, LinkTransportError> {
+        self.check_running()?;
+        if addr != self.remote.remote_addr {
+            return Err(LinkTransportError::PeerNotFound(addr.to_string()));
+        }
+        Ok(self.remote.clone())
+    }
+
+    async fn accept(&mut self) -> Result<Option<PeerConnection>, LinkTransportError> {
+        self.check_running()?;
+        Ok(Some(self.remote.clone()))
+    }
+
+    async fn send(
+        &self,
+        peer: &PeerConnection,
+        stream_type: StreamType,
+        data: &[u8],
+    ) -> Result<(), LinkTransportError> {
+        self.check_running()?;
+        if peer.remote_addr != self.remote.remote_addr {
+            return Err(LinkTransportError::PeerNotFound(peer.peer_id.clone()));
+        }
+        self.outgoing
+            .send((stream_type, data.to_vec()))
+            .map_err(|_| LinkTransportError::SendError("link closed".to_string()))
+    }
+
+    async fn receive(&self) -> Result<(PeerConnection, StreamType, Vec<u8>), LinkTransportError> {
+        self.check_running()?;
+        let (stream_type, data) = self
+            .incoming
+            .lock()
+            .await
+            .recv()
+            .await
+            .ok_or_else(|| LinkTransportError::ReceiveError("link closed".to_string()))?;
+        Ok((self.remote.clone(), stream_type, data))
+    }
+
+    fn default_peer(&self) -> Result<PeerConnection, LinkTransportError> {
+        self.default_peer
+            .clone()
+            .ok_or(LinkTransportError::NotConnected)
+    }
+
+    fn set_default_peer(&mut self, peer: PeerConnection) -> Result<(), LinkTransportError> {
+        self.default_peer = Some(peer);
+        Ok(())
+    }
+}
+
+/// Conditions of a simulated network path
+///
+/// Loss follows a two-state model: once a packet is lost, the next one is
+/// lost too with probability `1 - 1 / mean_loss_burst`, so bursts average
+/// `mean_loss_burst` packets while the overall rate stays `loss_percent`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkProfile {
+    /// Name for test output
+    pub name: &'static str,
+    /// One-way propagation delay
+    pub latency: Duration,
+    /// Largest extra delay added at random to each packet
+    pub jitter: Duration,
+    /// Share of packets lost, in percent
+    pub loss_percent: f64,
+    /// Average number of packets lost in a row; 1 for independent losses
+    pub mean_loss_burst: f64,
+    /// Link capacity in kbps; packets queue behind each other beyond it
+    pub bandwidth_kbps: u32,
+}
+
+impl NetworkProfile {
+    /// Wired LAN: sub-millisecond delay, no loss
+    #[must_use]
+    pub fn lan() -> Self {
+        Self {
+            name: "lan",
+            latency: Duration::from_millis(1),
+            jitter: Duration::from_millis(1),
+            loss_percent: 0.0,
+            mean_loss_burst: 1.0,
+            bandwidth_kbps: 100_000,
+        }
+    }
+
+    /// Congested Wi-Fi: little delay but bursty loss and jitter
+    #[must_use]
+    pub fn lossy_wifi() -> Self {
+        Self {
+            name: "lossy-wifi",
+            latency: Duration::from_millis(10),
+            jitter: Duration::from_millis(20),
+            loss_percent: 5.0,
+            mean_loss_burst: 3.0,
+            bandwidth_kbps: 20_000,
+        }
+    }
+
+    /// 3G cellular: long delay, heavy jitter and a narrow link
+    #[must_use]
+    pub fn cellular_3g() -> Self {
+        Self {
+            name: "3g",
+            latency: Duration::from_millis(150),
+            jitter: Duration::from_millis(40),
+            loss_percent: 1.0,
+            mean_loss_burst: 1.5,
+            bandwidth_kbps: 1_500,
+        }
+    }
+}
+
+/// Packet-by-packet simulation of one direction of a [`NetworkProfile`]
+///
+/// Seeded, so a run with the same packets gives the same result.
+#[derive(Debug)]
+pub struct NetworkSimulator {
+    profile: NetworkProfile,
+    rng: StdRng,
+    in_burst: bool,
+    link_free_at: Duration,
+}
+
+impl NetworkSimulator {
+    /// Simulate `profile` with a random seed
+    #[must_use]
+    pub fn new(profile: NetworkProfile, seed: u64) -> Self {
+        Self {
+            profile,
+            rng: StdRng::seed_from_u64(seed),
+            in_burst: false,
+            link_free_at: Duration::ZERO,
+        }
+    }
+
+    /// The simulated conditions
+    #[must_use]
+    pub fn profile(&self) -> &NetworkProfile {
+        &self.profile
+    }
+
+    /// Send a packet of `bytes` at `sent_at`
+    ///
+    /// Returns when it arrives, or `None` if it is lost. Packets may arrive
+    /// out of order when jitter exceeds their spacing.
+    pub fn transmit(&mut self, sent_at: Duration, bytes: usize) -> Option<Duration> {
+        let loss = (self.profile.loss_percent / 100.0).clamp(0.0, 1.0);
+        let leave_burst = 1.0 / self.profile.mean_loss_burst.max(1.0);
+        let enter_burst = if loss < 1.0 {
+            (loss * leave_burst / (1.0 - loss)).min(1.0)
+        } else {
+            1.0
+        };
+        let roll: f64 = self.rng.gen();
+        self.in_burst = if self.in_burst {
+            roll >= leave_burst
+        } else {
+            roll < enter_burst
+        };
+
+        // A lost packet still took its turn on the link
+        let serialization = Duration::from_secs_f64(
+            bytes as f64 * 8.0 / (f64::from(self.profile.bandwidth_kbps.max(1)) * 1000.0),
+        );
+        let departs = sent_at.max(self.link_free_at) + serialization;
+        self.link_free_at = departs;
+        if self.in_burst {
+            return None;
+        }
+
+        let jitter = self.profile.jitter.mul_f64(self.rng.gen::<f64>());
+        Some(departs + self.profile.latency + jitter)
+    }
+}
+
+/// Which side of the harness call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// The peer that places calls
+    Caller,
+    /// The peer that receives calls
+    Callee,
+}
+
+/// One in-process peer
+pub struct LoopbackPeer {
+    identity: PeerIdentityString,
+    service: Arc<WebRtcService<PeerIdentityString, MemorySignaling>>,
+    signaling: Arc<SignalingHandler<MemorySignaling>>,
+    link: Mutex<MemoryLink>,
+}
+
+impl LoopbackPeer {
+    async fn start(
+        identity: &str,
+        signaling: MemorySignaling,
+        mut link: MemoryLink,
+        config: WebRtcConfig,
+    ) -> Result<Self, HarnessError> {
+        let signaling = Arc::new(SignalingHandler::new(Arc::new(signaling)));
+        let service = WebRtcService::builder(Arc::clone(&signaling))
+            .with_config(config)
+            .build()
+            .await?;
+        service.start().await?;
+        link.start().await?;
+        Ok(Self {
+            identity: PeerIdentityString::new(identity),
+            service: Arc::new(service),
+            signaling,
+            link: Mutex::new(link),
+        })
+    }
+
+    /// Identity of this peer
+    #[must_use]
+    pub fn identity(&self) -> &PeerIdentityString {
+        &self.identity
+    }
+
+    /// The peer's service
+    #[must_use]
+    pub fn service(&self) -> &Arc<WebRtcService<PeerIdentityString, MemorySignaling>> {
+        &self.service
+    }
+
+    /// Media transport of one of this peer's calls
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the call does not exist
+    pub async fn media_transport(
+        &self,
+        call_id: CallId,
+    ) -> Result<Arc<QuicMediaTransport>, HarnessError> {
+        self.service
+            .call_manager()
+            .media_transport(call_id)
+            .await
+            .ok_or_else(|| CallError::CallNotFound(call_id.to_string()).into())
+    }
+
+    async fn send(&self, to: &LoopbackPeer, message: SignalingMessage) -> Result<(), HarnessError> {
+        self.signaling
+            .send_message(&to.identity.to_string_repr(), message)
+            .await
+    }
+
+    async fn next_message(&self) -> Result<(String, SignalingMessage), HarnessError> {
+        tokio::time::timeout(DELIVERY_TIMEOUT, self.signaling.receive_message())
+            .await
+            .map_err(|_| HarnessError::Timeout("signaling message"))?
+    }
+
+    /// Connect the media transport of a call over the link
+    async fn connect_media(&self, call_id: CallId, incoming: bool) -> Result<(), HarnessError> {
+        let peer = {
+            let mut link = self.link.lock().await;
+            if incoming {
+                link.accept().await?.ok_or(HarnessError::Disconnected)?
+            } else {
+                let addr = link.remote_addr();
+                link.connect(addr).await?
+            }
+        };
+        self.service
+            .call_manager()
+            .connect_quic_transport(call_id, peer)
+            .await?;
+        Ok(())
+    }
+
+    /// Release the link connection of a call that ended
+    async fn disconnect_media(&self) -> Result<(), HarnessError> {
+        let mut link = self.link.lock().await;
+        let peer = link.remote.clone();
+        link.disconnect(&peer).await?;
+        Ok(())
+    }
+}
+
+/// Two in-process peers with a call flow driver
+pub struct LoopbackHarness {
+    caller: LoopbackPeer,
+    callee: LoopbackPeer,
+}
+
+impl LoopbackHarness {
+    /// Start two peers with default configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns error if either service fails to start
+    pub async fn new() -> Result<Self, HarnessError> {
+        Self::with_config(WebRtcConfig::default(), WebRtcConfig::default()).await
+    }
+
+    /// Start two peers with the given configurations
+    ///
+    /// # Errors
+    ///
+    /// Returns error if either service fails to start
+    pub async fn with_config(
+        caller_config: WebRtcConfig,
+        callee_config: WebRtcConfig,
+    ) -> Result<Self, HarnessError> {
+        let (caller_signaling, callee_signaling) = MemorySignaling::pair(CALLER_ID, CALLEE_ID);
+        let (caller_link, callee_link) = MemoryLink::pair(CALLER_ID, CALLEE_ID);
+        Ok(Self {
+            caller: LoopbackPeer::start(CALLER_ID, caller_signaling, caller_link, caller_config)
+                .await?,
+            callee: LoopbackPeer::start(CALLEE_ID, callee_signaling, callee_link, callee_config)
+                .await?,
+        })
+    }
+
+    /// The calling peer
+    #[must_use]
+    pub fn caller(&self) -> &LoopbackPeer {
+        &self.caller
+    }
+
+    /// The called peer
+    #[must_use]
+    pub fn callee(&self) -> &LoopbackPeer {
+        &self.callee
+    }
+
+    /// Get a peer by role
+    #[must_use]
+    pub fn peer(&self, role: Role) -> &LoopbackPeer {
+        match role {
+            Role::Caller => &self.caller,
+            Role::Callee => &self.callee,
+        }
+    }
+
+    /// Place a call and deliver the offer, leaving the callee ringing
+    ///
+    /// # Errors
+    ///
+    /// Returns error if either side rejects a step of the call setup
+    pub async fn offer(&self, constraints: MediaConstraints) -> Result<CallId, HarnessError> {
+        let (caller, callee) = (&self.caller, &self.callee);
+        let call_id = caller
+            .service
+            .initiate_call(callee.identity.clone(), constraints)
+            .await?;
+        caller.connect_media(call_id, false).await?;
+        let caps = caller
+            .service
+            .call_manager()
+            .exchange_capabilities(call_id)
+            .await?;
+        caller
+            .send(
+                callee,
+                SignalingMessage::CapabilityExchange {
+                    session_id: call_id.to_string(),
+                    audio: caps.audio,
+                    video: caps.video,
+                    data_channel: caps.data_channel,
+                    max_bandwidth_kbps: caps.max_bandwidth_kbps,
+                    quic_endpoint: None,
+                },
+            )
+            .await?;
+
+        let (from, message) = callee.next_message().await?;
+        let SignalingMessage::CapabilityExchange {
+            session_id,
+            audio,
+            video,
+            ..
+        } = message
+        else {
+            return Err(unexpected(&message));
+        };
+        let offered = MediaConstraints {
+            audio,
+            video,
+            screen_share: false,
+            latency: None,
+        };
+        callee
+            .service
+            .handle_incoming_call(CallOffer {
+                call_id: parse_call_id(&session_id)?,
+                caller: PeerIdentityString::new(from),
+                callee: callee.identity.clone(),
+                sdp: String::new(),
+                media_types: offered.to_media_types(),
+                timestamp: Utc::now(),
+            })
+            .await?;
+        Ok(call_id)
+    }
+
+    /// Answer a ringing call and confirm it on both sides
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the call is not ringing or the caller rejects the
+    /// callee's capabilities
+    pub async fn accept(&self, call_id: CallId) -> Result<(), HarnessError> {
+        let (caller, callee) = (&self.caller, &self.callee);
+        let manager = callee.service.call_manager();
+        let (_, constraints, _) = manager
+            .get_call_info(call_id)
+            .await
+            .ok_or_else(|| CallError::CallNotFound(call_id.to_string()))?;
+        callee.connect_media(call_id, true).await?;
+        let caps = manager.exchange_capabilities(call_id).await?;
+        callee.service.accept_call(call_id, constraints).await?;
+        callee
+            .send(
+                caller,
+                SignalingMessage::ConnectionConfirm {
+                    session_id: call_id.to_string(),
+                    audio: caps.audio,
+                    video: caps.video,
+                    data_channel: caps.data_channel,
+                    max_bandwidth_kbps: caps.max_bandwidth_kbps,
+                    quic_endpoint: None,
+                },
+            )
+            .await?;
+
+        let (_, message) = caller.next_message().await?;
+        let SignalingMessage::ConnectionConfirm {
+            audio,
+            video,
+            data_channel,
+            max_bandwidth_kbps,
+            ..
+        } = message
+        else {
+            return Err(unexpected(&message));
+        };
+        caller
+            .service
+            .call_manager()
+            .confirm_connection(
+                call_id,
+                MediaCapabilities {
+                    audio,
+                    video,
+                    data_channel,
+                    max_bandwidth_kbps,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        caller
+            .send(
+                callee,
+                SignalingMessage::ConnectionReady {
+                    session_id: call_id.to_string(),
+                },
+            )
+            .await?;
+
+        match callee.next_message().await? {
+            (_, SignalingMessage::ConnectionReady { .. }) => Ok(()),
+            (_, message) => Err(unexpected(&message)),
+        }
+    }
+
+    /// Place a call and have the callee accept it
+    ///
+    /// # Errors
+    ///
+    /// Returns error if any step of the call setup fails
+    pub async fn connect_call(
+        &self,
+        constraints: MediaConstraints,
+    ) -> Result<CallId, HarnessError> {
+        let call_id = self.offer(constraints).await?;
+        self.accept(call_id).await?;
+        Ok(call_id)
+    }
+
+    /// Decline a ringing call; both sides drop it
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the call is not ringing
+    pub async fn reject(&self, call_id: CallId) -> Result<(), HarnessError> {
+        self.callee.service.reject_call(call_id).await?;
+        self.bye(Role::Callee, call_id, Some("rejected")).await
+    }
+
+    /// End a call from one side; the other side ends it on `Bye`
+    ///
+    /// # Errors
+    ///
+    /// Returns error if either side does not have the call
+    pub async fn hang_up(&self, by: Role, call_id: CallId) -> Result<(), HarnessError> {
+        self.bye(by, call_id, None).await
+    }
+
+    /// Announce one side's described tracks to the other side
+    ///
+    /// The sender's [`CallManager::track_update`] is carried over signaling
+    /// and handed to the receiver's [`CallManager::handle_track_update`].
+    ///
+    /// [`CallManager::track_update`]: crate::call::CallManager::track_update
+    /// [`CallManager::handle_track_update`]: crate::call::CallManager::handle_track_update
+    ///
+    /// # Errors
+    ///
+    /// Returns error if either side does not have the call
+    pub async fn announce_tracks(&self, by: Role, call_id: CallId) -> Result<(), HarnessError> {
+        let (from, to) = self.ends(by);
+        let update = from.service.call_manager().track_update(call_id).await?;
+        from.send(to, update).await?;
+
+        match to.next_message().await? {
+            (_, SignalingMessage::TrackUpdate { session_id, tracks }) => {
+                to.service
+                    .call_manager()
+                    .handle_track_update(parse_call_id(&session_id)?, tracks)
+                    .await?;
+                Ok(())
+            }
+            (_, message) => Err(unexpected(&message)),
+        }
+    }
+
+    /// Have one side choose the layers it receives of a video track of the
+    /// other side
+    ///
+    /// The chooser's [`CallManager::select_layer`] is carried over signaling
+    /// and handed to the sender's [`CallManager::handle_layer_selection`].
+    ///
+    /// [`CallManager::select_layer`]: crate::call::CallManager::select_layer
+    /// [`CallManager::handle_layer_selection`]: crate::call::CallManager::handle_layer_selection
+    ///
+    /// # Errors
+    ///
+    /// Returns error if either side does not have the call or the track
+    pub async fn select_layer(
+        &self,
+        by: Role,
+        call_id: CallId,
+        track_id: TrackId,
+        layer: VideoLayer,
+    ) -> Result<(), HarnessError> {
+        let (from, to) = self.ends(by);
+        let selection = from
+            .service
+            .call_manager()
+            .select_layer(call_id, track_id, layer)
+            .await?;
+        from.send(to, selection).await?;
+
+        match to.next_message().await? {
+            (
+                _,
+                SignalingMessage::LayerSelection {
+                    session_id,
+                    track_id,
+                    layer,
+                },
+            ) => {
+                to.service
+                    .call_manager()
+                    .handle_layer_selection(parse_call_id(&session_id)?, track_id, layer)
+                    .await?;
+                Ok(())
+            }
+            (_, message) => Err(unexpected(&message)),
+        }
+    }
+
+    async fn bye(
+        &self,
+        by: Role,
+        call_id: CallId,
+        reason: Option<&str>,
+    ) -> Result<(), HarnessError> {
+        let (from, to) = self.ends(by);
+        from.service.end_call(call_id).await?;
+        from.disconnect_media().await?;
+        from.send(
+            to,
+            SignalingMessage::Bye {
+                session_id: call_id.to_string(),
+                reason: reason.map(str::to_string),
+            },
+        )
+        .await?;
+
+        match to.next_message().await? {
+            (_, SignalingMessage::Bye { session_id, .. }) => {
+                to.service.end_call(parse_call_id(&session_id)?).await?;
+                to.disconnect_media().await
+            }
+            (_, message) => Err(unexpected(&message)),
+        }
+    }
+
+    /// Send an RTP packet from one side and deliver it to the other
+    ///
+    /// The packet goes through the sender's media transport, across the
+    /// link, and is handed to the receiver's media transport, so both
+    /// sides count it and any taps or dumps on the call see it.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the sender's transport refuses the packet (e.g. the
+    /// call is not connected) or it does not arrive
+    pub async fn send_media(
+        &self,
+        from: Role,
+        call_id: CallId,
+        packet: &RtpPacket,
+    ) -> Result<(), HarnessError> {
+        let (sender, receiver) = self.ends(from);
+        let bytes = packet
+            .to_bytes()
+            .map_err(|e| HarnessError::Rtp(e.to_string()))?;
+        let stream_type = link_stream_type(packet.stream_type);
+
+        sender
+            .media_transport(call_id)
+            .await?
+            .send_rtp(stream_type, &bytes)
+            .await?;
+        {
+            let link = sender.link.lock().await;
+            let peer = link.remote.clone();
+            link.send(&peer, stream_type, &bytes).await?;
+        }
+
+        let received = {
+            let link = receiver.link.lock().await;
+            tokio::time::timeout(DELIVERY_TIMEOUT, link.receive())
+                .await
+                .map_err(|_| HarnessError::Timeout("media packet"))??
+        };
+        let (_, stream_type, data) = received;
+        receiver
+            .media_transport(call_id)
+            .await?
+            .deliver_rtp(stream_type, &data)
+            .await;
+        Ok(())
+    }
+
+    /// Check that both peers released everything their calls held
+    ///
+    /// Background tasks only notice an ended call on their next tick, so
+    /// this waits up to [`DELIVERY_TIMEOUT`] for the counts to reach zero.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HarnessError::Leaked`] with the first peer still holding
+    /// resources
+    pub async fn assert_no_leaks(&self) -> Result<(), HarnessError> {
+        let deadline = tokio::time::Instant::now() + DELIVERY_TIMEOUT;
+        loop {
+            let leak = [(CALLER_ID, &self.caller), (CALLEE_ID, &self.callee)]
+                .into_iter()
+                .map(|(peer, end)| (peer, end.service.resource_counts()))
+                .find(|(_, counts)| !counts.is_idle());
+            match leak {
+                None => return Ok(()),
+                Some((peer, counts)) if tokio::time::Instant::now() >= deadline => {
+                    return Err(HarnessError::Leaked { peer, counts });
+                }
+                Some(_) => tokio::time::sleep(Duration::from_millis(10)).await,
+            }
+        }
+    }
+
+    fn ends(&self, from: Role) -> (&LoopbackPeer, &LoopbackPeer) {
+        match from {
+            Role::Caller => (&self.caller, &self.callee),
+            Role::Callee => (&self.callee, &self.caller),
+        }
+    }
+}
+
+/// Addresses of the two ends of a pair, from the documentation range
+fn link_addrs() -> (SocketAddr, SocketAddr) {
+    (
+        SocketAddr::from(([192, 0, 2, 1], 5000)),
+        SocketAddr::from(([192, 0, 2, 2], 5000)),
+    )
+}
+
+fn link_stream_type(stream_type: RtpStreamType) -> StreamType {
+    match stream_type {
+        RtpStreamType::Audio => StreamType::Audio,
+        RtpStreamType::Video => StreamType::Video,
+        RtpStreamType::ScreenShare => StreamType::Screen,
+        RtpStreamType::RtcpFeedback => StreamType::RtcpFeedback,
+        RtpStreamType::Data => StreamType::Data,
+    }
+}
+
+fn parse_call_id(session_id: &str) -> Result<CallId, HarnessError> {
+    Uuid::parse_str(session_id)
+        .map(CallId)
+        .map_err(|_| HarnessError::UnexpectedMessage(format!("session ID {session_id}")))
+}
+
+fn unexpected(message: &SignalingMessage) -> HarnessError {
+    HarnessError::UnexpectedMessage(format!("{message:?}"))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::audio_pipeline::{AudioPipeline, DeviceFormat};
+    use crate::quic_media_transport::StreamKey;
+    use crate::synthetic::ToneSource;
+    use crate::types::{AudioParameters, CallEvent, CallState, MediaType, TrackInfo, TrackSource};
+    use crate::verify::ToneDetector;
+
+    #[tokio::test]
+    async fn test_full_loopback_call() {
+        let harness = LoopbackHarness::new().await.unwrap();
+        let call_id = harness.offer(MediaConstraints::audio_only()).await.unwrap();
+        assert_eq!(
+            harness.callee().service().get_call_state(call_id).await,
+            Some(CallState::Calling)
+        );
+
+        harness.accept(call_id).await.unwrap();
+        for role in [Role::Caller, Role::Callee] {
+            assert_eq!(
+                harness.peer(role).service().get_call_state(call_id).await,
+                Some(CallState::Connected)
+            );
+        }
+
+        // A tone sent by the caller is heard by the callee
+        let mut tap = harness
+            .callee()
+            .service()
+            .tap_media(call_id, RtpStreamType::Audio)
+            .await
+            .unwrap();
+        let device = DeviceFormat::default();
+        let mut tone = ToneSource::new(440.0, device);
+        let mut encoder = AudioPipeline::new(AudioParameters::default(), device).unwrap();
+        let mut decoder = AudioPipeline::new(AudioParameters::default(), device).unwrap();
+        let mut heard = Vec::new();
+        for seq in 0..20u16 {
+            let timestamp = tone.timestamp_ms();
+            let payload = encoder.encode(&tone.next_frames(480), timestamp).unwrap();
+            let packet = RtpPacket::new(
+                111,
+                seq,
+                u32::from(seq) * 480,
+                1,
+                payload.to_vec(),
+                RtpStreamType::Audio,
+            )
+            .unwrap();
+            harness
+                .send_media(Role::Caller, call_id, &packet)
+                .await
+                .unwrap();
+            let received = tap.recv().await.unwrap();
+            assert_eq!(received.sequence_number, seq);
+            heard.extend(decoder.decode(&received.payload).unwrap());
+        }
+        let detector = ToneDetector::new(device.sample_rate_hz, device.channels);
+        assert!(detector.matches(&heard, 440.0, 2.0));
+        let stats = harness
+            .callee()
+            .service()
+            .get_call_stats(call_id)
+            .await
+            .unwrap();
+        assert_eq!(stats.packets_received, 20);
+
+        harness.hang_up(Role::Callee, call_id).await.unwrap();
+        for role in [Role::Caller, Role::Callee] {
+            assert_eq!(
+                harness.peer(role).service().get_call_state(call_id).await,
+                None
+            );
+        }
+        drop(tap);
+        harness.assert_no_leaks().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_track_metadata_reaches_peer() {
+        let harness = LoopbackHarness::new().await.unwrap();
+        let call_id = harness
+            .connect_call(MediaConstraints::video_call())
+            .await
+            .unwrap();
+        let caller = harness.caller().service().call_manager();
+        let callee = harness.callee().service().call_manager();
+        let mut events = callee.subscribe_events();
+
+        let camera = TrackInfo::new(0, MediaType::Video, TrackSource::Camera, "Front camera");
+        let slides = TrackInfo::new(1, MediaType::Video, TrackSource::Slides, "Roadmap");
+        caller
+            .describe_track(call_id, camera.clone())
+            .await
+            .unwrap();
+        caller
+            .describe_track(call_id, slides.clone())
+            .await
+            .unwrap();
+        harness
+            .announce_tracks(Role::Caller, call_id)
+            .await
+            .unwrap();
+
+        let announced = vec![camera.clone(), slides.clone()];
+        assert!(matches!(
+            events.recv().await.unwrap(),
+            CallEvent::RemoteTrackMetadata { call_id: id, tracks } if id == call_id && tracks == announced
+        ));
+        let slides_key = StreamKey::new(StreamType::Video, 1);
+        assert_eq!(callee.remote_track(call_id, slides_key).await, Some(slides));
+
+        // A later update replaces the earlier one
+        assert!(caller.withdraw_track(call_id, slides_key).await.unwrap());
+        harness
+            .announce_tracks(Role::Caller, call_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            callee.remote_track_info(call_id).await.unwrap(),
+            vec![camera]
+        );
+        assert_eq!(callee.remote_track(call_id, slides_key).await, None);
+
+        harness.hang_up(Role::Caller, call_id).await.unwrap();
+        harness.assert_no_leaks().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_layer_selection_reaches_sender() {
+        let harness = LoopbackHarness::new().await.unwrap();
+        let call_id = harness
+            .connect_call(MediaConstraints::video_call())
+            .await
+            .unwrap();
+        let caller = harness.caller().service().call_manager();
+        let mut events = caller.subscribe_events();
+
+        let camera = TrackInfo::new(0, MediaType::Video, TrackSource::Camera, "Camera");
+        caller.describe_track(call_id, camera).await.unwrap();
+        // The callee can only choose layers of tracks it has been told of
+        assert!(harness
+            .select_layer(Role::Callee, call_id, 0, VideoLayer::BASE)
+            .await
+            .is_err());
+        harness
+            .announce_tracks(Role::Caller, call_id)
+            .await
+            .unwrap();
+
+        let layer = VideoLayer::new(1, 2);
+        harness
+            .select_layer(Role::Callee, call_id, 0, layer)
+            .await
+            .unwrap();
+        assert!(matches!(
+            events.recv().await.unwrap(),
+            CallEvent::LayerSelected { call_id: id, track_id: 0, layer: chosen }
+                if id == call_id && chosen == layer
+        ));
+        assert!(harness
+            .select_layer(Role::Callee, call_id, 1, layer)
+            .await
+            .is_err());
+
+        harness.hang_up(Role::Caller, call_id).await.unwrap();
+        harness.assert_no_leaks().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_remote_tracks_have_own_receivers() {
+        let harness = LoopbackHarness::new().await.unwrap();
+        let call_id = harness
+            .connect_call(MediaConstraints::video_call())
+            .await
+            .unwrap();
+        let caller = harness.caller().service().call_manager();
+        let callee = harness.callee().service().call_manager();
+        let mut events = callee.subscribe_events();
+
+        let camera = TrackInfo::new(0, MediaType::Video, TrackSource::Camera, "Camera");
+        let slides = TrackInfo::new(1, MediaType::Video, TrackSource::Slides, "Slides");
+        caller
+            .describe_track(call_id, camera.clone())
+            .await
+            .unwrap();
+        caller
+            .describe_track(call_id, slides.clone())
+            .await
+            .unwrap();
+        harness
+            .announce_tracks(Role::Caller, call_id)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            events.recv().await.unwrap(),
+            CallEvent::RemoteTrackMetadata { .. }
+        ));
+        for expected in [&camera, &slides] {
+            assert!(matches!(
+                events.recv().await.unwrap(),
+                CallEvent::RemoteTrackAdded { track, .. } if &track == expected
+            ));
+        }
+
+        let mut tracks = callee.remote_tracks(call_id).await.unwrap();
+        assert_eq!(tracks.len(), 2);
+        let mut slides_track = tracks.pop().unwrap();
+        let mut camera_track = tracks.pop().unwrap();
+        assert_eq!(slides_track.info, slides);
+
+        // A packet on the slides track reaches only the slides receiver
+        let packet = RtpPacket::new(96, 7, 0, 1, vec![0; 10], RtpStreamType::Video).unwrap();
+        harness
+            .callee()
+            .media_transport(call_id)
+            .await
+            .unwrap()
+            .deliver_track_rtp(slides.stream_key(), &packet.to_bytes().unwrap())
+            .await;
+        assert_eq!(
+            slides_track.packets.recv().await.unwrap().sequence_number,
+            7
+        );
+        assert!(camera_track.packets.try_recv().is_err());
+
+        // Withdrawn tracks are removed and their receivers end
+        caller
+            .withdraw_track(call_id, slides.stream_key())
+            .await
+            .unwrap();
+        harness
+            .announce_tracks(Role::Caller, call_id)
+            .await
+            .unwrap();
+        assert!(matches!(
+            events.recv().await.unwrap(),
+            CallEvent::RemoteTrackMetadata { .. }
+        ));
+        assert!(matches!(
+            events.recv().await.unwrap(),
+            CallEvent::RemoteTrackRemoved { track, .. } if track == slides
+        ));
+        assert!(slides_track.packets.recv().await.is_none());
+
+        harness.hang_up(Role::Caller, call_id).await.unwrap();
+        assert!(camera_track.packets.recv().await.is_none());
+        harness.assert_no_leaks().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rejected_and_unaccepted_calls() {
+        let harness = LoopbackHarness::new().await.unwrap();
+        let call_id = harness.offer(MediaConstraints::video_call()).await.unwrap();
+
+        // Media is gated until the call is accepted
+        let packet = RtpPacket::new(96, 0, 0, 1, vec![0; 10], RtpStreamType::Video).unwrap();
+        assert!(matches!(
+            harness.send_media(Role::Caller, call_id, &packet).await,
+            Err(HarnessError::Transport(MediaTransportError::MediaGated(_)))
+        ));
+
+        harness.reject(call_id).await.unwrap();
+        assert_eq!(
+            harness.caller().service().get_call_state(call_id).await,
+            None
+        );
+        assert!(harness.accept(call_id).await.is_err());
+        harness.assert_no_leaks().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_leaked_call_is_reported() {
+        let harness = LoopbackHarness::new().await.unwrap();
+        let call_id = harness
+            .connect_call(MediaConstraints::audio_only())
+            .await
+            .unwrap();
+        harness
+            .caller()
+            .service()
+            .start_stats_history(call_id)
+            .await
+            .unwrap();
+        let counts = harness.caller().service().resource_counts();
+        assert_eq!((counts.calls, counts.tasks), (1, 1));
+
+        // Only the callee ends the call
+        harness.callee().service().end_call(call_id).await.unwrap();
+        assert!(matches!(
+            harness.assert_no_leaks().await,
+            Err(HarnessError::Leaked {
+                peer: CALLER_ID,
+                counts: ResourceCounts { calls: 1, .. }
+            })
+        ));
+
+        harness.caller().service().end_call(call_id).await.unwrap();
+        harness.assert_no_leaks().await.unwrap();
+    }
+
+    #[test]
+    fn test_network_simulator_loss_and_delay() {
+        let send = |profile: NetworkProfile| {
+            let mut sim = NetworkSimulator::new(profile, 7);
+            (0..10_000u64)
+                .map(|i| sim.transmit(Duration::from_millis(i * 20), 100))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            send(NetworkProfile::lossy_wifi()),
+            send(NetworkProfile::lossy_wifi())
+        );
+
+        let arrivals = send(NetworkProfile::lossy_wifi());
+        let lost = arrivals.iter().filter(|arrival| arrival.is_none()).count();
+        assert!((400..600).contains(&lost), "lost {lost}");
+        // Losses come in bursts
+        let bursts = arrivals
+            .windows(2)
+            .filter(|pair| pair[0].is_some() && pair[1].is_none())
+            .count();
+        assert!(bursts < lost / 2, "{bursts} bursts for {lost} losses");
+
+        assert!(send(NetworkProfile::lan()).iter().all(Option::is_some));
+
+        // A burst larger than the link queues up
+        let mut sim = NetworkSimulator::new(NetworkProfile::cellular_3g(), 1);
+        let first = sim.transmit(Duration::ZERO, 1_500).unwrap();
+        let last = (0..9)
+            .filter_map(|_| sim.transmit(Duration::ZERO, 1_500))
+            .last()
+            .unwrap();
+        assert!(first >= Duration::from_millis(150));
+        assert!(last >= Duration::from_millis(150 + 80));
+    }
+}
@@ -0,0 +1,320 @@
+//! Timed metadata synchronized with video
+//!
+//! Captions, drawing annotations on a screen share and similar data only
+//! make sense next to the frame they belong to. [`TimedMetadata`] carries
+//! the RTP timestamp of that frame and is framed like media: an
+//! [`RtpPacket`] on its own data stream ([`metadata_key`]), so it shares
+//! the media path, taps and captures.
+//!
+//! On arrival, metadata waits in a [`MetadataBuffer`] alongside the video
+//! jitter buffer. As each frame is rendered, [`MetadataBuffer::release`]
+//! hands over the metadata up to and including that frame's timestamp, so
+//! the application shows it with the matching frame rather than when it
+//! happened to arrive.
+
+use crate::link_transport::StreamType;
+use crate::quic_bridge::{RtpPacket, StreamType as RtpStreamType};
+use crate::quic_media_transport::{StreamKey, TrackId};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use thiserror::Error;
+
+/// Track of the timed-metadata stream among the data streams
+pub const METADATA_TRACK: TrackId = TrackId::MAX - 1;
+
+/// Payload type of timed-metadata packets
+pub const METADATA_PAYLOAD_TYPE: u8 = 127;
+
+/// RTP clock rate of video, and so of metadata timestamps
+pub const VIDEO_CLOCK_RATE: u32 = 90_000;
+
+/// Key of the stream timed metadata is sent on
+#[must_use]
+pub const fn metadata_key() -> StreamKey {
+    StreamKey::new(StreamType::Data, METADATA_TRACK)
+}
+
+/// Timed metadata errors
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum MetadataError {
+    /// The metadata does not fit in one packet
+    #[error("Metadata too large: {0}")]
+    TooLarge(String),
+
+    /// A packet is not timed metadata
+    #[error("Malformed metadata packet: {0}")]
+    Malformed(String),
+}
+
+/// What a piece of metadata is
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetadataKind {
+    /// Caption or subtitle text, UTF-8
+    Caption,
+    /// Drawing annotation, e.g. on a screen share
+    Annotation,
+    /// Application-defined metadata
+    Custom(String),
+    /// Presenter cursor on a screen share, see [`crate::cursor`]
+    Cursor,
+}
+
+/// Metadata tied to one frame of a video track
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimedMetadata {
+    /// Video track the metadata belongs to
+    pub track_id: TrackId,
+    /// RTP timestamp of the frame it belongs to
+    pub timestamp: u32,
+    /// What the metadata is
+    pub kind: MetadataKind,
+    /// Metadata content
+    pub data: Vec<u8>,
+}
+
+/// Packet payload: everything but the timestamp, which is in the header
+#[derive(Serialize, Deserialize)]
+struct Body {
+    track_id: TrackId,
+    kind: MetadataKind,
+    data: Vec<u8>,
+}
+
+impl TimedMetadata {
+    /// Create metadata for the frame at `timestamp`
+    pub fn new(track_id: TrackId, timestamp: u32, kind: MetadataKind, data: Vec<u8>) -> Self {
+        Self {
+            track_id,
+            timestamp,
+            kind,
+            data,
+        }
+    }
+
+    /// Caption text for the frame at `timestamp`
+    pub fn caption(track_id: TrackId, timestamp: u32, text: impl Into<String>) -> Self {
+        Self::new(
+            track_id,
+            timestamp,
+            MetadataKind::Caption,
+            text.into().into_bytes(),
+        )
+    }
+
+    /// Frame the metadata as a packet for [`metadata_key`]
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the metadata does not fit in one packet
+    pub fn to_rtp(&self, ssrc: u32, sequence_number: u16) -> Result<RtpPacket, MetadataError> {
+        let body = Body {
+            track_id: self.track_id,
+            kind: self.kind.clone(),
+            data: self.data.clone(),
+        };
+        let payload =
+            postcard::to_stdvec(&body).map_err(|e| MetadataError::TooLarge(e.to_string()))?;
+        let mut packet = RtpPacket::new(
+            METADATA_PAYLOAD_TYPE,
+            sequence_number,
+            self.timestamp,
+            ssrc,
+            payload,
+            RtpStreamType::Data,
+        )
+        .map_err(|e| MetadataError::TooLarge(e.to_string()))?;
+        packet.marker = true;
+        Ok(packet)
+    }
+
+    /// Read metadata framed by [`Self::to_rtp`]
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the packet does not hold timed metadata
+    pub fn from_rtp(packet: &RtpPacket) -> Result<Self, MetadataError> {
+        if packet.payload_type != METADATA_PAYLOAD_TYPE {
+            return Err(MetadataError::Malformed(format!(
+                "payload type {}",
+                packet.payload_type
+            )));
+        }
+        let body: Body = postcard::from_bytes(&packet.payload)
+            .map_err(|e| MetadataError::Malformed(e.to_string()))?;
+        Ok(Self::new(
+            body.track_id,
+            packet.timestamp,
+            body.kind,
+            body.data,
+        ))
+    }
+}
+
+/// Signed distance from `b` to `a` in RTP timestamp space, across wraps
+pub(crate) fn timestamp_diff(a: u32, b: u32) -> i32 {
+    a.wrapping_sub(b) as i32
+}
+
+/// Holds received metadata until the frame it belongs to is rendered
+///
+/// Metadata for a frame already released is late: it goes out with the
+/// next release if it is at most [`Self::max_delay_ms`] behind, and is
+/// dropped otherwise.
+#[derive(Debug)]
+pub struct MetadataBuffer {
+    /// Oldest timestamp first
+    pending: VecDeque<TimedMetadata>,
+    capacity: usize,
+    max_delay_ms: u32,
+    released: Option<u32>,
+    dropped: u64,
+}
+
+impl MetadataBuffer {
+    /// Create a buffer holding up to `capacity` entries
+    ///
+    /// `max_delay_ms` is usually the video jitter buffer's
+    /// [`LatencyTuning::jitter_max_ms`](crate::types::LatencyTuning::jitter_max_ms).
+    #[must_use]
+    pub fn new(capacity: usize, max_delay_ms: u32) -> Self {
+        Self {
+            pending: VecDeque::new(),
+            capacity,
+            max_delay_ms,
+            released: None,
+            dropped: 0,
+        }
+    }
+
+    /// How late metadata may be and still be delivered
+    #[must_use]
+    pub fn max_delay_ms(&self) -> u32 {
+        self.max_delay_ms
+    }
+
+    /// Entries waiting for their frame
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Check if nothing is waiting
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Entries dropped as too late or for lack of room
+    #[must_use]
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Add received metadata
+    ///
+    /// Returns false if it was dropped.
+    pub fn push(&mut self, metadata: TimedMetadata) -> bool {
+        if self.is_too_late(metadata.timestamp) || self.capacity == 0 {
+            self.dropped += 1;
+            return false;
+        }
+        if self.pending.len() == self.capacity {
+            self.pending.pop_front();
+            self.dropped += 1;
+        }
+        // Usually in order, so search from the back
+        let at = self
+            .pending
+            .iter()
+            .rposition(|queued| timestamp_diff(metadata.timestamp, queued.timestamp) >= 0)
+            .map_or(0, |index| index + 1);
+        self.pending.insert(at, metadata);
+        true
+    }
+
+    /// Take the metadata to show with the frame at `frame_timestamp`
+    ///
+    /// Returns everything up to and including that timestamp, oldest first.
+    pub fn release(&mut self, frame_timestamp: u32) -> Vec<TimedMetadata> {
+        self.released = Some(frame_timestamp);
+        let due = self
+            .pending
+            .iter()
+            .take_while(|queued| timestamp_diff(queued.timestamp, frame_timestamp) <= 0)
+            .count();
+        let mut released: Vec<TimedMetadata> = self.pending.drain(..due).collect();
+        let before = released.len();
+        released.retain(|metadata| !self.is_too_late(metadata.timestamp));
+        self.dropped += (before - released.len()) as u64;
+        released
+    }
+
+    fn is_too_late(&self, timestamp: u32) -> bool {
+        let Some(released) = self.released else {
+            return false;
+        };
+        let max_delay = u64::from(self.max_delay_ms) * u64::from(VIDEO_CLOCK_RATE) / 1000;
+        i64::from(timestamp_diff(released, timestamp)) > max_delay as i64
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    /// 30 fps in 90 kHz ticks
+    const FRAME: u32 = 3000;
+
+    #[test]
+    fn test_metadata_packet_roundtrip() {
+        let caption = TimedMetadata::caption(1, 123_456, "Hello");
+        let packet = caption.to_rtp(9, 4).unwrap();
+        assert_eq!(packet.timestamp, 123_456);
+        let bytes = packet.to_bytes().unwrap();
+        let restored = TimedMetadata::from_rtp(&RtpPacket::from_bytes(&bytes).unwrap()).unwrap();
+        assert_eq!(restored, caption);
+
+        let too_big = TimedMetadata::new(1, 0, MetadataKind::Annotation, vec![0; 2000]);
+        assert!(matches!(
+            too_big.to_rtp(9, 5),
+            Err(MetadataError::TooLarge(_))
+        ));
+        let video = RtpPacket::new(96, 1, 0, 9, vec![1], RtpStreamType::Video).unwrap();
+        assert!(TimedMetadata::from_rtp(&video).is_err());
+    }
+
+    #[test]
+    fn test_released_with_matching_frame() {
+        let mut buffer = MetadataBuffer::new(16, 200);
+        let base = u32::MAX - FRAME; // wraps between frames
+        let at = |frame: u32| base.wrapping_add(frame * FRAME);
+
+        // Out of order arrival
+        buffer.push(TimedMetadata::caption(0, at(2), "two"));
+        buffer.push(TimedMetadata::caption(0, at(1), "one"));
+        buffer.push(TimedMetadata::caption(0, at(3), "three"));
+
+        assert!(buffer.release(at(0)).is_empty());
+        let texts = |released: Vec<TimedMetadata>| {
+            released
+                .into_iter()
+                .map(|m| String::from_utf8(m.data).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(texts(buffer.release(at(2))), vec!["one", "two"]);
+        assert_eq!(buffer.len(), 1);
+
+        // Slightly late still shows; far too late is dropped
+        assert!(buffer.push(TimedMetadata::caption(0, at(1), "late")));
+        assert!(!buffer.push(TimedMetadata::caption(
+            0,
+            at(2).wrapping_sub(90 * 300),
+            "stale"
+        )));
+        assert_eq!(texts(buffer.release(at(3))), vec!["late", "three"]);
+        assert_eq!(buffer.dropped(), 1);
+        assert!(buffer.is_empty());
+    }
+}
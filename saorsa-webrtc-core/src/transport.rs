@@ -2,11 +2,19 @@
 //!
 //! This module provides transport adapters for different signaling mechanisms.
 
+use crate::compression::CompressionConfig;
+use crate::connection_pool::{ConnectionPool, ConnectionPoolConfig};
+use crate::dual_stack;
 use crate::link_transport::StreamType as LinkStreamType;
+use crate::redact;
 use crate::signaling::{SignalingMessage, SignalingTransport};
+use crate::wire_format::{decode_frame, FrameCodec, ProtocolHello, WireFormat, WireFrame};
 use async_trait::async_trait;
-use std::net::SocketAddr;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
 
 /// Maximum signaling message size (64KB) to prevent DoS attacks
@@ -18,16 +26,111 @@ const MAX_SESSION_ID_LENGTH: usize = 256;
 /// Maximum SDP string length (reasonable for WebRTC)
 const MAX_SDP_LENGTH: usize = 32 * 1024;
 
+/// Maximum number of tracks in one track update
+const MAX_TRACKS: usize = 64;
+
+/// Maximum track label length
+const MAX_TRACK_LABEL_LENGTH: usize = 256;
+
+/// Maximum length of an encoded handoff key or token
+const MAX_HANDOFF_TOKEN_LENGTH: usize = 4 * 1024;
+
 /// Transport configuration
 #[derive(Debug, Clone)]
 pub struct TransportConfig {
     /// Local endpoint address
+    ///
+    /// May be IPv4 or IPv6. Binding the IPv6 unspecified address (`[::]`)
+    /// accepts both families on hosts that allow dual-stack sockets. A
+    /// non-zero port binds exactly that UDP port.
     pub local_addr: Option<SocketAddr>,
+    /// UDP ports to bind from, for deployments behind strict firewalls
+    ///
+    /// The first free port in the range is used, on the IP address of
+    /// `local_addr` (unspecified IPv4 if unset). Cannot be combined with a
+    /// non-zero `local_addr` port. Query the port actually bound with
+    /// [`AntQuicTransport::local_port`].
+    pub port_range: Option<PortRange>,
+    /// Signaling wire formats to offer during the protocol handshake,
+    /// in preference order
+    pub wire_formats: Vec<WireFormat>,
+    /// Signaling compression to offer during the protocol handshake
+    pub compression: CompressionConfig,
+    /// Connection reuse settings
+    pub pool: ConnectionPoolConfig,
 }
 
 impl Default for TransportConfig {
     fn default() -> Self {
-        Self { local_addr: None }
+        Self {
+            local_addr: None,
+            port_range: None,
+            wire_formats: WireFormat::ALL.to_vec(),
+            compression: CompressionConfig::default(),
+            pool: ConnectionPoolConfig::default(),
+        }
+    }
+}
+
+/// Inclusive range of local UDP ports
+///
+/// Parses from `"5000"` or `"5000-5010"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PortRange {
+    first: u16,
+    last: u16,
+}
+
+impl PortRange {
+    /// Create a range, or `None` if it is empty or includes port 0
+    #[must_use]
+    pub fn new(first: u16, last: u16) -> Option<Self> {
+        (first != 0 && first <= last).then_some(Self { first, last })
+    }
+
+    /// First port in the range
+    #[must_use]
+    pub fn first(&self) -> u16 {
+        self.first
+    }
+
+    /// Last port in the range
+    #[must_use]
+    pub fn last(&self) -> u16 {
+        self.last
+    }
+
+    /// Check whether a port is in the range
+    #[must_use]
+    pub fn contains(&self, port: u16) -> bool {
+        (self.first..=self.last).contains(&port)
+    }
+
+    /// Ports in the range, in bind order
+    pub fn ports(&self) -> impl Iterator<Item = u16> {
+        self.first..=self.last
+    }
+}
+
+impl fmt::Display for PortRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.first == self.last {
+            write!(f, "{}", self.first)
+        } else {
+            write!(f, "{}-{}", self.first, self.last)
+        }
+    }
+}
+
+impl FromStr for PortRange {
+    type Err = TransportError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || TransportError::InvalidConfig(format!("invalid port range: {s}"));
+        let (first, last) = s.split_once('-').unwrap_or((s, s));
+        let first = first.trim().parse().map_err(|_| invalid())?;
+        let last = last.trim().parse().map_err(|_| invalid())?;
+        Self::new(first, last).ok_or_else(invalid)
     }
 }
 
@@ -45,6 +148,10 @@ pub enum TransportError {
     /// Receive error
     #[error("Receive error: {0}")]
     ReceiveError(String),
+
+    /// Invalid transport configuration
+    #[error("Invalid configuration: {0}")]
+    InvalidConfig(String),
 }
 
 /// ant-quic transport adapter
@@ -57,6 +164,8 @@ pub struct AntQuicTransport {
     node: Option<Arc<ant_quic::Node>>,
     peer_map: Arc<tokio::sync::RwLock<std::collections::HashMap<String, ant_quic::PeerId>>>,
     default_peer: Arc<tokio::sync::RwLock<Option<ant_quic::PeerId>>>,
+    codecs: Arc<tokio::sync::RwLock<std::collections::HashMap<String, FrameCodec>>>,
+    pool: Arc<ConnectionPool<SocketAddr, ant_quic::PeerId>>,
     shutdown: Arc<tokio::sync::watch::Sender<bool>>,
     shutdown_rx: tokio::sync::watch::Receiver<bool>,
 }
@@ -66,11 +175,14 @@ impl AntQuicTransport {
     #[must_use]
     pub fn new(config: TransportConfig) -> Self {
         let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+        let pool = Arc::new(ConnectionPool::new(config.pool.clone()));
         Self {
+            pool,
             config,
             node: None,
             peer_map: Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
             default_peer: Arc::new(tokio::sync::RwLock::new(None)),
+            codecs: Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
             shutdown: Arc::new(shutdown_tx),
             shutdown_rx,
         }
@@ -90,18 +202,23 @@ impl AntQuicTransport {
     pub async fn start(&mut self) -> Result<(), TransportError> {
         use ant_quic::{Node, NodeConfigBuilder};
 
-        // Build node configuration
-        let config_builder = NodeConfigBuilder::default();
-        let node_config = if let Some(addr) = self.config.local_addr {
-            config_builder.bind_addr(addr).build()
-        } else {
-            config_builder.build()
+        let node = match self.config.port_range {
+            Some(range) => self.bind_in_range(range).await?,
+            None => {
+                // Build node configuration
+                let config_builder = NodeConfigBuilder::default();
+                let node_config = if let Some(addr) = self.config.local_addr {
+                    config_builder.bind_addr(addr).build()
+                } else {
+                    config_builder.build()
+                };
+
+                Node::with_config(node_config).await.map_err(|e| {
+                    TransportError::ConnectionError(format!("Failed to create QUIC node: {}", e))
+                })?
+            }
         };
 
-        let node = Node::with_config(node_config).await.map_err(|e| {
-            TransportError::ConnectionError(format!("Failed to create QUIC node: {}", e))
-        })?;
-
         let node_arc = Arc::new(node);
 
         // Spawn background task to accept incoming connections
@@ -121,7 +238,11 @@ impl AntQuicTransport {
                         if let Some(conn) = result {
                             let peer_id = conn.peer_id;
                             let addr = conn.remote_addr;
-                            tracing::debug!("Accepted connection from {:?} at {:?}", peer_id, addr);
+                            tracing::debug!(
+                                "Accepted connection from {} at {}",
+                                redact::identity(format_args!("{peer_id:?}")),
+                                redact::transport_addr(&addr)
+                            );
                             // Store the peer mapping
                             let peer_str = format!("{:?}", peer_id);
                             peer_map.write().await.insert(peer_str, peer_id);
@@ -131,10 +252,75 @@ impl AntQuicTransport {
             }
         });
 
+        // Spawn background task to close idle pooled connections
+        let node_clone = node_arc.clone();
+        let peer_map = self.peer_map.clone();
+        let pool = self.pool.clone();
+        let mut shutdown_rx = self.shutdown_rx.clone();
+        let sweep_interval = (self.config.pool.idle_timeout / 2).max(Duration::from_secs(1));
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(sweep_interval);
+            loop {
+                tokio::select! {
+                    _ = shutdown_rx.changed() => {
+                        if *shutdown_rx.borrow() {
+                            break;
+                        }
+                    }
+                    _ = interval.tick() => {
+                        close_connections(&node_clone, &peer_map, pool.evict_idle()).await;
+                    }
+                }
+            }
+        });
+
         self.node = Some(node_arc);
         Ok(())
     }
 
+    /// Create a node on the first free port of a range
+    async fn bind_in_range(&self, range: PortRange) -> Result<ant_quic::Node, TransportError> {
+        use ant_quic::{Node, NodeConfigBuilder};
+
+        let ip = match self.config.local_addr {
+            Some(addr) if addr.port() != 0 => {
+                return Err(TransportError::InvalidConfig(format!(
+                    "local_addr port {} conflicts with port range {}",
+                    addr.port(),
+                    range
+                )));
+            }
+            Some(addr) => addr.ip(),
+            None => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        };
+
+        let mut last_error = None;
+        for port in range.ports() {
+            let addr = SocketAddr::new(ip, port);
+            let node_config = NodeConfigBuilder::default().bind_addr(addr).build();
+            match Node::with_config(node_config).await {
+                Ok(node) => {
+                    tracing::info!(
+                        "Bound QUIC node to {} (range {})",
+                        redact::addr(addr),
+                        range
+                    );
+                    return Ok(node);
+                }
+                Err(e) => {
+                    tracing::debug!("Port {} unavailable: {}", port, e);
+                    last_error = Some(e.to_string());
+                }
+            }
+        }
+
+        Err(TransportError::ConnectionError(format!(
+            "No free port in range {}: {}",
+            range,
+            last_error.unwrap_or_default()
+        )))
+    }
+
     /// Stop the transport and shutdown accept loop
     ///
     /// # Errors
@@ -174,6 +360,9 @@ impl AntQuicTransport {
 
     /// Get local address
     ///
+    /// An unspecified bind address (`0.0.0.0` or `[::]`) is reported as the
+    /// loopback address of the same family, for connection purposes.
+    ///
     /// # Errors
     ///
     /// Returns error if transport is not started
@@ -183,35 +372,140 @@ impl AntQuicTransport {
             .as_ref()
             .ok_or_else(|| TransportError::ConnectionError("Transport not started".to_string()))?;
 
-        let mut addr = node.local_addr().ok_or_else(|| {
+        let addr = node.local_addr().ok_or_else(|| {
             TransportError::ConnectionError("No local address available".to_string())
         })?;
 
-        // If bound to 0.0.0.0, replace with localhost for connection purposes
-        if addr.ip().is_unspecified() {
-            addr.set_ip(std::net::IpAddr::V4(std::net::Ipv4Addr::LOCALHOST));
-        }
+        Ok(dual_stack::connectable(addr))
+    }
 
-        Ok(addr)
+    /// Get the UDP port the transport is bound to
+    ///
+    /// With a [`PortRange`] or an ephemeral port this is only known once
+    /// the transport has started. Deployments behind strict firewalls can
+    /// use it to check or report which port to open.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if transport is not started
+    pub async fn local_port(&self) -> Result<u16, TransportError> {
+        Ok(self.local_addr().await?.port())
     }
 
     /// Connect to a peer
     ///
+    /// Reuses a pooled connection to `addr` if one is still open. Each call
+    /// takes a lease on the connection; call [`release_peer`](Self::release_peer)
+    /// when the call or data channel using it ends so it can be evicted once
+    /// idle, or the pool fills up and further connects fail. A new connection starts the protocol handshake (see
+    /// [`send_hello`](Self::send_hello)).
+    ///
     /// # Errors
     ///
-    /// Returns error if connection fails
+    /// Returns error if connection fails or the pool is full
     pub async fn connect_to_peer(&mut self, addr: SocketAddr) -> Result<String, TransportError> {
+        let (peer_str, new) = self.connect_addr(addr).await?;
+        if new {
+            self.start_handshake(&peer_str).await;
+        }
+        Ok(peer_str)
+    }
+
+    /// Connect to a peer without starting the protocol handshake
+    ///
+    /// Returns the peer ID and whether the connection is new rather than
+    /// pooled. Media links use this: their framing has no hello.
+    async fn connect_addr(&mut self, addr: SocketAddr) -> Result<(String, bool), TransportError> {
         let node = self
             .node
             .as_ref()
             .ok_or_else(|| TransportError::ConnectionError("Transport not started".to_string()))?;
 
+        if let Some(peer_id) = self.pool.acquire(&addr) {
+            if node.is_connected(&peer_id).await {
+                tracing::debug!("Reusing pooled connection to {}", redact::addr(addr));
+                return Ok((format!("{:?}", peer_id), false));
+            }
+            // Stale entry: the connection closed underneath us
+            self.pool.remove(&addr);
+        }
+
         let conn = node
             .connect_addr(addr)
             .await
             .map_err(|e| TransportError::ConnectionError(format!("Failed to connect: {}", e)))?;
 
-        let peer_id = conn.peer_id;
+        Ok((self.register_connection(addr, conn.peer_id).await?, true))
+    }
+
+    /// Connect to a peer reachable at several addresses
+    ///
+    /// Used when a peer advertises both IPv4 and IPv6 endpoints. A pooled
+    /// connection to any of the addresses is reused; otherwise attempts are
+    /// raced Happy-Eyeballs style (see [`dual_stack::race`]) and the first
+    /// to connect is kept, and starts the protocol handshake. Returns the
+    /// peer ID and the address that won.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if no address is given, every attempt fails or the
+    /// pool is full
+    pub async fn connect_to_any(
+        &mut self,
+        addrs: &[SocketAddr],
+    ) -> Result<(String, SocketAddr), TransportError> {
+        let node = self
+            .node
+            .clone()
+            .ok_or_else(|| TransportError::ConnectionError("Transport not started".to_string()))?;
+
+        for addr in addrs {
+            if let Some(peer_id) = self.pool.acquire(addr) {
+                if node.is_connected(&peer_id).await {
+                    tracing::debug!("Reusing pooled connection to {}", redact::addr(*addr));
+                    return Ok((format!("{:?}", peer_id), *addr));
+                }
+                self.pool.remove(addr);
+            }
+        }
+
+        let (addr, conn) = dual_stack::race(addrs, dual_stack::CONNECTION_ATTEMPT_DELAY, |addr| {
+            let node = node.clone();
+            async move { node.connect_addr(addr).await }
+        })
+        .await
+        .map_err(|errors| {
+            let attempts: Vec<String> = errors
+                .iter()
+                .map(|(addr, e)| format!("{}: {}", addr, e))
+                .collect();
+            TransportError::ConnectionError(format!(
+                "Failed to connect to any address [{}]",
+                attempts.join(", ")
+            ))
+        })?;
+
+        let peer_str = self.register_connection(addr, conn.peer_id).await?;
+        self.start_handshake(&peer_str).await;
+        Ok((peer_str, addr))
+    }
+
+    /// Pool and map a newly established connection
+    async fn register_connection(
+        &self,
+        addr: SocketAddr,
+        peer_id: ant_quic::PeerId,
+    ) -> Result<String, TransportError> {
+        let node = self
+            .node
+            .as_ref()
+            .ok_or_else(|| TransportError::ConnectionError("Transport not started".to_string()))?;
+
+        let evicted = self
+            .pool
+            .insert(addr, peer_id)
+            .map_err(|e| TransportError::ConnectionError(e.to_string()))?;
+        close_connections(node, &self.peer_map, evicted.into_iter().collect()).await;
 
         // Generate string representation for peer ID
         let peer_str = format!("{:?}", peer_id);
@@ -237,10 +531,26 @@ impl AntQuicTransport {
     /// Returns error if disconnection fails
     pub async fn disconnect_peer(&mut self, peer: &String) -> Result<(), TransportError> {
         let mut peer_map = self.peer_map.write().await;
-        peer_map.remove(peer);
+        if let Some(peer_id) = peer_map.remove(peer) {
+            self.pool.remove_connection(&peer_id);
+        }
         Ok(())
     }
 
+    /// Release a lease on a pooled connection taken by `connect_to_peer`
+    ///
+    /// [`LinkTransport::disconnect`](crate::link_transport::LinkTransport::disconnect)
+    /// does the same for connections taken by `connect`.
+    pub fn release_peer(&self, addr: &SocketAddr) {
+        self.pool.release(addr);
+    }
+
+    /// Get the number of pooled connections
+    #[must_use]
+    pub fn pooled_connections(&self) -> usize {
+        self.pool.len()
+    }
+
     /// Get a handle for sending on a specific stream type
     ///
     /// This method prepares the transport for multiplexed streams.
@@ -304,7 +614,6 @@ impl AntQuicTransport {
     ///
     /// Returns error if receive fails
     pub async fn receive_bytes(&self) -> Result<Vec<u8>, TransportError> {
-        use std::time::Duration;
         let span = tracing::debug_span!("transport_receive_bytes");
         let _enter = span.enter();
 
@@ -376,16 +685,72 @@ impl AntQuicTransport {
     }
 }
 
-#[async_trait]
-impl SignalingTransport for AntQuicTransport {
-    type PeerId = String;
-    type Error = TransportError;
+impl AntQuicTransport {
+    /// Start the protocol version handshake with a peer
+    ///
+    /// Sends a [`ProtocolHello`] advertising the configured wire formats and
+    /// compression algorithms. Until the peer replies, messages to it continue
+    /// to be sent as uncompressed JSON; once the reply is received by
+    /// `receive_message`, the most preferred settings both sides support are
+    /// used. [`connect_to_peer`](Self::connect_to_peer) and
+    /// [`connect_to_any`](Self::connect_to_any) send it on every new
+    /// connection, and the accepting side replies to it, so this is only
+    /// needed to renegotiate.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the transport is not started, the peer is unknown, or
+    /// sending fails
+    pub async fn send_hello(&self, peer: &String) -> Result<(), TransportError> {
+        self.codecs.write().await.entry(peer.clone()).or_default();
+        self.send_hello_frame(peer).await
+    }
 
-    async fn send_message(
-        &self,
-        peer: &String,
-        message: SignalingMessage,
-    ) -> Result<(), TransportError> {
+    /// Get the wire format negotiated with a peer
+    ///
+    /// Returns JSON if no handshake has completed with the peer.
+    pub async fn wire_format(&self, peer: &String) -> WireFormat {
+        self.codec(peer).await.format
+    }
+
+    /// Get the frame codec negotiated with a peer
+    ///
+    /// Returns uncompressed JSON if no handshake has completed with the peer.
+    pub async fn codec(&self, peer: &String) -> FrameCodec {
+        self.codecs
+            .read()
+            .await
+            .get(peer)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Send the hello on a new connection, staying on JSON if it fails
+    async fn start_handshake(&self, peer: &String) {
+        if let Err(e) = self.send_hello(peer).await {
+            tracing::warn!(
+                "Failed to send protocol hello to peer {}: {}",
+                redact::identity(peer),
+                e
+            );
+        }
+    }
+
+    async fn send_hello_frame(&self, peer: &String) -> Result<(), TransportError> {
+        let hello = ProtocolHello {
+            wire_formats: self.config.wire_formats.clone(),
+            compression: self.config.compression.algorithms.clone(),
+            ..ProtocolHello::default()
+        };
+        let data = hello
+            .encode()
+            .map_err(|e| TransportError::SendError(format!("Failed to serialize hello: {}", e)))?;
+        self.send_frame(peer, &data).await?;
+        tracing::debug!("Sent protocol hello to peer: {}", redact::identity(peer));
+        Ok(())
+    }
+
+    async fn send_frame(&self, peer: &String, data: &[u8]) -> Result<(), TransportError> {
         if peer.is_empty() {
             return Err(TransportError::SendError(
                 "Peer ID cannot be empty".to_string(),
@@ -403,61 +768,116 @@ impl SignalingTransport for AntQuicTransport {
             .get(peer)
             .ok_or_else(|| TransportError::SendError(format!("Peer not found: {}", peer)))?;
 
-        // Serialize the message
-        let data = serde_json::to_vec(&message).map_err(|e| {
+        // Send over QUIC
+        node.send(peer_id, data)
+            .await
+            .map_err(|e| TransportError::SendError(format!("Failed to send: {}", e)))
+    }
+
+    /// Record a peer's hello and reply if we have not yet sent ours
+    async fn handle_hello(
+        &self,
+        peer: &String,
+        hello: ProtocolHello,
+    ) -> Result<(), TransportError> {
+        let codec =
+            FrameCodec::negotiate(&self.config.wire_formats, &self.config.compression, &hello);
+        let previous = self.codecs.write().await.insert(peer.clone(), codec);
+        tracing::debug!(
+            peer = %redact::identity(peer),
+            version = hello.version,
+            format = ?codec.format,
+            compression = ?codec.compression,
+            "Negotiated signaling wire format"
+        );
+
+        if previous.is_none() {
+            self.send_hello_frame(peer).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SignalingTransport for AntQuicTransport {
+    type PeerId = String;
+    type Error = TransportError;
+
+    async fn send_message(
+        &self,
+        peer: &String,
+        message: SignalingMessage,
+    ) -> Result<(), TransportError> {
+        if peer.is_empty() {
+            return Err(TransportError::SendError(
+                "Peer ID cannot be empty".to_string(),
+            ));
+        }
+
+        // Serialize (and compress) the message as negotiated with this peer
+        let data = self.codec(peer).await.encode(&message).map_err(|e| {
             TransportError::SendError(format!("Failed to serialize message: {}", e))
         })?;
 
-        // Send over QUIC
-        node.send(peer_id, &data)
-            .await
-            .map_err(|e| TransportError::SendError(format!("Failed to send: {}", e)))?;
+        self.send_frame(peer, &data).await?;
 
-        tracing::debug!("Sent signaling message to peer: {}", peer);
+        tracing::debug!("Sent signaling message to peer: {}", redact::identity(peer));
         Ok(())
     }
 
     async fn receive_message(&self) -> Result<(String, SignalingMessage), TransportError> {
-        use std::time::Duration;
         let node = self
             .node
             .as_ref()
             .ok_or_else(|| TransportError::ReceiveError("Transport not started".to_string()))?;
 
-        // Receive data from any peer (this will block until data arrives)
-        // The Node handles incoming connections internally
-        let (peer_id, data) = node
-            .recv(Duration::from_secs(30))
-            .await
-            .map_err(|e| TransportError::ReceiveError(format!("Failed to receive: {}", e)))?;
+        loop {
+            // Receive data from any peer (this will block until data arrives)
+            // The Node handles incoming connections internally
+            let (peer_id, data) = node
+                .recv(Duration::from_secs(30))
+                .await
+                .map_err(|e| TransportError::ReceiveError(format!("Failed to receive: {}", e)))?;
 
-        // Check message size limit to prevent DoS
-        if data.len() > MAX_SIGNALING_MESSAGE_SIZE {
-            return Err(TransportError::ReceiveError(format!(
-                "Message size {} exceeds maximum of {} bytes",
-                data.len(),
-                MAX_SIGNALING_MESSAGE_SIZE
-            )));
-        }
+            // Check message size limit to prevent DoS
+            if data.len() > MAX_SIGNALING_MESSAGE_SIZE {
+                return Err(TransportError::ReceiveError(format!(
+                    "Message size {} exceeds maximum of {} bytes",
+                    data.len(),
+                    MAX_SIGNALING_MESSAGE_SIZE
+                )));
+            }
 
-        // Deserialize the message
-        let message: SignalingMessage = serde_json::from_slice(&data).map_err(|e| {
-            TransportError::ReceiveError(format!("Failed to deserialize message: {}", e))
-        })?;
+            // Deserialize the frame in whichever format the peer used
+            let frame = decode_frame(&data).map_err(|e| {
+                TransportError::ReceiveError(format!("Failed to deserialize message: {}", e))
+            })?;
 
-        // Validate message fields
-        validate_signaling_message(&message)?;
+            // Generate string representation for peer ID
+            let peer_str = format!("{:?}", peer_id);
 
-        // Generate string representation for peer ID
-        let peer_str = format!("{:?}", peer_id);
+            // Update peer map if needed
+            let mut peer_map = self.peer_map.write().await;
+            peer_map.entry(peer_str.clone()).or_insert(peer_id);
+            drop(peer_map);
 
-        // Update peer map if needed
-        let mut peer_map = self.peer_map.write().await;
-        peer_map.entry(peer_str.clone()).or_insert(peer_id);
-        drop(peer_map);
+            let message = match frame {
+                WireFrame::Message(message, _) => message,
+                WireFrame::Hello(hello) => {
+                    self.handle_hello(&peer_str, hello).await?;
+                    continue;
+                }
+            };
 
-        tracing::debug!("Received signaling message from peer: {}", peer_str);
-        Ok((peer_str, message))
+            // Validate message fields
+            validate_signaling_message(&message)?;
+
+            tracing::debug!(
+                "Received signaling message from peer: {}",
+                redact::identity(&peer_str)
+            );
+            return Ok((peer_str, message));
+        }
     }
 
     async fn discover_peer_endpoint(
@@ -467,11 +887,33 @@ impl SignalingTransport for AntQuicTransport {
         // TODO: Implement actual peer discovery via DHT or gossip
         // For now, return None to indicate discovery not available
 
-        tracing::debug!("Attempting to discover endpoint for peer: {}", peer);
+        tracing::debug!(
+            "Attempting to discover endpoint for peer: {}",
+            redact::identity(peer)
+        );
         Ok(None)
     }
 }
 
+/// Close connections evicted from the pool
+async fn close_connections(
+    node: &ant_quic::Node,
+    peer_map: &tokio::sync::RwLock<std::collections::HashMap<String, ant_quic::PeerId>>,
+    connections: Vec<(SocketAddr, ant_quic::PeerId)>,
+) {
+    for (addr, peer_id) in connections {
+        tracing::debug!("Closing idle pooled connection to {}", redact::addr(addr));
+        if let Err(e) = node.disconnect(&peer_id).await {
+            tracing::warn!(
+                "Failed to close pooled connection to {}: {}",
+                redact::addr(addr),
+                e
+            );
+        }
+        peer_map.write().await.remove(&format!("{:?}", peer_id));
+    }
+}
+
 /// Validate signaling message fields to prevent abuse
 fn validate_signaling_message(message: &SignalingMessage) -> Result<(), TransportError> {
     match message {
@@ -518,7 +960,12 @@ fn validate_signaling_message(message: &SignalingMessage) -> Result<(), Transpor
         }
         SignalingMessage::IceComplete { session_id }
         | SignalingMessage::Bye { session_id, .. }
-        | SignalingMessage::ConnectionReady { session_id } => {
+        | SignalingMessage::Cancel { session_id, .. }
+        | SignalingMessage::Progress { session_id, .. }
+        | SignalingMessage::AudioOnly { session_id, .. }
+        | SignalingMessage::LayerSelection { session_id, .. }
+        | SignalingMessage::ConnectionReady { session_id }
+        | SignalingMessage::HandoffComplete { session_id } => {
             if session_id.len() > MAX_SESSION_ID_LENGTH {
                 return Err(TransportError::ReceiveError(format!(
                     "Session ID length {} exceeds maximum of {}",
@@ -540,6 +987,55 @@ fn validate_signaling_message(message: &SignalingMessage) -> Result<(), Transpor
             // Capability fields are bounded by their types (bool, u32)
             // so no additional length validation needed
         }
+        SignalingMessage::HandoffOffer {
+            session_id,
+            key: value,
+        }
+        | SignalingMessage::HandoffJoin {
+            session_id,
+            token: value,
+        } => {
+            if session_id.len() > MAX_SESSION_ID_LENGTH {
+                return Err(TransportError::ReceiveError(format!(
+                    "Session ID length {} exceeds maximum of {}",
+                    session_id.len(),
+                    MAX_SESSION_ID_LENGTH
+                )));
+            }
+            if value.len() > MAX_HANDOFF_TOKEN_LENGTH {
+                return Err(TransportError::ReceiveError(format!(
+                    "Handoff token length {} exceeds maximum of {}",
+                    value.len(),
+                    MAX_HANDOFF_TOKEN_LENGTH
+                )));
+            }
+        }
+        SignalingMessage::TrackUpdate { session_id, tracks } => {
+            if session_id.len() > MAX_SESSION_ID_LENGTH {
+                return Err(TransportError::ReceiveError(format!(
+                    "Session ID length {} exceeds maximum of {}",
+                    session_id.len(),
+                    MAX_SESSION_ID_LENGTH
+                )));
+            }
+            if tracks.len() > MAX_TRACKS {
+                return Err(TransportError::ReceiveError(format!(
+                    "Track count {} exceeds maximum of {}",
+                    tracks.len(),
+                    MAX_TRACKS
+                )));
+            }
+            if let Some(track) = tracks
+                .iter()
+                .find(|track| track.label.len() > MAX_TRACK_LABEL_LENGTH)
+            {
+                return Err(TransportError::ReceiveError(format!(
+                    "Track label length {} exceeds maximum of {}",
+                    track.label.len(),
+                    MAX_TRACK_LABEL_LENGTH
+                )));
+            }
+        }
     }
     Ok(())
 }
@@ -573,8 +1069,8 @@ impl crate::link_transport::LinkTransport for AntQuicTransport {
         addr: SocketAddr,
     ) -> Result<crate::link_transport::PeerConnection, crate::link_transport::LinkTransportError>
     {
-        let peer_id_str = self
-            .connect_to_peer(addr)
+        let (peer_id_str, _) = self
+            .connect_addr(addr)
             .await
             .map_err(|e| crate::link_transport::LinkTransportError::IoError(e.to_string()))?;
 
@@ -595,6 +1091,15 @@ impl crate::link_transport::LinkTransport for AntQuicTransport {
         Ok(None)
     }
 
+    async fn disconnect(
+        &mut self,
+        peer: &crate::link_transport::PeerConnection,
+    ) -> Result<(), crate::link_transport::LinkTransportError> {
+        // Accepted connections are not pooled, so there is no lease to give back
+        self.release_peer(&peer.remote_addr);
+        Ok(())
+    }
+
     async fn send(
         &self,
         peer: &crate::link_transport::PeerConnection,
@@ -633,8 +1138,6 @@ impl crate::link_transport::LinkTransport for AntQuicTransport {
         ),
         crate::link_transport::LinkTransportError,
     > {
-        use std::time::Duration;
-
         let node = self
             .node
             .as_ref()
@@ -674,13 +1177,16 @@ impl crate::link_transport::LinkTransport for AntQuicTransport {
         peer_map.entry(peer_str.clone()).or_insert(peer_id);
         drop(peer_map);
 
+        // Pooled connections know the address, of either family, they were
+        // made to; accepted ones are not pooled
+        let remote_addr = self.pool.key_of(&peer_id).unwrap_or_else(|| {
+            SocketAddr::new(std::net::IpAddr::V4(std::net::Ipv4Addr::LOCALHOST), 0)
+        });
+
         Ok((
             crate::link_transport::PeerConnection {
                 peer_id: peer_str,
-                remote_addr: SocketAddr::new(
-                    std::net::IpAddr::V4(std::net::Ipv4Addr::LOCALHOST),
-                    0,
-                ),
+                remote_addr,
             },
             stream_type,
             payload,
@@ -766,6 +1272,7 @@ mod tests {
     fn test_ant_quic_transport_config() {
         let config = TransportConfig {
             local_addr: Some("127.0.0.1:8080".parse().unwrap()),
+            ..Default::default()
         };
         let transport = AntQuicTransport::new(config.clone());
 
@@ -776,6 +1283,74 @@ mod tests {
     fn test_transport_config_default() {
         let config = TransportConfig::default();
         assert!(config.local_addr.is_none());
+        assert!(config.port_range.is_none());
+        assert_eq!(config.wire_formats, WireFormat::ALL.to_vec());
+        assert_eq!(config.pool, ConnectionPoolConfig::default());
+    }
+
+    #[test]
+    fn test_port_range_parsing() {
+        let range: PortRange = "5000-5010".parse().unwrap();
+        assert_eq!((range.first(), range.last()), (5000, 5010));
+        assert!(range.contains(5005));
+        assert!(!range.contains(5011));
+        assert_eq!(range.to_string(), "5000-5010");
+
+        let single: PortRange = "6000".parse().unwrap();
+        assert_eq!(single.ports().collect::<Vec<_>>(), vec![6000]);
+        assert_eq!(single.to_string(), "6000");
+
+        for invalid in ["", "0-10", "10-5", "abc", "5000-70000"] {
+            assert!(invalid.parse::<PortRange>().is_err(), "{invalid}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_port_range_skips_busy_ports() {
+        let busy = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let busy_port = busy.local_addr().unwrap().port();
+        let Some(range) = PortRange::new(busy_port, busy_port.saturating_add(8)) else {
+            return;
+        };
+
+        let mut transport = AntQuicTransport::new(TransportConfig {
+            local_addr: Some("127.0.0.1:0".parse().unwrap()),
+            port_range: Some(range),
+            ..Default::default()
+        });
+        transport.start().await.unwrap();
+
+        let port = transport.local_port().await.unwrap();
+        assert!(range.contains(port));
+        assert_ne!(port, busy_port);
+        transport.stop().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_port_range_conflicts_with_fixed_port() {
+        let mut transport = AntQuicTransport::new(TransportConfig {
+            local_addr: Some("127.0.0.1:5000".parse().unwrap()),
+            port_range: PortRange::new(6000, 6010),
+            ..Default::default()
+        });
+        assert!(matches!(
+            transport.start().await,
+            Err(TransportError::InvalidConfig(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_local_port_requires_start() {
+        let transport = AntQuicTransport::new(TransportConfig::default());
+        assert!(transport.local_port().await.is_err());
+    }
+
+    #[test]
+    fn test_pool_starts_empty() {
+        let transport = AntQuicTransport::new(TransportConfig::default());
+        assert_eq!(transport.pooled_connections(), 0);
+        transport.release_peer(&"127.0.0.1:9000".parse().unwrap());
+        assert_eq!(transport.pooled_connections(), 0);
     }
 
     #[test]
@@ -799,6 +1374,26 @@ mod tests {
         let result = transport.get_stream_handle(LinkStreamType::Data);
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_ipv6_bind_reports_ipv6_local_addr() {
+        let mut transport = AntQuicTransport::new(TransportConfig {
+            local_addr: Some("[::1]:0".parse().unwrap()),
+            ..Default::default()
+        });
+        if transport.start().await.is_err() {
+            // Host without IPv6
+            return;
+        }
+
+        let addr = AntQuicTransport::local_addr(&transport).await.unwrap();
+        assert!(addr.is_ipv6());
+        assert!(addr.ip().is_loopback());
+
+        let result = transport.connect_to_any(&[]).await;
+        assert!(result.is_err());
+        transport.stop().unwrap();
+    }
 }
 
 #[cfg(test)]
@@ -1,119 +1,31 @@
 //! WebRTC types and data structures
 
 use crate::identity::PeerIdentity;
-use crate::quic_media_transport::MediaTransportState;
+use crate::quality::DegradationReason;
+use crate::quic_media_transport::{MediaTransportState, TrackId, TransportStats};
 use chrono::{DateTime, Utc};
+use saorsa_webrtc_codecs::{Channels, OpusEncoderConfig, SampleRate};
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
+use std::collections::BTreeMap;
 
-/// Unique identifier for a call
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub struct CallId(pub Uuid);
+pub use saorsa_webrtc_proto::types::{
+    CallId, CallProgress, CallState, LatencyProfile, LatencyTuning, LayerStructure,
+    MediaConstraints, MediaType, TrackInfo, TrackSource, VideoLayer,
+};
+pub use saorsa_webrtc_proto::{InvalidShortCode, ShortCode, SHORT_CODE_LEN};
 
-impl CallId {
-    /// Create a new random call ID
-    pub fn new() -> Self {
-        Self(Uuid::new_v4())
-    }
-}
-
-impl Default for CallId {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl std::fmt::Display for CallId {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
-    }
-}
-
-/// Media constraints for a call
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MediaConstraints {
-    /// Enable audio
-    pub audio: bool,
-    /// Enable video
-    pub video: bool,
-    /// Enable screen sharing
-    pub screen_share: bool,
-}
-
-impl MediaConstraints {
-    /// Audio-only call
-    pub fn audio_only() -> Self {
-        Self {
-            audio: true,
-            video: false,
-            screen_share: false,
-        }
-    }
-
-    /// Video call with audio
-    pub fn video_call() -> Self {
-        Self {
-            audio: true,
-            video: true,
-            screen_share: false,
-        }
-    }
-
-    /// Screen share with audio
-    pub fn screen_share() -> Self {
-        Self {
-            audio: true,
-            video: false,
-            screen_share: true,
-        }
-    }
-
-    /// Check if audio is enabled
-    pub fn has_audio(&self) -> bool {
-        self.audio
-    }
-
-    /// Check if video is enabled
-    pub fn has_video(&self) -> bool {
-        self.video
-    }
-
-    /// Check if screen share is enabled
-    pub fn has_screen_share(&self) -> bool {
-        self.screen_share
-    }
-
-    /// Convert to media types
-    pub fn to_media_types(&self) -> Vec<MediaType> {
-        let mut types = Vec::new();
-        if self.audio {
-            types.push(MediaType::Audio);
-        }
-        if self.video {
-            types.push(MediaType::Video);
-        }
-        if self.screen_share {
-            types.push(MediaType::ScreenShare);
-        }
-        types
-    }
-}
-
-/// Types of media in a call
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub enum MediaType {
-    /// Audio stream
-    Audio,
-    /// Video stream
-    Video,
-    /// Screen share stream
-    ScreenShare,
-    /// Data channel
-    DataChannel,
+/// Which side placed a call
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CallDirection {
+    /// We called the remote peer
+    Outgoing,
+    /// The remote peer called us
+    Incoming,
 }
 
 /// Call offer message
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(bound = "I: PeerIdentity")]
 pub struct CallOffer<I: PeerIdentity> {
     /// Unique call identifier
@@ -132,6 +44,7 @@ pub struct CallOffer<I: PeerIdentity> {
 
 /// Call answer message
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct CallAnswer {
     /// Call identifier
     pub call_id: CallId,
@@ -156,56 +69,6 @@ pub struct IceCandidate {
     pub sdp_mline_index: Option<u32>,
 }
 
-/// Call state enumeration
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub enum CallState {
-    /// No active call
-    Idle,
-    /// Initiating call
-    Calling,
-    /// Establishing connection
-    Connecting,
-    /// Call is active
-    Connected,
-    /// Call is ending
-    Ending,
-    /// Call failed
-    Failed,
-}
-
-impl CallState {
-    /// Convert from `MediaTransportState` to `CallState`
-    ///
-    /// Maps QUIC transport states to call states:
-    /// - `Disconnected` -> `Idle` (initial/reset state)
-    /// - `Connecting` -> `Connecting`
-    /// - `Connected` -> `Connected`
-    /// - `Failed` -> `Failed`
-    #[must_use]
-    pub fn from_transport_state(transport_state: MediaTransportState) -> Self {
-        match transport_state {
-            MediaTransportState::Disconnected => CallState::Idle,
-            MediaTransportState::Connecting => CallState::Connecting,
-            MediaTransportState::Connected => CallState::Connected,
-            MediaTransportState::Failed => CallState::Failed,
-        }
-    }
-
-    /// Convert from `MediaTransportState` with call ending context
-    ///
-    /// Similar to `from_transport_state`, but returns `Ending` instead of
-    /// `Idle` when the transport is disconnected, suitable for cleanup scenarios.
-    #[must_use]
-    pub fn from_transport_state_ending(transport_state: MediaTransportState) -> Self {
-        match transport_state {
-            MediaTransportState::Disconnected => CallState::Ending,
-            MediaTransportState::Connecting => CallState::Connecting,
-            MediaTransportState::Connected => CallState::Connected,
-            MediaTransportState::Failed => CallState::Failed,
-        }
-    }
-}
-
 /// Map `MediaTransportState` to `CallState`
 ///
 /// Convenience function for converting transport states to call states.
@@ -217,6 +80,7 @@ pub fn call_state_from_transport(transport_state: MediaTransportState) -> CallSt
 
 /// Call quality metrics
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct CallQualityMetrics {
     /// Round-trip time in milliseconds
     pub rtt_ms: u32,
@@ -226,11 +90,34 @@ pub struct CallQualityMetrics {
     pub jitter_ms: u32,
     /// Bandwidth in kilobits per second
     pub bandwidth_kbps: u32,
+    /// Packets received per second
+    #[serde(default)]
+    pub packet_rate: f32,
     /// Timestamp when metrics were collected
     pub timestamp: DateTime<Utc>,
 }
 
 impl CallQualityMetrics {
+    /// Metrics measured by a call's media transport
+    ///
+    /// Bandwidth and packet rate are the 1s inbound rates of the snapshot:
+    /// what the network is delivering, which unlike the outbound rate does
+    /// not drop when we suspend our own video. The transport does not
+    /// measure RTT, loss or jitter, so they are zero; set them from other
+    /// measurements where available.
+    #[must_use]
+    pub fn from_transport_stats(stats: &TransportStats) -> Self {
+        let received = stats.received_rates();
+        Self {
+            rtt_ms: 0,
+            packet_loss_percent: 0.0,
+            jitter_ms: 0,
+            bandwidth_kbps: received.bitrate_kbps(),
+            packet_rate: received.packet_rate_1s as f32,
+            timestamp: Utc::now(),
+        }
+    }
+
     /// Check if quality is good
     pub fn is_good_quality(&self) -> bool {
         self.rtt_ms < 100
@@ -315,6 +202,11 @@ pub struct AdaptationSettings {
 ///
 /// Replaces SDP offer/answer with a simpler capability exchange.
 /// Used to negotiate media types and bandwidth without WebRTC SDP overhead.
+///
+/// Optional features are advertised in [`extensions`](Self::extensions), a
+/// key/value map that peers negotiate without a schema change: unknown keys
+/// are carried through untouched, and an extension is in use only when both
+/// peers list it.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MediaCapabilities {
     /// Audio capability
@@ -325,9 +217,37 @@ pub struct MediaCapabilities {
     pub data_channel: bool,
     /// Maximum bandwidth in kbps
     pub max_bandwidth_kbps: u32,
+    /// Audio format preferences; peers that predate negotiation get the
+    /// defaults, which match what they send
+    #[serde(default)]
+    pub audio_params: AudioParameters,
+    /// Optional extensions keyed by name, with extension-defined parameters
+    /// (e.g. a version) as the value
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extensions: BTreeMap<String, String>,
 }
 
 impl MediaCapabilities {
+    /// Forward error correction extension
+    pub const EXT_FEC: &'static str = "fec";
+
+    /// Simulcast extension
+    pub const EXT_SIMULCAST: &'static str = "simulcast";
+
+    /// End-to-end media encryption extension
+    pub const EXT_E2EE: &'static str = "e2ee";
+
+    /// Early media extension: audio may flow before the call is accepted
+    pub const EXT_EARLY_MEDIA: &'static str = "early-media";
+
+    /// Extension keys with a meaning defined by this crate
+    pub const WELL_KNOWN_EXTENSIONS: [&'static str; 4] = [
+        Self::EXT_FEC,
+        Self::EXT_SIMULCAST,
+        Self::EXT_E2EE,
+        Self::EXT_EARLY_MEDIA,
+    ];
+
     /// Create capabilities from media constraints
     #[must_use]
     pub fn from_constraints(constraints: &MediaConstraints) -> Self {
@@ -340,6 +260,8 @@ impl MediaCapabilities {
             } else {
                 128 // Audio-only calls
             },
+            audio_params: AudioParameters::default(),
+            extensions: BTreeMap::new(),
         }
     }
 
@@ -351,6 +273,8 @@ impl MediaCapabilities {
             video: false,
             data_channel: false,
             max_bandwidth_kbps: 128,
+            audio_params: AudioParameters::default(),
+            extensions: BTreeMap::new(),
         }
     }
 
@@ -362,6 +286,8 @@ impl MediaCapabilities {
             video: true,
             data_channel: false,
             max_bandwidth_kbps: 2500,
+            audio_params: AudioParameters::default(),
+            extensions: BTreeMap::new(),
         }
     }
 
@@ -371,6 +297,57 @@ impl MediaCapabilities {
         (!constraints.audio || self.audio)
             && (!(constraints.video || constraints.screen_share) || self.video)
     }
+
+    /// Set the advertised audio format preferences
+    #[must_use]
+    pub fn with_audio_params(mut self, audio_params: AudioParameters) -> Self {
+        self.audio_params = audio_params;
+        self
+    }
+
+    /// Advertise an extension with the given parameters
+    #[must_use]
+    pub fn with_extension(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.extensions.insert(key.into(), value.into());
+        self
+    }
+
+    /// Check if an extension is advertised
+    #[must_use]
+    pub fn supports_extension(&self, key: &str) -> bool {
+        self.extensions.contains_key(key)
+    }
+
+    /// Get the parameters of an advertised extension
+    #[must_use]
+    pub fn extension(&self, key: &str) -> Option<&str> {
+        self.extensions.get(key).map(String::as_str)
+    }
+
+    /// Check if both sides advertise an extension
+    #[must_use]
+    pub fn mutually_supports(&self, remote: &Self, key: &str) -> bool {
+        self.supports_extension(key) && remote.supports_extension(key)
+    }
+
+    /// Extensions advertised by both sides
+    ///
+    /// Values are taken from `self`, so the local parameters win when both
+    /// sides advertise the same extension differently.
+    #[must_use]
+    pub fn mutual_extensions(&self, remote: &Self) -> BTreeMap<String, String> {
+        self.extensions
+            .iter()
+            .filter(|(key, _)| remote.supports_extension(key))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+
+    /// Check if an extension key has a meaning defined by this crate
+    #[must_use]
+    pub fn is_well_known_extension(key: &str) -> bool {
+        Self::WELL_KNOWN_EXTENSIONS.contains(&key)
+    }
 }
 
 impl Default for MediaCapabilities {
@@ -379,6 +356,130 @@ impl Default for MediaCapabilities {
     }
 }
 
+/// Audio format a peer can send and receive
+///
+/// Each side advertises its preferences in [`MediaCapabilities`] and both
+/// derive the same agreed format with [`negotiate`](Self::negotiate), which
+/// then configures the Opus encoder, decoder and any resampling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AudioParameters {
+    /// Channel count, 1 (mono) or 2 (stereo)
+    pub channels: u8,
+    /// Highest sample rate in Hz
+    pub max_sample_rate_hz: u32,
+    /// Target Opus bitrate in bits per second
+    pub target_bitrate_bps: u32,
+    /// Previous frames repeated in each packet for loss resilience (see
+    /// [`crate::red`]); 0, the default and what older peers get, disables
+    /// redundancy
+    #[serde(default)]
+    pub redundancy: u8,
+}
+
+impl AudioParameters {
+    /// Sample rates Opus can code, highest first
+    pub const OPUS_SAMPLE_RATES: [u32; 5] = [48_000, 24_000, 16_000, 12_000, 8_000];
+
+    /// Lowest Opus bitrate in bits per second
+    pub const MIN_BITRATE_BPS: u32 = 6_000;
+
+    /// Highest Opus bitrate in bits per second
+    pub const MAX_BITRATE_BPS: u32 = 510_000;
+
+    /// Stereo at 48 kHz, for music or spatial audio
+    #[must_use]
+    pub fn stereo() -> Self {
+        Self {
+            channels: 2,
+            target_bitrate_bps: 128_000,
+            ..Self::default()
+        }
+    }
+
+    /// Agree on a format both sides support
+    ///
+    /// Takes the fewer channels, the highest Opus rate neither side's
+    /// maximum exceeds, the lower bitrate clamped to Opus' range and the
+    /// lower redundancy. The result is the same whichever side computes it.
+    #[must_use]
+    pub fn negotiate(&self, remote: &Self) -> Self {
+        let max_rate = self.max_sample_rate_hz.min(remote.max_sample_rate_hz);
+        Self {
+            channels: self.channels.min(remote.channels).clamp(1, 2),
+            max_sample_rate_hz: Self::OPUS_SAMPLE_RATES
+                .into_iter()
+                .find(|&rate| rate <= max_rate)
+                .unwrap_or(8_000),
+            target_bitrate_bps: self
+                .target_bitrate_bps
+                .min(remote.target_bitrate_bps)
+                .clamp(Self::MIN_BITRATE_BPS, Self::MAX_BITRATE_BPS),
+            redundancy: self
+                .redundancy
+                .min(remote.redundancy)
+                .min(crate::red::MAX_REDUNDANCY),
+        }
+    }
+
+    /// Opus sample rate for these parameters
+    ///
+    /// Rates that are not Opus rates round down to the nearest one.
+    #[must_use]
+    pub fn sample_rate(&self) -> SampleRate {
+        match self.max_sample_rate_hz {
+            48_000.. => SampleRate::Hz48000,
+            24_000.. => SampleRate::Hz24000,
+            16_000.. => SampleRate::Hz16000,
+            12_000.. => SampleRate::Hz12000,
+            _ => SampleRate::Hz8000,
+        }
+    }
+
+    /// Opus channel layout for these parameters
+    #[must_use]
+    pub fn opus_channels(&self) -> Channels {
+        if self.channels >= 2 {
+            Channels::Stereo
+        } else {
+            Channels::Mono
+        }
+    }
+
+    /// Opus encoder configuration for these parameters
+    ///
+    /// With redundancy, the target bitrate is shared between each frame and
+    /// its copies, so the audio on the wire stays within it.
+    #[must_use]
+    pub fn encoder_config(&self) -> OpusEncoderConfig {
+        OpusEncoderConfig {
+            sample_rate: self.sample_rate(),
+            channels: self.opus_channels(),
+            bitrate: (self.target_bitrate_bps / (1 + u32::from(self.redundancy)))
+                .clamp(Self::MIN_BITRATE_BPS, Self::MAX_BITRATE_BPS),
+        }
+    }
+
+    /// Bitrate of the audio on the wire, copies included, in bits per
+    /// second
+    #[must_use]
+    pub fn wire_bitrate_bps(&self) -> u32 {
+        self.encoder_config()
+            .bitrate
+            .saturating_mul(1 + u32::from(self.redundancy))
+    }
+}
+
+impl Default for AudioParameters {
+    fn default() -> Self {
+        Self {
+            channels: 1,
+            max_sample_rate_hz: 48_000,
+            target_bitrate_bps: 64_000,
+            redundancy: 0,
+        }
+    }
+}
+
 /// Video resolution options
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum VideoResolution {
@@ -452,8 +553,12 @@ pub enum SignalingMessage<I: PeerIdentity> {
     },
 }
 
+/// Application metadata attached to a call, e.g. a support ticket ID
+pub type CallMetadata = std::collections::BTreeMap<String, String>;
+
 /// Call event for notifications
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(bound = "I: PeerIdentity")]
 pub enum CallEvent<I: PeerIdentity> {
     /// Incoming call received
@@ -470,6 +575,36 @@ pub enum CallEvent<I: PeerIdentity> {
         /// Media constraints
         constraints: MediaConstraints,
     },
+    /// Another call came in while we are on a call
+    ///
+    /// Raised instead of [`CallEvent::IncomingCall`]. Answer the waiting
+    /// call with `CallManager::answer_waiting_call`, which puts the current
+    /// call on hold, or decline it with `CallManager::reject_busy`.
+    CallWaiting {
+        /// The waiting call's offer
+        offer: CallOffer<I>,
+        /// The call we are on
+        active: CallId,
+    },
+    /// We put a call on hold; its media is paused
+    CallHeld {
+        /// Call identifier
+        call_id: CallId,
+    },
+    /// We took a call off hold
+    CallResumed {
+        /// Call identifier
+        call_id: CallId,
+    },
+    /// The application attached metadata to a call
+    MetadataChanged {
+        /// Call identifier
+        call_id: CallId,
+        /// Key that was set
+        key: String,
+        /// New value
+        value: String,
+    },
     /// Call accepted
     CallAccepted {
         /// Call identifier
@@ -482,11 +617,62 @@ pub enum CallEvent<I: PeerIdentity> {
         /// Call identifier
         call_id: CallId,
     },
+    /// The callee reported progress on an outgoing call
+    CallProgress {
+        /// Call identifier
+        call_id: CallId,
+        /// Reported progress
+        progress: CallProgress,
+    },
+    /// The caller stopped ringing us, e.g. because another of our devices
+    /// answered
+    CallCancelled {
+        /// Call identifier
+        call_id: CallId,
+        /// Reason given by the caller, if any
+        reason: Option<String>,
+    },
+    /// Both peers called each other at once and one call was cancelled
+    GlareResolved {
+        /// The call that was cancelled
+        cancelled: CallId,
+        /// The call that continues
+        kept: CallId,
+    },
     /// Call ended
     CallEnded {
         /// Call identifier
         call_id: CallId,
     },
+    /// The peer moved the call to another of its devices
+    PeerHandedOff {
+        /// Call identifier
+        call_id: CallId,
+        /// Device that held the call
+        from: I,
+        /// Device now holding the call
+        to: I,
+    },
+    /// We moved the call to another of our devices and released it here
+    CallHandedOff {
+        /// Call identifier
+        call_id: CallId,
+        /// Device now holding the call
+        to: I,
+    },
+    /// Media for an outgoing call was prepared while it rings
+    ///
+    /// Start the encoders now so the first frame is ready on acceptance.
+    /// Start capture too only if `capture` is set; otherwise wait for the
+    /// call to be accepted. Nothing is sent before then either way.
+    MediaPrepared {
+        /// Call identifier
+        call_id: CallId,
+        /// Media the call will carry
+        constraints: MediaConstraints,
+        /// Capture may start before acceptance
+        capture: bool,
+    },
     /// Connection established
     ConnectionEstablished {
         /// Call identifier
@@ -499,6 +685,13 @@ pub enum CallEvent<I: PeerIdentity> {
         /// Error description
         error: String,
     },
+    /// Peer stopped responding to keepalives
+    Reconnecting {
+        /// Call identifier
+        call_id: CallId,
+        /// Consecutive keepalive intervals without traffic from the peer
+        missed: u32,
+    },
     /// Quality changed
     QualityChanged {
         /// Call identifier
@@ -506,6 +699,67 @@ pub enum CallEvent<I: PeerIdentity> {
         /// Current metrics
         metrics: CallQualityMetrics,
     },
+    /// The loss rate changed how strong our audio's in-band FEC should be
+    ///
+    /// Apply it with
+    /// [`AudioPipeline::set_expected_loss_percent`](crate::audio_pipeline::AudioPipeline::set_expected_loss_percent).
+    AudioFecChanged {
+        /// Call identifier
+        call_id: CallId,
+        /// Expected loss to tune FEC for, in percent; 0 turns it off
+        expected_loss_percent: u8,
+    },
+    /// We suspended our outgoing video because the network stayed poor
+    VideoSuspended {
+        /// Call identifier
+        call_id: CallId,
+        /// What was poor
+        reason: DegradationReason,
+    },
+    /// We resumed our outgoing video after the network recovered
+    VideoResumed {
+        /// Call identifier
+        call_id: CallId,
+    },
+    /// The peer suspended or resumed its video because of its network
+    PeerAudioOnly {
+        /// Call identifier
+        call_id: CallId,
+        /// `true` while the peer's video is suspended
+        active: bool,
+    },
+    /// The peer chose which layers of one of our video tracks it receives
+    ///
+    /// Apply it with [`crate::media::VideoTrack::select_layer`].
+    LayerSelected {
+        /// Call identifier
+        call_id: CallId,
+        /// Our video track the selection applies to
+        track_id: TrackId,
+        /// Highest layer the peer wants
+        layer: VideoLayer,
+    },
+    /// Remote peer described its media tracks
+    RemoteTrackMetadata {
+        /// Call identifier
+        call_id: CallId,
+        /// Every track the peer currently sends
+        tracks: Vec<TrackInfo>,
+    },
+    /// Remote peer started sending a track
+    RemoteTrackAdded {
+        /// Call identifier
+        call_id: CallId,
+        /// The new track
+        track: TrackInfo,
+    },
+    /// Remote peer stopped sending a track
+    RemoteTrackRemoved {
+        /// Call identifier
+        call_id: CallId,
+        /// The removed track
+        track: TrackInfo,
+    },
 }
 
 /// Call session information
@@ -642,6 +896,7 @@ mod tests {
             packet_loss_percent: 0.5,
             jitter_ms: 10,
             bandwidth_kbps: 1000,
+            packet_rate: 100.0,
             timestamp: Utc::now(),
         };
         assert!(good.is_good_quality());
@@ -652,12 +907,46 @@ mod tests {
             packet_loss_percent: 5.0,
             jitter_ms: 50,
             bandwidth_kbps: 200,
+            packet_rate: 20.0,
             timestamp: Utc::now(),
         };
         assert!(!bad.is_good_quality());
         assert!(bad.needs_adaptation());
     }
 
+    #[test]
+    fn test_quality_metrics_from_transport_stats() {
+        use crate::bitrate::{Rates, StreamRates};
+        use crate::link_transport::StreamType;
+
+        let mut stats = TransportStats::default();
+        for (stream_type, bitrate, packet_rate) in [
+            (StreamType::Audio, 64_000.0, 50.0),
+            (StreamType::Video, 936_000.0, 90.0),
+        ] {
+            let received = Rates {
+                bitrate_1s_bps: bitrate,
+                packet_rate_1s: packet_rate,
+                ..Rates::default()
+            };
+            stats.stream_rates.insert(
+                stream_type,
+                StreamRates {
+                    sent: Rates::default(),
+                    received,
+                },
+            );
+        }
+
+        let metrics = CallQualityMetrics::from_transport_stats(&stats);
+        assert_eq!(metrics.bandwidth_kbps, 1000);
+        assert!((metrics.packet_rate - 140.0).abs() < f32::EPSILON);
+        assert_eq!(
+            CallQualityMetrics::from_transport_stats(&TransportStats::default()).bandwidth_kbps,
+            0
+        );
+    }
+
     #[test]
     fn test_video_resolution() {
         let hd720 = VideoResolution::HD720;
@@ -779,4 +1068,107 @@ mod tests {
         assert!(!caps.data_channel);
         assert_eq!(caps.max_bandwidth_kbps, 128);
     }
+
+    #[test]
+    fn test_media_capabilities_mutual_extensions() {
+        let local = MediaCapabilities::video()
+            .with_extension(MediaCapabilities::EXT_FEC, "1")
+            .with_extension(MediaCapabilities::EXT_E2EE, "2");
+        let remote = MediaCapabilities::video()
+            .with_extension(MediaCapabilities::EXT_E2EE, "1")
+            .with_extension("x-vendor", "");
+
+        assert!(local.mutually_supports(&remote, MediaCapabilities::EXT_E2EE));
+        assert!(!local.mutually_supports(&remote, MediaCapabilities::EXT_FEC));
+        assert_eq!(local.extension(MediaCapabilities::EXT_FEC), Some("1"));
+
+        let mutual = local.mutual_extensions(&remote);
+        assert_eq!(mutual.len(), 1);
+        assert_eq!(
+            mutual.get(MediaCapabilities::EXT_E2EE).map(String::as_str),
+            Some("2")
+        );
+
+        assert!(MediaCapabilities::is_well_known_extension("simulcast"));
+        assert!(!MediaCapabilities::is_well_known_extension("x-vendor"));
+    }
+
+    #[test]
+    fn test_media_capabilities_extensions_serialization() {
+        // Capabilities without extensions serialize as before
+        let json = serde_json::to_value(MediaCapabilities::audio_only()).unwrap();
+        assert!(json.get("extensions").is_none());
+
+        // Peers that predate extensions are still understood
+        let legacy: MediaCapabilities = serde_json::from_str(
+            r#"{"audio":true,"video":false,"data_channel":false,"max_bandwidth_kbps":128}"#,
+        )
+        .unwrap();
+        assert!(legacy.extensions.is_empty());
+
+        // Unknown extensions and fields survive a roundtrip
+        let future: MediaCapabilities = serde_json::from_str(
+            r#"{"audio":true,"video":true,"data_channel":false,"max_bandwidth_kbps":2500,
+                "extensions":{"x-future":"v3"},"future_field":1}"#,
+        )
+        .unwrap();
+        assert_eq!(future.extension("x-future"), Some("v3"));
+        let roundtrip: MediaCapabilities =
+            serde_json::from_str(&serde_json::to_string(&future).unwrap()).unwrap();
+        assert_eq!(roundtrip, future);
+    }
+
+    #[test]
+    fn test_audio_parameters_negotiation() {
+        let local = AudioParameters::stereo();
+        let remote = AudioParameters {
+            channels: 1,
+            max_sample_rate_hz: 44_100,
+            target_bitrate_bps: 1_000_000,
+            redundancy: 0,
+        };
+
+        let agreed = local.negotiate(&remote);
+        assert_eq!(agreed, remote.negotiate(&local));
+        assert_eq!(agreed.channels, 1);
+        // 44.1 kHz is not an Opus rate, so the next lower one is used
+        assert_eq!(agreed.max_sample_rate_hz, 24_000);
+        assert_eq!(agreed.target_bitrate_bps, 128_000);
+
+        let config = agreed.encoder_config();
+        assert_eq!(config.sample_rate, SampleRate::Hz24000);
+        assert_eq!(config.channels, Channels::Mono);
+
+        // Peers without audio parameters get the defaults
+        let legacy: MediaCapabilities = serde_json::from_str(
+            r#"{"audio":true,"video":false,"data_channel":false,"max_bandwidth_kbps":128}"#,
+        )
+        .unwrap();
+        assert_eq!(legacy.audio_params, AudioParameters::default());
+    }
+
+    #[test]
+    fn test_audio_redundancy_negotiation() {
+        let red = AudioParameters {
+            redundancy: 5,
+            ..AudioParameters::default()
+        };
+        let agreed = red.negotiate(&red);
+        assert_eq!(agreed.redundancy, crate::red::MAX_REDUNDANCY);
+        // Copies share the bitrate budget
+        assert_eq!(agreed.encoder_config().bitrate, 64_000 / 3);
+        assert!(agreed.wire_bitrate_bps() <= agreed.target_bitrate_bps);
+
+        // Peers that do not send redundancy never receive it
+        let legacy: AudioParameters = serde_json::from_str(
+            r#"{"channels":1,"max_sample_rate_hz":48000,"target_bitrate_bps":64000}"#,
+        )
+        .unwrap();
+        assert_eq!(legacy.redundancy, 0);
+        assert_eq!(red.negotiate(&legacy).redundancy, 0);
+        assert_eq!(
+            AudioParameters::default().wire_bitrate_bps(),
+            AudioParameters::default().target_bitrate_bps
+        );
+    }
 }
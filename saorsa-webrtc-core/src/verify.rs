@@ -0,0 +1,284 @@
+//! Media content verification for end-to-end tests
+//!
+//! Detectors for the content produced by the [`synthetic`](crate::synthetic)
+//! sources, so tests can assert that media actually arrived intact rather
+//! than only that send calls returned `Ok`:
+//!
+//! - [`ToneDetector`] measures the frequency of received audio.
+//! - [`decode_frame_counter`] reads the frame number back out of a
+//!   test-pattern frame.
+//! - [`FrameTracker`] collects decoded frame numbers and their latency into
+//!   a [`FlowReport`] of loss, reordering and delay.
+
+use crate::synthetic::{counter_top, COUNTER_BITS};
+use saorsa_webrtc_codecs::VideoFrame;
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+/// Quietest audio the tone detector measures (about -50 dBFS peak)
+pub const MIN_TONE_PEAK: i16 = 100;
+
+/// Measures the frequency of a received tone
+#[derive(Debug, Clone, Copy)]
+pub struct ToneDetector {
+    sample_rate_hz: u32,
+    channels: u8,
+}
+
+impl ToneDetector {
+    /// Create a detector for audio in the given format
+    #[must_use]
+    pub fn new(sample_rate_hz: u32, channels: u8) -> Self {
+        Self {
+            sample_rate_hz,
+            channels: channels.max(1),
+        }
+    }
+
+    /// Estimate the frequency of a pure tone from interleaved samples
+    ///
+    /// Uses the first channel and the interpolated positions of its rising
+    /// zero crossings. Returns `None` for silence or fewer than two full
+    /// periods.
+    #[must_use]
+    pub fn frequency(&self, samples: &[i16]) -> Option<f32> {
+        let mono: Vec<i16> = samples
+            .iter()
+            .step_by(usize::from(self.channels))
+            .copied()
+            .collect();
+        if mono.iter().map(|s| s.unsigned_abs()).max()? < MIN_TONE_PEAK.unsigned_abs() {
+            return None;
+        }
+
+        let crossings: Vec<f64> = mono
+            .windows(2)
+            .enumerate()
+            .filter(|(_, pair)| pair[0] < 0 && pair[1] >= 0)
+            .map(|(i, pair)| {
+                let (before, after) = (f64::from(pair[0]), f64::from(pair[1]));
+                i as f64 + before / (before - after)
+            })
+            .collect();
+        let (first, last) = (crossings.first()?, crossings.last()?);
+        let periods = crossings.len().checked_sub(1).filter(|p| *p >= 2)?;
+        let samples_per_period = (last - first) / periods as f64;
+        Some((f64::from(self.sample_rate_hz) / samples_per_period) as f32)
+    }
+
+    /// Check if audio carries a tone within `tolerance_hz` of `expected_hz`
+    #[must_use]
+    pub fn matches(&self, samples: &[i16], expected_hz: f32, tolerance_hz: f32) -> bool {
+        self.frequency(samples)
+            .is_some_and(|hz| (hz - expected_hz).abs() <= tolerance_hz)
+    }
+}
+
+/// Read the frame number from a test-pattern frame
+///
+/// Samples the centre of each counter cell. Returns `None` if the frame is
+/// not a valid RGB test pattern or a cell is neither clearly black nor
+/// clearly white (e.g. it was corrupted or scaled).
+#[must_use]
+pub fn decode_frame_counter(frame: &VideoFrame) -> Option<u32> {
+    let (width, height) = (frame.width as usize, frame.height as usize);
+    if frame.width < 2 * COUNTER_BITS || frame.data.len() != width * height * 3 {
+        return None;
+    }
+    let top = counter_top(frame.height) as usize;
+    let y = top + (height - top) / 2;
+
+    let mut counter = 0u32;
+    for bit in 0..COUNTER_BITS as usize {
+        let x = (2 * bit + 1) * width / (2 * COUNTER_BITS as usize);
+        let offset = (y * width + x) * 3;
+        let pixel = frame.data.get(offset..offset + 3)?;
+        let luma = pixel.iter().map(|c| u32::from(*c)).sum::<u32>() / 3;
+        let set = match luma {
+            0..=63 => false,
+            192.. => true,
+            _ => return None,
+        };
+        counter = counter << 1 | u32::from(set);
+    }
+    Some(counter)
+}
+
+/// Delivery summary of a test-pattern stream
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FlowReport {
+    /// Distinct frames received
+    pub received: u64,
+    /// Frames missing between the first and last received
+    pub lost: u64,
+    /// Frames received more than once
+    pub duplicates: u64,
+    /// Frames that arrived after a later frame
+    pub out_of_order: u64,
+    /// Highest latency observed
+    pub max_latency: Duration,
+}
+
+impl FlowReport {
+    /// Fraction of frames lost, from 0.0 to 1.0
+    #[must_use]
+    pub fn loss_ratio(&self) -> f64 {
+        let expected = self.received + self.lost;
+        if expected == 0 {
+            return 0.0;
+        }
+        self.lost as f64 / expected as f64
+    }
+
+    /// Check loss and latency against bounds
+    #[must_use]
+    pub fn within(&self, max_loss_ratio: f64, max_latency: Duration) -> bool {
+        self.received > 0 && self.loss_ratio() <= max_loss_ratio && self.max_latency <= max_latency
+    }
+}
+
+/// Tracks decoded frame numbers of a received test pattern
+#[derive(Debug, Default)]
+pub struct FrameTracker {
+    seen: BTreeSet<u32>,
+    highest: Option<u32>,
+    duplicates: u64,
+    out_of_order: u64,
+    max_latency: Duration,
+}
+
+impl FrameTracker {
+    /// Create an empty tracker
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a received frame number and how long it took to arrive
+    pub fn observe(&mut self, frame_number: u32, latency: Duration) {
+        self.max_latency = self.max_latency.max(latency);
+        if !self.seen.insert(frame_number) {
+            self.duplicates += 1;
+            return;
+        }
+        match self.highest {
+            Some(highest) if frame_number < highest => self.out_of_order += 1,
+            _ => self.highest = Some(frame_number),
+        }
+    }
+
+    /// Decode a frame's number and record it
+    ///
+    /// Returns the frame number, or `None` if it could not be decoded.
+    pub fn observe_frame(&mut self, frame: &VideoFrame, latency: Duration) -> Option<u32> {
+        let frame_number = decode_frame_counter(frame)?;
+        self.observe(frame_number, latency);
+        Some(frame_number)
+    }
+
+    /// Summarize delivery so far
+    #[must_use]
+    pub fn report(&self) -> FlowReport {
+        let received = self.seen.len() as u64;
+        let span = match (self.seen.first(), self.seen.last()) {
+            (Some(first), Some(last)) => u64::from(last - first) + 1,
+            _ => 0,
+        };
+        FlowReport {
+            received,
+            lost: span - received,
+            duplicates: self.duplicates,
+            out_of_order: self.out_of_order,
+            max_latency: self.max_latency,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::audio_pipeline::{AudioPipeline, DeviceFormat};
+    use crate::synthetic::{TestPatternSource, ToneSource};
+    use crate::types::AudioParameters;
+
+    #[test]
+    fn test_tone_detector() {
+        let format = DeviceFormat {
+            sample_rate_hz: 48_000,
+            channels: 2,
+        };
+        let detector = ToneDetector::new(48_000, 2);
+        for hz in [300.0, 440.0, 1_000.0, 3_150.0] {
+            let samples = ToneSource::new(hz, format).next_frames(4_800);
+            let measured = detector.frequency(&samples).unwrap();
+            assert!(
+                (measured - hz).abs() < 0.5,
+                "{hz} Hz measured as {measured}"
+            );
+        }
+
+        assert_eq!(detector.frequency(&[0; 9_600]), None);
+        let quiet = ToneSource::new(440.0, format)
+            .with_amplitude(50)
+            .next_frames(4_800);
+        assert_eq!(detector.frequency(&quiet), None);
+    }
+
+    #[test]
+    fn test_tone_survives_audio_pipeline() {
+        let device = DeviceFormat::default();
+        let mut sender = AudioPipeline::new(AudioParameters::default(), device).unwrap();
+        let mut receiver = AudioPipeline::new(AudioParameters::default(), device).unwrap();
+        let mut tone = ToneSource::new(440.0, device);
+
+        let mut received = Vec::new();
+        for _ in 0..10 {
+            let timestamp = tone.timestamp_ms();
+            let packet = sender.encode(&tone.next_frames(960), timestamp).unwrap();
+            received.extend(receiver.decode(&packet).unwrap());
+        }
+        let detector = ToneDetector::new(device.sample_rate_hz, device.channels);
+        assert!(detector.matches(&received, 440.0, 2.0));
+        assert!(!detector.matches(&received, 880.0, 2.0));
+    }
+
+    #[test]
+    fn test_frame_counter_decode() {
+        for (width, height) in [(64, 16), (320, 240), (333, 77)] {
+            let mut pattern = TestPatternSource::new(width, height, 30).unwrap();
+            for expected in 0..40 {
+                let frame = pattern.next_frame();
+                assert_eq!(decode_frame_counter(&frame), Some(expected));
+            }
+        }
+
+        let mut corrupted = TestPatternSource::new(128, 72, 30).unwrap().next_frame();
+        // Grey out the centre of the first counter cell
+        let offset = (67 * 128 + 2) * 3;
+        corrupted.data[offset..offset + 3].copy_from_slice(&[128; 3]);
+        assert_eq!(decode_frame_counter(&corrupted), None);
+        corrupted.data.pop();
+        assert_eq!(decode_frame_counter(&corrupted), None);
+    }
+
+    #[test]
+    fn test_frame_tracker_report() {
+        let mut tracker = FrameTracker::new();
+        assert_eq!(tracker.report(), FlowReport::default());
+
+        for (frame, latency_ms) in [(10, 20), (11, 25), (13, 40), (12, 90), (13, 30), (15, 20)] {
+            tracker.observe(frame, Duration::from_millis(latency_ms));
+        }
+        let report = tracker.report();
+        assert_eq!(report.received, 5);
+        assert_eq!(report.lost, 1);
+        assert_eq!(report.duplicates, 1);
+        assert_eq!(report.out_of_order, 1);
+        assert_eq!(report.max_latency, Duration::from_millis(90));
+        assert!((report.loss_ratio() - 1.0 / 6.0).abs() < 1e-9);
+        assert!(report.within(0.2, Duration::from_millis(100)));
+        assert!(!report.within(0.1, Duration::from_millis(100)));
+        assert!(!report.within(0.2, Duration::from_millis(50)));
+    }
+}
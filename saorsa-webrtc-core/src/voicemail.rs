@@ -0,0 +1,501 @@
+//! Auto-answer and voicemail for headless deployments
+//!
+//! When enabled, incoming calls that are still ringing after a delay are
+//! answered automatically, a pre-encoded Opus greeting is played to the
+//! caller and the caller's audio is recorded to a file.
+//!
+//! Greetings and recordings use the same packet file format: a sequence of
+//! Opus packets, each prefixed with its length as a big-endian `u16` (the
+//! framing used on QUIC media streams). Packets are played back at a fixed
+//! packet interval, 20ms by default.
+
+use crate::call::CallManager;
+use crate::identity::PeerIdentity;
+use crate::quic_media_transport::framing;
+use crate::types::{CallId, CallState, MediaConstraints};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+/// Default delay before a ringing call is auto-answered (10 seconds)
+pub const DEFAULT_AUTO_ANSWER_DELAY: Duration = Duration::from_secs(10);
+
+/// Default interval between greeting packets (20ms Opus frames)
+pub const DEFAULT_PACKET_INTERVAL: Duration = Duration::from_millis(20);
+
+/// File extension for voicemail recordings
+pub const RECORDING_EXTENSION: &str = "opus-packets";
+
+/// Voicemail errors
+#[derive(Error, Debug)]
+pub enum VoicemailError {
+    /// Reading or writing a packet file failed
+    #[error("I/O error: {0}")]
+    Io(String),
+
+    /// Packet file is malformed
+    #[error("Invalid packet file: {0}")]
+    Format(String),
+
+    /// Call could not be answered
+    #[error("Call error: {0}")]
+    Call(String),
+}
+
+impl From<std::io::Error> for VoicemailError {
+    fn from(err: std::io::Error) -> Self {
+        VoicemailError::Io(err.to_string())
+    }
+}
+
+/// Auto-answer configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutoAnswerConfig {
+    /// Whether incoming calls are auto-answered
+    pub enabled: bool,
+    /// How long a call rings before it is answered
+    pub delay: Duration,
+    /// Pre-encoded Opus greeting played after answering
+    pub greeting: Option<PathBuf>,
+    /// Directory caller audio is recorded to; no recording if `None`
+    pub recording_dir: Option<PathBuf>,
+    /// Interval between greeting packets
+    pub packet_interval: Duration,
+}
+
+impl Default for AutoAnswerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            delay: DEFAULT_AUTO_ANSWER_DELAY,
+            greeting: None,
+            recording_dir: None,
+            packet_interval: DEFAULT_PACKET_INTERVAL,
+        }
+    }
+}
+
+/// Read the packets of a packet file
+///
+/// # Errors
+///
+/// Returns error if the file cannot be read or is truncated
+pub fn read_packets(path: &Path) -> Result<Vec<Vec<u8>>, VoicemailError> {
+    let data = std::fs::read(path)?;
+    let frames = framing::split_frames(&data).map_err(VoicemailError::Format)?;
+    Ok(frames.into_iter().map(<[u8]>::to_vec).collect())
+}
+
+/// Path of a call's recording in `dir`
+#[must_use]
+pub fn recording_path(dir: &Path, call_id: CallId) -> PathBuf {
+    dir.join(format!("{call_id}.{RECORDING_EXTENSION}"))
+}
+
+/// Delete the recordings of the given calls from `dir`
+///
+/// # Returns
+///
+/// Number of recordings deleted.
+///
+/// # Errors
+///
+/// Returns error if a recording exists but cannot be deleted
+pub fn purge_recordings(
+    dir: &Path,
+    calls: impl IntoIterator<Item = CallId>,
+) -> Result<usize, VoicemailError> {
+    let mut deleted = 0;
+    for call_id in calls {
+        match std::fs::remove_file(recording_path(dir, call_id)) {
+            Ok(()) => deleted += 1,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(deleted)
+}
+
+/// Delete the recordings in `dir` last written before `before`
+///
+/// # Returns
+///
+/// Number of recordings deleted.
+///
+/// # Errors
+///
+/// Returns error if the directory cannot be listed or a recording cannot
+/// be deleted
+pub fn purge_recordings_before(dir: &Path, before: DateTime<Utc>) -> Result<usize, VoicemailError> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e.into()),
+    };
+    let mut deleted = 0;
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some(RECORDING_EXTENSION) {
+            continue;
+        }
+        let modified: DateTime<Utc> = std::fs::metadata(&path)?.modified()?.into();
+        if modified < before {
+            std::fs::remove_file(&path)?;
+            deleted += 1;
+        }
+    }
+    Ok(deleted)
+}
+
+/// Records a caller's audio packets to a packet file
+#[derive(Debug)]
+pub struct VoicemailRecorder {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    packets: u64,
+}
+
+impl VoicemailRecorder {
+    /// Create a recording for a call in `dir`
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be created
+    pub fn create(dir: &Path, call_id: CallId) -> Result<Self, VoicemailError> {
+        std::fs::create_dir_all(dir)?;
+        let path = recording_path(dir, call_id);
+        let writer = BufWriter::new(File::create(&path)?);
+        Ok(Self {
+            path,
+            writer,
+            packets: 0,
+        })
+    }
+
+    /// Append a packet
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the packet is too large or the write fails
+    pub fn record(&mut self, packet: &[u8]) -> Result<(), VoicemailError> {
+        let framed = framing::frame_rtp(packet).map_err(VoicemailError::Format)?;
+        self.writer.write_all(&framed)?;
+        self.packets += 1;
+        Ok(())
+    }
+
+    /// Number of packets recorded
+    #[must_use]
+    pub fn packets(&self) -> u64 {
+        self.packets
+    }
+
+    /// Flush the recording and return its path
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the flush fails
+    pub fn finish(mut self) -> Result<PathBuf, VoicemailError> {
+        self.writer.flush()?;
+        Ok(self.path)
+    }
+}
+
+/// Answers ringing calls automatically and records voicemail
+pub struct AutoAnswer<I: PeerIdentity> {
+    config: AutoAnswerConfig,
+    call_manager: Arc<CallManager<I>>,
+    recorders: Arc<Mutex<HashMap<CallId, VoicemailRecorder>>>,
+}
+
+impl<I: PeerIdentity> Clone for AutoAnswer<I> {
+    fn clone(&self) -> Self {
+        Self {
+            config: self.config.clone(),
+            call_manager: Arc::clone(&self.call_manager),
+            recorders: Arc::clone(&self.recorders),
+        }
+    }
+}
+
+impl<I: PeerIdentity> AutoAnswer<I> {
+    /// Create an auto-answer policy for a call manager
+    #[must_use]
+    pub fn new(config: AutoAnswerConfig, call_manager: Arc<CallManager<I>>) -> Self {
+        Self {
+            config,
+            call_manager,
+            recorders: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Get the configuration
+    #[must_use]
+    pub fn config(&self) -> &AutoAnswerConfig {
+        &self.config
+    }
+
+    /// Answer a call once it has rung for the configured delay
+    pub fn schedule(&self, call_id: CallId) -> JoinHandle<()> {
+        let auto_answer = self.clone();
+        let task_guard = self.call_manager.resources().track_task();
+        tokio::spawn(async move {
+            let _task_guard = task_guard;
+            tokio::time::sleep(auto_answer.config.delay).await;
+            if let Err(e) = auto_answer.answer(call_id).await {
+                tracing::warn!(call_id = %call_id, error = %e, "Auto-answer failed");
+            }
+        })
+    }
+
+    /// Answer a ringing call now, then record and play the greeting
+    ///
+    /// # Returns
+    ///
+    /// `false` if the call is no longer ringing.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the call cannot be accepted or the greeting or
+    /// recording file cannot be opened. Failing to send a greeting packet
+    /// only stops playback.
+    pub async fn answer(&self, call_id: CallId) -> Result<bool, VoicemailError> {
+        if self.call_manager.get_call_state(call_id).await != Some(CallState::Calling) {
+            return Ok(false);
+        }
+
+        let greeting = match self.config.greeting {
+            Some(ref path) => read_packets(path)?,
+            None => Vec::new(),
+        };
+
+        self.call_manager
+            .accept_call(call_id, MediaConstraints::audio_only())
+            .await
+            .map_err(|e| VoicemailError::Call(e.to_string()))?;
+        self.call_manager
+            .record_policy_decision(call_id, "auto_answer", true, None);
+        tracing::info!(call_id = %call_id, "Call auto-answered");
+
+        if let Some(ref dir) = self.config.recording_dir {
+            let recorder = VoicemailRecorder::create(dir, call_id)?;
+            self.recorders.lock().await.insert(call_id, recorder);
+        }
+
+        self.play_greeting(call_id, &greeting).await;
+        Ok(true)
+    }
+
+    /// Send greeting packets paced at the packet interval
+    async fn play_greeting(&self, call_id: CallId, greeting: &[Vec<u8>]) {
+        let Some(transport) = self.call_manager.media_transport(call_id).await else {
+            return;
+        };
+
+        let mut interval = tokio::time::interval(self.config.packet_interval);
+        for packet in greeting {
+            interval.tick().await;
+            if let Err(e) = transport.send_audio(packet).await {
+                tracing::warn!(call_id = %call_id, error = %e, "Greeting playback stopped");
+                return;
+            }
+        }
+    }
+
+    /// Record a packet of the caller's audio
+    ///
+    /// # Returns
+    ///
+    /// `false` if the call is not being recorded.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the write fails
+    pub async fn record(&self, call_id: CallId, packet: &[u8]) -> Result<bool, VoicemailError> {
+        match self.recorders.lock().await.get_mut(&call_id) {
+            Some(recorder) => recorder.record(packet).map(|()| true),
+            None => Ok(false),
+        }
+    }
+
+    /// Finish a call's recording
+    ///
+    /// # Returns
+    ///
+    /// The path of the recording, or `None` if the call was not recorded.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the recording cannot be flushed
+    pub async fn finish(&self, call_id: CallId) -> Result<Option<PathBuf>, VoicemailError> {
+        match self.recorders.lock().await.remove(&call_id) {
+            Some(recorder) => recorder.finish().map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::call::CallManagerConfig;
+    use crate::identity::PeerIdentityString;
+    use crate::link_transport::PeerConnection;
+    use crate::types::CallOffer;
+
+    fn write_greeting(path: &Path, packets: &[&[u8]]) {
+        let mut data = Vec::new();
+        for packet in packets {
+            data.extend(framing::frame_rtp(packet).unwrap());
+        }
+        std::fs::write(path, data).unwrap();
+    }
+
+    async fn ringing_call(manager: &CallManager<PeerIdentityString>) -> CallId {
+        let call_id = CallId::new();
+        manager
+            .handle_incoming_call(CallOffer {
+                call_id,
+                caller: PeerIdentityString::new("caller"),
+                callee: PeerIdentityString::new("voicemail"),
+                sdp: String::new(),
+                media_types: MediaConstraints::audio_only().to_media_types(),
+                timestamp: chrono::Utc::now(),
+            })
+            .await
+            .unwrap();
+        manager
+            .connect_quic_transport(
+                call_id,
+                PeerConnection {
+                    peer_id: "caller".to_string(),
+                    remote_addr: "127.0.0.1:9000".parse().unwrap(),
+                },
+            )
+            .await
+            .unwrap();
+        call_id
+    }
+
+    #[test]
+    fn test_recording_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let call_id = CallId::new();
+
+        let mut recorder = VoicemailRecorder::create(dir.path(), call_id).unwrap();
+        recorder.record(&[1, 2, 3]).unwrap();
+        recorder.record(&[]).unwrap();
+        assert_eq!(recorder.packets(), 2);
+        let path = recorder.finish().unwrap();
+
+        assert_eq!(read_packets(&path).unwrap(), vec![vec![1, 2, 3], vec![]]);
+    }
+
+    #[test]
+    fn test_purge_recordings() {
+        let dir = tempfile::tempdir().unwrap();
+        let (kept, purged) = (CallId::new(), CallId::new());
+        for call_id in [kept, purged] {
+            VoicemailRecorder::create(dir.path(), call_id)
+                .unwrap()
+                .finish()
+                .unwrap();
+        }
+        let greeting = dir.path().join("greeting");
+        std::fs::write(&greeting, []).unwrap();
+
+        assert_eq!(
+            purge_recordings(dir.path(), [purged, CallId::new()]).unwrap(),
+            1
+        );
+        assert!(recording_path(dir.path(), kept).exists());
+        assert!(!recording_path(dir.path(), purged).exists());
+
+        let later = Utc::now() + chrono::Duration::seconds(1);
+        assert_eq!(purge_recordings_before(dir.path(), later).unwrap(), 1);
+        assert!(!recording_path(dir.path(), kept).exists());
+        assert!(greeting.exists());
+        assert_eq!(
+            purge_recordings_before(&dir.path().join("missing"), later).unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_truncated_packet_file_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("greeting");
+        std::fs::write(&path, [0, 5, 1]).unwrap();
+        assert!(matches!(
+            read_packets(&path),
+            Err(VoicemailError::Format(_))
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_auto_answer_plays_greeting_and_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let greeting = dir.path().join("greeting.opus-packets");
+        write_greeting(&greeting, &[&[0xF8, 1], &[0xF8, 2], &[0xF8, 3]]);
+
+        let manager = Arc::new(
+            CallManager::<PeerIdentityString>::new(CallManagerConfig::default())
+                .await
+                .unwrap(),
+        );
+        let auto_answer = AutoAnswer::new(
+            AutoAnswerConfig {
+                enabled: true,
+                delay: Duration::from_secs(5),
+                greeting: Some(greeting),
+                recording_dir: Some(dir.path().join("voicemail")),
+                ..Default::default()
+            },
+            Arc::clone(&manager),
+        );
+
+        let call_id = ringing_call(&manager).await;
+        let handle = auto_answer.schedule(call_id);
+
+        tokio::time::advance(Duration::from_secs(4)).await;
+        assert_eq!(
+            manager.get_call_state(call_id).await,
+            Some(CallState::Calling)
+        );
+
+        handle.await.unwrap();
+        assert_eq!(
+            manager.get_call_state(call_id).await,
+            Some(CallState::Connected)
+        );
+        let transport = manager.media_transport(call_id).await.unwrap();
+        assert_eq!(transport.stats().await.packets_sent, 3);
+
+        assert!(auto_answer.record(call_id, &[0xF8, 9]).await.unwrap());
+        let path = auto_answer.finish(call_id).await.unwrap().unwrap();
+        assert_eq!(read_packets(&path).unwrap(), vec![vec![0xF8, 9]]);
+        assert!(!auto_answer.record(call_id, &[0xF8, 9]).await.unwrap());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_auto_answer_skips_answered_calls() {
+        let manager = Arc::new(
+            CallManager::<PeerIdentityString>::new(CallManagerConfig::default())
+                .await
+                .unwrap(),
+        );
+        let auto_answer = AutoAnswer::new(AutoAnswerConfig::default(), Arc::clone(&manager));
+
+        let call_id = ringing_call(&manager).await;
+        manager.reject_call(call_id).await.unwrap();
+        assert!(!auto_answer.answer(call_id).await.unwrap());
+    }
+}
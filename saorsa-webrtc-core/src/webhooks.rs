@@ -0,0 +1,702 @@
+//! Call notification webhooks
+//!
+//! Headless deployments can have the service POST a JSON [`WebhookPayload`]
+//! to configured URLs when a call comes in, is missed or ends, so bots and
+//! dashboards can react to call activity without embedding the crate.
+//!
+//! Each request carries the Unix time it was sent in [`TIMESTAMP_HEADER`]
+//! and, when a secret is configured, an HMAC-SHA256 of the timestamp and
+//! body in [`SIGNATURE_HEADER`]; receivers check it with
+//! [`verify_signature`]. Failed deliveries are retried with exponential
+//! backoff.
+//!
+//! Requests go through a [`WebhookSender`]. The `webhooks` feature provides
+//! [`HttpWebhookSender`]; embedders without it supply their own.
+
+use crate::identity::PeerIdentity;
+use crate::types::{CallEvent, CallId, CallMetadata, MediaType};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// Header with the Unix time the request was sent, in seconds
+pub const TIMESTAMP_HEADER: &str = "x-saorsa-timestamp";
+
+/// Header with `sha256=` and the hex HMAC of `"{timestamp}.{body}"`
+pub const SIGNATURE_HEADER: &str = "x-saorsa-signature";
+
+/// Header with the event name, e.g. `incoming_call`
+pub const EVENT_HEADER: &str = "x-saorsa-event";
+
+/// Webhook errors
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum WebhookError {
+    /// The request could not be sent or no response arrived
+    #[error("Webhook transport error: {0}")]
+    Transport(String),
+
+    /// The receiver answered with a status other than 2xx
+    #[error("Webhook rejected with status {0}")]
+    Status(u16),
+
+    /// The payload could not be encoded
+    #[error("Webhook encoding error: {0}")]
+    Encoding(String),
+}
+
+impl WebhookError {
+    /// Check if sending again may succeed
+    ///
+    /// Transport errors, rate limiting and server errors are retried; other
+    /// statuses mean the receiver will not take the payload.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Transport(_) => true,
+            Self::Status(status) => *status == 429 || *status >= 500,
+            Self::Encoding(_) => false,
+        }
+    }
+}
+
+/// Webhook configuration
+#[derive(Clone, PartialEq, Eq)]
+pub struct WebhookConfig {
+    /// URLs every notification is POSTed to
+    pub urls: Vec<String>,
+    /// Key for signing requests; unsigned if `None`
+    pub secret: Option<String>,
+    /// Attempts per URL, including the first
+    pub max_attempts: u32,
+    /// Wait before the first retry
+    pub initial_backoff: Duration,
+    /// Longest wait between retries
+    pub max_backoff: Duration,
+    /// How long to wait for each response
+    pub timeout: Duration,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            urls: Vec::new(),
+            secret: None,
+            max_attempts: 5,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl fmt::Debug for WebhookConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebhookConfig")
+            .field("urls", &self.urls)
+            .field("secret", &self.secret.as_ref().map(|_| "<redacted>"))
+            .field("max_attempts", &self.max_attempts)
+            .field("initial_backoff", &self.initial_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+impl WebhookConfig {
+    /// Wait before retry number `attempt`, counting from 1
+    #[must_use]
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// Why an incoming call was missed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MissedReason {
+    /// Do-not-disturb was turned on by hand
+    DoNotDisturb,
+    /// It arrived during quiet hours
+    QuietHours,
+    /// It rang but ended without being answered
+    Unanswered,
+}
+
+/// Call activity a webhook reports
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// A call is ringing
+    IncomingCall {
+        /// Call identifier
+        call_id: CallId,
+        /// Who is calling
+        caller: String,
+        /// Media the caller offered
+        media_types: Vec<MediaType>,
+    },
+    /// An incoming call was not answered
+    MissedCall {
+        /// Call identifier
+        call_id: CallId,
+        /// Who called
+        caller: String,
+        /// Why it was missed
+        reason: MissedReason,
+    },
+    /// A call ended
+    CallEnded {
+        /// Call identifier
+        call_id: CallId,
+        /// Remote peer, if the call was seen starting
+        peer: Option<String>,
+        /// Time from answer to hang-up, if it was answered
+        duration_ms: Option<u64>,
+        /// Metadata the application attached to the call
+        #[serde(default, skip_serializing_if = "CallMetadata::is_empty")]
+        metadata: CallMetadata,
+    },
+}
+
+impl WebhookEvent {
+    /// Name of the event, as in the `event` field and [`EVENT_HEADER`]
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::IncomingCall { .. } => "incoming_call",
+            Self::MissedCall { .. } => "missed_call",
+            Self::CallEnded { .. } => "call_ended",
+        }
+    }
+}
+
+/// Body of a webhook request
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookPayload {
+    /// Unique per notification, so receivers can drop retried duplicates
+    pub id: uuid::Uuid,
+    /// When the activity happened
+    pub timestamp: DateTime<Utc>,
+    /// What happened
+    #[serde(flatten)]
+    pub event: WebhookEvent,
+}
+
+impl WebhookPayload {
+    /// Payload for an event happening now
+    #[must_use]
+    pub fn new(event: WebhookEvent) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4(),
+            timestamp: Utc::now(),
+            event,
+        }
+    }
+}
+
+/// Compute the [`SIGNATURE_HEADER`] value of a request
+///
+/// # Errors
+///
+/// Returns [`WebhookError::Encoding`] if the secret cannot key the HMAC
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> Result<String, WebhookError> {
+    let digest = signing_mac(secret, timestamp, body)?
+        .finalize()
+        .into_bytes();
+    let hex: String = digest.iter().map(|byte| format!("{byte:02x}")).collect();
+    Ok(format!("sha256={hex}"))
+}
+
+/// Check a [`SIGNATURE_HEADER`] value in constant time
+#[must_use]
+pub fn verify_signature(secret: &str, timestamp: i64, body: &[u8], signature: &str) -> bool {
+    let Some(hex) = signature.strip_prefix("sha256=") else {
+        return false;
+    };
+    if hex.len() % 2 != 0 || !hex.is_ascii() {
+        return false;
+    }
+    let digest: Option<Vec<u8>> = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect();
+    match (digest, signing_mac(secret, timestamp, body)) {
+        (Some(digest), Ok(mac)) => mac.verify_slice(&digest).is_ok(),
+        _ => false,
+    }
+}
+
+/// HMAC over `"{timestamp}.{body}"`
+fn signing_mac(secret: &str, timestamp: i64, body: &[u8]) -> Result<Hmac<Sha256>, WebhookError> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .map_err(|e| WebhookError::Encoding(e.to_string()))?;
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    Ok(mac)
+}
+
+/// One HTTP POST of a webhook
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookRequest {
+    /// Where to POST
+    pub url: String,
+    /// Headers besides `content-type: application/json`
+    pub headers: Vec<(&'static str, String)>,
+    /// JSON body
+    pub body: Vec<u8>,
+    /// How long to wait for the response
+    pub timeout: Duration,
+}
+
+/// Sends webhook requests over HTTP
+#[async_trait]
+pub trait WebhookSender: fmt::Debug + Send + Sync {
+    /// POST the request, returning the response status
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WebhookError::Transport`] if no response arrived
+    async fn send(&self, request: &WebhookRequest) -> Result<u16, WebhookError>;
+}
+
+/// Webhook sender shared with the service
+pub type SharedWebhookSender = Arc<dyn WebhookSender>;
+
+/// [`WebhookSender`] over `reqwest`
+#[cfg(feature = "webhooks")]
+#[derive(Debug, Clone, Default)]
+pub struct HttpWebhookSender {
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "webhooks")]
+impl HttpWebhookSender {
+    /// Create a sender with its own connection pool
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(feature = "webhooks")]
+#[async_trait]
+impl WebhookSender for HttpWebhookSender {
+    async fn send(&self, request: &WebhookRequest) -> Result<u16, WebhookError> {
+        let mut builder = self
+            .client
+            .post(&request.url)
+            .timeout(request.timeout)
+            .header("content-type", "application/json")
+            .body(request.body.clone());
+        for (name, value) in &request.headers {
+            builder = builder.header(*name, value);
+        }
+        let response = builder
+            .send()
+            .await
+            .map_err(|e| WebhookError::Transport(e.to_string()))?;
+        Ok(response.status().as_u16())
+    }
+}
+
+/// Delivers notifications to the configured URLs
+#[derive(Debug, Clone)]
+pub struct WebhookNotifier {
+    config: WebhookConfig,
+    sender: SharedWebhookSender,
+}
+
+impl WebhookNotifier {
+    /// Create a notifier
+    #[must_use]
+    pub fn new(config: WebhookConfig, sender: SharedWebhookSender) -> Self {
+        Self { config, sender }
+    }
+
+    /// Configuration in use
+    #[must_use]
+    pub fn config(&self) -> &WebhookConfig {
+        &self.config
+    }
+
+    /// Build the signed request of a payload for one URL
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WebhookError::Encoding`] if the payload cannot be encoded
+    pub fn request(
+        &self,
+        url: &str,
+        payload: &WebhookPayload,
+    ) -> Result<WebhookRequest, WebhookError> {
+        let body =
+            serde_json::to_vec(payload).map_err(|e| WebhookError::Encoding(e.to_string()))?;
+        let timestamp = Utc::now().timestamp();
+        let mut headers = vec![
+            (EVENT_HEADER, payload.event.name().to_string()),
+            (TIMESTAMP_HEADER, timestamp.to_string()),
+        ];
+        if let Some(secret) = &self.config.secret {
+            headers.push((SIGNATURE_HEADER, sign(secret, timestamp, &body)?));
+        }
+        Ok(WebhookRequest {
+            url: url.to_string(),
+            headers,
+            body,
+            timeout: self.config.timeout,
+        })
+    }
+
+    /// POST a payload to one URL, retrying failures that may pass
+    ///
+    /// Each attempt is signed afresh so its timestamp is current.
+    ///
+    /// # Errors
+    ///
+    /// Returns the last error once the attempts are used up or the receiver
+    /// rejects the payload
+    pub async fn deliver(&self, url: &str, payload: &WebhookPayload) -> Result<(), WebhookError> {
+        let mut attempt = 1;
+        loop {
+            let result = match self.sender.send(&self.request(url, payload)?).await {
+                Ok(status) if (200..300).contains(&status) => Ok(()),
+                Ok(status) => Err(WebhookError::Status(status)),
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(()) => return Ok(()),
+                Err(e) if e.is_retryable() && attempt < self.config.max_attempts.max(1) => {
+                    let wait = self.config.backoff(attempt);
+                    tracing::debug!(url, attempt, error = %e, ?wait, "Retrying webhook");
+                    tokio::time::sleep(wait).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Deliver an event to every URL in the background
+    pub fn notify(&self, event: WebhookEvent) {
+        let payload = Arc::new(WebhookPayload::new(event));
+        for url in &self.config.urls {
+            let notifier = self.clone();
+            let url = url.clone();
+            let payload = Arc::clone(&payload);
+            tokio::spawn(async move {
+                if let Err(e) = notifier.deliver(&url, &payload).await {
+                    tracing::warn!(url, event = payload.event.name(), error = %e, "Webhook delivery failed");
+                }
+            });
+        }
+    }
+}
+
+/// What is known about a call the webhooks have seen start
+#[derive(Debug)]
+struct TrackedCall {
+    peer: String,
+    incoming: bool,
+    answered_at: Option<Instant>,
+    metadata: CallMetadata,
+}
+
+/// Turns call events into webhook events
+///
+/// Remembers calls from their first event so a missed or ended call can be
+/// reported with its peer and duration.
+#[derive(Debug, Default)]
+pub struct CallActivity {
+    calls: HashMap<CallId, TrackedCall>,
+}
+
+impl CallActivity {
+    /// Create a tracker that has seen no calls
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Webhook event for a call event, if it is one webhooks report
+    pub fn on_call_event<I: PeerIdentity>(&mut self, event: &CallEvent<I>) -> Option<WebhookEvent> {
+        match event {
+            CallEvent::IncomingCall { offer } | CallEvent::CallWaiting { offer, .. } => {
+                let caller = offer.caller.to_string_repr();
+                self.calls.insert(
+                    offer.call_id,
+                    TrackedCall {
+                        peer: caller.clone(),
+                        incoming: true,
+                        answered_at: None,
+                        metadata: CallMetadata::new(),
+                    },
+                );
+                Some(WebhookEvent::IncomingCall {
+                    call_id: offer.call_id,
+                    caller,
+                    media_types: offer.media_types.clone(),
+                })
+            }
+            CallEvent::CallInitiated {
+                call_id, callee, ..
+            } => {
+                self.calls.insert(
+                    *call_id,
+                    TrackedCall {
+                        peer: callee.to_string_repr(),
+                        incoming: false,
+                        answered_at: None,
+                        metadata: CallMetadata::new(),
+                    },
+                );
+                None
+            }
+            CallEvent::CallAccepted { call_id, .. } => {
+                if let Some(call) = self.calls.get_mut(call_id) {
+                    call.answered_at.get_or_insert_with(Instant::now);
+                }
+                None
+            }
+            CallEvent::MetadataChanged {
+                call_id,
+                key,
+                value,
+            } => {
+                if let Some(call) = self.calls.get_mut(call_id) {
+                    call.metadata.insert(key.clone(), value.clone());
+                }
+                None
+            }
+            CallEvent::CallCancelled { call_id, .. } => self.missed_if_unanswered(*call_id),
+            CallEvent::CallEnded { call_id } => self.missed_if_unanswered(*call_id).or_else(|| {
+                let call = self.calls.remove(call_id);
+                Some(WebhookEvent::CallEnded {
+                    call_id: *call_id,
+                    duration_ms: call
+                        .as_ref()
+                        .and_then(|call| call.answered_at)
+                        .map(|at| u64::try_from(at.elapsed().as_millis()).unwrap_or(u64::MAX)),
+                    metadata: call
+                        .as_ref()
+                        .map(|call| call.metadata.clone())
+                        .unwrap_or_default(),
+                    peer: call.map(|call| call.peer),
+                })
+            }),
+            _ => None,
+        }
+    }
+
+    /// Missed-call event for a call do-not-disturb kept from ringing
+    #[must_use]
+    pub fn on_missed_call<I: PeerIdentity>(
+        &mut self,
+        missed: &crate::dnd::MissedCall<I>,
+    ) -> WebhookEvent {
+        self.calls.remove(&missed.call_id);
+        WebhookEvent::MissedCall {
+            call_id: missed.call_id,
+            caller: missed.caller.to_string_repr(),
+            reason: match missed.reason {
+                crate::dnd::DndReason::Manual => MissedReason::DoNotDisturb,
+                crate::dnd::DndReason::QuietHours => MissedReason::QuietHours,
+            },
+        }
+    }
+
+    /// Report an incoming call that stopped before it was answered
+    fn missed_if_unanswered(&mut self, call_id: CallId) -> Option<WebhookEvent> {
+        let call = self.calls.get(&call_id)?;
+        if !call.incoming || call.answered_at.is_some() {
+            return None;
+        }
+        let call = self.calls.remove(&call_id)?;
+        Some(WebhookEvent::MissedCall {
+            call_id,
+            caller: call.peer,
+            reason: MissedReason::Unanswered,
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::identity::PeerIdentityString;
+    use crate::types::CallOffer;
+
+    /// Sender answering with queued statuses, recording each request
+    #[derive(Debug, Default)]
+    struct ScriptedSender {
+        statuses: parking_lot::Mutex<Vec<Result<u16, WebhookError>>>,
+        requests: parking_lot::Mutex<Vec<WebhookRequest>>,
+    }
+
+    #[async_trait]
+    impl WebhookSender for ScriptedSender {
+        async fn send(&self, request: &WebhookRequest) -> Result<u16, WebhookError> {
+            self.requests.lock().push(request.clone());
+            self.statuses.lock().remove(0)
+        }
+    }
+
+    #[test]
+    fn test_signature_round_trip() {
+        let signature = sign("s3cret", 1_700_000_000, b"{}").unwrap();
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature.len(), "sha256=".len() + 64);
+        assert!(verify_signature("s3cret", 1_700_000_000, b"{}", &signature));
+        assert!(!verify_signature("other", 1_700_000_000, b"{}", &signature));
+        assert!(!verify_signature(
+            "s3cret",
+            1_700_000_001,
+            b"{}",
+            &signature
+        ));
+        assert!(!verify_signature(
+            "s3cret",
+            1_700_000_000,
+            b"{}",
+            "sha256=zz"
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_deliver_retries_server_errors() {
+        let sender = Arc::new(ScriptedSender::default());
+        *sender.statuses.lock() = vec![
+            Err(WebhookError::Transport("refused".to_string())),
+            Ok(503),
+            Ok(204),
+        ];
+        let notifier = WebhookNotifier::new(
+            WebhookConfig {
+                urls: vec!["https://example.com/hook".to_string()],
+                secret: Some("s3cret".to_string()),
+                ..Default::default()
+            },
+            Arc::clone(&sender) as SharedWebhookSender,
+        );
+        let payload = WebhookPayload::new(WebhookEvent::CallEnded {
+            call_id: CallId::new(),
+            peer: None,
+            duration_ms: None,
+            metadata: CallMetadata::new(),
+        });
+        notifier
+            .deliver("https://example.com/hook", &payload)
+            .await
+            .unwrap();
+
+        let requests = sender.requests.lock().clone();
+        assert_eq!(requests.len(), 3);
+        let header = |name: &str| {
+            requests[2]
+                .headers
+                .iter()
+                .find(|(header, _)| *header == name)
+                .map(|(_, value)| value.clone())
+                .unwrap()
+        };
+        assert_eq!(header(EVENT_HEADER), "call_ended");
+        let timestamp: i64 = header(TIMESTAMP_HEADER).parse().unwrap();
+        assert!(verify_signature(
+            "s3cret",
+            timestamp,
+            &requests[2].body,
+            &header(SIGNATURE_HEADER)
+        ));
+
+        // A rejected payload is not sent again
+        *sender.statuses.lock() = vec![Ok(400)];
+        assert_eq!(
+            notifier.deliver("https://example.com/hook", &payload).await,
+            Err(WebhookError::Status(400))
+        );
+        assert_eq!(sender.requests.lock().len(), 4);
+    }
+
+    #[test]
+    fn test_call_activity_reports_missed_and_ended_calls() {
+        let mut activity = CallActivity::new();
+        let offer = |call_id| CallOffer {
+            call_id,
+            caller: PeerIdentityString::new("alice"),
+            callee: PeerIdentityString::new("me"),
+            sdp: String::new(),
+            media_types: vec![MediaType::Audio],
+            timestamp: Utc::now(),
+        };
+
+        let unanswered = CallId::new();
+        let event = activity
+            .on_call_event(&CallEvent::IncomingCall {
+                offer: offer(unanswered),
+            })
+            .unwrap();
+        assert_eq!(event.name(), "incoming_call");
+        assert_eq!(
+            activity.on_call_event::<PeerIdentityString>(&CallEvent::CallEnded {
+                call_id: unanswered
+            }),
+            Some(WebhookEvent::MissedCall {
+                call_id: unanswered,
+                caller: "alice".to_string(),
+                reason: MissedReason::Unanswered,
+            })
+        );
+
+        let answered = CallId::new();
+        activity.on_call_event(&CallEvent::IncomingCall {
+            offer: offer(answered),
+        });
+        activity.on_call_event::<PeerIdentityString>(&CallEvent::CallAccepted {
+            call_id: answered,
+            answer: crate::types::CallAnswer {
+                call_id: answered,
+                sdp: String::new(),
+                accepted: true,
+                timestamp: Utc::now(),
+            },
+        });
+        activity.on_call_event::<PeerIdentityString>(&CallEvent::MetadataChanged {
+            call_id: answered,
+            key: "ticket".to_string(),
+            value: "SUP-42".to_string(),
+        });
+        let Some(WebhookEvent::CallEnded {
+            peer,
+            duration_ms,
+            metadata,
+            ..
+        }) = activity
+            .on_call_event::<PeerIdentityString>(&CallEvent::CallEnded { call_id: answered })
+        else {
+            unreachable!("answered call should end");
+        };
+        assert_eq!(peer.as_deref(), Some("alice"));
+        assert!(duration_ms.is_some());
+        assert_eq!(metadata.get("ticket").map(String::as_str), Some("SUP-42"));
+
+        let json = serde_json::to_value(WebhookPayload::new(WebhookEvent::MissedCall {
+            call_id: unanswered,
+            caller: "alice".to_string(),
+            reason: MissedReason::QuietHours,
+        }))
+        .unwrap();
+        assert_eq!(json["event"], "missed_call");
+        assert_eq!(json["reason"], "quiet_hours");
+    }
+}
@@ -0,0 +1,583 @@
+//! Signaling wire formats
+//!
+//! Signaling messages can be encoded as JSON, CBOR, or postcard. JSON is the
+//! baseline that every peer understands; the compact binary formats are used
+//! only after both peers have advertised support in a [`ProtocolHello`].
+//!
+//! # Framing
+//!
+//! JSON frames are sent bare so peers that predate negotiation can still read
+//! them. Every other frame starts with a single tag byte identifying its
+//! contents, which lets the receiver decode any frame without tracking which
+//! format was negotiated. Frames above the negotiated threshold may be
+//! further wrapped by [`Compression`].
+
+use crate::compression::{Compression, CompressionConfig};
+use crate::signaling::SignalingMessage;
+use saorsa_webrtc_proto::wire::{self, CompactMessage, TAG_CBOR, TAG_HELLO, TAG_POSTCARD};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Current signaling protocol version advertised in [`ProtocolHello`]
+pub const SIGNALING_PROTOCOL_VERSION: u16 = 1;
+
+/// Wire format errors
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum WireFormatError {
+    /// Failed to encode a message
+    #[error("Encode error: {0}")]
+    Encode(String),
+
+    /// Failed to decode a message
+    #[error("Decode error: {0}")]
+    Decode(String),
+
+    /// Frame starts with an unknown tag byte
+    #[error("Unknown frame tag: {0:#04x}")]
+    UnknownTag(u8),
+
+    /// Frame contains no data
+    #[error("Empty frame")]
+    EmptyFrame,
+
+    /// Failed to compress or decompress a frame
+    #[error("Compression error: {0}")]
+    Compression(String),
+
+    /// Decompressed frame exceeds the size limit
+    #[error("Decompressed frame exceeds maximum of {0} bytes")]
+    DecompressedTooLarge(usize),
+}
+
+/// Serialization format for signaling messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WireFormat {
+    /// JSON (default, understood by all peers)
+    Json,
+    /// CBOR (RFC 8949)
+    Cbor,
+    /// postcard (most compact)
+    Postcard,
+}
+
+impl Default for WireFormat {
+    fn default() -> Self {
+        Self::Json
+    }
+}
+
+impl WireFormat {
+    /// All supported formats, most compact first
+    pub const ALL: [WireFormat; 3] = [Self::Postcard, Self::Cbor, Self::Json];
+
+    /// Encode a signaling message into a frame
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the message cannot be serialized
+    pub fn encode(self, message: &SignalingMessage) -> Result<Vec<u8>, WireFormatError> {
+        match self {
+            Self::Json => {
+                serde_json::to_vec(message).map_err(|e| WireFormatError::Encode(e.to_string()))
+            }
+            Self::Cbor => {
+                let mut frame = vec![TAG_CBOR];
+                ciborium::ser::into_writer(&CompactMessage::from(message.clone()), &mut frame)
+                    .map_err(|e| WireFormatError::Encode(e.to_string()))?;
+                Ok(frame)
+            }
+            Self::Postcard => {
+                wire::encode_postcard(message).map_err(|e| WireFormatError::Encode(e.to_string()))
+            }
+        }
+    }
+
+    /// Pick the format to send to a remote peer
+    ///
+    /// Returns the first format in `local` (in preference order) that the
+    /// remote also supports, falling back to JSON.
+    #[must_use]
+    pub fn negotiate(local: &[WireFormat], remote: &[WireFormat]) -> WireFormat {
+        local
+            .iter()
+            .copied()
+            .find(|format| remote.contains(format))
+            .unwrap_or(Self::Json)
+    }
+}
+
+/// Protocol version handshake
+///
+/// Sent by each peer to advertise the signaling protocol version, the wire
+/// formats it can decode, and the compression algorithms it accepts. Hello
+/// frames are always JSON-encoded so that any future protocol version can
+/// still read them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtocolHello {
+    /// Signaling protocol version
+    pub version: u16,
+    /// Supported wire formats in preference order
+    pub wire_formats: Vec<WireFormat>,
+    /// Supported compression algorithms in preference order
+    #[serde(default)]
+    pub compression: Vec<Compression>,
+}
+
+impl Default for ProtocolHello {
+    fn default() -> Self {
+        Self {
+            version: SIGNALING_PROTOCOL_VERSION,
+            wire_formats: WireFormat::ALL.to_vec(),
+            compression: Compression::ALL.to_vec(),
+        }
+    }
+}
+
+impl ProtocolHello {
+    /// Encode into a hello frame
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the hello cannot be serialized
+    pub fn encode(&self) -> Result<Vec<u8>, WireFormatError> {
+        let mut frame = vec![TAG_HELLO];
+        serde_json::to_writer(&mut frame, self)
+            .map_err(|e| WireFormatError::Encode(e.to_string()))?;
+        Ok(frame)
+    }
+}
+
+/// Per-peer frame encoding negotiated in the protocol handshake
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameCodec {
+    /// Serialization format
+    pub format: WireFormat,
+    /// Compression algorithm, if both peers support one
+    pub compression: Option<Compression>,
+    /// Frames larger than this many bytes are compressed
+    pub compression_threshold: usize,
+}
+
+impl Default for FrameCodec {
+    fn default() -> Self {
+        Self {
+            format: WireFormat::Json,
+            compression: None,
+            compression_threshold: crate::compression::DEFAULT_COMPRESSION_THRESHOLD,
+        }
+    }
+}
+
+impl FrameCodec {
+    /// Negotiate the codec for a peer from the local settings and its hello
+    #[must_use]
+    pub fn negotiate(
+        wire_formats: &[WireFormat],
+        compression: &CompressionConfig,
+        remote: &ProtocolHello,
+    ) -> Self {
+        Self {
+            format: WireFormat::negotiate(wire_formats, &remote.wire_formats),
+            compression: Compression::negotiate(&compression.algorithms, &remote.compression),
+            compression_threshold: compression.threshold,
+        }
+    }
+
+    /// Encode a signaling message, compressing it if above the threshold
+    ///
+    /// # Errors
+    ///
+    /// Returns error if serialization or compression fails
+    pub fn encode(&self, message: &SignalingMessage) -> Result<Vec<u8>, WireFormatError> {
+        let frame = self.format.encode(message)?;
+        match self.compression {
+            Some(algorithm) if frame.len() > self.compression_threshold => {
+                algorithm.compress(&frame)
+            }
+            _ => Ok(frame),
+        }
+    }
+}
+
+/// A decoded signaling frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WireFrame {
+    /// A signaling message, along with the format it was encoded in
+    Message(SignalingMessage, WireFormat),
+    /// A protocol version handshake
+    Hello(ProtocolHello),
+}
+
+/// Decode a signaling frame in any supported format
+///
+/// # Errors
+///
+/// Returns error if the frame is empty, has an unknown tag, cannot be
+/// decompressed, or cannot be deserialized
+pub fn decode_frame(data: &[u8]) -> Result<WireFrame, WireFormatError> {
+    let (&tag, body) = data.split_first().ok_or(WireFormatError::EmptyFrame)?;
+    match Compression::from_tag(tag) {
+        Some(algorithm) => decode_uncompressed(&algorithm.decompress(body)?),
+        None => decode_uncompressed(data),
+    }
+}
+
+/// Decode a frame that is not wrapped in compression
+fn decode_uncompressed(data: &[u8]) -> Result<WireFrame, WireFormatError> {
+    let (&tag, body) = data.split_first().ok_or(WireFormatError::EmptyFrame)?;
+    match tag {
+        b'{' => serde_json::from_slice(data)
+            .map(|message| WireFrame::Message(message, WireFormat::Json))
+            .map_err(|e| WireFormatError::Decode(e.to_string())),
+        TAG_CBOR => ciborium::de::from_reader::<CompactMessage, _>(body)
+            .map(|message| WireFrame::Message(message.into(), WireFormat::Cbor))
+            .map_err(|e| WireFormatError::Decode(e.to_string())),
+        TAG_POSTCARD => wire::decode_postcard(data)
+            .map(|message| WireFrame::Message(message, WireFormat::Postcard))
+            .map_err(|e| WireFormatError::Decode(e.to_string())),
+        TAG_HELLO => serde_json::from_slice(body)
+            .map(WireFrame::Hello)
+            .map_err(|e| WireFormatError::Decode(e.to_string())),
+        other => Err(WireFormatError::UnknownTag(other)),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::panic)]
+mod tests {
+    use super::*;
+    use crate::types::{CallProgress, MediaType, TrackInfo, TrackSource, VideoLayer};
+
+    fn sample_messages() -> Vec<SignalingMessage> {
+        vec![
+            SignalingMessage::Offer {
+                session_id: "s1".to_string(),
+                sdp: "v=0".to_string(),
+                quic_endpoint: Some("127.0.0.1:9000".parse().unwrap()),
+            },
+            SignalingMessage::IceCandidate {
+                session_id: "s2".to_string(),
+                candidate: "candidate:1".to_string(),
+                sdp_mid: Some("0".to_string()),
+                sdp_mline_index: Some(0),
+            },
+            SignalingMessage::CapabilityExchange {
+                session_id: "s3".to_string(),
+                audio: true,
+                video: false,
+                data_channel: true,
+                max_bandwidth_kbps: 2500,
+                quic_endpoint: Some("[::1]:9000".parse().unwrap()),
+            },
+            SignalingMessage::Bye {
+                session_id: "s4".to_string(),
+                reason: None,
+            },
+            SignalingMessage::TrackUpdate {
+                session_id: "s5".to_string(),
+                tracks: vec![
+                    TrackInfo::new(0, MediaType::Video, TrackSource::Camera, "Front camera"),
+                    TrackInfo::new(1, MediaType::Video, TrackSource::Slides, "Q3 review"),
+                ],
+            },
+            SignalingMessage::HandoffJoin {
+                session_id: "s6".to_string(),
+                token: "eyJjYWxsX2lkIjoi".to_string(),
+            },
+            SignalingMessage::Cancel {
+                session_id: "s7".to_string(),
+                reason: Some("answered elsewhere".to_string()),
+            },
+            SignalingMessage::Progress {
+                session_id: "s8".to_string(),
+                progress: CallProgress::Queued { position: Some(3) },
+            },
+            SignalingMessage::AudioOnly {
+                session_id: "s9".to_string(),
+                active: true,
+            },
+            SignalingMessage::LayerSelection {
+                session_id: "s10".to_string(),
+                track_id: 1,
+                layer: VideoLayer::new(2, 1),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_roundtrip_all_formats() {
+        for format in WireFormat::ALL {
+            for message in sample_messages() {
+                let frame = format.encode(&message).unwrap();
+                let decoded = decode_frame(&frame).unwrap();
+                assert_eq!(decoded, WireFrame::Message(message, format));
+            }
+        }
+    }
+
+    #[test]
+    fn test_json_frame_is_untagged() {
+        let message = &sample_messages()[0];
+        let frame = WireFormat::Json.encode(message).unwrap();
+        assert_eq!(frame, serde_json::to_vec(message).unwrap());
+    }
+
+    #[test]
+    fn test_binary_formats_are_smaller() {
+        let message = &sample_messages()[2];
+        let json = WireFormat::Json.encode(message).unwrap().len();
+        let cbor = WireFormat::Cbor.encode(message).unwrap().len();
+        let postcard = WireFormat::Postcard.encode(message).unwrap().len();
+        assert!(postcard < cbor);
+        assert!(cbor < json);
+    }
+
+    #[test]
+    fn test_hello_roundtrip() {
+        let hello = ProtocolHello::default();
+        let frame = hello.encode().unwrap();
+        assert_eq!(decode_frame(&frame).unwrap(), WireFrame::Hello(hello));
+    }
+
+    #[test]
+    fn test_negotiate_prefers_local_order() {
+        let local = [WireFormat::Cbor, WireFormat::Postcard, WireFormat::Json];
+        let remote = [WireFormat::Postcard, WireFormat::Cbor];
+        assert_eq!(WireFormat::negotiate(&local, &remote), WireFormat::Cbor);
+    }
+
+    #[test]
+    fn test_negotiate_falls_back_to_json() {
+        let local = [WireFormat::Postcard];
+        let remote = [WireFormat::Cbor];
+        assert_eq!(WireFormat::negotiate(&local, &remote), WireFormat::Json);
+        assert_eq!(WireFormat::negotiate(&local, &[]), WireFormat::Json);
+    }
+
+    #[test]
+    fn test_codec_compresses_above_threshold() {
+        let remote = ProtocolHello::default();
+        let codec = FrameCodec::negotiate(
+            &[WireFormat::Json],
+            &CompressionConfig {
+                algorithms: vec![Compression::Deflate],
+                threshold: 64,
+            },
+            &remote,
+        );
+        assert_eq!(codec.compression, Some(Compression::Deflate));
+
+        let small = SignalingMessage::ConnectionReady {
+            session_id: "s".to_string(),
+        };
+        let frame = codec.encode(&small).unwrap();
+        assert_eq!(frame[0], b'{');
+        assert_eq!(
+            decode_frame(&frame).unwrap(),
+            WireFrame::Message(small, WireFormat::Json)
+        );
+
+        let large = SignalingMessage::Offer {
+            session_id: "s".to_string(),
+            sdp: "a=rtpmap:111 opus/48000/2\r\n".repeat(64),
+            quic_endpoint: None,
+        };
+        let frame = codec.encode(&large).unwrap();
+        assert_eq!(frame[0], saorsa_webrtc_proto::wire::TAG_DEFLATE);
+        assert!(frame.len() < WireFormat::Json.encode(&large).unwrap().len());
+        assert_eq!(
+            decode_frame(&frame).unwrap(),
+            WireFrame::Message(large, WireFormat::Json)
+        );
+    }
+
+    #[test]
+    fn test_codec_without_common_compression() {
+        let remote = ProtocolHello {
+            compression: Vec::new(),
+            ..ProtocolHello::default()
+        };
+        let codec = FrameCodec::negotiate(&WireFormat::ALL, &CompressionConfig::default(), &remote);
+        assert_eq!(codec.format, WireFormat::Postcard);
+        assert_eq!(codec.compression, None);
+    }
+
+    #[test]
+    fn test_hello_without_compression_field() {
+        // Hellos from peers that predate compression omit the field
+        let mut frame = vec![TAG_HELLO];
+        frame.extend_from_slice(br#"{"version":1,"wire_formats":["cbor"]}"#);
+        match decode_frame(&frame).unwrap() {
+            WireFrame::Hello(hello) => assert!(hello.compression.is_empty()),
+            other => panic!("Expected hello, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_nested_compression_rejected() {
+        let inner = Compression::Zstd.compress(b"{}").unwrap();
+        let outer = Compression::Zstd.compress(&inner).unwrap();
+        assert_eq!(
+            decode_frame(&outer),
+            Err(WireFormatError::UnknownTag(
+                saorsa_webrtc_proto::wire::TAG_ZSTD
+            ))
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_bad_frames() {
+        assert_eq!(decode_frame(&[]), Err(WireFormatError::EmptyFrame));
+        assert_eq!(
+            decode_frame(&[0x55, 1, 2]),
+            Err(WireFormatError::UnknownTag(0x55))
+        );
+        assert!(matches!(
+            decode_frame(&[TAG_POSTCARD, 0xFF]),
+            Err(WireFormatError::Decode(_))
+        ));
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod proptests {
+    use super::*;
+    use crate::types::{CallProgress, MediaType, TrackInfo, TrackSource, VideoLayer};
+    use proptest::prelude::*;
+    use std::net::SocketAddr;
+
+    fn message_strategy() -> impl Strategy<Value = SignalingMessage> {
+        // IPv6 scope IDs and flow labels are local to the sender and are not
+        // serialized, so endpoints are generated without them
+        let endpoint = prop::option::of(
+            (any::<std::net::IpAddr>(), any::<u16>())
+                .prop_map(|(ip, port)| SocketAddr::new(ip, port)),
+        );
+        prop_oneof![
+            (".*", ".*", endpoint.clone()).prop_map(|(session_id, sdp, quic_endpoint)| {
+                SignalingMessage::Offer {
+                    session_id,
+                    sdp,
+                    quic_endpoint,
+                }
+            }),
+            (
+                ".*",
+                ".*",
+                prop::option::of(".*"),
+                prop::option::of(any::<u16>())
+            )
+                .prop_map(|(session_id, candidate, sdp_mid, sdp_mline_index)| {
+                    SignalingMessage::IceCandidate {
+                        session_id,
+                        candidate,
+                        sdp_mid,
+                        sdp_mline_index,
+                    }
+                }),
+            (".*", any::<[bool; 3]>(), any::<u32>(), endpoint).prop_map(
+                |(session_id, [audio, video, data_channel], max_bandwidth_kbps, quic_endpoint)| {
+                    SignalingMessage::CapabilityExchange {
+                        session_id,
+                        audio,
+                        video,
+                        data_channel,
+                        max_bandwidth_kbps,
+                        quic_endpoint,
+                    }
+                }
+            ),
+            ".*".prop_map(|session_id| SignalingMessage::ConnectionReady { session_id }),
+            (".*", prop::collection::vec((any::<u16>(), ".*"), 0..4)).prop_map(
+                |(session_id, tracks)| SignalingMessage::TrackUpdate {
+                    session_id,
+                    tracks: tracks
+                        .into_iter()
+                        .map(|(track_id, label)| {
+                            TrackInfo::new(track_id, MediaType::Video, TrackSource::Slides, label)
+                        })
+                        .collect(),
+                }
+            ),
+            (".*", ".*")
+                .prop_map(|(session_id, key)| SignalingMessage::HandoffOffer { session_id, key }),
+            (".*", ".*").prop_map(|(session_id, token)| SignalingMessage::HandoffJoin {
+                session_id,
+                token
+            }),
+            ".*".prop_map(|session_id| SignalingMessage::HandoffComplete { session_id }),
+            (".*", prop::option::of(".*"))
+                .prop_map(|(session_id, reason)| SignalingMessage::Cancel { session_id, reason }),
+            (".*", prop::option::of(any::<u32>())).prop_map(|(session_id, position)| {
+                SignalingMessage::Progress {
+                    session_id,
+                    progress: CallProgress::Queued { position },
+                }
+            }),
+            (".*", any::<bool>()).prop_map(|(session_id, active)| SignalingMessage::AudioOnly {
+                session_id,
+                active
+            }),
+            (".*", any::<u16>(), any::<u8>(), any::<u8>()).prop_map(
+                |(session_id, track_id, spatial, temporal)| SignalingMessage::LayerSelection {
+                    session_id,
+                    track_id,
+                    layer: VideoLayer::new(spatial, temporal),
+                }
+            ),
+            (".*", prop::option::of(".*"))
+                .prop_map(|(session_id, reason)| SignalingMessage::Bye { session_id, reason }),
+        ]
+    }
+
+    fn format_strategy() -> impl Strategy<Value = WireFormat> {
+        prop::sample::select(WireFormat::ALL.to_vec())
+    }
+
+    proptest! {
+        #[test]
+        fn prop_message_roundtrip(message in message_strategy(), format in format_strategy()) {
+            let frame = format.encode(&message).unwrap();
+            prop_assert_eq!(decode_frame(&frame), Ok(WireFrame::Message(message, format)));
+        }
+
+        #[test]
+        fn prop_compressed_message_roundtrip(
+            message in message_strategy(),
+            format in format_strategy(),
+            compression in prop::sample::select(Compression::ALL.to_vec()),
+        ) {
+            let codec = FrameCodec {
+                format,
+                compression: Some(compression),
+                compression_threshold: 0,
+            };
+            let frame = codec.encode(&message).unwrap();
+            prop_assert_eq!(decode_frame(&frame), Ok(WireFrame::Message(message, format)));
+        }
+
+        #[test]
+        fn prop_decode_arbitrary_input(data in prop::collection::vec(any::<u8>(), 0..512)) {
+            // Whatever decodes must survive a re-encode unchanged
+            if let Ok(WireFrame::Message(message, format)) = decode_frame(&data) {
+                let frame = format.encode(&message).unwrap();
+                prop_assert_eq!(decode_frame(&frame), Ok(WireFrame::Message(message, format)));
+            }
+        }
+
+        #[test]
+        fn prop_decode_arbitrary_tagged_input(
+            tag in prop::sample::select(vec![b'{', TAG_CBOR, TAG_POSTCARD, TAG_HELLO]),
+            body in prop::collection::vec(any::<u8>(), 0..512),
+        ) {
+            let mut data = vec![tag];
+            data.extend_from_slice(&body);
+            if let Ok(WireFrame::Message(message, format)) = decode_frame(&data) {
+                let frame = format.encode(&message).unwrap();
+                prop_assert_eq!(decode_frame(&frame), Ok(WireFrame::Message(message, format)));
+            }
+        }
+    }
+}
@@ -0,0 +1,363 @@
+//! A/V sync and quality regression suite with golden metrics
+//!
+//! Runs a loopback video call through the network simulator at several
+//! profiles and checks that end-to-end latency, the loss left after
+//! retransmission and the A/V sync error stay within the thresholds in
+//! `tests/golden/av_sync.json`.
+//!
+//! Media is timed on a simulated clock: packets are pushed through
+//! [`NetworkSimulator`], lost ones are reported after a short wait and
+//! resent with [`RtxSender`], and everything that arrives is then delivered
+//! through the harness in arrival order. Sending several seconds of media
+//! per profile takes a while, so the suite is behind the `heavy-tests`
+//! feature:
+//!
+//! ```text
+//! cargo test -p saorsa-webrtc-core --features heavy-tests --test av_sync_golden
+//! ```
+#![cfg(feature = "heavy-tests")]
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use saorsa_webrtc_core::testing::{LoopbackHarness, NetworkProfile, NetworkSimulator, Role};
+use saorsa_webrtc_core::{
+    LinkStreamType, MediaConstraints, RtpPacket, RtxConfig, RtxMapping, RtxReceiver, RtxSender,
+    StreamKey, StreamType,
+};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::Duration;
+
+/// Length of media sent per profile
+const CALL_LENGTH: Duration = Duration::from_secs(10);
+
+/// Opus frame duration
+const AUDIO_FRAME: Duration = Duration::from_millis(20);
+
+/// Video frame duration at 30 fps
+const VIDEO_FRAME: Duration = Duration::from_micros(33_333);
+
+/// Video frames between keyframes
+const KEYFRAME_INTERVAL: u32 = 60;
+
+/// How long the receiver waits for a late packet before reporting it lost
+const NACK_DELAY: Duration = Duration::from_millis(20);
+
+/// Times a lost packet is requested again before it is given up on
+const MAX_RETRANSMISSIONS: u32 = 3;
+
+const AUDIO_SSRC: u32 = 0xA0D1_0001;
+const VIDEO_SSRC: u32 = 0x71DE_0001;
+
+/// Limits one profile must stay within
+#[derive(Debug, Deserialize)]
+struct Golden {
+    /// 95th percentile of capture-to-arrival time
+    max_latency_p95_ms: u64,
+    /// Packets never delivered, even after retransmission
+    max_residual_loss_percent: f64,
+    /// 95th percentile of how far video frames lag audio played on time
+    max_av_skew_p95_ms: u64,
+}
+
+/// What a run measured
+#[derive(Debug)]
+struct Metrics {
+    latency_p95_ms: u64,
+    residual_loss_percent: f64,
+    av_skew_p95_ms: u64,
+}
+
+#[derive(Debug, Clone)]
+struct Sent {
+    captured: Duration,
+    packet: RtpPacket,
+}
+
+#[derive(Debug)]
+struct Delivery {
+    arrival: Duration,
+    captured: Duration,
+    packet: RtpPacket,
+}
+
+enum Event {
+    Send(Sent),
+    Retransmit { sent: Sent, attempt: u32 },
+}
+
+fn golden(profile: &NetworkProfile) -> Golden {
+    let all: HashMap<String, Golden> =
+        serde_json::from_str(include_str!("golden/av_sync.json")).expect("golden metrics");
+    all.into_iter()
+        .find(|(name, _)| name == profile.name)
+        .map(|(_, golden)| golden)
+        .unwrap_or_else(|| panic!("no golden metrics for {}", profile.name))
+}
+
+/// Audio and video packets of the call in capture order
+fn media() -> Vec<Sent> {
+    let mut sent = Vec::new();
+    let audio_frames = (CALL_LENGTH.as_micros() / AUDIO_FRAME.as_micros()) as u32;
+    for i in 0..audio_frames {
+        let packet = RtpPacket::new(
+            111,
+            i as u16,
+            i * 960,
+            AUDIO_SSRC,
+            vec![0xA5; 80],
+            StreamType::Audio,
+        )
+        .unwrap();
+        sent.push(Sent {
+            captured: AUDIO_FRAME * i,
+            packet,
+        });
+    }
+
+    let video_frames = (CALL_LENGTH.as_micros() / VIDEO_FRAME.as_micros()) as u32;
+    let mut sequence = 0u16;
+    for i in 0..video_frames {
+        let packets = if i % KEYFRAME_INTERVAL == 0 { 10 } else { 3 };
+        for _ in 0..packets {
+            let packet = RtpPacket::new(
+                96,
+                sequence,
+                i * 3000,
+                VIDEO_SSRC,
+                vec![0x5A; 1_100],
+                StreamType::Video,
+            )
+            .unwrap();
+            sequence = sequence.wrapping_add(1);
+            sent.push(Sent {
+                captured: VIDEO_FRAME * i,
+                packet,
+            });
+        }
+    }
+
+    sent.sort_by_key(|sent| sent.captured);
+    sent
+}
+
+fn rtx_mapping(stream_type: LinkStreamType, media_ssrc: u32, rtx_payload_type: u8) -> RtxMapping {
+    RtxMapping {
+        media_ssrc,
+        rtx_ssrc: media_ssrc + 1,
+        rtx_payload_type,
+        track: StreamKey::primary(stream_type),
+    }
+}
+
+/// Run the media through the network, repairing losses with RTX
+///
+/// Returns what arrived, in arrival order.
+fn simulate(profile: &NetworkProfile, media: &[Sent]) -> Vec<Delivery> {
+    let mut forward = NetworkSimulator::new(profile.clone(), 1);
+    let mut feedback = NetworkSimulator::new(profile.clone(), 2);
+
+    let mappings = [
+        (rtx_mapping(LinkStreamType::Audio, AUDIO_SSRC, 99), 111),
+        (rtx_mapping(LinkStreamType::Video, VIDEO_SSRC, 97), 96),
+    ];
+    let mut rtx_sender = RtxSender::new(RtxConfig {
+        history_packets: 1_024,
+        ..RtxConfig::default()
+    });
+    let mut rtx_receiver = RtxReceiver::new();
+    for (mapping, payload_type) in mappings {
+        rtx_sender.add_stream(mapping);
+        rtx_receiver.add_stream(mapping, payload_type);
+    }
+
+    // Events in time order; the counter keeps simultaneous ones in order
+    let mut events: BTreeMap<(Duration, usize), Event> = BTreeMap::new();
+    for (i, sent) in media.iter().enumerate() {
+        events.insert((sent.captured, i), Event::Send(sent.clone()));
+    }
+    let mut next_id = media.len();
+    let mut deliveries = Vec::new();
+
+    while let Some(((now, _), event)) = events.pop_first() {
+        let (sent, attempt, packet) = match event {
+            Event::Send(sent) => {
+                rtx_sender.remember(&sent.packet);
+                let packet = sent.packet.clone();
+                (sent, 0, packet)
+            }
+            Event::Retransmit { sent, attempt } => {
+                let Some(rtx) = rtx_sender
+                    .retransmit(sent.packet.ssrc, &[sent.packet.sequence_number])
+                    .unwrap()
+                    .pop()
+                else {
+                    continue;
+                };
+                (sent, attempt, rtx)
+            }
+        };
+
+        let bytes = packet.to_bytes().unwrap().len();
+        if let Some(arrival) = forward.transmit(now, bytes) {
+            let packet = if attempt == 0 {
+                packet
+            } else {
+                rtx_receiver.restore(packet).unwrap()
+            };
+            deliveries.push(Delivery {
+                arrival,
+                captured: sent.captured,
+                packet,
+            });
+            continue;
+        }
+
+        // The receiver notices the gap once later packets arrive, then
+        // asks for the packet over the reverse path
+        if attempt == MAX_RETRANSMISSIONS {
+            continue;
+        }
+        let noticed = now + profile.latency + NACK_DELAY;
+        let requested = feedback
+            .transmit(noticed, 16)
+            .unwrap_or(noticed + profile.latency * 2 + NACK_DELAY);
+        events.insert(
+            (requested, next_id),
+            Event::Retransmit {
+                sent,
+                attempt: attempt + 1,
+            },
+        );
+        next_id += 1;
+    }
+
+    deliveries.sort_by_key(|delivery| delivery.arrival);
+    deliveries
+}
+
+fn percentile(mut values: Vec<Duration>, percent: usize) -> Duration {
+    values.sort();
+    values
+        .get(values.len().saturating_sub(1) * percent / 100)
+        .copied()
+        .unwrap_or_default()
+}
+
+fn measure(media: &[Sent], deliveries: &[Delivery]) -> Metrics {
+    let latency = |delivery: &Delivery| delivery.arrival.saturating_sub(delivery.captured);
+    let latency_p95 = percentile(deliveries.iter().map(latency).collect(), 95);
+
+    let delivered: HashSet<(u32, u16)> = deliveries
+        .iter()
+        .map(|delivery| (delivery.packet.ssrc, delivery.packet.sequence_number))
+        .collect();
+    let residual_loss_percent = (media.len() - delivered.len()) as f64 * 100.0 / media.len() as f64;
+
+    // Audio plays out after a buffer covering most of its jitter; a video
+    // frame shows when its last packet arrives, so one arriving after its
+    // audio has played lags behind it
+    let audio_delay = percentile(
+        deliveries
+            .iter()
+            .filter(|delivery| delivery.packet.ssrc == AUDIO_SSRC)
+            .map(latency)
+            .collect(),
+        95,
+    );
+    let mut expected: HashMap<u32, usize> = HashMap::new();
+    for sent in media.iter().filter(|sent| sent.packet.ssrc == VIDEO_SSRC) {
+        *expected.entry(sent.packet.timestamp).or_default() += 1;
+    }
+    let mut frames: HashMap<u32, (Duration, Duration, usize)> = HashMap::new();
+    for delivery in deliveries
+        .iter()
+        .filter(|delivery| delivery.packet.ssrc == VIDEO_SSRC)
+    {
+        let frame = frames.entry(delivery.packet.timestamp).or_insert((
+            delivery.captured,
+            Duration::ZERO,
+            0,
+        ));
+        frame.1 = frame.1.max(delivery.arrival);
+        frame.2 += 1;
+    }
+    let skews = frames
+        .iter()
+        .filter(|(timestamp, (_, _, packets))| expected.get(timestamp) == Some(packets))
+        .map(|(_, (captured, shown, _))| shown.saturating_sub(*captured + audio_delay))
+        .collect();
+
+    Metrics {
+        latency_p95_ms: latency_p95.as_millis() as u64,
+        residual_loss_percent,
+        av_skew_p95_ms: percentile(skews, 95).as_millis() as u64,
+    }
+}
+
+async fn run_profile(profile: NetworkProfile) {
+    let golden = golden(&profile);
+    let media = media();
+    let deliveries = simulate(&profile, &media);
+    let metrics = measure(&media, &deliveries);
+    println!("{}: {metrics:?}", profile.name);
+
+    // What arrived goes through both peers' media transports
+    let harness = LoopbackHarness::new().await.unwrap();
+    let call_id = harness
+        .connect_call(MediaConstraints::video_call())
+        .await
+        .unwrap();
+    for delivery in &deliveries {
+        harness
+            .send_media(Role::Caller, call_id, &delivery.packet)
+            .await
+            .unwrap();
+    }
+    let stats = harness
+        .callee()
+        .media_transport(call_id)
+        .await
+        .unwrap()
+        .stats()
+        .await;
+    assert_eq!(stats.packets_received, deliveries.len() as u64);
+    harness.hang_up(Role::Caller, call_id).await.unwrap();
+    harness.assert_no_leaks().await.unwrap();
+
+    assert!(
+        metrics.latency_p95_ms <= golden.max_latency_p95_ms,
+        "{}: p95 latency {} ms above {} ms",
+        profile.name,
+        metrics.latency_p95_ms,
+        golden.max_latency_p95_ms
+    );
+    assert!(
+        metrics.residual_loss_percent <= golden.max_residual_loss_percent,
+        "{}: residual loss {:.2}% above {:.2}%",
+        profile.name,
+        metrics.residual_loss_percent,
+        golden.max_residual_loss_percent
+    );
+    assert!(
+        metrics.av_skew_p95_ms <= golden.max_av_skew_p95_ms,
+        "{}: p95 A/V skew {} ms above {} ms",
+        profile.name,
+        metrics.av_skew_p95_ms,
+        golden.max_av_skew_p95_ms
+    );
+}
+
+#[tokio::test]
+async fn test_lan_within_golden_metrics() {
+    run_profile(NetworkProfile::lan()).await;
+}
+
+#[tokio::test]
+async fn test_lossy_wifi_within_golden_metrics() {
+    run_profile(NetworkProfile::lossy_wifi()).await;
+}
+
+#[tokio::test]
+async fn test_3g_within_golden_metrics() {
+    run_profile(NetworkProfile::cellular_3g()).await;
+}
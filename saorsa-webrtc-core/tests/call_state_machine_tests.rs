@@ -116,6 +116,7 @@ async fn end_call_is_idempotent_by_removal() {
 async fn concurrent_call_limit_is_enforced() {
     let cfg = CallManagerConfig {
         max_concurrent_calls: 1,
+        ..Default::default()
     };
     let mgr = CallManager::<PeerIdentityString>::new(cfg).await.unwrap();
 
@@ -157,19 +158,23 @@ async fn call_manager_errors_on_non_existent_calls() {
         mgr.end_call(fake).await,
         Err(CallError::CallNotFound(_))
     ));
+    #[cfg(feature = "legacy-webrtc")]
     assert!(matches!(
         mgr.create_offer(fake).await,
         Err(CallError::CallNotFound(_))
     ));
+    #[cfg(feature = "legacy-webrtc")]
     assert!(matches!(
         mgr.handle_answer(fake, "x".to_string()).await,
         Err(CallError::CallNotFound(_))
     ));
     // Legacy ICE methods (deprecated)
+    #[cfg(feature = "legacy-webrtc")]
     assert!(matches!(
         mgr.add_ice_candidate(fake, "x".to_string()).await,
         Err(CallError::CallNotFound(_))
     ));
+    #[cfg(feature = "legacy-webrtc")]
     assert!(matches!(
         mgr.start_ice_gathering(fake).await,
         Err(CallError::CallNotFound(_))
@@ -0,0 +1,65 @@
+//! Calls on a single-threaded runtime
+//!
+//! Runs a loopback call end to end on a `current_thread` runtime, where a
+//! blocking wait on another task would hang the test instead of stalling
+//! one worker.
+//!
+//! ```text
+//! cargo test -p saorsa-webrtc-core --features test-utils --test current_thread_runtime
+//! ```
+#![cfg(feature = "test-utils")]
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use saorsa_webrtc_core::testing::{LoopbackHarness, Role};
+use saorsa_webrtc_core::{
+    ExecutionModel, MediaConstraints, MediaTransportState, RtpPacket, StreamType,
+};
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_call_on_current_thread_runtime() {
+    let harness = LoopbackHarness::new().await.unwrap();
+    for role in [Role::Caller, Role::Callee] {
+        assert_eq!(
+            harness.peer(role).service().execution_model(),
+            ExecutionModel::CurrentThread
+        );
+    }
+
+    let call_id = harness
+        .connect_call(MediaConstraints::audio_only())
+        .await
+        .unwrap();
+    for seq in 0..10u16 {
+        let packet = RtpPacket::new(
+            111,
+            seq,
+            u32::from(seq) * 960,
+            1,
+            vec![0; 40],
+            StreamType::Audio,
+        )
+        .unwrap();
+        harness
+            .send_media(Role::Caller, call_id, &packet)
+            .await
+            .unwrap();
+    }
+
+    // Synchronous accessors read state without waiting on another task
+    let transport = harness.callee().media_transport(call_id).await.unwrap();
+    assert_eq!(transport.current_state(), MediaTransportState::Connected);
+    let stats = harness
+        .callee()
+        .service()
+        .get_call_stats(call_id)
+        .await
+        .unwrap();
+    assert_eq!(stats.packets_received, 10);
+
+    harness.hang_up(Role::Caller, call_id).await.unwrap();
+    assert_eq!(
+        harness.caller().service().get_call_state(call_id).await,
+        None
+    );
+    harness.assert_no_leaks().await.unwrap();
+}
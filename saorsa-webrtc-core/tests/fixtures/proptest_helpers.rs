@@ -95,6 +95,7 @@ pub fn media_constraints_strategy() -> impl Strategy<Value = MediaConstraints> {
                 audio,
                 video,
                 screen_share,
+                latency: None,
             }),
     ]
 }
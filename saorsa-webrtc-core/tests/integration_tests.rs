@@ -115,6 +115,7 @@ async fn test_full_call_flow() {
     assert_eq!(call_state, None);
 }
 
+#[cfg(feature = "legacy-webrtc")]
 #[tokio::test]
 async fn test_media_track_creation_integration() {
     let mut media_manager = MediaStreamManager::new();
@@ -249,6 +250,13 @@ async fn test_call_constraints_integration() {
         call_manager.get_call_state(screen_call_id).await,
         Some(CallState::Calling)
     );
+    let calls = call_manager.calls().await;
+    assert_eq!(calls.len(), 3);
+    assert!(calls.contains(&(
+        video_call_id,
+        PeerIdentityString::new("video-peer"),
+        CallState::Calling
+    )));
 
     // Clean up
     call_manager.end_call(audio_call_id).await.unwrap();
@@ -273,16 +281,19 @@ async fn test_error_handling_integration() {
         .is_err());
     assert!(call_manager.reject_call(fake_call_id).await.is_err());
     assert!(call_manager.end_call(fake_call_id).await.is_err());
-    assert!(call_manager.create_offer(fake_call_id).await.is_err());
-    // Legacy ICE methods (deprecated)
-    assert!(call_manager
-        .add_ice_candidate(fake_call_id, "dummy".to_string())
-        .await
-        .is_err());
-    assert!(call_manager
-        .start_ice_gathering(fake_call_id)
-        .await
-        .is_err());
+    // Legacy SDP/ICE methods (deprecated)
+    #[cfg(feature = "legacy-webrtc")]
+    {
+        assert!(call_manager.create_offer(fake_call_id).await.is_err());
+        assert!(call_manager
+            .add_ice_candidate(fake_call_id, "dummy".to_string())
+            .await
+            .is_err());
+        assert!(call_manager
+            .start_ice_gathering(fake_call_id)
+            .await
+            .is_err());
+    }
 }
 
 #[tokio::test]
@@ -603,6 +614,7 @@ async fn test_signaling_message_quic_variants() {
 // ============================================================================
 
 /// Test complete call lifecycle with media stream setup and teardown
+#[cfg(feature = "legacy-webrtc")]
 #[tokio::test]
 async fn test_e2e_complete_call_lifecycle_with_media() {
     let config = CallManagerConfig::default();
@@ -640,6 +652,7 @@ async fn test_e2e_complete_call_lifecycle_with_media() {
         video: true,
         data_channel: false,
         max_bandwidth_kbps: 2500,
+        ..Default::default()
     };
 
     call_manager
@@ -713,6 +726,7 @@ async fn test_e2e_bidirectional_media_streams() {
 }
 
 /// Test media stream creation with different constraints
+#[cfg(feature = "legacy-webrtc")]
 #[tokio::test]
 async fn test_e2e_media_streams_various_constraints() {
     let mut media_manager = MediaStreamManager::new();
@@ -1093,6 +1107,7 @@ async fn test_multi_peer_call_rejection() {
 }
 
 /// Test concurrent media tracks for multiple calls
+#[cfg(feature = "legacy-webrtc")]
 #[tokio::test]
 async fn test_multi_peer_concurrent_media_tracks() {
     let mut media_manager = MediaStreamManager::new();
@@ -1176,6 +1191,7 @@ async fn test_multi_peer_quic_native_calls() {
         video: true,
         data_channel: false,
         max_bandwidth_kbps: 2500,
+        ..Default::default()
     };
 
     call_manager
@@ -1305,6 +1321,7 @@ async fn test_error_capability_mismatch_scenarios() {
             video: true,
             data_channel: false,
             max_bandwidth_kbps: 2500,
+            ..Default::default()
         };
         let result = call_manager.confirm_connection(call_id, video_caps).await;
         // This should succeed since peer has at least the required capabilities
@@ -1332,11 +1349,14 @@ async fn test_error_operations_on_invalid_calls() {
         .is_err());
     assert!(call_manager.reject_call(fake_id).await.is_err());
     assert!(call_manager.end_call(fake_id).await.is_err());
-    assert!(call_manager.create_offer(fake_id).await.is_err());
-    assert!(call_manager
-        .add_ice_candidate(fake_id, "dummy".to_string())
-        .await
-        .is_err());
+    #[cfg(feature = "legacy-webrtc")]
+    {
+        assert!(call_manager.create_offer(fake_id).await.is_err());
+        assert!(call_manager
+            .add_ice_candidate(fake_id, "dummy".to_string())
+            .await
+            .is_err());
+    }
 
     // Test with ended call
     let peer = PeerIdentityString::new("peer");
@@ -1590,6 +1610,7 @@ async fn test_connection_state_transitions() {
         video: true,
         data_channel: false,
         max_bandwidth_kbps: 2500,
+        ..Default::default()
     };
     call_manager
         .confirm_connection(call_id, caps)
@@ -1802,3 +1823,471 @@ async fn test_connection_state_endpoint_change() {
     // Clean up
     call_manager.end_call(call_id).await.unwrap();
 }
+
+#[tokio::test]
+async fn test_scheduled_call_reminds_and_dials() {
+    use saorsa_webrtc_core::{
+        ScheduleEvent, SchedulerConfig, WebRtcConfig, WebRtcEvent, WebRtcService,
+    };
+    use std::time::Duration;
+
+    let signaling = Arc::new(SignalingHandler::new(Arc::new(
+        MockSignalingTransport::new(),
+    )));
+    let config = WebRtcConfig {
+        scheduler: SchedulerConfig {
+            reminder_lead: Duration::from_secs(60),
+            check_interval: Duration::from_millis(10),
+            store_path: None,
+        },
+        ..WebRtcConfig::default()
+    };
+    let service: WebRtcService<PeerIdentityString, MockSignalingTransport> =
+        WebRtcService::builder(signaling)
+            .with_config(config)
+            .build()
+            .await
+            .unwrap();
+
+    let mut events = service.subscribe_events();
+    let at = chrono::Utc::now() + chrono::Duration::milliseconds(200);
+    let id = service
+        .schedule_call(
+            PeerIdentityString::new("bob"),
+            MediaConstraints::audio_only(),
+            at,
+        )
+        .unwrap();
+    assert_eq!(service.scheduled_calls().len(), 1);
+    service.start().await.unwrap();
+
+    let mut reminded = false;
+    let call_id = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            match events.recv().await.unwrap() {
+                WebRtcEvent::Schedule(ScheduleEvent::Reminder { call }) => {
+                    assert_eq!(call.id, id);
+                    reminded = true;
+                }
+                WebRtcEvent::Schedule(ScheduleEvent::Dialed {
+                    id: dialed,
+                    call_id,
+                    ..
+                }) => {
+                    assert_eq!(dialed, id);
+                    break call_id;
+                }
+                _ => {}
+            }
+        }
+    })
+    .await
+    .unwrap();
+
+    assert!(reminded);
+    assert!(service.scheduled_calls().is_empty());
+    assert_eq!(
+        service.get_call_state(call_id).await,
+        Some(CallState::Calling)
+    );
+}
+
+#[tokio::test]
+async fn test_builder_plugs_in_components() {
+    use saorsa_webrtc_codecs::CodecRegistry;
+    use saorsa_webrtc_core::protocol_handler::AuthDecision;
+    use saorsa_webrtc_core::stats_history::StatsHistoryStore;
+    use saorsa_webrtc_core::{
+        metrics, CallPolicy, Clock, HistoryStore, MetricsRecorder, MockClock, WebRtcService,
+    };
+
+    #[derive(Debug)]
+    struct DenyMallory;
+
+    #[async_trait::async_trait]
+    impl CallPolicy for DenyMallory {
+        fn name(&self) -> &str {
+            "deny_mallory"
+        }
+
+        async fn outgoing(&self, peer: &str, _constraints: &MediaConstraints) -> AuthDecision {
+            if peer == "mallory" {
+                AuthDecision::Deny("blocked".to_string())
+            } else {
+                AuthDecision::Allow
+            }
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct Counters(std::sync::Mutex<std::collections::HashMap<&'static str, u64>>);
+
+    impl MetricsRecorder for Counters {
+        fn increment_counter(&self, name: &'static str, value: u64) {
+            *self.0.lock().unwrap().entry(name).or_default() += value;
+        }
+    }
+
+    let signaling = Arc::new(SignalingHandler::new(Arc::new(
+        MockSignalingTransport::new(),
+    )));
+    let clock = MockClock::new();
+    let counters = Arc::new(Counters::default());
+    let store = Arc::new(parking_lot::Mutex::new(StatsHistoryStore::default()));
+    let codecs = Arc::new(CodecRegistry::new());
+    let service: WebRtcService<PeerIdentityString, MockSignalingTransport> =
+        WebRtcService::builder(signaling)
+            .with_codec_registry(Arc::clone(&codecs))
+            .with_call_policy(Arc::new(DenyMallory))
+            .with_history_store(store.clone())
+            .with_clock(clock.shared())
+            .with_metrics(counters.clone())
+            .build()
+            .await
+            .unwrap();
+
+    assert!(Arc::ptr_eq(service.media().codec_registry(), &codecs));
+    assert!(service
+        .initiate_call(
+            PeerIdentityString::new("mallory"),
+            MediaConstraints::audio_only()
+        )
+        .await
+        .is_err());
+    let call_id = service
+        .initiate_call(
+            PeerIdentityString::new("bob"),
+            MediaConstraints::audio_only(),
+        )
+        .await
+        .unwrap();
+
+    // Statistics are sampled into the plugged-in store, stamped by the clock
+    service.start_stats_history(call_id).await.unwrap();
+    tokio::time::timeout(std::time::Duration::from_secs(5), async {
+        while HistoryStore::get(&*store, call_id).is_none_or(|h| h.is_empty()) {
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .unwrap();
+    let history = HistoryStore::get(&*store, call_id).unwrap();
+    assert_eq!(history.peer(), Some("bob"));
+    assert_eq!(history.samples()[0].timestamp, clock.utc_now());
+
+    let counts = counters.0.lock().unwrap().clone();
+    assert_eq!(counts.get(metrics::CALLS_INITIATED), Some(&1));
+    assert_eq!(counts.get(metrics::POLICY_DENIALS), Some(&1));
+}
+
+#[tokio::test]
+async fn test_governor_downgrades_on_low_battery() {
+    use saorsa_webrtc_core::{
+        GovernorConfig, GovernorEvent, GovernorReason, PerformanceLevel, PowerSource, PowerState,
+        WebRtcConfig, WebRtcEvent, WebRtcService,
+    };
+    use std::time::Duration;
+
+    #[derive(Debug)]
+    struct Battery(parking_lot::Mutex<PowerState>);
+
+    impl PowerSource for Battery {
+        fn power_state(&self) -> PowerState {
+            *self.0.lock()
+        }
+    }
+
+    let signaling = Arc::new(SignalingHandler::new(Arc::new(
+        MockSignalingTransport::new(),
+    )));
+    let battery = Arc::new(Battery(parking_lot::Mutex::new(PowerState::default())));
+    let config = WebRtcConfig {
+        governor: GovernorConfig {
+            enabled: true,
+            interval: Duration::from_millis(10),
+            ..Default::default()
+        },
+        ..WebRtcConfig::default()
+    };
+    let service: WebRtcService<PeerIdentityString, MockSignalingTransport> =
+        WebRtcService::builder(signaling)
+            .with_config(config)
+            .with_power_source(battery.clone())
+            .build()
+            .await
+            .unwrap();
+    let governor = Arc::clone(service.governor().unwrap());
+    assert_eq!(governor.level(), PerformanceLevel::Full);
+
+    let mut events = service.subscribe_events();
+    service.start().await.unwrap();
+    *battery.0.lock() = PowerState {
+        on_battery: true,
+        battery_percent: Some(10),
+        low_power_mode: false,
+    };
+
+    let event = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            if let WebRtcEvent::Performance(event) = events.recv().await.unwrap() {
+                break event;
+            }
+        }
+    })
+    .await
+    .unwrap();
+    assert_eq!(
+        event,
+        GovernorEvent::Downgraded {
+            from: PerformanceLevel::Full,
+            to: PerformanceLevel::Minimal,
+            reason: GovernorReason::LowBattery,
+        }
+    );
+    assert_eq!(governor.settings().max_fps, 15);
+}
+
+#[tokio::test]
+async fn test_incoming_call_rings_every_device() {
+    use saorsa_webrtc_core::{CallEvent, CallOffer, WebRtcService};
+
+    let transport = Arc::new(MockSignalingTransport::new());
+    let alice: WebRtcService<PeerIdentityString, MockSignalingTransport> =
+        WebRtcService::builder(Arc::new(SignalingHandler::new(transport.clone())))
+            .build()
+            .await
+            .unwrap();
+    let bob = PeerIdentityString::new("bob");
+    for device in ["bob-desktop", "bob-phone", "bob-tablet"] {
+        alice.register_device(&bob, device.to_string());
+    }
+    alice.register_device(&bob, "bob-phone".to_string());
+
+    let call_id = alice
+        .initiate_call(bob.clone(), MediaConstraints::audio_only())
+        .await
+        .unwrap();
+    let offer = SignalingMessage::CapabilityExchange {
+        session_id: call_id.to_string(),
+        audio: true,
+        video: false,
+        data_channel: false,
+        max_bandwidth_kbps: 64,
+        quic_endpoint: None,
+    };
+    assert_eq!(
+        alice
+            .ring_devices(call_id, &bob, offer.clone())
+            .await
+            .unwrap(),
+        3
+    );
+
+    // The phone answers first; the others stop ringing
+    assert!(
+        alice
+            .handle_device_answer(call_id, &"bob-phone".to_string())
+            .await
+    );
+    for device in ["bob-desktop", "bob-tablet"] {
+        assert!(matches!(
+            transport.receive_from_peer(device),
+            Some(SignalingMessage::Cancel { .. })
+        ));
+    }
+    assert_eq!(transport.receive_from_peer("bob-phone"), Some(offer));
+
+    // A late answer loses and is cancelled too
+    assert!(
+        !alice
+            .handle_device_answer(call_id, &"bob-tablet".to_string())
+            .await
+    );
+    assert!(matches!(
+        transport.receive_from_peer("bob-tablet"),
+        Some(SignalingMessage::Cancel { .. })
+    ));
+
+    // On a device that lost, the cancel stops the ringing call
+    let desktop: WebRtcService<PeerIdentityString, MockSignalingTransport> =
+        WebRtcService::builder(Arc::new(SignalingHandler::new(Arc::new(
+            MockSignalingTransport::new(),
+        ))))
+        .build()
+        .await
+        .unwrap();
+    let mut events = desktop.subscribe_call_events();
+    desktop
+        .handle_incoming_call(CallOffer {
+            call_id,
+            caller: PeerIdentityString::new("alice"),
+            callee: bob.clone(),
+            sdp: String::new(),
+            media_types: vec![MediaType::Audio],
+            timestamp: chrono::Utc::now(),
+        })
+        .await
+        .unwrap();
+    desktop
+        .handle_cancel(call_id, Some("answered elsewhere".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(desktop.get_call_state(call_id).await, None);
+    let mut cancelled = false;
+    while let Ok(event) = events.try_recv() {
+        cancelled |= matches!(event, CallEvent::CallCancelled { call_id: id, .. } if id == call_id);
+    }
+    assert!(cancelled);
+}
+
+#[tokio::test]
+async fn test_do_not_disturb_diverts_to_missed_calls() {
+    use saorsa_webrtc_core::{
+        CallOffer, DndAction, DndConfig, DndReason, IncomingCallOutcome, WebRtcConfig,
+        WebRtcService,
+    };
+
+    let config = WebRtcConfig {
+        dnd: DndConfig {
+            action: DndAction::Reject,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let service: WebRtcService<PeerIdentityString, MockSignalingTransport> =
+        WebRtcService::builder(Arc::new(SignalingHandler::new(Arc::new(
+            MockSignalingTransport::new(),
+        ))))
+        .with_config(config)
+        .build()
+        .await
+        .unwrap();
+    let offer = |caller: &str| CallOffer {
+        call_id: CallId::new(),
+        caller: PeerIdentityString::new(caller),
+        callee: PeerIdentityString::new("me"),
+        sdp: String::new(),
+        media_types: vec![MediaType::Audio],
+        timestamp: chrono::Utc::now(),
+    };
+
+    service.set_do_not_disturb(true);
+    let blocked = offer("alice");
+    let outcome = service.handle_incoming_call(blocked.clone()).await.unwrap();
+    assert_eq!(
+        outcome,
+        IncomingCallOutcome::DoNotDisturb {
+            call_id: blocked.call_id,
+            action: DndAction::Reject,
+        }
+    );
+    assert_eq!(service.get_call_state(blocked.call_id).await, None);
+    let missed = service.missed_calls();
+    assert_eq!(missed.len(), 1);
+    assert_eq!(missed[0].caller.as_str(), "alice");
+    assert_eq!(missed[0].reason, DndReason::Manual);
+
+    service.set_do_not_disturb(false);
+    let ringing = offer("bob");
+    assert_eq!(
+        service.handle_incoming_call(ringing.clone()).await.unwrap(),
+        IncomingCallOutcome::Ringing(ringing.call_id)
+    );
+    assert_eq!(service.missed_calls().len(), 1);
+}
+
+#[tokio::test]
+async fn test_webhooks_report_incoming_and_missed_calls() {
+    use saorsa_webrtc_core::webhooks::{
+        verify_signature, WebhookRequest, SIGNATURE_HEADER, TIMESTAMP_HEADER,
+    };
+    use saorsa_webrtc_core::{
+        CallOffer, WebRtcConfig, WebRtcService, WebhookConfig, WebhookError, WebhookPayload,
+        WebhookSender,
+    };
+    use std::time::Duration;
+
+    /// Forwards each request to the test and accepts it
+    #[derive(Debug)]
+    struct ChannelSender(tokio::sync::mpsc::UnboundedSender<WebhookRequest>);
+
+    #[async_trait::async_trait]
+    impl WebhookSender for ChannelSender {
+        async fn send(&self, request: &WebhookRequest) -> Result<u16, WebhookError> {
+            let _ = self.0.send(request.clone());
+            Ok(200)
+        }
+    }
+
+    let (requests, mut received) = tokio::sync::mpsc::unbounded_channel();
+    let config = WebRtcConfig {
+        webhooks: Some(WebhookConfig {
+            urls: vec!["https://bot.example/calls".to_string()],
+            secret: Some("s3cret".to_string()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let service: WebRtcService<PeerIdentityString, MockSignalingTransport> =
+        WebRtcService::builder(Arc::new(SignalingHandler::new(Arc::new(
+            MockSignalingTransport::new(),
+        ))))
+        .with_config(config)
+        .with_webhook_sender(Arc::new(ChannelSender(requests)))
+        .build()
+        .await
+        .unwrap();
+    service.start().await.unwrap();
+    // Let the webhook task subscribe before events are sent
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let offer = |caller: &str| CallOffer {
+        call_id: CallId::new(),
+        caller: PeerIdentityString::new(caller),
+        callee: PeerIdentityString::new("me"),
+        sdp: String::new(),
+        media_types: vec![MediaType::Audio],
+        timestamp: chrono::Utc::now(),
+    };
+    async fn next_payload(
+        received: &mut tokio::sync::mpsc::UnboundedReceiver<WebhookRequest>,
+    ) -> serde_json::Value {
+        let request = tokio::time::timeout(Duration::from_secs(5), received.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        let header = |name: &str| {
+            request
+                .headers
+                .iter()
+                .find(|(header, _)| *header == name)
+                .map(|(_, value)| value.clone())
+                .unwrap()
+        };
+        assert_eq!(request.url, "https://bot.example/calls");
+        assert!(verify_signature(
+            "s3cret",
+            header(TIMESTAMP_HEADER).parse().unwrap(),
+            &request.body,
+            &header(SIGNATURE_HEADER)
+        ));
+        serde_json::from_slice::<serde_json::Value>(&request.body).unwrap()
+    }
+
+    service.set_do_not_disturb(true);
+    let blocked = offer("alice");
+    service.handle_incoming_call(blocked.clone()).await.unwrap();
+    let payload = next_payload(&mut received).await;
+    assert_eq!(payload["event"], "missed_call");
+    assert_eq!(payload["caller"], "alice");
+    assert_eq!(payload["reason"], "do_not_disturb");
+
+    service.set_do_not_disturb(false);
+    let ringing = offer("bob");
+    service.handle_incoming_call(ringing.clone()).await.unwrap();
+    let payload = next_payload(&mut received).await;
+    assert_eq!(payload["event"], "incoming_call");
+    assert_eq!(payload["caller"], "bob");
+    let payload: WebhookPayload = serde_json::from_value(payload).unwrap();
+    assert_eq!(payload.event.name(), "incoming_call");
+}
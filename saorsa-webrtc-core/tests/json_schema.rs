@@ -0,0 +1,24 @@
+//! Checks the published JSON Schemas of the core message types
+//!
+//! The schemas in `schemas/` at the repository root are what frontends
+//! generate their types from, so they must match the types. After an
+//! intended change, regenerate them with:
+//!
+//! ```text
+//! SAORSA_UPDATE_SCHEMAS=1 cargo test -p saorsa-webrtc-core --features schema --test json_schema
+//! ```
+#![cfg(feature = "schema")]
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use saorsa_webrtc_core::schema::{check_published, schemas};
+use std::path::Path;
+
+#[test]
+fn published_schemas_are_current() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../schemas");
+    let stale = check_published(&dir, &schemas()).expect("read published schemas");
+    assert!(
+        stale.is_empty(),
+        "stale schemas {stale:?}; regenerate with SAORSA_UPDATE_SCHEMAS=1"
+    );
+}
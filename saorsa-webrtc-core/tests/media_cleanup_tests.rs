@@ -1,5 +1,7 @@
 //! Media cleanup and resource management tests
 
+#![cfg(feature = "legacy-webrtc")]
+
 use saorsa_webrtc_core::media::MediaStreamManager;
 use saorsa_webrtc_core::types::MediaType;
 
@@ -4,8 +4,10 @@
 //! - Real transport tests use AntQuicTransport (for basic creation/start)
 //! - Mock transport tests verify send/receive logic without network dependencies
 
+use saorsa_webrtc_core::link_transport::LinkTransport;
 use saorsa_webrtc_core::signaling::{SignalingMessage, SignalingTransport};
 use saorsa_webrtc_core::transport::{AntQuicTransport, TransportConfig};
+use saorsa_webrtc_core::{ConnectionPoolConfig, WireFormat};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
@@ -260,3 +262,98 @@ async fn test_transport_disconnect() {
     let result = transport1.send_message(&peer_id, message).await;
     assert!(result.is_err());
 }
+
+#[tokio::test]
+async fn test_connect_negotiates_wire_format() {
+    let mut transport1 = AntQuicTransport::new(TransportConfig::default());
+    let mut transport2 = AntQuicTransport::new(TransportConfig::default());
+
+    transport1
+        .start()
+        .await
+        .expect("Failed to start transport1");
+    transport2
+        .start()
+        .await
+        .expect("Failed to start transport2");
+
+    // Connecting sends the hello; nothing else starts the handshake
+    let addr2 = transport2.local_addr().await.expect("Should have addr2");
+    let peer2 = transport1
+        .connect_to_peer(addr2)
+        .await
+        .expect("Failed to connect");
+    let message = SignalingMessage::Offer {
+        session_id: "test".to_string(),
+        sdp: "sdp".to_string(),
+        quic_endpoint: None,
+    };
+    transport1
+        .send_message(&peer2, message)
+        .await
+        .expect("Failed to send");
+
+    // The accepting side handles the hello and replies before the offer.
+    // Receiving fails until its accept loop has seen the connection.
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+    let peer1 = loop {
+        match tokio::time::timeout(Duration::from_secs(1), transport2.receive_message()).await {
+            Ok(Ok((peer, _msg))) => break peer,
+            _ if tokio::time::Instant::now() < deadline => {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+            result => panic!("Failed to receive: {result:?}"),
+        }
+    };
+    assert_eq!(transport2.wire_format(&peer1).await, WireFormat::ALL[0]);
+
+    // The reply is the only frame, so receiving times out after handling it
+    let _ = tokio::time::timeout(Duration::from_secs(1), transport1.receive_message()).await;
+    assert_eq!(transport1.wire_format(&peer2).await, WireFormat::ALL[0]);
+}
+
+#[tokio::test]
+async fn test_sequential_links_beyond_pool_size() {
+    let mut caller = AntQuicTransport::new(TransportConfig {
+        pool: ConnectionPoolConfig {
+            max_connections: 2,
+            ..ConnectionPoolConfig::default()
+        },
+        ..TransportConfig::default()
+    });
+    caller.start().await.expect("Failed to start caller");
+
+    let mut callees = Vec::new();
+    for _ in 0..4 {
+        let mut callee = AntQuicTransport::new(TransportConfig::default());
+        callee.start().await.expect("Failed to start callee");
+        callees.push(callee);
+    }
+
+    // One call after another, each to a new peer, releasing its link when
+    // it ends
+    for callee in &callees {
+        let addr = AntQuicTransport::local_addr(callee)
+            .await
+            .expect("Should have addr");
+        let peer = LinkTransport::connect(&mut caller, addr)
+            .await
+            .expect("Failed to connect");
+        caller.disconnect(&peer).await.expect("Failed to release");
+    }
+    assert_eq!(caller.pooled_connections(), 2);
+
+    // Links still in use are not evicted for new ones
+    for callee in &callees[..2] {
+        let addr = AntQuicTransport::local_addr(callee)
+            .await
+            .expect("Should have addr");
+        LinkTransport::connect(&mut caller, addr)
+            .await
+            .expect("Failed to connect");
+    }
+    let addr = AntQuicTransport::local_addr(&callees[2])
+        .await
+        .expect("Should have addr");
+    assert!(LinkTransport::connect(&mut caller, addr).await.is_err());
+}
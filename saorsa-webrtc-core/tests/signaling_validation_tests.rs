@@ -1,10 +1,13 @@
 //! Signaling validation and edge case tests
 
+use saorsa_webrtc_core::signaling::SignalingMessage;
+#[cfg(feature = "legacy-webrtc")]
 use saorsa_webrtc_core::{
-    call::CallError, identity::PeerIdentityString, signaling::SignalingMessage,
-    types::MediaConstraints, CallManager, CallManagerConfig,
+    call::CallError, identity::PeerIdentityString, types::MediaConstraints, CallManager,
+    CallManagerConfig,
 };
 
+#[cfg(feature = "legacy-webrtc")]
 #[tokio::test]
 #[allow(deprecated)]
 async fn handle_answer_rejects_empty_sdp() {
@@ -24,6 +27,7 @@ async fn handle_answer_rejects_empty_sdp() {
     assert!(matches!(res, Err(CallError::ConfigError(ref msg)) if msg.contains("cannot be empty")));
 }
 
+#[cfg(feature = "legacy-webrtc")]
 #[tokio::test]
 #[allow(deprecated)]
 async fn handle_answer_rejects_malformed_sdp() {
@@ -43,6 +47,7 @@ async fn handle_answer_rejects_malformed_sdp() {
     assert!(matches!(res, Err(CallError::ConfigError(_))));
 }
 
+#[cfg(feature = "legacy-webrtc")]
 #[tokio::test]
 #[allow(deprecated)]
 async fn add_ice_candidate_handles_empty() {
@@ -62,6 +67,7 @@ async fn add_ice_candidate_handles_empty() {
     assert!(res_empty.is_ok() || matches!(res_empty, Err(CallError::ConfigError(_))));
 }
 
+#[cfg(feature = "legacy-webrtc")]
 #[tokio::test]
 #[allow(deprecated)]
 async fn add_ice_candidate_handles_garbage() {
@@ -0,0 +1,47 @@
+//! Checks the published wire protocol test vectors
+//!
+//! `tests/vectors/wire_protocol.json` is what other implementations test
+//! against, so it must conform and must match what this crate generates.
+//! After an intended wire change, regenerate it with:
+//!
+//! ```text
+//! cargo run -p saorsa-webrtc-core --bin saorsa-conformance -- generate \
+//!     saorsa-webrtc-core/tests/vectors/wire_protocol.json
+//! ```
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use saorsa_webrtc_core::conformance::ExpectedFrame;
+use saorsa_webrtc_core::TestVectors;
+
+fn published() -> TestVectors {
+    let json = include_str!("vectors/wire_protocol.json");
+    TestVectors::from_json(json).expect("published vectors parse")
+}
+
+#[test]
+fn published_vectors_conform() {
+    let mismatches = published().check();
+    assert!(mismatches.is_empty(), "{mismatches:#?}");
+}
+
+#[test]
+fn published_vectors_are_current() {
+    let generated = TestVectors::generate().unwrap();
+    let published = published();
+
+    // Compressed frames depend on the compressor build, so only compare
+    // what they decode to
+    let decoded = |vectors: &TestVectors| -> Vec<(String, bool, ExpectedFrame, Option<String>)> {
+        vectors
+            .frames
+            .iter()
+            .map(|v| {
+                let bytes = v.canonical.then(|| v.frame.clone());
+                (v.name.clone(), v.canonical, v.expected.clone(), bytes)
+            })
+            .collect()
+    };
+    assert_eq!(decoded(&published), decoded(&generated));
+    assert_eq!(published.negotiations, generated.negotiations);
+    assert_eq!(published.protocol_version, generated.protocol_version);
+}
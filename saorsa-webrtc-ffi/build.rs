@@ -0,0 +1,45 @@
+//! Generates the C header for the FFI
+//!
+//! The header is always written to `OUT_DIR`. The committed copy in
+//! `include/` is the ABI contract and only changes when the build runs with
+//! `SAORSA_UPDATE_HEADER=1`; `tests/abi.rs` fails while the two differ.
+
+use std::env;
+use std::path::PathBuf;
+
+const HEADER: &str = "saorsa_webrtc.h";
+
+fn main() {
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-env-changed=SAORSA_UPDATE_HEADER");
+    // The ABI test compiles C for the same target
+    if let Ok(target) = env::var("TARGET") {
+        println!("cargo:rustc-env=SAORSA_FFI_TARGET={target}");
+    }
+
+    let (Ok(crate_dir), Ok(out_dir)) = (env::var("CARGO_MANIFEST_DIR"), env::var("OUT_DIR")) else {
+        return;
+    };
+    let crate_dir = PathBuf::from(crate_dir);
+
+    let config = match cbindgen::Config::from_file(crate_dir.join("cbindgen.toml")) {
+        Ok(config) => config,
+        Err(e) => {
+            println!("cargo:warning=failed to read cbindgen.toml: {e}");
+            return;
+        }
+    };
+    let bindings = match cbindgen::generate_with_config(&crate_dir, config) {
+        Ok(bindings) => bindings,
+        Err(e) => {
+            println!("cargo:warning=failed to generate {HEADER}: {e}");
+            return;
+        }
+    };
+
+    bindings.write_to_file(PathBuf::from(out_dir).join(HEADER));
+    if env::var_os("SAORSA_UPDATE_HEADER").is_some() {
+        bindings.write_to_file(crate_dir.join("include").join(HEADER));
+    }
+}
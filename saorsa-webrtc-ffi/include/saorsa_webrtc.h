@@ -0,0 +1,433 @@
+/* saorsa-webrtc C API. Generated by cbindgen from saorsa-webrtc-ffi; do not edit. */
+
+#ifndef SAORSA_WEBRTC_H
+#define SAORSA_WEBRTC_H
+
+/* Regenerate with SAORSA_UPDATE_HEADER=1 cargo build -p saorsa-webrtc-ffi */
+
+#include <stddef.h>
+#include <stdint.h>
+#include <stdbool.h>
+
+/**
+ * Maximum queued events per handle; the oldest are dropped beyond this
+ */
+#define MAX_QUEUED_EVENTS 256
+
+/**
+ * Maximum decoded remote video frames held per call
+ */
+#define MAX_QUEUED_VIDEO_FRAMES 8
+
+/**
+ * Maximum decoded remote audio frames held per call
+ */
+#define MAX_QUEUED_AUDIO_FRAMES 50
+
+/**
+ * Largest accepted video dimension
+ */
+#define MAX_VIDEO_DIMENSION 8192
+
+/**
+ * Largest accepted audio channel count
+ */
+#define MAX_AUDIO_CHANNELS 8
+
+/**
+ * FFI call state
+ */
+typedef enum CallState {
+  /**
+   * Call is being initiated
+   */
+  CALL_STATE_CONNECTING = 0,
+  /**
+   * Call is active
+   */
+  CALL_STATE_ACTIVE = 1,
+  /**
+   * Call is ended
+   */
+  CALL_STATE_ENDED = 2,
+  /**
+   * Call failed
+   */
+  CALL_STATE_FAILED = 3,
+} CallState;
+
+/**
+ * FFI result code
+ */
+typedef enum SaorsaResult {
+  /**
+   * Operation succeeded
+   */
+  SAORSA_RESULT_SUCCESS = 0,
+  /**
+   * Invalid parameter
+   */
+  SAORSA_RESULT_INVALID_PARAMETER = 1,
+  /**
+   * Out of memory
+   */
+  SAORSA_RESULT_OUT_OF_MEMORY = 2,
+  /**
+   * Not initialized
+   */
+  SAORSA_RESULT_NOT_INITIALIZED = 3,
+  /**
+   * Already initialized
+   */
+  SAORSA_RESULT_ALREADY_INITIALIZED = 4,
+  /**
+   * Connection failed
+   */
+  SAORSA_RESULT_CONNECTION_FAILED = 5,
+  /**
+   * Internal error
+   */
+  SAORSA_RESULT_INTERNAL_ERROR = 99,
+} SaorsaResult;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Initialize the library with an identity
+ *
+ * # Safety
+ * `identity` must be a valid null-terminated C string
+ * Returns a handle pointer, or null on error
+ */
+void *saorsa_init(const char *identity);
+
+/**
+ * Start a call to a peer
+ *
+ * # Safety
+ * `handle` must be a valid handle from `saorsa_init`
+ * `peer` must be a valid null-terminated C string
+ * Returns a call ID as a C string (caller must free), or null on error
+ */
+char *saorsa_call(void *handle, const char *peer);
+
+/**
+ * Get the current state of a call
+ *
+ * Ringing incoming calls report `Connecting`.
+ *
+ * # Safety
+ * `handle` must be a valid handle from `saorsa_init`
+ * `call_id` must be a valid null-terminated C string from `saorsa_call`
+ */
+enum CallState saorsa_call_state(void *handle, const char *call_id);
+
+/**
+ * Get the short code of a call, to show or read out in place of its ID
+ *
+ * Every function taking a `call_id` also accepts the call's short code, as
+ * long as no other call of the handle has the same code.
+ *
+ * # Safety
+ * `handle` must be a valid handle from `saorsa_init`
+ * `call_id` must be a valid null-terminated C string from `saorsa_call`
+ * Returns a C string owned by the caller (free with `saorsa_free_string`),
+ * or null if the handle or call is unknown
+ */
+char *saorsa_call_short_code(void *handle, const char *call_id);
+
+/**
+ * End a call
+ *
+ * Declines a ringing call. Ending an already ended call succeeds without
+ * effect.
+ *
+ * # Safety
+ * `handle` must be a valid handle from `saorsa_init`
+ * `call_id` must be a valid null-terminated C string from `saorsa_call`
+ */
+enum SaorsaResult saorsa_end_call(void *handle, const char *call_id);
+
+/**
+ * Register an incoming call announced out of band
+ *
+ * For VoIP push: the app reports the call to the system call UI (CallKit's
+ * `reportNewIncomingCall`) as soon as the push arrives, then hands the
+ * caller to the library. The call rings, reporting `Connecting`, until
+ * answered with `saorsa_answer_call` or declined with `saorsa_end_call`.
+ *
+ * While do-not-disturb is on the call does not ring: a `missed_call`
+ * event is raised instead of `incoming_call` and the call is already
+ * `Ended`, so the app should end the call it reported to the system.
+ *
+ * # Safety
+ * `handle` must be a valid handle from `saorsa_init`
+ * `peer` must be a valid null-terminated C string
+ * Returns a call ID as a C string (caller must free), or null on error
+ */
+char *saorsa_receive_incoming_call(void *handle, const char *peer);
+
+/**
+ * Answer a ringing incoming call
+ *
+ * Called from the system call UI's answer action (CallKit's
+ * `CXAnswerCallAction`).
+ *
+ * # Safety
+ * `handle` must be a valid handle from `saorsa_init`
+ * `call_id` must be a valid null-terminated C string from
+ * `saorsa_receive_incoming_call`
+ */
+enum SaorsaResult saorsa_answer_call(void *handle, const char *call_id);
+
+/**
+ * Mute or unmute a call's outgoing audio
+ *
+ * Called from the system call UI's mute action (CallKit's
+ * `CXSetMutedCallAction`) as well as the app's own UI. Muted audio frames
+ * are accepted by `saorsa_push_audio_frame` and discarded.
+ *
+ * # Safety
+ * `handle` must be a valid handle from `saorsa_init`
+ * `call_id` must be a valid null-terminated C string identifying a call
+ */
+enum SaorsaResult saorsa_set_muted(void *handle, const char *call_id, bool muted);
+
+/**
+ * Turn do-not-disturb on or off
+ *
+ * While on, calls passed to `saorsa_receive_incoming_call` do not ring.
+ *
+ * # Safety
+ * `handle` must be a valid handle from `saorsa_init`
+ */
+enum SaorsaResult saorsa_set_do_not_disturb(void *handle, bool enabled);
+
+/**
+ * Set the daily quiet hours during which do-not-disturb is on
+ *
+ * `hours` lists comma-separated `HH:MM-HH:MM` ranges in local time, which
+ * is `utc_offset_minutes` east of UTC; an empty string removes the quiet
+ * hours. A range ending before it starts runs past midnight.
+ *
+ * # Safety
+ * `handle` must be a valid handle from `saorsa_init`
+ * `hours` must be a valid null-terminated C string
+ */
+enum SaorsaResult saorsa_set_quiet_hours(void *handle,
+                                         const char *hours,
+                                         int32_t utc_offset_minutes);
+
+/**
+ * Tell the library whether the platform audio session is active
+ *
+ * With CallKit the system activates the audio session itself; forward
+ * `provider(_:didActivate:)` and `provider(_:didDeactivate:)` here. While
+ * inactive, pushed audio is discarded and no remote audio is handed out.
+ * Sessions start active, so apps without CallKit never need this.
+ *
+ * # Safety
+ * `handle` must be a valid handle from `saorsa_init`
+ */
+enum SaorsaResult saorsa_set_audio_session_active(void *handle, bool active);
+
+/**
+ * Turn discontinuous transmission (DTX) on or off
+ *
+ * With DTX on, pushed audio frames classified as silence are not sent and
+ * their bytes are reported as `dtx_bytes_saved` in the call statistics.
+ * DTX starts off.
+ *
+ * # Safety
+ * `handle` must be a valid handle from `saorsa_init`
+ */
+enum SaorsaResult saorsa_set_dtx(void *handle, bool enabled);
+
+/**
+ * Take the next pending event as JSON
+ *
+ * The event is copied into the caller-owned buffer `buf` of `len` bytes
+ * and NUL-terminated; the library never retains `buf`. Events are JSON
+ * objects with a `type` field (`call_started`, `call_ended`,
+ * `incoming_call`, `missed_call`, `call_answered`, `mute_changed`,
+ * `audio_session_changed`, `events_dropped`).
+ *
+ * Returns:
+ * - `n > 0`: an event of `n` bytes (excluding the NUL) was written and
+ *   removed from the queue
+ * - `0`: no event is pending
+ * - `-1`: invalid handle
+ * - `n < -1`: `buf` is null or too small; `-n` bytes (including the NUL)
+ *   are needed and the event stays queued
+ *
+ * # Safety
+ * `handle` must be a valid handle from `saorsa_init`
+ * `buf` must be null or point to at least `len` writable bytes
+ */
+ptrdiff_t saorsa_poll_event(void *handle, char *buf, size_t len);
+
+/**
+ * Deliver events to a callback instead of the poll queue
+ *
+ * The callback receives each event as NUL-terminated JSON, valid only for
+ * the duration of the call, together with `user_data`. It runs
+ * synchronously on the thread of the library call that raised the event,
+ * and may call back into the library. Passing a null callback returns to
+ * queueing for `saorsa_poll_event`.
+ *
+ * # Safety
+ * `handle` must be a valid handle from `saorsa_init`
+ * `user_data` is passed through untouched and must stay valid, and safe to
+ * use from any thread, until the callback is replaced or the handle freed
+ */
+enum SaorsaResult saorsa_set_event_callback(void *handle,
+                                            void (*callback)(const char *event, void *user_data),
+                                            void *user_data);
+
+/**
+ * Get a JSON statistics snapshot of a call
+ *
+ * The snapshot has `call_id`, `peer`, `active`, `duration_ms`, packet
+ * and byte counters, and talk statistics of the outgoing audio:
+ * `talk_ms`, `silence_ms`, `silence_ratio`, `talk_bursts` and
+ * `dtx_bytes_saved`. Ended calls report their final statistics.
+ *
+ * # Safety
+ * `handle` must be a valid handle from `saorsa_init`
+ * `call_id` must be a valid null-terminated C string from `saorsa_call`
+ * Returns a C string owned by the caller (free with `saorsa_free_string`),
+ * or null if the handle or call is unknown
+ */
+char *saorsa_get_call_stats(void *handle, const char *call_id);
+
+/**
+ * Push a locally captured I420 video frame into a call
+ *
+ * For apps that capture through platform APIs. `buf` holds the Y, U and V
+ * planes back to back, `i420_size(width, height)` bytes in total, and is
+ * copied before returning. `timestamp_us` is the capture time in
+ * microseconds.
+ *
+ * Until the FFI is wired to a media transport, pushed frames are looped
+ * back as remote media so rendering paths can be exercised end to end.
+ *
+ * # Safety
+ * `handle` must be a valid handle from `saorsa_init`
+ * `call_id` must be a valid null-terminated C string from `saorsa_call`
+ * `buf` must point to `i420_size(width, height)` readable bytes
+ */
+enum SaorsaResult saorsa_push_video_frame(void *handle,
+                                          const char *call_id,
+                                          const uint8_t *buf,
+                                          uint32_t width,
+                                          uint32_t height,
+                                          uint64_t timestamp_us);
+
+/**
+ * Push a locally captured audio frame into a call
+ *
+ * `samples` holds `sample_count` interleaved 16-bit PCM samples across
+ * `channels` channels (at most `MAX_AUDIO_CHANNELS`) and is copied before
+ * returning; `sample_count` must be a multiple of `channels`.
+ *
+ * Like video, pushed audio is looped back as remote media until the FFI is
+ * wired to a media transport. Audio pushed while the call is muted or the
+ * audio session is inactive is discarded. Every other frame counts toward
+ * the call's talk statistics, and with DTX on silent frames are not sent.
+ *
+ * # Safety
+ * `handle` must be a valid handle from `saorsa_init`
+ * `call_id` must be a valid null-terminated C string from `saorsa_call`
+ * `samples` must point to `sample_count` readable samples
+ */
+enum SaorsaResult saorsa_push_audio_frame(void *handle,
+                                          const char *call_id,
+                                          const int16_t *samples,
+                                          size_t sample_count,
+                                          uint32_t sample_rate,
+                                          uint32_t channels,
+                                          uint64_t timestamp_us);
+
+/**
+ * Take the next remote video frame of a call
+ *
+ * The I420 frame is copied into the caller-owned buffer `buf` of `len`
+ * bytes; `width`, `height` and `timestamp_us` receive its format when not
+ * null. When rendering falls behind, only the newest
+ * `MAX_QUEUED_VIDEO_FRAMES` frames are kept.
+ *
+ * Returns:
+ * - `n > 0`: a frame of `n` bytes was written and removed from the queue
+ * - `0`: no frame is pending
+ * - `-1`: invalid handle or unknown/ended call
+ * - `n < -1`: `buf` is null or too small; `-n` bytes are needed and the
+ *   frame stays queued
+ *
+ * # Safety
+ * `handle` must be a valid handle from `saorsa_init`
+ * `call_id` must be a valid null-terminated C string from `saorsa_call`
+ * `buf` must be null or point to at least `len` writable bytes; the out
+ * pointers must be null or valid for writes
+ */
+ptrdiff_t saorsa_pull_video_frame(void *handle,
+                                  const char *call_id,
+                                  uint8_t *buf,
+                                  size_t len,
+                                  uint32_t *width,
+                                  uint32_t *height,
+                                  uint64_t *timestamp_us);
+
+/**
+ * Take the next remote audio frame of a call
+ *
+ * Interleaved 16-bit PCM is copied into the caller-owned buffer `buf` of
+ * `len` samples; `sample_rate`, `channels` and `timestamp_us` receive its
+ * format when not null. Only the newest `MAX_QUEUED_AUDIO_FRAMES` frames
+ * are kept, and none are handed out while the audio session is inactive.
+ *
+ * Returns:
+ * - `n > 0`: a frame of `n` samples was written and removed from the queue
+ * - `0`: no frame is pending
+ * - `-1`: invalid handle or unknown/ended call
+ * - `n < -1`: `buf` is null or too small; `-n` samples are needed and the
+ *   frame stays queued
+ *
+ * # Safety
+ * `handle` must be a valid handle from `saorsa_init`
+ * `call_id` must be a valid null-terminated C string from `saorsa_call`
+ * `buf` must be null or point to at least `len` writable samples; the out
+ * pointers must be null or valid for writes
+ */
+ptrdiff_t saorsa_pull_audio_frame(void *handle,
+                                  const char *call_id,
+                                  int16_t *buf,
+                                  size_t len,
+                                  uint32_t *sample_rate,
+                                  uint32_t *channels,
+                                  uint64_t *timestamp_us);
+
+/**
+ * Free a string returned by the library
+ *
+ * # Safety
+ * `str_ptr` must be a string previously returned by this library
+ * After calling this, `str_ptr` is invalid and must not be used
+ */
+void saorsa_free_string(char *str_ptr);
+
+/**
+ * Free library resources
+ *
+ * # Safety
+ * `handle` must be a valid handle from `saorsa_init`
+ * After calling this, `handle` is invalid and must not be used
+ */
+void saorsa_free(void *handle);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* SAORSA_WEBRTC_H */
@@ -0,0 +1,352 @@
+//! Android JNI bindings
+//!
+//! Enabled with the `android` feature on Android targets. The natives back
+//! a Java class wrapping the C API, plus helpers for audio routing and a
+//! foreground call service:
+//!
+//! ```java
+//! package com.saorsa.webrtc;
+//!
+//! public final class SaorsaWebRtc {
+//!     static { System.loadLibrary("saorsa_webrtc_ffi"); }
+//!
+//!     public static native long nativeInit(String identity);
+//!     public static native String nativeCall(long handle, String peer);
+//!     public static native int nativeCallState(long handle, String callId);
+//!     public static native int nativeEndCall(long handle, String callId);
+//!     public static native String nativePollEvent(long handle);
+//!     public static native String nativeGetCallStats(long handle, String callId);
+//!     public static native void nativeFree(long handle);
+//!
+//!     public static native int nativeSetCallServiceHooks(long handle, CallServiceHooks hooks);
+//!     public static native boolean nativeSetAudioRoute(Context context, int route);
+//!     public static native boolean nativeResetAudio(Context context);
+//! }
+//!
+//! public interface CallServiceHooks {
+//!     void onCallStarted(String callId, String peer);
+//!     void onCallEnded(String callId);
+//! }
+//! ```
+//!
+//! Return codes match the C API (`SaorsaResult`, `CallState`). A typical
+//! app starts its foreground service (type `phoneCall`) from
+//! `onCallStarted` and stops it from `onCallEnded` once no calls remain.
+
+use crate::{
+    saorsa_call, saorsa_call_state, saorsa_end_call, saorsa_free, saorsa_free_string,
+    saorsa_get_call_stats, saorsa_init, saorsa_poll_event, FfiEvent, SaorsaResult,
+};
+use jni::objects::{GlobalRef, JClass, JObject, JString, JValue};
+use jni::sys::{jboolean, jint, jlong, jstring, JNI_FALSE, JNI_TRUE};
+use jni::{JNIEnv, JavaVM};
+use std::ffi::{c_char, c_void, CString};
+use std::sync::Arc;
+
+/// `AudioManager.MODE_NORMAL`
+const MODE_NORMAL: jint = 0;
+
+/// `AudioManager.MODE_IN_COMMUNICATION`
+const MODE_IN_COMMUNICATION: jint = 3;
+
+/// Where call audio is played
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioRoute {
+    /// Handset earpiece
+    Earpiece = 0,
+    /// Loudspeaker
+    Speaker = 1,
+    /// Bluetooth headset over SCO
+    Bluetooth = 2,
+}
+
+impl AudioRoute {
+    fn from_jint(route: jint) -> Option<Self> {
+        match route {
+            0 => Some(Self::Earpiece),
+            1 => Some(Self::Speaker),
+            2 => Some(Self::Bluetooth),
+            _ => None,
+        }
+    }
+}
+
+fn to_handle(handle: jlong) -> *mut c_void {
+    handle as usize as *mut c_void
+}
+
+/// Run `f` with a Java string as a C string
+fn with_c_str<T>(env: &mut JNIEnv, s: &JString, f: impl FnOnce(*const c_char) -> T) -> Option<T> {
+    let s: String = env.get_string(s).ok()?.into();
+    let s = CString::new(s).ok()?;
+    Some(f(s.as_ptr()))
+}
+
+/// Convert a string owned by the library to a Java string, freeing it
+fn into_jstring(env: &mut JNIEnv, s: *mut c_char) -> jstring {
+    if s.is_null() {
+        return std::ptr::null_mut();
+    }
+    let value = unsafe { crate::c_char_to_string(s) };
+    saorsa_free_string(s);
+    value
+        .and_then(|value| env.new_string(value).ok())
+        .map_or(std::ptr::null_mut(), |value| value.into_raw())
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_saorsa_webrtc_SaorsaWebRtc_nativeInit(
+    mut env: JNIEnv,
+    _class: JClass,
+    identity: JString,
+) -> jlong {
+    with_c_str(&mut env, &identity, |identity| saorsa_init(identity))
+        .map_or(0, |handle| handle as usize as jlong)
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_saorsa_webrtc_SaorsaWebRtc_nativeCall(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    peer: JString,
+) -> jstring {
+    let call_id = with_c_str(&mut env, &peer, |peer| saorsa_call(to_handle(handle), peer));
+    into_jstring(&mut env, call_id.unwrap_or(std::ptr::null_mut()))
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_saorsa_webrtc_SaorsaWebRtc_nativeCallState(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    call_id: JString,
+) -> jint {
+    with_c_str(&mut env, &call_id, |call_id| {
+        saorsa_call_state(to_handle(handle), call_id)
+    })
+    .unwrap_or(crate::CallState::Failed) as jint
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_saorsa_webrtc_SaorsaWebRtc_nativeEndCall(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    call_id: JString,
+) -> jint {
+    with_c_str(&mut env, &call_id, |call_id| {
+        saorsa_end_call(to_handle(handle), call_id)
+    })
+    .unwrap_or(SaorsaResult::InvalidParameter) as jint
+}
+
+/// Next queued event as JSON, or null if none is pending
+#[no_mangle]
+pub extern "system" fn Java_com_saorsa_webrtc_SaorsaWebRtc_nativePollEvent(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let mut buf = vec![0 as c_char; 512];
+    loop {
+        let written = saorsa_poll_event(to_handle(handle), buf.as_mut_ptr(), buf.len());
+        if written < -1 {
+            buf.resize(written.unsigned_abs(), 0);
+            continue;
+        }
+        if written <= 0 {
+            return std::ptr::null_mut();
+        }
+        let event = unsafe { crate::c_char_to_string(buf.as_ptr()) };
+        return event
+            .and_then(|event| env.new_string(event).ok())
+            .map_or(std::ptr::null_mut(), |event| event.into_raw());
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_saorsa_webrtc_SaorsaWebRtc_nativeGetCallStats(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    call_id: JString,
+) -> jstring {
+    let stats = with_c_str(&mut env, &call_id, |call_id| {
+        saorsa_get_call_stats(to_handle(handle), call_id)
+    });
+    into_jstring(&mut env, stats.unwrap_or(std::ptr::null_mut()))
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_saorsa_webrtc_SaorsaWebRtc_nativeFree(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) {
+    saorsa_free(to_handle(handle));
+}
+
+/// Route call events to a `CallServiceHooks` object, or back to the poll
+/// queue when `hooks` is null
+#[no_mangle]
+pub extern "system" fn Java_com_saorsa_webrtc_SaorsaWebRtc_nativeSetCallServiceHooks(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    hooks: JObject,
+) -> jint {
+    let Some(handle) = crate::get_handle(to_handle(handle)) else {
+        return SaorsaResult::InvalidParameter as jint;
+    };
+    if hooks.is_null() {
+        return handle.set_listener(None) as jint;
+    }
+    let (Ok(vm), Ok(hooks)) = (env.get_java_vm(), env.new_global_ref(hooks)) else {
+        return SaorsaResult::InternalError as jint;
+    };
+    let hooks = Arc::new((vm, hooks));
+    handle.set_listener(Some(Arc::new(move |event: &FfiEvent| {
+        let (vm, hooks) = &*hooks;
+        dispatch_hook(vm, hooks, event);
+    }))) as jint
+}
+
+fn dispatch_hook(vm: &JavaVM, hooks: &GlobalRef, event: &FfiEvent) {
+    let Ok(mut env) = vm.attach_current_thread() else {
+        return;
+    };
+    let result = match event {
+        FfiEvent::CallStarted { call_id, peer } => {
+            match (env.new_string(call_id), env.new_string(peer)) {
+                (Ok(call_id), Ok(peer)) => env
+                    .call_method(
+                        hooks.as_obj(),
+                        "onCallStarted",
+                        "(Ljava/lang/String;Ljava/lang/String;)V",
+                        &[JValue::Object(&call_id), JValue::Object(&peer)],
+                    )
+                    .map(drop),
+                (Err(e), _) | (_, Err(e)) => Err(e),
+            }
+        }
+        FfiEvent::CallEnded { call_id } => env.new_string(call_id).and_then(|call_id| {
+            env.call_method(
+                hooks.as_obj(),
+                "onCallEnded",
+                "(Ljava/lang/String;)V",
+                &[JValue::Object(&call_id)],
+            )
+            .map(drop)
+        }),
+        // The app drives these itself, and drops are only raised by the
+        // poll queue
+        FfiEvent::IncomingCall { .. }
+        | FfiEvent::MissedCall { .. }
+        | FfiEvent::CallAnswered { .. }
+        | FfiEvent::MuteChanged { .. }
+        | FfiEvent::AudioSessionChanged { .. }
+        | FfiEvent::EventsDropped { .. } => Ok(()),
+    };
+    // A throwing hook must not leave an exception pending on this thread
+    if result.is_err() && env.exception_check().unwrap_or(false) {
+        let _ = env.exception_clear();
+    }
+}
+
+/// Route call audio to the earpiece, speaker or a Bluetooth headset
+///
+/// Puts `AudioManager` in communication mode. Uses the speakerphone and SCO
+/// controls, which are deprecated from API 31 in favour of
+/// `setCommunicationDevice` but still honoured.
+#[no_mangle]
+pub extern "system" fn Java_com_saorsa_webrtc_SaorsaWebRtc_nativeSetAudioRoute(
+    mut env: JNIEnv,
+    _class: JClass,
+    context: JObject,
+    route: jint,
+) -> jboolean {
+    let Some(route) = AudioRoute::from_jint(route) else {
+        return JNI_FALSE;
+    };
+    to_jboolean(&mut env, |env| {
+        let audio = audio_manager(env, &context)?;
+        let bluetooth = route == AudioRoute::Bluetooth;
+        set_mode(env, &audio, MODE_IN_COMMUNICATION)?;
+        call_bool(
+            env,
+            &audio,
+            "setSpeakerphoneOn",
+            route == AudioRoute::Speaker,
+        )?;
+        let sco = if bluetooth {
+            "startBluetoothSco"
+        } else {
+            "stopBluetoothSco"
+        };
+        env.call_method(&audio, sco, "()V", &[])?;
+        call_bool(env, &audio, "setBluetoothScoOn", bluetooth)
+    })
+}
+
+/// Restore normal audio mode after the last call
+#[no_mangle]
+pub extern "system" fn Java_com_saorsa_webrtc_SaorsaWebRtc_nativeResetAudio(
+    mut env: JNIEnv,
+    _class: JClass,
+    context: JObject,
+) -> jboolean {
+    to_jboolean(&mut env, |env| {
+        let audio = audio_manager(env, &context)?;
+        call_bool(env, &audio, "setSpeakerphoneOn", false)?;
+        env.call_method(&audio, "stopBluetoothSco", "()V", &[])?;
+        call_bool(env, &audio, "setBluetoothScoOn", false)?;
+        set_mode(env, &audio, MODE_NORMAL)
+    })
+}
+
+fn audio_manager<'local>(
+    env: &mut JNIEnv<'local>,
+    context: &JObject,
+) -> jni::errors::Result<JObject<'local>> {
+    let service = env.new_string("audio")?;
+    env.call_method(
+        context,
+        "getSystemService",
+        "(Ljava/lang/String;)Ljava/lang/Object;",
+        &[JValue::Object(&service)],
+    )?
+    .l()
+}
+
+fn set_mode(env: &mut JNIEnv, audio: &JObject, mode: jint) -> jni::errors::Result<()> {
+    env.call_method(audio, "setMode", "(I)V", &[JValue::Int(mode)])
+        .map(drop)
+}
+
+fn call_bool(
+    env: &mut JNIEnv,
+    audio: &JObject,
+    method: &str,
+    value: bool,
+) -> jni::errors::Result<()> {
+    let value = if value { JNI_TRUE } else { JNI_FALSE };
+    env.call_method(audio, method, "(Z)V", &[JValue::Bool(value)])
+        .map(drop)
+}
+
+/// Run a JNI sequence, turning failures (and pending exceptions) into false
+fn to_jboolean(
+    env: &mut JNIEnv,
+    f: impl FnOnce(&mut JNIEnv) -> jni::errors::Result<()>,
+) -> jboolean {
+    match f(env) {
+        Ok(()) => JNI_TRUE,
+        Err(_) => {
+            if env.exception_check().unwrap_or(false) {
+                let _ = env.exception_clear();
+            }
+            JNI_FALSE
+        }
+    }
+}
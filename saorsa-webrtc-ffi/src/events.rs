@@ -0,0 +1,272 @@
+//! Polled events and call statistics
+//!
+//! Runtimes that cannot accept callbacks drain events with
+//! `saorsa_poll_event` and query `saorsa_get_call_stats`; both hand out
+//! JSON so bindings only need a JSON parser.
+
+use crate::media_io::RemoteMedia;
+use saorsa_webrtc_core::talk::{TalkConfig, TalkStats, TalkTracker};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Maximum queued events per handle; the oldest are dropped beyond this
+pub const MAX_QUEUED_EVENTS: usize = 256;
+
+/// Event delivered through `saorsa_poll_event`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FfiEvent {
+    /// An outgoing call was started
+    CallStarted {
+        /// Call identifier
+        call_id: String,
+        /// Remote peer
+        peer: String,
+    },
+    /// A call ended
+    CallEnded {
+        /// Call identifier
+        call_id: String,
+    },
+    /// An incoming call is ringing
+    IncomingCall {
+        /// Call identifier
+        call_id: String,
+        /// Calling peer
+        peer: String,
+    },
+    /// An incoming call did not ring because do-not-disturb is on
+    MissedCall {
+        /// Call identifier
+        call_id: String,
+        /// Calling peer
+        peer: String,
+    },
+    /// An incoming call was answered
+    CallAnswered {
+        /// Call identifier
+        call_id: String,
+    },
+    /// Outgoing audio of a call was muted or unmuted
+    MuteChanged {
+        /// Call identifier
+        call_id: String,
+        /// Whether audio is muted
+        muted: bool,
+    },
+    /// The platform audio session was activated or deactivated
+    AudioSessionChanged {
+        /// Whether audio may flow
+        active: bool,
+    },
+    /// Events were dropped because the queue was full
+    EventsDropped {
+        /// Number of dropped events
+        count: u64,
+    },
+}
+
+/// Receives events in place of the poll queue
+pub(crate) type EventListener = Arc<dyn Fn(&FfiEvent) + Send + Sync>;
+
+/// Bounded queue of JSON-encoded events
+#[derive(Debug, Default)]
+pub struct EventQueue {
+    events: VecDeque<String>,
+    dropped: u64,
+}
+
+impl EventQueue {
+    /// Queue an event, dropping the oldest if full
+    pub fn push(&mut self, event: &FfiEvent) {
+        let Ok(json) = serde_json::to_string(event) else {
+            return;
+        };
+        if self.events.len() >= MAX_QUEUED_EVENTS {
+            self.events.pop_front();
+            self.dropped += 1;
+        }
+        self.events.push_back(json);
+    }
+
+    /// Next event, preceded by a drop notice if events were lost
+    pub fn front(&mut self) -> Option<&String> {
+        if self.dropped > 0 {
+            let notice = FfiEvent::EventsDropped {
+                count: self.dropped,
+            };
+            if let Ok(json) = serde_json::to_string(&notice) {
+                self.events.push_front(json);
+            }
+            self.dropped = 0;
+        }
+        self.events.front()
+    }
+
+    /// Remove the next event
+    pub fn pop(&mut self) {
+        self.events.pop_front();
+    }
+}
+
+/// State of a call made through the FFI
+#[derive(Debug)]
+pub struct FfiCall {
+    /// Remote peer
+    pub peer: String,
+    /// When the call started
+    pub started: Instant,
+    /// When the call ended, if it has
+    pub ended: Option<Instant>,
+    /// Incoming and not yet answered
+    pub ringing: bool,
+    /// Outgoing audio is muted
+    pub muted: bool,
+    /// Media packets sent
+    pub packets_sent: u64,
+    /// Media packets received
+    pub packets_received: u64,
+    /// Media bytes sent
+    pub bytes_sent: u64,
+    /// Media bytes received
+    pub bytes_received: u64,
+    /// Talk time and silence of outgoing audio
+    pub talk: TalkTracker,
+    /// Remote frames awaiting the app's renderer
+    pub remote: RemoteMedia,
+}
+
+impl FfiCall {
+    /// Start tracking an outgoing call
+    pub fn new(peer: String) -> Self {
+        Self {
+            peer,
+            started: Instant::now(),
+            ended: None,
+            ringing: false,
+            muted: false,
+            packets_sent: 0,
+            packets_received: 0,
+            bytes_sent: 0,
+            bytes_received: 0,
+            talk: TalkTracker::new(TalkConfig::default()),
+            remote: RemoteMedia::default(),
+        }
+    }
+
+    /// Start tracking a ringing incoming call
+    pub fn incoming(peer: String) -> Self {
+        Self {
+            ringing: true,
+            ..Self::new(peer)
+        }
+    }
+
+    /// Statistics snapshot
+    pub fn stats(&self, call_id: &str) -> CallStats {
+        let until = self.ended.unwrap_or_else(Instant::now);
+        let talk = self.talk.stats();
+        CallStats {
+            call_id: call_id.to_string(),
+            peer: self.peer.clone(),
+            active: self.ended.is_none(),
+            muted: self.muted,
+            duration_ms: until.duration_since(self.started).as_millis() as u64,
+            packets_sent: self.packets_sent,
+            packets_received: self.packets_received,
+            bytes_sent: self.bytes_sent,
+            bytes_received: self.bytes_received,
+            silence_ratio: talk.silence_ratio(),
+            talk,
+        }
+    }
+}
+
+/// Statistics snapshot returned by `saorsa_get_call_stats`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CallStats {
+    /// Call identifier
+    pub call_id: String,
+    /// Remote peer
+    pub peer: String,
+    /// Whether the call is still active
+    pub active: bool,
+    /// Whether outgoing audio is muted
+    pub muted: bool,
+    /// Call duration in milliseconds
+    pub duration_ms: u64,
+    /// Media packets sent
+    pub packets_sent: u64,
+    /// Media packets received
+    pub packets_received: u64,
+    /// Media bytes sent
+    pub bytes_sent: u64,
+    /// Media bytes received
+    pub bytes_received: u64,
+    /// Talk time, talk bursts and bytes saved by DTX
+    #[serde(flatten)]
+    pub talk: TalkStats,
+    /// Fraction of outgoing audio that was silence
+    pub silence_ratio: f64,
+}
+
+/// JSON Schemas of [`FfiEvent`] and [`CallStats`], named for publishing
+#[cfg(feature = "schema")]
+#[must_use]
+pub fn schemas() -> Vec<saorsa_webrtc_core::schema::NamedSchema> {
+    vec![
+        ("ffi-event", schemars::schema_for!(FfiEvent)),
+        ("call-stats", schemars::schema_for!(CallStats)),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_json() {
+        let event = FfiEvent::CallStarted {
+            call_id: "c1".to_string(),
+            peer: "bob".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_string(&event).ok().as_deref(),
+            Some(r#"{"type":"call_started","call_id":"c1","peer":"bob"}"#)
+        );
+    }
+
+    #[test]
+    fn test_queue_overflow_reports_drops() {
+        let mut queue = EventQueue::default();
+        for i in 0..MAX_QUEUED_EVENTS + 2 {
+            queue.push(&FfiEvent::CallEnded {
+                call_id: i.to_string(),
+            });
+        }
+
+        assert_eq!(
+            queue.front().map(String::as_str),
+            Some(r#"{"type":"events_dropped","count":2}"#)
+        );
+        queue.pop();
+        assert_eq!(
+            queue.front().map(String::as_str),
+            Some(r#"{"type":"call_ended","call_id":"2"}"#)
+        );
+    }
+
+    /// Published in `schemas/` at the repository root; regenerate with
+    /// `SAORSA_UPDATE_SCHEMAS=1 cargo test -p saorsa-webrtc-ffi --features schema`
+    #[cfg(feature = "schema")]
+    #[test]
+    fn test_published_schemas_are_current() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../schemas");
+        let stale = saorsa_webrtc_core::schema::check_published(&dir, &schemas());
+        assert_eq!(stale.ok(), Some(Vec::new()));
+    }
+}
@@ -0,0 +1,137 @@
+//! Generation-tagged handle table
+//!
+//! A handle packs a slot index with the slot's generation. Freeing a handle
+//! bumps the generation before the slot is reused, so a stale or forged
+//! handle no longer matches and is rejected instead of reaching whatever
+//! now occupies the slot.
+
+/// Bits of a handle holding the generation; the rest hold the slot index
+const GENERATION_BITS: u32 = usize::BITS / 2;
+
+/// Mask selecting the generation
+const GENERATION_MASK: usize = (1 << GENERATION_BITS) - 1;
+
+/// Largest slot index that fits in a handle
+const MAX_INDEX: usize = usize::MAX >> GENERATION_BITS;
+
+#[derive(Debug)]
+struct Slot<T> {
+    generation: usize,
+    value: Option<T>,
+}
+
+/// Slots addressed by generation-tagged handles
+#[derive(Debug)]
+pub struct HandleTable<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<usize>,
+}
+
+impl<T> Default for HandleTable<T> {
+    fn default() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+}
+
+impl<T> HandleTable<T> {
+    /// Store a value, returning its handle, or `None` if the table is full
+    ///
+    /// Handles are never zero, so they cannot be mistaken for null.
+    pub fn insert(&mut self, value: T) -> Option<usize> {
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                let index = self.slots.len();
+                if index > MAX_INDEX {
+                    return None;
+                }
+                self.slots.push(Slot {
+                    generation: 1,
+                    value: None,
+                });
+                index
+            }
+        };
+        let slot = self.slots.get_mut(index)?;
+        slot.value = Some(value);
+        Some(index << GENERATION_BITS | slot.generation)
+    }
+
+    /// Value of a live handle
+    pub fn get(&self, handle: usize) -> Option<&T> {
+        let (index, generation) = split(handle);
+        self.slots
+            .get(index)
+            .filter(|slot| slot.generation == generation)?
+            .value
+            .as_ref()
+    }
+
+    /// Remove a live handle, invalidating it and every copy of it
+    pub fn remove(&mut self, handle: usize) -> Option<T> {
+        let (index, generation) = split(handle);
+        let slot = self
+            .slots
+            .get_mut(index)
+            .filter(|slot| slot.generation == generation)?;
+        let value = slot.value.take()?;
+        // A slot whose generation is exhausted is retired rather than
+        // wrapping back to a generation old handles might still carry
+        if slot.generation < GENERATION_MASK {
+            slot.generation += 1;
+            self.free.push(index);
+        }
+        Some(value)
+    }
+}
+
+fn split(handle: usize) -> (usize, usize) {
+    (handle >> GENERATION_BITS, handle & GENERATION_MASK)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reused_slot_rejects_stale_handle() {
+        let mut table = HandleTable::default();
+        let first = table.insert("first").unwrap_or_default();
+        assert_ne!(first, 0);
+        assert_eq!(table.remove(first), Some("first"));
+
+        let second = table.insert("second").unwrap_or_default();
+        assert_eq!(split(second).0, split(first).0);
+        assert_ne!(second, first);
+        assert_eq!(table.get(first), None);
+        assert_eq!(table.remove(first), None);
+        assert_eq!(table.get(second), Some(&"second"));
+    }
+
+    #[test]
+    fn test_forged_handles_rejected() {
+        let mut table = HandleTable::default();
+        let handle = table.insert(1u8).unwrap_or_default();
+        for forged in [0, handle + 1, handle ^ (1 << GENERATION_BITS), usize::MAX] {
+            assert_eq!(table.get(forged), None);
+        }
+    }
+
+    #[test]
+    fn test_exhausted_slot_is_retired() {
+        let mut table = HandleTable::default();
+        let handle = table.insert(1u8).unwrap_or_default();
+        if let Some(slot) = table.slots.get_mut(0) {
+            slot.generation = GENERATION_MASK;
+        }
+        let exhausted = split(handle).0 << GENERATION_BITS | GENERATION_MASK;
+        assert_eq!(table.remove(exhausted), Some(1));
+        assert!(table.free.is_empty());
+
+        let next = table.insert(2u8).unwrap_or_default();
+        assert_eq!(split(next).0, 1);
+    }
+}
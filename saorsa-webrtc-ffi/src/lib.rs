@@ -5,11 +5,28 @@
 #![deny(clippy::expect_used)]
 #![allow(clippy::not_unsafe_ptr_arg_deref)]
 
+#[cfg(all(target_os = "android", feature = "android"))]
+mod android;
+mod events;
+mod handles;
+mod media_io;
 mod types;
 
+#[cfg(feature = "schema")]
+pub use events::schemas;
+pub use events::{CallStats, FfiEvent, MAX_QUEUED_EVENTS};
+use events::{EventListener, EventQueue, FfiCall};
+use handles::HandleTable;
+pub use media_io::{
+    i420_size, AudioFrame, VideoFrame, MAX_AUDIO_CHANNELS, MAX_QUEUED_AUDIO_FRAMES,
+    MAX_QUEUED_VIDEO_FRAMES, MAX_VIDEO_DIMENSION,
+};
 use once_cell::sync::Lazy;
+use saorsa_webrtc_core::dnd::{DoNotDisturb, QuietHours};
+use saorsa_webrtc_core::types::{CallId, ShortCode};
 use std::collections::HashMap;
 use std::ffi::c_char;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::sync::Mutex;
 pub use types::{c_char_to_string, string_to_c_char, CallState, SaorsaResult};
@@ -32,25 +49,122 @@ static RUNTIME: Lazy<tokio::runtime::Runtime> = Lazy::new(|| {
 });
 
 /// Global handle storage
-static HANDLES: Lazy<Mutex<HashMap<usize, Arc<SaorsaHandle>>>> =
-    Lazy::new(|| Mutex::new(HashMap::new()));
-
-/// Handle counter
-static HANDLE_COUNTER: Lazy<Mutex<usize>> = Lazy::new(|| Mutex::new(1));
+static HANDLES: Lazy<Mutex<HandleTable<Arc<SaorsaHandle>>>> =
+    Lazy::new(|| Mutex::new(HandleTable::default()));
 
 /// Internal handle structure
 struct SaorsaHandle {
     #[allow(dead_code)]
     identity: String,
     // In a full implementation, this would contain WebRTC service, call manager, etc.
+    /// Calls by call ID
+    calls: Mutex<HashMap<String, FfiCall>>,
+    /// Events awaiting `saorsa_poll_event`
+    events: Mutex<EventQueue>,
+    /// Receives events instead of the queue while set
+    listener: Mutex<Option<EventListener>>,
+    /// Whether the platform audio session lets audio flow
+    audio_session_active: AtomicBool,
+    /// Whether silent audio frames are left unsent
+    dtx: AtomicBool,
+    /// Do-not-disturb toggle and quiet hours
+    dnd: DoNotDisturb,
 }
 
 impl SaorsaHandle {
     fn new(identity: String) -> Self {
-        Self { identity }
+        Self {
+            identity,
+            calls: Mutex::new(HashMap::new()),
+            events: Mutex::new(EventQueue::default()),
+            listener: Mutex::new(None),
+            audio_session_active: AtomicBool::new(true),
+            dtx: AtomicBool::new(false),
+            dnd: DoNotDisturb::default(),
+        }
+    }
+
+    fn push_event(&self, event: &FfiEvent) {
+        // Clone out of the lock so a listener may call back into the library
+        let listener = self.listener.lock().ok().and_then(|l| l.clone());
+        match listener {
+            Some(listener) => listener(event),
+            None => {
+                if let Ok(mut events) = self.events.lock() {
+                    events.push(event);
+                }
+            }
+        }
+    }
+
+    /// Deliver events to `listener` instead of the poll queue, or back to
+    /// the queue with `None`
+    fn set_listener(&self, listener: Option<EventListener>) -> SaorsaResult {
+        match self.listener.lock() {
+            Ok(mut current) => {
+                *current = listener;
+                SaorsaResult::Success
+            }
+            Err(_) => SaorsaResult::InternalError,
+        }
+    }
+
+    /// Full ID of the call `id` names, which may be the call's short code
+    ///
+    /// An ID naming no call, or a short code matching several calls, is
+    /// returned unchanged and so fails the lookup that follows.
+    fn resolve_call_id(&self, id: String) -> String {
+        let Ok(code) = id.parse::<ShortCode>() else {
+            return id;
+        };
+        let Ok(calls) = self.calls.lock() else {
+            return id;
+        };
+        if calls.contains_key(&id) {
+            return id;
+        }
+        let mut matches = calls.keys().filter(|key| short_code(key) == code);
+        match (matches.next(), matches.next()) {
+            (Some(key), None) => key.clone(),
+            _ => id,
+        }
+    }
+
+    /// Run `f` on a connected call, or fail with `InvalidParameter` if the
+    /// call is unknown, still ringing or ended
+    fn with_active_call<T>(
+        &self,
+        call_id: &str,
+        f: impl FnOnce(&mut FfiCall) -> T,
+    ) -> Result<T, SaorsaResult> {
+        let mut calls = self.calls.lock().map_err(|_| SaorsaResult::InternalError)?;
+        match calls.get_mut(call_id) {
+            Some(call) if call.ended.is_none() && !call.ringing => Ok(f(call)),
+            _ => Err(SaorsaResult::InvalidParameter),
+        }
+    }
+}
+
+/// Short code of a call ID, matching `CallId::short_code` for UUID call IDs
+fn short_code(call_id: &str) -> ShortCode {
+    match call_id.parse::<CallId>() {
+        Ok(call_id) => call_id.short_code(),
+        Err(_) => ShortCode::of(call_id.as_bytes()),
     }
 }
 
+/// Look up a live handle
+///
+/// Every entry point goes through here, so null, forged and freed handles
+/// are all rejected the same way.
+fn get_handle(handle: *mut std::ffi::c_void) -> Option<Arc<SaorsaHandle>> {
+    if handle.is_null() {
+        return None;
+    }
+    let handles = HANDLES.lock().ok()?;
+    handles.get(handle as usize).cloned()
+}
+
 /// Initialize the library with an identity
 ///
 /// # Safety
@@ -67,23 +181,14 @@ pub extern "C" fn saorsa_init(identity: *const c_char) -> *mut std::ffi::c_void
     // Create handle
     let handle = Arc::new(SaorsaHandle::new(identity_str));
 
-    // Get next handle ID
-    let handle_id = match HANDLE_COUNTER.lock() {
-        Ok(mut counter) => {
-            let id = *counter;
-            *counter = counter.wrapping_add(1);
-            id
-        }
-        Err(_) => return std::ptr::null_mut(),
-    };
-
-    // Store handle
-    match HANDLES.lock() {
-        Ok(mut handles) => {
-            handles.insert(handle_id, handle);
-            handle_id as *mut std::ffi::c_void
-        }
-        Err(_) => std::ptr::null_mut(),
+    // Store handle under a generation-tagged ID
+    match HANDLES
+        .lock()
+        .ok()
+        .and_then(|mut handles| handles.insert(handle))
+    {
+        Some(handle_id) => handle_id as *mut std::ffi::c_void,
+        None => std::ptr::null_mut(),
     }
 }
 
@@ -105,59 +210,708 @@ pub extern "C" fn saorsa_call(handle: *mut std::ffi::c_void, peer: *const c_char
         _ => return std::ptr::null_mut(),
     };
 
-    let handle_id = handle as usize;
-
-    // Get handle
-    let _handle = match HANDLES.lock() {
-        Ok(handles) => match handles.get(&handle_id) {
-            Some(h) => Arc::clone(h),
-            None => return std::ptr::null_mut(),
-        },
-        Err(_) => return std::ptr::null_mut(),
+    let Some(handle_ref) = get_handle(handle) else {
+        return std::ptr::null_mut();
     };
 
     // In a full implementation, would initiate actual call
     // For now, return a mock call ID
-    let call_id = format!("call-{}-{}", handle_id, peer_str);
+    let call_id = format!("call-{}-{}", handle as usize, peer_str);
+    match handle_ref.calls.lock() {
+        Ok(mut calls) => {
+            calls.insert(call_id.clone(), FfiCall::new(peer_str.clone()));
+        }
+        Err(_) => return std::ptr::null_mut(),
+    }
+    handle_ref.push_event(&FfiEvent::CallStarted {
+        call_id: call_id.clone(),
+        peer: peer_str,
+    });
+
     unsafe { string_to_c_char(call_id) }
 }
 
 /// Get the current state of a call
 ///
+/// Ringing incoming calls report `Connecting`.
+///
 /// # Safety
 /// `handle` must be a valid handle from `saorsa_init`
 /// `call_id` must be a valid null-terminated C string from `saorsa_call`
 #[no_mangle]
 pub extern "C" fn saorsa_call_state(
     handle: *mut std::ffi::c_void,
-    _call_id: *const c_char,
+    call_id: *const c_char,
 ) -> CallState {
-    if handle.is_null() {
+    let (Some(handle), Some(call_id)) = (get_handle(handle), unsafe { c_char_to_string(call_id) })
+    else {
         return CallState::Failed;
-    }
+    };
+    let call_id = handle.resolve_call_id(call_id);
 
-    // In a full implementation, would look up actual call state
-    CallState::Active
+    let state = match handle.calls.lock() {
+        Ok(calls) => match calls.get(&call_id) {
+            Some(call) if call.ended.is_some() => CallState::Ended,
+            Some(call) if call.ringing => CallState::Connecting,
+            Some(_) => CallState::Active,
+            None => CallState::Failed,
+        },
+        Err(_) => CallState::Failed,
+    };
+    state
+}
+
+/// Get the short code of a call, to show or read out in place of its ID
+///
+/// Every function taking a `call_id` also accepts the call's short code, as
+/// long as no other call of the handle has the same code.
+///
+/// # Safety
+/// `handle` must be a valid handle from `saorsa_init`
+/// `call_id` must be a valid null-terminated C string from `saorsa_call`
+/// Returns a C string owned by the caller (free with `saorsa_free_string`),
+/// or null if the handle or call is unknown
+#[no_mangle]
+pub extern "C" fn saorsa_call_short_code(
+    handle: *mut std::ffi::c_void,
+    call_id: *const c_char,
+) -> *mut c_char {
+    let (Some(handle), Some(call_id)) = (get_handle(handle), unsafe { c_char_to_string(call_id) })
+    else {
+        return std::ptr::null_mut();
+    };
+    let call_id = handle.resolve_call_id(call_id);
+
+    let known = handle
+        .calls
+        .lock()
+        .is_ok_and(|calls| calls.contains_key(&call_id));
+    if !known {
+        return std::ptr::null_mut();
+    }
+    unsafe { string_to_c_char(short_code(&call_id).to_string()) }
 }
 
 /// End a call
 ///
+/// Declines a ringing call. Ending an already ended call succeeds without
+/// effect.
+///
 /// # Safety
 /// `handle` must be a valid handle from `saorsa_init`
 /// `call_id` must be a valid null-terminated C string from `saorsa_call`
 #[no_mangle]
 pub extern "C" fn saorsa_end_call(
     handle: *mut std::ffi::c_void,
-    _call_id: *const c_char,
+    call_id: *const c_char,
 ) -> SaorsaResult {
-    if handle.is_null() {
+    let (Some(handle), Some(call_id)) = (get_handle(handle), unsafe { c_char_to_string(call_id) })
+    else {
+        return SaorsaResult::InvalidParameter;
+    };
+    let call_id = handle.resolve_call_id(call_id);
+
+    let newly_ended = match handle.calls.lock() {
+        Ok(mut calls) => match calls.get_mut(&call_id) {
+            Some(call) if call.ended.is_none() => {
+                call.ended = Some(std::time::Instant::now());
+                true
+            }
+            Some(_) => false,
+            None => return SaorsaResult::InvalidParameter,
+        },
+        Err(_) => return SaorsaResult::InternalError,
+    };
+    if newly_ended {
+        handle.push_event(&FfiEvent::CallEnded { call_id });
+    }
+
+    SaorsaResult::Success
+}
+
+/// Register an incoming call announced out of band
+///
+/// For VoIP push: the app reports the call to the system call UI (CallKit's
+/// `reportNewIncomingCall`) as soon as the push arrives, then hands the
+/// caller to the library. The call rings, reporting `Connecting`, until
+/// answered with `saorsa_answer_call` or declined with `saorsa_end_call`.
+///
+/// While do-not-disturb is on the call does not ring: a `missed_call`
+/// event is raised instead of `incoming_call` and the call is already
+/// `Ended`, so the app should end the call it reported to the system.
+///
+/// # Safety
+/// `handle` must be a valid handle from `saorsa_init`
+/// `peer` must be a valid null-terminated C string
+/// Returns a call ID as a C string (caller must free), or null on error
+#[no_mangle]
+pub extern "C" fn saorsa_receive_incoming_call(
+    handle: *mut std::ffi::c_void,
+    peer: *const c_char,
+) -> *mut c_char {
+    let (Some(handle_ref), Some(peer)) = (get_handle(handle), unsafe { c_char_to_string(peer) })
+    else {
+        return std::ptr::null_mut();
+    };
+    if peer.is_empty() {
+        return std::ptr::null_mut();
+    }
+
+    // A fresh ID for every call, so a second push from the same peer rings
+    // alongside the first rather than replacing it
+    let call_id = CallId::new().to_string();
+    let missed = handle_ref.dnd.active_now().is_some();
+    let mut call = FfiCall::incoming(peer.clone());
+    if missed {
+        call.ringing = false;
+        call.ended = Some(call.started);
+    }
+    match handle_ref.calls.lock() {
+        Ok(mut calls) => {
+            calls.insert(call_id.clone(), call);
+        }
+        Err(_) => return std::ptr::null_mut(),
+    }
+    let call_id_out = call_id.clone();
+    handle_ref.push_event(&if missed {
+        FfiEvent::MissedCall { call_id, peer }
+    } else {
+        FfiEvent::IncomingCall { call_id, peer }
+    });
+    let call_id = call_id_out;
+
+    unsafe { string_to_c_char(call_id) }
+}
+
+/// Answer a ringing incoming call
+///
+/// Called from the system call UI's answer action (CallKit's
+/// `CXAnswerCallAction`).
+///
+/// # Safety
+/// `handle` must be a valid handle from `saorsa_init`
+/// `call_id` must be a valid null-terminated C string from
+/// `saorsa_receive_incoming_call`
+#[no_mangle]
+pub extern "C" fn saorsa_answer_call(
+    handle: *mut std::ffi::c_void,
+    call_id: *const c_char,
+) -> SaorsaResult {
+    let (Some(handle), Some(call_id)) = (get_handle(handle), unsafe { c_char_to_string(call_id) })
+    else {
         return SaorsaResult::InvalidParameter;
+    };
+    let call_id = handle.resolve_call_id(call_id);
+
+    match handle.calls.lock() {
+        Ok(mut calls) => match calls.get_mut(&call_id) {
+            Some(call) if call.ended.is_none() && call.ringing => call.ringing = false,
+            _ => return SaorsaResult::InvalidParameter,
+        },
+        Err(_) => return SaorsaResult::InternalError,
     }
+    handle.push_event(&FfiEvent::CallAnswered { call_id });
 
-    // In a full implementation, would end the actual call
     SaorsaResult::Success
 }
 
+/// Mute or unmute a call's outgoing audio
+///
+/// Called from the system call UI's mute action (CallKit's
+/// `CXSetMutedCallAction`) as well as the app's own UI. Muted audio frames
+/// are accepted by `saorsa_push_audio_frame` and discarded.
+///
+/// # Safety
+/// `handle` must be a valid handle from `saorsa_init`
+/// `call_id` must be a valid null-terminated C string identifying a call
+#[no_mangle]
+pub extern "C" fn saorsa_set_muted(
+    handle: *mut std::ffi::c_void,
+    call_id: *const c_char,
+    muted: bool,
+) -> SaorsaResult {
+    let (Some(handle), Some(call_id)) = (get_handle(handle), unsafe { c_char_to_string(call_id) })
+    else {
+        return SaorsaResult::InvalidParameter;
+    };
+    let call_id = handle.resolve_call_id(call_id);
+
+    match handle.with_active_call(&call_id, |call| std::mem::replace(&mut call.muted, muted)) {
+        Ok(was_muted) => {
+            if was_muted != muted {
+                handle.push_event(&FfiEvent::MuteChanged { call_id, muted });
+            }
+            SaorsaResult::Success
+        }
+        Err(e) => e,
+    }
+}
+
+/// Turn do-not-disturb on or off
+///
+/// While on, calls passed to `saorsa_receive_incoming_call` do not ring.
+///
+/// # Safety
+/// `handle` must be a valid handle from `saorsa_init`
+#[no_mangle]
+pub extern "C" fn saorsa_set_do_not_disturb(
+    handle: *mut std::ffi::c_void,
+    enabled: bool,
+) -> SaorsaResult {
+    let Some(handle) = get_handle(handle) else {
+        return SaorsaResult::InvalidParameter;
+    };
+    handle.dnd.set_enabled(enabled);
+    SaorsaResult::Success
+}
+
+/// Set the daily quiet hours during which do-not-disturb is on
+///
+/// `hours` lists comma-separated `HH:MM-HH:MM` ranges in local time, which
+/// is `utc_offset_minutes` east of UTC; an empty string removes the quiet
+/// hours. A range ending before it starts runs past midnight.
+///
+/// # Safety
+/// `handle` must be a valid handle from `saorsa_init`
+/// `hours` must be a valid null-terminated C string
+#[no_mangle]
+pub extern "C" fn saorsa_set_quiet_hours(
+    handle: *mut std::ffi::c_void,
+    hours: *const c_char,
+    utc_offset_minutes: i32,
+) -> SaorsaResult {
+    let (Some(handle), Some(hours)) = (get_handle(handle), unsafe { c_char_to_string(hours) })
+    else {
+        return SaorsaResult::InvalidParameter;
+    };
+    let Ok(quiet_hours) = hours
+        .split(',')
+        .filter(|range| !range.trim().is_empty())
+        .map(str::parse::<QuietHours>)
+        .collect::<Result<Vec<_>, _>>()
+    else {
+        return SaorsaResult::InvalidParameter;
+    };
+
+    let mut config = handle.dnd.config();
+    config.quiet_hours = quiet_hours;
+    config.utc_offset_minutes = utc_offset_minutes;
+    handle.dnd.set_config(config);
+    SaorsaResult::Success
+}
+
+/// Tell the library whether the platform audio session is active
+///
+/// With CallKit the system activates the audio session itself; forward
+/// `provider(_:didActivate:)` and `provider(_:didDeactivate:)` here. While
+/// inactive, pushed audio is discarded and no remote audio is handed out.
+/// Sessions start active, so apps without CallKit never need this.
+///
+/// # Safety
+/// `handle` must be a valid handle from `saorsa_init`
+#[no_mangle]
+pub extern "C" fn saorsa_set_audio_session_active(
+    handle: *mut std::ffi::c_void,
+    active: bool,
+) -> SaorsaResult {
+    let Some(handle) = get_handle(handle) else {
+        return SaorsaResult::InvalidParameter;
+    };
+    if handle.audio_session_active.swap(active, Ordering::SeqCst) != active {
+        handle.push_event(&FfiEvent::AudioSessionChanged { active });
+    }
+    SaorsaResult::Success
+}
+
+/// Turn discontinuous transmission (DTX) on or off
+///
+/// With DTX on, pushed audio frames classified as silence are not sent and
+/// their bytes are reported as `dtx_bytes_saved` in the call statistics.
+/// DTX starts off.
+///
+/// # Safety
+/// `handle` must be a valid handle from `saorsa_init`
+#[no_mangle]
+pub extern "C" fn saorsa_set_dtx(handle: *mut std::ffi::c_void, enabled: bool) -> SaorsaResult {
+    let Some(handle) = get_handle(handle) else {
+        return SaorsaResult::InvalidParameter;
+    };
+    handle.dtx.store(enabled, Ordering::SeqCst);
+    SaorsaResult::Success
+}
+
+/// Take the next pending event as JSON
+///
+/// The event is copied into the caller-owned buffer `buf` of `len` bytes
+/// and NUL-terminated; the library never retains `buf`. Events are JSON
+/// objects with a `type` field (`call_started`, `call_ended`,
+/// `incoming_call`, `missed_call`, `call_answered`, `mute_changed`,
+/// `audio_session_changed`, `events_dropped`).
+///
+/// Returns:
+/// - `n > 0`: an event of `n` bytes (excluding the NUL) was written and
+///   removed from the queue
+/// - `0`: no event is pending
+/// - `-1`: invalid handle
+/// - `n < -1`: `buf` is null or too small; `-n` bytes (including the NUL)
+///   are needed and the event stays queued
+///
+/// # Safety
+/// `handle` must be a valid handle from `saorsa_init`
+/// `buf` must be null or point to at least `len` writable bytes
+#[no_mangle]
+pub extern "C" fn saorsa_poll_event(
+    handle: *mut std::ffi::c_void,
+    buf: *mut c_char,
+    len: usize,
+) -> isize {
+    let Some(handle) = get_handle(handle) else {
+        return -1;
+    };
+    let Ok(mut events) = handle.events.lock() else {
+        return -1;
+    };
+    let Some(event) = events.front() else {
+        return 0;
+    };
+
+    let needed = event.len() + 1;
+    if buf.is_null() || len < needed {
+        return -(needed as isize);
+    }
+    // SAFETY: the caller guarantees `buf` holds `len >= needed` bytes
+    unsafe {
+        std::ptr::copy_nonoverlapping(event.as_ptr(), buf.cast::<u8>(), event.len());
+        *buf.add(event.len()) = 0;
+    }
+    let written = event.len() as isize;
+    events.pop();
+    written
+}
+
+/// Deliver events to a callback instead of the poll queue
+///
+/// The callback receives each event as NUL-terminated JSON, valid only for
+/// the duration of the call, together with `user_data`. It runs
+/// synchronously on the thread of the library call that raised the event,
+/// and may call back into the library. Passing a null callback returns to
+/// queueing for `saorsa_poll_event`.
+///
+/// # Safety
+/// `handle` must be a valid handle from `saorsa_init`
+/// `user_data` is passed through untouched and must stay valid, and safe to
+/// use from any thread, until the callback is replaced or the handle freed
+#[no_mangle]
+pub extern "C" fn saorsa_set_event_callback(
+    handle: *mut std::ffi::c_void,
+    callback: Option<extern "C" fn(event: *const c_char, user_data: *mut std::ffi::c_void)>,
+    user_data: *mut std::ffi::c_void,
+) -> SaorsaResult {
+    let Some(handle) = get_handle(handle) else {
+        return SaorsaResult::InvalidParameter;
+    };
+    let listener = callback.map(|callback| {
+        // Carried as an address so the listener is Send + Sync
+        let user_data = user_data as usize;
+        Arc::new(move |event: &FfiEvent| {
+            let Ok(json) = serde_json::to_string(event) else {
+                return;
+            };
+            if let Ok(json) = std::ffi::CString::new(json) {
+                callback(json.as_ptr(), user_data as *mut std::ffi::c_void);
+            }
+        }) as EventListener
+    });
+    handle.set_listener(listener)
+}
+
+/// Get a JSON statistics snapshot of a call
+///
+/// The snapshot has `call_id`, `peer`, `active`, `duration_ms`, packet
+/// and byte counters, and talk statistics of the outgoing audio:
+/// `talk_ms`, `silence_ms`, `silence_ratio`, `talk_bursts` and
+/// `dtx_bytes_saved`. Ended calls report their final statistics.
+///
+/// # Safety
+/// `handle` must be a valid handle from `saorsa_init`
+/// `call_id` must be a valid null-terminated C string from `saorsa_call`
+/// Returns a C string owned by the caller (free with `saorsa_free_string`),
+/// or null if the handle or call is unknown
+#[no_mangle]
+pub extern "C" fn saorsa_get_call_stats(
+    handle: *mut std::ffi::c_void,
+    call_id: *const c_char,
+) -> *mut c_char {
+    let (Some(handle), Some(call_id)) = (get_handle(handle), unsafe { c_char_to_string(call_id) })
+    else {
+        return std::ptr::null_mut();
+    };
+    let call_id = handle.resolve_call_id(call_id);
+
+    let stats = match handle.calls.lock() {
+        Ok(calls) => match calls.get(&call_id) {
+            Some(call) => call.stats(&call_id),
+            None => return std::ptr::null_mut(),
+        },
+        Err(_) => return std::ptr::null_mut(),
+    };
+    match serde_json::to_string(&stats) {
+        Ok(json) => unsafe { string_to_c_char(json) },
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Push a locally captured I420 video frame into a call
+///
+/// For apps that capture through platform APIs. `buf` holds the Y, U and V
+/// planes back to back, `i420_size(width, height)` bytes in total, and is
+/// copied before returning. `timestamp_us` is the capture time in
+/// microseconds.
+///
+/// Until the FFI is wired to a media transport, pushed frames are looped
+/// back as remote media so rendering paths can be exercised end to end.
+///
+/// # Safety
+/// `handle` must be a valid handle from `saorsa_init`
+/// `call_id` must be a valid null-terminated C string from `saorsa_call`
+/// `buf` must point to `i420_size(width, height)` readable bytes
+#[no_mangle]
+pub extern "C" fn saorsa_push_video_frame(
+    handle: *mut std::ffi::c_void,
+    call_id: *const c_char,
+    buf: *const u8,
+    width: u32,
+    height: u32,
+    timestamp_us: u64,
+) -> SaorsaResult {
+    let (Some(handle), Some(call_id)) = (get_handle(handle), unsafe { c_char_to_string(call_id) })
+    else {
+        return SaorsaResult::InvalidParameter;
+    };
+    let call_id = handle.resolve_call_id(call_id);
+    let Some(len) = i420_size(width, height) else {
+        return SaorsaResult::InvalidParameter;
+    };
+    if buf.is_null() {
+        return SaorsaResult::InvalidParameter;
+    }
+    // SAFETY: the caller guarantees `buf` holds a full frame
+    let data = unsafe { std::slice::from_raw_parts(buf, len) }.to_vec();
+
+    let frame = VideoFrame {
+        data,
+        width,
+        height,
+        timestamp_us,
+    };
+    match handle.with_active_call(&call_id, |call| {
+        call.packets_sent += 1;
+        call.bytes_sent += len as u64;
+        call.packets_received += 1;
+        call.bytes_received += len as u64;
+        call.remote.push_video(frame);
+    }) {
+        Ok(()) => SaorsaResult::Success,
+        Err(e) => e,
+    }
+}
+
+/// Push a locally captured audio frame into a call
+///
+/// `samples` holds `sample_count` interleaved 16-bit PCM samples across
+/// `channels` channels (at most `MAX_AUDIO_CHANNELS`) and is copied before
+/// returning; `sample_count` must be a multiple of `channels`.
+///
+/// Like video, pushed audio is looped back as remote media until the FFI is
+/// wired to a media transport. Audio pushed while the call is muted or the
+/// audio session is inactive is discarded. Every other frame counts toward
+/// the call's talk statistics, and with DTX on silent frames are not sent.
+///
+/// # Safety
+/// `handle` must be a valid handle from `saorsa_init`
+/// `call_id` must be a valid null-terminated C string from `saorsa_call`
+/// `samples` must point to `sample_count` readable samples
+#[no_mangle]
+pub extern "C" fn saorsa_push_audio_frame(
+    handle: *mut std::ffi::c_void,
+    call_id: *const c_char,
+    samples: *const i16,
+    sample_count: usize,
+    sample_rate: u32,
+    channels: u32,
+    timestamp_us: u64,
+) -> SaorsaResult {
+    let (Some(handle), Some(call_id)) = (get_handle(handle), unsafe { c_char_to_string(call_id) })
+    else {
+        return SaorsaResult::InvalidParameter;
+    };
+    let call_id = handle.resolve_call_id(call_id);
+    if samples.is_null() {
+        return SaorsaResult::InvalidParameter;
+    }
+    // SAFETY: the caller guarantees `samples` holds `sample_count` samples
+    let samples = unsafe { std::slice::from_raw_parts(samples, sample_count) }.to_vec();
+    let frame = AudioFrame {
+        samples,
+        sample_rate,
+        channels,
+        timestamp_us,
+    };
+    if !frame.is_valid() {
+        return SaorsaResult::InvalidParameter;
+    }
+
+    let bytes = (sample_count * std::mem::size_of::<i16>()) as u64;
+    let session_active = handle.audio_session_active.load(Ordering::SeqCst);
+    let dtx = handle.dtx.load(Ordering::SeqCst);
+    match handle.with_active_call(&call_id, |call| {
+        if call.muted || !session_active {
+            return;
+        }
+        if !call
+            .talk
+            .record_pcm(&frame.samples, frame.sample_rate, frame.channels, dtx)
+        {
+            return;
+        }
+        call.packets_sent += 1;
+        call.bytes_sent += bytes;
+        call.packets_received += 1;
+        call.bytes_received += bytes;
+        call.remote.push_audio(frame);
+    }) {
+        Ok(()) => SaorsaResult::Success,
+        Err(e) => e,
+    }
+}
+
+/// Take the next remote video frame of a call
+///
+/// The I420 frame is copied into the caller-owned buffer `buf` of `len`
+/// bytes; `width`, `height` and `timestamp_us` receive its format when not
+/// null. When rendering falls behind, only the newest
+/// `MAX_QUEUED_VIDEO_FRAMES` frames are kept.
+///
+/// Returns:
+/// - `n > 0`: a frame of `n` bytes was written and removed from the queue
+/// - `0`: no frame is pending
+/// - `-1`: invalid handle or unknown/ended call
+/// - `n < -1`: `buf` is null or too small; `-n` bytes are needed and the
+///   frame stays queued
+///
+/// # Safety
+/// `handle` must be a valid handle from `saorsa_init`
+/// `call_id` must be a valid null-terminated C string from `saorsa_call`
+/// `buf` must be null or point to at least `len` writable bytes; the out
+/// pointers must be null or valid for writes
+#[no_mangle]
+pub extern "C" fn saorsa_pull_video_frame(
+    handle: *mut std::ffi::c_void,
+    call_id: *const c_char,
+    buf: *mut u8,
+    len: usize,
+    width: *mut u32,
+    height: *mut u32,
+    timestamp_us: *mut u64,
+) -> isize {
+    let (Some(handle), Some(call_id)) = (get_handle(handle), unsafe { c_char_to_string(call_id) })
+    else {
+        return -1;
+    };
+    let call_id = handle.resolve_call_id(call_id);
+
+    handle
+        .with_active_call(&call_id, |call| {
+            let Some(frame) = call.remote.front_video() else {
+                return 0;
+            };
+            let needed = frame.data.len();
+            if buf.is_null() || len < needed {
+                return -(needed as isize);
+            }
+            // SAFETY: the caller guarantees `buf` holds `len >= needed` bytes
+            // and that non-null out pointers are writable
+            unsafe {
+                std::ptr::copy_nonoverlapping(frame.data.as_ptr(), buf, needed);
+                write_out(width, frame.width);
+                write_out(height, frame.height);
+                write_out(timestamp_us, frame.timestamp_us);
+            }
+            call.remote.pop_video();
+            needed as isize
+        })
+        .unwrap_or(-1)
+}
+
+/// Take the next remote audio frame of a call
+///
+/// Interleaved 16-bit PCM is copied into the caller-owned buffer `buf` of
+/// `len` samples; `sample_rate`, `channels` and `timestamp_us` receive its
+/// format when not null. Only the newest `MAX_QUEUED_AUDIO_FRAMES` frames
+/// are kept, and none are handed out while the audio session is inactive.
+///
+/// Returns:
+/// - `n > 0`: a frame of `n` samples was written and removed from the queue
+/// - `0`: no frame is pending
+/// - `-1`: invalid handle or unknown/ended call
+/// - `n < -1`: `buf` is null or too small; `-n` samples are needed and the
+///   frame stays queued
+///
+/// # Safety
+/// `handle` must be a valid handle from `saorsa_init`
+/// `call_id` must be a valid null-terminated C string from `saorsa_call`
+/// `buf` must be null or point to at least `len` writable samples; the out
+/// pointers must be null or valid for writes
+#[no_mangle]
+pub extern "C" fn saorsa_pull_audio_frame(
+    handle: *mut std::ffi::c_void,
+    call_id: *const c_char,
+    buf: *mut i16,
+    len: usize,
+    sample_rate: *mut u32,
+    channels: *mut u32,
+    timestamp_us: *mut u64,
+) -> isize {
+    let (Some(handle), Some(call_id)) = (get_handle(handle), unsafe { c_char_to_string(call_id) })
+    else {
+        return -1;
+    };
+    let call_id = handle.resolve_call_id(call_id);
+
+    let session_active = handle.audio_session_active.load(Ordering::SeqCst);
+    handle
+        .with_active_call(&call_id, |call| {
+            let Some(frame) = call.remote.front_audio().filter(|_| session_active) else {
+                return 0;
+            };
+            let needed = frame.samples.len();
+            if buf.is_null() || len < needed {
+                return -(needed as isize);
+            }
+            // SAFETY: the caller guarantees `buf` holds `len >= needed`
+            // samples and that non-null out pointers are writable
+            unsafe {
+                std::ptr::copy_nonoverlapping(frame.samples.as_ptr(), buf, needed);
+                write_out(sample_rate, frame.sample_rate);
+                write_out(channels, frame.channels);
+                write_out(timestamp_us, frame.timestamp_us);
+            }
+            call.remote.pop_audio();
+            needed as isize
+        })
+        .unwrap_or(-1)
+}
+
+/// Write an optional out parameter
+///
+/// # Safety
+/// `ptr` must be null or valid for writes
+unsafe fn write_out<T>(ptr: *mut T, value: T) {
+    if !ptr.is_null() {
+        *ptr = value;
+    }
+}
+
 /// Free a string returned by the library
 ///
 /// # Safety
@@ -183,11 +937,9 @@ pub extern "C" fn saorsa_free(handle: *mut std::ffi::c_void) {
         return;
     }
 
-    let handle_id = handle as usize;
-
-    // Remove handle
+    // Remove handle; stale and forged handles are ignored
     if let Ok(mut handles) = HANDLES.lock() {
-        handles.remove(&handle_id);
+        handles.remove(handle as usize);
     }
 }
 
@@ -306,6 +1058,539 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_short_code_names_call() {
+        let identity = std::ffi::CString::new("alice").ok().map(|s| s.into_raw());
+        let peer = std::ffi::CString::new("bob").ok().map(|s| s.into_raw());
+        if let (Some(id_ptr), Some(peer_ptr)) = (identity, peer) {
+            let handle = saorsa_init(id_ptr);
+            let call_id = saorsa_call(handle, peer_ptr);
+
+            let code = saorsa_call_short_code(handle, call_id);
+            assert!(!code.is_null());
+            assert_eq!(saorsa_call_state(handle, code), CallState::Active);
+            assert_eq!(saorsa_end_call(handle, code), SaorsaResult::Success);
+            assert_eq!(saorsa_call_state(handle, call_id), CallState::Ended);
+
+            // The event names the call by its full ID
+            let mut buf = vec![0 as c_char; 512];
+            let mut event = String::new();
+            while saorsa_poll_event(handle, buf.as_mut_ptr(), buf.len()) > 0 {
+                event = unsafe { c_char_to_string(buf.as_ptr()) }.unwrap_or_default();
+            }
+            let full_id = unsafe { c_char_to_string(call_id) }.unwrap_or_default();
+            assert!(event.contains("call_ended") && event.contains(&full_id));
+
+            let unknown = std::ffi::CString::new("ZZZZZZ").ok();
+            if let Some(unknown) = unknown {
+                assert!(saorsa_call_short_code(handle, unknown.as_ptr()).is_null());
+            }
+
+            saorsa_free_string(code);
+            saorsa_free_string(call_id);
+            saorsa_free(handle);
+            unsafe {
+                let _ = std::ffi::CString::from_raw(peer_ptr);
+                let _ = std::ffi::CString::from_raw(id_ptr);
+            }
+        }
+    }
+
+    #[test]
+    fn test_poll_event_and_stats() {
+        let identity = std::ffi::CString::new("alice").ok().map(|s| s.into_raw());
+        let peer = std::ffi::CString::new("bob").ok().map(|s| s.into_raw());
+        if let (Some(id_ptr), Some(peer_ptr)) = (identity, peer) {
+            let handle = saorsa_init(id_ptr);
+            let mut buf = [0 as c_char; 256];
+            assert_eq!(saorsa_poll_event(handle, buf.as_mut_ptr(), buf.len()), 0);
+
+            let call_id = saorsa_call(handle, peer_ptr);
+
+            // Too small: size is reported and the event stays queued
+            let needed = -saorsa_poll_event(handle, buf.as_mut_ptr(), 4);
+            assert!(needed > 4);
+            let written = saorsa_poll_event(handle, buf.as_mut_ptr(), buf.len());
+            assert_eq!(written + 1, needed);
+            let event = unsafe { c_char_to_string(buf.as_ptr()) }.unwrap_or_default();
+            assert!(event.starts_with(r#"{"type":"call_started""#));
+
+            assert_eq!(saorsa_end_call(handle, call_id), SaorsaResult::Success);
+            assert_eq!(saorsa_call_state(handle, call_id), CallState::Ended);
+            assert!(saorsa_poll_event(handle, buf.as_mut_ptr(), buf.len()) > 0);
+            assert_eq!(saorsa_poll_event(handle, buf.as_mut_ptr(), buf.len()), 0);
+
+            let stats = saorsa_get_call_stats(handle, call_id);
+            let json = unsafe { c_char_to_string(stats) }.unwrap_or_default();
+            assert!(json.contains(r#""peer":"bob""#));
+            assert!(json.contains(r#""active":false"#));
+            saorsa_free_string(stats);
+
+            saorsa_free_string(call_id);
+            saorsa_free(handle);
+            assert_eq!(saorsa_poll_event(handle, buf.as_mut_ptr(), buf.len()), -1);
+            unsafe {
+                let _ = std::ffi::CString::from_raw(peer_ptr);
+                let _ = std::ffi::CString::from_raw(id_ptr);
+            }
+        }
+    }
+
+    extern "C" fn count_events(event: *const c_char, user_data: *mut std::ffi::c_void) {
+        let json = unsafe { c_char_to_string(event) }.unwrap_or_default();
+        if json.contains(r#""type":"call_"#) {
+            // SAFETY: the test passes a pointer to a live AtomicUsize
+            let counter = unsafe { &*(user_data as *const std::sync::atomic::AtomicUsize) };
+            counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_event_callback_replaces_queue() {
+        let identity = std::ffi::CString::new("alice").ok().map(|s| s.into_raw());
+        let peer = std::ffi::CString::new("bob").ok().map(|s| s.into_raw());
+        if let (Some(id_ptr), Some(peer_ptr)) = (identity, peer) {
+            let handle = saorsa_init(id_ptr);
+            let counter = std::sync::atomic::AtomicUsize::new(0);
+            let user_data = &counter as *const _ as *mut std::ffi::c_void;
+            assert_eq!(
+                saorsa_set_event_callback(handle, Some(count_events), user_data),
+                SaorsaResult::Success
+            );
+
+            let call_id = saorsa_call(handle, peer_ptr);
+            saorsa_end_call(handle, call_id);
+            assert_eq!(counter.load(std::sync::atomic::Ordering::SeqCst), 2);
+            let mut buf = [0 as c_char; 256];
+            assert_eq!(saorsa_poll_event(handle, buf.as_mut_ptr(), buf.len()), 0);
+
+            // Clearing the callback goes back to queueing
+            saorsa_set_event_callback(handle, None, std::ptr::null_mut());
+            let second = saorsa_call(handle, peer_ptr);
+            assert!(saorsa_poll_event(handle, buf.as_mut_ptr(), buf.len()) > 0);
+            assert_eq!(counter.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+            saorsa_free_string(second);
+            saorsa_free_string(call_id);
+            saorsa_free(handle);
+            assert_eq!(
+                saorsa_set_event_callback(handle, Some(count_events), user_data),
+                SaorsaResult::InvalidParameter
+            );
+            unsafe {
+                let _ = std::ffi::CString::from_raw(peer_ptr);
+                let _ = std::ffi::CString::from_raw(id_ptr);
+            }
+        }
+    }
+
+    #[test]
+    fn test_incoming_call_answer_and_mute() {
+        let identity = std::ffi::CString::new("alice").ok().map(|s| s.into_raw());
+        let peer = std::ffi::CString::new("carol").ok().map(|s| s.into_raw());
+        if let (Some(id_ptr), Some(peer_ptr)) = (identity, peer) {
+            let handle = saorsa_init(id_ptr);
+            let mut buf = [0 as c_char; 256];
+            let mut next_event = || {
+                let written = saorsa_poll_event(handle, buf.as_mut_ptr(), buf.len());
+                if written > 0 {
+                    unsafe { c_char_to_string(buf.as_ptr()) }.unwrap_or_default()
+                } else {
+                    String::new()
+                }
+            };
+
+            let call_id = saorsa_receive_incoming_call(handle, peer_ptr);
+            assert!(!call_id.is_null());
+            assert!(next_event().starts_with(r#"{"type":"incoming_call""#));
+            assert_eq!(saorsa_call_state(handle, call_id), CallState::Connecting);
+
+            // No media or mute until answered
+            let samples = [1i16, 2];
+            assert_eq!(
+                saorsa_push_audio_frame(handle, call_id, samples.as_ptr(), 2, 48_000, 1, 0),
+                SaorsaResult::InvalidParameter
+            );
+            assert_eq!(
+                saorsa_set_muted(handle, call_id, true),
+                SaorsaResult::InvalidParameter
+            );
+
+            assert_eq!(saorsa_answer_call(handle, call_id), SaorsaResult::Success);
+            assert_eq!(
+                saorsa_answer_call(handle, call_id),
+                SaorsaResult::InvalidParameter
+            );
+            assert!(next_event().starts_with(r#"{"type":"call_answered""#));
+            assert_eq!(saorsa_call_state(handle, call_id), CallState::Active);
+
+            assert_eq!(
+                saorsa_set_muted(handle, call_id, true),
+                SaorsaResult::Success
+            );
+            assert!(next_event().contains(r#""muted":true"#));
+            // Repeating the same state raises no event
+            saorsa_set_muted(handle, call_id, true);
+            assert_eq!(next_event(), "");
+
+            // Muted and inactive-session audio is accepted but dropped
+            assert_eq!(
+                saorsa_push_audio_frame(handle, call_id, samples.as_ptr(), 2, 48_000, 1, 0),
+                SaorsaResult::Success
+            );
+            saorsa_set_muted(handle, call_id, false);
+            saorsa_set_audio_session_active(handle, false);
+            assert!(next_event().contains(r#""muted":false"#));
+            assert_eq!(
+                next_event(),
+                r#"{"type":"audio_session_changed","active":false}"#
+            );
+            saorsa_push_audio_frame(handle, call_id, samples.as_ptr(), 2, 48_000, 1, 0);
+
+            let stats = saorsa_get_call_stats(handle, call_id);
+            let json = unsafe { c_char_to_string(stats) }.unwrap_or_default();
+            assert!(json.contains(r#""packets_sent":0"#));
+            saorsa_free_string(stats);
+
+            saorsa_set_audio_session_active(handle, true);
+            saorsa_push_audio_frame(handle, call_id, samples.as_ptr(), 2, 48_000, 1, 0);
+            let mut pcm = [0i16; 4];
+            let null = std::ptr::null_mut();
+            assert_eq!(
+                saorsa_pull_audio_frame(handle, call_id, pcm.as_mut_ptr(), 4, null, null, &mut 0),
+                2
+            );
+
+            saorsa_free_string(call_id);
+            saorsa_free(handle);
+            unsafe {
+                let _ = std::ffi::CString::from_raw(peer_ptr);
+                let _ = std::ffi::CString::from_raw(id_ptr);
+            }
+        }
+    }
+
+    #[test]
+    fn test_incoming_calls_from_same_peer_are_separate() {
+        let identity = std::ffi::CString::new("alice").ok().map(|s| s.into_raw());
+        let peer = std::ffi::CString::new("carol").ok().map(|s| s.into_raw());
+        if let (Some(id_ptr), Some(peer_ptr)) = (identity, peer) {
+            let handle = saorsa_init(id_ptr);
+
+            let first = saorsa_receive_incoming_call(handle, peer_ptr);
+            assert_eq!(saorsa_answer_call(handle, first), SaorsaResult::Success);
+            let second = saorsa_receive_incoming_call(handle, peer_ptr);
+            assert!(!first.is_null() && !second.is_null());
+            assert_ne!(unsafe { c_char_to_string(first) }, unsafe {
+                c_char_to_string(second)
+            });
+
+            // The second call rings without touching the answered one
+            assert_eq!(saorsa_call_state(handle, first), CallState::Active);
+            assert_eq!(saorsa_call_state(handle, second), CallState::Connecting);
+            assert_eq!(saorsa_end_call(handle, second), SaorsaResult::Success);
+            assert_eq!(saorsa_call_state(handle, first), CallState::Active);
+
+            saorsa_free_string(first);
+            saorsa_free_string(second);
+            saorsa_free(handle);
+            unsafe {
+                let _ = std::ffi::CString::from_raw(peer_ptr);
+                let _ = std::ffi::CString::from_raw(id_ptr);
+            }
+        }
+    }
+
+    #[test]
+    fn test_do_not_disturb_misses_incoming_calls() {
+        let identity = std::ffi::CString::new("alice").ok().map(|s| s.into_raw());
+        let peer = std::ffi::CString::new("carol").ok().map(|s| s.into_raw());
+        let hours = std::ffi::CString::new("00:00-00:00")
+            .ok()
+            .map(|s| s.into_raw());
+        let bad = std::ffi::CString::new("22:00").ok().map(|s| s.into_raw());
+        let empty = std::ffi::CString::new("").ok().map(|s| s.into_raw());
+        if let (Some(id_ptr), Some(peer_ptr), Some(hours_ptr), Some(bad_ptr), Some(empty_ptr)) =
+            (identity, peer, hours, bad, empty)
+        {
+            let handle = saorsa_init(id_ptr);
+            let mut buf = [0 as c_char; 256];
+            let mut next_event = || {
+                let written = saorsa_poll_event(handle, buf.as_mut_ptr(), buf.len());
+                if written > 0 {
+                    unsafe { c_char_to_string(buf.as_ptr()) }.unwrap_or_default()
+                } else {
+                    String::new()
+                }
+            };
+
+            assert_eq!(
+                saorsa_set_do_not_disturb(handle, true),
+                SaorsaResult::Success
+            );
+            let call_id = saorsa_receive_incoming_call(handle, peer_ptr);
+            assert!(!call_id.is_null());
+            assert!(next_event().starts_with(r#"{"type":"missed_call""#));
+            assert_eq!(saorsa_call_state(handle, call_id), CallState::Ended);
+            assert_eq!(
+                saorsa_answer_call(handle, call_id),
+                SaorsaResult::InvalidParameter
+            );
+            saorsa_free_string(call_id);
+
+            // Quiet hours covering the whole day keep it on
+            saorsa_set_do_not_disturb(handle, false);
+            assert_eq!(
+                saorsa_set_quiet_hours(handle, bad_ptr, 0),
+                SaorsaResult::InvalidParameter
+            );
+            assert_eq!(
+                saorsa_set_quiet_hours(handle, hours_ptr, 60),
+                SaorsaResult::Success
+            );
+            let call_id = saorsa_receive_incoming_call(handle, peer_ptr);
+            assert!(next_event().starts_with(r#"{"type":"missed_call""#));
+            saorsa_free_string(call_id);
+
+            assert_eq!(
+                saorsa_set_quiet_hours(handle, empty_ptr, 0),
+                SaorsaResult::Success
+            );
+            let call_id = saorsa_receive_incoming_call(handle, peer_ptr);
+            assert!(next_event().starts_with(r#"{"type":"incoming_call""#));
+            assert_eq!(saorsa_call_state(handle, call_id), CallState::Connecting);
+
+            saorsa_free_string(call_id);
+            saorsa_free(handle);
+            unsafe {
+                for ptr in [id_ptr, peer_ptr, hours_ptr, bad_ptr, empty_ptr] {
+                    let _ = std::ffi::CString::from_raw(ptr);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_push_and_pull_media() {
+        let identity = std::ffi::CString::new("alice").ok().map(|s| s.into_raw());
+        let peer = std::ffi::CString::new("bob").ok().map(|s| s.into_raw());
+        if let (Some(id_ptr), Some(peer_ptr)) = (identity, peer) {
+            let handle = saorsa_init(id_ptr);
+            let call_id = saorsa_call(handle, peer_ptr);
+
+            // A 4x2 I420 frame is 8 luma + 2x2 chroma bytes
+            let frame: Vec<u8> = (0..12).collect();
+            assert_eq!(
+                saorsa_push_video_frame(handle, call_id, frame.as_ptr(), 0, 2, 1),
+                SaorsaResult::InvalidParameter
+            );
+            assert_eq!(
+                saorsa_push_video_frame(handle, call_id, frame.as_ptr(), 4, 2, 1_000),
+                SaorsaResult::Success
+            );
+
+            let mut pulled = [0u8; 16];
+            let (mut width, mut height, mut ts) = (0u32, 0u32, 0u64);
+            assert_eq!(
+                saorsa_pull_video_frame(
+                    handle,
+                    call_id,
+                    pulled.as_mut_ptr(),
+                    4,
+                    &mut width,
+                    &mut height,
+                    &mut ts
+                ),
+                -12
+            );
+            assert_eq!(
+                saorsa_pull_video_frame(
+                    handle,
+                    call_id,
+                    pulled.as_mut_ptr(),
+                    pulled.len(),
+                    &mut width,
+                    &mut height,
+                    &mut ts
+                ),
+                12
+            );
+            assert_eq!(&pulled[..12], frame.as_slice());
+            assert_eq!((width, height, ts), (4, 2, 1_000));
+
+            let samples = [1i16, -1, 2, -2];
+            assert_eq!(
+                saorsa_push_audio_frame(handle, call_id, samples.as_ptr(), 3, 48_000, 2, 0),
+                SaorsaResult::InvalidParameter
+            );
+            assert_eq!(
+                saorsa_push_audio_frame(handle, call_id, samples.as_ptr(), 4, 48_000, 2, 20),
+                SaorsaResult::Success
+            );
+            let mut pcm = [0i16; 8];
+            let (mut rate, mut channels) = (0u32, 0u32);
+            assert_eq!(
+                saorsa_pull_audio_frame(
+                    handle,
+                    call_id,
+                    pcm.as_mut_ptr(),
+                    pcm.len(),
+                    &mut rate,
+                    &mut channels,
+                    std::ptr::null_mut()
+                ),
+                4
+            );
+            assert_eq!(&pcm[..4], &samples);
+            assert_eq!((rate, channels), (48_000, 2));
+            assert_eq!(
+                saorsa_pull_audio_frame(
+                    handle,
+                    call_id,
+                    pcm.as_mut_ptr(),
+                    pcm.len(),
+                    std::ptr::null_mut(),
+                    std::ptr::null_mut(),
+                    std::ptr::null_mut()
+                ),
+                0
+            );
+
+            let stats = saorsa_get_call_stats(handle, call_id);
+            let json = unsafe { c_char_to_string(stats) }.unwrap_or_default();
+            assert!(json.contains(r#""packets_sent":2"#));
+            assert!(json.contains(r#""bytes_sent":20"#));
+            saorsa_free_string(stats);
+
+            // Ended calls accept no more media
+            assert_eq!(saorsa_end_call(handle, call_id), SaorsaResult::Success);
+            assert_eq!(
+                saorsa_push_video_frame(handle, call_id, frame.as_ptr(), 4, 2, 2_000),
+                SaorsaResult::InvalidParameter
+            );
+
+            saorsa_free_string(call_id);
+            saorsa_free(handle);
+            unsafe {
+                let _ = std::ffi::CString::from_raw(peer_ptr);
+                let _ = std::ffi::CString::from_raw(id_ptr);
+            }
+        }
+    }
+
+    #[test]
+    fn test_dtx_skips_silent_audio() {
+        let identity = std::ffi::CString::new("alice").ok().map(|s| s.into_raw());
+        let peer = std::ffi::CString::new("bob").ok().map(|s| s.into_raw());
+        if let (Some(id_ptr), Some(peer_ptr)) = (identity, peer) {
+            let handle = saorsa_init(id_ptr);
+            let call_id = saorsa_call(handle, peer_ptr);
+            assert_eq!(saorsa_set_dtx(handle, true), SaorsaResult::Success);
+
+            // 20 ms mono frames at 48 kHz
+            let speech = [8_000i16; 960];
+            let silence = [0i16; 960];
+            for samples in [&speech, &silence, &silence] {
+                assert_eq!(
+                    saorsa_push_audio_frame(handle, call_id, samples.as_ptr(), 960, 48_000, 1, 0),
+                    SaorsaResult::Success
+                );
+            }
+            // Past the hangover, silence is suppressed
+            for _ in 0..20 {
+                saorsa_push_audio_frame(handle, call_id, silence.as_ptr(), 960, 48_000, 1, 0);
+            }
+
+            let stats = saorsa_get_call_stats(handle, call_id);
+            let json = unsafe { c_char_to_string(stats) }.unwrap_or_default();
+            assert!(json.contains(r#""talk_ms":320"#), "{json}");
+            assert!(json.contains(r#""silence_ms":140"#), "{json}");
+            assert!(json.contains(r#""talk_bursts":1"#), "{json}");
+            assert!(json.contains(r#""packets_sent":16"#), "{json}");
+            assert!(json.contains(r#""dtx_bytes_saved":13440"#), "{json}");
+            saorsa_free_string(stats);
+
+            saorsa_free_string(call_id);
+            saorsa_free(handle);
+            unsafe {
+                let _ = std::ffi::CString::from_raw(peer_ptr);
+                let _ = std::ffi::CString::from_raw(id_ptr);
+            }
+        }
+    }
+
+    #[test]
+    fn test_stale_handle_rejected_after_reuse() {
+        let identity = std::ffi::CString::new("alice").ok().map(|s| s.into_raw());
+        let peer = std::ffi::CString::new("bob").ok().map(|s| s.into_raw());
+        if let (Some(id_ptr), Some(peer_ptr)) = (identity, peer) {
+            let stale = saorsa_init(id_ptr);
+            let call_id = saorsa_call(stale, peer_ptr);
+            saorsa_free(stale);
+
+            // Other tests may grab the freed slot; keep allocating until the
+            // slot comes back under a new generation or we give up
+            let mut fresh = Vec::new();
+            for _ in 0..64 {
+                let handle = saorsa_init(id_ptr);
+                fresh.push(handle);
+                if handle as usize >> (usize::BITS / 2) == stale as usize >> (usize::BITS / 2) {
+                    break;
+                }
+            }
+            assert!(!fresh.contains(&stale));
+
+            assert!(saorsa_call(stale, peer_ptr).is_null());
+            assert_eq!(saorsa_call_state(stale, call_id), CallState::Failed);
+            assert_eq!(
+                saorsa_end_call(stale, call_id),
+                SaorsaResult::InvalidParameter
+            );
+            let frame = [0u8; 6];
+            assert_eq!(
+                saorsa_push_video_frame(stale, call_id, frame.as_ptr(), 2, 2, 0),
+                SaorsaResult::InvalidParameter
+            );
+            assert!(saorsa_get_call_stats(stale, call_id).is_null());
+            let mut buf = [0 as c_char; 64];
+            assert_eq!(saorsa_poll_event(stale, buf.as_mut_ptr(), buf.len()), -1);
+
+            // Freeing the stale handle must not free its successor
+            saorsa_free(stale);
+            for handle in fresh {
+                assert!(saorsa_poll_event(handle, buf.as_mut_ptr(), buf.len()) >= 0);
+                saorsa_free(handle);
+            }
+
+            saorsa_free_string(call_id);
+            unsafe {
+                let _ = std::ffi::CString::from_raw(peer_ptr);
+                let _ = std::ffi::CString::from_raw(id_ptr);
+            }
+        }
+    }
+
+    #[test]
+    fn test_forged_handle_rejected() {
+        let identity = std::ffi::CString::new("alice").ok().map(|s| s.into_raw());
+        if let Some(id_ptr) = identity {
+            let handle = saorsa_init(id_ptr);
+            let mut buf = [0 as c_char; 64];
+            for forged in [handle as usize + 1, handle as usize ^ 1, usize::MAX] {
+                let forged = forged as *mut std::ffi::c_void;
+                assert_eq!(saorsa_poll_event(forged, buf.as_mut_ptr(), buf.len()), -1);
+                saorsa_free(forged);
+            }
+            // The real handle survives frees of forged ones
+            assert_eq!(saorsa_poll_event(handle, buf.as_mut_ptr(), buf.len()), 0);
+
+            saorsa_free(handle);
+            unsafe {
+                let _ = std::ffi::CString::from_raw(id_ptr);
+            }
+        }
+    }
+
     #[test]
     fn test_double_free_is_safe() {
         let identity = std::ffi::CString::new("test").ok().map(|s| s.into_raw());
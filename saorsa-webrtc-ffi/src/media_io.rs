@@ -0,0 +1,163 @@
+//! External media I/O
+//!
+//! Apps that own capture and rendering through platform APIs push raw
+//! frames in and pull remote frames out. Video frames are I420 (planar
+//! Y, U, V with 2x2 subsampled chroma); audio frames are interleaved
+//! signed 16-bit PCM.
+
+use std::collections::VecDeque;
+
+/// Maximum decoded remote video frames held per call
+pub const MAX_QUEUED_VIDEO_FRAMES: usize = 8;
+
+/// Maximum decoded remote audio frames held per call
+pub const MAX_QUEUED_AUDIO_FRAMES: usize = 50;
+
+/// Largest accepted video dimension
+pub const MAX_VIDEO_DIMENSION: u32 = 8192;
+
+/// Largest accepted audio channel count
+pub const MAX_AUDIO_CHANNELS: u32 = 8;
+
+/// Size in bytes of an I420 frame, or `None` for invalid dimensions
+pub fn i420_size(width: u32, height: u32) -> Option<usize> {
+    if width == 0 || height == 0 || width > MAX_VIDEO_DIMENSION || height > MAX_VIDEO_DIMENSION {
+        return None;
+    }
+    let (width, height) = (width as usize, height as usize);
+    let chroma = width.div_ceil(2) * height.div_ceil(2);
+    Some(width * height + 2 * chroma)
+}
+
+/// Raw I420 video frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VideoFrame {
+    /// Y, U and V planes back to back
+    pub data: Vec<u8>,
+    /// Width in pixels
+    pub width: u32,
+    /// Height in pixels
+    pub height: u32,
+    /// Capture timestamp in microseconds
+    pub timestamp_us: u64,
+}
+
+/// Interleaved 16-bit PCM audio frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AudioFrame {
+    /// Interleaved samples
+    pub samples: Vec<i16>,
+    /// Sample rate in Hz
+    pub sample_rate: u32,
+    /// Channel count
+    pub channels: u32,
+    /// Capture timestamp in microseconds
+    pub timestamp_us: u64,
+}
+
+impl AudioFrame {
+    /// Check that the format is usable and samples fill whole channel groups
+    pub fn is_valid(&self) -> bool {
+        self.sample_rate > 0
+            && (1..=MAX_AUDIO_CHANNELS).contains(&self.channels)
+            && !self.samples.is_empty()
+            && self.samples.len().is_multiple_of(self.channels as usize)
+    }
+}
+
+/// Remote frames waiting to be pulled
+///
+/// When rendering falls behind, the oldest frames are dropped so latency
+/// stays bounded.
+#[derive(Debug, Default)]
+pub struct RemoteMedia {
+    video: VecDeque<VideoFrame>,
+    audio: VecDeque<AudioFrame>,
+}
+
+impl RemoteMedia {
+    /// Queue a remote video frame
+    pub fn push_video(&mut self, frame: VideoFrame) {
+        if self.video.len() >= MAX_QUEUED_VIDEO_FRAMES {
+            self.video.pop_front();
+        }
+        self.video.push_back(frame);
+    }
+
+    /// Queue a remote audio frame
+    pub fn push_audio(&mut self, frame: AudioFrame) {
+        if self.audio.len() >= MAX_QUEUED_AUDIO_FRAMES {
+            self.audio.pop_front();
+        }
+        self.audio.push_back(frame);
+    }
+
+    /// Next remote video frame, left queued
+    pub fn front_video(&self) -> Option<&VideoFrame> {
+        self.video.front()
+    }
+
+    /// Remove the next remote video frame
+    pub fn pop_video(&mut self) -> Option<VideoFrame> {
+        self.video.pop_front()
+    }
+
+    /// Next remote audio frame, left queued
+    pub fn front_audio(&self) -> Option<&AudioFrame> {
+        self.audio.front()
+    }
+
+    /// Remove the next remote audio frame
+    pub fn pop_audio(&mut self) -> Option<AudioFrame> {
+        self.audio.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_i420_size() {
+        assert_eq!(i420_size(640, 480), Some(640 * 480 * 3 / 2));
+        // Odd dimensions round chroma up
+        assert_eq!(i420_size(3, 3), Some(9 + 2 * 4));
+        assert_eq!(i420_size(0, 480), None);
+        assert_eq!(i420_size(MAX_VIDEO_DIMENSION + 1, 2), None);
+    }
+
+    #[test]
+    fn test_remote_video_drops_oldest() {
+        let mut media = RemoteMedia::default();
+        for timestamp_us in 0..MAX_QUEUED_VIDEO_FRAMES as u64 + 3 {
+            media.push_video(VideoFrame {
+                data: vec![0; 6],
+                width: 2,
+                height: 2,
+                timestamp_us,
+            });
+        }
+        assert_eq!(media.front_video().map(|f| f.timestamp_us), Some(3));
+    }
+
+    #[test]
+    fn test_audio_frame_validation() {
+        let frame = AudioFrame {
+            samples: vec![0; 960],
+            sample_rate: 48_000,
+            channels: 2,
+            timestamp_us: 0,
+        };
+        assert!(frame.is_valid());
+        assert!(!AudioFrame {
+            samples: vec![0; 961],
+            ..frame.clone()
+        }
+        .is_valid());
+        assert!(!AudioFrame {
+            channels: 0,
+            ..frame
+        }
+        .is_valid());
+    }
+}
@@ -0,0 +1,138 @@
+//! C ABI lock
+//!
+//! Checks the committed header matches the one generated from the current
+//! sources, then builds the static library, compiles `tests/c/abi_test.c`
+//! against it and runs the result.
+
+#![cfg(unix)]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const HEADER: &str = "saorsa_webrtc.h";
+
+fn committed_include_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("include")
+}
+
+/// Static library and the native libraries it must be linked with
+struct StaticLib {
+    path: PathBuf,
+    native_libs: Vec<String>,
+}
+
+/// Build the static library for `target`
+///
+/// `cargo test` builds neither library crate type, so the test builds its
+/// own in a separate target directory rather than relying on artifacts left
+/// by an earlier `cargo build`.
+fn build_static_lib(target: &str) -> Result<StaticLib, String> {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let output = Command::new(cargo)
+        .args(["rustc", "-p", "saorsa-webrtc-ffi", "--lib"])
+        .args(["--crate-type", "staticlib", "--message-format=json"])
+        .args(["--target", target, "--target-dir"])
+        .arg(Path::new(env!("CARGO_TARGET_TMPDIR")).join("abi"))
+        .args(["--", "--print", "native-static-libs"])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .map_err(|e| format!("cannot run cargo: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "building the static library failed:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    let mut path = None;
+    let mut native_libs = None;
+    for message in String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+    {
+        match message["reason"].as_str() {
+            Some("compiler-artifact") if message["target"]["name"] == "saorsa_webrtc_ffi" => {
+                path = message["filenames"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(serde_json::Value::as_str)
+                    .find(|file| file.ends_with(".a"))
+                    .map(PathBuf::from);
+            }
+            Some("compiler-message") => {
+                // Replayed by cargo when the library is already fresh
+                if let Some(libs) = message["message"]["message"]
+                    .as_str()
+                    .and_then(|m| m.strip_prefix("native-static-libs: "))
+                {
+                    native_libs = Some(libs.split_whitespace().map(String::from).collect());
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(StaticLib {
+        path: path.ok_or("cargo reported no static library")?,
+        native_libs: native_libs.ok_or("rustc reported no native libraries")?,
+    })
+}
+
+#[test]
+fn committed_header_is_current() {
+    let generated = std::fs::read_to_string(Path::new(env!("OUT_DIR")).join(HEADER));
+    let committed = std::fs::read_to_string(committed_include_dir().join(HEADER));
+    assert!(generated.is_ok(), "header was not generated");
+    assert!(
+        generated.ok() == committed.ok(),
+        "include/{HEADER} is stale; rebuild with SAORSA_UPDATE_HEADER=1 and commit it"
+    );
+}
+
+#[test]
+fn c_program_exercises_abi() {
+    let target = env!("SAORSA_FFI_TARGET");
+    let lib = build_static_lib(target);
+    assert!(
+        lib.is_ok(),
+        "{}",
+        lib.as_ref().err().map_or("", String::as_str)
+    );
+    let Ok(lib) = lib else { return };
+
+    let compiler = cc::Build::new()
+        .target(target)
+        .host(target)
+        .opt_level(0)
+        .cargo_metadata(false)
+        .cargo_warnings(false)
+        .try_get_compiler();
+    assert!(compiler.is_ok(), "no C compiler: {:?}", compiler.err());
+    let Ok(compiler) = compiler else { return };
+
+    let exe = Path::new(env!("CARGO_TARGET_TMPDIR")).join("saorsa_abi_test");
+    let source = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/c/abi_test.c");
+    let status = compiler
+        .to_command()
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg("-I")
+        .arg(committed_include_dir())
+        .arg(&source)
+        .arg("-o")
+        .arg(&exe)
+        .arg(&lib.path)
+        .args(&lib.native_libs)
+        .status();
+    assert!(
+        status.as_ref().is_ok_and(|s| s.success()),
+        "compiling {} failed: {status:?}",
+        source.display()
+    );
+
+    let output = Command::new(&exe).output();
+    assert!(
+        output.as_ref().is_ok_and(|o| o.status.success()),
+        "C ABI test failed: {output:?}"
+    );
+}
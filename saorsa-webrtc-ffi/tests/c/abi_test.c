@@ -0,0 +1,57 @@
+/* Exercises the C API through the generated header; exits non-zero on failure. */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "saorsa_webrtc.h"
+
+#define CHECK(cond)                                                     \
+  do {                                                                  \
+    if (!(cond)) {                                                      \
+      fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, \
+              #cond);                                                   \
+      return 1;                                                         \
+    }                                                                   \
+  } while (0)
+
+int main(void) {
+  char event[256];
+
+  CHECK(saorsa_init(NULL) == NULL);
+  void *handle = saorsa_init("alice");
+  CHECK(handle != NULL);
+
+  char *call_id = saorsa_call(handle, "bob");
+  CHECK(call_id != NULL);
+  CHECK(saorsa_call_state(handle, call_id) == CALL_STATE_ACTIVE);
+
+  ptrdiff_t written = saorsa_poll_event(handle, event, sizeof event);
+  CHECK(written > 0);
+  CHECK(strstr(event, "\"call_started\"") != NULL);
+  CHECK(saorsa_poll_event(handle, event, sizeof event) == 0);
+
+  uint8_t frame[6] = {1, 2, 3, 4, 5, 6};
+  uint8_t pulled[6] = {0};
+  uint32_t width = 0, height = 0;
+  CHECK(saorsa_push_video_frame(handle, call_id, frame, 2, 2, 7) ==
+        SAORSA_RESULT_SUCCESS);
+  CHECK(saorsa_pull_video_frame(handle, call_id, pulled, sizeof pulled, &width,
+                                &height, NULL) == (ptrdiff_t)sizeof frame);
+  CHECK(memcmp(frame, pulled, sizeof frame) == 0);
+  CHECK(width == 2 && height == 2);
+
+  char *stats = saorsa_get_call_stats(handle, call_id);
+  CHECK(stats != NULL);
+  CHECK(strstr(stats, "\"peer\":\"bob\"") != NULL);
+  saorsa_free_string(stats);
+
+  CHECK(saorsa_end_call(handle, call_id) == SAORSA_RESULT_SUCCESS);
+  CHECK(saorsa_call_state(handle, call_id) == CALL_STATE_ENDED);
+
+  saorsa_free(handle);
+  CHECK(saorsa_end_call(handle, call_id) == SAORSA_RESULT_INVALID_PARAMETER);
+  CHECK(saorsa_poll_event(handle, event, sizeof event) == -1);
+  saorsa_free_string(call_id);
+
+  return 0;
+}
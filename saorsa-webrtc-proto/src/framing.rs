@@ -0,0 +1,292 @@
+//! RTP packet framing for QUIC streams
+//!
+//! Media packets travel over QUIC streams, which carry bytes rather than
+//! messages, so each packet is sent with a 2-byte big-endian length prefix.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Frame an RTP packet with 2-byte length prefix (big-endian u16)
+///
+/// # Arguments
+///
+/// * `packet` - The RTP packet bytes to frame
+///
+/// # Returns
+///
+/// A vector with 2-byte length prefix followed by packet data
+///
+/// # Errors
+///
+/// Returns error if packet is too large (> 65535 bytes)
+pub fn frame_rtp(packet: &[u8]) -> Result<Vec<u8>, String> {
+    if packet.len() > u16::MAX as usize {
+        return Err(alloc::format!(
+            "RTP packet too large: {} bytes",
+            packet.len()
+        ));
+    }
+
+    let mut framed = Vec::with_capacity(2 + packet.len());
+    framed.extend_from_slice(&(packet.len() as u16).to_be_bytes());
+    framed.extend_from_slice(packet);
+    Ok(framed)
+}
+
+/// Unframe an RTP packet, extracting length prefix and validating
+///
+/// # Arguments
+///
+/// * `data` - The framed data (length prefix + packet)
+///
+/// # Returns
+///
+/// A tuple of (expected_length, remaining_data) or error
+///
+/// # Errors
+///
+/// Returns error if frame is too small or length mismatches
+pub fn unframe_rtp(data: &[u8]) -> Result<(u16, &[u8]), String> {
+    if data.len() < 2 {
+        return Err(alloc::format!(
+            "Frame too small: {} bytes (need >= 2)",
+            data.len()
+        ));
+    }
+
+    // Parse length prefix from first 2 bytes
+    let len_bytes = &data[0..2];
+    let expected_len = u16::from_be_bytes([len_bytes[0], len_bytes[1]]);
+
+    let packet_data = &data[2..];
+
+    if packet_data.len() < expected_len as usize {
+        return Err(alloc::format!(
+            "Incomplete packet: {} bytes (expected {})",
+            packet_data.len(),
+            expected_len
+        ));
+    }
+
+    Ok((expected_len, packet_data))
+}
+
+/// Split a buffer into complete frames
+///
+/// # Arguments
+///
+/// * `data` - The data buffer containing one or more frames
+///
+/// # Returns
+///
+/// A vector of (frame_data, remaining_data) or error
+pub fn split_frames(data: &[u8]) -> Result<Vec<&[u8]>, String> {
+    let mut frames = Vec::new();
+    let mut offset = 0;
+
+    while offset < data.len() {
+        if offset + 2 > data.len() {
+            return Err("Incomplete length header".into());
+        }
+
+        let len_bytes = &data[offset..offset + 2];
+        let frame_len = u16::from_be_bytes([len_bytes[0], len_bytes[1]]) as usize;
+
+        let frame_start = offset + 2;
+        let frame_end = frame_start + frame_len;
+
+        if frame_end > data.len() {
+            return Err(alloc::format!(
+                "Incomplete frame: {} bytes (expected {})",
+                data.len() - frame_start,
+                frame_len
+            ));
+        }
+
+        frames.push(&data[frame_start..frame_end]);
+        offset = frame_end;
+    }
+
+    Ok(frames)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod framing_tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_rtp_empty() {
+        let packet = &[];
+        let framed = frame_rtp(packet).unwrap();
+        assert_eq!(framed.len(), 2);
+        assert_eq!(framed[0..2], [0, 0]);
+    }
+
+    #[test]
+    fn test_frame_rtp_small() {
+        let packet = &[0x80, 0x60, 0x00, 0x01];
+        let framed = frame_rtp(packet).unwrap();
+        assert_eq!(framed.len(), 6);
+        assert_eq!(framed[0..2], [0, 4]); // length = 4
+        assert_eq!(&framed[2..], packet);
+    }
+
+    #[test]
+    fn test_frame_rtp_large() {
+        let packet = vec![0x42; 1000];
+        let framed = frame_rtp(&packet).unwrap();
+        assert_eq!(framed.len(), 1002);
+        assert_eq!(framed[0..2], [3, 232]); // 1000 in big-endian
+    }
+
+    #[test]
+    fn test_frame_rtp_max_size() {
+        let packet = vec![0x42; 65535];
+        let framed = frame_rtp(&packet).unwrap();
+        assert_eq!(framed.len(), 65537);
+        assert_eq!(framed[0..2], [255, 255]);
+    }
+
+    #[test]
+    fn test_frame_rtp_too_large() {
+        let packet = vec![0x42; 65536];
+        let result = frame_rtp(&packet);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_unframe_rtp_empty_frame() {
+        let data = &[0, 0];
+        let (len, packet) = unframe_rtp(data).unwrap();
+        assert_eq!(len, 0);
+        assert!(packet.is_empty());
+    }
+
+    #[test]
+    fn test_unframe_rtp_valid() {
+        let data = &[0, 4, 0x80, 0x60, 0x00, 0x01];
+        let (len, packet) = unframe_rtp(data).unwrap();
+        assert_eq!(len, 4);
+        assert_eq!(packet, &[0x80, 0x60, 0x00, 0x01]);
+    }
+
+    #[test]
+    fn test_unframe_rtp_too_small() {
+        let data = &[0];
+        let result = unframe_rtp(data);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_unframe_rtp_incomplete_packet() {
+        let data = &[0, 4, 0x80, 0x60]; // Says 4 bytes but only 2
+        let result = unframe_rtp(data);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_roundtrip_frame_unframe() {
+        let original = &[0x80, 0x60, 0x00, 0x01, 0xAA, 0xBB, 0xCC, 0xDD];
+        let framed = frame_rtp(original).unwrap();
+        let (len, packet) = unframe_rtp(&framed).unwrap();
+
+        assert_eq!(len as usize, original.len());
+        assert_eq!(packet, original);
+    }
+
+    #[test]
+    fn test_split_frames_single() {
+        let packet = &[0x80, 0x60, 0x00, 0x01];
+        let framed = frame_rtp(packet).unwrap();
+
+        let frames = split_frames(&framed).unwrap();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0], packet);
+    }
+
+    #[test]
+    fn test_split_frames_multiple() {
+        let packet1 = &[0x80, 0x60];
+        let packet2 = &[0x81, 0x61, 0xAA, 0xBB];
+
+        let mut combined = Vec::new();
+        combined.extend_from_slice(&frame_rtp(packet1).unwrap());
+        combined.extend_from_slice(&frame_rtp(packet2).unwrap());
+
+        let frames = split_frames(&combined).unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0], packet1);
+        assert_eq!(frames[1], packet2);
+    }
+
+    #[test]
+    fn test_split_frames_incomplete() {
+        let packet = &[0x80, 0x60];
+        let framed = frame_rtp(packet).unwrap();
+
+        let incomplete = &framed[0..3]; // Missing 1 byte of payload
+        let result = split_frames(incomplete);
+        assert!(result.is_err());
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod framing_proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn prop_frame_unframe_roundtrip(packet in prop::collection::vec(any::<u8>(), 0..2048)) {
+            let framed = frame_rtp(&packet).unwrap();
+            let (len, data) = unframe_rtp(&framed).unwrap();
+            prop_assert_eq!(usize::from(len), packet.len());
+            prop_assert_eq!(data, packet.as_slice());
+        }
+
+        #[test]
+        fn prop_split_frames_recovers_packets(
+            packets in prop::collection::vec(prop::collection::vec(any::<u8>(), 0..300), 0..16),
+        ) {
+            let mut buffer = Vec::new();
+            for packet in &packets {
+                buffer.extend_from_slice(&frame_rtp(packet).unwrap());
+            }
+            let frames = split_frames(&buffer).unwrap();
+            prop_assert_eq!(frames.len(), packets.len());
+            for (frame, packet) in frames.iter().zip(&packets) {
+                prop_assert_eq!(*frame, packet.as_slice());
+            }
+        }
+
+        #[test]
+        fn prop_split_frames_rejects_truncation(
+            packets in prop::collection::vec(prop::collection::vec(any::<u8>(), 1..300), 1..8),
+            cut in 1usize..300,
+        ) {
+            let mut buffer = Vec::new();
+            for packet in &packets {
+                buffer.extend_from_slice(&frame_rtp(packet).unwrap());
+            }
+            buffer.truncate(buffer.len().saturating_sub(cut));
+            // Anything short of a frame boundary is an error, never a short frame
+            if let Ok(frames) = split_frames(&buffer) {
+                let consumed: usize = frames.iter().map(|frame| frame.len() + 2).sum();
+                prop_assert_eq!(consumed, buffer.len());
+            }
+        }
+
+        #[test]
+        fn prop_parsers_accept_arbitrary_input(data in prop::collection::vec(any::<u8>(), 0..1024)) {
+            if let Ok((len, rest)) = unframe_rtp(&data) {
+                prop_assert!(rest.len() >= usize::from(len));
+            }
+            if let Ok(frames) = split_frames(&data) {
+                let consumed: usize = frames.iter().map(|frame| frame.len() + 2).sum();
+                prop_assert_eq!(consumed, data.len());
+            }
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! Saorsa WebRTC wire protocol
+//!
+//! The data types peers exchange and the framing they are sent in, shared
+//! with `saorsa-webrtc-core` so other implementations, such as firmware on
+//! a microcontroller or a sandboxed client, use exactly the same wire types.
+//!
+//! The crate needs only `alloc`. Without the default `std` feature it builds
+//! for `no_std` targets; [`CallId::new`] then is not available, so call IDs
+//! have to come from the peer or be parsed.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use saorsa_webrtc_proto::{wire, SignalingMessage};
+//!
+//! let message = SignalingMessage::ConnectionReady {
+//!     session_id: "3c8c4e2a-5f9e-4b7e-9a55-0e3a6b1f2d4c".into(),
+//! };
+//! let frame = wire::encode_postcard(&message)?;
+//! assert_eq!(wire::decode_postcard(&frame)?, message);
+//! # Ok::<(), wire::FrameError>(())
+//! ```
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(missing_docs)]
+#![deny(unsafe_code)]
+#![deny(clippy::panic)]
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![warn(clippy::all)]
+#![allow(clippy::derivable_impls)]
+
+extern crate alloc;
+
+/// RTP packet framing for QUIC streams
+pub mod framing;
+
+/// Short codes for call IDs
+pub mod short_code;
+
+/// Signaling messages
+pub mod signaling;
+
+/// Media stream identifiers
+pub mod stream;
+
+/// Call, media and track types
+pub mod types;
+
+/// Signaling frame tags and binary encoding
+pub mod wire;
+
+pub use short_code::{InvalidShortCode, ShortCode, SHORT_CODE_LEN};
+pub use signaling::SignalingMessage;
+pub use stream::{StreamKey, StreamType, TrackId, PRIMARY_TRACK};
+pub use types::{
+    CallId, CallProgress, CallState, LatencyProfile, LatencyTuning, LayerStructure,
+    MediaConstraints, MediaTransportState, MediaType, TrackInfo, TrackSource, VideoLayer,
+};
+pub use wire::{CompactMessage, FrameError};
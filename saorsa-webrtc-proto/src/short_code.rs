@@ -0,0 +1,140 @@
+//! Short codes people can read out for call IDs
+//!
+//! A short code is six Crockford base32 characters derived from an ID by
+//! hashing it, so both peers and every log derive the same code. Codes are
+//! not unique: 30 bits make a collision among a handful of concurrent calls
+//! unlikely but possible, so whatever resolves a code must check that it
+//! matches only one call.
+
+use core::fmt;
+use core::str::FromStr;
+
+/// Characters in a short code
+pub const SHORT_CODE_LEN: usize = 6;
+
+/// Crockford base32: no I, L, O or U, which read like 1, 1, 0 and V
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Human-readable code of an ID, e.g. `7KQ2XM`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ShortCode([u8; SHORT_CODE_LEN]);
+
+impl ShortCode {
+    /// Code of an ID's bytes
+    #[must_use]
+    pub fn of(id: &[u8]) -> Self {
+        // FNV-1a spreads IDs whose leading bytes agree, such as time-ordered
+        // UUIDs, across codes
+        let hash = id.iter().fold(FNV_OFFSET, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+        });
+        let mut code = [0; SHORT_CODE_LEN];
+        for (i, c) in code.iter_mut().enumerate() {
+            *c = ALPHABET[((hash >> (59 - 5 * i)) & 0x1f) as usize];
+        }
+        Self(code)
+    }
+
+    /// The code as a string
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        // Always ASCII from ALPHABET
+        core::str::from_utf8(&self.0).unwrap_or_default()
+    }
+}
+
+impl fmt::Display for ShortCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Error parsing a [`ShortCode`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidShortCode;
+
+impl fmt::Display for InvalidShortCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Short code must be {SHORT_CODE_LEN} characters of 0-9 and A-Z"
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidShortCode {}
+
+impl FromStr for ShortCode {
+    type Err = InvalidShortCode;
+
+    /// Parse a code as someone might type it: in either case, with dashes
+    /// or spaces, and with O for 0 or I and L for 1
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut code = [0; SHORT_CODE_LEN];
+        let mut len = 0;
+        for c in s.chars().filter(|c| !matches!(c, '-' | ' ')) {
+            let c = match c.to_ascii_uppercase() {
+                'O' => '0',
+                'I' | 'L' => '1',
+                c => c,
+            };
+            let byte = u8::try_from(c).map_err(|_| InvalidShortCode)?;
+            if len == SHORT_CODE_LEN || !ALPHABET.contains(&byte) {
+                return Err(InvalidShortCode);
+            }
+            code[len] = byte;
+            len += 1;
+        }
+        if len == SHORT_CODE_LEN {
+            Ok(Self(code))
+        } else {
+            Err(InvalidShortCode)
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::types::CallId;
+    use alloc::string::ToString;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_code_is_deterministic() {
+        let call_id = CallId(Uuid::from_u128(0x3c8c_4e2a_5f9e_4b7e_9a55_0e3a_6b1f_2d4c));
+        let code = call_id.short_code();
+        assert_eq!(code, call_id.short_code());
+        assert_eq!(code.as_str().len(), SHORT_CODE_LEN);
+        assert_eq!(code.to_string().parse::<ShortCode>().unwrap(), code);
+        assert_ne!(code, CallId(Uuid::from_u128(1)).short_code());
+    }
+
+    #[test]
+    fn test_parse_forgives_typing() {
+        let code: ShortCode = "7KQ2X0".parse().unwrap();
+        assert_eq!("7kq-2xo".parse::<ShortCode>().unwrap(), code);
+        assert_eq!(
+            "1ab 1cd".parse::<ShortCode>(),
+            "Iab lcd".parse::<ShortCode>()
+        );
+        for invalid in ["", "7KQ2X", "7KQ2X0A", "7KQ2XU", "7KQ2X€"] {
+            assert_eq!(invalid.parse::<ShortCode>(), Err(InvalidShortCode));
+        }
+    }
+
+    #[test]
+    fn test_sequential_ids_spread() {
+        let mut codes: alloc::vec::Vec<_> = (0..1000u128)
+            .map(|i| CallId(Uuid::from_u128(i << 64)).short_code())
+            .collect();
+        codes.sort_by_key(|code| code.0);
+        codes.dedup();
+        assert!(codes.len() > 990);
+    }
+}
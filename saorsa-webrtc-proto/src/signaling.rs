@@ -0,0 +1,283 @@
+//! Signaling messages
+//!
+//! The messages peers exchange to set up, update and end a call. JSON
+//! encodes them internally tagged by a `type` field; the binary formats go
+//! through [`CompactMessage`](crate::wire::CompactMessage).
+
+use crate::stream::TrackId;
+use crate::types::{CallId, CallProgress, TrackInfo, VideoLayer};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::net::SocketAddr;
+use serde::{Deserialize, Serialize};
+
+/// Signaling message types
+///
+/// Supports both legacy WebRTC (SDP/ICE) and QUIC-native (capability exchange) signaling.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum SignalingMessage {
+    // === Legacy WebRTC Messages (deprecated for new calls) ===
+    /// SDP offer (legacy WebRTC)
+    #[serde(rename = "offer")]
+    Offer {
+        /// Session ID
+        session_id: String,
+        /// SDP content
+        sdp: String,
+        /// Optional QUIC endpoint
+        quic_endpoint: Option<SocketAddr>,
+    },
+
+    /// SDP answer (legacy WebRTC)
+    #[serde(rename = "answer")]
+    Answer {
+        /// Session ID
+        session_id: String,
+        /// SDP content
+        sdp: String,
+        /// Optional QUIC endpoint
+        quic_endpoint: Option<SocketAddr>,
+    },
+
+    /// ICE candidate (legacy WebRTC)
+    #[serde(rename = "ice_candidate")]
+    IceCandidate {
+        /// Session ID
+        session_id: String,
+        /// Candidate string
+        candidate: String,
+        /// SDP mid
+        sdp_mid: Option<String>,
+        /// SDP mline index
+        sdp_mline_index: Option<u16>,
+    },
+
+    /// ICE gathering complete (legacy WebRTC)
+    #[serde(rename = "ice_complete")]
+    IceComplete {
+        /// Session ID
+        session_id: String,
+    },
+
+    // === QUIC-Native Messages ===
+    /// Capability exchange (QUIC-native)
+    ///
+    /// Sent instead of SDP offer. Contains local media capabilities.
+    #[serde(rename = "capability_exchange")]
+    CapabilityExchange {
+        /// Session/call ID
+        session_id: String,
+        /// Local media capabilities
+        audio: bool,
+        /// Video capability
+        video: bool,
+        /// Data channel capability
+        data_channel: bool,
+        /// Maximum bandwidth in kbps
+        max_bandwidth_kbps: u32,
+        /// QUIC endpoint for direct connection
+        quic_endpoint: Option<SocketAddr>,
+    },
+
+    /// Connection confirmation (QUIC-native)
+    ///
+    /// Sent in response to CapabilityExchange to confirm connection is ready.
+    #[serde(rename = "connection_confirm")]
+    ConnectionConfirm {
+        /// Session/call ID
+        session_id: String,
+        /// Peer's media capabilities (for mutual agreement)
+        audio: bool,
+        /// Video capability
+        video: bool,
+        /// Data channel capability
+        data_channel: bool,
+        /// Maximum bandwidth in kbps
+        max_bandwidth_kbps: u32,
+        /// QUIC endpoint for direct connection
+        quic_endpoint: Option<SocketAddr>,
+    },
+
+    /// Connection ready notification (QUIC-native)
+    ///
+    /// Sent when QUIC connection is established and media can flow.
+    #[serde(rename = "connection_ready")]
+    ConnectionReady {
+        /// Session/call ID
+        session_id: String,
+    },
+
+    /// Media track metadata (QUIC-native)
+    ///
+    /// Lists every track the sender currently sends, with its kind, source
+    /// and label. Sent after the capability exchange and again whenever the
+    /// sender's tracks change; each update replaces the previous one.
+    #[serde(rename = "track_update")]
+    TrackUpdate {
+        /// Session/call ID
+        session_id: String,
+        /// The sender's tracks
+        tracks: Vec<TrackInfo>,
+    },
+
+    /// Video layer selection (QUIC-native)
+    ///
+    /// Sent by the receiver of a simulcast or SVC video track to choose the
+    /// highest layer it wants; the sender stops sending the layers above.
+    #[serde(rename = "layer_selection")]
+    LayerSelection {
+        /// Session/call ID
+        session_id: String,
+        /// The sender's video track
+        track_id: TrackId,
+        /// Highest layer wanted
+        layer: VideoLayer,
+    },
+
+    /// Call handoff announcement (QUIC-native)
+    ///
+    /// Sent by the device holding a call when it hands the call to another
+    /// of the user's devices. Carries the key that the new device's token
+    /// must verify against.
+    #[serde(rename = "handoff_offer")]
+    HandoffOffer {
+        /// Session/call ID
+        session_id: String,
+        /// One-time handoff key, base64url encoded
+        key: String,
+    },
+
+    /// Call handoff join (QUIC-native)
+    ///
+    /// Sent by the device taking over a call, with the token it received
+    /// from the device holding the call.
+    #[serde(rename = "handoff_join")]
+    HandoffJoin {
+        /// Session/call ID
+        session_id: String,
+        /// Encoded handoff token
+        token: String,
+    },
+
+    /// Call handoff completion (QUIC-native)
+    ///
+    /// Sent by the remote peer to both devices once media flows to the new
+    /// device; the old device then drops the call.
+    #[serde(rename = "handoff_complete")]
+    HandoffComplete {
+        /// Session/call ID
+        session_id: String,
+    },
+
+    // === Common Messages ===
+    /// Call progress, sent by the callee while the call is unanswered
+    #[serde(rename = "progress")]
+    Progress {
+        /// Session ID
+        session_id: String,
+        /// How far the call has got
+        progress: CallProgress,
+    },
+
+    /// Audio-only fallback
+    ///
+    /// Sent when the sender suspends its video because of a poor network,
+    /// and again when video resumes, so the receiver can show a placeholder
+    /// instead of a frozen frame.
+    #[serde(rename = "audio_only")]
+    AudioOnly {
+        /// Session ID
+        session_id: String,
+        /// `true` while video is suspended
+        active: bool,
+    },
+
+    /// Stop ringing a call that was not answered here
+    ///
+    /// Sent by a caller that rang several devices of the callee, to every
+    /// device but the one that answered, or to all of them when the caller
+    /// gives up.
+    #[serde(rename = "cancel")]
+    Cancel {
+        /// Session ID
+        session_id: String,
+        /// Optional reason
+        reason: Option<String>,
+    },
+
+    /// Close session
+    #[serde(rename = "bye")]
+    Bye {
+        /// Session ID
+        session_id: String,
+        /// Optional reason
+        reason: Option<String>,
+    },
+}
+
+impl SignalingMessage {
+    /// Get the session ID
+    #[must_use]
+    pub fn session_id(&self) -> &str {
+        match self {
+            // Legacy WebRTC
+            Self::Offer { session_id, .. }
+            | Self::Answer { session_id, .. }
+            | Self::IceCandidate { session_id, .. }
+            | Self::IceComplete { session_id }
+            // QUIC-native
+            | Self::CapabilityExchange { session_id, .. }
+            | Self::ConnectionConfirm { session_id, .. }
+            | Self::ConnectionReady { session_id }
+            | Self::TrackUpdate { session_id, .. }
+            | Self::LayerSelection { session_id, .. }
+            | Self::HandoffOffer { session_id, .. }
+            | Self::HandoffJoin { session_id, .. }
+            | Self::HandoffComplete { session_id }
+            // Common
+            | Self::Progress { session_id, .. }
+            | Self::AudioOnly { session_id, .. }
+            | Self::Cancel { session_id, .. }
+            | Self::Bye { session_id, .. } => session_id,
+        }
+    }
+
+    /// Get the call this message belongs to
+    ///
+    /// Peers correlate a call end to end by using its [`CallId`] as the
+    /// session ID. Returns `None` for session IDs that are not call IDs.
+    #[must_use]
+    pub fn call_id(&self) -> Option<CallId> {
+        self.session_id().parse().ok()
+    }
+
+    /// Check if this is a QUIC-native message
+    #[must_use]
+    pub fn is_quic_native(&self) -> bool {
+        matches!(
+            self,
+            Self::CapabilityExchange { .. }
+                | Self::ConnectionConfirm { .. }
+                | Self::ConnectionReady { .. }
+                | Self::TrackUpdate { .. }
+                | Self::LayerSelection { .. }
+                | Self::HandoffOffer { .. }
+                | Self::HandoffJoin { .. }
+                | Self::HandoffComplete { .. }
+        )
+    }
+
+    /// Check if this is a legacy WebRTC message
+    #[must_use]
+    pub fn is_legacy_webrtc(&self) -> bool {
+        matches!(
+            self,
+            Self::Offer { .. }
+                | Self::Answer { .. }
+                | Self::IceCandidate { .. }
+                | Self::IceComplete { .. }
+        )
+    }
+}
@@ -0,0 +1,98 @@
+//! Media stream identifiers
+
+use crate::types::MediaType;
+
+/// Stream type identifiers for QUIC media streams
+///
+/// Allocates stream IDs in the 0x20-0x2F range to enable multiple
+/// concurrent media streams over a single QUIC connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum StreamType {
+    /// Audio RTP stream (0x20)
+    Audio = 0x20,
+    /// Video RTP stream (0x21)
+    Video = 0x21,
+    /// Screen share RTP stream (0x22)
+    Screen = 0x22,
+    /// RTCP feedback stream (0x23)
+    RtcpFeedback = 0x23,
+    /// Data channel (0x24)
+    Data = 0x24,
+}
+
+impl StreamType {
+    /// Get stream type as byte value
+    #[must_use]
+    pub fn as_u8(self) -> u8 {
+        self as u8
+    }
+
+    /// Try to convert byte value to StreamType
+    ///
+    /// # Errors
+    ///
+    /// Returns None if byte is not a valid StreamType
+    pub fn try_from_u8(val: u8) -> Option<Self> {
+        match val {
+            0x20 => Some(StreamType::Audio),
+            0x21 => Some(StreamType::Video),
+            0x22 => Some(StreamType::Screen),
+            0x23 => Some(StreamType::RtcpFeedback),
+            0x24 => Some(StreamType::Data),
+            _ => None,
+        }
+    }
+
+    /// Stream type carrying a kind of media
+    #[must_use]
+    pub fn for_media(media_type: &MediaType) -> Self {
+        match media_type {
+            MediaType::Audio => StreamType::Audio,
+            MediaType::Video => StreamType::Video,
+            MediaType::ScreenShare => StreamType::Screen,
+            MediaType::DataChannel => StreamType::Data,
+        }
+    }
+}
+
+/// Identifies one track among the tracks of a stream type
+///
+/// A call can carry several tracks of the same type, such as a camera and a
+/// second camera on two video tracks. Each track has its own QUIC stream.
+pub type TrackId = u16;
+
+/// Track used by the per-stream-type methods
+pub const PRIMARY_TRACK: TrackId = 0;
+
+/// Key of one media stream: a track of a stream type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StreamKey {
+    /// Stream type
+    pub stream_type: StreamType,
+    /// Track within the stream type
+    pub track_id: TrackId,
+}
+
+impl StreamKey {
+    /// Key of a track of a stream type
+    #[must_use]
+    pub const fn new(stream_type: StreamType, track_id: TrackId) -> Self {
+        Self {
+            stream_type,
+            track_id,
+        }
+    }
+
+    /// Key of the primary track of a stream type
+    #[must_use]
+    pub const fn primary(stream_type: StreamType) -> Self {
+        Self::new(stream_type, PRIMARY_TRACK)
+    }
+}
+
+impl From<StreamType> for StreamKey {
+    fn from(stream_type: StreamType) -> Self {
+        Self::primary(stream_type)
+    }
+}
@@ -0,0 +1,452 @@
+//! Call, media and track types
+
+use crate::short_code::ShortCode;
+use crate::stream::{StreamKey, StreamType, TrackId};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Unique identifier for a call
+///
+/// Call IDs are totally ordered so both peers can resolve glare the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct CallId(pub Uuid);
+
+#[cfg(feature = "std")]
+impl CallId {
+    /// Create a new random call ID
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+impl CallId {
+    /// Short code to read out or search logs for in place of the UUID
+    ///
+    /// Not unique; see [`crate::short_code`].
+    #[must_use]
+    pub fn short_code(&self) -> ShortCode {
+        ShortCode::of(self.0.as_bytes())
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for CallId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for CallId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for CallId {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Uuid::parse_str(s).map(Self)
+    }
+}
+
+/// Media constraints for a call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct MediaConstraints {
+    /// Enable audio
+    pub audio: bool,
+    /// Enable video
+    pub video: bool,
+    /// Enable screen sharing
+    pub screen_share: bool,
+    /// Latency/quality trade-off; the call manager's default when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency: Option<LatencyProfile>,
+}
+
+impl MediaConstraints {
+    /// Audio-only call
+    pub fn audio_only() -> Self {
+        Self {
+            audio: true,
+            video: false,
+            screen_share: false,
+
+            latency: None,
+        }
+    }
+
+    /// Video call with audio
+    pub fn video_call() -> Self {
+        Self {
+            audio: true,
+            video: true,
+            screen_share: false,
+
+            latency: None,
+        }
+    }
+
+    /// Screen share with audio
+    pub fn screen_share() -> Self {
+        Self {
+            audio: true,
+            video: false,
+            screen_share: true,
+
+            latency: None,
+        }
+    }
+
+    /// Check if audio is enabled
+    pub fn has_audio(&self) -> bool {
+        self.audio
+    }
+
+    /// Check if video is enabled
+    pub fn has_video(&self) -> bool {
+        self.video
+    }
+
+    /// Check if screen share is enabled
+    pub fn has_screen_share(&self) -> bool {
+        self.screen_share
+    }
+
+    /// Convert to media types
+    pub fn to_media_types(&self) -> Vec<MediaType> {
+        let mut types = Vec::new();
+        if self.audio {
+            types.push(MediaType::Audio);
+        }
+        if self.video {
+            types.push(MediaType::Video);
+        }
+        if self.screen_share {
+            types.push(MediaType::ScreenShare);
+        }
+        types
+    }
+
+    /// Create constraints from the media types of an offer
+    pub fn from_media_types(types: &[MediaType]) -> Self {
+        Self {
+            audio: types.contains(&MediaType::Audio),
+            video: types.contains(&MediaType::Video),
+            screen_share: types.contains(&MediaType::ScreenShare),
+            latency: None,
+        }
+    }
+
+    /// Use a latency profile for the call
+    #[must_use]
+    pub fn with_latency(mut self, profile: LatencyProfile) -> Self {
+        self.latency = Some(profile);
+        self
+    }
+}
+
+/// Latency/quality trade-off of a call
+///
+/// One knob for the settings that trade delay against quality; see
+/// [`tuning`](Self::tuning) for what each profile sets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum LatencyProfile {
+    /// Lowest delay for conversation, at some cost in quality
+    Interactive,
+    /// Conversation with headroom for poorer networks
+    Balanced,
+    /// Best picture and sound, tolerating delay, e.g. for presentations
+    Quality,
+}
+
+impl Default for LatencyProfile {
+    fn default() -> Self {
+        Self::Balanced
+    }
+}
+
+impl LatencyProfile {
+    /// Media settings for the profile
+    #[must_use]
+    pub fn tuning(self) -> LatencyTuning {
+        match self {
+            // No time to wait for retransmissions, so lean on FEC instead
+            Self::Interactive => LatencyTuning {
+                jitter_target_ms: 20,
+                jitter_max_ms: 80,
+                encoder_lookahead_frames: 0,
+                max_b_frames: 0,
+                pacer_burst_ms: 5,
+                pacing_factor_percent: 250,
+                fec_percent: 20,
+            },
+            Self::Balanced => LatencyTuning {
+                jitter_target_ms: 40,
+                jitter_max_ms: 200,
+                encoder_lookahead_frames: 1,
+                max_b_frames: 0,
+                pacer_burst_ms: 10,
+                pacing_factor_percent: 150,
+                fec_percent: 10,
+            },
+            Self::Quality => LatencyTuning {
+                jitter_target_ms: 100,
+                jitter_max_ms: 500,
+                encoder_lookahead_frames: 8,
+                max_b_frames: 2,
+                pacer_burst_ms: 40,
+                pacing_factor_percent: 110,
+                fec_percent: 5,
+            },
+        }
+    }
+}
+
+/// Media settings derived from a [`LatencyProfile`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct LatencyTuning {
+    /// Delay the jitter buffer aims to hold, in milliseconds
+    pub jitter_target_ms: u32,
+    /// Delay the jitter buffer may grow to before dropping, in milliseconds
+    pub jitter_max_ms: u32,
+    /// Frames the video encoder may buffer to plan rate control
+    pub encoder_lookahead_frames: u32,
+    /// Bidirectionally predicted frames between reference frames
+    pub max_b_frames: u32,
+    /// Media the pacer may send in one burst, in milliseconds of bitrate
+    pub pacer_burst_ms: u32,
+    /// Pacing rate as a percentage of the target bitrate; higher drains
+    /// queues faster
+    pub pacing_factor_percent: u32,
+    /// Forward error correction overhead as a percentage of media
+    pub fec_percent: u8,
+}
+
+/// Types of media in a call
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum MediaType {
+    /// Audio stream
+    Audio,
+    /// Video stream
+    Video,
+    /// Screen share stream
+    ScreenShare,
+    /// Data channel
+    DataChannel,
+}
+
+/// What a media track carries, so the receiver can lay it out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum TrackSource {
+    /// Microphone or other audio input
+    Microphone,
+    /// Camera
+    Camera,
+    /// Captured screen or window
+    Screen,
+    /// Presentation slides
+    Slides,
+    /// Anything else
+    Other,
+}
+
+/// Description of one media track, announced to the remote peer
+///
+/// Several tracks can share a media type (a camera and a slide deck are
+/// both video), so peers send this metadata alongside the media to say
+/// what each track is.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct TrackInfo {
+    /// Track within its stream type
+    pub track_id: TrackId,
+    /// Kind of media
+    pub kind: MediaType,
+    /// What the track captures
+    pub source: TrackSource,
+    /// Human-readable label, e.g. "Front camera"
+    #[serde(default)]
+    pub label: String,
+}
+
+impl TrackInfo {
+    /// Describe a track
+    pub fn new(
+        track_id: TrackId,
+        kind: MediaType,
+        source: TrackSource,
+        label: impl Into<String>,
+    ) -> Self {
+        Self {
+            track_id,
+            kind,
+            source,
+            label: label.into(),
+        }
+    }
+
+    /// Transport stream carrying the track
+    #[must_use]
+    pub fn stream_key(&self) -> StreamKey {
+        StreamKey::new(StreamType::for_media(&self.kind), self.track_id)
+    }
+}
+
+/// One layer of a simulcast or SVC video track
+///
+/// Layers order by spatial layer, then temporal layer; higher layers have
+/// more resolution or frame rate. Layer `0/0` is the base layer.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct VideoLayer {
+    /// Spatial layer (resolution)
+    pub spatial: u8,
+    /// Temporal layer (frame rate)
+    pub temporal: u8,
+}
+
+impl VideoLayer {
+    /// Base layer: lowest resolution and frame rate
+    pub const BASE: Self = Self::new(0, 0);
+
+    /// Create a layer
+    #[must_use]
+    pub const fn new(spatial: u8, temporal: u8) -> Self {
+        Self { spatial, temporal }
+    }
+}
+
+/// How the layers of a video track relate to each other
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum LayerStructure {
+    /// Simulcast: each spatial layer is a separate encoding, so a receiver
+    /// needs only the one it chose
+    #[default]
+    Simulcast,
+    /// Scalable video coding: each spatial layer builds on the ones below,
+    /// so a receiver needs all of them up to the one it chose
+    Svc,
+}
+
+impl LayerStructure {
+    /// Check if packets of `layer` go to a receiver that selected `selected`
+    ///
+    /// Temporal layers always build on the lower ones.
+    #[must_use]
+    pub fn forwards(self, selected: VideoLayer, layer: VideoLayer) -> bool {
+        let spatial = match self {
+            Self::Simulcast => layer.spatial == selected.spatial,
+            Self::Svc => layer.spatial <= selected.spatial,
+        };
+        spatial && layer.temporal <= selected.temporal
+    }
+}
+
+/// How far the callee's side has got with a call, as reported to the caller
+///
+/// Lets the caller's UI play ringback and show accurate progress instead of
+/// only knowing the call is still unanswered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum CallProgress {
+    /// The callee is being alerted; play ringback
+    Ringing,
+    /// The call waits in a queue before anyone is alerted
+    Queued {
+        /// Place in the queue where known, 1 being next
+        position: Option<u32>,
+    },
+    /// The callee sends audio, such as a tone or an announcement, before
+    /// answering; play it instead of local ringback
+    EarlyMedia,
+}
+
+/// Call state enumeration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum CallState {
+    /// No active call
+    Idle,
+    /// Initiating call
+    Calling,
+    /// Establishing connection
+    Connecting,
+    /// Call is active
+    Connected,
+    /// Peer stopped responding to keepalives; waiting for it to recover
+    Reconnecting,
+    /// Call is ending
+    Ending,
+    /// Call failed
+    Failed,
+}
+
+impl CallState {
+    /// Convert from `MediaTransportState` to `CallState`
+    ///
+    /// Maps QUIC transport states to call states:
+    /// - `Disconnected` -> `Idle` (initial/reset state)
+    /// - `Connecting` -> `Connecting`
+    /// - `Connected` -> `Connected`
+    /// - `Failed` -> `Failed`
+    #[must_use]
+    pub fn from_transport_state(transport_state: MediaTransportState) -> Self {
+        match transport_state {
+            MediaTransportState::Disconnected => CallState::Idle,
+            MediaTransportState::Connecting => CallState::Connecting,
+            MediaTransportState::Connected => CallState::Connected,
+            MediaTransportState::Failed => CallState::Failed,
+        }
+    }
+
+    /// Convert from `MediaTransportState` with call ending context
+    ///
+    /// Similar to `from_transport_state`, but returns `Ending` instead of
+    /// `Idle` when the transport is disconnected, suitable for cleanup scenarios.
+    #[must_use]
+    pub fn from_transport_state_ending(transport_state: MediaTransportState) -> Self {
+        match transport_state {
+            MediaTransportState::Disconnected => CallState::Ending,
+            MediaTransportState::Connecting => CallState::Connecting,
+            MediaTransportState::Connected => CallState::Connected,
+            MediaTransportState::Failed => CallState::Failed,
+        }
+    }
+}
+
+/// Connection state for the media transport
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaTransportState {
+    /// Not connected to any peer
+    Disconnected,
+    /// Connecting to a peer
+    Connecting,
+    /// Connected and ready for media
+    Connected,
+    /// Connection failed
+    Failed,
+}
+
+impl Default for MediaTransportState {
+    fn default() -> Self {
+        Self::Disconnected
+    }
+}
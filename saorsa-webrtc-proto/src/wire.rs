@@ -0,0 +1,450 @@
+//! Signaling frames
+//!
+//! JSON frames are sent bare so peers that predate negotiation can still read
+//! them. Every other frame starts with a single tag byte identifying its
+//! contents, which lets the receiver decode any frame without tracking which
+//! format was negotiated.
+//!
+//! Binary frames carry a [`CompactMessage`]. Postcard frames can be encoded
+//! and decoded here without `std`; CBOR, JSON and compression are left to
+//! the full implementation.
+
+use crate::signaling::SignalingMessage;
+use crate::stream::TrackId;
+use crate::types::{CallProgress, TrackInfo, VideoLayer};
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::net::SocketAddr;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Frame tag for CBOR-encoded signaling messages
+pub const TAG_CBOR: u8 = 0x01;
+
+/// Frame tag for postcard-encoded signaling messages
+pub const TAG_POSTCARD: u8 = 0x02;
+
+/// Frame tag for zstd-compressed frames
+pub const TAG_ZSTD: u8 = 0x10;
+
+/// Frame tag for deflate-compressed frames
+pub const TAG_DEFLATE: u8 = 0x11;
+
+/// Frame tag for protocol hello frames
+pub const TAG_HELLO: u8 = 0x7F;
+
+/// Frame errors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameError {
+    /// Failed to encode a message
+    Encode(postcard::Error),
+    /// Failed to decode a message
+    Decode(postcard::Error),
+    /// Frame starts with a tag other than the one expected
+    UnexpectedTag(u8),
+    /// Frame contains no data
+    EmptyFrame,
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Encode(e) => write!(f, "Encode error: {e}"),
+            Self::Decode(e) => write!(f, "Decode error: {e}"),
+            Self::UnexpectedTag(tag) => write!(f, "Unexpected frame tag: {tag:#04x}"),
+            Self::EmptyFrame => write!(f, "Empty frame"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FrameError {}
+
+/// A signaling message in the representation binary frames carry
+///
+/// The internally tagged representation [`SignalingMessage`] uses for JSON
+/// cannot be decoded by postcard, which is not self-describing, and loses
+/// the binary `SocketAddr` encoding under CBOR. Binary frames serialize this
+/// externally tagged wrapper instead, whose variants are identified by
+/// index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactMessage(pub SignalingMessage);
+
+impl From<SignalingMessage> for CompactMessage {
+    fn from(message: SignalingMessage) -> Self {
+        Self(message)
+    }
+}
+
+impl From<CompactMessage> for SignalingMessage {
+    fn from(message: CompactMessage) -> Self {
+        message.0
+    }
+}
+
+impl Serialize for CompactMessage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Mirror::from(self.0.clone()).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CompactMessage {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Mirror::deserialize(deserializer).map(|message| Self(message.into()))
+    }
+}
+
+/// Encode a signaling message into a postcard frame
+///
+/// # Errors
+///
+/// Returns error if the message cannot be serialized
+pub fn encode_postcard(message: &SignalingMessage) -> Result<Vec<u8>, FrameError> {
+    let mut frame = vec![TAG_POSTCARD];
+    let body = postcard::to_allocvec(&CompactMessage::from(message.clone()))
+        .map_err(FrameError::Encode)?;
+    frame.extend_from_slice(&body);
+    Ok(frame)
+}
+
+/// Decode a postcard frame
+///
+/// # Errors
+///
+/// Returns error if the frame is empty, is not a postcard frame, or cannot
+/// be deserialized
+pub fn decode_postcard(frame: &[u8]) -> Result<SignalingMessage, FrameError> {
+    match frame.split_first() {
+        None => Err(FrameError::EmptyFrame),
+        Some((&TAG_POSTCARD, body)) => postcard::from_bytes::<CompactMessage>(body)
+            .map(SignalingMessage::from)
+            .map_err(FrameError::Decode),
+        Some((&tag, _)) => Err(FrameError::UnexpectedTag(tag)),
+    }
+}
+
+/// Externally tagged mirror of [`SignalingMessage`]
+#[derive(Serialize, Deserialize)]
+enum Mirror {
+    Offer {
+        session_id: String,
+        sdp: String,
+        quic_endpoint: Option<SocketAddr>,
+    },
+    Answer {
+        session_id: String,
+        sdp: String,
+        quic_endpoint: Option<SocketAddr>,
+    },
+    IceCandidate {
+        session_id: String,
+        candidate: String,
+        sdp_mid: Option<String>,
+        sdp_mline_index: Option<u16>,
+    },
+    IceComplete {
+        session_id: String,
+    },
+    CapabilityExchange {
+        session_id: String,
+        audio: bool,
+        video: bool,
+        data_channel: bool,
+        max_bandwidth_kbps: u32,
+        quic_endpoint: Option<SocketAddr>,
+    },
+    ConnectionConfirm {
+        session_id: String,
+        audio: bool,
+        video: bool,
+        data_channel: bool,
+        max_bandwidth_kbps: u32,
+        quic_endpoint: Option<SocketAddr>,
+    },
+    ConnectionReady {
+        session_id: String,
+    },
+    Bye {
+        session_id: String,
+        reason: Option<String>,
+    },
+    // Variants are identified by index, so new ones go at the end
+    TrackUpdate {
+        session_id: String,
+        tracks: Vec<TrackInfo>,
+    },
+    HandoffOffer {
+        session_id: String,
+        key: String,
+    },
+    HandoffJoin {
+        session_id: String,
+        token: String,
+    },
+    HandoffComplete {
+        session_id: String,
+    },
+    Cancel {
+        session_id: String,
+        reason: Option<String>,
+    },
+    Progress {
+        session_id: String,
+        progress: CallProgress,
+    },
+    AudioOnly {
+        session_id: String,
+        active: bool,
+    },
+    LayerSelection {
+        session_id: String,
+        track_id: TrackId,
+        layer: VideoLayer,
+    },
+}
+
+impl From<SignalingMessage> for Mirror {
+    fn from(message: SignalingMessage) -> Self {
+        match message {
+            SignalingMessage::Offer {
+                session_id,
+                sdp,
+                quic_endpoint,
+            } => Self::Offer {
+                session_id,
+                sdp,
+                quic_endpoint,
+            },
+            SignalingMessage::Answer {
+                session_id,
+                sdp,
+                quic_endpoint,
+            } => Self::Answer {
+                session_id,
+                sdp,
+                quic_endpoint,
+            },
+            SignalingMessage::IceCandidate {
+                session_id,
+                candidate,
+                sdp_mid,
+                sdp_mline_index,
+            } => Self::IceCandidate {
+                session_id,
+                candidate,
+                sdp_mid,
+                sdp_mline_index,
+            },
+            SignalingMessage::IceComplete { session_id } => Self::IceComplete { session_id },
+            SignalingMessage::CapabilityExchange {
+                session_id,
+                audio,
+                video,
+                data_channel,
+                max_bandwidth_kbps,
+                quic_endpoint,
+            } => Self::CapabilityExchange {
+                session_id,
+                audio,
+                video,
+                data_channel,
+                max_bandwidth_kbps,
+                quic_endpoint,
+            },
+            SignalingMessage::ConnectionConfirm {
+                session_id,
+                audio,
+                video,
+                data_channel,
+                max_bandwidth_kbps,
+                quic_endpoint,
+            } => Self::ConnectionConfirm {
+                session_id,
+                audio,
+                video,
+                data_channel,
+                max_bandwidth_kbps,
+                quic_endpoint,
+            },
+            SignalingMessage::ConnectionReady { session_id } => {
+                Self::ConnectionReady { session_id }
+            }
+            SignalingMessage::Bye { session_id, reason } => Self::Bye { session_id, reason },
+            SignalingMessage::TrackUpdate { session_id, tracks } => {
+                Self::TrackUpdate { session_id, tracks }
+            }
+            SignalingMessage::HandoffOffer { session_id, key } => {
+                Self::HandoffOffer { session_id, key }
+            }
+            SignalingMessage::HandoffJoin { session_id, token } => {
+                Self::HandoffJoin { session_id, token }
+            }
+            SignalingMessage::HandoffComplete { session_id } => {
+                Self::HandoffComplete { session_id }
+            }
+            SignalingMessage::Cancel { session_id, reason } => Self::Cancel { session_id, reason },
+            SignalingMessage::Progress {
+                session_id,
+                progress,
+            } => Self::Progress {
+                session_id,
+                progress,
+            },
+            SignalingMessage::AudioOnly { session_id, active } => {
+                Self::AudioOnly { session_id, active }
+            }
+            SignalingMessage::LayerSelection {
+                session_id,
+                track_id,
+                layer,
+            } => Self::LayerSelection {
+                session_id,
+                track_id,
+                layer,
+            },
+        }
+    }
+}
+
+impl From<Mirror> for SignalingMessage {
+    fn from(message: Mirror) -> Self {
+        match message {
+            Mirror::Offer {
+                session_id,
+                sdp,
+                quic_endpoint,
+            } => Self::Offer {
+                session_id,
+                sdp,
+                quic_endpoint,
+            },
+            Mirror::Answer {
+                session_id,
+                sdp,
+                quic_endpoint,
+            } => Self::Answer {
+                session_id,
+                sdp,
+                quic_endpoint,
+            },
+            Mirror::IceCandidate {
+                session_id,
+                candidate,
+                sdp_mid,
+                sdp_mline_index,
+            } => Self::IceCandidate {
+                session_id,
+                candidate,
+                sdp_mid,
+                sdp_mline_index,
+            },
+            Mirror::IceComplete { session_id } => Self::IceComplete { session_id },
+            Mirror::CapabilityExchange {
+                session_id,
+                audio,
+                video,
+                data_channel,
+                max_bandwidth_kbps,
+                quic_endpoint,
+            } => Self::CapabilityExchange {
+                session_id,
+                audio,
+                video,
+                data_channel,
+                max_bandwidth_kbps,
+                quic_endpoint,
+            },
+            Mirror::ConnectionConfirm {
+                session_id,
+                audio,
+                video,
+                data_channel,
+                max_bandwidth_kbps,
+                quic_endpoint,
+            } => Self::ConnectionConfirm {
+                session_id,
+                audio,
+                video,
+                data_channel,
+                max_bandwidth_kbps,
+                quic_endpoint,
+            },
+            Mirror::ConnectionReady { session_id } => Self::ConnectionReady { session_id },
+            Mirror::Bye { session_id, reason } => Self::Bye { session_id, reason },
+            Mirror::TrackUpdate { session_id, tracks } => Self::TrackUpdate { session_id, tracks },
+            Mirror::HandoffOffer { session_id, key } => Self::HandoffOffer { session_id, key },
+            Mirror::HandoffJoin { session_id, token } => Self::HandoffJoin { session_id, token },
+            Mirror::HandoffComplete { session_id } => Self::HandoffComplete { session_id },
+            Mirror::Cancel { session_id, reason } => Self::Cancel { session_id, reason },
+            Mirror::Progress {
+                session_id,
+                progress,
+            } => Self::Progress {
+                session_id,
+                progress,
+            },
+            Mirror::AudioOnly { session_id, active } => Self::AudioOnly { session_id, active },
+            Mirror::LayerSelection {
+                session_id,
+                track_id,
+                layer,
+            } => Self::LayerSelection {
+                session_id,
+                track_id,
+                layer,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::types::{MediaType, TrackSource};
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_postcard_roundtrip() {
+        let messages = [
+            SignalingMessage::CapabilityExchange {
+                session_id: "s1".to_string(),
+                audio: true,
+                video: false,
+                data_channel: true,
+                max_bandwidth_kbps: 2500,
+                quic_endpoint: Some("[::1]:9000".parse().unwrap()),
+            },
+            SignalingMessage::TrackUpdate {
+                session_id: "s2".to_string(),
+                tracks: vec![TrackInfo::new(
+                    0,
+                    MediaType::Video,
+                    TrackSource::Camera,
+                    "Front camera",
+                )],
+            },
+            SignalingMessage::LayerSelection {
+                session_id: "s3".to_string(),
+                track_id: 1,
+                layer: VideoLayer::new(2, 1),
+            },
+        ];
+        for message in messages {
+            let frame = encode_postcard(&message).unwrap();
+            assert_eq!(frame[0], TAG_POSTCARD);
+            assert_eq!(decode_postcard(&frame).unwrap(), message);
+        }
+    }
+
+    #[test]
+    fn test_decode_postcard_rejects_other_frames() {
+        assert_eq!(decode_postcard(&[]), Err(FrameError::EmptyFrame));
+        assert_eq!(decode_postcard(b"{}"), Err(FrameError::UnexpectedTag(b'{')));
+        assert!(matches!(
+            decode_postcard(&[TAG_POSTCARD, 0xFF]),
+            Err(FrameError::Decode(_))
+        ));
+    }
+}
@@ -144,7 +144,102 @@ public final class SaorsaWebRTC {
         }
         
         let result = saorsa_end_call(handle, callId)
-        if result != SAORSA_SUCCESS {
+        if result != SAORSA_RESULT_SUCCESS {
+            throw SaorsaError(result: Int32(result.rawValue))
+        }
+        #endif
+    }
+    
+    // MARK: - CallKit
+    
+    /// Register an incoming call announced by a VoIP push
+    ///
+    /// Report the call to CallKit with `reportNewIncomingCall` first. The
+    /// call rings until answered with `answerCall(callId:)` or declined with
+    /// `endCall(callId:)`. While do-not-disturb is on the call is already
+    /// `.ended`, and the call reported to CallKit should be ended too.
+    /// - Parameter peer: Identity of the caller
+    /// - Returns: Call ID string, different for every call
+    /// - Throws: SaorsaError if the call cannot be registered
+    public func receiveIncomingCall(peer: String) throws -> String {
+        guard !peer.isEmpty else {
+            throw SaorsaError.invalidParameter("Peer cannot be empty")
+        }
+        
+        #if canImport(SaorsaWebRTCFFI)
+        guard let handle = handle else {
+            throw SaorsaError.invalidHandle
+        }
+        
+        guard let callIdPtr = saorsa_receive_incoming_call(handle, peer) else {
+            throw SaorsaError.internalError
+        }
+        
+        let callId = String(cString: callIdPtr)
+        saorsa_free_string(callIdPtr)
+        return callId
+        #else
+        // Mock for testing without FFI
+        return UUID().uuidString.lowercased()
+        #endif
+    }
+    
+    /// Answer a ringing incoming call, from `CXAnswerCallAction`
+    /// - Parameter callId: Call ID from receiveIncomingCall()
+    /// - Throws: SaorsaError if the call is not ringing
+    public func answerCall(callId: String) throws {
+        guard !callId.isEmpty else {
+            throw SaorsaError.invalidParameter("Call ID cannot be empty")
+        }
+        
+        #if canImport(SaorsaWebRTCFFI)
+        guard let handle = handle else {
+            throw SaorsaError.invalidHandle
+        }
+        
+        let result = saorsa_answer_call(handle, callId)
+        if result != SAORSA_RESULT_SUCCESS {
+            throw SaorsaError(result: Int32(result.rawValue))
+        }
+        #endif
+    }
+    
+    /// Mute or unmute a call's outgoing audio, from `CXSetMutedCallAction`
+    /// - Parameters:
+    ///   - callId: Call ID of an active call
+    ///   - muted: Whether outgoing audio is muted
+    /// - Throws: SaorsaError if the call is not active
+    public func setMuted(callId: String, muted: Bool) throws {
+        guard !callId.isEmpty else {
+            throw SaorsaError.invalidParameter("Call ID cannot be empty")
+        }
+        
+        #if canImport(SaorsaWebRTCFFI)
+        guard let handle = handle else {
+            throw SaorsaError.invalidHandle
+        }
+        
+        let result = saorsa_set_muted(handle, callId, muted)
+        if result != SAORSA_RESULT_SUCCESS {
+            throw SaorsaError(result: Int32(result.rawValue))
+        }
+        #endif
+    }
+    
+    /// Tell the library whether CallKit has activated the audio session
+    ///
+    /// Forward `provider(_:didActivate:)` and `provider(_:didDeactivate:)`.
+    /// While inactive no audio is sent or played. Sessions start active.
+    /// - Parameter active: Whether the audio session is active
+    /// - Throws: SaorsaError if the service is not initialized
+    public func setAudioSessionActive(_ active: Bool) throws {
+        #if canImport(SaorsaWebRTCFFI)
+        guard let handle = handle else {
+            throw SaorsaError.invalidHandle
+        }
+        
+        let result = saorsa_set_audio_session_active(handle, active)
+        if result != SAORSA_RESULT_SUCCESS {
             throw SaorsaError(result: Int32(result.rawValue))
         }
         #endif
@@ -115,6 +115,36 @@ final class SaorsaWebRTCTests: XCTestCase {
         try service.endCall(callId: callId)
     }
     
+    // MARK: - CallKit Tests
+    
+    func testIncomingCallsFromSamePeerGetDifferentIds() throws {
+        let service = try SaorsaWebRTC(identity: "alice")
+        
+        let callId1 = try service.receiveIncomingCall(peer: "carol")
+        let callId2 = try service.receiveIncomingCall(peer: "carol")
+        
+        XCTAssertNotEqual(callId1, callId2)
+    }
+    
+    func testAnswerAndMuteIncomingCall() throws {
+        let service = try SaorsaWebRTC(identity: "alice")
+        let callId = try service.receiveIncomingCall(peer: "carol")
+        
+        try service.answerCall(callId: callId)
+        try service.setMuted(callId: callId, muted: true)
+        try service.setAudioSessionActive(false)
+        try service.setAudioSessionActive(true)
+        try service.endCall(callId: callId)
+    }
+    
+    func testCallKitMethodsRejectEmptyIds() throws {
+        let service = try SaorsaWebRTC(identity: "alice")
+        
+        XCTAssertThrowsError(try service.receiveIncomingCall(peer: ""))
+        XCTAssertThrowsError(try service.answerCall(callId: ""))
+        XCTAssertThrowsError(try service.setMuted(callId: "", muted: true))
+    }
+    
     // MARK: - Error Type Tests
     
     func testErrorEquality() {
@@ -0,0 +1,115 @@
+#!/usr/bin/env node
+// Generate webview-src/types.ts from the JSON Schemas in schemas/tauri/
+//
+// Handles the subset of JSON Schema that schemars emits for the plugin's
+// payloads. Run with --check to fail instead of writing when the file is
+// out of date, as CI does.
+
+import { readdirSync, readFileSync, writeFileSync } from 'node:fs';
+import { dirname, join } from 'node:path';
+import { fileURLToPath } from 'node:url';
+
+const root = dirname(dirname(fileURLToPath(import.meta.url)));
+const schemaDir = join(root, '..', 'schemas', 'tauri');
+const output = join(root, 'webview-src', 'types.ts');
+
+/** Declarations by type name, in the order they were first seen */
+const declarations = new Map();
+
+function docComment(description, indent) {
+  if (!description) {
+    return '';
+  }
+  const lines = description
+    .replace(/\[`([^`]+)`\]/g, '`$1`')
+    .split('\n')
+    .map((line) => (line ? `${indent} * ${line}` : `${indent} *`));
+  return `${indent}/**\n${lines.join('\n')}\n${indent} */\n`;
+}
+
+function typeOf(schema, indent) {
+  if (schema.$ref) {
+    return schema.$ref.replace('#/$defs/', '');
+  }
+  if (schema.const !== undefined) {
+    return JSON.stringify(schema.const);
+  }
+  if (schema.enum) {
+    return schema.enum.map((value) => JSON.stringify(value)).join(' | ');
+  }
+  if (schema.oneOf || schema.anyOf) {
+    return (schema.oneOf ?? schema.anyOf).map((variant) => typeOf(variant, indent)).join(' | ');
+  }
+  const types = Array.isArray(schema.type) ? schema.type : [schema.type];
+  return types.map((type) => primitive(type, schema, indent)).join(' | ');
+}
+
+function primitive(type, schema, indent) {
+  switch (type) {
+    case 'string':
+      return 'string';
+    case 'integer':
+    case 'number':
+      return 'number';
+    case 'boolean':
+      return 'boolean';
+    case 'null':
+      return 'null';
+    case 'array':
+      return `${wrap(typeOf(schema.items, indent))}[]`;
+    case 'object':
+      return objectType(schema, indent);
+    default:
+      throw new Error(`unsupported schema type ${JSON.stringify(type)}`);
+  }
+}
+
+function wrap(type) {
+  return type.includes(' ') && !type.startsWith('{') ? `(${type})` : type;
+}
+
+function objectType(schema, indent) {
+  const required = new Set(schema.required ?? []);
+  const inner = `${indent}  `;
+  const fields = Object.entries(schema.properties ?? {}).map(
+    ([name, property]) =>
+      `${docComment(property.description, inner)}${inner}${name}${required.has(name) ? '' : '?'}: ${typeOf(property, inner)};`,
+  );
+  return `{\n${fields.join('\n')}\n${indent}}`;
+}
+
+function declare(name, schema) {
+  const body = typeOf(schema, '');
+  const code = schema.type === 'object'
+    ? `${docComment(schema.description, '')}export interface ${name} ${body}\n`
+    : `${docComment(schema.description, '')}export type ${name} = ${body};\n`;
+  const existing = declarations.get(name);
+  if (existing !== undefined && existing !== code) {
+    throw new Error(`conflicting definitions of ${name}`);
+  }
+  declarations.set(name, code);
+}
+
+for (const file of readdirSync(schemaDir).filter((f) => f.endsWith('.schema.json')).sort()) {
+  const schema = JSON.parse(readFileSync(join(schemaDir, file), 'utf8'));
+  declare(schema.title, schema);
+  for (const [name, definition] of Object.entries(schema.$defs ?? {})) {
+    declare(name, definition);
+  }
+}
+
+const generated = [
+  '// Generated by scripts/generate-types.mjs from schemas/tauri/. Do not edit;',
+  '// regenerate with `npm run generate` after the schemas change.',
+  '',
+  ...[...declarations.entries()].sort(([a], [b]) => a.localeCompare(b)).map(([, code]) => code),
+].join('\n');
+
+if (process.argv.includes('--check')) {
+  if (readFileSync(output, 'utf8') !== generated) {
+    console.error(`${output} is out of date; run \`npm run generate\``);
+    process.exit(1);
+  }
+} else {
+  writeFileSync(output, generated);
+}
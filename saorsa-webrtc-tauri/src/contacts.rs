@@ -0,0 +1,106 @@
+//! Contact list commands
+//!
+//! The contact list is kept in `contactsFile` of the plugin configuration,
+//! resolved against the app data directory like `identityFile`, or in
+//! memory if none is configured.
+
+use crate::WebRtcServiceWrapper;
+use saorsa_webrtc_core::contacts::{Contact, ContactBook, ContactPermissions};
+use saorsa_webrtc_core::identity::PeerIdentityString;
+use std::sync::Arc;
+use tauri::State;
+use tokio::sync::RwLock;
+
+pub(crate) type ContactsState = Arc<RwLock<ContactBook<PeerIdentityString>>>;
+
+/// List contacts, by name
+#[tauri::command]
+pub async fn list_contacts(
+    contacts: State<'_, ContactsState>,
+) -> Result<Vec<Contact<PeerIdentityString>>, String> {
+    Ok(contacts.read().await.list().cloned().collect())
+}
+
+/// Add a contact
+#[tauri::command]
+pub async fn add_contact(
+    contacts: State<'_, ContactsState>,
+    name: String,
+    identity: String,
+    fingerprint: Option<String>,
+    notes: Option<String>,
+) -> Result<Contact<PeerIdentityString>, String> {
+    let mut contact = Contact::new(name, PeerIdentityString::new(identity));
+    contact.fingerprint = fingerprint;
+    contact.notes = notes.unwrap_or_default();
+
+    let mut book = contacts.write().await;
+    book.add(contact.clone()).map_err(|e| e.to_string())?;
+    Ok(book.get(&contact.name).cloned().unwrap_or(contact))
+}
+
+/// Change a contact's fingerprint or notes; omitted fields are kept
+#[tauri::command]
+pub async fn update_contact(
+    contacts: State<'_, ContactsState>,
+    name: String,
+    fingerprint: Option<String>,
+    notes: Option<String>,
+) -> Result<(), String> {
+    contacts
+        .write()
+        .await
+        .update(&name, |contact| {
+            if fingerprint.is_some() {
+                contact.fingerprint = fingerprint;
+            }
+            if let Some(notes) = notes {
+                contact.notes = notes;
+            }
+        })
+        .map_err(|e| e.to_string())
+}
+
+/// Set what a contact may do
+#[tauri::command]
+pub async fn set_contact_permissions(
+    contacts: State<'_, ContactsState>,
+    name: String,
+    permissions: ContactPermissions,
+) -> Result<(), String> {
+    contacts
+        .write()
+        .await
+        .set_permissions(&name, permissions)
+        .map_err(|e| e.to_string())
+}
+
+/// Remove a contact
+#[tauri::command]
+pub async fn remove_contact(
+    contacts: State<'_, ContactsState>,
+    name: String,
+) -> Result<(), String> {
+    contacts
+        .write()
+        .await
+        .remove(&name)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Call a contact by name
+#[tauri::command]
+pub async fn call_contact(
+    state: State<'_, WebRtcServiceWrapper>,
+    contacts: State<'_, ContactsState>,
+    name: String,
+) -> Result<String, String> {
+    let peer = contacts
+        .read()
+        .await
+        .get(&name)
+        .map(|contact| contact.identity.as_str().to_string())
+        .ok_or_else(|| format!("Contact not found: {name}"))?;
+    crate::call(state, peer).await
+}
@@ -0,0 +1,80 @@
+//! Persisted identity and auto-start
+//!
+//! Configured in `tauri.conf.json` under `plugins > saorsa-webrtc`, or with
+//! [`init_with_config`](crate::init_with_config):
+//!
+//! ```json
+//! { "plugins": { "saorsa-webrtc": { "identityFile": "identity", "autoStart": true, "contactsFile": "contacts.json" } } }
+//! ```
+//!
+//! A relative `identityFile` is resolved against the app data directory.
+//! With `autoStart` the service starts on launch using the stored identity,
+//! generating and storing a new one on first run.
+
+use serde::Deserialize;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Runtime};
+use tokio::sync::RwLock;
+
+/// Plugin configuration
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginConfig {
+    /// File the identity is stored in
+    #[serde(default)]
+    pub identity_file: Option<PathBuf>,
+    /// Start the service on app launch
+    #[serde(default)]
+    pub auto_start: bool,
+    /// File the contact list is kept in
+    #[serde(default)]
+    pub contacts_file: Option<PathBuf>,
+}
+
+/// Identity of the running service and where it is persisted
+#[derive(Debug, Default)]
+pub(crate) struct IdentityStore {
+    /// Resolved identity file, if configured
+    pub(crate) path: Option<PathBuf>,
+    /// Identity the service was started with
+    pub(crate) current: Option<String>,
+}
+
+pub(crate) type IdentityState = Arc<RwLock<IdentityStore>>;
+
+/// Resolve the configured identity file against the app data directory
+pub(crate) fn resolve_path<R: Runtime>(app: &AppHandle<R>, file: &Path) -> Option<PathBuf> {
+    if file.is_absolute() {
+        return Some(file.to_path_buf());
+    }
+    app.path_resolver().app_data_dir().map(|dir| dir.join(file))
+}
+
+/// Read a stored identity; `None` if the file is missing or blank
+pub(crate) fn load(path: &Path) -> io::Result<Option<String>> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => {
+            let identity = contents.trim();
+            Ok((!identity.is_empty()).then(|| identity.to_string()))
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Store an identity, replacing the file atomically
+pub(crate) fn store(path: &Path, identity: &str) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, format!("{identity}\n"))?;
+    std::fs::rename(tmp, path)
+}
+
+/// Generate a fresh identity
+pub(crate) fn generate() -> String {
+    format!("peer-{}", uuid::Uuid::new_v4().simple())
+}
@@ -5,19 +5,35 @@
 #![deny(clippy::expect_used)]
 
 use saorsa_webrtc_core::{
+    contacts::ContactBook,
+    dnd::{MissedCall, QuietHours},
+    health::HealthReport,
     identity::PeerIdentityString,
+    invite::CallInvite,
     service::{WebRtcConfig, WebRtcService},
     signaling::SignalingHandler,
+    stats_history::{StatsDelta, StatsSample},
     types::{CallId, CallState, MediaConstraints},
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tauri::{
     plugin::{Builder, TauriPlugin},
-    Manager, Runtime, State,
+    AppHandle, Manager, Runtime, State,
 };
 use tokio::sync::RwLock;
 
+mod contacts;
+pub mod identity;
+#[cfg(feature = "notifications")]
+pub mod notifications;
+pub mod permissions;
+
+use contacts::ContactsState;
+pub use identity::PluginConfig;
+use identity::{IdentityState, IdentityStore};
+use permissions::{MediaDevice, MediaPermissions};
+
 type WebRtcServiceWrapper = Arc<RwLock<Option<WebRtcService<PeerIdentityString, MockTransport>>>>;
 
 #[allow(dead_code)]
@@ -38,21 +54,57 @@ struct CallRequest {
     screen_share: bool,
 }
 
+/// Peer and media parsed from an invitation link
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+struct InviteDetails {
+    peer: String,
+    audio: bool,
+    video: bool,
+    screen_share: bool,
+}
+
 #[allow(dead_code)]
 fn default_audio_only() -> bool {
     true
 }
 
 /// Initialize the WebRTC service
+///
+/// The identity is persisted if an identity file is configured.
 #[tauri::command]
-async fn initialize(
-    state: State<'_, WebRtcServiceWrapper>,
-    identity: String,
-) -> Result<(), String> {
+async fn initialize<R: Runtime>(app: AppHandle<R>, identity: String) -> Result<(), String> {
     if identity.is_empty() {
         return Err("Identity cannot be empty".to_string());
     }
 
+    start_service(&app, identity).await
+}
+
+/// Get the identity the service is running with
+#[tauri::command]
+async fn get_identity(identity: State<'_, IdentityState>) -> Result<Option<String>, String> {
+    Ok(identity.read().await.current.clone())
+}
+
+/// Replace the identity with a freshly generated one and restart the service
+///
+/// Active calls are dropped with the old service.
+#[tauri::command]
+async fn rotate_identity<R: Runtime>(app: AppHandle<R>) -> Result<String, String> {
+    let identity = identity::generate();
+    start_service(&app, identity.clone()).await?;
+    Ok(identity)
+}
+
+/// Start (or restart) the service with an identity, persisting it
+async fn start_service<R: Runtime>(app: &AppHandle<R>, identity: String) -> Result<(), String> {
+    let identity_state = app.state::<IdentityState>();
+    let mut stored = identity_state.write().await;
+    if let Some(path) = &stored.path {
+        identity::store(path, &identity).map_err(|e| format!("Failed to store identity: {e}"))?;
+    }
+
     let transport = Arc::new(MockTransport::new());
     let signaling = Arc::new(SignalingHandler::new(transport));
 
@@ -67,11 +119,25 @@ async fn initialize(
         .await
         .map_err(|e| format!("Failed to start service: {e}"))?;
 
-    *state.write().await = Some(service);
+    #[cfg(feature = "notifications")]
+    notifications::spawn_listener(app.clone(), service.subscribe_call_events());
+
+    *app.state::<WebRtcServiceWrapper>().write().await = Some(service);
+    stored.current = Some(identity);
 
     Ok(())
 }
 
+/// Start the service on launch with the stored (or a new) identity
+async fn auto_start<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let path = app.state::<IdentityState>().read().await.path.clone();
+    let stored = match &path {
+        Some(path) => identity::load(path).map_err(|e| format!("Failed to load identity: {e}"))?,
+        None => None,
+    };
+    start_service(app, stored.unwrap_or_else(identity::generate)).await
+}
+
 /// Initiate a call to a peer
 #[tauri::command]
 async fn call(state: State<'_, WebRtcServiceWrapper>, peer: String) -> Result<String, String> {
@@ -84,6 +150,8 @@ async fn call(state: State<'_, WebRtcServiceWrapper>, peer: String) -> Result<St
         .as_ref()
         .ok_or_else(|| "Service not initialized".to_string())?;
 
+    permissions::ensure(true, false).await?;
+
     let peer_identity = PeerIdentityString::new(peer);
     let constraints = MediaConstraints::audio_only();
 
@@ -92,6 +160,9 @@ async fn call(state: State<'_, WebRtcServiceWrapper>, peer: String) -> Result<St
         .await
         .map_err(|e| format!("Failed to initiate call: {e}"))?;
 
+    // Stats history feeds charts only; the call proceeds without it
+    let _ = service.start_stats_history(call_id).await;
+
     Ok(call_id.to_string())
 }
 
@@ -113,11 +184,14 @@ async fn call_with_constraints(
         .as_ref()
         .ok_or_else(|| "Service not initialized".to_string())?;
 
+    permissions::ensure(audio, video).await?;
+
     let peer_identity = PeerIdentityString::new(peer);
     let constraints = MediaConstraints {
         audio,
         video,
         screen_share,
+        latency: None,
     };
 
     let call_id = service
@@ -125,9 +199,74 @@ async fn call_with_constraints(
         .await
         .map_err(|e| format!("Failed to initiate call: {e}"))?;
 
+    // Stats history feeds charts only; the call proceeds without it
+    let _ = service.start_stats_history(call_id).await;
+
     Ok(call_id.to_string())
 }
 
+/// Create a `saorsa://call` invitation link for the running identity
+#[tauri::command]
+async fn create_invite(
+    identity: State<'_, IdentityState>,
+    audio: bool,
+    video: bool,
+    screen_share: bool,
+) -> Result<String, String> {
+    let current = identity
+        .read()
+        .await
+        .current
+        .clone()
+        .ok_or_else(|| "Service not initialized".to_string())?;
+
+    let constraints = MediaConstraints {
+        audio,
+        video,
+        screen_share,
+        latency: None,
+    };
+    Ok(CallInvite::new(current, constraints).to_uri())
+}
+
+/// Parse a `saorsa://call` invitation link
+///
+/// The result can be passed to `call_with_constraints` to join the call.
+#[tauri::command]
+async fn parse_invite(uri: String) -> Result<InviteDetails, String> {
+    let invite = CallInvite::parse(&uri).map_err(|e| e.to_string())?;
+    Ok(InviteDetails {
+        peer: invite.peer,
+        audio: invite.constraints.audio,
+        video: invite.constraints.video,
+        screen_share: invite.constraints.screen_share,
+    })
+}
+
+/// Check microphone and camera permissions without prompting
+#[tauri::command]
+async fn check_media_permissions() -> Result<MediaPermissions, String> {
+    Ok(permissions::check_all().await)
+}
+
+/// Request microphone and/or camera permissions, prompting if undecided
+///
+/// Devices that are not requested are only checked.
+#[tauri::command]
+async fn request_media_permissions(audio: bool, video: bool) -> Result<MediaPermissions, String> {
+    let microphone = if audio {
+        permissions::request(MediaDevice::Microphone).await
+    } else {
+        permissions::check(MediaDevice::Microphone).await
+    };
+    let camera = if video {
+        permissions::request(MediaDevice::Camera).await
+    } else {
+        permissions::check(MediaDevice::Camera).await
+    };
+    Ok(MediaPermissions { microphone, camera })
+}
+
 /// Get the state of a call
 #[tauri::command]
 async fn get_call_state(
@@ -139,17 +278,181 @@ async fn get_call_state(
         .as_ref()
         .ok_or_else(|| "Service not initialized".to_string())?;
 
-    let call_id_uuid =
-        uuid::Uuid::parse_str(&call_id).map_err(|e| format!("Invalid call ID: {e}"))?;
+    let call_id = resolve(service, &call_id).await?;
 
     let call_state = service
-        .get_call_state(CallId(call_id_uuid))
+        .get_call_state(call_id)
         .await
         .ok_or_else(|| "Call not found".to_string())?;
 
     Ok(call_state_to_string(call_state))
 }
 
+/// Get the short code of a call, to show in place of its ID
+#[tauri::command]
+async fn get_call_short_code(
+    state: State<'_, WebRtcServiceWrapper>,
+    call_id: String,
+) -> Result<String, String> {
+    let service_guard = state.read().await;
+    let service = service_guard
+        .as_ref()
+        .ok_or_else(|| "Service not initialized".to_string())?;
+
+    Ok(resolve(service, &call_id).await?.short_code().to_string())
+}
+
+/// Get the per-second statistics samples of a call, oldest first
+///
+/// Also available for ended calls until their history is evicted.
+#[tauri::command]
+async fn get_call_stats_history(
+    state: State<'_, WebRtcServiceWrapper>,
+    call_id: String,
+) -> Result<Vec<StatsSample>, String> {
+    let service_guard = state.read().await;
+    let service = service_guard
+        .as_ref()
+        .ok_or_else(|| "Service not initialized".to_string())?;
+
+    let call_id = resolve(service, &call_id).await?;
+
+    service
+        .get_stats_history(call_id)
+        .ok_or_else(|| "No stats history for call".to_string())
+}
+
+/// Frontend event carrying a [`StatsDeltaPayload`]
+pub const STATS_DELTA_EVENT: &str = "saorsa-webrtc://stats-delta";
+
+/// Payload of [`STATS_DELTA_EVENT`]
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+struct StatsDeltaPayload {
+    call_id: String,
+    /// Milliseconds since the subscription started
+    at_ms: u64,
+    /// Milliseconds since the previous delta of this call
+    elapsed_ms: u64,
+    packets_sent: u64,
+    packets_received: u64,
+    bytes_sent: u64,
+    bytes_received: u64,
+    stream_errors: u64,
+}
+
+impl StatsDeltaPayload {
+    fn new(call_id: CallId, delta: &StatsDelta) -> Self {
+        let millis = |d: std::time::Duration| u64::try_from(d.as_millis()).unwrap_or(u64::MAX);
+        Self {
+            call_id: call_id.to_string(),
+            at_ms: millis(delta.at),
+            elapsed_ms: millis(delta.elapsed),
+            packets_sent: delta.packets_sent,
+            packets_received: delta.packets_received,
+            bytes_sent: delta.bytes_sent,
+            bytes_received: delta.bytes_received,
+            stream_errors: delta.stream_errors,
+        }
+    }
+}
+
+/// Emit [`STATS_DELTA_EVENT`] with a call's statistics changes every
+/// `interval_ms` until the call ends
+#[tauri::command]
+async fn subscribe_call_stats<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, WebRtcServiceWrapper>,
+    call_id: String,
+    interval_ms: u64,
+) -> Result<(), String> {
+    let service_guard = state.read().await;
+    let service = service_guard
+        .as_ref()
+        .ok_or_else(|| "Service not initialized".to_string())?;
+
+    let call_id = resolve(service, &call_id).await?;
+
+    let mut deltas = service
+        .subscribe_stats(call_id, std::time::Duration::from_millis(interval_ms))
+        .await
+        .map_err(|e| format!("Failed to subscribe to stats: {e}"))?;
+    tauri::async_runtime::spawn(async move {
+        while let Some(delta) = deltas.recv().await {
+            let _ = app.emit_all(STATS_DELTA_EVENT, StatsDeltaPayload::new(call_id, &delta));
+        }
+    });
+    Ok(())
+}
+
+/// Turn do-not-disturb on or off
+///
+/// While on, incoming calls do not ring and are listed by `get_missed_calls`.
+#[tauri::command]
+async fn set_do_not_disturb(
+    state: State<'_, WebRtcServiceWrapper>,
+    enabled: bool,
+) -> Result<(), String> {
+    let service_guard = state.read().await;
+    let service = service_guard
+        .as_ref()
+        .ok_or_else(|| "Service not initialized".to_string())?;
+
+    service.set_do_not_disturb(enabled);
+    Ok(())
+}
+
+/// Set the daily quiet hours during which do-not-disturb is on
+///
+/// Each range is `HH:MM-HH:MM` in the user's local time, which is
+/// `utc_offset_minutes` east of UTC (the negated JavaScript
+/// `Date.getTimezoneOffset()`). An empty list removes the quiet hours.
+#[tauri::command]
+async fn set_quiet_hours(
+    state: State<'_, WebRtcServiceWrapper>,
+    hours: Vec<String>,
+    utc_offset_minutes: i32,
+) -> Result<(), String> {
+    let service_guard = state.read().await;
+    let service = service_guard
+        .as_ref()
+        .ok_or_else(|| "Service not initialized".to_string())?;
+
+    let quiet_hours = hours
+        .iter()
+        .map(|range| range.parse::<QuietHours>().map_err(|e| e.to_string()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut config = service.dnd().config();
+    config.quiet_hours = quiet_hours;
+    config.utc_offset_minutes = utc_offset_minutes;
+    service.dnd().set_config(config);
+    Ok(())
+}
+
+/// Get the calls held back by do-not-disturb, oldest first
+#[tauri::command]
+async fn get_missed_calls(
+    state: State<'_, WebRtcServiceWrapper>,
+) -> Result<Vec<MissedCall<PeerIdentityString>>, String> {
+    let service_guard = state.read().await;
+    let service = service_guard
+        .as_ref()
+        .ok_or_else(|| "Service not initialized".to_string())?;
+
+    Ok(service.missed_calls())
+}
+
+/// Get a health report of the service for diagnostics screens
+#[tauri::command]
+async fn get_health(state: State<'_, WebRtcServiceWrapper>) -> Result<HealthReport, String> {
+    let service_guard = state.read().await;
+    let service = service_guard
+        .as_ref()
+        .ok_or_else(|| "Service not initialized".to_string())?;
+
+    Ok(service.health().await)
+}
+
 /// End a call
 #[tauri::command]
 async fn end_call(state: State<'_, WebRtcServiceWrapper>, call_id: String) -> Result<(), String> {
@@ -158,11 +461,10 @@ async fn end_call(state: State<'_, WebRtcServiceWrapper>, call_id: String) -> Re
         .as_ref()
         .ok_or_else(|| "Service not initialized".to_string())?;
 
-    let call_id_uuid =
-        uuid::Uuid::parse_str(&call_id).map_err(|e| format!("Invalid call ID: {e}"))?;
+    let call_id = resolve(service, &call_id).await?;
 
     service
-        .end_call(CallId(call_id_uuid))
+        .end_call(call_id)
         .await
         .map_err(|e| format!("Failed to end call: {e}"))?;
 
@@ -175,70 +477,280 @@ async fn accept_call(
     state: State<'_, WebRtcServiceWrapper>,
     call_id: String,
 ) -> Result<(), String> {
+    let call_id = {
+        let service_guard = state.read().await;
+        let service = service_guard
+            .as_ref()
+            .ok_or_else(|| "Service not initialized".to_string())?;
+        resolve(service, &call_id).await?
+    };
+    accept(&state, call_id).await
+}
+
+/// Reject an incoming call
+#[tauri::command]
+async fn reject_call(
+    state: State<'_, WebRtcServiceWrapper>,
+    call_id: String,
+) -> Result<(), String> {
+    let call_id = {
+        let service_guard = state.read().await;
+        let service = service_guard
+            .as_ref()
+            .ok_or_else(|| "Service not initialized".to_string())?;
+        resolve(service, &call_id).await?
+    };
+    reject(&state, call_id).await
+}
+
+/// Put a connected call on hold
+#[tauri::command]
+async fn hold_call(state: State<'_, WebRtcServiceWrapper>, call_id: String) -> Result<(), String> {
     let service_guard = state.read().await;
     let service = service_guard
         .as_ref()
         .ok_or_else(|| "Service not initialized".to_string())?;
 
-    let call_id_uuid =
-        uuid::Uuid::parse_str(&call_id).map_err(|e| format!("Invalid call ID: {e}"))?;
+    let call_id = resolve(service, &call_id).await?;
 
     service
-        .accept_call(CallId(call_id_uuid), MediaConstraints::audio_only())
+        .hold_call(call_id)
         .await
-        .map_err(|e| format!("Failed to accept call: {e}"))?;
+        .map_err(|e| format!("Failed to hold call: {e}"))
+}
 
-    Ok(())
+/// Attach a metadata value to a call, e.g. a support ticket ID
+///
+/// Metadata is included in call events, statistics histories, the audit
+/// log and webhooks.
+#[tauri::command]
+async fn set_call_metadata(
+    state: State<'_, WebRtcServiceWrapper>,
+    call_id: String,
+    key: String,
+    value: String,
+) -> Result<(), String> {
+    let service_guard = state.read().await;
+    let service = service_guard
+        .as_ref()
+        .ok_or_else(|| "Service not initialized".to_string())?;
+
+    let call_id = resolve(service, &call_id).await?;
+
+    service
+        .set_call_metadata(call_id, &key, &value)
+        .await
+        .map_err(|e| format!("Failed to set call metadata: {e}"))
 }
 
-/// Reject an incoming call
+/// Take a call off hold
 #[tauri::command]
-async fn reject_call(
+async fn resume_call(
+    state: State<'_, WebRtcServiceWrapper>,
+    call_id: String,
+) -> Result<(), String> {
+    let service_guard = state.read().await;
+    let service = service_guard
+        .as_ref()
+        .ok_or_else(|| "Service not initialized".to_string())?;
+
+    let call_id = resolve(service, &call_id).await?;
+
+    service
+        .resume_call(call_id)
+        .await
+        .map_err(|e| format!("Failed to resume call: {e}"))
+}
+
+/// Decline a waiting call, telling the caller we are busy
+#[tauri::command]
+async fn reject_busy(
     state: State<'_, WebRtcServiceWrapper>,
     call_id: String,
+    caller: String,
 ) -> Result<(), String> {
     let service_guard = state.read().await;
     let service = service_guard
         .as_ref()
         .ok_or_else(|| "Service not initialized".to_string())?;
 
-    let call_id_uuid =
-        uuid::Uuid::parse_str(&call_id).map_err(|e| format!("Invalid call ID: {e}"))?;
+    let call_id = resolve(service, &call_id).await?;
+
+    service
+        .reject_busy(call_id, &PeerIdentityString::new(caller))
+        .await
+        .map_err(|e| format!("Failed to reject call: {e}"))
+}
+
+/// Accept a call with audio, after checking microphone permission
+///
+/// A call we are already on is put on hold.
+async fn accept(state: &WebRtcServiceWrapper, call_id: CallId) -> Result<(), String> {
+    let service_guard = state.read().await;
+    let service = service_guard
+        .as_ref()
+        .ok_or_else(|| "Service not initialized".to_string())?;
+
+    permissions::ensure(true, false).await?;
+
+    service
+        .answer_waiting_call(call_id, MediaConstraints::audio_only())
+        .await
+        .map_err(|e| format!("Failed to accept call: {e}"))?;
+
+    let _ = service.start_stats_history(call_id).await;
+
+    Ok(())
+}
+
+/// Reject a call
+async fn reject(state: &WebRtcServiceWrapper, call_id: CallId) -> Result<(), String> {
+    let service_guard = state.read().await;
+    let service = service_guard
+        .as_ref()
+        .ok_or_else(|| "Service not initialized".to_string())?;
 
     service
-        .reject_call(CallId(call_id_uuid))
+        .reject_call(call_id)
         .await
         .map_err(|e| format!("Failed to reject call: {e}"))?;
 
     Ok(())
 }
 
+/// Resolve a call ID or short code passed by the frontend
+async fn resolve(
+    service: &WebRtcService<PeerIdentityString, MockTransport>,
+    call_id: &str,
+) -> Result<CallId, String> {
+    service
+        .resolve_call_id(call_id)
+        .await
+        .map_err(|e| format!("Invalid call ID: {e}"))
+}
+
 fn call_state_to_string(state: CallState) -> String {
     match state {
         CallState::Idle => "idle".to_string(),
         CallState::Calling => "calling".to_string(),
         CallState::Connecting => "connecting".to_string(),
         CallState::Connected => "connected".to_string(),
+        CallState::Reconnecting => "reconnecting".to_string(),
         CallState::Ending => "ending".to_string(),
         CallState::Failed => "failed".to_string(),
     }
 }
 
-pub fn init<R: Runtime>() -> TauriPlugin<R> {
+/// JSON Schemas of the command results and event payloads, named for
+/// publishing under `schemas/tauri/`
+///
+/// Commands that return a list return arrays of the listed types. The
+/// payloads of the incoming call events are included with the
+/// `notifications` feature.
+#[cfg(feature = "schema")]
+#[must_use]
+pub fn schemas() -> Vec<saorsa_webrtc_core::schema::NamedSchema> {
+    use saorsa_webrtc_core::contacts::Contact;
+    use schemars::schema_for;
+
+    let schemas = vec![
+        ("tauri/invite-details", schema_for!(InviteDetails)),
+        ("tauri/media-permissions", schema_for!(MediaPermissions)),
+        ("tauri/stats-sample", schema_for!(StatsSample)),
+        ("tauri/stats-delta", schema_for!(StatsDeltaPayload)),
+        (
+            "tauri/missed-call",
+            schema_for!(MissedCall<PeerIdentityString>),
+        ),
+        ("tauri/contact", schema_for!(Contact<PeerIdentityString>)),
+        ("tauri/health-report", schema_for!(HealthReport)),
+    ];
+    #[cfg(feature = "notifications")]
+    let schemas = [schemas, notifications::schemas()].concat();
+    schemas
+}
+
+/// Create the plugin, configured from `tauri.conf.json`
+pub fn init<R: Runtime>() -> TauriPlugin<R, Option<PluginConfig>> {
+    build(None)
+}
+
+/// Create the plugin with explicit configuration, ignoring `tauri.conf.json`
+pub fn init_with_config<R: Runtime>(config: PluginConfig) -> TauriPlugin<R, Option<PluginConfig>> {
+    build(Some(config))
+}
+
+fn build<R: Runtime>(
+    config_override: Option<PluginConfig>,
+) -> TauriPlugin<R, Option<PluginConfig>> {
     let service_wrapper: WebRtcServiceWrapper = Arc::new(RwLock::new(None));
 
-    Builder::new("saorsa-webrtc")
+    Builder::<R, Option<PluginConfig>>::new("saorsa-webrtc")
         .invoke_handler(tauri::generate_handler![
             initialize,
+            get_identity,
+            rotate_identity,
             call,
             call_with_constraints,
+            create_invite,
+            parse_invite,
+            check_media_permissions,
+            request_media_permissions,
             get_call_state,
+            get_call_short_code,
+            get_call_stats_history,
+            subscribe_call_stats,
+            set_do_not_disturb,
+            set_quiet_hours,
+            get_missed_calls,
+            get_health,
+            contacts::list_contacts,
+            contacts::add_contact,
+            contacts::update_contact,
+            contacts::set_contact_permissions,
+            contacts::remove_contact,
+            contacts::call_contact,
             end_call,
             accept_call,
             reject_call,
+            reject_busy,
+            hold_call,
+            resume_call,
+            set_call_metadata,
         ])
-        .setup(move |app_handle| {
+        .setup_with_config(move |app_handle, config| {
+            let config = config_override.or(config).unwrap_or_default();
+            let path = config
+                .identity_file
+                .as_deref()
+                .and_then(|file| identity::resolve_path(app_handle, file));
+
+            let contacts = match config
+                .contacts_file
+                .as_deref()
+                .and_then(|file| identity::resolve_path(app_handle, file))
+            {
+                Some(path) => ContactBook::open(path)?,
+                None => ContactBook::new(),
+            };
+
             app_handle.manage(service_wrapper.clone());
+            app_handle.manage(ContactsState::new(RwLock::new(contacts)));
+            app_handle.manage(IdentityState::new(RwLock::new(IdentityStore {
+                path,
+                current: None,
+            })));
+            #[cfg(feature = "notifications")]
+            app_handle.manage(notifications::RingingCall::default());
+
+            if config.auto_start {
+                let app = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = auto_start(&app).await {
+                        eprintln!("saorsa-webrtc: auto-start failed: {e}");
+                    }
+                });
+            }
             Ok(())
         })
         .build()
@@ -309,6 +821,42 @@ impl SignalingTransport for MockTransport {
 mod tests {
     use super::*;
 
+    /// Published in `schemas/tauri/` at the repository root; regenerate with
+    /// `SAORSA_UPDATE_SCHEMAS=1 cargo test -p saorsa-webrtc-tauri --features schema`
+    #[cfg(feature = "schema")]
+    #[test]
+    fn test_published_schemas_are_current() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../schemas");
+        let stale = saorsa_webrtc_core::schema::check_published(&dir, &schemas());
+        assert_eq!(stale.ok(), Some(Vec::new()));
+    }
+
+    /// Every registered command and emitted event has a wrapper in the
+    /// TypeScript client
+    #[test]
+    fn test_typescript_client_covers_commands() {
+        let client = include_str!("../webview-src/index.ts");
+        let source = include_str!("lib.rs");
+        // The first occurrence is the plugin's handler list, above this test
+        let start = source.find("generate_handler![").unwrap_or_default();
+        let handlers = &source[start..];
+        let handlers = &handlers[..handlers.find(']').unwrap_or_default()];
+
+        let commands: Vec<&str> = handlers
+            .split(',')
+            .filter_map(|path| path.trim().rsplit("::").next())
+            .filter(|name| !name.is_empty() && !name.starts_with("generate_handler"))
+            .collect();
+        assert!(commands.len() > 20);
+        for command in commands {
+            assert!(
+                client.contains(&format!("'plugin:saorsa-webrtc|{command}'")),
+                "{command} has no wrapper in webview-src/index.ts"
+            );
+        }
+        assert!(client.contains(&format!("'{STATS_DELTA_EVENT}'")));
+    }
+
     #[tokio::test]
     async fn test_service_integration() {
         let transport = Arc::new(MockTransport::new());
@@ -352,6 +900,10 @@ mod tests {
         assert_eq!(call_state_to_string(CallState::Calling), "calling");
         assert_eq!(call_state_to_string(CallState::Connecting), "connecting");
         assert_eq!(call_state_to_string(CallState::Connected), "connected");
+        assert_eq!(
+            call_state_to_string(CallState::Reconnecting),
+            "reconnecting"
+        );
         assert_eq!(call_state_to_string(CallState::Ending), "ending");
         assert_eq!(call_state_to_string(CallState::Failed), "failed");
     }
@@ -0,0 +1,219 @@
+//! Native notifications and tray integration for incoming calls
+//!
+//! Enabled with the `notifications` feature. When a call comes in the plugin
+//! shows a native notification, emits [`INCOMING_CALL_EVENT`] (or
+//! [`CALL_WAITING_EVENT`] while on another call) to the frontend and, if the app has a tray created with [`TRAY_ID`], updates its
+//! tooltip and enables the Accept/Decline items added by
+//! [`with_call_items`]. This works while the window is hidden.
+//!
+//! Tauri 1 notifications cannot carry action buttons, so Accept/Decline are
+//! tray menu items. The app forwards its tray events to
+//! [`handle_tray_event`]:
+//!
+//! ```ignore
+//! use saorsa_webrtc_tauri::notifications;
+//!
+//! tauri::Builder::default()
+//!     .plugin(saorsa_webrtc_tauri::init())
+//!     .system_tray(
+//!         SystemTray::new()
+//!             .with_id(notifications::TRAY_ID)
+//!             .with_menu(notifications::with_call_items(SystemTrayMenu::new())),
+//!     )
+//!     .on_system_tray_event(|app, event| {
+//!         notifications::handle_tray_event(app, &event);
+//!     })
+//! ```
+
+use crate::WebRtcServiceWrapper;
+use saorsa_webrtc_core::{
+    identity::{PeerIdentity, PeerIdentityString},
+    types::{CallEvent, CallId},
+};
+use serde::Serialize;
+use std::sync::Arc;
+use tauri::{
+    api::notification::Notification, AppHandle, CustomMenuItem, Manager, Runtime, SystemTrayEvent,
+    SystemTrayMenu,
+};
+use tokio::sync::{broadcast, Mutex};
+
+/// Id the app must give its tray for the plugin to update it
+pub const TRAY_ID: &str = "saorsa-webrtc";
+
+/// Tray menu item accepting the ringing call
+pub const TRAY_ACCEPT_ID: &str = "saorsa-webrtc-accept";
+
+/// Tray menu item declining the ringing call
+pub const TRAY_DECLINE_ID: &str = "saorsa-webrtc-decline";
+
+/// Frontend event emitted for each incoming call
+pub const INCOMING_CALL_EVENT: &str = "saorsa-webrtc://incoming-call";
+
+/// Frontend event emitted for each call arriving while we are on a call
+pub const CALL_WAITING_EVENT: &str = "saorsa-webrtc://call-waiting";
+
+/// Tray tooltip while no call is ringing
+const IDLE_TOOLTIP: &str = "No incoming call";
+
+/// Call currently ringing, if any
+pub(crate) type RingingCall = Arc<Mutex<Option<CallId>>>;
+
+/// Payload of [`INCOMING_CALL_EVENT`]
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+struct IncomingCallPayload {
+    call_id: String,
+    caller: String,
+}
+
+/// Payload of [`CALL_WAITING_EVENT`]
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+struct CallWaitingPayload {
+    call_id: String,
+    caller: String,
+    active: String,
+}
+
+/// Schemas of the event payloads, for [`crate::schemas`]
+#[cfg(feature = "schema")]
+pub(crate) fn schemas() -> Vec<saorsa_webrtc_core::schema::NamedSchema> {
+    vec![
+        (
+            "tauri/incoming-call",
+            schemars::schema_for!(IncomingCallPayload),
+        ),
+        (
+            "tauri/call-waiting",
+            schemars::schema_for!(CallWaitingPayload),
+        ),
+    ]
+}
+
+/// Append the Accept/Decline items to a tray menu
+///
+/// Both items start disabled and are enabled while a call rings.
+#[must_use]
+pub fn with_call_items(menu: SystemTrayMenu) -> SystemTrayMenu {
+    menu.add_item(CustomMenuItem::new(TRAY_ACCEPT_ID, "Accept call").disabled())
+        .add_item(CustomMenuItem::new(TRAY_DECLINE_ID, "Decline call").disabled())
+}
+
+/// Handle a tray event from the app's `on_system_tray_event`
+///
+/// Returns `true` if the event was one of the plugin's items.
+pub fn handle_tray_event<R: Runtime>(app: &AppHandle<R>, event: &SystemTrayEvent) -> bool {
+    let SystemTrayEvent::MenuItemClick { id, .. } = event else {
+        return false;
+    };
+    let accept = match id.as_str() {
+        TRAY_ACCEPT_ID => true,
+        TRAY_DECLINE_ID => false,
+        _ => return false,
+    };
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let Some(call_id) = app.state::<RingingCall>().lock().await.take() else {
+            return;
+        };
+        let service = app.state::<WebRtcServiceWrapper>();
+        let result = if accept {
+            crate::accept(&service, call_id).await
+        } else {
+            crate::reject(&service, call_id).await
+        };
+        if let Err(e) = result {
+            eprintln!("saorsa-webrtc: tray action failed: {e}");
+        }
+        update_tray(&app, None);
+    });
+    true
+}
+
+/// Watch call events and surface incoming calls natively
+pub(crate) fn spawn_listener<R: Runtime>(
+    app: AppHandle<R>,
+    mut events: broadcast::Receiver<CallEvent<PeerIdentityString>>,
+) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+            let ringing = app.state::<RingingCall>();
+            match event {
+                CallEvent::IncomingCall { offer } => {
+                    let caller = offer.caller.to_string_repr();
+                    *ringing.lock().await = Some(offer.call_id);
+                    notify(&app, &caller);
+                    update_tray(&app, Some(&caller));
+                    let _ = app.emit_all(
+                        INCOMING_CALL_EVENT,
+                        IncomingCallPayload {
+                            call_id: offer.call_id.to_string(),
+                            caller,
+                        },
+                    );
+                }
+                CallEvent::CallWaiting { offer, active } => {
+                    let caller = offer.caller.to_string_repr();
+                    *ringing.lock().await = Some(offer.call_id);
+                    notify(&app, &caller);
+                    update_tray(&app, Some(&caller));
+                    let _ = app.emit_all(
+                        CALL_WAITING_EVENT,
+                        CallWaitingPayload {
+                            call_id: offer.call_id.to_string(),
+                            caller,
+                            active: active.to_string(),
+                        },
+                    );
+                }
+                CallEvent::CallAccepted { call_id, .. }
+                | CallEvent::CallRejected { call_id }
+                | CallEvent::CallEnded { call_id }
+                | CallEvent::ConnectionEstablished { call_id }
+                | CallEvent::ConnectionFailed { call_id, .. } => {
+                    let mut ringing = ringing.lock().await;
+                    if *ringing == Some(call_id) {
+                        *ringing = None;
+                        update_tray(&app, None);
+                    }
+                }
+                _ => {}
+            }
+        }
+    });
+}
+
+fn notify<R: Runtime>(app: &AppHandle<R>, caller: &str) {
+    let identifier = app.config().tauri.bundle.identifier.clone();
+    if let Err(e) = Notification::new(identifier)
+        .title("Incoming call")
+        .body(format!("{caller} is calling"))
+        .show()
+    {
+        eprintln!("saorsa-webrtc: failed to show notification: {e}");
+    }
+}
+
+/// Reflect the ringing caller (or none) in the tray, if the app has one
+fn update_tray<R: Runtime>(app: &AppHandle<R>, caller: Option<&str>) {
+    let Some(tray) = app.tray_handle_by_id(TRAY_ID) else {
+        return;
+    };
+    let tooltip = caller.map_or_else(
+        || IDLE_TOOLTIP.to_string(),
+        |caller| format!("Incoming call from {caller}"),
+    );
+    let _ = tray.set_tooltip(&tooltip);
+    for id in [TRAY_ACCEPT_ID, TRAY_DECLINE_ID] {
+        if let Some(item) = tray.try_get_item(id) {
+            let _ = item.set_enabled(caller.is_some());
+        }
+    }
+}
@@ -0,0 +1,312 @@
+//! Microphone and camera permission brokering
+//!
+//! Capture fails deep in the media stack when the OS has not granted access,
+//! so the plugin checks (and where possible requests) permissions up front
+//! and reports structured states to the frontend:
+//!
+//! - macOS: TCC via `AVCaptureDevice` authorization status and request
+//! - Windows: privacy settings in the capability access consent store; the
+//!   OS prompts on first capture and denials must be changed in Settings
+//! - Linux: the xdg-desktop-portal camera interface inside a sandbox;
+//!   unsandboxed apps are not gated by the OS
+
+use serde::{Deserialize, Serialize};
+
+/// Capture device class subject to OS permissions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum MediaDevice {
+    /// Audio capture
+    Microphone,
+    /// Video capture
+    Camera,
+}
+
+/// OS permission state for a capture device
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionState {
+    /// Access granted
+    Granted,
+    /// Access denied by the user
+    Denied,
+    /// Not decided yet; the OS prompts on request or first capture
+    Prompt,
+    /// Blocked by policy (e.g. parental controls or MDM)
+    Restricted,
+    /// The platform offers no way to query the permission
+    Unsupported,
+}
+
+impl PermissionState {
+    /// Whether starting capture may succeed
+    #[must_use]
+    pub fn allows_capture(self) -> bool {
+        matches!(self, Self::Granted | Self::Prompt | Self::Unsupported)
+    }
+}
+
+/// Permission state of one device, as returned to the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PermissionStatus {
+    /// Device the state applies to
+    pub device: MediaDevice,
+    /// Current state
+    pub state: PermissionState,
+    /// OS settings page where the user can change a denial
+    pub settings_url: Option<String>,
+}
+
+/// Permission states of both capture devices
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct MediaPermissions {
+    /// Microphone permission
+    pub microphone: PermissionStatus,
+    /// Camera permission
+    pub camera: PermissionStatus,
+}
+
+impl PermissionStatus {
+    fn new(device: MediaDevice, state: PermissionState) -> Self {
+        Self {
+            device,
+            state,
+            settings_url: platform::settings_url(device).map(str::to_string),
+        }
+    }
+}
+
+/// Check a device's permission without prompting
+pub async fn check(device: MediaDevice) -> PermissionStatus {
+    PermissionStatus::new(device, platform::check(device).await)
+}
+
+/// Request a device's permission, prompting the user if undecided
+pub async fn request(device: MediaDevice) -> PermissionStatus {
+    PermissionStatus::new(device, platform::request(device).await)
+}
+
+/// Check both devices
+pub async fn check_all() -> MediaPermissions {
+    MediaPermissions {
+        microphone: check(MediaDevice::Microphone).await,
+        camera: check(MediaDevice::Camera).await,
+    }
+}
+
+/// Fail early if a required device cannot be captured
+///
+/// # Errors
+///
+/// Returns an error naming the device and state if capture is not allowed
+pub async fn ensure(audio: bool, video: bool) -> Result<(), String> {
+    let required = [
+        (audio, MediaDevice::Microphone),
+        (video, MediaDevice::Camera),
+    ];
+    for device in required
+        .into_iter()
+        .filter(|(needed, _)| *needed)
+        .map(|(_, device)| device)
+    {
+        let status = check(device).await;
+        if !status.state.allows_capture() {
+            return Err(format!(
+                "Permission {:?} for {:?}",
+                status.state, status.device
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{MediaDevice, PermissionState};
+    use objc::runtime::{Class, Object, BOOL, NO};
+    use objc::{msg_send, sel, sel_impl};
+    use std::sync::Mutex;
+
+    #[link(name = "AVFoundation", kind = "framework")]
+    extern "C" {
+        static AVMediaTypeAudio: *mut Object;
+        static AVMediaTypeVideo: *mut Object;
+    }
+
+    fn media_type(device: MediaDevice) -> *mut Object {
+        // SAFETY: framework-provided NSString constants, valid for the process lifetime
+        unsafe {
+            match device {
+                MediaDevice::Microphone => AVMediaTypeAudio,
+                MediaDevice::Camera => AVMediaTypeVideo,
+            }
+        }
+    }
+
+    fn status(device: MediaDevice) -> PermissionState {
+        let Some(class) = Class::get("AVCaptureDevice") else {
+            return PermissionState::Unsupported;
+        };
+        // SAFETY: class method taking an AVMediaType and returning AVAuthorizationStatus
+        let status: isize =
+            unsafe { msg_send![class, authorizationStatusForMediaType: media_type(device)] };
+        match status {
+            0 => PermissionState::Prompt,
+            1 => PermissionState::Restricted,
+            2 => PermissionState::Denied,
+            3 => PermissionState::Granted,
+            _ => PermissionState::Unsupported,
+        }
+    }
+
+    pub(super) async fn check(device: MediaDevice) -> PermissionState {
+        status(device)
+    }
+
+    pub(super) async fn request(device: MediaDevice) -> PermissionState {
+        let state = status(device);
+        let Some(class) = Class::get("AVCaptureDevice") else {
+            return state;
+        };
+        if state != PermissionState::Prompt {
+            return state;
+        }
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let tx = Mutex::new(Some(tx));
+        let handler = block::ConcreteBlock::new(move |granted: BOOL| {
+            if let Some(tx) = tx.lock().ok().and_then(|mut tx| tx.take()) {
+                let _ = tx.send(granted != NO);
+            }
+        })
+        .copy();
+        // SAFETY: the block is retained by AVFoundation until it is called
+        unsafe {
+            let _: () = msg_send![class, requestAccessForMediaType: media_type(device)
+                                                  completionHandler: &*handler];
+        }
+
+        match rx.await {
+            Ok(true) => PermissionState::Granted,
+            Ok(false) => PermissionState::Denied,
+            Err(_) => status(device),
+        }
+    }
+
+    pub(super) fn settings_url(device: MediaDevice) -> Option<&'static str> {
+        Some(match device {
+            MediaDevice::Microphone => {
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_Microphone"
+            }
+            MediaDevice::Camera => {
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_Camera"
+            }
+        })
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::{MediaDevice, PermissionState};
+    use winreg::enums::HKEY_CURRENT_USER;
+    use winreg::RegKey;
+
+    const CONSENT_STORE: &str =
+        r"Software\Microsoft\Windows\CurrentVersion\CapabilityAccessManager\ConsentStore";
+
+    fn capability(device: MediaDevice) -> &'static str {
+        match device {
+            MediaDevice::Microphone => "microphone",
+            MediaDevice::Camera => "webcam",
+        }
+    }
+
+    fn consent(path: &str) -> Option<String> {
+        RegKey::predef(HKEY_CURRENT_USER)
+            .open_subkey(path)
+            .and_then(|key| key.get_value::<String, _>("Value"))
+            .ok()
+    }
+
+    pub(super) async fn check(device: MediaDevice) -> PermissionState {
+        let global = format!(r"{CONSENT_STORE}\{}", capability(device));
+        // Desktop (unpackaged) apps have their own toggle under the global one
+        let desktop = format!(r"{global}\NonPackaged");
+        match (consent(&global).as_deref(), consent(&desktop).as_deref()) {
+            (Some("Deny"), _) | (_, Some("Deny")) => PermissionState::Denied,
+            (Some("Allow"), _) => PermissionState::Granted,
+            _ => PermissionState::Prompt,
+        }
+    }
+
+    pub(super) async fn request(device: MediaDevice) -> PermissionState {
+        // Windows prompts on first capture; there is no request API for
+        // desktop apps
+        check(device).await
+    }
+
+    pub(super) fn settings_url(device: MediaDevice) -> Option<&'static str> {
+        Some(match device {
+            MediaDevice::Microphone => "ms-settings:privacy-microphone",
+            MediaDevice::Camera => "ms-settings:privacy-webcam",
+        })
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{MediaDevice, PermissionState};
+    use ashpd::desktop::camera::Camera;
+
+    pub(super) async fn check(device: MediaDevice) -> PermissionState {
+        // Audio servers do not gate capture; sandboxes grant it statically
+        if device == MediaDevice::Microphone || !ashpd::is_sandboxed().await {
+            return PermissionState::Granted;
+        }
+        // The portal cannot be queried without prompting
+        match Camera::new().await {
+            Ok(camera) if camera.is_present().await.unwrap_or(false) => PermissionState::Prompt,
+            Ok(_) | Err(_) => PermissionState::Unsupported,
+        }
+    }
+
+    pub(super) async fn request(device: MediaDevice) -> PermissionState {
+        let state = check(device).await;
+        if state != PermissionState::Prompt {
+            return state;
+        }
+        let Ok(camera) = Camera::new().await else {
+            return PermissionState::Unsupported;
+        };
+        match camera.request_access().await.and_then(|r| r.response()) {
+            Ok(()) => PermissionState::Granted,
+            Err(ashpd::Error::Response(_)) => PermissionState::Denied,
+            Err(_) => PermissionState::Unsupported,
+        }
+    }
+
+    pub(super) fn settings_url(_device: MediaDevice) -> Option<&'static str> {
+        None
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+mod platform {
+    use super::{MediaDevice, PermissionState};
+
+    pub(super) async fn check(_device: MediaDevice) -> PermissionState {
+        PermissionState::Unsupported
+    }
+
+    pub(super) async fn request(_device: MediaDevice) -> PermissionState {
+        PermissionState::Unsupported
+    }
+
+    pub(super) fn settings_url(_device: MediaDevice) -> Option<&'static str> {
+        None
+    }
+}
@@ -0,0 +1,263 @@
+// Typed client for the saorsa-webrtc Tauri plugin
+//
+// Wraps each plugin command in a function with typed arguments and result,
+// and each frontend event in a typed subscription helper. Payload types are
+// generated from the plugin's JSON Schemas into ./types.
+//
+// Every `callId` argument takes either a full call ID or the call's six
+// character short code, as returned by `getCallShortCode`.
+
+import { invoke } from '@tauri-apps/api/tauri';
+import { listen, type Event, type UnlistenFn } from '@tauri-apps/api/event';
+import type {
+  CallWaitingPayload,
+  Contact,
+  ContactPermissions,
+  HealthReport,
+  IncomingCallPayload,
+  InviteDetails,
+  MediaPermissions,
+  MissedCall,
+  StatsDeltaPayload,
+  StatsSample,
+} from './types';
+
+export * from './types';
+
+/** Event carrying a `StatsDeltaPayload`, emitted after `subscribeCallStats` */
+export const STATS_DELTA_EVENT = 'saorsa-webrtc://stats-delta';
+
+/** Event emitted for each incoming call (`notifications` feature) */
+export const INCOMING_CALL_EVENT = 'saorsa-webrtc://incoming-call';
+
+/** Event emitted for each call arriving while on a call (`notifications` feature) */
+export const CALL_WAITING_EVENT = 'saorsa-webrtc://call-waiting';
+
+/** State of a call, as returned by `getCallState` */
+export type CallState =
+  | 'idle'
+  | 'calling'
+  | 'connecting'
+  | 'connected'
+  | 'reconnecting'
+  | 'ending'
+  | 'failed';
+
+/** Media of a call or invitation */
+export interface CallMedia {
+  audio: boolean;
+  video: boolean;
+  screenShare: boolean;
+}
+
+/** Initialize the service with an identity, persisting it if configured */
+export async function initialize(identity: string): Promise<void> {
+  await invoke('plugin:saorsa-webrtc|initialize', { identity });
+}
+
+/** Identity the service is running with, if started */
+export async function getIdentity(): Promise<string | null> {
+  return await invoke<string | null>('plugin:saorsa-webrtc|get_identity');
+}
+
+/** Replace the identity with a new one and restart the service, dropping active calls */
+export async function rotateIdentity(): Promise<string> {
+  return await invoke<string>('plugin:saorsa-webrtc|rotate_identity');
+}
+
+/** Call a peer, returning the call ID; audio only if `media` is omitted */
+export async function call(peer: string, media?: CallMedia): Promise<string> {
+  if (media === undefined) {
+    return await invoke<string>('plugin:saorsa-webrtc|call', { peer });
+  }
+  return await invoke<string>('plugin:saorsa-webrtc|call_with_constraints', { peer, ...media });
+}
+
+/** Create a `saorsa://call` invitation link for the running identity */
+export async function createInvite(media: CallMedia): Promise<string> {
+  return await invoke<string>('plugin:saorsa-webrtc|create_invite', { ...media });
+}
+
+/** Parse a `saorsa://call` invitation link */
+export async function parseInvite(uri: string): Promise<InviteDetails> {
+  return await invoke<InviteDetails>('plugin:saorsa-webrtc|parse_invite', { uri });
+}
+
+/** Check microphone and camera permissions without prompting */
+export async function checkMediaPermissions(): Promise<MediaPermissions> {
+  return await invoke<MediaPermissions>('plugin:saorsa-webrtc|check_media_permissions');
+}
+
+/** Request microphone and/or camera permissions, prompting if undecided */
+export async function requestMediaPermissions(
+  audio: boolean,
+  video: boolean,
+): Promise<MediaPermissions> {
+  return await invoke<MediaPermissions>('plugin:saorsa-webrtc|request_media_permissions', {
+    audio,
+    video,
+  });
+}
+
+/** State of a call */
+export async function getCallState(callId: string): Promise<CallState> {
+  return await invoke<CallState>('plugin:saorsa-webrtc|get_call_state', { callId });
+}
+
+/** Short code of a call, to show or read out in place of its ID */
+export async function getCallShortCode(callId: string): Promise<string> {
+  return await invoke<string>('plugin:saorsa-webrtc|get_call_short_code', { callId });
+}
+
+/** Per-second statistics samples of a call, oldest first */
+export async function getCallStatsHistory(callId: string): Promise<StatsSample[]> {
+  return await invoke<StatsSample[]>('plugin:saorsa-webrtc|get_call_stats_history', { callId });
+}
+
+/** Emit `STATS_DELTA_EVENT` for a call every `intervalMs` until it ends */
+export async function subscribeCallStats(callId: string, intervalMs: number): Promise<void> {
+  await invoke('plugin:saorsa-webrtc|subscribe_call_stats', { callId, intervalMs });
+}
+
+/** Turn do-not-disturb on or off */
+export async function setDoNotDisturb(enabled: boolean): Promise<void> {
+  await invoke('plugin:saorsa-webrtc|set_do_not_disturb', { enabled });
+}
+
+/**
+ * Set the daily quiet hours, as `HH:MM-HH:MM` ranges in local time
+ *
+ * The offset from UTC defaults to the browser's. An empty list removes the
+ * quiet hours.
+ */
+export async function setQuietHours(
+  hours: string[],
+  utcOffsetMinutes: number = -new Date().getTimezoneOffset(),
+): Promise<void> {
+  await invoke('plugin:saorsa-webrtc|set_quiet_hours', { hours, utcOffsetMinutes });
+}
+
+/** Calls held back by do-not-disturb, oldest first */
+export async function getMissedCalls(): Promise<MissedCall[]> {
+  return await invoke<MissedCall[]>('plugin:saorsa-webrtc|get_missed_calls');
+}
+
+/** Health report of the service */
+export async function getHealth(): Promise<HealthReport> {
+  return await invoke<HealthReport>('plugin:saorsa-webrtc|get_health');
+}
+
+/** Contacts, by name */
+export async function listContacts(): Promise<Contact[]> {
+  return await invoke<Contact[]>('plugin:saorsa-webrtc|list_contacts');
+}
+
+/** Add a contact */
+export async function addContact(
+  name: string,
+  identity: string,
+  fingerprint?: string,
+  notes?: string,
+): Promise<Contact> {
+  return await invoke<Contact>('plugin:saorsa-webrtc|add_contact', {
+    name,
+    identity,
+    fingerprint,
+    notes,
+  });
+}
+
+/** Change a contact's fingerprint or notes; omitted fields are kept */
+export async function updateContact(
+  name: string,
+  changes: { fingerprint?: string; notes?: string },
+): Promise<void> {
+  await invoke('plugin:saorsa-webrtc|update_contact', { name, ...changes });
+}
+
+/** Set what a contact may do */
+export async function setContactPermissions(
+  name: string,
+  permissions: ContactPermissions,
+): Promise<void> {
+  await invoke('plugin:saorsa-webrtc|set_contact_permissions', { name, permissions });
+}
+
+/** Remove a contact */
+export async function removeContact(name: string): Promise<void> {
+  await invoke('plugin:saorsa-webrtc|remove_contact', { name });
+}
+
+/** Call a contact by name, returning the call ID */
+export async function callContact(name: string): Promise<string> {
+  return await invoke<string>('plugin:saorsa-webrtc|call_contact', { name });
+}
+
+/** End a call */
+export async function endCall(callId: string): Promise<void> {
+  await invoke('plugin:saorsa-webrtc|end_call', { callId });
+}
+
+/** Accept an incoming call with audio; a call already on is put on hold */
+export async function acceptCall(callId: string): Promise<void> {
+  await invoke('plugin:saorsa-webrtc|accept_call', { callId });
+}
+
+/** Reject an incoming call */
+export async function rejectCall(callId: string): Promise<void> {
+  await invoke('plugin:saorsa-webrtc|reject_call', { callId });
+}
+
+/** Decline a waiting call, telling the caller we are busy */
+export async function rejectBusy(callId: string, caller: string): Promise<void> {
+  await invoke('plugin:saorsa-webrtc|reject_busy', { callId, caller });
+}
+
+/** Put a connected call on hold */
+export async function holdCall(callId: string): Promise<void> {
+  await invoke('plugin:saorsa-webrtc|hold_call', { callId });
+}
+
+/** Take a call off hold */
+export async function resumeCall(callId: string): Promise<void> {
+  await invoke('plugin:saorsa-webrtc|resume_call', { callId });
+}
+
+/** Attach a metadata value to a call, e.g. a support ticket ID */
+export async function setCallMetadata(callId: string, key: string, value: string): Promise<void> {
+  await invoke('plugin:saorsa-webrtc|set_call_metadata', { callId, key, value });
+}
+
+/**
+ * Listen for statistics deltas, of one call or of every call
+ *
+ * Deltas are only emitted for calls passed to `subscribeCallStats`.
+ */
+export async function onStatsDelta(
+  handler: (delta: StatsDeltaPayload) => void,
+  callId?: string,
+): Promise<UnlistenFn> {
+  return await listen(STATS_DELTA_EVENT, (event: Event<StatsDeltaPayload>) => {
+    if (callId === undefined || event.payload.call_id === callId) {
+      handler(event.payload);
+    }
+  });
+}
+
+/** Listen for incoming calls (`notifications` feature) */
+export async function onIncomingCall(
+  handler: (call: IncomingCallPayload) => void,
+): Promise<UnlistenFn> {
+  return await listen(INCOMING_CALL_EVENT, (event: Event<IncomingCallPayload>) => {
+    handler(event.payload);
+  });
+}
+
+/** Listen for calls arriving while on a call (`notifications` feature) */
+export async function onCallWaiting(
+  handler: (call: CallWaitingPayload) => void,
+): Promise<UnlistenFn> {
+  return await listen(CALL_WAITING_EVENT, (event: Event<CallWaitingPayload>) => {
+    handler(event.payload);
+  });
+}
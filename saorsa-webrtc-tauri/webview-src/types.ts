@@ -0,0 +1,435 @@
+// Generated by scripts/generate-types.mjs from schemas/tauri/. Do not edit;
+// regenerate with `npm run generate` after the schemas change.
+
+/**
+ * Unique identifier for a call
+ *
+ * Call IDs are totally ordered so both peers can resolve glare the same way.
+ */
+export type CallId = string;
+
+/**
+ * Payload of `CALL_WAITING_EVENT`
+ */
+export interface CallWaitingPayload {
+  call_id: string;
+  caller: string;
+  active: string;
+}
+
+/**
+ * Codecs registered with the service
+ */
+export interface CodecHealth {
+  /**
+   * Video MIME types with an encoder and a decoder, preferred first
+   */
+  video: string[];
+  /**
+   * Audio MIME types with an encoder and a decoder, preferred first
+   */
+  audio: string[];
+}
+
+/**
+ * Someone the user knows
+ */
+export interface Contact {
+  /**
+   * Name the user knows them by
+   */
+  name: string;
+  /**
+   * Their peer identity
+   */
+  identity: PeerIdentityString;
+  /**
+   * Fingerprint the user verified, if any
+   */
+  fingerprint?: string | null;
+  /**
+   * Free-form notes
+   */
+  notes?: string;
+  /**
+   * What they may do
+   */
+  permissions?: ContactPermissions;
+  /**
+   * When the contact was added
+   */
+  added_at: string;
+}
+
+/**
+ * What a contact may do
+ */
+export interface ContactPermissions {
+  /**
+   * Answer their calls without asking
+   */
+  auto_accept?: boolean;
+  /**
+   * Let their video calls ring; audio calls always may
+   */
+  video_allowed?: boolean;
+  /**
+   * Accept files they send
+   */
+  file_transfer_allowed?: boolean;
+}
+
+/**
+ * Media devices found
+ */
+export interface DeviceHealth {
+  /**
+   * Names of the audio devices
+   */
+  audio: string[];
+  /**
+   * Names of the video devices
+   */
+  video: string[];
+}
+
+/**
+ * What happens to calls arriving during do-not-disturb
+ */
+export type DndAction = "reject" | "divert";
+
+/**
+ * Why do-not-disturb is on
+ */
+export type DndReason = "manual" | "quiet_hours";
+
+/**
+ * Counters of an event channel
+ */
+export interface EventChannelStats {
+  /**
+   * Events the channel buffers per subscriber
+   *
+   * The configured capacity rounded up to a power of two, as tokio does.
+   */
+  capacity: number;
+  /**
+   * Events sent while anyone was subscribed
+   */
+  sent: number;
+  /**
+   * Events sent with nobody subscribed, which go nowhere
+   */
+  unheard: number;
+  /**
+   * Events that pushed an unread event out of a lagging subscriber's
+   * buffer; each is one event that subscriber lost
+   */
+  overflowed: number;
+}
+
+/**
+ * Counters of the call, media and service event channels
+ */
+export interface EventStats {
+  /**
+   * Call manager events
+   */
+  call: EventChannelStats;
+  /**
+   * Media stream manager events
+   */
+  media: EventChannelStats;
+  /**
+   * Service events
+   */
+  service: EventChannelStats;
+}
+
+/**
+ * Snapshot of the health of the whole service
+ */
+export interface HealthReport {
+  /**
+   * When the snapshot was taken
+   */
+  generated_at: string;
+  /**
+   * Media transports of ongoing calls
+   */
+  transport: TransportHealth;
+  /**
+   * Signaling transport
+   */
+  signaling: SignalingHealth;
+  /**
+   * Registered codecs
+   */
+  codecs: CodecHealth;
+  /**
+   * Media devices
+   */
+  devices: DeviceHealth;
+  /**
+   * Supervised background tasks
+   */
+  tasks: ServiceHealth;
+  /**
+   * Event channels, including events lost by lagging subscribers
+   */
+  events?: EventStats;
+}
+
+/**
+ * Payload of `INCOMING_CALL_EVENT`
+ */
+export interface IncomingCallPayload {
+  call_id: string;
+  caller: string;
+}
+
+/**
+ * Peer and media parsed from an invitation link
+ */
+export interface InviteDetails {
+  peer: string;
+  audio: boolean;
+  video: boolean;
+  screen_share: boolean;
+}
+
+/**
+ * Capture device class subject to OS permissions
+ */
+export type MediaDevice = "microphone" | "camera";
+
+/**
+ * Permission states of both capture devices
+ */
+export interface MediaPermissions {
+  /**
+   * Microphone permission
+   */
+  microphone: PermissionStatus;
+  /**
+   * Camera permission
+   */
+  camera: PermissionStatus;
+}
+
+/**
+ * A call that did not ring because of do-not-disturb
+ */
+export interface MissedCall {
+  /**
+   * Call identifier
+   */
+  call_id: CallId;
+  /**
+   * Who called
+   */
+  caller: PeerIdentityString;
+  /**
+   * When the call arrived
+   */
+  at: string;
+  /**
+   * Why it did not ring
+   */
+  reason: DndReason;
+  /**
+   * What was done with it
+   */
+  action: DndAction;
+}
+
+/**
+ * Simple string-based peer identity
+ *
+ * This is a basic implementation that uses strings as peer identifiers.
+ * Suitable for testing or simple applications. For production use, consider
+ * using more robust identity systems like FourWordAddress from saorsa-core.
+ */
+export type PeerIdentityString = string;
+
+/**
+ * OS permission state for a capture device
+ */
+export type PermissionState = "granted" | "denied" | "prompt" | "restricted" | "unsupported";
+
+/**
+ * Permission state of one device, as returned to the frontend
+ */
+export interface PermissionStatus {
+  /**
+   * Device the state applies to
+   */
+  device: MediaDevice;
+  /**
+   * Current state
+   */
+  state: PermissionState;
+  /**
+   * OS settings page where the user can change a denial
+   */
+  settings_url?: string | null;
+}
+
+/**
+ * Health of every supervised task
+ */
+export interface ServiceHealth {
+  /**
+   * Supervised tasks, by name
+   */
+  tasks: TaskHealth[];
+}
+
+/**
+ * Signaling transport liveness
+ */
+export interface SignalingHealth {
+  /**
+   * Milliseconds since the last message arrived, if any has
+   */
+  last_message_ms?: number | null;
+  /**
+   * Receive errors since the last message arrived
+   */
+  consecutive_errors: number;
+}
+
+/**
+ * Payload of `STATS_DELTA_EVENT`
+ */
+export interface StatsDeltaPayload {
+  call_id: string;
+  /**
+   * Milliseconds since the subscription started
+   */
+  at_ms: number;
+  /**
+   * Milliseconds since the previous delta of this call
+   */
+  elapsed_ms: number;
+  packets_sent: number;
+  packets_received: number;
+  bytes_sent: number;
+  bytes_received: number;
+  stream_errors: number;
+}
+
+/**
+ * One statistics sample
+ *
+ * Counters are cumulative; rates are the 1s averages across all streams.
+ */
+export interface StatsSample {
+  /**
+   * When the sample was taken
+   */
+  timestamp: string;
+  /**
+   * Total packets sent
+   */
+  packets_sent: number;
+  /**
+   * Total packets received
+   */
+  packets_received: number;
+  /**
+   * Total bytes sent
+   */
+  bytes_sent: number;
+  /**
+   * Total bytes received
+   */
+  bytes_received: number;
+  /**
+   * Number of stream errors
+   */
+  stream_errors: number;
+  /**
+   * Outbound bitrate in bits per second
+   */
+  send_bitrate_bps: number;
+  /**
+   * Inbound bitrate in bits per second
+   */
+  recv_bitrate_bps: number;
+  /**
+   * Outbound packets per second
+   */
+  send_packet_rate: number;
+  /**
+   * Inbound packets per second
+   */
+  recv_packet_rate: number;
+}
+
+/**
+ * Health of one supervised task
+ */
+export interface TaskHealth {
+  /**
+   * Task name
+   */
+  name: string;
+  /**
+   * Current state
+   */
+  status: TaskStatus;
+  /**
+   * Restarts since the task was first started
+   */
+  restarts: number;
+  /**
+   * How the task last ended, e.g. its panic message
+   */
+  last_failure?: string | null;
+  /**
+   * When the task entered its current state
+   */
+  since: string;
+}
+
+/**
+ * State of a supervised task
+ */
+export type TaskStatus = {
+  status: "running";
+} | {
+  /**
+   * Consecutive failures so far
+   */
+  attempt: number;
+  /**
+   * Milliseconds until the restart
+   */
+  retry_in_ms: number;
+  status: "restarting";
+} | {
+  status: "failed";
+};
+
+/**
+ * Media transports of ongoing calls
+ */
+export interface TransportHealth {
+  /**
+   * Calls with a media transport
+   */
+  calls: number;
+  /**
+   * Transports connected to their peer
+   */
+  connected: number;
+  /**
+   * Transports still connecting
+   */
+  connecting: number;
+  /**
+   * Transports whose connection failed
+   */
+  failed: number;
+}